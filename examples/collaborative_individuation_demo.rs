@@ -9,9 +9,9 @@ use std::collections::HashMap;
 use tokio::time::{sleep, Duration};
 use weavemesh_core::{
     WeaveProtocol, WeaveConfig, BasicGroupCommunication, GroupId,
-    Message, MessagePriority, MessageId, BasicNode, NodeConfig, NodeType, NodeRole, NodeCapability,
+    Message, MessageEnvelope, MessagePriority, MessageId, BasicNode, NodeConfig, NodeType, NodeRole, NodeCapability,
     BasicAttributionEngine, AttributionConfig, CollaborationType, SecurityLevel, AIType,
-    AttributionContext,
+    AttributionContext, CapabilityDowngradePolicy,
 };
 
 /// Demonstrates collaborative individuation principles through WeaveMesh Core
@@ -65,6 +65,8 @@ async fn demonstrate_collaborative_individuation_framework() -> Result<()> {
         security_level: SecurityLevel::Internal,
         metadata: HashMap::new(),
         debug_mode: false,
+        downgrade_strategy: CapabilityDowngradePolicy::default(),
+        ..Default::default()
     };
     let human_node = BasicNode::new(human_config);
     
@@ -77,6 +79,8 @@ async fn demonstrate_collaborative_individuation_framework() -> Result<()> {
         security_level: SecurityLevel::Internal,
         metadata: HashMap::new(),
         debug_mode: false,
+        downgrade_strategy: CapabilityDowngradePolicy::default(),
+        ..Default::default()
     };
     let ai_node = BasicNode::new(ai_config);
     
@@ -218,6 +222,7 @@ async fn demonstrate_multi_perspective_integration() -> Result<()> {
             },
             priority: MessagePriority::Normal,
             requires_ack: false,
+            envelope: MessageEnvelope::Live,
         };
         
         println!("   🎯 {}: {}", perspective, insight);
@@ -19,8 +19,8 @@ use weavemesh_core::{
     // Networking
     networking::{
         ZenohSession, NodeDiscovery, NodeCommunication, NodeInfo, NodeCapability,
-        DiscoveryConfig, CommunicationConfig, OutgoingMessage, DeliveryOptions,
-        MessagePriority, MessageType,
+        DiscoveryConfig, DiscoveryBackend, CommunicationConfig, OutgoingMessage, DeliveryOptions,
+        MessagePriority, MessageType, PayloadSummaryConfig, UnhandledTypePolicy, BroadcastLimits,
     },
     // Sacred Alliance
     sacred_alliance::{
@@ -73,7 +73,12 @@ async fn main() -> anyhow::Result<()> {
         announcement_interval: 3,
         discovery_timeout: 2,
         node_timeout: 30,
+        missed_heartbeats_before_offline: 3,
         debug: true,
+        max_nodes: None,
+        backend: DiscoveryBackend::Zenoh,
+        clock_skew: Default::default(),
+        require_signed_announcements: false,
     };
     
     let comm_config = CommunicationConfig {
@@ -83,6 +88,14 @@ async fn main() -> anyhow::Result<()> {
         require_acks: true,
         enable_encryption: false, // Simplified for demo
         debug: true,
+        handler_threads: 4,
+        max_pending_acks: None,
+        payload_summary: PayloadSummaryConfig::default(),
+        unhandled_type_policy: UnhandledTypePolicy::Warn,
+        handler_announcement_debounce_ms: 500,
+        allow_global: false,
+        broadcast_limits: BroadcastLimits::default(),
+        accepted_broadcast_scopes: None,
     };
     
     // Samuel's networking
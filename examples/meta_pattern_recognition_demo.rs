@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use tokio::time::{sleep, Duration};
 use weavemesh_core::{
     WeaveProtocol, WeaveConfig, BasicGroupCommunication, GroupId,
-    Message, MessagePriority, MessageId, BasicNode, NodeConfig, NodeType, NodeRole, NodeCapability,
+    Message, MessageEnvelope, MessagePriority, MessageId, BasicNode, NodeConfig, NodeType, NodeRole, NodeCapability,
     BasicAttributionEngine, AttributionConfig, SecurityLevel, AIType,
     AttributionContext, Attribution, CollaborationType,
 };
@@ -257,6 +257,7 @@ async fn demonstrate_meta_pattern_emergence() -> Result<()> {
             },
             priority: MessagePriority::Normal,
             requires_ack: false,
+            envelope: MessageEnvelope::Live,
         };
         
         group_comm.add_message_to_history(emergence_group.clone(), message);
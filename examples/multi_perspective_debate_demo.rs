@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use tokio::time::{sleep, Duration};
 use weavemesh_core::{
     WeaveProtocol, WeaveConfig, BasicGroupCommunication, GroupId,
-    Message, MessagePriority, MessageId,
+    Message, MessageEnvelope, MessagePriority, MessageId,
     BasicAttributionEngine, AttributionConfig,
     AttributionContext,
 };
@@ -244,6 +244,7 @@ async fn demonstrate_collaborative_debate_process(perspectives: &[DebatePerspect
             },
             priority: MessagePriority::Normal,
             requires_ack: false,
+            envelope: MessageEnvelope::Live,
         };
         
         group_comm.add_message_to_history(debate_group.clone(), message);
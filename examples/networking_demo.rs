@@ -10,8 +10,9 @@ use uuid::Uuid;
 
 use weavemesh_core::networking::{
     ZenohSession, NodeDiscovery, NodeCommunication, NodeInfo, NodeCapability,
-    DiscoveryConfig, CommunicationConfig, OutgoingMessage, DeliveryOptions,
-    MessagePriority, MessageType, WeaveMeshTopics,
+    DiscoveryConfig, DiscoveryBackend, CommunicationConfig, OutgoingMessage, DeliveryOptions,
+    MessagePriority, MessageType, WeaveMeshTopics, PayloadSummaryConfig, UnhandledTypePolicy,
+    BroadcastLimits, BroadcastScope,
 };
 
 #[tokio::main]
@@ -36,7 +37,12 @@ async fn main() -> anyhow::Result<()> {
         announcement_interval: 5, // Announce every 5 seconds
         discovery_timeout: 3,
         node_timeout: 30,
+        missed_heartbeats_before_offline: 3,
         debug: true,
+        max_nodes: None,
+        backend: DiscoveryBackend::Zenoh,
+        clock_skew: Default::default(),
+        require_signed_announcements: false,
     };
     
     let discovery1 = NodeDiscovery::new(
@@ -59,6 +65,14 @@ async fn main() -> anyhow::Result<()> {
         require_acks: true,
         enable_encryption: false, // Disabled for demo
         debug: true,
+        handler_threads: 4,
+        max_pending_acks: None,
+        payload_summary: PayloadSummaryConfig::default(),
+        unhandled_type_policy: UnhandledTypePolicy::Warn,
+        handler_announcement_debounce_ms: 500,
+        allow_global: false,
+        broadcast_limits: BroadcastLimits::default(),
+        accepted_broadcast_scopes: None,
     };
     
     let comm1 = NodeCommunication::new(
@@ -196,10 +210,10 @@ async fn main() -> anyhow::Result<()> {
     // Demonstrate broadcast messaging
     println!("\n📢 Broadcasting message to all nodes...");
     
-    comm1.broadcast_message(
+    comm1.broadcast(
+        BroadcastScope::Context("demo-context".to_string()),
         MessageType::SystemControl,
         b"Broadcast message from Node 1 to all nodes!".to_vec(),
-        Some("demo-context".to_string()),
     ).await?;
     
     println!("✅ Broadcast message sent");
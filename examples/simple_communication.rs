@@ -64,6 +64,7 @@ async fn main() -> anyhow::Result<()> {
             content: AllianceMessageContent::Text(text.to_string()),
             timestamp: utils::now(),
             metadata: HashMap::new(),
+            reply_to: None,
         };
         
         alliance.send_message(message)?;
@@ -0,0 +1,544 @@
+//! Sacred Alliance level progression
+//!
+//! Assesses the [`SacredAllianceLevel`] of a channel or project from
+//! measurable collaboration signals instead of setting it by hand. Each level
+//! above Basic requires minimum values for some [`AllianceSignal`]s. A scope
+//! is promoted as soon as it meets a higher level's thresholds, but keeps its
+//! level until a signal drops more than the hysteresis margin below the
+//! threshold, so levels do not flap around a boundary.
+//!
+//! Every assessment produces a [`LevelAssessment`] explaining which signals
+//! drove the outcome. Levels can be pinned manually; pinned scopes keep
+//! their level and their reports say so, along with the level the signals
+//! would have given.
+//!
+//! [`spawn_periodic_assessment`] reassesses a set of channels on a fixed
+//! interval from the signals each channel measures itself.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::attribution::Attribution;
+use crate::ide::project::{CoreCeremonyOutcome, CoreCeremonyRecord};
+use crate::sacred_alliance::{BasicSacredAllianceChannel, SacredAllianceLevel};
+
+/// A measurable collaboration signal, valued 0.0 to 1.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AllianceSignal {
+    /// Share of ceremonies completed successfully
+    CeremonyCompletion,
+    /// How evenly work is attributed between humans and AI
+    AttributionBalance,
+    /// Share of conflicts resolved successfully
+    ConflictResolution,
+    /// How consistently participants are present
+    PresenceConsistency,
+}
+
+/// Current values of the signals for one scope
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct AllianceSignals {
+    /// Share of ceremonies completed successfully
+    pub ceremony_completion: f64,
+    /// How evenly work is attributed between humans and AI
+    pub attribution_balance: f64,
+    /// Share of conflicts resolved successfully
+    pub conflict_resolution: f64,
+    /// How consistently participants are present
+    pub presence_consistency: f64,
+}
+
+impl AllianceSignals {
+    /// Value of one signal
+    pub fn value(&self, signal: AllianceSignal) -> f64 {
+        match signal {
+            AllianceSignal::CeremonyCompletion => self.ceremony_completion,
+            AllianceSignal::AttributionBalance => self.attribution_balance,
+            AllianceSignal::ConflictResolution => self.conflict_resolution,
+            AllianceSignal::PresenceConsistency => self.presence_consistency,
+        }
+    }
+}
+
+/// Share of ceremonies that completed successfully; 0.0 without ceremonies
+pub fn ceremony_completion_rate(ceremonies: &[CoreCeremonyRecord]) -> f64 {
+    if ceremonies.is_empty() {
+        return 0.0;
+    }
+    let completed = ceremonies
+        .iter()
+        .filter(|c| matches!(c.outcome, CoreCeremonyOutcome::Successful))
+        .count();
+    completed as f64 / ceremonies.len() as f64
+}
+
+/// Balance of human and AI attribution within `window` before `now`
+///
+/// 1.0 when humans and AI appear in equally many attributions, 0.0 when
+/// only one side does or there are none.
+pub fn attribution_balance(attributions: &[Attribution], window: Duration, now: DateTime<Utc>) -> f64 {
+    let recent = attributions
+        .iter()
+        .filter(|a| a.timestamp <= now && now - a.timestamp <= window);
+    let (human, ai) = recent.fold((0usize, 0usize), |(human, ai), a| {
+        (human + a.human_contributor.is_some() as usize, ai + a.ai_contributor.is_some() as usize)
+    });
+    match human.max(ai) {
+        0 => 0.0,
+        most => human.min(ai) as f64 / most as f64,
+    }
+}
+
+/// Signal thresholds required for a level
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelRequirement {
+    /// Level granted
+    pub level: SacredAllianceLevel,
+    /// Minimum value of each signal
+    pub thresholds: BTreeMap<AllianceSignal, f64>,
+}
+
+impl LevelRequirement {
+    /// Requirement with the given thresholds
+    pub fn new(level: SacredAllianceLevel, thresholds: &[(AllianceSignal, f64)]) -> Self {
+        Self {
+            level,
+            thresholds: thresholds.iter().copied().collect(),
+        }
+    }
+
+    /// Whether every signal is at least its threshold less `margin`
+    fn met(&self, signals: &AllianceSignals, margin: f64) -> bool {
+        self.thresholds
+            .iter()
+            .all(|(signal, threshold)| signals.value(*signal) >= threshold - margin)
+    }
+}
+
+/// How levels are assessed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressionRules {
+    /// Requirements from the lowest level to the highest
+    ///
+    /// The first requirement is the floor every assessed scope gets.
+    pub requirements: Vec<LevelRequirement>,
+    /// How far below a threshold a signal may drop before the level is lost
+    pub hysteresis: f64,
+    /// Window over which attribution balance is measured
+    pub attribution_window_days: i64,
+}
+
+impl Default for ProgressionRules {
+    fn default() -> Self {
+        use AllianceSignal::*;
+        Self {
+            requirements: vec![
+                LevelRequirement::new(SacredAllianceLevel::Basic, &[]),
+                LevelRequirement::new(SacredAllianceLevel::Active, &[
+                    (CeremonyCompletion, 0.5),
+                    (PresenceConsistency, 0.5),
+                ]),
+                LevelRequirement::new(SacredAllianceLevel::Facilitator, &[
+                    (CeremonyCompletion, 0.7),
+                    (AttributionBalance, 0.6),
+                    (ConflictResolution, 0.6),
+                    (PresenceConsistency, 0.7),
+                ]),
+                LevelRequirement::new(SacredAllianceLevel::Guardian, &[
+                    (CeremonyCompletion, 0.9),
+                    (AttributionBalance, 0.8),
+                    (ConflictResolution, 0.8),
+                    (PresenceConsistency, 0.85),
+                ]),
+            ],
+            hysteresis: 0.05,
+            attribution_window_days: 30,
+        }
+    }
+}
+
+impl ProgressionRules {
+    /// Level the signals give a scope currently at `current`
+    fn assess(&self, current: &SacredAllianceLevel, signals: &AllianceSignals) -> SacredAllianceLevel {
+        let floor = match self.requirements.first() {
+            Some(requirement) => requirement.level.clone(),
+            None => return current.clone(),
+        };
+        let highest = |margin: f64, below: Option<&SacredAllianceLevel>| {
+            self.requirements
+                .iter()
+                .filter(|r| below.is_none_or(|level| r.level <= *level))
+                .filter(|r| r.met(signals, margin))
+                .map(|r| r.level.clone())
+                .max()
+        };
+
+        let promoted = highest(0.0, None).unwrap_or_else(|| floor.clone());
+        if promoted > *current {
+            return promoted;
+        }
+        // Hold on to the current level, or fall back to the best one still
+        // held within the hysteresis margin
+        highest(self.hysteresis, Some(current)).unwrap_or(floor)
+    }
+
+    fn requirement(&self, level: &SacredAllianceLevel) -> Option<&LevelRequirement> {
+        self.requirements.iter().find(|r| r.level == *level)
+    }
+}
+
+/// How one signal compared with the threshold that decided an assessment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalExplanation {
+    /// Signal compared
+    pub signal: AllianceSignal,
+    /// Measured value
+    pub value: f64,
+    /// Threshold it was compared with
+    pub threshold: f64,
+    /// Whether the value met the threshold
+    pub met: bool,
+    /// Whether this signal caused the level change
+    pub drove_change: bool,
+}
+
+/// Outcome of assessing one scope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelAssessment {
+    /// Channel or project assessed
+    pub scope: String,
+    /// Level before the assessment
+    pub previous: SacredAllianceLevel,
+    /// Level after the assessment
+    pub level: SacredAllianceLevel,
+    /// Level the signals give, which differs from `level` when pinned
+    pub recommended: SacredAllianceLevel,
+    /// Whether the level is pinned manually
+    pub pinned: bool,
+    /// Signals assessed
+    pub signals: AllianceSignals,
+    /// Signals compared with the thresholds of the deciding level
+    pub explanations: Vec<SignalExplanation>,
+    /// When the assessment ran
+    pub assessed_at: DateTime<Utc>,
+}
+
+impl LevelAssessment {
+    /// Whether the level changed
+    pub fn changed(&self) -> bool {
+        self.previous != self.level
+    }
+
+    /// Signals that caused the change
+    pub fn drivers(&self) -> Vec<AllianceSignal> {
+        self.explanations.iter().filter(|e| e.drove_change).map(|e| e.signal).collect()
+    }
+
+    /// One-line summary of the assessment
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{}: {:?} -> {:?}", self.scope, self.previous, self.level);
+        if self.pinned {
+            summary.push_str(&format!(" (pinned; signals give {:?})", self.recommended));
+        }
+        let drivers: Vec<String> = self.explanations
+            .iter()
+            .filter(|e| e.drove_change)
+            .map(|e| format!("{:?} {:.2} vs {:.2}", e.signal, e.value, e.threshold))
+            .collect();
+        if !drivers.is_empty() {
+            summary.push_str(&format!(" because {}", drivers.join(", ")));
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ScopeLevel {
+    level: SacredAllianceLevel,
+    pinned: bool,
+}
+
+/// Assesses levels per scope and announces changes
+#[derive(Debug)]
+pub struct AllianceAssessor {
+    rules: ProgressionRules,
+    scopes: HashMap<String, ScopeLevel>,
+    changes: broadcast::Sender<LevelAssessment>,
+}
+
+impl AllianceAssessor {
+    /// Create an assessor with no scopes
+    pub fn new(rules: ProgressionRules) -> Self {
+        Self {
+            rules,
+            scopes: HashMap::new(),
+            changes: broadcast::channel(64).0,
+        }
+    }
+
+    /// Rules in use
+    pub fn rules(&self) -> &ProgressionRules {
+        &self.rules
+    }
+
+    /// Assessments that changed a level
+    pub fn subscribe(&self) -> broadcast::Receiver<LevelAssessment> {
+        self.changes.subscribe()
+    }
+
+    /// Current level of a scope
+    pub fn level(&self, scope: &str) -> Option<SacredAllianceLevel> {
+        self.scopes.get(scope).map(|s| s.level.clone())
+    }
+
+    /// Start tracking a scope at a known level
+    pub fn track(&mut self, scope: impl Into<String>, level: SacredAllianceLevel) {
+        self.scopes.entry(scope.into()).or_insert(ScopeLevel { level, pinned: false });
+    }
+
+    /// Fix a scope's level until unpinned
+    pub fn pin(&mut self, scope: impl Into<String>, level: SacredAllianceLevel) {
+        self.scopes.insert(scope.into(), ScopeLevel { level, pinned: true });
+    }
+
+    /// Let assessments change a scope's level again
+    pub fn unpin(&mut self, scope: &str) {
+        if let Some(state) = self.scopes.get_mut(scope) {
+            state.pinned = false;
+        }
+    }
+
+    /// Assess a scope from its current signals
+    ///
+    /// Untracked scopes start at the lowest level of the rules.
+    pub fn assess(&mut self, scope: &str, signals: AllianceSignals, now: DateTime<Utc>) -> LevelAssessment {
+        let floor = self.rules.requirements.first().map(|r| r.level.clone()).unwrap_or_default();
+        let state = self.scopes
+            .entry(scope.to_string())
+            .or_insert(ScopeLevel { level: floor, pinned: false });
+        let previous = state.level.clone();
+        let recommended = self.rules.assess(&previous, &signals);
+        if !state.pinned {
+            state.level = recommended.clone();
+        }
+
+        let assessment = LevelAssessment {
+            scope: scope.to_string(),
+            previous: previous.clone(),
+            level: state.level.clone(),
+            recommended: recommended.clone(),
+            pinned: state.pinned,
+            signals,
+            explanations: self.explain(&previous, &recommended, &signals),
+            assessed_at: now,
+        };
+        if assessment.changed() {
+            tracing::info!("Sacred Alliance level changed: {}", assessment.summary());
+            let _ = self.changes.send(assessment.clone());
+        }
+        assessment
+    }
+
+    /// Compare signals with the thresholds that decided the outcome
+    ///
+    /// A promotion is explained by the new level's thresholds, all met; a
+    /// demotion or hold by the current level's, with the missed ones driving
+    /// a demotion. Demotions are judged with the hysteresis margin.
+    fn explain(
+        &self,
+        previous: &SacredAllianceLevel,
+        recommended: &SacredAllianceLevel,
+        signals: &AllianceSignals,
+    ) -> Vec<SignalExplanation> {
+        let promoted = recommended > previous;
+        let demoted = recommended < previous;
+        let (requirement, margin) = if promoted {
+            (self.rules.requirement(recommended), 0.0)
+        } else {
+            (self.rules.requirement(previous), self.rules.hysteresis)
+        };
+        let requirement = match requirement {
+            Some(requirement) => requirement,
+            None => return Vec::new(),
+        };
+
+        requirement
+            .thresholds
+            .iter()
+            .map(|(signal, threshold)| {
+                let value = signals.value(*signal);
+                let met = value >= threshold - margin;
+                SignalExplanation {
+                    signal: *signal,
+                    value,
+                    threshold: *threshold,
+                    met,
+                    drove_change: (promoted && met) || (demoted && !met),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for AllianceAssessor {
+    fn default() -> Self {
+        Self::new(ProgressionRules::default())
+    }
+}
+
+/// Reassess every channel each `interval` until `cancel` fires
+///
+/// The first round runs immediately. Level changes reach the assessor's
+/// subscribers as usual.
+pub fn spawn_periodic_assessment(
+    assessor: Arc<Mutex<AllianceAssessor>>,
+    channels: Vec<Arc<RwLock<BasicSacredAllianceChannel>>>,
+    interval: std::time::Duration,
+    cancel: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = ticker.tick() => {}
+            }
+            let mut assessor = assessor.lock().await;
+            for channel in &channels {
+                channel.write().await.assess_alliance_level(&mut assessor, Utc::now());
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(value: f64) -> AllianceSignals {
+        AllianceSignals {
+            ceremony_completion: value,
+            attribution_balance: value,
+            conflict_resolution: value,
+            presence_consistency: value,
+        }
+    }
+
+    #[test]
+    fn test_promotion_and_hysteresis() {
+        let mut assessor = AllianceAssessor::default();
+        let mut changes = assessor.subscribe();
+        let now = Utc::now();
+
+        let report = assessor.assess("channel", signals(0.72), now);
+        assert_eq!((report.previous.clone(), report.level.clone()), (SacredAllianceLevel::Basic, SacredAllianceLevel::Facilitator));
+        assert_eq!(report.drivers().len(), 4);
+        assert_eq!(changes.try_recv().unwrap().level, SacredAllianceLevel::Facilitator);
+
+        // Dipping below a threshold but within the margin keeps the level
+        let mut dipped = signals(0.72);
+        dipped.presence_consistency = 0.67;
+        let report = assessor.assess("channel", dipped, now);
+        assert!(!report.changed());
+        assert!(report.drivers().is_empty());
+        assert!(changes.try_recv().is_err());
+
+        // Dropping past the margin demotes, blaming only the missed signal
+        dipped.presence_consistency = 0.6;
+        let report = assessor.assess("channel", dipped, now);
+        assert_eq!(report.level, SacredAllianceLevel::Active);
+        assert_eq!(report.drivers(), vec![AllianceSignal::PresenceConsistency]);
+        let explanation = &report.explanations.iter().find(|e| e.drove_change).unwrap();
+        assert_eq!((explanation.value, explanation.threshold, explanation.met), (0.6, 0.7, false));
+        assert!(report.summary().contains("PresenceConsistency 0.60 vs 0.70"));
+    }
+
+    #[test]
+    fn test_pinned_level() {
+        let mut assessor = AllianceAssessor::default();
+        assessor.pin("project", SacredAllianceLevel::Guardian);
+
+        let report = assessor.assess("project", signals(0.2), Utc::now());
+        assert_eq!(report.level, SacredAllianceLevel::Guardian);
+        assert_eq!(report.recommended, SacredAllianceLevel::Basic);
+        assert!(report.pinned && !report.changed());
+        assert!(report.summary().contains("pinned"));
+
+        assessor.unpin("project");
+        let report = assessor.assess("project", signals(0.2), Utc::now());
+        assert_eq!(report.level, SacredAllianceLevel::Basic);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_periodic_channel_assessment() {
+        use crate::sacred_alliance::{
+            AllianceMessage, BasicCeremonyAction, ChannelConfig, MessageContent, Participant, ParticipantType,
+            PresenceStatus, PresenceUpdate,
+        };
+        use uuid::Uuid;
+
+        let message = |sender: &str, content| AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: sender.to_string(),
+            content,
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        };
+        let mut channel = BasicSacredAllianceChannel::new("pairing".to_string(), ChannelConfig::default());
+        for (id, participant_type) in [("human", ParticipantType::Human), ("ai", ParticipantType::Ai)] {
+            channel.add_participant(Participant {
+                id: id.to_string(),
+                participant_type,
+                presence: PresenceStatus::Active,
+                capabilities: vec![],
+                joined_at: Utc::now(),
+            }).unwrap();
+        }
+        // A split vote, settled by completing the ceremony
+        for (sender, vote, action_type) in [("human", "yes", "vote"), ("ai", "no", BasicCeremonyAction::COMPLETE)] {
+            let action = BasicCeremonyAction {
+                action_type: action_type.to_string(),
+                description: "retro".to_string(),
+                parameters: HashMap::from([
+                    (BasicCeremonyAction::CEREMONY_ID.to_string(), "retro".to_string()),
+                    ("vote".to_string(), vote.to_string()),
+                ]),
+            };
+            channel.send_message(message(sender, MessageContent::Ceremony(action))).unwrap();
+        }
+        let channel = Arc::new(RwLock::new(channel));
+
+        let assessor = Arc::new(Mutex::new(AllianceAssessor::default()));
+        let mut changes = assessor.lock().await.subscribe();
+        let cancel = CancellationToken::new();
+        let interval = std::time::Duration::from_secs(60);
+        let handle = spawn_periodic_assessment(assessor.clone(), vec![channel.clone()], interval, cancel.clone());
+
+        let report = changes.recv().await.unwrap();
+        assert_eq!((report.scope.as_str(), report.level.clone()), ("pairing", SacredAllianceLevel::Guardian));
+        assert_eq!(report.signals, signals(1.0));
+        assert_eq!(*channel.read().await.alliance_level(), SacredAllianceLevel::Guardian);
+
+        // Half the participants step away; the next round demotes the channel
+        let away = PresenceUpdate { status: PresenceStatus::Away, message: None, duration: None };
+        channel.write().await.send_message(message("ai", MessageContent::Presence(away))).unwrap();
+        channel.write().await.send_message(message("human", MessageContent::Text("carrying on".to_string()))).unwrap();
+        tokio::time::sleep(interval).await;
+        let report = changes.recv().await.unwrap();
+        assert_eq!(report.level, SacredAllianceLevel::Active);
+        assert_eq!(report.drivers(), vec![AllianceSignal::PresenceConsistency]);
+        assert_eq!(*channel.read().await.alliance_level(), SacredAllianceLevel::Active);
+
+        cancel.cancel();
+        handle.await.unwrap();
+    }
+}
@@ -0,0 +1,467 @@
+//! Scoped API tokens for the HTTP interface
+//!
+//! Each token grants a set of scopes, expires, and may be limited to a set
+//! of client addresses. Only a hash of the secret is kept; the secret itself
+//! is returned once when the token is issued.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::mesh::security::{ResolutionStatus, SecurityEvent, SecurityEventType, SecuritySeverity, SecuritySystem};
+use crate::storage::{content_checksum, AccessControl, Storage};
+
+/// Storage name of the persisted token registry
+const REGISTRY_RESOURCE_NAME: &str = "api-tokens";
+
+/// Permission carried by an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ApiScope {
+    #[serde(rename = "financial:read")]
+    FinancialRead,
+    #[serde(rename = "financial:write")]
+    FinancialWrite,
+    #[serde(rename = "mesh:read")]
+    MeshRead,
+    #[serde(rename = "events:subscribe")]
+    EventsSubscribe,
+    #[serde(rename = "debug:read")]
+    DebugRead,
+    #[serde(rename = "situation:read")]
+    SituationRead,
+    #[serde(rename = "situation:write")]
+    SituationWrite,
+    /// Manage tokens; grants every other scope
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiScope {
+    /// Every scope
+    pub const ALL: [ApiScope; 8] = [
+        ApiScope::FinancialRead,
+        ApiScope::FinancialWrite,
+        ApiScope::MeshRead,
+        ApiScope::EventsSubscribe,
+        ApiScope::DebugRead,
+        ApiScope::SituationRead,
+        ApiScope::SituationWrite,
+        ApiScope::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::FinancialRead => "financial:read",
+            ApiScope::FinancialWrite => "financial:write",
+            ApiScope::MeshRead => "mesh:read",
+            ApiScope::EventsSubscribe => "events:subscribe",
+            ApiScope::DebugRead => "debug:read",
+            ApiScope::SituationRead => "situation:read",
+            ApiScope::SituationWrite => "situation:write",
+            ApiScope::Admin => "admin",
+        }
+    }
+
+    /// Scope an endpoint requires, by path prefix and method
+    ///
+    /// Paths outside the known endpoint groups require `Admin`.
+    pub fn required_for(method: &str, path: &str) -> ApiScope {
+        let read = matches!(method, "GET" | "HEAD");
+        let group = path.trim_start_matches('/').split('/').next().unwrap_or("");
+        match group {
+            "financial" if read => ApiScope::FinancialRead,
+            "financial" => ApiScope::FinancialWrite,
+            "mesh" | "channels" | "metrics" => ApiScope::MeshRead,
+            "events" => ApiScope::EventsSubscribe,
+            "diagnostics" | "debug" => ApiScope::DebugRead,
+            "situations" if read => ApiScope::SituationRead,
+            "situations" => ApiScope::SituationWrite,
+            _ => ApiScope::Admin,
+        }
+    }
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ApiScope::ALL.iter()
+            .find(|scope| scope.as_str() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown API scope: {}", s))
+    }
+}
+
+/// Stored form of an API token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenRecord {
+    pub token_id: Uuid,
+    /// Who or what the token is for, e.g. "billing-dashboard"
+    pub name: String,
+    /// Hex SHA-256 of the secret
+    pub token_hash: String,
+    pub scopes: BTreeSet<ApiScope>,
+    pub created_at: DateTime<Utc>,
+    /// Name of the token that issued this one
+    pub created_by: String,
+    pub expires_at: DateTime<Utc>,
+    /// Client addresses allowed to use the token; any if empty
+    #[serde(default)]
+    pub ip_allowlist: Vec<IpAddr>,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_from: Option<IpAddr>,
+    #[serde(default)]
+    pub use_count: u64,
+}
+
+impl ApiTokenRecord {
+    /// Whether the token carries `scope`, directly or through `Admin`
+    pub fn grants(&self, scope: ApiScope) -> bool {
+        self.scopes.contains(&scope) || self.scopes.contains(&ApiScope::Admin)
+    }
+}
+
+/// Parameters of a new token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewApiToken {
+    pub name: String,
+    pub scopes: BTreeSet<ApiScope>,
+    /// Lifetime in seconds
+    pub ttl_seconds: i64,
+    #[serde(default)]
+    pub ip_allowlist: Vec<IpAddr>,
+}
+
+/// Errors authenticating or managing API tokens
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ApiTokenError {
+    #[error("No API token presented")]
+    Missing,
+    #[error("Unknown API token")]
+    Unknown,
+    #[error("API token has been revoked")]
+    Revoked,
+    #[error("API token has expired")]
+    Expired,
+    #[error("API token may not be used from {0:?}")]
+    AddressNotAllowed(Option<IpAddr>),
+    #[error("API token is restricted to an IP allowlist but the client address is unknown; serve the router with `into_make_service_with_connect_info::<SocketAddr>()`")]
+    ClientAddressUnknown,
+    #[error("API token lacks the {0} scope")]
+    MissingScope(ApiScope),
+    #[error("Invalid token request: {0}")]
+    InvalidRequest(String),
+}
+
+/// Hashed API tokens, persisted through [`Storage`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ApiTokenRegistry {
+    /// Tokens by hash of their secret
+    tokens: HashMap<String, ApiTokenRecord>,
+    #[serde(skip)]
+    persisted_id: Option<String>,
+}
+
+impl ApiTokenRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issue a token, returning its secret and record
+    pub fn issue(&mut self, request: NewApiToken, created_by: &str, now: DateTime<Utc>) -> Result<(String, ApiTokenRecord), ApiTokenError> {
+        if request.scopes.is_empty() {
+            return Err(ApiTokenError::InvalidRequest("a token needs at least one scope".to_string()));
+        }
+        if request.ttl_seconds <= 0 {
+            return Err(ApiTokenError::InvalidRequest("ttl_seconds must be positive".to_string()));
+        }
+
+        let mut bytes = [0u8; 32];
+        SystemRandom::new().fill(&mut bytes)
+            .map_err(|_| ApiTokenError::InvalidRequest("no randomness available".to_string()))?;
+        let secret: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+        let record = ApiTokenRecord {
+            token_id: Uuid::new_v4(),
+            name: request.name,
+            token_hash: content_checksum(secret.as_bytes()),
+            scopes: request.scopes,
+            created_at: now,
+            created_by: created_by.to_string(),
+            expires_at: now + Duration::seconds(request.ttl_seconds),
+            ip_allowlist: request.ip_allowlist,
+            revoked_at: None,
+            last_used_at: None,
+            last_used_from: None,
+            use_count: 0,
+        };
+        self.tokens.insert(record.token_hash.clone(), record.clone());
+        Ok((secret, record))
+    }
+
+    /// Check `secret` for `scope` from `client`, recording the use
+    pub fn authorize(
+        &mut self,
+        secret: &str,
+        scope: ApiScope,
+        client: Option<IpAddr>,
+        now: DateTime<Utc>,
+    ) -> Result<ApiTokenRecord, ApiTokenError> {
+        let record = self.tokens.get_mut(&content_checksum(secret.as_bytes())).ok_or(ApiTokenError::Unknown)?;
+        if record.revoked_at.is_some() {
+            return Err(ApiTokenError::Revoked);
+        }
+        if record.expires_at <= now {
+            return Err(ApiTokenError::Expired);
+        }
+        if !record.ip_allowlist.is_empty() {
+            match client {
+                None => return Err(ApiTokenError::ClientAddressUnknown),
+                Some(ip) if !record.ip_allowlist.contains(&ip) => return Err(ApiTokenError::AddressNotAllowed(client)),
+                Some(_) => {}
+            }
+        }
+        if !record.grants(scope) {
+            return Err(ApiTokenError::MissingScope(scope));
+        }
+
+        record.last_used_at = Some(now);
+        record.last_used_from = client;
+        record.use_count += 1;
+        Ok(record.clone())
+    }
+
+    /// Revoke a token; it is refused from the next request on
+    pub fn revoke(&mut self, token_id: Uuid, now: DateTime<Utc>) -> Option<ApiTokenRecord> {
+        let record = self.tokens.values_mut().find(|record| record.token_id == token_id)?;
+        record.revoked_at.get_or_insert(now);
+        Some(record.clone())
+    }
+
+    /// All tokens, newest first
+    pub fn list(&self) -> Vec<ApiTokenRecord> {
+        let mut tokens: Vec<ApiTokenRecord> = self.tokens.values().cloned().collect();
+        tokens.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+        tokens
+    }
+
+    /// Persist the registry, replacing any copy it persisted before
+    pub async fn persist<S: Storage>(&mut self, storage: &mut S) -> Result<String> {
+        let content = serde_json::to_vec(&*self)?;
+        let resource_id = storage.store_resource(
+            REGISTRY_RESOURCE_NAME.to_string(),
+            content,
+            "application/json".to_string(),
+            AccessControl::default(),
+            vec!["api-tokens".to_string()],
+        ).await?;
+
+        if let Some(previous) = self.persisted_id.replace(resource_id.clone()) {
+            let _ = storage.delete_resource(&previous).await;
+        }
+        Ok(resource_id)
+    }
+
+    /// Load a previously persisted registry
+    pub async fn load<S: Storage>(storage: &S, resource_id: &str) -> Result<Self> {
+        let content = storage.get_resource_content(resource_id).await?;
+        let mut registry: Self = serde_json::from_slice(&content)?;
+        registry.persisted_id = Some(resource_id.to_string());
+        Ok(registry)
+    }
+}
+
+/// Shared token registry that reports issuance and revocation as security events
+pub struct ApiTokenAuthority {
+    registry: RwLock<ApiTokenRegistry>,
+    security: Option<Arc<SecuritySystem>>,
+}
+
+impl ApiTokenAuthority {
+    pub fn new(registry: ApiTokenRegistry) -> Self {
+        Self { registry: RwLock::new(registry), security: None }
+    }
+
+    /// Log token issuance and revocation to `security`
+    pub fn with_security_system(mut self, security: Arc<SecuritySystem>) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    /// Issue a token on behalf of `issuer`, who must hold `Admin`
+    ///
+    /// Pass `None` only to bootstrap the first admin token from the command line.
+    pub async fn issue(&self, request: NewApiToken, issuer: Option<&ApiTokenRecord>) -> Result<(String, ApiTokenRecord), ApiTokenError> {
+        if let Some(issuer) = issuer {
+            if !issuer.grants(ApiScope::Admin) {
+                return Err(ApiTokenError::MissingScope(ApiScope::Admin));
+            }
+        }
+        let created_by = issuer.map_or("bootstrap", |issuer| issuer.name.as_str()).to_string();
+        let (secret, record) = self.registry.write().await.issue(request, &created_by, Utc::now())?;
+
+        let scopes: Vec<&str> = record.scopes.iter().map(|scope| scope.as_str()).collect();
+        self.log(
+            SecuritySeverity::Medium,
+            format!("API token '{}' issued by {}", record.name, created_by),
+            &record,
+            [("scopes".to_string(), scopes.join(",")), ("expires_at".to_string(), record.expires_at.to_rfc3339())],
+        ).await;
+        Ok((secret, record))
+    }
+
+    /// Check a presented secret for `scope`
+    pub async fn authorize(&self, secret: &str, scope: ApiScope, client: Option<IpAddr>) -> Result<ApiTokenRecord, ApiTokenError> {
+        self.registry.write().await.authorize(secret, scope, client, Utc::now())
+    }
+
+    /// Revoke a token on behalf of `revoked_by`
+    pub async fn revoke(&self, token_id: Uuid, revoked_by: &str) -> Option<ApiTokenRecord> {
+        let record = self.registry.write().await.revoke(token_id, Utc::now())?;
+        self.log(
+            SecuritySeverity::Medium,
+            format!("API token '{}' revoked by {}", record.name, revoked_by),
+            &record,
+            [("revoked_by".to_string(), revoked_by.to_string()), ("expires_at".to_string(), record.expires_at.to_rfc3339())],
+        ).await;
+        Some(record)
+    }
+
+    /// All tokens, with their last use
+    pub async fn list(&self) -> Vec<ApiTokenRecord> {
+        self.registry.read().await.list()
+    }
+
+    /// Persist the registry through `storage`
+    pub async fn persist<S: Storage>(&self, storage: &mut S) -> Result<String> {
+        self.registry.write().await.persist(storage).await
+    }
+
+    async fn log(&self, severity: SecuritySeverity, description: String, record: &ApiTokenRecord, metadata: [(String, String); 2]) {
+        let Some(security) = &self.security else {
+            return;
+        };
+        let mut metadata: HashMap<String, String> = metadata.into_iter().collect();
+        metadata.insert("token_id".to_string(), record.token_id.to_string());
+        metadata.insert("token_name".to_string(), record.name.clone());
+        security.log_security_event(SecurityEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: SecurityEventType::ConfigurationChange,
+            involved_nodes: Vec::new(),
+            description,
+            severity,
+            response_actions: Vec::new(),
+            resolution_status: ResolutionStatus::Resolved,
+            metadata,
+            related_events: Vec::new(),
+        }).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn request(scopes: &[ApiScope], ttl_seconds: i64) -> NewApiToken {
+        NewApiToken {
+            name: "dashboard".to_string(),
+            scopes: scopes.iter().copied().collect(),
+            ttl_seconds,
+            ip_allowlist: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_endpoint_scopes() {
+        assert_eq!(ApiScope::required_for("GET", "/financial/summary"), ApiScope::FinancialRead);
+        assert_eq!(ApiScope::required_for("POST", "/financial/limits"), ApiScope::FinancialWrite);
+        assert_eq!(ApiScope::required_for("GET", "/mesh/topology"), ApiScope::MeshRead);
+        assert_eq!(ApiScope::required_for("GET", "/diagnostics/ping/x"), ApiScope::DebugRead);
+        assert_eq!(ApiScope::required_for("DELETE", "/situations/pins/x"), ApiScope::SituationWrite);
+        assert_eq!(ApiScope::required_for("POST", "/tokens"), ApiScope::Admin);
+        assert_eq!("events:subscribe".parse::<ApiScope>(), Ok(ApiScope::EventsSubscribe));
+        assert_eq!(serde_json::to_string(&ApiScope::DebugRead).unwrap(), "\"debug:read\"");
+    }
+
+    #[tokio::test]
+    async fn test_expiry_allowlist_and_persistence() {
+        let mut registry = ApiTokenRegistry::new();
+        let now = Utc::now();
+        let office: IpAddr = "10.0.0.7".parse().unwrap();
+        let (secret, record) = registry.issue(
+            NewApiToken { ip_allowlist: vec![office], ..request(&[ApiScope::MeshRead], 60) },
+            "admin",
+            now,
+        ).unwrap();
+        assert_ne!(record.token_hash, secret);
+
+        assert!(registry.authorize(&secret, ApiScope::MeshRead, Some(office), now).is_ok());
+        assert_eq!(
+            registry.authorize(&secret, ApiScope::MeshRead, Some("10.0.0.8".parse().unwrap()), now).unwrap_err(),
+            ApiTokenError::AddressNotAllowed(Some("10.0.0.8".parse().unwrap()))
+        );
+        assert_eq!(
+            registry.authorize(&secret, ApiScope::MeshRead, None, now).unwrap_err(),
+            ApiTokenError::ClientAddressUnknown
+        );
+        assert_eq!(
+            registry.authorize(&secret, ApiScope::MeshRead, Some(office), now + Duration::seconds(61)).unwrap_err(),
+            ApiTokenError::Expired
+        );
+
+        let mut storage = MemoryStorage::new();
+        let resource_id = registry.persist(&mut storage).await.unwrap();
+        let content = storage.get_resource_content(&resource_id).await.unwrap();
+        assert!(!String::from_utf8(content).unwrap().contains(&secret));
+
+        let restored = ApiTokenRegistry::load(&storage, &resource_id).await.unwrap();
+        let restored = &restored.list()[0];
+        assert_eq!(restored.use_count, 1);
+        assert_eq!(restored.last_used_from, Some(office));
+    }
+
+    #[tokio::test]
+    async fn test_issuance_and_revocation_are_audited() {
+        let security = Arc::new(SecuritySystem::new(Uuid::new_v4(), None));
+        let authority = ApiTokenAuthority::new(ApiTokenRegistry::new()).with_security_system(Arc::clone(&security));
+
+        let (_, admin) = authority.issue(request(&[ApiScope::Admin], 3600), None).await.unwrap();
+        let (secret, reader) = authority.issue(request(&[ApiScope::FinancialRead], 3600), Some(&admin)).await.unwrap();
+        assert_eq!(reader.created_by, "dashboard");
+        assert_eq!(
+            authority.issue(request(&[ApiScope::MeshRead], 3600), Some(&reader)).await.unwrap_err(),
+            ApiTokenError::MissingScope(ApiScope::Admin)
+        );
+
+        assert!(authority.authorize(&secret, ApiScope::FinancialRead, None).await.is_ok());
+        authority.revoke(reader.token_id, &admin.name).await.unwrap();
+        assert_eq!(authority.authorize(&secret, ApiScope::FinancialRead, None).await.unwrap_err(), ApiTokenError::Revoked);
+
+        let events = security.get_security_events(None).await;
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|event| event.event_type == SecurityEventType::ConfigurationChange));
+        assert_eq!(events[1].metadata["scopes"], "financial:read");
+        assert_eq!(events[2].metadata["token_id"], reader.token_id.to_string());
+    }
+}
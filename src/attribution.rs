@@ -4,11 +4,16 @@
 //! in WeaveMesh, supporting various collaboration patterns while maintaining
 //! simplicity and extensibility through plugins.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use crate::storage::content_checksum;
+
 /// Unique identifier for attribution records
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AttributionId(Uuid);
@@ -43,7 +48,7 @@ impl std::fmt::Display for AttributionId {
 }
 
 /// Types of collaboration patterns
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum CollaborationType {
     /// Human-led work with possible AI assistance
     HumanLed,
@@ -59,10 +64,26 @@ pub enum CollaborationType {
     Automated,
     /// Coordination between multiple contributors
     Coordination,
+    /// Single-author commit taken from git history
+    GitCommit,
     /// Custom collaboration type
     Custom(String),
 }
 
+impl CollaborationType {
+    /// Built-in collaboration types (every variant except `Custom`)
+    pub const BUILT_IN: [CollaborationType; 8] = [
+        CollaborationType::HumanLed,
+        CollaborationType::AILed,
+        CollaborationType::CoCreated,
+        CollaborationType::PairProgramming,
+        CollaborationType::Individual,
+        CollaborationType::Automated,
+        CollaborationType::Coordination,
+        CollaborationType::GitCommit,
+    ];
+}
+
 /// Basic attribution information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attribution {
@@ -75,12 +96,20 @@ pub struct Attribution {
     /// AI contributor identifier  
     pub ai_contributor: Option<String>,
     
+    /// Additional contributor, such as a commit co-author
+    #[serde(default)]
+    pub secondary_contributor: Option<String>,
+    
     /// Type of collaboration
     pub collaboration_type: CollaborationType,
     
     /// Confidence in attribution (0.0 to 1.0)
     pub confidence: f32,
     
+    /// Size of the contribution, such as lines changed in a commit
+    #[serde(default)]
+    pub weight: Option<f64>,
+    
     /// Timestamp of attribution
     pub timestamp: DateTime<Utc>,
     
@@ -100,8 +129,10 @@ impl Attribution {
             id: AttributionId::new(),
             human_contributor,
             ai_contributor,
+            secondary_contributor: None,
             collaboration_type,
             confidence: confidence.clamp(0.0, 1.0),
+            weight: None,
             timestamp: Utc::now(),
             metadata: HashMap::new(),
         }
@@ -181,7 +212,8 @@ impl Attribution {
         }
         
         // Check if collaborative work has appropriate contributors
-        if self.is_collaborative() && !self.has_both_contributors() {
+        let has_partner = self.ai_contributor.is_some() || self.secondary_contributor.is_some();
+        if self.is_collaborative() && !(self.human_contributor.is_some() && has_partner) {
             return Err(AttributionError::IncompleteCollaboration);
         }
         
@@ -261,6 +293,52 @@ pub struct AttributionConfig {
     
     /// Patterns that indicate collaborative work
     pub collaboration_indicators: Vec<String>,
+    
+    /// Multiplier applied to an attribution's weight by collaboration type
+    ///
+    /// Types without an entry use a multiplier of 1.0.
+    #[serde(default = "default_type_weights")]
+    pub type_weights: HashMap<CollaborationType, f64>,
+}
+
+fn default_type_weights() -> HashMap<CollaborationType, f64> {
+    CollaborationType::BUILT_IN
+        .iter()
+        .map(|collaboration_type| (collaboration_type.clone(), 1.0))
+        .collect()
+}
+
+impl AttributionConfig {
+    /// Multiplier for a collaboration type, falling back to 1.0
+    pub fn type_weight(&self, collaboration_type: &CollaborationType) -> f64 {
+        self.type_weights.get(collaboration_type).copied().unwrap_or(1.0)
+    }
+    
+    /// Check that type weights are non-negative and cover every built-in type
+    pub fn validate_weights(&self) -> Result<(), AttributionError> {
+        for (collaboration_type, weight) in &self.type_weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(AttributionError::ConfigError(format!(
+                    "Invalid weight {} for collaboration type {:?}",
+                    weight, collaboration_type
+                )));
+            }
+        }
+        
+        let missing: Vec<String> = CollaborationType::BUILT_IN
+            .iter()
+            .filter(|collaboration_type| !self.type_weights.contains_key(*collaboration_type))
+            .map(|collaboration_type| format!("{:?}", collaboration_type))
+            .collect();
+        if !missing.is_empty() {
+            return Err(AttributionError::ConfigError(format!(
+                "Missing type weights for: {}",
+                missing.join(", ")
+            )));
+        }
+        
+        Ok(())
+    }
 }
 
 impl Default for AttributionConfig {
@@ -289,6 +367,7 @@ impl Default for AttributionConfig {
                 "review".to_string(),
                 "session".to_string(),
             ],
+            type_weights: default_type_weights(),
         }
     }
 }
@@ -316,6 +395,12 @@ pub struct BasicAttributionEngine {
     
     /// Historical attribution data
     history: Vec<Attribution>,
+    
+    /// Keys of attributions added through `import`
+    imported_keys: HashSet<String>,
+    
+    /// Whether the history is read-only
+    frozen: bool,
 }
 
 impl BasicAttributionEngine {
@@ -324,6 +409,8 @@ impl BasicAttributionEngine {
         Self {
             config,
             history: Vec::new(),
+            imported_keys: HashSet::new(),
+            frozen: false,
         }
     }
     
@@ -334,6 +421,10 @@ impl BasicAttributionEngine {
     
     /// Analyze context and determine attribution
     pub fn analyze(&mut self, context: AttributionContext) -> Result<AttributionAnalysis, AttributionError> {
+        if self.frozen {
+            return Err(AttributionError::Frozen);
+        }
+        
         let mut confidence_factors = HashMap::new();
         let mut reasoning = Vec::new();
         
@@ -563,27 +654,107 @@ impl BasicAttributionEngine {
         suggestions
     }
     
+    /// Add an externally derived attribution to the history once per key
+    ///
+    /// Returns false if an attribution with this key was already imported,
+    /// or if the engine is frozen.
+    pub fn import(&mut self, key: String, attribution: Attribution) -> bool {
+        if self.frozen || !self.imported_keys.insert(key) {
+            return false;
+        }
+        self.history.push(attribution);
+        true
+    }
+    
+    /// Whether an attribution with this key was already imported
+    pub fn is_imported(&self, key: &str) -> bool {
+        self.imported_keys.contains(key)
+    }
+    
     /// Get attribution history
     pub fn get_history(&self) -> &[Attribution] {
         &self.history
     }
     
+    /// Make the history read-only; later analyses and imports are rejected
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+    
+    /// Whether `freeze` has been called
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+    
+    /// Append the history to a hash-chained NDJSON ledger at `path`
+    ///
+    /// An existing ledger is verified first and only attributions it does not
+    /// already hold are appended, chained from its last entry.
+    pub fn export_ledger(&self, path: &Path) -> Result<LedgerExport, AttributionError> {
+        let existing = if path.exists() {
+            read_ledger(path).map_err(|e| AttributionError::ExportFailed(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+        let exported: HashSet<&AttributionId> = existing.iter().map(|entry| &entry.attribution.id).collect();
+        let mut prev_hash = existing.last().map_or_else(|| LEDGER_GENESIS_HASH.to_string(), |e| e.entry_hash.clone());
+        
+        let mut lines = String::new();
+        let mut appended = 0;
+        for attribution in self.history.iter().filter(|a| !exported.contains(&a.id)) {
+            let entry = LedgerEntry::new(attribution.clone(), prev_hash);
+            let line = serde_json::to_string(&entry).map_err(|e| AttributionError::ExportFailed(e.to_string()))?;
+            lines.push_str(&line);
+            lines.push('\n');
+            prev_hash = entry.entry_hash;
+            appended += 1;
+        }
+        
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| AttributionError::ExportFailed(e.to_string()))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|e| AttributionError::ExportFailed(e.to_string()))?;
+        
+        Ok(LedgerExport {
+            path: path.to_path_buf(),
+            entries: existing.len() + appended,
+            appended,
+            last_hash: prev_hash,
+        })
+    }
+    
+    /// Attribution weight scaled by its collaboration type's multiplier
+    ///
+    /// The raw weight is the attribution's size-based weight scaled by its
+    /// confidence, or the confidence alone when no weight was recorded.
+    pub fn normalized_weight(&self, attribution: &Attribution) -> f64 {
+        let raw = attribution.weight.unwrap_or(1.0) * attribution.confidence as f64;
+        raw * self.config.type_weight(&attribution.collaboration_type)
+    }
+    
     /// Get attribution statistics
     pub fn get_statistics(&self) -> AttributionStatistics {
         let total = self.history.len();
         let mut collaboration_types = HashMap::new();
         let mut total_confidence = 0.0;
+        let mut weighted_total = 0.0;
         
         for attribution in &self.history {
             let count = collaboration_types.entry(format!("{:?}", attribution.collaboration_type)).or_insert(0);
             *count += 1;
             total_confidence += attribution.confidence;
+            weighted_total += self.normalized_weight(attribution);
         }
         
         AttributionStatistics {
             total_attributions: total,
             average_confidence: if total > 0 { total_confidence / total as f32 } else { 0.0 },
             collaboration_type_distribution: collaboration_types,
+            weighted_total,
         }
     }
 }
@@ -599,6 +770,9 @@ pub struct AttributionStatistics {
     
     /// Distribution of collaboration types
     pub collaboration_type_distribution: HashMap<String, usize>,
+    
+    /// Sum of normalized attribution weights
+    pub weighted_total: f64,
 }
 
 /// Attribution-related errors
@@ -618,14 +792,130 @@ pub enum AttributionError {
     
     #[error("Configuration error: {0}")]
     ConfigError(String),
+    
+    #[error("Attribution engine is frozen")]
+    Frozen,
+    
+    #[error("Ledger export failed: {0}")]
+    ExportFailed(String),
+}
+
+/// `prev_hash` of the first ledger entry
+pub const LEDGER_GENESIS_HASH: &str = "genesis";
+
+/// One line of an exported attribution ledger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub attribution: Attribution,
+    /// Hash of the attribution and `prev_hash`
+    pub entry_hash: String,
+    /// `entry_hash` of the line before, [`LEDGER_GENESIS_HASH`] for the first
+    pub prev_hash: String,
+}
+
+impl LedgerEntry {
+    fn new(attribution: Attribution, prev_hash: String) -> Self {
+        let entry_hash = Self::compute_hash(&attribution, &prev_hash);
+        Self { attribution, entry_hash, prev_hash }
+    }
+    
+    /// Hash over the attribution's canonical JSON (sorted keys) and `prev_hash`
+    pub fn compute_hash(attribution: &Attribution, prev_hash: &str) -> String {
+        let canonical = serde_json::to_value(attribution)
+            .and_then(|value| serde_json::to_vec(&(value, prev_hash)))
+            .unwrap_or_default();
+        content_checksum(&canonical)
+    }
+}
+
+/// Result of [`BasicAttributionEngine::export_ledger`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerExport {
+    pub path: PathBuf,
+    /// Lines in the ledger after the export
+    pub entries: usize,
+    /// Lines written by this export
+    pub appended: usize,
+    /// `entry_hash` of the last line
+    pub last_hash: String,
+}
+
+impl LedgerExport {
+    /// Re-read the ledger and check its hash chain
+    ///
+    /// Entries appended later are checked too; the entry this export ended
+    /// on must still carry `last_hash`.
+    pub fn verify_integrity(&self) -> Result<(), IntegrityError> {
+        let entries = read_ledger(&self.path)?;
+        if entries.len() < self.entries {
+            return Err(IntegrityError::Truncated { expected: self.entries, actual: entries.len() });
+        }
+        if let Some(last) = self.entries.checked_sub(1).map(|i| &entries[i]) {
+            if last.entry_hash != self.last_hash {
+                return Err(IntegrityError::TamperedEntry {
+                    line_number: self.entries,
+                    expected_hash: self.last_hash.clone(),
+                    actual_hash: last.entry_hash.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ways an exported ledger can fail verification
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrityError {
+    #[error("Failed to read ledger: {0}")]
+    Io(#[from] std::io::Error),
+    
+    #[error("Line {line_number} is not a ledger entry: {reason}")]
+    MalformedEntry { line_number: usize, reason: String },
+    
+    #[error("Line {line_number} was altered: expected hash {expected_hash}, found {actual_hash}")]
+    TamperedEntry { line_number: usize, expected_hash: String, actual_hash: String },
+    
+    #[error("Line {line_number} does not follow the line before: expected prev_hash {expected_prev_hash}, found {actual_prev_hash}")]
+    BrokenChain { line_number: usize, expected_prev_hash: String, actual_prev_hash: String },
+    
+    #[error("Ledger holds {actual} entries, {expected} were exported")]
+    Truncated { expected: usize, actual: usize },
+}
+
+/// Read a ledger, checking each entry's hash and its link to the one before
+fn read_ledger(path: &Path) -> Result<Vec<LedgerEntry>, IntegrityError> {
+    let file = std::fs::File::open(path)?;
+    let mut entries: Vec<LedgerEntry> = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line_number = index + 1;
+        let entry: LedgerEntry = serde_json::from_str(&line?)
+            .map_err(|e| IntegrityError::MalformedEntry { line_number, reason: e.to_string() })?;
+        
+        let expected_prev_hash = entries.last().map_or(LEDGER_GENESIS_HASH, |e| e.entry_hash.as_str());
+        if entry.prev_hash != expected_prev_hash {
+            return Err(IntegrityError::BrokenChain {
+                line_number,
+                expected_prev_hash: expected_prev_hash.to_string(),
+                actual_prev_hash: entry.prev_hash,
+            });
+        }
+        let actual_hash = LedgerEntry::compute_hash(&entry.attribution, &entry.prev_hash);
+        if entry.entry_hash != actual_hash {
+            return Err(IntegrityError::TamperedEntry { line_number, expected_hash: entry.entry_hash, actual_hash });
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
 }
 
 /// Attribution builder for easy construction
 pub struct AttributionBuilder {
     human_contributor: Option<String>,
     ai_contributor: Option<String>,
+    secondary_contributor: Option<String>,
     collaboration_type: CollaborationType,
     confidence: f32,
+    weight: Option<f64>,
     metadata: HashMap<String, String>,
 }
 
@@ -634,8 +924,10 @@ impl Default for AttributionBuilder {
         Self {
             human_contributor: None,
             ai_contributor: None,
+            secondary_contributor: None,
             collaboration_type: CollaborationType::Individual,
             confidence: 1.0,
+            weight: None,
             metadata: HashMap::new(),
         }
     }
@@ -647,6 +939,54 @@ impl AttributionBuilder {
         Self::default()
     }
     
+    /// Start from a git commit
+    ///
+    /// The author's name becomes the human contributor and the weight is the
+    /// number of lines inserted and deleted relative to the first parent. A
+    /// `Co-authored-by` trailer makes the commit CoCreated, with the first
+    /// co-author as secondary contributor; otherwise it is a GitCommit.
+    pub fn from_git_commit(
+        repo: &git2::Repository,
+        commit: &git2::Commit,
+    ) -> Result<AttributionBuilder, AttributionError> {
+        let git_error = |e: git2::Error| AttributionError::AnalysisFailed(e.to_string());
+        
+        let author = commit.author().name().map(str::to_string).ok_or(AttributionError::MissingContributor)?;
+        let parent_tree = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0).and_then(|parent| parent.tree()).map_err(git_error)?),
+        };
+        let tree = commit.tree().map_err(git_error)?;
+        let stats = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .and_then(|diff| diff.stats())
+            .map_err(git_error)?;
+        
+        let co_authors: Vec<String> = git2::message_trailers_strs(commit.message().unwrap_or_default())
+            .map(|trailers| {
+                trailers.iter()
+                    .filter(|(key, _)| key.eq_ignore_ascii_case(crate::git::attribution_integration::CO_AUTHOR_TRAILER))
+                    .map(|(_, value)| value.split('<').next().unwrap_or_default().trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        let mut builder = Self::new()
+            .human(author)
+            .weight((stats.insertions() + stats.deletions()) as f64)
+            .metadata("commit_hash".to_string(), commit.id().to_string());
+        builder = match co_authors.first() {
+            Some(co_author) => {
+                builder.secondary_contributor = Some(co_author.clone());
+                builder
+                    .collaboration_type(CollaborationType::CoCreated)
+                    .metadata("co_authors".to_string(), co_authors.join(", "))
+            }
+            None => builder.collaboration_type(CollaborationType::GitCommit),
+        };
+        Ok(builder)
+    }
+    
     /// Set human contributor
     pub fn human(mut self, contributor: String) -> Self {
         self.human_contributor = Some(contributor);
@@ -659,6 +999,18 @@ impl AttributionBuilder {
         self
     }
     
+    /// Set secondary contributor
+    pub fn secondary(mut self, contributor: String) -> Self {
+        self.secondary_contributor = Some(contributor);
+        self
+    }
+    
+    /// Set size-based weight
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+    
     /// Set collaboration type
     pub fn collaboration_type(mut self, collaboration_type: CollaborationType) -> Self {
         self.collaboration_type = collaboration_type;
@@ -677,16 +1029,19 @@ impl AttributionBuilder {
         self
     }
     
-    /// Build the attribution
-    pub fn build(self) -> Attribution {
+    /// Build and validate the attribution
+    pub fn build(self) -> Result<Attribution, AttributionError> {
         let mut attribution = Attribution::new(
             self.human_contributor,
             self.ai_contributor,
             self.collaboration_type,
             self.confidence,
         );
+        attribution.secondary_contributor = self.secondary_contributor;
         attribution.metadata = self.metadata;
-        attribution
+        attribution.weight = self.weight;
+        attribution.validate()?;
+        Ok(attribution)
     }
 }
 
@@ -720,7 +1075,8 @@ mod tests {
             .collaboration_type(CollaborationType::CoCreated)
             .confidence(0.8)
             .metadata("project".to_string(), "test".to_string())
-            .build();
+            .build()
+            .unwrap();
         
         assert_eq!(attribution.human_contributor, Some("alice".to_string()));
         assert_eq!(attribution.ai_contributor, Some("claude".to_string()));
@@ -730,6 +1086,48 @@ mod tests {
         assert!(attribution.has_both_contributors());
     }
     
+    fn commit(repo: &git2::Repository, content: &str, message: &str) -> git2::Oid {
+        std::fs::write(repo.workdir().unwrap().join("notes.txt"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("notes.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Alice", "alice@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+    }
+    
+    #[test]
+    fn test_builder_from_git_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let first = commit(&repo, "one\ntwo\nthree\n", "Add notes");
+        let second = commit(
+            &repo,
+            "one\n2\nthree\nfour\n",
+            "Revise notes\n\nCo-authored-by: Bob Builder <bob@example.com>\n",
+        );
+        
+        let solo = AttributionBuilder::from_git_commit(&repo, &repo.find_commit(first).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(solo.human_contributor.as_deref(), Some("Alice"));
+        assert_eq!(solo.collaboration_type, CollaborationType::GitCommit);
+        assert_eq!(solo.weight, Some(3.0));
+        assert!(solo.secondary_contributor.is_none());
+        
+        let paired = AttributionBuilder::from_git_commit(&repo, &repo.find_commit(second).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(paired.collaboration_type, CollaborationType::CoCreated);
+        assert_eq!(paired.human_contributor.as_deref(), Some("Alice"));
+        assert_eq!(paired.secondary_contributor.as_deref(), Some("Bob Builder"));
+        // "two" replaced by "2", "four" added
+        assert_eq!(paired.weight, Some(3.0));
+    }
+    
     #[test]
     fn test_basic_attribution_engine() {
         let mut engine = BasicAttributionEngine::default();
@@ -755,4 +1153,70 @@ mod tests {
         assert!(analysis.attribution.is_collaborative());
         assert!(analysis.attribution.has_both_contributors());
     }
+    
+    #[test]
+    fn test_type_weights() {
+        let mut config = AttributionConfig::default();
+        assert!(config.validate_weights().is_ok());
+        
+        config.type_weights.insert(CollaborationType::CoCreated, 2.0);
+        let mut engine = BasicAttributionEngine::new(config.clone());
+        
+        let co_created = Attribution::new(Some("alice".to_string()), Some("claude".to_string()), CollaborationType::CoCreated, 0.5);
+        let custom = Attribution::new(Some("alice".to_string()), None, CollaborationType::Custom("review".to_string()), 0.5);
+        assert_eq!(engine.normalized_weight(&co_created), 1.0);
+        assert_eq!(engine.normalized_weight(&custom), 0.5);
+        
+        let sized = AttributionBuilder::new().human("alice".to_string()).weight(40.0).confidence(0.5).build().unwrap();
+        assert_eq!(engine.normalized_weight(&sized), 20.0);
+        
+        engine.history.push(co_created);
+        engine.history.push(custom);
+        engine.history.push(sized);
+        assert_eq!(engine.get_statistics().weighted_total, 21.5);
+        
+        config.type_weights.insert(CollaborationType::Automated, -1.0);
+        assert!(config.validate_weights().is_err());
+        
+        config.type_weights.remove(&CollaborationType::Automated);
+        assert!(matches!(config.validate_weights(), Err(AttributionError::ConfigError(_))));
+    }
+    
+    #[test]
+    fn test_ledger_export_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("attributions.ndjson");
+        let mut engine = BasicAttributionEngine::default();
+        engine.import("a".to_string(), Attribution::new_human("alice".to_string()));
+        engine.import("b".to_string(), Attribution::new_ai("claude".to_string()));
+        
+        let export = engine.export_ledger(&path).unwrap();
+        assert_eq!((export.entries, export.appended), (2, 2));
+        export.verify_integrity().unwrap();
+        
+        // Exporting again only appends what is new
+        engine.import("c".to_string(), Attribution::new_human("bob".to_string()));
+        let export = engine.export_ledger(&path).unwrap();
+        assert_eq!((export.entries, export.appended), (3, 1));
+        export.verify_integrity().unwrap();
+        
+        engine.freeze();
+        assert!(!engine.import("d".to_string(), Attribution::new_human("eve".to_string())));
+        assert!(matches!(engine.analyze(AttributionContext::new("edit".to_string())), Err(AttributionError::Frozen)));
+        
+        let original = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, original.replacen("bob", "eve", 1)).unwrap();
+        match export.verify_integrity() {
+            Err(IntegrityError::TamperedEntry { line_number, expected_hash, actual_hash }) => {
+                assert_eq!(line_number, 3);
+                assert_ne!(expected_hash, actual_hash);
+            }
+            other => panic!("expected a tampered entry, got {:?}", other),
+        }
+        
+        // Dropping a line breaks the chain at the line after it
+        let lines: Vec<&str> = original.lines().collect();
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(matches!(export.verify_integrity(), Err(IntegrityError::BrokenChain { line_number: 2, .. })));
+    }
 }
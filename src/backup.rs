@@ -0,0 +1,717 @@
+//! Backup and restore of a node's state
+//!
+//! A [`NodeBackup`] holds one [`BackupComponent`] per subsystem: identity,
+//! storage contents, trust relationships, group memberships, financial
+//! records and attribution history. [`NodeBackup::create_backup`] writes a
+//! single archive: a plaintext [`BackupManifest`] with each component's
+//! format version and checksum, and the component snapshots encrypted with
+//! AES-256-GCM under a key derived from a passphrase (PBKDF2-HMAC-SHA256).
+//! The manifest is bound to the ciphertext as associated data, so it can be
+//! read without the passphrase with [`read_manifest`] but not altered.
+//!
+//! Backups are taken from a live node. Each component is snapshotted under
+//! its own lock, held only while its state is copied, so traffic is never
+//! blocked for the whole backup. Each snapshot is consistent on its own, but
+//! snapshots of different components are taken one after another; a change
+//! landing between them (a trust relationship established after storage was
+//! copied, say) may be in one and not the other.
+//!
+//! [`NodeBackup::restore_backup`] decrypts and validates the whole archive
+//! before restoring anything. It refuses components written by a newer
+//! format than this code supports; upgrade the node and restore again
+//! rather than restoring into older code.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use anyhow::Result;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::attribution::{Attribution, BasicAttributionEngine};
+use crate::financial::{CostRecord, FinancialTracker, SpendingLimits};
+use crate::group_communication::{BasicGroupCommunication, GroupCommunication, GroupMembership};
+use crate::mesh::security::{SecuritySystem, TrustRelationship};
+use crate::mesh::verification::IdentityFile;
+use crate::storage::{content_checksum, MemoryStorage, Storage, StoredResource};
+
+/// Current archive layout
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// PBKDF2 iterations used unless [`BackupOptions`] says otherwise
+pub const DEFAULT_KDF_ITERATIONS: u32 = 200_000;
+
+/// Most PBKDF2 iterations an archive may ask for; beyond this a crafted
+/// archive could stall the restoring node
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LEN: usize = 16;
+
+/// Errors from creating or restoring backups
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Corrupted backup archive: {0}")]
+    Corrupt(String),
+
+    #[error("Unsupported backup format {0}")]
+    UnsupportedFormat(u32),
+
+    #[error("Wrong passphrase or tampered backup archive")]
+    WrongPassphrase,
+
+    #[error("Checksum mismatch for component {0}")]
+    ChecksumMismatch(String),
+
+    #[error("Component {component} was backed up at version {backup_version}, newer than supported version {supported_version}; upgrade before restoring")]
+    NewerComponent { component: String, backup_version: u32, supported_version: u32 },
+
+    #[error("No component registered for {0}; skip it to restore the rest")]
+    UnknownComponent(String),
+
+    #[error("Component {component} failed: {reason}")]
+    Component { component: String, reason: String },
+}
+
+/// One subsystem's persisted state
+#[async_trait::async_trait]
+pub trait BackupComponent: Send + Sync {
+    /// Name of the component in the manifest
+    fn name(&self) -> &str;
+
+    /// Format version of the snapshots this code writes and reads
+    fn version(&self) -> u32;
+
+    /// Serialize the current state
+    async fn snapshot(&self) -> Result<Vec<u8>>;
+
+    /// Replace the current state with a snapshot
+    async fn restore(&self, snapshot: &[u8]) -> Result<()>;
+}
+
+/// Manifest entry of one component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentEntry {
+    pub name: String,
+    pub version: u32,
+    /// Hex SHA-256 of the snapshot
+    pub checksum: String,
+    /// Snapshot size in bytes
+    pub size: u64,
+}
+
+/// What a backup holds, readable without the passphrase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub components: Vec<ComponentEntry>,
+}
+
+impl BackupManifest {
+    /// Entry of the component called `name`
+    pub fn component(&self, name: &str) -> Option<&ComponentEntry> {
+        self.components.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// How to create a backup
+#[derive(Debug, Clone)]
+pub struct BackupOptions {
+    pub passphrase: String,
+    /// Components to back up; all of them if empty
+    pub components: HashSet<String>,
+    pub kdf_iterations: u32,
+}
+
+impl BackupOptions {
+    /// Back up every component under `passphrase`
+    pub fn new(passphrase: &str) -> Self {
+        Self {
+            passphrase: passphrase.to_string(),
+            components: HashSet::new(),
+            kdf_iterations: DEFAULT_KDF_ITERATIONS,
+        }
+    }
+
+    /// Back up only the named components
+    pub fn with_components(mut self, components: &[&str]) -> Self {
+        self.components = components.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Derive the key with `iterations` rounds of PBKDF2, at most [`MAX_KDF_ITERATIONS`]
+    pub fn with_kdf_iterations(mut self, iterations: u32) -> Self {
+        self.kdf_iterations = iterations;
+        self
+    }
+}
+
+/// How to restore a backup
+#[derive(Debug, Clone, Default)]
+pub struct RestoreOptions {
+    /// Components left as they are
+    pub skip: HashSet<String>,
+}
+
+impl RestoreOptions {
+    /// Leave the component called `name` as it is
+    pub fn skip(mut self, name: &str) -> Self {
+        self.skip.insert(name.to_string());
+        self
+    }
+}
+
+/// Outcome of a restore
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub restored: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Key derivation parameters stored in the archive
+#[derive(Debug, Serialize, Deserialize)]
+struct KdfParams {
+    /// Base64 salt
+    salt: String,
+    iterations: u32,
+}
+
+/// The archive as written to disk (JSON)
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupArchive {
+    manifest: BackupManifest,
+    kdf: KdfParams,
+    /// Base64 AES-GCM nonce
+    nonce: String,
+    /// Base64 encrypted [`BackupPayload`], manifest as associated data
+    ciphertext: String,
+}
+
+/// Component snapshots by name, base64
+type BackupPayload = BTreeMap<String, String>;
+
+/// The components of a node's state
+#[derive(Default)]
+pub struct NodeBackup {
+    components: Vec<Arc<dyn BackupComponent>>,
+}
+
+impl NodeBackup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include `component` in backups and restores
+    pub fn with_component(mut self, component: impl BackupComponent + 'static) -> Self {
+        self.components.push(Arc::new(component));
+        self
+    }
+
+    /// Snapshot the components into an encrypted archive at `path`
+    ///
+    /// The archive is written next to `path` and renamed into place, so an
+    /// interrupted backup never leaves a partial archive behind.
+    pub async fn create_backup(&self, path: &Path, options: &BackupOptions) -> Result<BackupManifest, BackupError> {
+        let mut manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: Utc::now(),
+            components: Vec::new(),
+        };
+        let mut payload = BackupPayload::new();
+        for component in &self.components {
+            let name = component.name();
+            if !options.components.is_empty() && !options.components.contains(name) {
+                continue;
+            }
+            let snapshot = component.snapshot().await.map_err(|e| component_error(name, e))?;
+            manifest.components.push(ComponentEntry {
+                name: name.to_string(),
+                version: component.version(),
+                checksum: content_checksum(&snapshot),
+                size: snapshot.len() as u64,
+            });
+            payload.insert(name.to_string(), encode(&snapshot));
+        }
+
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt).and_then(|_| rng.fill(&mut nonce))
+            .map_err(|_| BackupError::Corrupt("failed to generate randomness".to_string()))?;
+        let iterations = options.kdf_iterations.clamp(1, MAX_KDF_ITERATIONS);
+        let key = derive_key(&options.passphrase, &salt, iterations);
+
+        let mut ciphertext = serde_json::to_vec(&payload).map_err(|e| BackupError::Corrupt(e.to_string()))?;
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(manifest_aad(&manifest)?),
+            &mut ciphertext,
+        ).map_err(|_| BackupError::Corrupt("encryption failed".to_string()))?;
+
+        let archive = BackupArchive {
+            manifest: manifest.clone(),
+            kdf: KdfParams { salt: encode(&salt), iterations },
+            nonce: encode(&nonce),
+            ciphertext: encode(&ciphertext),
+        };
+        let partial = partial_path(path);
+        std::fs::write(&partial, serde_json::to_vec(&archive).map_err(|e| BackupError::Corrupt(e.to_string()))?)?;
+        std::fs::rename(&partial, path)?;
+
+        info!("Backed up {} components to {}", manifest.components.len(), path.display());
+        Ok(manifest)
+    }
+
+    /// Restore the components from the archive at `path`
+    ///
+    /// Everything is decrypted and checked first; a refused restore leaves
+    /// every component untouched.
+    pub async fn restore_backup(
+        &self,
+        path: &Path,
+        passphrase: &str,
+        options: &RestoreOptions,
+    ) -> Result<RestoreReport, BackupError> {
+        let archive = read_archive(path)?;
+        let salt = decode(&archive.kdf.salt)?;
+        let nonce: [u8; NONCE_LEN] = decode(&archive.nonce)?
+            .try_into()
+            .map_err(|_| BackupError::Corrupt("invalid nonce".to_string()))?;
+        let mut ciphertext = decode(&archive.ciphertext)?;
+
+        if !(1..=MAX_KDF_ITERATIONS).contains(&archive.kdf.iterations) {
+            return Err(BackupError::Corrupt(format!(
+                "KDF iteration count {} outside 1..={}",
+                archive.kdf.iterations, MAX_KDF_ITERATIONS
+            )));
+        }
+        let key = derive_key(passphrase, &salt, archive.kdf.iterations);
+        let plaintext = key.open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(manifest_aad(&archive.manifest)?),
+            &mut ciphertext,
+        ).map_err(|_| BackupError::WrongPassphrase)?;
+        let payload: BackupPayload = serde_json::from_slice(plaintext)
+            .map_err(|e| BackupError::Corrupt(e.to_string()))?;
+
+        let mut report = RestoreReport::default();
+        let mut pending = Vec::new();
+        for entry in &archive.manifest.components {
+            if options.skip.contains(&entry.name) {
+                report.skipped.push(entry.name.clone());
+                continue;
+            }
+            let component = self.components.iter()
+                .find(|component| component.name() == entry.name)
+                .ok_or_else(|| BackupError::UnknownComponent(entry.name.clone()))?;
+            if entry.version > component.version() {
+                return Err(BackupError::NewerComponent {
+                    component: entry.name.clone(),
+                    backup_version: entry.version,
+                    supported_version: component.version(),
+                });
+            }
+            let snapshot = payload.get(&entry.name)
+                .ok_or_else(|| BackupError::Corrupt(format!("missing snapshot of {}", entry.name)))
+                .and_then(|data| decode(data))?;
+            if content_checksum(&snapshot) != entry.checksum {
+                return Err(BackupError::ChecksumMismatch(entry.name.clone()));
+            }
+            pending.push((component, snapshot));
+        }
+
+        for (component, snapshot) in pending {
+            component.restore(&snapshot).await.map_err(|e| component_error(component.name(), e))?;
+            report.restored.push(component.name().to_string());
+        }
+        info!("Restored {} components from {}", report.restored.len(), path.display());
+        Ok(report)
+    }
+}
+
+/// Manifest of the archive at `path`, without decrypting it
+pub fn read_manifest(path: &Path) -> Result<BackupManifest, BackupError> {
+    Ok(read_archive(path)?.manifest)
+}
+
+fn read_archive(path: &Path) -> Result<BackupArchive, BackupError> {
+    let archive: BackupArchive = serde_json::from_slice(&std::fs::read(path)?)
+        .map_err(|e| BackupError::Corrupt(e.to_string()))?;
+    if archive.manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(BackupError::UnsupportedFormat(archive.manifest.format_version));
+    }
+    Ok(archive)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> LessSafeKey {
+    let mut key = [0u8; 32];
+    let iterations = NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN);
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).expect("AES-256 keys are 32 bytes"))
+}
+
+fn manifest_aad(manifest: &BackupManifest) -> Result<Vec<u8>, BackupError> {
+    serde_json::to_vec(manifest).map_err(|e| BackupError::Corrupt(e.to_string()))
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn decode(data: &str) -> Result<Vec<u8>, BackupError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| BackupError::Corrupt(e.to_string()))
+}
+
+fn component_error(component: &str, error: anyhow::Error) -> BackupError {
+    BackupError::Component { component: component.to_string(), reason: error.to_string() }
+}
+
+/// The node's identity file
+pub struct IdentityComponent(pub PathBuf);
+
+#[async_trait::async_trait]
+impl BackupComponent for IdentityComponent {
+    fn name(&self) -> &str {
+        "identity"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        let identity: IdentityFile = serde_json::from_slice(&tokio::fs::read(&self.0).await?)?;
+        Ok(serde_json::to_vec(&identity)?)
+    }
+
+    async fn restore(&self, snapshot: &[u8]) -> Result<()> {
+        let identity: IdentityFile = serde_json::from_slice(snapshot)?;
+        if let Some(dir) = self.0.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        identity.save(&self.0)
+    }
+}
+
+/// Every resource in a store, restored under the same IDs
+pub struct StorageComponent(pub Arc<RwLock<MemoryStorage>>);
+
+#[async_trait::async_trait]
+impl BackupComponent for StorageComponent {
+    fn name(&self) -> &str {
+        "storage"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        let storage = self.0.read().await;
+        let mut resources = Vec::new();
+        for metadata in storage.list_resources(None) {
+            resources.push(storage.get_resource(&metadata.resource_id).await?);
+        }
+        Ok(serde_json::to_vec(&resources)?)
+    }
+
+    async fn restore(&self, snapshot: &[u8]) -> Result<()> {
+        let resources: Vec<StoredResource> = serde_json::from_slice(snapshot)?;
+        let mut storage = self.0.write().await;
+        for resource in resources {
+            storage.restore_resource(resource).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Trust relationships of the security system
+pub struct TrustComponent(pub Arc<SecuritySystem>);
+
+#[async_trait::async_trait]
+impl BackupComponent for TrustComponent {
+    fn name(&self) -> &str {
+        "trust"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&self.0.trust_relationships().await)?)
+    }
+
+    async fn restore(&self, snapshot: &[u8]) -> Result<()> {
+        let relationships: Vec<TrustRelationship> = serde_json::from_slice(snapshot)?;
+        self.0.restore_trust_relationships(relationships).await;
+        Ok(())
+    }
+}
+
+/// Group memberships of the node
+pub struct GroupsComponent(pub Arc<RwLock<BasicGroupCommunication>>);
+
+#[async_trait::async_trait]
+impl BackupComponent for GroupsComponent {
+    fn name(&self) -> &str {
+        "groups"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&self.0.read().await.get_memberships().await?)?)
+    }
+
+    async fn restore(&self, snapshot: &[u8]) -> Result<()> {
+        let memberships: Vec<GroupMembership> = serde_json::from_slice(snapshot)?;
+        let mut groups = self.0.write().await;
+        for membership in memberships {
+            groups.add_membership(membership);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FinancialSnapshot {
+    limits: SpendingLimits,
+    /// Oldest first
+    records: Vec<CostRecord>,
+}
+
+/// Spending limits and cost records
+pub struct FinancialComponent(pub Arc<RwLock<FinancialTracker>>);
+
+#[async_trait::async_trait]
+impl BackupComponent for FinancialComponent {
+    fn name(&self) -> &str {
+        "financial"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        let tracker = self.0.read().await;
+        let mut records: Vec<CostRecord> = tracker.get_recent_costs(usize::MAX).into_iter().cloned().collect();
+        records.reverse();
+        Ok(serde_json::to_vec(&FinancialSnapshot { limits: tracker.get_limits().clone(), records })?)
+    }
+
+    async fn restore(&self, snapshot: &[u8]) -> Result<()> {
+        let snapshot: FinancialSnapshot = serde_json::from_slice(snapshot)?;
+        let mut tracker = self.0.write().await;
+        tracker.update_limits(snapshot.limits);
+        tracker.clear_records();
+        for record in snapshot.records {
+            tracker.record_cost(record)?;
+        }
+        Ok(())
+    }
+}
+
+/// Attribution history
+///
+/// Restored attributions are imported under their IDs, so restoring twice
+/// does not duplicate them.
+pub struct AttributionComponent(pub Arc<RwLock<BasicAttributionEngine>>);
+
+#[async_trait::async_trait]
+impl BackupComponent for AttributionComponent {
+    fn name(&self) -> &str {
+        "attribution"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    async fn snapshot(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self.0.read().await.get_history())?)
+    }
+
+    async fn restore(&self, snapshot: &[u8]) -> Result<()> {
+        let history: Vec<Attribution> = serde_json::from_slice(snapshot)?;
+        let mut engine = self.0.write().await;
+        for attribution in history {
+            engine.import(attribution.id.as_string(), attribution);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group_communication::{GroupId, GroupPermissions, GroupRole};
+    use crate::mesh::security::TrustLevel;
+    use crate::mesh::verification::inspect_identity_file;
+    use crate::financial::OperationType;
+    use crate::storage::AccessControl;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    struct Node {
+        identity: PathBuf,
+        storage: Arc<RwLock<MemoryStorage>>,
+        security: Arc<SecuritySystem>,
+        groups: Arc<RwLock<BasicGroupCommunication>>,
+        financial: Arc<RwLock<FinancialTracker>>,
+        attribution: Arc<RwLock<BasicAttributionEngine>>,
+    }
+
+    impl Node {
+        fn new(dir: &Path, node_id: Uuid) -> Self {
+            Self {
+                identity: dir.join("identity.json"),
+                storage: Arc::new(RwLock::new(MemoryStorage::new())),
+                security: Arc::new(SecuritySystem::new(node_id, None)),
+                groups: Arc::new(RwLock::new(BasicGroupCommunication::new(node_id.to_string()))),
+                financial: Arc::new(RwLock::new(FinancialTracker::with_defaults())),
+                attribution: Arc::new(RwLock::new(BasicAttributionEngine::default())),
+            }
+        }
+
+        fn backup(&self) -> NodeBackup {
+            NodeBackup::new()
+                .with_component(IdentityComponent(self.identity.clone()))
+                .with_component(StorageComponent(Arc::clone(&self.storage)))
+                .with_component(TrustComponent(Arc::clone(&self.security)))
+                .with_component(GroupsComponent(Arc::clone(&self.groups)))
+                .with_component(FinancialComponent(Arc::clone(&self.financial)))
+                .with_component(AttributionComponent(Arc::clone(&self.attribution)))
+        }
+    }
+
+    fn options() -> BackupOptions {
+        BackupOptions::new("correct horse").with_kdf_iterations(1_000)
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore_onto_a_fresh_node() {
+        let original_dir = tempfile::tempdir().unwrap();
+        let node_id = Uuid::new_v4();
+        let node = Node::new(original_dir.path(), node_id);
+        IdentityFile::generate(node_id).unwrap().save(&node.identity).unwrap();
+        let resource_id = node.storage.write().await.store_resource(
+            "notes".to_string(), b"hello".to_vec(), "text/plain".to_string(), AccessControl::default(), vec![],
+        ).await.unwrap();
+        let partner = Uuid::new_v4();
+        node.security.establish_trust(partner, TrustLevel::Verified, Vec::new()).await.unwrap();
+        node.groups.write().await.add_membership(GroupMembership {
+            group_id: GroupId::new("ops"),
+            role: GroupRole::Administrator,
+            permissions: GroupPermissions::default(),
+            joined_at: Utc::now(),
+            is_active: true,
+            metadata: HashMap::new(),
+        });
+        node.financial.write().await.record_cost(CostRecord {
+            operation_id: "op-1".to_string(),
+            timestamp: Utc::now(),
+            cost: 42,
+            currency: "tokens".to_string(),
+            operation_type: OperationType::Storage,
+            context: None,
+            metadata: HashMap::new(),
+        }).unwrap();
+        node.attribution.write().await.import("a1".to_string(), Attribution::new_human("alice".to_string()));
+
+        let archive = original_dir.path().join("node.backup");
+        let manifest = node.backup().create_backup(&archive, &options()).await.unwrap();
+        assert_eq!(manifest.components.len(), 6);
+        assert_eq!(read_manifest(&archive).unwrap(), manifest);
+
+        let fresh_dir = tempfile::tempdir().unwrap();
+        let fresh = Node::new(fresh_dir.path(), node_id);
+        let report = fresh.backup()
+            .restore_backup(&archive, "correct horse", &RestoreOptions::default().skip("financial"))
+            .await
+            .unwrap();
+        assert_eq!(report.skipped, vec!["financial".to_string()]);
+        assert_eq!(report.restored.len(), 5);
+
+        assert_eq!(inspect_identity_file(&fresh.identity).unwrap(), inspect_identity_file(&node.identity).unwrap());
+        assert_eq!(fresh.storage.read().await.get_resource_content(&resource_id).await.unwrap(), b"hello");
+        assert_eq!(fresh.security.get_trust_level(partner).await, TrustLevel::Verified);
+        assert_eq!(fresh.groups.read().await.get_memberships().await.unwrap().len(), 1);
+        assert_eq!(fresh.attribution.read().await.get_history().len(), 1);
+        assert_eq!(fresh.financial.read().await.record_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_passphrase_corruption_and_newer_versions_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = Node::new(dir.path(), Uuid::new_v4());
+        node.security.establish_trust(Uuid::new_v4(), TrustLevel::Basic, Vec::new()).await.unwrap();
+        let backup = NodeBackup::new().with_component(TrustComponent(Arc::clone(&node.security)));
+        let archive = dir.path().join("node.backup");
+        backup.create_backup(&archive, &options()).await.unwrap();
+
+        let restore = |path: PathBuf, passphrase: &'static str| {
+            let fresh = NodeBackup::new()
+                .with_component(TrustComponent(Arc::new(SecuritySystem::new(Uuid::new_v4(), None))));
+            async move { fresh.restore_backup(&path, passphrase, &RestoreOptions::default()).await }
+        };
+        assert!(matches!(restore(archive.clone(), "wrong").await, Err(BackupError::WrongPassphrase)));
+
+        let mut archived: serde_json::Value = serde_json::from_slice(&std::fs::read(&archive).unwrap()).unwrap();
+        archived["manifest"]["components"][0]["version"] = 2.into();
+        let tampered = dir.path().join("tampered.backup");
+        std::fs::write(&tampered, serde_json::to_vec(&archived).unwrap()).unwrap();
+        assert!(matches!(restore(tampered, "correct horse").await, Err(BackupError::WrongPassphrase)));
+
+        let truncated = dir.path().join("truncated.backup");
+        let bytes = std::fs::read(&archive).unwrap();
+        std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(restore(truncated, "correct horse").await, Err(BackupError::Corrupt(_))));
+
+        let mut archived: serde_json::Value = serde_json::from_slice(&std::fs::read(&archive).unwrap()).unwrap();
+        archived["kdf"]["iterations"] = u32::MAX.into();
+        let costly = dir.path().join("costly.backup");
+        std::fs::write(&costly, serde_json::to_vec(&archived).unwrap()).unwrap();
+        assert!(matches!(restore(costly, "correct horse").await, Err(BackupError::Corrupt(_))));
+
+        struct FutureTrust(TrustComponent);
+        #[async_trait::async_trait]
+        impl BackupComponent for FutureTrust {
+            fn name(&self) -> &str { self.0.name() }
+            fn version(&self) -> u32 { 2 }
+            async fn snapshot(&self) -> Result<Vec<u8>> { self.0.snapshot().await }
+            async fn restore(&self, snapshot: &[u8]) -> Result<()> { self.0.restore(snapshot).await }
+        }
+        let newer = dir.path().join("newer.backup");
+        NodeBackup::new()
+            .with_component(FutureTrust(TrustComponent(Arc::clone(&node.security))))
+            .create_backup(&newer, &options())
+            .await
+            .unwrap();
+        assert!(matches!(
+            restore(newer, "correct horse").await,
+            Err(BackupError::NewerComponent { backup_version: 2, supported_version: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,595 @@
+//! Shared pool for CPU-heavy work
+//!
+//! Conflict analysis, content classification, compression and hashing can
+//! take long enough to starve message handling if they run on the async
+//! runtime. A [`ComputePool`] runs them on blocking threads instead, with a
+//! cap on total concurrency, a cap per [`ComputeCategory`], and priorities
+//! deciding which category gets the next free slot. Callers await the result
+//! and can give up through a `CancellationToken`.
+//!
+//! Storage checksums, merge previews in `GitManager`, classified payload
+//! summaries in `NodeCommunication` and event-log rotation all run here once
+//! they are given a pool.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::resource_profile::ResourceProfile;
+
+/// Kinds of CPU-heavy work, each with its own concurrency limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ComputeCategory {
+    /// Merge previews and semantic conflict analysis
+    ConflictAnalysis,
+    /// Payload classification
+    Classification,
+    /// Payload compression
+    Compression,
+    /// Content and chunk hashing
+    Hashing,
+}
+
+impl ComputeCategory {
+    /// All categories
+    pub const ALL: [ComputeCategory; 4] = [
+        ComputeCategory::ConflictAnalysis,
+        ComputeCategory::Classification,
+        ComputeCategory::Compression,
+        ComputeCategory::Hashing,
+    ];
+}
+
+/// Limits for one category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryLimits {
+    /// Tasks of this category running at once
+    pub max_concurrent: usize,
+    /// Higher priorities get free slots first
+    pub priority: u8,
+}
+
+/// Sizing of the compute pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComputePoolConfig {
+    /// Tasks running at once across all categories
+    pub pool_size: usize,
+    /// Per-category limits; categories not listed get one slot at priority 0
+    pub categories: HashMap<ComputeCategory, CategoryLimits>,
+}
+
+impl Default for ComputePoolConfig {
+    fn default() -> Self {
+        let pool_size = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+        let half = (pool_size / 2).max(1);
+        let categories = HashMap::from([
+            (ComputeCategory::Classification, CategoryLimits { max_concurrent: half, priority: 3 }),
+            (ComputeCategory::Hashing, CategoryLimits { max_concurrent: half, priority: 2 }),
+            (ComputeCategory::Compression, CategoryLimits { max_concurrent: half, priority: 2 }),
+            (ComputeCategory::ConflictAnalysis, CategoryLimits { max_concurrent: half, priority: 1 }),
+        ]);
+        Self { pool_size, categories }
+    }
+}
+
+impl ComputePoolConfig {
+    /// Default configuration sized for a resource profile
+    pub fn for_profile(profile: ResourceProfile) -> Self {
+        match profile {
+            ResourceProfile::Standard => Self::default(),
+            ResourceProfile::Constrained => {
+                let mut categories = Self::default().categories;
+                for limits in categories.values_mut() {
+                    limits.max_concurrent = 1;
+                }
+                Self { pool_size: 1, categories }
+            }
+        }
+    }
+
+    fn limits(&self, category: ComputeCategory) -> CategoryLimits {
+        self.categories
+            .get(&category)
+            .copied()
+            .unwrap_or(CategoryLimits { max_concurrent: 1, priority: 0 })
+    }
+}
+
+/// Errors from running work on the pool
+#[derive(Debug, thiserror::Error)]
+pub enum ComputeError {
+    #[error("Compute task cancelled")]
+    Cancelled,
+
+    #[error("Compute task panicked: {0}")]
+    Panicked(String),
+}
+
+/// Queue and execution figures for one category
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryMetrics {
+    /// Tasks waiting for a slot
+    pub queued: usize,
+    /// Tasks running now
+    pub running: usize,
+    /// Most tasks of this category seen running at once
+    pub peak_running: usize,
+    /// Tasks that ran to completion
+    pub completed: u64,
+    /// Tasks whose caller cancelled them
+    pub cancelled: u64,
+    /// Mean execution time of completed tasks in milliseconds
+    pub avg_execution_ms: f64,
+    /// Longest execution time in milliseconds
+    pub max_execution_ms: f64,
+}
+
+#[derive(Debug, Default)]
+struct CategoryState {
+    waiting: VecDeque<u64>,
+    running: usize,
+    peak_running: usize,
+    completed: u64,
+    cancelled: u64,
+    total_execution: Duration,
+    max_execution: Duration,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    running: usize,
+    next_ticket: u64,
+    categories: HashMap<ComputeCategory, CategoryState>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    config: ComputePoolConfig,
+    state: Mutex<State>,
+    released: Notify,
+}
+
+impl Inner {
+    /// Whether the task holding `ticket` may take a slot now
+    fn can_start(&self, state: &State, category: ComputeCategory, ticket: u64) -> bool {
+        if state.running >= self.config.pool_size {
+            return false;
+        }
+        let limits = self.config.limits(category);
+        let own = match state.categories.get(&category) {
+            Some(own) => own,
+            None => return false,
+        };
+        if own.running >= limits.max_concurrent || own.waiting.front() != Some(&ticket) {
+            return false;
+        }
+        // Yield to higher-priority categories that could use the slot
+        !state.categories.iter().any(|(other, other_state)| {
+            let other_limits = self.config.limits(*other);
+            other_limits.priority > limits.priority
+                && !other_state.waiting.is_empty()
+                && other_state.running < other_limits.max_concurrent
+        })
+    }
+
+    fn remove_waiter(&self, category: ComputeCategory, ticket: u64, cancelled: bool) {
+        {
+            let mut state = self.state.lock().unwrap();
+            let entry = state.categories.entry(category).or_default();
+            entry.waiting.retain(|t| *t != ticket);
+            if cancelled {
+                entry.cancelled += 1;
+            }
+        }
+        self.released.notify_waiters();
+    }
+}
+
+/// Removes a waiting task from its queue if it stops waiting without a slot
+struct WaitGuard<'a> {
+    inner: &'a Inner,
+    category: ComputeCategory,
+    ticket: u64,
+    waiting: bool,
+}
+
+impl Drop for WaitGuard<'_> {
+    fn drop(&mut self) {
+        if self.waiting {
+            self.inner.remove_waiter(self.category, self.ticket, true);
+        }
+    }
+}
+
+/// A held slot, released on drop
+struct Slot {
+    inner: Arc<Inner>,
+    category: ComputeCategory,
+}
+
+impl Slot {
+    fn record_execution(&self, elapsed: Duration) {
+        let mut state = self.inner.state.lock().unwrap();
+        let entry = state.categories.entry(self.category).or_default();
+        entry.completed += 1;
+        entry.total_execution += elapsed;
+        entry.max_execution = entry.max_execution.max(elapsed);
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            state.running -= 1;
+            if let Some(entry) = state.categories.get_mut(&self.category) {
+                entry.running -= 1;
+            }
+        }
+        self.inner.released.notify_waiters();
+    }
+}
+
+/// Bounded executor for CPU-heavy work; clones share the same pool
+#[derive(Debug, Clone)]
+pub struct ComputePool {
+    inner: Arc<Inner>,
+}
+
+impl ComputePool {
+    /// Create a pool with the given sizing
+    pub fn new(config: ComputePoolConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                config,
+                state: Mutex::new(State::default()),
+                released: Notify::new(),
+            }),
+        }
+    }
+
+    /// Pool sizing
+    pub fn config(&self) -> &ComputePoolConfig {
+        &self.inner.config
+    }
+
+    /// Run `work` on a blocking thread once a slot for `category` is free
+    ///
+    /// Cancelling while queued gives up the place in the queue. Cancelling
+    /// while running returns at once, but the work itself runs to completion
+    /// and holds its slot until it does.
+    pub async fn run<F, R>(&self, category: ComputeCategory, cancel: &CancellationToken, work: F) -> Result<R, ComputeError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let slot = self.acquire(category, cancel).await?;
+        let handle = tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let result = work();
+            slot.record_execution(started.elapsed());
+            result
+        });
+
+        tokio::select! {
+            result = handle => result.map_err(|e| ComputeError::Panicked(e.to_string())),
+            _ = cancel.cancelled() => {
+                let mut state = self.inner.state.lock().unwrap();
+                state.categories.entry(category).or_default().cancelled += 1;
+                Err(ComputeError::Cancelled)
+            }
+        }
+    }
+
+    /// Wait for a slot in `category`
+    async fn acquire(&self, category: ComputeCategory, cancel: &CancellationToken) -> Result<Slot, ComputeError> {
+        if cancel.is_cancelled() {
+            return Err(ComputeError::Cancelled);
+        }
+        let ticket = {
+            let mut state = self.inner.state.lock().unwrap();
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.categories.entry(category).or_default().waiting.push_back(ticket);
+            ticket
+        };
+        let mut guard = WaitGuard { inner: &self.inner, category, ticket, waiting: true };
+
+        loop {
+            let released = self.inner.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            {
+                let mut state = self.inner.state.lock().unwrap();
+                if self.inner.can_start(&state, category, ticket) {
+                    state.running += 1;
+                    let entry = state.categories.entry(category).or_default();
+                    entry.waiting.pop_front();
+                    entry.running += 1;
+                    entry.peak_running = entry.peak_running.max(entry.running);
+                    guard.waiting = false;
+                    drop(state);
+                    // The next waiter in this category may be able to start too
+                    self.inner.released.notify_waiters();
+                    return Ok(Slot { inner: self.inner.clone(), category });
+                }
+            }
+
+            tokio::select! {
+                _ = &mut released => {}
+                _ = cancel.cancelled() => return Err(ComputeError::Cancelled),
+            }
+        }
+    }
+
+    /// Queue depth and execution figures per category
+    pub fn metrics(&self) -> HashMap<ComputeCategory, CategoryMetrics> {
+        let state = self.inner.state.lock().unwrap();
+        ComputeCategory::ALL
+            .iter()
+            .map(|category| {
+                let metrics = match state.categories.get(category) {
+                    Some(entry) => CategoryMetrics {
+                        queued: entry.waiting.len(),
+                        running: entry.running,
+                        peak_running: entry.peak_running,
+                        completed: entry.completed,
+                        cancelled: entry.cancelled,
+                        avg_execution_ms: if entry.completed > 0 {
+                            entry.total_execution.as_secs_f64() * 1000.0 / entry.completed as f64
+                        } else {
+                            0.0
+                        },
+                        max_execution_ms: entry.max_execution.as_secs_f64() * 1000.0,
+                    },
+                    None => CategoryMetrics::default(),
+                };
+                (*category, metrics)
+            })
+            .collect()
+    }
+}
+
+impl Default for ComputePool {
+    fn default() -> Self {
+        Self::new(ComputePoolConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::event_log::EventLogger;
+    use crate::mesh::events::{EventSystem, NodeLifecycleType};
+    use crate::networking::payload_summary::{
+        ContentClassifier, PayloadSummarizer, PayloadSummaryConfig,
+    };
+    use crate::ide::security::CoreClassification;
+    use crate::storage::{AccessControl, MemoryStorage, Storage};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn config(pool_size: usize, limits: &[(ComputeCategory, usize, u8)]) -> ComputePoolConfig {
+        ComputePoolConfig {
+            pool_size,
+            categories: limits
+                .iter()
+                .map(|(category, max_concurrent, priority)| {
+                    (*category, CategoryLimits { max_concurrent: *max_concurrent, priority: *priority })
+                })
+                .collect(),
+        }
+    }
+
+    /// Sleeps on the calling thread, tracking the peak number running at once
+    fn slow_task(running: Arc<AtomicUsize>, peak: Arc<AtomicUsize>) -> impl FnOnce() + Send + 'static {
+        move || {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(60));
+            running.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_saturated_pool_keeps_message_handling_responsive() {
+        let pool = ComputePool::new(config(3, &[
+            (ComputeCategory::ConflictAnalysis, 2, 1),
+            (ComputeCategory::Hashing, 1, 2),
+        ]));
+        let cancel = CancellationToken::new();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let hash_running = Arc::new(AtomicUsize::new(0));
+        let hash_peak = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = Vec::new();
+        for i in 0..8 {
+            let pool = pool.clone();
+            let cancel = cancel.clone();
+            let (category, work) = if i % 2 == 0 {
+                (ComputeCategory::ConflictAnalysis, slow_task(running.clone(), peak.clone()))
+            } else {
+                (ComputeCategory::Hashing, slow_task(hash_running.clone(), hash_peak.clone()))
+            };
+            tasks.push(tokio::spawn(async move { pool.run(category, &cancel, work).await }));
+        }
+
+        // Message handling on the same single-threaded runtime stays prompt
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let metrics = pool.metrics();
+        assert_eq!(metrics[&ComputeCategory::ConflictAnalysis].queued, 2);
+        assert_eq!(metrics[&ComputeCategory::Hashing].queued, 3);
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Instant>(1);
+        let mut worst = Duration::ZERO;
+        for _ in 0..10 {
+            tx.send(Instant::now()).await.unwrap();
+            worst = worst.max(rx.recv().await.unwrap().elapsed());
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(worst < Duration::from_millis(20), "message handling stalled for {:?}", worst);
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(hash_peak.load(Ordering::SeqCst), 1);
+
+        let metrics = pool.metrics();
+        assert_eq!(metrics[&ComputeCategory::ConflictAnalysis].completed, 4);
+        assert_eq!(metrics[&ComputeCategory::Hashing].completed, 4);
+        assert_eq!(metrics[&ComputeCategory::Hashing].queued, 0);
+        assert!(metrics[&ComputeCategory::Hashing].avg_execution_ms >= 60.0);
+    }
+
+    #[tokio::test]
+    async fn test_priority_and_cancellation() {
+        let pool = ComputePool::new(config(1, &[
+            (ComputeCategory::ConflictAnalysis, 1, 1),
+            (ComputeCategory::Classification, 1, 3),
+        ]));
+        let cancel = CancellationToken::new();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // Occupy the only slot, then queue low priority before high priority work
+        let blocker = {
+            let pool = pool.clone();
+            let cancel = cancel.clone();
+            tokio::spawn(async move {
+                pool.run(ComputeCategory::Hashing, &cancel, || std::thread::sleep(Duration::from_millis(50))).await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let mut queued = Vec::new();
+        for (category, label) in [(ComputeCategory::ConflictAnalysis, "conflict"), (ComputeCategory::Classification, "classify")] {
+            let pool = pool.clone();
+            let cancel = cancel.clone();
+            let order = order.clone();
+            queued.push(tokio::spawn(async move {
+                pool.run(category, &cancel, move || order.lock().unwrap().push(label)).await
+            }));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let abandoned = CancellationToken::new();
+        let waiting = {
+            let pool = pool.clone();
+            let abandoned = abandoned.clone();
+            tokio::spawn(async move { pool.run(ComputeCategory::ConflictAnalysis, &abandoned, || ()).await })
+        };
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(pool.metrics()[&ComputeCategory::ConflictAnalysis].queued, 2);
+        abandoned.cancel();
+        assert!(matches!(waiting.await.unwrap(), Err(ComputeError::Cancelled)));
+        assert_eq!(pool.metrics()[&ComputeCategory::ConflictAnalysis].queued, 1);
+        assert_eq!(pool.metrics()[&ComputeCategory::ConflictAnalysis].cancelled, 1);
+
+        blocker.await.unwrap().unwrap();
+        for task in queued {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec!["classify", "conflict"]);
+    }
+
+    /// Opens every payload, slowly enough to hold its slot while others queue
+    struct SlowClassifier;
+
+    impl ContentClassifier for SlowClassifier {
+        fn classify(&self, _payload: &[u8], _content_type: &str) -> CoreClassification {
+            std::thread::sleep(Duration::from_millis(30));
+            CoreClassification::Public
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_heavy_call_sites_contend_within_limits() {
+        let pool = ComputePool::new(config(2, &[
+            (ComputeCategory::Classification, 1, 3),
+            (ComputeCategory::Hashing, 1, 2),
+            (ComputeCategory::Compression, 1, 1),
+        ]));
+        let cancel = CancellationToken::new();
+
+        // Slow fake hashing saturates its category before the real callers arrive
+        let mut tasks = Vec::new();
+        for _ in 0..2 {
+            let pool = pool.clone();
+            let cancel = cancel.clone();
+            tasks.push(tokio::spawn(async move {
+                pool.run(ComputeCategory::Hashing, &cancel, || std::thread::sleep(Duration::from_millis(40))).await
+            }));
+        }
+
+        let storage = {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                let mut storage = MemoryStorage::new().with_compute_pool(pool);
+                for i in 0..3u8 {
+                    let id = storage.store_resource(
+                        format!("chunk-{}", i),
+                        vec![i; 64 * 1024],
+                        "application/octet-stream".to_string(),
+                        AccessControl::default(),
+                        Vec::new(),
+                    ).await.unwrap();
+                    assert_eq!(storage.get_resource(&id).await.unwrap().content, vec![i; 64 * 1024]);
+                }
+            })
+        };
+
+        let summarizer = PayloadSummarizer::new(Arc::new(SlowClassifier)).with_compute_pool(pool.clone());
+        let summaries: Vec<_> = (0..3)
+            .map(|i| {
+                let summarizer = summarizer.clone();
+                tokio::spawn(async move {
+                    summarizer.summarize(format!("payload {}", i).as_bytes(), &PayloadSummaryConfig::default()).await
+                })
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let logger = EventLogger::open(&dir.path().join("events.ndjson")).unwrap().with_auto_rotation(1, pool.clone());
+        let mut events = EventSystem::new(uuid::Uuid::new_v4(), None);
+        events.add_provider(Box::new(logger.clone()));
+        let publishing = tokio::spawn(async move {
+            for _ in 0..3 {
+                let event = events.create_node_event(NodeLifecycleType::NodeJoined, uuid::Uuid::new_v4(), None, None);
+                events.publish_event(event).await.unwrap();
+            }
+        });
+
+        // Message handling on the same runtime stays prompt while the pool is contended
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Instant>(1);
+        let mut worst = Duration::ZERO;
+        for _ in 0..10 {
+            tx.send(Instant::now()).await.unwrap();
+            worst = worst.max(rx.recv().await.unwrap().elapsed());
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(worst < Duration::from_millis(20), "message handling stalled for {:?}", worst);
+
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        storage.await.unwrap();
+        for summary in summaries {
+            assert_eq!(summary.await.unwrap().classification, Some(CoreClassification::Public));
+        }
+        publishing.await.unwrap();
+
+        let metrics = pool.metrics();
+        for category in [ComputeCategory::Classification, ComputeCategory::Hashing, ComputeCategory::Compression] {
+            assert_eq!(metrics[&category].peak_running, 1, "{:?} exceeded its limit", category);
+            assert_eq!(metrics[&category].queued, 0);
+        }
+        // Two fake tasks, then a checksum on each store and each read
+        assert_eq!(metrics[&ComputeCategory::Hashing].completed, 8);
+        assert_eq!(metrics[&ComputeCategory::Classification].completed, 3);
+        assert_eq!(metrics[&ComputeCategory::Compression].completed, 3);
+        assert_eq!(logger.segments().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,586 @@
+//! Approval prompts over the Sacred Alliance channel
+//!
+//! When an operation needs user approval on a headless node, a ticket is
+//! posted to the project's channel and resolved by the first reply from a
+//! designated approver. Replies are structured `ApprovalMessage`s, never
+//! parsed from free text.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use uuid::Uuid;
+
+use super::{ApprovalResult, FinancialTracker, OperationType};
+use crate::sacred_alliance::{
+    AllianceMessage, ApprovalMessage, BasicSacredAllianceChannel, ChannelConfig, MessageContent, Participant,
+};
+use crate::WeaveMeshError;
+
+/// Where approval traffic is posted
+pub trait ApprovalChannel {
+    /// Post a message visible to the whole channel
+    fn post(&mut self, message: AllianceMessage) -> anyhow::Result<()>;
+
+    /// Post a message meant only for `recipient`
+    fn post_private(&mut self, recipient: &str, message: AllianceMessage) -> anyhow::Result<()>;
+}
+
+/// A project's alliance channel with a direct channel per private recipient
+///
+/// Private posts never reach the project channel's history; each goes to a
+/// two-participant channel between the sender and the recipient, opened on
+/// first use.
+pub struct AllianceApprovalChannel {
+    /// The project channel
+    channel: BasicSacredAllianceChannel,
+    /// Configuration of direct channels
+    direct_config: ChannelConfig,
+    /// Direct channels by recipient
+    direct: HashMap<String, BasicSacredAllianceChannel>,
+}
+
+impl AllianceApprovalChannel {
+    /// Wrap the project channel `channel`
+    pub fn new(channel: BasicSacredAllianceChannel) -> Self {
+        Self {
+            channel,
+            direct_config: ChannelConfig { max_participants: 2, ..ChannelConfig::default() },
+            direct: HashMap::new(),
+        }
+    }
+
+    /// The project channel
+    pub fn channel(&self) -> &BasicSacredAllianceChannel {
+        &self.channel
+    }
+
+    /// The direct channel with `recipient`, once something was posted to it
+    pub fn direct_channel(&self, recipient: &str) -> Option<&BasicSacredAllianceChannel> {
+        self.direct.get(recipient)
+    }
+
+    fn participant(&self, id: &str) -> anyhow::Result<Participant> {
+        self.channel.get_participants().iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("{} is not a participant of the project channel", id))
+    }
+}
+
+impl ApprovalChannel for AllianceApprovalChannel {
+    fn post(&mut self, message: AllianceMessage) -> anyhow::Result<()> {
+        self.channel.send_message(message)
+    }
+
+    fn post_private(&mut self, recipient: &str, message: AllianceMessage) -> anyhow::Result<()> {
+        if !self.direct.contains_key(recipient) {
+            let mut direct = BasicSacredAllianceChannel::new(
+                format!("direct:{}:{}", message.sender, recipient),
+                self.direct_config.clone(),
+            );
+            direct.add_participant(self.participant(&message.sender)?)?;
+            direct.add_participant(self.participant(recipient)?)?;
+            self.direct.insert(recipient.to_string(), direct);
+        }
+        match self.direct.get_mut(recipient) {
+            Some(direct) => direct.send_message(message),
+            None => Err(anyhow::anyhow!("No direct channel with {}", recipient)),
+        }
+    }
+}
+
+/// What to do about an operation after checking its cost
+#[derive(Debug, Clone)]
+pub enum ApprovalGate {
+    /// Within limits and under the auto-approval threshold
+    Approved,
+    /// Over a spending limit
+    Denied { reason: String },
+    /// Waiting on the posted ticket
+    Pending(ApprovalTicket),
+}
+
+/// An operation waiting for a decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalTicket {
+    /// Ticket identifier
+    pub ticket_id: Uuid,
+    /// Operation awaiting approval
+    pub operation: String,
+    /// Estimated cost in base units
+    pub estimated_cost: u64,
+    /// Currency of the estimate
+    pub currency: String,
+    /// Who asked for the operation
+    pub requester: String,
+    /// When the ticket was opened
+    pub requested_at: DateTime<Utc>,
+    /// When the ticket expires without a decision
+    pub expires_at: DateTime<Utc>,
+}
+
+/// How a ticket was resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TicketOutcome {
+    /// Approved by the named participant
+    Approved { decided_by: String },
+    /// Denied by the named participant
+    Denied { decided_by: String },
+    /// Expired without a decision
+    Expired,
+}
+
+/// A resolved ticket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResolution {
+    /// The resolved ticket
+    pub ticket: ApprovalTicket,
+    /// How it was resolved
+    pub outcome: TicketOutcome,
+    /// When it was resolved
+    pub resolved_at: DateTime<Utc>,
+}
+
+/// Bridges approval tickets to a Sacred Alliance channel
+pub struct ChannelApprovalBridge {
+    /// Participant ID the bridge posts as
+    participant_id: String,
+    /// Participants allowed to decide tickets
+    approvers: Vec<String>,
+    /// How long a ticket stays open
+    timeout: Duration,
+    /// Open tickets by ID
+    pending: HashMap<Uuid, ApprovalTicket>,
+}
+
+impl ChannelApprovalBridge {
+    /// Create a bridge posting as `participant_id`, with tickets decided by `approvers`
+    pub fn new(participant_id: String, approvers: Vec<String>, timeout: Duration) -> Self {
+        Self {
+            participant_id,
+            approvers,
+            timeout,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Whether a participant may decide tickets
+    pub fn is_approver(&self, participant_id: &str) -> bool {
+        self.approvers.iter().any(|a| a == participant_id)
+    }
+
+    /// Open tickets
+    pub fn pending(&self) -> Vec<&ApprovalTicket> {
+        self.pending.values().collect()
+    }
+
+    /// Check an operation against `tracker`, posting a ticket when it needs user approval
+    ///
+    /// [`ApprovalResult::UserApprovalRequired`] becomes a ticket on `channel`
+    /// priced in the tracker's currency; other results pass through.
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_approval<C: ApprovalChannel>(
+        &mut self,
+        tracker: &FinancialTracker,
+        channel: &mut C,
+        operation: &str,
+        operation_type: &OperationType,
+        estimated_cost: u64,
+        requester: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ApprovalGate, WeaveMeshError> {
+        match tracker.check_approval(estimated_cost, operation_type)? {
+            ApprovalResult::Approved => Ok(ApprovalGate::Approved),
+            ApprovalResult::Denied { reason } => Ok(ApprovalGate::Denied { reason }),
+            ApprovalResult::UserApprovalRequired { estimated_cost } => {
+                let currency = tracker.get_limits().currency.clone();
+                self.request_approval(channel, operation, estimated_cost, &currency, requester, now)
+                    .map(ApprovalGate::Pending)
+            }
+        }
+    }
+
+    /// Open a ticket and post the request to the channel
+    pub fn request_approval<C: ApprovalChannel>(
+        &mut self,
+        channel: &mut C,
+        operation: &str,
+        estimated_cost: u64,
+        currency: &str,
+        requester: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ApprovalTicket, WeaveMeshError> {
+        let ticket = ApprovalTicket {
+            ticket_id: Uuid::new_v4(),
+            operation: operation.to_string(),
+            estimated_cost,
+            currency: currency.to_string(),
+            requester: requester.to_string(),
+            requested_at: now,
+            expires_at: now + self.timeout,
+        };
+
+        self.post(channel, None, now, ApprovalMessage::Request {
+            ticket_id: ticket.ticket_id,
+            operation: ticket.operation.clone(),
+            estimated_cost,
+            currency: ticket.currency.clone(),
+            requester: ticket.requester.clone(),
+            expires_at: ticket.expires_at,
+        })?;
+
+        self.pending.insert(ticket.ticket_id, ticket.clone());
+        Ok(ticket)
+    }
+
+    /// Handle a channel message, resolving a ticket on the first authorized decision
+    ///
+    /// Decisions from participants who are not approvers get a private
+    /// rejection and leave the ticket open. Anything other than a decision
+    /// on an open ticket is ignored.
+    pub fn handle_message<C: ApprovalChannel>(
+        &mut self,
+        channel: &mut C,
+        message: &AllianceMessage,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApprovalResolution>, WeaveMeshError> {
+        let (ticket_id, approve) = match &message.content {
+            MessageContent::Approval(ApprovalMessage::Decision { ticket_id, approve, .. }) => (*ticket_id, *approve),
+            _ => return Ok(None),
+        };
+
+        if !self.pending.contains_key(&ticket_id) {
+            return Ok(None);
+        }
+
+        if !self.is_approver(&message.sender) {
+            self.post(channel, Some(&message.sender), now, ApprovalMessage::Unauthorized {
+                ticket_id,
+                participant: message.sender.clone(),
+            })?;
+            return Ok(None);
+        }
+
+        let ticket = match self.pending.remove(&ticket_id) {
+            Some(ticket) => ticket,
+            None => return Ok(None),
+        };
+
+        self.post(channel, None, now, ApprovalMessage::Resolved {
+            ticket_id,
+            approved: approve,
+            decided_by: message.sender.clone(),
+        })?;
+
+        let outcome = if approve {
+            TicketOutcome::Approved { decided_by: message.sender.clone() }
+        } else {
+            TicketOutcome::Denied { decided_by: message.sender.clone() }
+        };
+
+        Ok(Some(ApprovalResolution {
+            ticket,
+            outcome,
+            resolved_at: now,
+        }))
+    }
+
+    /// Expire open tickets past their deadline, posting a notice for each
+    pub fn expire<C: ApprovalChannel>(
+        &mut self,
+        channel: &mut C,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ApprovalResolution>, WeaveMeshError> {
+        let expired: Vec<Uuid> = self.pending.values()
+            .filter(|ticket| ticket.expires_at <= now)
+            .map(|ticket| ticket.ticket_id)
+            .collect();
+
+        let mut resolutions = Vec::new();
+        for ticket_id in expired {
+            if let Some(ticket) = self.pending.remove(&ticket_id) {
+                self.post(channel, None, now, ApprovalMessage::Expired { ticket_id })?;
+                resolutions.push(ApprovalResolution {
+                    ticket,
+                    outcome: TicketOutcome::Expired,
+                    resolved_at: now,
+                });
+            }
+        }
+
+        Ok(resolutions)
+    }
+
+    /// Expire overdue tickets every `interval` until `resolutions` is closed
+    ///
+    /// Expiry notices are posted to `channel` and each expired ticket is sent
+    /// to `resolutions`.
+    pub fn spawn_expiry<C: ApprovalChannel + Send + 'static>(
+        bridge: Arc<Mutex<Self>>,
+        channel: Arc<Mutex<C>>,
+        interval: std::time::Duration,
+        resolutions: mpsc::UnboundedSender<ApprovalResolution>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                if resolutions.is_closed() {
+                    break;
+                }
+                let expired = {
+                    let mut channel = channel.lock().await;
+                    bridge.lock().await.expire(&mut *channel, Utc::now())
+                };
+                match expired {
+                    Ok(expired) => {
+                        for resolution in expired {
+                            let _ = resolutions.send(resolution);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Failed to post approval expiry: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Post an approval message as the bridge, privately if `recipient` is set
+    fn post<C: ApprovalChannel>(
+        &self,
+        channel: &mut C,
+        recipient: Option<&str>,
+        now: DateTime<Utc>,
+        approval: ApprovalMessage,
+    ) -> Result<(), WeaveMeshError> {
+        let message = AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: self.participant_id.clone(),
+            content: MessageContent::Approval(approval),
+            timestamp: now,
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        };
+
+        let result = match recipient {
+            Some(recipient) => channel.post_private(recipient, message),
+            None => channel.post(message),
+        };
+        result.map_err(WeaveMeshError::Other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Channel recording public and private posts
+    #[derive(Default)]
+    struct MockChannel {
+        public: Vec<AllianceMessage>,
+        private: Vec<(String, AllianceMessage)>,
+    }
+
+    impl ApprovalChannel for MockChannel {
+        fn post(&mut self, message: AllianceMessage) -> anyhow::Result<()> {
+            self.public.push(message);
+            Ok(())
+        }
+
+        fn post_private(&mut self, recipient: &str, message: AllianceMessage) -> anyhow::Result<()> {
+            self.private.push((recipient.to_string(), message));
+            Ok(())
+        }
+    }
+
+    fn bridge() -> ChannelApprovalBridge {
+        ChannelApprovalBridge::new("treasurer-bot".to_string(), vec!["alice".to_string()], Duration::minutes(10))
+    }
+
+    fn decision(sender: &str, ticket_id: Uuid, approve: bool) -> AllianceMessage {
+        AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: sender.to_string(),
+            content: MessageContent::Approval(ApprovalMessage::Decision { ticket_id, approve, note: None }),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        }
+    }
+
+    fn approval(message: &AllianceMessage) -> &ApprovalMessage {
+        match &message.content {
+            MessageContent::Approval(approval) => approval,
+            other => panic!("expected approval message, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_request_and_approve() {
+        let mut channel = MockChannel::default();
+        let mut bridge = bridge();
+        let now = Utc::now();
+        let ticket = bridge.request_approval(&mut channel, "fine-tune model", 120, "USD", "ci-node", now).unwrap();
+
+        assert!(matches!(
+            approval(&channel.public[0]),
+            ApprovalMessage::Request { estimated_cost: 120, requester, .. } if requester == "ci-node"
+        ));
+
+        let resolution = bridge.handle_message(&mut channel, &decision("alice", ticket.ticket_id, true), now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolution.outcome, TicketOutcome::Approved { decided_by: "alice".to_string() });
+        assert!(bridge.pending().is_empty());
+        assert_eq!(
+            approval(&channel.public[1]),
+            &ApprovalMessage::Resolved { ticket_id: ticket.ticket_id, approved: true, decided_by: "alice".to_string() }
+        );
+        assert_eq!(channel.public[1].sender, "treasurer-bot");
+
+        // Later replies to a resolved ticket are ignored
+        assert!(bridge.handle_message(&mut channel, &decision("alice", ticket.ticket_id, false), now).unwrap().is_none());
+        assert_eq!(channel.public.len(), 2);
+    }
+
+    #[test]
+    fn test_denial() {
+        let mut channel = MockChannel::default();
+        let mut bridge = bridge();
+        let now = Utc::now();
+        let ticket = bridge.request_approval(&mut channel, "bulk export", 80, "USD", "ci-node", now).unwrap();
+
+        let resolution = bridge.handle_message(&mut channel, &decision("alice", ticket.ticket_id, false), now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolution.outcome, TicketOutcome::Denied { decided_by: "alice".to_string() });
+        assert!(matches!(approval(&channel.public[1]), ApprovalMessage::Resolved { approved: false, .. }));
+    }
+
+    #[test]
+    fn test_unauthorized_reply_gets_private_rejection() {
+        let mut channel = MockChannel::default();
+        let mut bridge = bridge();
+        let now = Utc::now();
+        let ticket = bridge.request_approval(&mut channel, "bulk export", 80, "USD", "ci-node", now).unwrap();
+
+        let result = bridge.handle_message(&mut channel, &decision("mallory", ticket.ticket_id, true), now).unwrap();
+        assert!(result.is_none());
+        assert_eq!(bridge.pending().len(), 1);
+        assert_eq!(channel.public.len(), 1);
+        assert_eq!(channel.private.len(), 1);
+        assert_eq!(channel.private[0].0, "mallory");
+        assert!(matches!(approval(&channel.private[0].1), ApprovalMessage::Unauthorized { participant, .. } if participant == "mallory"));
+
+        // The ticket can still be decided by an approver
+        assert!(bridge.handle_message(&mut channel, &decision("alice", ticket.ticket_id, true), now).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_expiry_notification() {
+        let mut channel = MockChannel::default();
+        let mut bridge = bridge();
+        let now = Utc::now();
+        let ticket = bridge.request_approval(&mut channel, "bulk export", 80, "USD", "ci-node", now).unwrap();
+
+        assert!(bridge.expire(&mut channel, now + Duration::minutes(5)).unwrap().is_empty());
+
+        let expired = bridge.expire(&mut channel, now + Duration::minutes(10)).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].outcome, TicketOutcome::Expired);
+        assert_eq!(approval(channel.public.last().unwrap()), &ApprovalMessage::Expired { ticket_id: ticket.ticket_id });
+        assert!(bridge.handle_message(&mut channel, &decision("alice", ticket.ticket_id, true), now).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expiry_runs_on_a_timer() {
+        let channel = Arc::new(Mutex::new(MockChannel::default()));
+        let bridge = Arc::new(Mutex::new(ChannelApprovalBridge::new(
+            "treasurer-bot".to_string(),
+            vec!["alice".to_string()],
+            Duration::zero(),
+        )));
+        let ticket = bridge.lock().await
+            .request_approval(&mut *channel.lock().await, "bulk export", 80, "USD", "ci-node", Utc::now())
+            .unwrap();
+
+        let (sender, mut resolutions) = mpsc::unbounded_channel();
+        let task = ChannelApprovalBridge::spawn_expiry(
+            bridge.clone(),
+            channel.clone(),
+            std::time::Duration::from_millis(10),
+            sender,
+        );
+        let resolution = tokio::time::timeout(std::time::Duration::from_secs(5), resolutions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolution.ticket.ticket_id, ticket.ticket_id);
+        assert_eq!(resolution.outcome, TicketOutcome::Expired);
+        assert!(bridge.lock().await.pending().is_empty());
+        assert_eq!(approval(channel.lock().await.public.last().unwrap()), &ApprovalMessage::Expired { ticket_id: ticket.ticket_id });
+
+        drop(resolutions);
+        tokio::time::timeout(std::time::Duration::from_secs(5), task).await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_user_approval_required_opens_a_ticket() {
+        let limits = crate::financial::SpendingLimits { auto_approval_threshold: 50, ..Default::default() };
+        let tracker = FinancialTracker::new(limits);
+        let mut channel = MockChannel::default();
+        let mut bridge = bridge();
+        let now = Utc::now();
+
+        let cheap = bridge.check_approval(&tracker, &mut channel, "lookup", &OperationType::AI, 10, "ci-node", now).unwrap();
+        assert!(matches!(cheap, ApprovalGate::Approved));
+        assert!(channel.public.is_empty());
+
+        let gate = bridge.check_approval(&tracker, &mut channel, "fine-tune model", &OperationType::AI, 80, "ci-node", now).unwrap();
+        let ticket = match gate {
+            ApprovalGate::Pending(ticket) => ticket,
+            other => panic!("expected a ticket, got {:?}", other),
+        };
+        assert_eq!(ticket.currency, tracker.get_limits().currency);
+        assert!(matches!(approval(&channel.public[0]), ApprovalMessage::Request { estimated_cost: 80, .. }));
+        assert_eq!(bridge.pending().len(), 1);
+    }
+
+    #[test]
+    fn test_private_post_uses_a_direct_channel() {
+        use crate::sacred_alliance::{ParticipantType, PresenceStatus};
+
+        let mut project = BasicSacredAllianceChannel::new("project".to_string(), ChannelConfig::default());
+        for (id, participant_type) in [("treasurer-bot", ParticipantType::Ai), ("mallory", ParticipantType::Human)] {
+            project.add_participant(Participant {
+                id: id.to_string(),
+                participant_type,
+                presence: PresenceStatus::Active,
+                capabilities: vec![],
+                joined_at: Utc::now(),
+            }).unwrap();
+        }
+        let mut channel = AllianceApprovalChannel::new(project);
+
+        let mut bridge = bridge();
+        let now = Utc::now();
+        let ticket = bridge.request_approval(&mut channel, "bulk export", 80, "USD", "ci-node", now).unwrap();
+        bridge.handle_message(&mut channel, &decision("mallory", ticket.ticket_id, true), now).unwrap();
+
+        // The rejection stays out of the project channel
+        assert_eq!(channel.channel().get_history().len(), 1);
+        let direct = channel.direct_channel("mallory").unwrap();
+        assert_eq!(direct.get_participants().len(), 2);
+        assert!(matches!(
+            approval(direct.get_history().last().unwrap()),
+            ApprovalMessage::Unauthorized { participant, .. } if participant == "mallory"
+        ));
+
+        // Private posts to someone outside the project fail
+        assert!(channel.post_private("stranger", decision("treasurer-bot", ticket.ticket_id, true)).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! End-of-period spend forecasting
+//!
+//! Spending limits apply to calendar periods: the UTC day, the ISO week
+//! starting Monday, and the calendar month. A [`SpendForecast`] projects the
+//! net spend at the end of the current period from the spend so far plus the
+//! recent run rate, measured in hourly buckets over a configurable lookback.
+//! The spread of those buckets gives a rough confidence interval and breach
+//! probability; both assume hours are independent, so treat them as
+//! indicators rather than statistics.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{CostRecord, SpendingPeriod};
+
+/// How forecasts are computed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastConfig {
+    /// Hours of recent spend used for the run rate
+    pub lookback_hours: i64,
+    /// Standard deviations covered by the projected interval (1.64 ≈ 90%)
+    pub confidence_z: f64,
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            lookback_hours: 72,
+            confidence_z: 1.64,
+        }
+    }
+}
+
+/// Projected spend at the end of the current period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendForecast {
+    /// Period forecast
+    pub period: SpendingPeriod,
+    /// Start of the current period
+    pub period_start: DateTime<Utc>,
+    /// End of the current period
+    pub period_end: DateTime<Utc>,
+    /// Net spend since the period started
+    pub spent_to_date: u64,
+    /// Recent net spend per day
+    pub run_rate_per_day: f64,
+    /// Expected net spend at the end of the period
+    pub projected_total: f64,
+    /// Lower end of the projected interval, never below the spend to date
+    pub projected_low: f64,
+    /// Upper end of the projected interval
+    pub projected_high: f64,
+    /// Configured limit for the period
+    pub limit: Option<u64>,
+    /// Rough probability of ending the period above the limit
+    pub breach_probability: f64,
+    /// Days until the limit is reached at the current rate; 0 if already over
+    pub days_until_breach: Option<f64>,
+}
+
+impl SpendForecast {
+    /// Whether the projection ends the period above the limit
+    pub fn projects_breach(&self) -> bool {
+        self.limit.is_some_and(|limit| self.projected_total > limit as f64)
+    }
+}
+
+/// Raised when a period's projection first crosses its limit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastAlert {
+    /// Period at risk
+    pub period: SpendingPeriod,
+    /// Start of the period at risk
+    pub period_start: DateTime<Utc>,
+    /// Projection that crossed the limit
+    pub projected_total: f64,
+    /// Limit crossed
+    pub limit: u64,
+    /// Days until the limit is reached at the current rate
+    pub days_until_breach: Option<f64>,
+}
+
+/// Bounds of the calendar period containing `now`
+///
+/// Session and Total have no end, so they cannot be forecast.
+pub fn period_bounds(period: &SpendingPeriod, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let today = now.date_naive();
+    let midnight = |date: NaiveDate| Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight exists"));
+    match period {
+        SpendingPeriod::Daily => {
+            let start = midnight(today);
+            Some((start, start + Duration::days(1)))
+        }
+        SpendingPeriod::Weekly => {
+            let start = midnight(today - Duration::days(today.weekday().num_days_from_monday() as i64));
+            Some((start, start + Duration::weeks(1)))
+        }
+        SpendingPeriod::Monthly => {
+            let first = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)?;
+            let next = if today.month() == 12 {
+                NaiveDate::from_ymd_opt(today.year() + 1, 1, 1)?
+            } else {
+                NaiveDate::from_ymd_opt(today.year(), today.month() + 1, 1)?
+            };
+            Some((midnight(first), midnight(next)))
+        }
+        SpendingPeriod::Session | SpendingPeriod::Total => None,
+    }
+}
+
+/// Forecast the current `period` from cost records
+///
+/// The run rate covers `[now - lookback, now)`, shortened to the recorded
+/// history when that is shorter, but never below one hour.
+pub fn forecast(
+    costs: &[CostRecord],
+    period: SpendingPeriod,
+    limit: Option<u64>,
+    now: DateTime<Utc>,
+    config: &ForecastConfig,
+) -> Option<SpendForecast> {
+    let (period_start, period_end) = period_bounds(&period, now)?;
+    let signed = |record: &CostRecord| if record.is_chargeback() { -(record.cost as f64) } else { record.cost as f64 };
+
+    let spent: f64 = costs
+        .iter()
+        .filter(|r| r.timestamp >= period_start && r.timestamp <= now)
+        .map(signed)
+        .sum();
+    let spent_to_date = spent.max(0.0) as u64;
+
+    // Hourly buckets over the lookback
+    let history_hours = costs
+        .iter()
+        .map(|r| r.timestamp)
+        .min()
+        .map_or(1, |earliest| ((now - earliest).num_seconds() as f64 / 3600.0).ceil() as i64);
+    let hours = history_hours.clamp(1, config.lookback_hours.max(1));
+    let window_start = now - Duration::hours(hours);
+    let mut buckets = vec![0.0; hours as usize];
+    for record in costs.iter().filter(|r| r.timestamp >= window_start && r.timestamp < now) {
+        let index = ((record.timestamp - window_start).num_seconds() / 3600) as usize;
+        buckets[index.min(hours as usize - 1)] += signed(record);
+    }
+    let rate_per_hour = (buckets.iter().sum::<f64>() / hours as f64).max(0.0);
+    let variance = buckets.iter().map(|b| (b - rate_per_hour).powi(2)).sum::<f64>() / hours as f64;
+
+    let remaining_hours = (period_end - now).num_seconds().max(0) as f64 / 3600.0;
+    let projected_total = spent_to_date as f64 + rate_per_hour * remaining_hours;
+    let spread = variance.sqrt() * remaining_hours.sqrt();
+    let margin = config.confidence_z * spread;
+
+    let (breach_probability, days_until_breach) = match limit {
+        Some(limit) if spent_to_date >= limit => (1.0, Some(0.0)),
+        Some(limit) => {
+            let probability = if spread > 0.0 {
+                // Logistic approximation of the normal CDF
+                let z = (projected_total - limit as f64) / spread;
+                1.0 / (1.0 + (-1.702 * z).exp())
+            } else if projected_total > limit as f64 {
+                1.0
+            } else {
+                0.0
+            };
+            let days = (rate_per_hour > 0.0).then(|| (limit - spent_to_date) as f64 / rate_per_hour / 24.0);
+            (probability, days)
+        }
+        None => (0.0, None),
+    };
+
+    Some(SpendForecast {
+        period,
+        period_start,
+        period_end,
+        spent_to_date,
+        run_rate_per_day: rate_per_hour * 24.0,
+        projected_total,
+        projected_low: (projected_total - margin).max(spent_to_date as f64),
+        projected_high: projected_total + margin,
+        limit,
+        breach_probability,
+        days_until_breach,
+    })
+}
+
+/// Whether a period's projection is currently over its limit, per period start
+///
+/// Used to raise a [`ForecastAlert`] once per crossing: a period alerts when
+/// its projection goes over the limit, and again only after the projection
+/// has dropped back under it. A new period starts with a clean slate.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BreachState {
+    period_start: Option<DateTime<Utc>>,
+    over_limit: bool,
+}
+
+impl BreachState {
+    /// Update with a fresh forecast, returning an alert on a new crossing
+    pub(crate) fn observe(&mut self, forecast: &SpendForecast) -> Option<ForecastAlert> {
+        if self.period_start != Some(forecast.period_start) {
+            self.period_start = Some(forecast.period_start);
+            self.over_limit = false;
+        }
+        let over_limit = forecast.projects_breach();
+        let crossed = over_limit && !self.over_limit;
+        self.over_limit = over_limit;
+        match (crossed, forecast.limit) {
+            (true, Some(limit)) => Some(ForecastAlert {
+                period: forecast.period.clone(),
+                period_start: forecast.period_start,
+                projected_total: forecast.projected_total,
+                limit,
+                days_until_breach: forecast.days_until_breach,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_period_bounds() {
+        let now = Utc.with_ymd_and_hms(2026, 12, 17, 15, 30, 0).unwrap();
+        let (start, end) = period_bounds(&SpendingPeriod::Daily, now).unwrap();
+        assert_eq!((start, end), (Utc.with_ymd_and_hms(2026, 12, 17, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2026, 12, 18, 0, 0, 0).unwrap()));
+        let (start, _) = period_bounds(&SpendingPeriod::Weekly, now).unwrap();
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 12, 14, 0, 0, 0).unwrap());
+        let (start, end) = period_bounds(&SpendingPeriod::Monthly, now).unwrap();
+        assert_eq!((start, end), (Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap(), Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()));
+        assert!(period_bounds(&SpendingPeriod::Total, now).is_none());
+    }
+}
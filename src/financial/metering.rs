@@ -0,0 +1,399 @@
+//! Usage Metering for Storage and Network Costs
+//!
+//! Storage implementations and `NodeCommunication` report the bytes they
+//! move through a `UsageMeter`. The `MeteringAggregator` accumulates those
+//! samples per context and attribution and converts them into `CostRecord`s
+//! once per rollup window, using configurable per-GB rates.
+
+use super::{CostRecord, OperationType};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Bytes in a gigabyte for rate calculations
+const BYTES_PER_GB: u128 = 1_000_000_000;
+
+/// Seconds in a day for storage-at-rest rates
+const SECONDS_PER_DAY: u128 = 86_400;
+
+/// Source of the current time
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    /// Current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock backed by the system time
+#[derive(Debug, Clone, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when advanced, for tests and simulations
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    /// Create a clock set to the given time
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+    }
+
+    /// Set the clock to a specific time
+    pub fn set(&self, time: DateTime<Utc>) {
+        *self.now.lock().unwrap() = time;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Kind of metered usage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UsageKind {
+    /// Bytes written to storage; they stay at rest until deleted
+    StorageWrite,
+    /// Bytes read from storage
+    StorageRead,
+    /// Bytes removed from storage
+    StorageDelete,
+    /// Bytes sent to other nodes
+    NetworkEgress,
+    /// Bytes received from other nodes
+    NetworkIngress,
+}
+
+/// Single usage measurement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSample {
+    /// What was measured
+    pub kind: UsageKind,
+    /// Number of bytes
+    pub bytes: u64,
+    /// Context the usage belongs to
+    pub context: Option<String>,
+    /// Attribution identifier, where available
+    pub attribution: Option<String>,
+}
+
+/// Receiver of usage measurements
+pub trait UsageMeter: Send + Sync + std::fmt::Debug {
+    /// Record a usage sample
+    fn record_usage(&self, sample: UsageSample);
+}
+
+/// Rates and rollup window for metered costs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteringConfig {
+    /// Cost per GB stored for one day, in base units
+    pub storage_per_gb_day: u64,
+    /// Cost per GB sent, in base units
+    pub egress_per_gb: u64,
+    /// Cost per GB received, in base units
+    pub ingress_per_gb: u64,
+    /// Length of a rollup window in seconds
+    pub rollup_interval_secs: u64,
+    /// Currency of the generated records
+    pub currency: String,
+}
+
+impl Default for MeteringConfig {
+    fn default() -> Self {
+        Self {
+            storage_per_gb_day: 1, // 0.01 USD
+            egress_per_gb: 9,      // 0.09 USD
+            ingress_per_gb: 0,
+            rollup_interval_secs: 3600,
+            currency: "USD".to_string(),
+        }
+    }
+}
+
+/// Usage accumulated for one context and attribution
+#[derive(Debug, Clone, Default)]
+struct AccumulatedUsage {
+    bytes_written: u64,
+    bytes_read: u64,
+    egress_bytes: u64,
+    ingress_bytes: u64,
+    /// Bytes currently at rest
+    stored_bytes: u64,
+    /// Stored bytes integrated over time within the window
+    byte_seconds: u128,
+    /// Last time `byte_seconds` was brought up to date
+    integrated_until: Option<DateTime<Utc>>,
+}
+
+impl AccumulatedUsage {
+    fn integrate_to(&mut self, time: DateTime<Utc>) {
+        if let Some(since) = self.integrated_until {
+            let seconds = (time - since).num_seconds().max(0) as u128;
+            self.byte_seconds += self.stored_bytes as u128 * seconds;
+        }
+        self.integrated_until = Some(time);
+    }
+
+    /// Reset per-window counters, keeping bytes at rest
+    fn start_window(&mut self) {
+        self.bytes_written = 0;
+        self.bytes_read = 0;
+        self.egress_bytes = 0;
+        self.ingress_bytes = 0;
+        self.byte_seconds = 0;
+    }
+
+    fn is_idle(&self) -> bool {
+        self.bytes_written == 0
+            && self.bytes_read == 0
+            && self.egress_bytes == 0
+            && self.ingress_bytes == 0
+            && self.byte_seconds == 0
+    }
+}
+
+type UsageKey = (Option<String>, Option<String>);
+
+#[derive(Debug)]
+struct MeteringState {
+    window_start: DateTime<Utc>,
+    usage: HashMap<UsageKey, AccumulatedUsage>,
+    pending: Vec<CostRecord>,
+}
+
+/// Converts usage samples into periodic cost rollups
+#[derive(Debug)]
+pub struct MeteringAggregator {
+    config: MeteringConfig,
+    clock: Arc<dyn Clock>,
+    state: Mutex<MeteringState>,
+}
+
+impl MeteringAggregator {
+    /// Create an aggregator using the system clock
+    pub fn new(config: MeteringConfig) -> Self {
+        Self::with_clock(config, Arc::new(SystemClock))
+    }
+
+    /// Create an aggregator with a specific clock
+    pub fn with_clock(config: MeteringConfig, clock: Arc<dyn Clock>) -> Self {
+        let window_start = clock.now();
+        Self {
+            config,
+            clock,
+            state: Mutex::new(MeteringState {
+                window_start,
+                usage: HashMap::new(),
+                pending: Vec::new(),
+            }),
+        }
+    }
+
+    /// Metering configuration
+    pub fn config(&self) -> &MeteringConfig {
+        &self.config
+    }
+
+    /// Close any finished rollup windows and return their cost records
+    pub fn take_rollups(&self) -> Vec<CostRecord> {
+        let mut state = self.state.lock().unwrap();
+        self.close_windows(&mut state, self.clock.now());
+        std::mem::take(&mut state.pending)
+    }
+
+    /// Close every window that ended at or before `now`
+    fn close_windows(&self, state: &mut MeteringState, now: DateTime<Utc>) {
+        let interval = Duration::seconds(self.config.rollup_interval_secs.max(1) as i64);
+
+        while state.window_start + interval <= now {
+            let window_end = state.window_start + interval;
+            let mut keys: Vec<UsageKey> = state.usage.keys().cloned().collect();
+            keys.sort();
+
+            for key in keys {
+                let usage = state.usage.get_mut(&key).expect("key collected from map");
+                usage.integrate_to(window_end);
+                if !usage.is_idle() {
+                    let records = self.rollup_records(&key, usage, state.window_start, window_end);
+                    state.pending.extend(records);
+                }
+                usage.start_window();
+            }
+
+            state.usage.retain(|_, usage| usage.stored_bytes > 0);
+            state.window_start = window_end;
+        }
+    }
+
+    /// Build the storage and network records for one key and window
+    fn rollup_records(
+        &self,
+        (context, attribution): &UsageKey,
+        usage: &AccumulatedUsage,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Vec<CostRecord> {
+        let mut records = Vec::new();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("window_start".to_string(), window_start.to_rfc3339());
+        metadata.insert("window_end".to_string(), window_end.to_rfc3339());
+        if let Some(attribution) = attribution {
+            metadata.insert("attribution".to_string(), attribution.clone());
+        }
+
+        if usage.byte_seconds > 0 || usage.bytes_written > 0 || usage.bytes_read > 0 {
+            let cost = rate_cost(
+                usage.byte_seconds,
+                self.config.storage_per_gb_day as u128,
+                BYTES_PER_GB * SECONDS_PER_DAY,
+            );
+            let mut metadata = metadata.clone();
+            metadata.insert("bytes_written".to_string(), usage.bytes_written.to_string());
+            metadata.insert("bytes_read".to_string(), usage.bytes_read.to_string());
+            metadata.insert("bytes_at_rest".to_string(), usage.stored_bytes.to_string());
+            metadata.insert("byte_seconds".to_string(), usage.byte_seconds.to_string());
+            records.push(self.cost_record(OperationType::Storage, cost, context, window_end, metadata));
+        }
+
+        if usage.egress_bytes > 0 || usage.ingress_bytes > 0 {
+            let cost = rate_cost(usage.egress_bytes as u128, self.config.egress_per_gb as u128, BYTES_PER_GB)
+                + rate_cost(usage.ingress_bytes as u128, self.config.ingress_per_gb as u128, BYTES_PER_GB);
+            let mut metadata = metadata;
+            metadata.insert("egress_bytes".to_string(), usage.egress_bytes.to_string());
+            metadata.insert("ingress_bytes".to_string(), usage.ingress_bytes.to_string());
+            records.push(self.cost_record(OperationType::Network, cost, context, window_end, metadata));
+        }
+
+        records
+    }
+
+    fn cost_record(
+        &self,
+        operation_type: OperationType,
+        cost: u64,
+        context: &Option<String>,
+        timestamp: DateTime<Utc>,
+        metadata: HashMap<String, String>,
+    ) -> CostRecord {
+        CostRecord {
+            operation_id: format!("metering-{}", uuid::Uuid::new_v4()),
+            timestamp,
+            cost,
+            currency: self.config.currency.clone(),
+            operation_type,
+            context: context.clone(),
+            metadata,
+        }
+    }
+}
+
+impl UsageMeter for MeteringAggregator {
+    fn record_usage(&self, sample: UsageSample) {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        self.close_windows(&mut state, now);
+
+        let usage = state.usage.entry((sample.context, sample.attribution)).or_default();
+        match sample.kind {
+            UsageKind::StorageWrite => {
+                usage.integrate_to(now);
+                usage.bytes_written += sample.bytes;
+                usage.stored_bytes += sample.bytes;
+            }
+            UsageKind::StorageDelete => {
+                usage.integrate_to(now);
+                usage.stored_bytes = usage.stored_bytes.saturating_sub(sample.bytes);
+            }
+            UsageKind::StorageRead => usage.bytes_read += sample.bytes,
+            UsageKind::NetworkEgress => usage.egress_bytes += sample.bytes,
+            UsageKind::NetworkIngress => usage.ingress_bytes += sample.bytes,
+        }
+    }
+}
+
+/// Apply a per-unit rate, rounding to the nearest base unit
+fn rate_cost(quantity: u128, rate: u128, unit: u128) -> u64 {
+    ((quantity * rate + unit / 2) / unit) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1_000_000_000;
+
+    fn sample(kind: UsageKind, bytes: u64, context: &str) -> UsageSample {
+        UsageSample {
+            kind,
+            bytes,
+            context: Some(context.to_string()),
+            attribution: None,
+        }
+    }
+
+    #[test]
+    fn test_network_rollup_matches_rates() {
+        let clock = ManualClock::new(Utc::now());
+        let config = MeteringConfig {
+            egress_per_gb: 9,
+            ingress_per_gb: 2,
+            ..MeteringConfig::default()
+        };
+        let aggregator = MeteringAggregator::with_clock(config, Arc::new(clock.clone()));
+
+        aggregator.record_usage(sample(UsageKind::NetworkEgress, 3 * GB, "sync"));
+        aggregator.record_usage(sample(UsageKind::NetworkIngress, 5 * GB, "sync"));
+        assert!(aggregator.take_rollups().is_empty());
+
+        clock.advance(Duration::hours(1));
+        let records = aggregator.take_rollups();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].operation_type, OperationType::Network);
+        assert_eq!(records[0].cost, 3 * 9 + 5 * 2);
+        assert_eq!(records[0].context.as_deref(), Some("sync"));
+    }
+
+    #[test]
+    fn test_storage_at_rest_rollup() {
+        let clock = ManualClock::new(Utc::now());
+        let config = MeteringConfig {
+            storage_per_gb_day: 48,
+            rollup_interval_secs: 3600,
+            ..MeteringConfig::default()
+        };
+        let aggregator = MeteringAggregator::with_clock(config, Arc::new(clock.clone()));
+
+        aggregator.record_usage(sample(UsageKind::StorageWrite, 2 * GB, "archive"));
+        clock.advance(Duration::minutes(30));
+        aggregator.record_usage(sample(UsageKind::StorageDelete, GB, "archive"));
+        clock.advance(Duration::minutes(30));
+
+        // 2 GB for half an hour plus 1 GB for half an hour at 48 per GB-day
+        let records = aggregator.take_rollups();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].cost, 3);
+
+        // Remaining bytes keep accruing in the following window
+        clock.advance(Duration::hours(1));
+        let records = aggregator.take_rollups();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].cost, 2);
+    }
+}
@@ -5,9 +5,33 @@
 //! financial implementations to build on top.
 
 use crate::WeaveMeshError;
+use crate::resource_profile::{
+    ComponentFootprint, EvictionCounters, EvictionKind, MemoryFootprint, ResourceProfile,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub mod approval;
+pub mod forecast;
+pub mod metering;
+
+pub use metering::{
+    Clock, SystemClock, ManualClock, UsageKind, UsageSample, UsageMeter,
+    MeteringConfig, MeteringAggregator,
+};
+pub use approval::{
+    ApprovalChannel, ApprovalTicket, TicketOutcome, ApprovalResolution, ChannelApprovalBridge,
+    AllianceApprovalChannel, ApprovalGate,
+};
+pub use forecast::{ForecastConfig, SpendForecast, ForecastAlert, period_bounds};
+
+use forecast::BreachState;
+
+/// Called when a period's projected spend first crosses its limit
+pub type ForecastAlertHandler = Arc<dyn Fn(&ForecastAlert) + Send + Sync>;
 
 /// Universal cost tracking for operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +52,38 @@ pub struct CostRecord {
     pub metadata: HashMap<String, String>,
 }
 
+impl CostRecord {
+    /// Metadata key linking a chargeback to the operation it reverses
+    pub const CHARGEBACK_OF: &'static str = "chargeback_of";
+    /// Metadata key holding the reason for a chargeback
+    pub const CHARGEBACK_REASON: &'static str = "chargeback_reason";
+    
+    /// Operation reversed by this record, if it is a chargeback
+    pub fn chargeback_of(&self) -> Option<&str> {
+        self.metadata.get(Self::CHARGEBACK_OF).map(String::as_str)
+    }
+    
+    /// Whether this record reverses another one
+    pub fn is_chargeback(&self) -> bool {
+        self.chargeback_of().is_some()
+    }
+}
+
+/// Reversal of a previously recorded cost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChargebackRecord {
+    /// Operation ID of the reversed cost
+    pub original_id: String,
+    /// Operation ID of the compensating record
+    pub chargeback_id: String,
+    /// Amount reversed
+    pub amount: u64,
+    /// Why the cost was reversed
+    pub reason: String,
+    /// When the chargeback was recorded
+    pub processed_at: DateTime<Utc>,
+}
+
 /// Types of operations for cost tracking
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum OperationType {
@@ -75,26 +131,77 @@ impl Default for SpendingLimits {
     }
 }
 
+impl SpendingLimits {
+    /// Check that the limits are consistent with each other
+    ///
+    /// Set limits must not shrink from per-operation to daily, weekly and
+    /// monthly, and auto-approval must not exceed the per-operation limit.
+    pub fn validate(&self) -> Result<(), WeaveMeshError> {
+        let mut problems = Vec::new();
+        let ordered = [
+            ("per_operation_limit", self.per_operation_limit),
+            ("daily_limit", self.daily_limit),
+            ("weekly_limit", self.weekly_limit),
+            ("monthly_limit", self.monthly_limit),
+        ];
+        for (i, (smaller_name, smaller)) in ordered.iter().enumerate() {
+            for (larger_name, larger) in &ordered[i + 1..] {
+                if let (Some(smaller), Some(larger)) = (smaller, larger) {
+                    if smaller > larger {
+                        problems.push(format!("{} ({}) exceeds {} ({})", smaller_name, smaller, larger_name, larger));
+                    }
+                }
+            }
+        }
+        if let Some(per_operation) = self.per_operation_limit {
+            if self.auto_approval_threshold > per_operation {
+                problems.push(format!(
+                    "auto_approval_threshold ({}) exceeds per_operation_limit ({})",
+                    self.auto_approval_threshold, per_operation
+                ));
+            }
+        }
+        if self.currency.trim().is_empty() {
+            problems.push("currency is empty".to_string());
+        }
+        
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(WeaveMeshError::Configuration(format!("invalid spending limits: {}", problems.join("; "))))
+        }
+    }
+}
+
 /// Spending period for analysis
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum SpendingPeriod {
     /// Last 24 hours
+    #[serde(alias = "daily")]
     Daily,
     /// Last 7 days
+    #[serde(alias = "weekly")]
     Weekly,
     /// Last 30 days
+    #[serde(alias = "monthly")]
     Monthly,
     /// Current session
+    #[serde(alias = "session")]
     Session,
     /// All time
+    #[serde(alias = "total")]
     Total,
 }
 
 /// Spending summary for a period
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpendingSummary {
-    /// Total spent in the period
+    /// Total spent in the period, before chargebacks
     pub total_spent: u64,
+    /// Total charged back in the period
+    pub chargebacks: u64,
+    /// Spending net of chargebacks
+    pub net_spent: u64,
     /// Number of operations
     pub operation_count: u32,
     /// Average cost per operation
@@ -112,6 +219,20 @@ pub struct SpendingSummary {
     pub period_end: DateTime<Utc>,
 }
 
+/// Spending state as of a point in time, for audits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendingSnapshot {
+    /// Net spend of all records up to the snapshot time
+    pub total_spent_at_time: u64,
+    /// Records up to the snapshot time, chargebacks included
+    pub records_count: usize,
+    /// Net spend in the 24 hours before the snapshot time, as a percentage
+    /// of the daily limit (0 without a daily limit)
+    pub daily_utilization_pct: f64,
+    /// Time the snapshot describes
+    pub snapshot_time: DateTime<Utc>,
+}
+
 /// Cost approval result
 #[derive(Debug, Clone)]
 pub enum ApprovalResult {
@@ -131,6 +252,20 @@ pub struct FinancialTracker {
     limits: SpendingLimits,
     /// Maximum records to keep in memory
     max_records: usize,
+    /// Counts of records dropped by the in-memory limit
+    eviction_counters: Arc<EvictionCounters>,
+    /// Source of the current time for periods and forecasts
+    clock: Arc<dyn Clock>,
+    /// How forecasts are computed
+    forecast_config: ForecastConfig,
+    /// Whether each period's projection is over its limit
+    breach_states: HashMap<SpendingPeriod, BreachState>,
+    /// Receives forecast breach alerts
+    forecast_alert_handler: Option<ForecastAlertHandler>,
+    /// Whether new records are refused, as for replayed history
+    read_only: bool,
+    /// Estimated costs held for approved operations not yet recorded
+    reservations: HashMap<Uuid, u64>,
 }
 
 impl FinancialTracker {
@@ -140,27 +275,184 @@ impl FinancialTracker {
             costs: Vec::new(),
             limits,
             max_records: 10000,
+            eviction_counters: Arc::new(EvictionCounters::new()),
+            clock: Arc::new(SystemClock),
+            forecast_config: ForecastConfig::default(),
+            breach_states: HashMap::new(),
+            forecast_alert_handler: None,
+            read_only: false,
+            reservations: HashMap::new(),
         }
     }
     
+    /// Use `clock` for the current time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Compute forecasts with `config`
+    pub fn with_forecast_config(mut self, config: ForecastConfig) -> Self {
+        self.forecast_config = config;
+        self
+    }
+    
+    /// Call `handler` when a period's projected spend first crosses its limit
+    pub fn set_forecast_alert_handler(&mut self, handler: ForecastAlertHandler) {
+        self.forecast_alert_handler = Some(handler);
+    }
+    
     /// Create a tracker with default limits
     pub fn with_defaults() -> Self {
         Self::new(SpendingLimits::default())
     }
     
+    /// Create a tracker with the in-memory bounds of a resource profile
+    pub fn for_profile(limits: SpendingLimits, profile: ResourceProfile) -> Self {
+        Self {
+            max_records: profile.limits().max_cost_records,
+            ..Self::new(limits)
+        }
+    }
+    
+    /// Record evictions into shared counters
+    pub fn with_eviction_counters(mut self, counters: Arc<EvictionCounters>) -> Self {
+        self.eviction_counters = counters;
+        self
+    }
+    
+    /// Counters of records dropped by the in-memory limit
+    pub fn eviction_counters(&self) -> &Arc<EvictionCounters> {
+        &self.eviction_counters
+    }
+    
+    /// Maximum records kept in memory
+    pub fn max_records(&self) -> usize {
+        self.max_records
+    }
+    
     /// Record a cost
+    ///
+    /// Records are kept ordered by timestamp; a late record is inserted
+    /// after any others with the same timestamp.
     pub fn record_cost(&mut self, record: CostRecord) -> Result<(), WeaveMeshError> {
-        self.costs.push(record);
+        if self.read_only {
+            return Err(WeaveMeshError::Generic("tracker is read-only".to_string()));
+        }
+        match self.costs.last() {
+            Some(last) if last.timestamp > record.timestamp => {
+                let index = self.costs.partition_point(|r| r.timestamp <= record.timestamp);
+                self.costs.insert(index, record);
+            }
+            _ => self.costs.push(record),
+        }
         
         // Keep only the most recent records
         if self.costs.len() > self.max_records {
             self.costs.remove(0);
+            self.eviction_counters.record(EvictionKind::CostRecord, 1);
         }
         
+        self.check_forecasts();
         Ok(())
     }
     
+    /// Spending state as of `time`, counting only records up to it
+    pub fn snapshot_at(&self, time: DateTime<Utc>) -> SpendingSnapshot {
+        let recorded = self.records_until(time);
+        let day_start = recorded.partition_point(|r| r.timestamp <= time - chrono::Duration::days(1));
+        let daily_spent = net_cost(&recorded[day_start..]);
+        let daily_utilization_pct = match self.limits.daily_limit {
+            Some(limit) if limit > 0 => daily_spent as f64 / limit as f64 * 100.0,
+            _ => 0.0,
+        };
+        
+        SpendingSnapshot {
+            total_spent_at_time: net_cost(recorded),
+            records_count: recorded.len(),
+            daily_utilization_pct,
+            snapshot_time: time,
+        }
+    }
+    
+    /// Read-only tracker holding the records up to `time`, with its clock
+    /// stopped at `time`
+    ///
+    /// Summaries, forecasts and approval checks on the replay answer as this
+    /// tracker would have at that moment, which suits what-if analysis of
+    /// past limits via [`update_limits`](Self::update_limits).
+    pub fn replay_to(&self, time: DateTime<Utc>) -> FinancialTracker {
+        FinancialTracker {
+            costs: self.records_until(time).to_vec(),
+            limits: self.limits.clone(),
+            max_records: self.max_records,
+            eviction_counters: Arc::new(EvictionCounters::new()),
+            clock: Arc::new(ManualClock::new(time)),
+            forecast_config: self.forecast_config.clone(),
+            breach_states: HashMap::new(),
+            forecast_alert_handler: None,
+            read_only: true,
+            reservations: HashMap::new(),
+        }
+    }
+    
+    /// Whether this tracker refuses new records
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    
+    /// Records with timestamps up to and including `time`
+    fn records_until(&self, time: DateTime<Utc>) -> &[CostRecord] {
+        &self.costs[..self.costs.partition_point(|r| r.timestamp <= time)]
+    }
+    
+    /// Projected spend at the end of the current calendar `period`
+    ///
+    /// Returns None for periods without an end (Session and Total).
+    pub fn get_forecast(&self, period: SpendingPeriod) -> Option<SpendForecast> {
+        let limit = self.limit_for(&period);
+        forecast::forecast(&self.costs, period, limit, self.clock.now(), &self.forecast_config)
+    }
+    
+    /// Re-forecast every limited period and raise alerts for new breaches
+    ///
+    /// Runs after each recorded cost; call it periodically as well so alerts
+    /// follow period rollovers without new spend. Each crossing alerts once,
+    /// until the projection drops back under the limit or the period ends.
+    pub fn check_forecasts(&mut self) -> Vec<ForecastAlert> {
+        let mut alerts = Vec::new();
+        for period in [SpendingPeriod::Daily, SpendingPeriod::Weekly, SpendingPeriod::Monthly] {
+            if self.limit_for(&period).is_none() {
+                continue;
+            }
+            let forecast = match self.get_forecast(period.clone()) {
+                Some(forecast) => forecast,
+                None => continue,
+            };
+            if let Some(alert) = self.breach_states.entry(period).or_default().observe(&forecast) {
+                if let Some(handler) = &self.forecast_alert_handler {
+                    handler(&alert);
+                }
+                alerts.push(alert);
+            }
+        }
+        alerts
+    }
+    
+    fn limit_for(&self, period: &SpendingPeriod) -> Option<u64> {
+        match period {
+            SpendingPeriod::Daily => self.limits.daily_limit,
+            SpendingPeriod::Weekly => self.limits.weekly_limit,
+            SpendingPeriod::Monthly => self.limits.monthly_limit,
+            SpendingPeriod::Session | SpendingPeriod::Total => None,
+        }
+    }
+    
     /// Check if an operation is approved within spending limits
+    ///
+    /// Every configured period counts recorded spend plus outstanding
+    /// reservations. When several limits would be exceeded, the reason names
+    /// the period overrun by the most.
     pub fn check_approval(
         &self,
         estimated_cost: u64,
@@ -175,15 +467,34 @@ impl FinancialTracker {
             }
         }
         
-        // Check daily limit
-        if let Some(daily_limit) = self.limits.daily_limit {
-            let daily_spent = self.get_spending_for_period(SpendingPeriod::Daily)?;
-            if daily_spent + estimated_cost > daily_limit {
-                return Ok(ApprovalResult::Denied {
-                    reason: format!("Would exceed daily limit: {} + {} > {}", daily_spent, estimated_cost, daily_limit),
-                });
+        // Check period limits, keeping the largest overrun
+        let reserved = self.reserved_cost();
+        let mut worst: Option<(u64, String)> = None;
+        for (period, name) in [
+            (SpendingPeriod::Daily, "daily"),
+            (SpendingPeriod::Weekly, "weekly"),
+            (SpendingPeriod::Monthly, "monthly"),
+        ] {
+            let Some(limit) = self.limit_for(&period) else {
+                continue;
+            };
+            let committed = self.get_spending_for_period(period)? + reserved;
+            let total = committed + estimated_cost;
+            if total > limit && worst.as_ref().is_none_or(|(overrun, _)| total - limit > *overrun) {
+                let reason = if reserved > 0 {
+                    format!(
+                        "Would exceed {} limit: {} (including {} reserved) + {} > {}",
+                        name, committed, reserved, estimated_cost, limit
+                    )
+                } else {
+                    format!("Would exceed {} limit: {} + {} > {}", name, committed, estimated_cost, limit)
+                };
+                worst = Some((total - limit, reason));
             }
         }
+        if let Some((_, reason)) = worst {
+            return Ok(ApprovalResult::Denied { reason });
+        }
         
         // Check if user approval is required
         if estimated_cost > self.limits.auto_approval_threshold {
@@ -193,9 +504,38 @@ impl FinancialTracker {
         Ok(ApprovalResult::Approved)
     }
     
+    /// Check approval and, unless denied, hold `estimated_cost` against every limit
+    ///
+    /// Concurrent operations reserving before any is recorded cannot jointly
+    /// exceed a limit. Release the reservation once the operation's cost is
+    /// recorded, or when it is declined or abandoned.
+    pub fn reserve_cost(
+        &mut self,
+        estimated_cost: u64,
+        operation_type: &OperationType,
+    ) -> Result<(ApprovalResult, Option<Uuid>), WeaveMeshError> {
+        let approval = self.check_approval(estimated_cost, operation_type)?;
+        if matches!(approval, ApprovalResult::Denied { .. }) {
+            return Ok((approval, None));
+        }
+        let reservation = Uuid::new_v4();
+        self.reservations.insert(reservation, estimated_cost);
+        Ok((approval, Some(reservation)))
+    }
+    
+    /// Drop a reservation, returning the cost it held
+    pub fn release_reservation(&mut self, reservation: &Uuid) -> Option<u64> {
+        self.reservations.remove(reservation)
+    }
+    
+    /// Total cost held by outstanding reservations
+    pub fn reserved_cost(&self) -> u64 {
+        self.reservations.values().sum()
+    }
+    
     /// Get total spending for a period
     pub fn get_spending_for_period(&self, period: SpendingPeriod) -> Result<u64, WeaveMeshError> {
-        let now = Utc::now();
+        let now = self.clock.now();
         let cutoff = match period {
             SpendingPeriod::Daily => now - chrono::Duration::days(1),
             SpendingPeriod::Weekly => now - chrono::Duration::weeks(1),
@@ -207,18 +547,64 @@ impl FinancialTracker {
             SpendingPeriod::Total => DateTime::<Utc>::MIN_UTC,
         };
         
-        let total = self.costs
+        let (gross, chargebacks) = self.costs
             .iter()
             .filter(|record| record.timestamp >= cutoff)
-            .map(|record| record.cost)
-            .sum();
+            .fold((0u64, 0u64), |(gross, chargebacks), record| {
+                if record.is_chargeback() {
+                    (gross, chargebacks + record.cost)
+                } else {
+                    (gross + record.cost, chargebacks)
+                }
+            });
         
-        Ok(total)
+        Ok(gross.saturating_sub(chargebacks))
+    }
+    
+    /// Reverse a recorded cost with a compensating chargeback record
+    pub fn chargeback(&mut self, operation_id: &str, reason: &str) -> Result<ChargebackRecord, WeaveMeshError> {
+        if self.costs.iter().any(|record| record.chargeback_of() == Some(operation_id)) {
+            return Err(WeaveMeshError::Generic("already charged back".to_string()));
+        }
+        
+        let original = match self.costs.iter().find(|record| record.operation_id == operation_id) {
+            Some(original) => original,
+            None => return Err(WeaveMeshError::Generic(format!("cost record not found: {}", operation_id))),
+        };
+        if original.is_chargeback() {
+            return Err(WeaveMeshError::Generic("cannot charge back a chargeback".to_string()));
+        }
+        
+        let processed_at = self.clock.now();
+        let chargeback_id = format!("chargeback-{}", uuid::Uuid::new_v4());
+        let mut metadata = HashMap::new();
+        metadata.insert(CostRecord::CHARGEBACK_OF.to_string(), operation_id.to_string());
+        metadata.insert(CostRecord::CHARGEBACK_REASON.to_string(), reason.to_string());
+        
+        let record = CostRecord {
+            operation_id: chargeback_id.clone(),
+            timestamp: processed_at,
+            cost: original.cost,
+            currency: original.currency.clone(),
+            operation_type: original.operation_type.clone(),
+            context: original.context.clone(),
+            metadata,
+        };
+        let amount = record.cost;
+        self.record_cost(record)?;
+        
+        Ok(ChargebackRecord {
+            original_id: operation_id.to_string(),
+            chargeback_id,
+            amount,
+            reason: reason.to_string(),
+            processed_at,
+        })
     }
     
     /// Get detailed spending summary for a period
     pub fn get_spending_summary(&self, period: SpendingPeriod) -> Result<SpendingSummary, WeaveMeshError> {
-        let now = Utc::now();
+        let now = self.clock.now();
         let (cutoff, period_start) = match period {
             SpendingPeriod::Daily => (now - chrono::Duration::days(1), now - chrono::Duration::days(1)),
             SpendingPeriod::Weekly => (now - chrono::Duration::weeks(1), now - chrono::Duration::weeks(1)),
@@ -232,14 +618,19 @@ impl FinancialTracker {
             .filter(|record| record.timestamp >= cutoff)
             .collect();
         
-        let total_spent: u64 = relevant_costs.iter().map(|r| r.cost).sum();
-        let operation_count = relevant_costs.len() as u32;
+        let (charged_back, spent): (Vec<&CostRecord>, Vec<&CostRecord>) = relevant_costs
+            .into_iter()
+            .partition(|record| record.is_chargeback());
+        
+        let total_spent: u64 = spent.iter().map(|r| r.cost).sum();
+        let chargebacks: u64 = charged_back.iter().map(|r| r.cost).sum();
+        let operation_count = spent.len() as u32;
         let average_cost = if operation_count > 0 { total_spent / operation_count as u64 } else { 0 };
         
         let mut by_operation_type: HashMap<OperationType, u64> = HashMap::new();
         let mut by_context: HashMap<String, u64> = HashMap::new();
         
-        for record in &relevant_costs {
+        for record in &spent {
             *by_operation_type.entry(record.operation_type.clone()).or_insert(0) += record.cost;
             
             if let Some(context) = &record.context {
@@ -247,8 +638,20 @@ impl FinancialTracker {
             }
         }
         
+        // Breakdowns are net of chargebacks
+        for record in &charged_back {
+            if let Some(total) = by_operation_type.get_mut(&record.operation_type) {
+                *total = total.saturating_sub(record.cost);
+            }
+            if let Some(total) = record.context.as_ref().and_then(|context| by_context.get_mut(context)) {
+                *total = total.saturating_sub(record.cost);
+            }
+        }
+        
         Ok(SpendingSummary {
             total_spent,
+            chargebacks,
+            net_spent: total_spent.saturating_sub(chargebacks),
             operation_count,
             average_cost,
             by_operation_type,
@@ -286,6 +689,25 @@ impl FinancialTracker {
     }
 }
 
+/// Spend of `records` net of chargebacks
+fn net_cost(records: &[CostRecord]) -> u64 {
+    let (gross, chargebacks) = records.iter().fold((0u64, 0u64), |(gross, chargebacks), record| {
+        if record.is_chargeback() {
+            (gross, chargebacks + record.cost)
+        } else {
+            (gross + record.cost, chargebacks)
+        }
+    });
+    gross.saturating_sub(chargebacks)
+}
+
+#[async_trait::async_trait]
+impl MemoryFootprint for FinancialTracker {
+    async fn memory_footprint(&self) -> ComponentFootprint {
+        ComponentFootprint::of::<CostRecord>("cost_records", self.costs.len())
+    }
+}
+
 /// Cost estimation interface
 pub trait CostEstimator {
     /// Estimate cost for an operation
@@ -349,6 +771,7 @@ impl CostEstimator for SimpleCostEstimator {
 pub struct FinancialManager {
     tracker: FinancialTracker,
     estimator: Box<dyn CostEstimator + Send + Sync>,
+    metering: Option<Arc<MeteringAggregator>>,
 }
 
 impl FinancialManager {
@@ -357,6 +780,7 @@ impl FinancialManager {
         Self {
             tracker: FinancialTracker::new(limits),
             estimator,
+            metering: None,
         }
     }
     
@@ -380,6 +804,23 @@ impl FinancialManager {
         Ok((estimated_cost, approval))
     }
     
+    /// Estimate an operation's cost and reserve it; see [`FinancialTracker::reserve_cost`]
+    pub fn estimate_and_reserve(
+        &mut self,
+        operation_type: &OperationType,
+        context: Option<&str>,
+        metadata: &HashMap<String, String>,
+    ) -> Result<(u64, ApprovalResult, Option<Uuid>), WeaveMeshError> {
+        let estimated_cost = self.estimator.estimate_cost(operation_type, context, metadata)?;
+        let (approval, reservation) = self.tracker.reserve_cost(estimated_cost, operation_type)?;
+        Ok((estimated_cost, approval, reservation))
+    }
+    
+    /// Drop a reservation made by [`Self::estimate_and_reserve`]
+    pub fn release_reservation(&mut self, reservation: &Uuid) -> Option<u64> {
+        self.tracker.release_reservation(reservation)
+    }
+    
     /// Record a completed operation
     pub fn record_operation(
         &mut self,
@@ -391,7 +832,7 @@ impl FinancialManager {
     ) -> Result<(), WeaveMeshError> {
         let record = CostRecord {
             operation_id,
-            timestamp: Utc::now(),
+            timestamp: self.tracker.clock.now(),
             cost: actual_cost,
             currency: self.tracker.limits.currency.clone(),
             operation_type,
@@ -402,11 +843,50 @@ impl FinancialManager {
         self.tracker.record_cost(record)
     }
     
+    /// Reverse a previously recorded cost
+    ///
+    /// Fails if the operation is unknown or has already been charged back.
+    pub fn chargeback(&mut self, operation_id: &str, reason: &str) -> Result<ChargebackRecord, WeaveMeshError> {
+        self.tracker.chargeback(operation_id, reason)
+    }
+    
+    /// Attach a metering aggregator whose rollups feed this manager
+    pub fn attach_metering(&mut self, aggregator: Arc<MeteringAggregator>) {
+        self.metering = Some(aggregator);
+    }
+    
+    /// Record finished metering rollups as cost records
+    ///
+    /// Call periodically (e.g. from a maintenance loop) so summaries reflect
+    /// measured storage and network usage. Returns the number of records added.
+    pub fn collect_metering(&mut self) -> Result<usize, WeaveMeshError> {
+        let records = match &self.metering {
+            Some(aggregator) => aggregator.take_rollups(),
+            None => return Ok(0),
+        };
+        
+        let count = records.len();
+        for record in records {
+            self.tracker.record_cost(record)?;
+        }
+        Ok(count)
+    }
+    
     /// Get spending summary
     pub fn get_summary(&self, period: SpendingPeriod) -> Result<SpendingSummary, WeaveMeshError> {
         self.tracker.get_spending_summary(period)
     }
     
+    /// Projected spend at the end of the current calendar period
+    pub fn get_forecast(&self, period: SpendingPeriod) -> Option<SpendForecast> {
+        self.tracker.get_forecast(period)
+    }
+    
+    /// Call `handler` when a period's projected spend first crosses its limit
+    pub fn set_forecast_alert_handler(&mut self, handler: ForecastAlertHandler) {
+        self.tracker.set_forecast_alert_handler(handler);
+    }
+    
     /// Update spending limits
     pub fn update_limits(&mut self, limits: SpendingLimits) {
         self.tracker.update_limits(limits);
@@ -418,6 +898,43 @@ impl FinancialManager {
     }
 }
 
+/// Components that can report spending and its forecast
+#[async_trait::async_trait]
+pub trait FinancialSource: Send + Sync {
+    /// Spending summary over a rolling period
+    async fn spending_summary(&self, period: SpendingPeriod) -> Result<SpendingSummary, WeaveMeshError>;
+    
+    /// Projected spend at the end of the current calendar period
+    async fn forecast(&self, period: SpendingPeriod) -> Option<SpendForecast>;
+    
+    /// Current spending limits
+    async fn spending_limits(&self) -> SpendingLimits;
+    
+    /// Replace the spending limits after [`SpendingLimits::validate`] accepts them
+    async fn set_spending_limits(&self, limits: SpendingLimits) -> Result<(), WeaveMeshError>;
+}
+
+#[async_trait::async_trait]
+impl FinancialSource for tokio::sync::RwLock<FinancialManager> {
+    async fn spending_summary(&self, period: SpendingPeriod) -> Result<SpendingSummary, WeaveMeshError> {
+        self.read().await.get_summary(period)
+    }
+    
+    async fn forecast(&self, period: SpendingPeriod) -> Option<SpendForecast> {
+        self.read().await.get_forecast(period)
+    }
+    
+    async fn spending_limits(&self) -> SpendingLimits {
+        self.read().await.get_limits().clone()
+    }
+    
+    async fn set_spending_limits(&self, limits: SpendingLimits) -> Result<(), WeaveMeshError> {
+        limits.validate()?;
+        self.write().await.update_limits(limits);
+        Ok(())
+    }
+}
+
 mod test_standalone;
 
 #[cfg(test)]
@@ -465,6 +982,103 @@ mod tests {
         assert!(matches!(approval, ApprovalResult::Denied { .. }));
     }
 
+    #[test]
+    fn test_approval_checks_every_period() {
+        use chrono::TimeZone;
+
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap());
+        let limits = SpendingLimits {
+            per_operation_limit: Some(500),
+            weekly_limit: Some(1500),
+            ..SpendingLimits::default()
+        };
+        let mut tracker = FinancialTracker::new(limits).with_clock(Arc::new(clock.clone()));
+        for _ in 0..3 {
+            spend(&mut tracker, &clock, 400, 1);
+            clock.advance(chrono::Duration::hours(23));
+        }
+        clock.advance(chrono::Duration::hours(1));
+
+        // Under the daily cap, but over the week
+        match tracker.check_approval(400, &OperationType::AI).unwrap() {
+            ApprovalResult::Denied { reason } => assert_eq!(reason, "Would exceed weekly limit: 1200 + 400 > 1500"),
+            other => panic!("unexpected approval {:?}", other),
+        }
+        assert!(!matches!(tracker.check_approval(300, &OperationType::AI).unwrap(), ApprovalResult::Denied { .. }));
+    }
+
+    #[test]
+    fn test_reservations_count_against_limits() {
+        let mut tracker = FinancialTracker::with_defaults();
+        let reservations: Vec<Uuid> = (0..10)
+            .map(|_| tracker.reserve_cost(100, &OperationType::AI).unwrap().1.unwrap())
+            .collect();
+        assert_eq!(tracker.reserved_cost(), 1000);
+
+        let (approval, reservation) = tracker.reserve_cost(100, &OperationType::AI).unwrap();
+        assert!(reservation.is_none());
+        match approval {
+            ApprovalResult::Denied { reason } => assert!(reason.starts_with("Would exceed daily limit: 1000 (including 1000 reserved)")),
+            other => panic!("unexpected approval {:?}", other),
+        }
+
+        assert_eq!(tracker.release_reservation(&reservations[0]), Some(100));
+        assert_eq!(tracker.release_reservation(&reservations[0]), None);
+        assert!(tracker.reserve_cost(100, &OperationType::AI).unwrap().1.is_some());
+    }
+
+    #[test]
+    fn test_constrained_profile_evicts_records() {
+        let mut tracker = FinancialTracker::for_profile(SpendingLimits::default(), ResourceProfile::Constrained);
+        let limit = ResourceProfile::Constrained.limits().max_cost_records;
+        assert_eq!(tracker.max_records(), limit);
+        
+        for i in 0..limit + 5 {
+            let record = CostRecord {
+                operation_id: format!("op-{}", i),
+                timestamp: Utc::now(),
+                cost: 1,
+                currency: "USD".to_string(),
+                operation_type: OperationType::Communication,
+                context: None,
+                metadata: HashMap::new(),
+            };
+            tracker.record_cost(record).unwrap();
+        }
+        
+        assert_eq!(tracker.record_count(), limit);
+        assert_eq!(tracker.eviction_counters().get(EvictionKind::CostRecord), 5);
+        
+        let standard = FinancialTracker::for_profile(SpendingLimits::default(), ResourceProfile::Standard);
+        assert_eq!(standard.max_records(), FinancialTracker::with_defaults().max_records());
+    }
+
+    #[test]
+    fn test_chargeback() {
+        let mut manager = FinancialManager::with_defaults();
+        manager.record_operation("op-1".to_string(), OperationType::AI, 40, Some("research".to_string()), HashMap::new()).unwrap();
+        manager.record_operation("op-2".to_string(), OperationType::Communication, 5, None, HashMap::new()).unwrap();
+        
+        let chargeback = manager.chargeback("op-1", "wrong operation type").unwrap();
+        assert_eq!(chargeback.original_id, "op-1");
+        assert_eq!(chargeback.amount, 40);
+        assert_eq!(chargeback.reason, "wrong operation type");
+        
+        let summary = manager.get_summary(SpendingPeriod::Total).unwrap();
+        assert_eq!(summary.total_spent, 45);
+        assert_eq!(summary.chargebacks, 40);
+        assert_eq!(summary.net_spent, 5);
+        assert_eq!(summary.operation_count, 2);
+        assert_eq!(summary.by_operation_type[&OperationType::AI], 0);
+        assert_eq!(summary.by_context["research"], 0);
+        assert_eq!(manager.tracker.get_spending_for_period(SpendingPeriod::Daily).unwrap(), 5);
+        
+        let again = manager.chargeback("op-1", "duplicate");
+        assert!(matches!(again, Err(WeaveMeshError::Generic(message)) if message == "already charged back"));
+        assert!(manager.chargeback("missing", "typo").is_err());
+        assert!(manager.chargeback(&chargeback.chargeback_id, "nested").is_err());
+    }
+
     #[test]
     fn test_cost_estimation() {
         let estimator = SimpleCostEstimator::new();
@@ -505,4 +1119,138 @@ mod tests {
         assert_eq!(summary.total_spent, 1);
         assert_eq!(summary.operation_count, 1);
     }
+
+    #[test]
+    fn test_metered_costs_in_summary() {
+        let clock = ManualClock::new(Utc::now() - chrono::Duration::hours(2));
+        let aggregator = Arc::new(MeteringAggregator::with_clock(
+            MeteringConfig { egress_per_gb: 9, ..MeteringConfig::default() },
+            Arc::new(clock.clone()),
+        ));
+        let mut manager = FinancialManager::with_defaults();
+        manager.attach_metering(Arc::clone(&aggregator));
+        
+        aggregator.record_usage(UsageSample {
+            kind: UsageKind::NetworkEgress,
+            bytes: 2_000_000_000,
+            context: Some("sync".to_string()),
+            attribution: Some("alice".to_string()),
+        });
+        assert_eq!(manager.collect_metering().unwrap(), 0);
+        
+        clock.advance(chrono::Duration::hours(1));
+        assert_eq!(manager.collect_metering().unwrap(), 1);
+        
+        let summary = manager.get_summary(SpendingPeriod::Daily).unwrap();
+        assert_eq!(summary.by_operation_type.get(&OperationType::Network), Some(&18));
+        assert_eq!(summary.by_context.get("sync"), Some(&18));
+    }
+
+    fn spend(tracker: &mut FinancialTracker, clock: &ManualClock, cost: u64, hours: i64) {
+        for _ in 0..hours {
+            tracker.record_cost(CostRecord {
+                operation_id: uuid::Uuid::new_v4().to_string(),
+                timestamp: clock.now(),
+                cost,
+                currency: "USD".to_string(),
+                operation_type: OperationType::AI,
+                context: None,
+                metadata: HashMap::new(),
+            }).unwrap();
+            clock.advance(chrono::Duration::hours(1));
+        }
+    }
+
+    #[test]
+    fn test_forecast_projection_and_breach_alerts() {
+        use chrono::TimeZone;
+        use std::sync::Mutex;
+
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+        let limits = SpendingLimits {
+            daily_limit: None,
+            weekly_limit: None,
+            monthly_limit: Some(20000),
+            ..SpendingLimits::default()
+        };
+        let mut tracker = FinancialTracker::new(limits).with_clock(Arc::new(clock.clone()));
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&alerts);
+        tracker.set_forecast_alert_handler(Arc::new(move |alert: &ForecastAlert| sink.lock().unwrap().push(alert.clone())));
+
+        // Steady 10 per hour for ten days: 2400 spent, 21 days left at 240 per day
+        spend(&mut tracker, &clock, 10, 240);
+        let forecast = tracker.get_forecast(SpendingPeriod::Monthly).unwrap();
+        assert_eq!(forecast.spent_to_date, 2400);
+        assert!((forecast.run_rate_per_day - 240.0).abs() < 1e-9);
+        assert!((forecast.projected_total - 7440.0).abs() < 1e-9);
+        assert_eq!((forecast.projected_low, forecast.projected_high), (forecast.projected_total, forecast.projected_total));
+        assert_eq!(forecast.breach_probability, 0.0);
+        assert!((forecast.days_until_breach.unwrap() - 17600.0 / 240.0).abs() < 1e-9);
+        assert!(alerts.lock().unwrap().is_empty());
+        assert!(tracker.get_forecast(SpendingPeriod::Total).is_none());
+
+        // A spike pushes the projection over the limit, alerting once
+        spend(&mut tracker, &clock, 50, 72);
+        let forecast = tracker.get_forecast(SpendingPeriod::Monthly).unwrap();
+        assert_eq!(forecast.spent_to_date, 6000);
+        assert!((forecast.projected_total - 27600.0).abs() < 1e-9);
+        assert!(forecast.breach_probability > 0.99);
+        assert_eq!(alerts.lock().unwrap().len(), 1);
+        assert!(tracker.check_forecasts().is_empty());
+
+        // A quiet start to April resets the period; a new spike alerts again
+        clock.set(Utc.with_ymd_and_hms(2026, 4, 4, 0, 0, 0).unwrap());
+        assert!(tracker.check_forecasts().is_empty());
+        let forecast = tracker.get_forecast(SpendingPeriod::Monthly).unwrap();
+        assert_eq!(forecast.spent_to_date, 0);
+        assert_eq!(forecast.projected_total, 0.0);
+        spend(&mut tracker, &clock, 300, 24);
+        let alerts = alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[1].period_start, Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(alerts[1].limit, 20000);
+    }
+
+    #[test]
+    fn test_snapshot_and_replay() {
+        use chrono::TimeZone;
+
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2026, 1, day, hour, 0, 0).unwrap();
+        let record = |id: &str, timestamp: DateTime<Utc>, cost: u64| CostRecord {
+            operation_id: id.to_string(),
+            timestamp,
+            cost,
+            currency: "USD".to_string(),
+            operation_type: OperationType::Computation,
+            context: None,
+            metadata: HashMap::new(),
+        };
+        let mut tracker = FinancialTracker::with_defaults();
+        tracker.record_cost(record("a", at(14, 10), 100)).unwrap();
+        tracker.record_cost(record("c", at(15, 16), 300)).unwrap();
+        // Arrives late but lands in timestamp order
+        tracker.record_cost(record("b", at(15, 9), 200)).unwrap();
+        let order: Vec<_> = tracker.get_recent_costs(3).iter().rev().map(|r| r.operation_id.clone()).collect();
+        assert_eq!(order, vec!["a", "b", "c"]);
+
+        let snapshot = tracker.snapshot_at(at(15, 15));
+        assert_eq!(snapshot.total_spent_at_time, 300);
+        assert_eq!(snapshot.records_count, 2);
+        assert!((snapshot.daily_utilization_pct - 20.0).abs() < 1e-9); // 200 of 1000
+        assert_eq!(snapshot.snapshot_time, at(15, 15));
+
+        let mut replay = tracker.replay_to(at(15, 15));
+        assert!(replay.is_read_only());
+        assert_eq!(replay.record_count(), 2);
+        assert_eq!(replay.get_spending_for_period(SpendingPeriod::Daily).unwrap(), 200);
+        assert!(replay.record_cost(record("d", at(15, 14), 1)).is_err());
+        assert!(replay.chargeback("a", "what-if").is_err());
+        assert_eq!(tracker.record_count(), 3);
+
+        tracker.clear_records();
+        let empty = tracker.snapshot_at(at(15, 15));
+        assert_eq!((empty.total_spent_at_time, empty.records_count, empty.daily_utilization_pct), (0, 0, 0.0));
+        assert_eq!(tracker.replay_to(at(15, 15)).record_count(), 0);
+    }
 }
@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn, error};
 
-use crate::attribution::{Attribution, AttributionContext, CollaborationType};
+use crate::attribution::{Attribution, AttributionContext, BasicAttributionEngine, CollaborationType};
 use super::{GitOperationType, GitManagerConfig};
 
 /// Git attribution engine for tracking contributions in git operations
@@ -69,6 +69,9 @@ pub struct GitAttributionContext {
     pub lines_changed: Option<GitLinesChanged>,
     /// Git metadata
     pub git_metadata: HashMap<String, String>,
+    /// Git manager operation this context belongs to
+    #[serde(default)]
+    pub operation_id: Option<String>,
 }
 
 /// Lines changed in git operation
@@ -160,6 +163,9 @@ pub struct GitAttributionRecord {
     pub confidence: f64,
     /// Record metadata
     pub metadata: HashMap<String, String>,
+    /// Git manager operation the record belongs to
+    #[serde(default)]
+    pub operation_id: Option<String>,
 }
 
 impl GitAttributionEngine {
@@ -184,9 +190,13 @@ impl GitAttributionEngine {
         
         // Check cache first
         let cache_key = self.generate_cache_key(context);
-        if let Some(cached_analysis) = self.attribution_cache.get(&cache_key) {
+        if let Some(cached_analysis) = self.attribution_cache.get(&cache_key).cloned() {
             debug!("Using cached attribution analysis");
-            return Ok(cached_analysis.clone());
+            if context.operation_id.is_some() {
+                // Each operation still gets its own record
+                self.record_attribution_analysis(context, &cached_analysis).await?;
+            }
+            return Ok(cached_analysis);
         }
         
         // Perform attribution analysis
@@ -415,6 +425,8 @@ impl GitAttributionEngine {
         context.repository_path.hash(&mut hasher);
         context.branch_name.hash(&mut hasher);
         context.affected_files.hash(&mut hasher);
+        context.base_context.metadata.get("human_contributor").hash(&mut hasher);
+        context.base_context.metadata.get("ai_contributor").hash(&mut hasher);
         
         format!("git_attr_{:x}", hasher.finish())
     }
@@ -430,6 +442,7 @@ impl GitAttributionEngine {
             parameters: context.git_metadata.clone(),
             confidence: analysis.confidence,
             metadata: HashMap::new(),
+            operation_id: context.operation_id.clone(),
         };
         
         self.operation_history.push(record);
@@ -450,6 +463,14 @@ impl GitAttributionEngine {
             .collect()
     }
     
+    /// Most recent attribution record of a git manager operation
+    pub fn record_for_operation(&self, operation_id: &str) -> Option<&GitAttributionRecord> {
+        self.operation_history
+            .iter()
+            .rev()
+            .find(|record| record.operation_id.as_deref() == Some(operation_id))
+    }
+    
     /// Get attribution statistics
     pub fn get_attribution_statistics(&self) -> GitAttributionStatistics {
         let total_records = self.operation_history.len();
@@ -528,10 +549,481 @@ impl GitAttributionContext {
                 .unwrap_or_default(),
             lines_changed: None, // Would be populated by git analysis
             git_metadata: parameters.clone(),
+            operation_id: None,
+        }
+    }
+}
+
+/// Commit trailer carrying an explicit WeaveMesh attribution
+///
+/// Format: `WeaveMesh-Attribution: human=<id>; ai=<id>; type=<CollaborationType>; confidence=<0-1>`,
+/// every field optional.
+pub const WEAVEMESH_TRAILER: &str = "WeaveMesh-Attribution";
+
+/// Trailer naming additional authors of a commit
+pub const CO_AUTHOR_TRAILER: &str = "Co-authored-by";
+
+/// Options for importing attributions from git history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryImportOptions {
+    /// Only commits at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only commits before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Stop after scanning this many commits in range
+    pub max_commits: Option<usize>,
+    /// Case-insensitive identity fragments marking AI contributors
+    pub ai_patterns: Vec<String>,
+    /// Case-insensitive identity fragments marking automation bots
+    pub bot_patterns: Vec<String>,
+    /// Report progress every this many scanned commits
+    pub progress_interval: usize,
+}
+
+impl Default for HistoryImportOptions {
+    fn default() -> Self {
+        Self {
+            since: None,
+            until: None,
+            max_commits: None,
+            ai_patterns: ["claude", "copilot", "chatgpt", "openai", "anthropic", "codex", "aider", "devin"]
+                .iter().map(|p| p.to_string()).collect(),
+            bot_patterns: ["[bot]", "dependabot", "renovate", "github-actions"]
+                .iter().map(|p| p.to_string()).collect(),
+            progress_interval: 1000,
+        }
+    }
+}
+
+/// Progress of a running history import
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    /// Commits scanned so far
+    pub commits_scanned: usize,
+    /// Attributions imported so far
+    pub imported: usize,
+}
+
+/// Outcome of a history import
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Commits in range that were looked at
+    pub commits_scanned: usize,
+    /// Attributions added
+    pub imported: usize,
+    /// Commits whose attribution was imported by an earlier run
+    pub already_imported: usize,
+    /// Merge commits, skipped because their changes belong to the merged commits
+    pub skipped_merges: usize,
+    /// Commits that changed no files
+    pub skipped_empty: usize,
+    /// Imported attributions per collaboration type
+    pub by_collaboration_type: HashMap<String, usize>,
+}
+
+/// Commits and lines attributed to one human contributor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HumanContributionSummary {
+    /// Contributor identifier
+    pub contributor: String,
+    /// Attributed commits
+    pub commits: usize,
+    /// Lines added and deleted in those commits
+    pub lines_changed: usize,
+    /// Commits an AI contributed to as well
+    pub ai_assisted_commits: usize,
+}
+
+/// Commits and lines attributed to one AI contributor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AIContributionSummary {
+    /// Contributor identifier
+    pub contributor: String,
+    /// Attributed commits
+    pub commits: usize,
+    /// Lines added and deleted in those commits
+    pub lines_changed: usize,
+    /// Humans it worked with, sorted
+    pub human_partners: Vec<String>,
+}
+
+/// A commit a human and an AI both contributed to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollaborationEvent {
+    /// Commit hash
+    pub commit_hash: String,
+    /// Git manager operation that made the commit
+    pub operation_id: String,
+    /// Commit time
+    pub timestamp: DateTime<Utc>,
+    /// Human contributor
+    pub human_contributor: String,
+    /// AI contributor
+    pub ai_contributor: String,
+    /// How they collaborated
+    pub collaboration_type: CollaborationType,
+    /// Lines added and deleted
+    pub lines_changed: usize,
+}
+
+/// Contributions to a repository joined from git history and attribution records
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttributionReport {
+    /// Per-human totals, most lines first
+    pub human_contributions: Vec<HumanContributionSummary>,
+    /// Per-AI totals, most lines first
+    pub ai_contributions: Vec<AIContributionSummary>,
+    /// Human-AI commits, oldest first
+    pub collaboration_events: Vec<CollaborationEvent>,
+    /// Lines added and deleted across all commits in range
+    pub total_lines_changed: usize,
+    /// Commits in range
+    pub commits_analyzed: usize,
+    /// Share of commits in range with an attribution record, in percent
+    pub attribution_coverage_pct: f64,
+}
+
+/// A commit in a report's range and its attribution, if recorded
+#[derive(Debug, Clone)]
+pub(crate) struct ReportCommit {
+    pub commit_hash: String,
+    pub timestamp: DateTime<Utc>,
+    pub lines_changed: usize,
+    pub attribution: Option<(String, Attribution)>,
+}
+
+impl AttributionReport {
+    /// Build a report from commits and the attributions joined to them
+    pub(crate) fn from_commits(commits: &[ReportCommit]) -> Self {
+        let mut humans: HashMap<String, HumanContributionSummary> = HashMap::new();
+        let mut ais: HashMap<String, AIContributionSummary> = HashMap::new();
+        let mut report = AttributionReport {
+            commits_analyzed: commits.len(),
+            total_lines_changed: commits.iter().map(|c| c.lines_changed).sum(),
+            ..Default::default()
+        };
+        
+        for commit in commits {
+            let Some((operation_id, attribution)) = &commit.attribution else {
+                continue;
+            };
+            let human = attribution.human_contributor.as_ref();
+            let ai = attribution.ai_contributor.as_ref();
+            if let Some(human) = human {
+                let summary = humans.entry(human.clone()).or_insert_with(|| HumanContributionSummary {
+                    contributor: human.clone(),
+                    commits: 0,
+                    lines_changed: 0,
+                    ai_assisted_commits: 0,
+                });
+                summary.commits += 1;
+                summary.lines_changed += commit.lines_changed;
+                summary.ai_assisted_commits += usize::from(ai.is_some());
+            }
+            if let Some(ai) = ai {
+                let summary = ais.entry(ai.clone()).or_insert_with(|| AIContributionSummary {
+                    contributor: ai.clone(),
+                    commits: 0,
+                    lines_changed: 0,
+                    human_partners: Vec::new(),
+                });
+                summary.commits += 1;
+                summary.lines_changed += commit.lines_changed;
+                if let Some(human) = human {
+                    if !summary.human_partners.contains(human) {
+                        summary.human_partners.push(human.clone());
+                        summary.human_partners.sort();
+                    }
+                }
+            }
+            if let (Some(human), Some(ai)) = (human, ai) {
+                report.collaboration_events.push(CollaborationEvent {
+                    commit_hash: commit.commit_hash.clone(),
+                    operation_id: operation_id.clone(),
+                    timestamp: commit.timestamp,
+                    human_contributor: human.clone(),
+                    ai_contributor: ai.clone(),
+                    collaboration_type: attribution.collaboration_type.clone(),
+                    lines_changed: commit.lines_changed,
+                });
+            }
+        }
+        
+        let attributed = commits.iter().filter(|c| c.attribution.is_some()).count();
+        if !commits.is_empty() {
+            report.attribution_coverage_pct = attributed as f64 * 100.0 / commits.len() as f64;
+        }
+        report.human_contributions = humans.into_values().collect();
+        report.human_contributions.sort_by(|a, b| {
+            b.lines_changed.cmp(&a.lines_changed).then_with(|| a.contributor.cmp(&b.contributor))
+        });
+        report.ai_contributions = ais.into_values().collect();
+        report.ai_contributions.sort_by(|a, b| {
+            b.lines_changed.cmp(&a.lines_changed).then_with(|| a.contributor.cmp(&b.contributor))
+        });
+        report.collaboration_events.sort_by_key(|event| event.timestamp);
+        report
+    }
+    
+    /// Markdown summary for a pull request description
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("## Attribution\n\n");
+        out.push_str(&format!(
+            "{} commits, {} lines changed, {:.1}% attributed\n",
+            self.commits_analyzed, self.total_lines_changed, self.attribution_coverage_pct,
+        ));
+        
+        if !self.human_contributions.is_empty() {
+            out.push_str("\n### Human contributions\n\n| Contributor | Commits | Lines | AI-assisted |\n|---|---:|---:|---:|\n");
+            for human in &self.human_contributions {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    human.contributor, human.commits, human.lines_changed, human.ai_assisted_commits,
+                ));
+            }
+        }
+        if !self.ai_contributions.is_empty() {
+            out.push_str("\n### AI contributions\n\n| Contributor | Commits | Lines | Worked with |\n|---|---:|---:|---|\n");
+            for ai in &self.ai_contributions {
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    ai.contributor, ai.commits, ai.lines_changed, ai.human_partners.join(", "),
+                ));
+            }
+        }
+        if !self.collaboration_events.is_empty() {
+            out.push_str("\n### Collaboration\n\n");
+            for event in &self.collaboration_events {
+                out.push_str(&format!(
+                    "- `{}` {} with {} ({:?}, {} lines)\n",
+                    &event.commit_hash[..event.commit_hash.len().min(7)],
+                    event.human_contributor, event.ai_contributor, event.collaboration_type, event.lines_changed,
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Kind of identity found on a commit
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IdentityKind {
+    Human,
+    Ai,
+    Bot,
+}
+
+impl HistoryImportOptions {
+    fn classify_identity(&self, identity: &str) -> IdentityKind {
+        let identity = identity.to_lowercase();
+        let matches = |patterns: &[String]| patterns.iter().any(|p| identity.contains(&p.to_lowercase()));
+        if matches(&self.bot_patterns) {
+            IdentityKind::Bot
+        } else if matches(&self.ai_patterns) {
+            IdentityKind::Ai
+        } else {
+            IdentityKind::Human
         }
     }
 }
 
+impl GitAttributionEngine {
+    /// Seed `target` with attributions derived from a repository's commit history
+    ///
+    /// Walks back from HEAD and derives one attribution per commit from the
+    /// author and committer, `Co-authored-by` trailers and the
+    /// [`WEAVEMESH_TRAILER`]. Merge and empty commits are skipped. Imports are
+    /// keyed by commit hash, so re-running only adds new commits.
+    pub fn import_history(
+        &self,
+        repo_path: &Path,
+        options: &HistoryImportOptions,
+        target: &mut BasicAttributionEngine,
+    ) -> Result<ImportReport> {
+        self.import_history_with_progress(repo_path, options, target, |_| {})
+    }
+    
+    /// [`import_history`](Self::import_history), calling `on_progress` every
+    /// `progress_interval` scanned commits
+    pub fn import_history_with_progress(
+        &self,
+        repo_path: &Path,
+        options: &HistoryImportOptions,
+        target: &mut BasicAttributionEngine,
+        mut on_progress: impl FnMut(&ImportProgress),
+    ) -> Result<ImportReport> {
+        let repo = git2::Repository::open(repo_path)?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+        revwalk.push_head()?;
+        
+        let mut report = ImportReport::default();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let committed_at = match DateTime::from_timestamp(commit.time().seconds(), 0) {
+                Some(time) => time,
+                None => continue,
+            };
+            if options.until.is_some_and(|until| committed_at >= until) {
+                continue;
+            }
+            if options.since.is_some_and(|since| committed_at < since) {
+                // Time-sorted, but topological order can interleave older commits
+                continue;
+            }
+            if options.max_commits.is_some_and(|max| report.commits_scanned >= max) {
+                break;
+            }
+            
+            report.commits_scanned += 1;
+            if options.progress_interval > 0 && report.commits_scanned % options.progress_interval == 0 {
+                info!("Imported {} attributions from {} commits", report.imported, report.commits_scanned);
+                on_progress(&ImportProgress {
+                    commits_scanned: report.commits_scanned,
+                    imported: report.imported,
+                });
+            }
+            
+            let commit_hash = commit.id().to_string();
+            if target.is_imported(&commit_hash) {
+                report.already_imported += 1;
+                continue;
+            }
+            if commit.parent_count() > 1 {
+                report.skipped_merges += 1;
+                continue;
+            }
+            let parent_tree = match commit.parent_count() {
+                0 => None,
+                _ => Some(commit.parent(0)?.tree()?),
+            };
+            let tree = commit.tree()?;
+            let unchanged = match &parent_tree {
+                Some(parent_tree) => parent_tree.id() == tree.id(),
+                None => tree.is_empty(),
+            };
+            if unchanged {
+                report.skipped_empty += 1;
+                continue;
+            }
+            
+            let mut attribution = Self::commit_attribution(&commit, options);
+            attribution.timestamp = committed_at;
+            attribution.add_metadata("commit_hash".to_string(), commit_hash.clone());
+            let type_key = format!("{:?}", attribution.collaboration_type);
+            if target.import(commit_hash, attribution) {
+                report.imported += 1;
+                *report.by_collaboration_type.entry(type_key).or_insert(0) += 1;
+            }
+        }
+        
+        info!(
+            "History import from {:?} done: {} imported, {} already imported, {} merges and {} empty commits skipped",
+            repo_path, report.imported, report.already_imported, report.skipped_merges, report.skipped_empty
+        );
+        Ok(report)
+    }
+    
+    /// Derive an attribution from a commit's identities and trailers
+    fn commit_attribution(commit: &git2::Commit, options: &HistoryImportOptions) -> Attribution {
+        let identity = |signature: git2::Signature| {
+            format!("{} <{}>", signature.name().unwrap_or_default(), signature.email().unwrap_or_default())
+        };
+        let author = identity(commit.author());
+        let committer = identity(commit.committer());
+        
+        let mut co_authors = Vec::new();
+        let mut explicit: Option<String> = None;
+        let message = commit.message().unwrap_or_default();
+        if let Ok(trailers) = git2::message_trailers_strs(message) {
+            for (key, value) in trailers.iter() {
+                if key.eq_ignore_ascii_case(CO_AUTHOR_TRAILER) {
+                    co_authors.push(value.trim().to_string());
+                } else if key.eq_ignore_ascii_case(WEAVEMESH_TRAILER) {
+                    explicit = Some(value.to_string());
+                }
+            }
+        }
+        
+        let mut attribution = match explicit.as_deref().and_then(parse_weavemesh_trailer) {
+            Some(attribution) => attribution,
+            None => {
+                let mut humans = Vec::new();
+                let mut machines = Vec::new();
+                let mut seen = std::collections::HashSet::new();
+                for id in std::iter::once(&author).chain(co_authors.iter()).chain(std::iter::once(&committer)) {
+                    if !seen.insert(id.to_lowercase()) {
+                        continue;
+                    }
+                    match options.classify_identity(id) {
+                        IdentityKind::Human => humans.push(id.clone()),
+                        IdentityKind::Ai | IdentityKind::Bot => machines.push(id.clone()),
+                    }
+                }
+                let author_is_human = options.classify_identity(&author) == IdentityKind::Human;
+                let collaboration_type = match (humans.len(), machines.len()) {
+                    (0, _) => CollaborationType::Automated,
+                    (_, 0) if humans.len() > 1 => CollaborationType::Coordination,
+                    (_, 0) => CollaborationType::Individual,
+                    _ if author_is_human => CollaborationType::HumanLed,
+                    _ => CollaborationType::AILed,
+                };
+                Attribution::new(
+                    humans.into_iter().next(),
+                    machines.into_iter().next(),
+                    collaboration_type,
+                    0.8,
+                )
+            }
+        };
+        
+        attribution.add_metadata("source".to_string(), "git_history".to_string());
+        attribution.add_metadata("author".to_string(), author);
+        attribution.add_metadata("committer".to_string(), committer);
+        if !co_authors.is_empty() {
+            attribution.add_metadata("co_authors".to_string(), co_authors.join(", "));
+        }
+        attribution
+    }
+}
+
+/// Parse the value of a [`WEAVEMESH_TRAILER`]
+fn parse_weavemesh_trailer(value: &str) -> Option<Attribution> {
+    let mut human = None;
+    let mut ai = None;
+    let mut collaboration_type = None;
+    let mut confidence = 1.0;
+    for field in value.split(';') {
+        let (key, value) = match field.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim().to_string()),
+            None => continue,
+        };
+        match key {
+            "human" => human = Some(value),
+            "ai" => ai = Some(value),
+            "type" => {
+                collaboration_type = CollaborationType::BUILT_IN
+                    .iter()
+                    .find(|t| format!("{:?}", t).eq_ignore_ascii_case(&value))
+                    .cloned()
+                    .or(Some(CollaborationType::Custom(value)));
+            }
+            "confidence" => confidence = value.parse().unwrap_or(confidence),
+            _ => {}
+        }
+    }
+    
+    if human.is_none() && ai.is_none() {
+        return None;
+    }
+    let collaboration_type = collaboration_type.unwrap_or(match (&human, &ai) {
+        (Some(_), Some(_)) => CollaborationType::CoCreated,
+        (Some(_), None) => CollaborationType::Individual,
+        _ => CollaborationType::Automated,
+    });
+    Some(Attribution::new(human, ai, collaboration_type, confidence))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -575,4 +1067,78 @@ mod tests {
         assert_eq!(context.branch_name, "feature/test");
         assert_eq!(context.base_context.source, "test_source");
     }
+    
+    /// Commit `files` onto `refs/heads/main` as `author`
+    fn commit_files(
+        repo: &git2::Repository,
+        parents: &[git2::Oid],
+        files: &[(&str, &str)],
+        author: (&str, &str),
+        message: &str,
+    ) -> git2::Oid {
+        let mut builder = repo.treebuilder(None).unwrap();
+        for (path, content) in files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            builder.insert(path, blob, 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let signature = git2::Signature::now(author.0, author.1).unwrap();
+        let parents: Vec<git2::Commit> = parents.iter().map(|p| repo.find_commit(*p).unwrap()).collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("refs/heads/main"), &signature, &signature, message, &tree, &parent_refs).unwrap()
+    }
+    
+    #[test]
+    fn test_import_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        let alice = ("Alice", "alice@example.com");
+        
+        let c1 = commit_files(&repo, &[], &[("a.txt", "1")], alice, "Initial commit");
+        let c2 = commit_files(
+            &repo, &[c1], &[("a.txt", "2")], alice,
+            "Refactor\n\nCo-authored-by: Claude <noreply@anthropic.com>\n",
+        );
+        let c3 = commit_files(
+            &repo, &[c2], &[("a.txt", "2"), ("deps.lock", "x")],
+            ("dependabot[bot]", "support@github.com"), "Bump deps",
+        );
+        let c4 = commit_files(&repo, &[c3], &[("a.txt", "2"), ("deps.lock", "x")], alice, "Empty");
+        let c5 = commit_files(
+            &repo, &[c4], &[("a.txt", "3"), ("deps.lock", "x")], alice,
+            "Pair\n\nWeaveMesh-Attribution: human=alice; ai=assistant; type=PairProgramming; confidence=0.9\n",
+        );
+        commit_files(&repo, &[c5, c1], &[("a.txt", "3"), ("deps.lock", "x")], alice, "Merge");
+        
+        let engine = GitAttributionEngine::new(&GitManagerConfig::default()).unwrap();
+        let mut target = BasicAttributionEngine::new(crate::attribution::AttributionConfig::default());
+        let options = HistoryImportOptions { progress_interval: 2, ..HistoryImportOptions::default() };
+        let mut progress = Vec::new();
+        let report = engine
+            .import_history_with_progress(dir.path(), &options, &mut target, |p| progress.push(p.clone()))
+            .unwrap();
+        
+        assert_eq!(report.commits_scanned, 6);
+        assert_eq!(report.imported, 4);
+        assert_eq!(report.skipped_merges, 1);
+        assert_eq!(report.skipped_empty, 1);
+        assert_eq!(progress.len(), 3);
+        assert_eq!(report.by_collaboration_type["Individual"], 1);
+        assert_eq!(report.by_collaboration_type["HumanLed"], 1);
+        assert_eq!(report.by_collaboration_type["Automated"], 1);
+        assert_eq!(report.by_collaboration_type["PairProgramming"], 1);
+        
+        let assisted = target.get_history().iter()
+            .find(|a| a.get_metadata("commit_hash") == Some(&c2.to_string()))
+            .unwrap();
+        assert_eq!(assisted.ai_contributor.as_deref(), Some("Claude <noreply@anthropic.com>"));
+        assert_eq!(assisted.human_contributor.as_deref(), Some("Alice <alice@example.com>"));
+        
+        // Re-running adds nothing
+        let rerun = engine.import_history(dir.path(), &options, &mut target).unwrap();
+        assert_eq!(rerun.imported, 0);
+        assert_eq!(rerun.already_imported, 4);
+        assert_eq!(target.get_history().len(), 4);
+    }
 }
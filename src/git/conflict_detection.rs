@@ -4,15 +4,23 @@
 //! enabling proactive conflict resolution and collaborative decision-making.
 
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
 use git2::{Repository, StatusOptions, DiffOptions};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use super::{GitManagerConfig, GitOperationType};
+use crate::compute::{ComputeCategory, ComputePool};
+use crate::sacred_alliance::BasicCeremonyAction;
+use crate::storage::{AccessControl as StorageAccessControl, ResourceFilter, Storage};
+use crate::resource_profile::{
+    ComponentFootprint, EvictionCounters, EvictionKind, MemoryFootprint, ResourceProfile,
+};
 
 /// Git conflict detector for identifying and analyzing conflicts
 pub struct GitConflictDetector {
@@ -24,6 +32,10 @@ pub struct GitConflictDetector {
     resolution_history: Vec<ConflictResolutionRecord>,
     /// Conflict patterns
     conflict_patterns: HashMap<String, ConflictPattern>,
+    /// Merge previews keyed by (source oid, target oid)
+    merge_preview_cache: HashMap<(String, String), MergePreview>,
+    /// Counts of cache entries dropped by the cache size limit
+    eviction_counters: Arc<EvictionCounters>,
 }
 
 /// Configuration for conflict detection
@@ -43,6 +55,27 @@ pub struct ConflictDetectionConfig {
     pub enable_prediction: bool,
     /// Minimum confidence for conflict prediction
     pub prediction_confidence_threshold: f64,
+    /// Conflict heatmap settings
+    #[serde(default)]
+    pub heatmap: ConflictHeatmapConfig,
+}
+
+/// Configuration for the conflict heatmap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictHeatmapConfig {
+    /// Files with more conflicts than this are hot
+    pub hot_file_threshold: usize,
+    /// How far back `GitManager::report_hot_files` looks, in days
+    pub window_days: i64,
+}
+
+impl Default for ConflictHeatmapConfig {
+    fn default() -> Self {
+        Self {
+            hot_file_threshold: 5,
+            window_days: 30,
+        }
+    }
 }
 
 impl Default for ConflictDetectionConfig {
@@ -55,6 +88,17 @@ impl Default for ConflictDetectionConfig {
             analysis_timeout_seconds: 60,
             enable_prediction: true,
             prediction_confidence_threshold: 0.6,
+            heatmap: ConflictHeatmapConfig::default(),
+        }
+    }
+}
+
+impl ConflictDetectionConfig {
+    /// Default configuration with the cache bound of a resource profile
+    pub fn for_profile(profile: ResourceProfile) -> Self {
+        Self {
+            cache_size: profile.limits().conflict_cache_size,
+            ..Self::default()
         }
     }
 }
@@ -128,6 +172,19 @@ pub enum ConflictSeverity {
     Blocking,
 }
 
+impl ConflictSeverity {
+    /// Numeric weight, from 1.0 for minor to 5.0 for blocking
+    pub fn score(&self) -> f64 {
+        match self {
+            ConflictSeverity::Minor => 1.0,
+            ConflictSeverity::Moderate => 2.0,
+            ConflictSeverity::Major => 3.0,
+            ConflictSeverity::Critical => 4.0,
+            ConflictSeverity::Blocking => 5.0,
+        }
+    }
+}
+
 /// Conflict location in file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictLocation {
@@ -302,6 +359,38 @@ pub enum ConflictResolutionStatus {
     Escalated,
 }
 
+/// Result of merging two refs in memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergePreview {
+    /// Commit being merged in
+    pub source_oid: String,
+    /// Commit being merged into
+    pub target_oid: String,
+    /// Whether the merge would complete without conflicts
+    pub is_clean: bool,
+    /// Conflicts the merge would produce
+    pub conflicts: Vec<GitConflict>,
+    /// Files the source brings in since the merge base
+    pub changed_files: Vec<FileChangeStats>,
+    /// Total lines added
+    pub insertions: usize,
+    /// Total lines removed
+    pub deletions: usize,
+    /// Whether performing the merge would trigger a ceremony
+    pub ceremony_required: bool,
+}
+
+/// Line statistics for one changed file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileChangeStats {
+    /// File path
+    pub path: String,
+    /// Lines added
+    pub insertions: usize,
+    /// Lines removed
+    pub deletions: usize,
+}
+
 /// Conflict pattern for learning
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictPattern {
@@ -321,6 +410,73 @@ pub struct ConflictPattern {
     pub success_rate: f64,
     /// Pattern confidence
     pub confidence: f64,
+    /// Successful resolutions by type, most frequent first
+    #[serde(default)]
+    pub successful_resolutions: Vec<(ResolutionType, usize)>,
+}
+
+impl ConflictPattern {
+    /// Key of the pattern a conflict belongs to
+    pub fn key_for(conflict: &GitConflict) -> String {
+        format!("{:?}:{}", conflict.conflict_type, file_pattern_for(&conflict.file_path))
+    }
+    
+    fn new(conflict: &GitConflict) -> Self {
+        let file_pattern = file_pattern_for(&conflict.file_path);
+        Self {
+            pattern_id: Self::key_for(conflict),
+            name: format!("{:?} in {}", conflict.conflict_type, file_pattern),
+            conflict_types: vec![conflict.conflict_type.clone()],
+            file_patterns: vec![file_pattern],
+            typical_resolutions: Vec::new(),
+            frequency: 0,
+            success_rate: 0.0,
+            confidence: 0.0,
+            successful_resolutions: Vec::new(),
+        }
+    }
+    
+    /// Fold in the outcome of one resolution
+    fn learn(&mut self, record: &ConflictResolutionRecord) {
+        let successes = (self.success_rate * self.frequency as f64).round() as usize
+            + usize::from(record.outcome.success);
+        self.frequency += 1;
+        self.success_rate = successes as f64 / self.frequency as f64;
+        // Trust grows with the number of observations
+        self.confidence = self.success_rate * self.frequency as f64 / (self.frequency as f64 + 2.0);
+        
+        if record.outcome.success {
+            let resolution_type = &record.resolution.resolution_type;
+            match self.successful_resolutions.iter_mut().find(|(t, _)| t == resolution_type) {
+                Some((_, count)) => *count += 1,
+                None => self.successful_resolutions.push((resolution_type.clone(), 1)),
+            }
+            self.successful_resolutions.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            self.typical_resolutions = self.successful_resolutions.iter().map(|(t, _)| t.clone()).collect();
+        }
+    }
+    
+    /// Share of successful resolutions that used `resolution_type`
+    fn success_share(&self, resolution_type: &ResolutionType) -> f64 {
+        let total: usize = self.successful_resolutions.iter().map(|(_, count)| count).sum();
+        match self.successful_resolutions.iter().find(|(t, _)| t == resolution_type) {
+            Some((_, count)) if total > 0 => *count as f64 / total as f64,
+            _ => 0.0,
+        }
+    }
+}
+
+/// File glob for a path: its directory and extension, e.g. `src/git/*.rs`
+fn file_pattern_for(file_path: &str) -> String {
+    let path = Path::new(file_path);
+    let file = match path.extension() {
+        Some(extension) => format!("*.{}", extension.to_string_lossy()),
+        None => "*".to_string(),
+    };
+    match path.parent().map(|dir| dir.to_string_lossy()).filter(|dir| !dir.is_empty()) {
+        Some(dir) => format!("{}/{}", dir, file),
+        None => file,
+    }
 }
 
 /// Conflict resolution record
@@ -371,9 +527,28 @@ impl GitConflictDetector {
             conflicts_cache: HashMap::new(),
             resolution_history: Vec::new(),
             conflict_patterns: HashMap::new(),
+            merge_preview_cache: HashMap::new(),
+            eviction_counters: Arc::new(EvictionCounters::new()),
         })
     }
     
+    /// Replace the detection configuration
+    pub fn with_config(mut self, config: ConflictDetectionConfig) -> Self {
+        self.config = config;
+        self
+    }
+    
+    /// Record cache evictions into shared counters
+    pub fn with_eviction_counters(mut self, counters: Arc<EvictionCounters>) -> Self {
+        self.eviction_counters = counters;
+        self
+    }
+    
+    /// Counters of cache entries dropped by the cache size limit
+    pub fn eviction_counters(&self) -> &Arc<EvictionCounters> {
+        &self.eviction_counters
+    }
+    
     /// Detect conflicts in repository
     pub async fn detect_conflicts(&mut self, repository_path: &Path) -> Result<Vec<GitConflict>> {
         debug!("Detecting conflicts in repository: {:?}", repository_path);
@@ -403,6 +578,7 @@ impl GitConflictDetector {
         
         // Analyze and enhance conflicts
         for conflict in &mut conflicts {
+            conflict.metadata.insert(REPOSITORY_METADATA_KEY.to_string(), cache_key.clone());
             self.analyze_conflict(conflict, &repo).await?;
             self.generate_resolutions(conflict).await?;
         }
@@ -412,6 +588,7 @@ impl GitConflictDetector {
             // Remove oldest entry (simplified LRU)
             if let Some(first_key) = self.conflicts_cache.keys().next().cloned() {
                 self.conflicts_cache.remove(&first_key);
+                self.eviction_counters.record(EvictionKind::ConflictCache, 1);
             }
         }
         self.conflicts_cache.insert(cache_key, conflicts.clone());
@@ -420,6 +597,147 @@ impl GitConflictDetector {
         Ok(conflicts)
     }
     
+    /// Preview merging `source_ref` into `target_ref` without touching the repository
+    ///
+    /// The merge happens entirely in memory, so the working tree, index and
+    /// refs are left as they were; bare repositories are supported. Results
+    /// are cached by the pair of resolved commit IDs.
+    pub fn preview_merge(&mut self, repository_path: &Path, source_ref: &str, target_ref: &str) -> Result<MergePreview> {
+        let repo = Repository::open(repository_path)?;
+        let source = repo.revparse_single(source_ref)?.peel_to_commit()?;
+        let target = repo.revparse_single(target_ref)?.peel_to_commit()?;
+        
+        let cache_key = (source.id().to_string(), target.id().to_string());
+        if let Some(preview) = self.merge_preview_cache.get(&cache_key) {
+            debug!("Using cached merge preview for {} into {}", source_ref, target_ref);
+            return Ok(preview.clone());
+        }
+        
+        let preview = build_merge_preview(&repo, &source, &target, source_ref, target_ref)?;
+        self.cache_merge_preview(cache_key, preview.clone());
+        
+        info!("Previewed merge of {} into {}: {} conflicts", source_ref, target_ref, preview.conflicts.len());
+        Ok(preview)
+    }
+    
+    /// [`preview_merge`](Self::preview_merge) with the merge itself run on a compute pool
+    ///
+    /// Refs are resolved and the cache consulted on the calling task; only a
+    /// cache miss takes a [`ComputeCategory::ConflictAnalysis`] slot.
+    pub async fn preview_merge_on(
+        &mut self,
+        pool: &ComputePool,
+        cancel: &CancellationToken,
+        repository_path: &Path,
+        source_ref: &str,
+        target_ref: &str,
+    ) -> Result<MergePreview> {
+        let cache_key = {
+            let repo = Repository::open(repository_path)?;
+            let source = repo.revparse_single(source_ref)?.peel_to_commit()?;
+            let target = repo.revparse_single(target_ref)?.peel_to_commit()?;
+            (source.id().to_string(), target.id().to_string())
+        };
+        if let Some(preview) = self.merge_preview_cache.get(&cache_key) {
+            debug!("Using cached merge preview for {} into {}", source_ref, target_ref);
+            return Ok(preview.clone());
+        }
+        
+        let path = repository_path.to_path_buf();
+        let (source_oid, target_oid) = cache_key.clone();
+        let (source_name, target_name) = (source_ref.to_string(), target_ref.to_string());
+        let preview = pool
+            .run(ComputeCategory::ConflictAnalysis, cancel, move || -> Result<MergePreview> {
+                let repo = Repository::open(&path)?;
+                let source = repo.find_commit(git2::Oid::from_str(&source_oid)?)?;
+                let target = repo.find_commit(git2::Oid::from_str(&target_oid)?)?;
+                build_merge_preview(&repo, &source, &target, &source_name, &target_name)
+            })
+            .await??;
+        self.cache_merge_preview(cache_key, preview.clone());
+        
+        info!("Previewed merge of {} into {}: {} conflicts", source_ref, target_ref, preview.conflicts.len());
+        Ok(preview)
+    }
+    
+    /// Cache a merge preview, evicting the oldest entry when full
+    fn cache_merge_preview(&mut self, cache_key: (String, String), preview: MergePreview) {
+        if self.merge_preview_cache.len() >= self.config.cache_size {
+            if let Some(first_key) = self.merge_preview_cache.keys().next().cloned() {
+                self.merge_preview_cache.remove(&first_key);
+                self.eviction_counters.record(EvictionKind::ConflictCache, 1);
+            }
+        }
+        self.merge_preview_cache.insert(cache_key, preview);
+    }
+    
+    /// Build a conflict from an in-memory index conflict entry
+    fn preview_conflict(
+        repo: &Repository,
+        conflict: &git2::IndexConflict,
+        source_ref: &str,
+        target_ref: &str,
+    ) -> Result<GitConflict> {
+        let entry_path = conflict.our.as_ref()
+            .or(conflict.their.as_ref())
+            .or(conflict.ancestor.as_ref())
+            .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+            .unwrap_or_default();
+        
+        let conflict_type = match (&conflict.ancestor, &conflict.our, &conflict.their) {
+            (None, Some(_), Some(_)) => ConflictType::AddAdd,
+            (Some(_), None, Some(_)) | (Some(_), Some(_), None) => ConflictType::DeleteModify,
+            (_, Some(ours), Some(theirs)) if ours.mode != theirs.mode => ConflictType::ModeConflict,
+            _ => ConflictType::ContentConflict,
+        };
+        
+        let mut is_binary = false;
+        let mut read_side = |entry: &Option<git2::IndexEntry>| -> Result<Option<String>> {
+            match entry {
+                Some(entry) => {
+                    let blob = repo.find_blob(entry.id)?;
+                    is_binary |= blob.is_binary();
+                    Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+                }
+                None => Ok(None),
+            }
+        };
+        let ours = read_side(&conflict.our)?.unwrap_or_default();
+        let theirs = read_side(&conflict.their)?.unwrap_or_default();
+        let base = read_side(&conflict.ancestor)?;
+        
+        Ok(GitConflict {
+            conflict_id: Uuid::new_v4().to_string(),
+            conflict_type,
+            severity: ConflictSeverity::Major,
+            file_path: entry_path.clone(),
+            location: ConflictLocation {
+                start_line: 0,
+                end_line: 0,
+                start_column: None,
+                end_column: None,
+                context: None,
+            },
+            description: format!("Merging {} into {} would conflict in {}", source_ref, target_ref, entry_path),
+            conflicting_refs: vec![target_ref.to_string(), source_ref.to_string()],
+            conflict_content: ConflictContent {
+                ours,
+                theirs,
+                base,
+                has_markers: false,
+                content_type: if is_binary {
+                    ContentType::Binary
+                } else {
+                    content_type_for_path(&entry_path)
+                },
+            },
+            suggested_resolutions: Vec::new(),
+            metadata: HashMap::new(),
+            detected_at: Utc::now(),
+            resolution_status: ConflictResolutionStatus::Detected,
+        })
+    }
+    
     /// Detect merge conflicts
     async fn detect_merge_conflicts(&self, repo: &Repository) -> Result<Vec<GitConflict>> {
         let mut conflicts = Vec::new();
@@ -755,9 +1073,165 @@ impl GitConflictDetector {
         }
         
         conflict.suggested_resolutions = resolutions;
+        self.apply_learned_patterns(conflict);
         Ok(())
     }
     
+    /// Boost and reorder suggestions using the pattern the conflict matches
+    ///
+    /// Suggestions whose type has resolved similar conflicts before gain
+    /// confidence in proportion to the pattern's confidence and to how often
+    /// that type succeeded. Suggestions are then ordered by confidence.
+    pub fn apply_learned_patterns(&self, conflict: &mut GitConflict) {
+        let pattern = match self.conflict_patterns.get(&ConflictPattern::key_for(conflict)) {
+            Some(pattern) => pattern,
+            None => return,
+        };
+        for resolution in &mut conflict.suggested_resolutions {
+            let boost = pattern.confidence * pattern.success_share(&resolution.resolution_type);
+            resolution.confidence += (1.0 - resolution.confidence) * boost;
+        }
+        conflict.suggested_resolutions.sort_by(|a, b| {
+            b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+    
+    /// Record how a conflict was resolved and learn from it
+    pub fn record_resolution(&mut self, record: ConflictResolutionRecord) {
+        let key = ConflictPattern::key_for(&record.conflict);
+        self.conflict_patterns.entry(key)
+            .or_insert_with(|| ConflictPattern::new(&record.conflict))
+            .learn(&record);
+        self.resolution_history.push(record);
+    }
+    
+    /// Learned conflict patterns
+    pub fn conflict_patterns(&self) -> Vec<&ConflictPattern> {
+        let mut patterns: Vec<&ConflictPattern> = self.conflict_patterns.values().collect();
+        patterns.sort_by(|a, b| b.frequency.cmp(&a.frequency).then_with(|| a.pattern_id.cmp(&b.pattern_id)));
+        patterns
+    }
+    
+    /// Store the learned patterns, replacing any stored earlier
+    ///
+    /// Returns the storage resource ID.
+    pub async fn save_patterns<S: Storage>(&self, storage: &mut S) -> Result<String> {
+        let previous: Vec<String> = storage.list_resources(Some(patterns_filter()))
+            .into_iter()
+            .map(|metadata| metadata.resource_id)
+            .collect();
+        let patterns: Vec<&ConflictPattern> = self.conflict_patterns();
+        let resource_id = storage.store_resource(
+            "git/conflict-patterns.json".to_string(),
+            serde_json::to_vec(&patterns)?,
+            "application/json".to_string(),
+            StorageAccessControl::default(),
+            vec![CONFLICT_PATTERNS_TAG.to_string()],
+        ).await?;
+        for id in previous {
+            storage.delete_resource(&id).await?;
+        }
+        Ok(resource_id)
+    }
+    
+    /// Replace the learned patterns with the most recently stored ones
+    ///
+    /// Returns the number of patterns loaded; none are loaded if nothing was stored.
+    pub async fn load_patterns<S: Storage>(&mut self, storage: &S) -> Result<usize> {
+        let latest = match storage.list_resources(Some(patterns_filter())).into_iter().next() {
+            Some(metadata) => metadata,
+            None => return Ok(0),
+        };
+        let patterns: Vec<ConflictPattern> = serde_json::from_slice(&storage.get_resource_content(&latest.resource_id).await?)?;
+        self.conflict_patterns = patterns.into_iter()
+            .map(|pattern| (pattern.pattern_id.clone(), pattern))
+            .collect();
+        Ok(self.conflict_patterns.len())
+    }
+    
+    /// Per-file conflict history of a repository since `since`
+    ///
+    /// Counts the cached conflicts of the repository together with resolved
+    /// conflicts recorded for it, each conflict once. Resolution records only
+    /// belong to a repository when their conflict carries its path under
+    /// `REPOSITORY_METADATA_KEY`, as detected conflicts do.
+    pub fn conflict_heatmap(&self, repository_path: &Path, since: DateTime<Utc>) -> HashMap<String, ConflictHeatmapEntry> {
+        let repository = repository_path.to_string_lossy().to_string();
+        let mut outcomes: HashMap<&str, bool> = HashMap::new();
+        for record in &self.resolution_history {
+            if record.conflict.metadata.get(REPOSITORY_METADATA_KEY) == Some(&repository) {
+                outcomes.insert(record.conflict.conflict_id.as_str(), record.outcome.success);
+            }
+        }
+        let cached = self.conflicts_cache.get(&repository).into_iter().flatten();
+        let resolved = self.resolution_history.iter()
+            .map(|record| &record.conflict)
+            .filter(|conflict| outcomes.contains_key(conflict.conflict_id.as_str()));
+        
+        let mut seen = std::collections::HashSet::new();
+        let mut by_file: HashMap<&str, Vec<&GitConflict>> = HashMap::new();
+        for conflict in cached.chain(resolved) {
+            if conflict.detected_at >= since && seen.insert(conflict.conflict_id.as_str()) {
+                by_file.entry(conflict.file_path.as_str()).or_default().push(conflict);
+            }
+        }
+        
+        by_file.into_iter()
+            .map(|(file_path, conflicts)| {
+                let conflict_count = conflicts.len();
+                let avg_severity = conflicts.iter().map(|c| c.severity.score()).sum::<f64>() / conflict_count as f64;
+                // Ties go to the type seen first
+                let mut type_counts: Vec<(&ConflictType, usize)> = Vec::new();
+                for conflict in &conflicts {
+                    match type_counts.iter_mut().find(|(t, _)| **t == conflict.conflict_type) {
+                        Some((_, count)) => *count += 1,
+                        None => type_counts.push((&conflict.conflict_type, 1)),
+                    }
+                }
+                let max_count = type_counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+                let most_common_type = type_counts.iter()
+                    .find(|(_, count)| *count == max_count)
+                    .map(|(t, _)| (*t).clone())
+                    .unwrap_or(ConflictType::ContentConflict);
+                let results: Vec<bool> = conflicts.iter()
+                    .filter_map(|c| outcomes.get(c.conflict_id.as_str()).copied())
+                    .collect();
+                let resolution_success_rate = if results.is_empty() {
+                    0.0
+                } else {
+                    results.iter().filter(|success| **success).count() as f64 / results.len() as f64
+                };
+                let entry = ConflictHeatmapEntry {
+                    file_path: file_path.to_string(),
+                    conflict_count,
+                    avg_severity,
+                    most_common_type,
+                    resolution_success_rate,
+                    is_hot: conflict_count > self.config.heatmap.hot_file_threshold,
+                };
+                (file_path.to_string(), entry)
+            })
+            .collect()
+    }
+    
+    /// Conflict heatmap as a JSON array, most conflicted files first
+    pub fn conflict_heatmap_json(&self, repository_path: &Path, since: DateTime<Utc>) -> Result<String> {
+        Ok(serde_json::to_string(&sorted_heatmap(self.conflict_heatmap(repository_path, since)))?)
+    }
+    
+    /// Hot files of a repository since `since`, most conflicted first
+    pub fn hot_files(&self, repository_path: &Path, since: DateTime<Utc>) -> Vec<ConflictHeatmapEntry> {
+        sorted_heatmap(self.conflict_heatmap(repository_path, since))
+            .into_iter()
+            .filter(|entry| entry.is_hot)
+            .collect()
+    }
+    
+    /// Heatmap settings
+    pub fn heatmap_config(&self) -> &ConflictHeatmapConfig {
+        &self.config.heatmap
+    }
+    
     /// Determine conflict type from git status
     fn determine_conflict_type_from_status(&self, status: git2::Status) -> ConflictType {
         if status.is_index_deleted() && status.is_wt_modified() {
@@ -771,24 +1245,7 @@ impl GitConflictDetector {
     
     /// Determine content type from file path
     fn determine_content_type(&self, file_path: &str) -> ContentType {
-        let extension = std::path::Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
-        
-        match extension {
-            "rs" | "py" | "js" | "ts" | "java" | "cpp" | "c" | "h" => ContentType::SourceCode,
-            "md" | "txt" | "rst" => ContentType::Documentation,
-            "json" | "yaml" | "yml" | "toml" | "ini" | "conf" => ContentType::Configuration,
-            "png" | "jpg" | "jpeg" | "gif" | "svg" => ContentType::Image,
-            _ => {
-                if file_path.ends_with(".bin") || file_path.contains("binary") {
-                    ContentType::Binary
-                } else {
-                    ContentType::Text
-                }
-            }
-        }
+        content_type_for_path(file_path)
     }
     
     /// Get total conflicts detected
@@ -831,6 +1288,122 @@ impl GitConflictDetector {
             average_resolution_time_minutes: avg_resolution_time,
             conflict_type_distribution,
             patterns_learned: self.conflict_patterns.len(),
+            trend: self.conflict_trend(),
+        }
+    }
+    
+    /// Weekly conflict counts, resolution time trend and per-pattern improvement
+    pub fn conflict_trend(&self) -> ConflictTrend {
+        let mut weeks: std::collections::BTreeMap<DateTime<Utc>, WeeklyConflictBucket> = std::collections::BTreeMap::new();
+        
+        for conflict in self.conflicts_cache.values().flatten() {
+            weekly_bucket(&mut weeks, conflict.detected_at).conflicts += 1;
+        }
+        for record in &self.resolution_history {
+            weekly_bucket(&mut weeks, record.conflict.detected_at).conflicts += 1;
+            let week = weekly_bucket(&mut weeks, record.recorded_at);
+            week.average_resolution_time_minutes = (week.average_resolution_time_minutes * week.resolved as f64
+                + record.resolution_time_minutes as f64) / (week.resolved + 1) as f64;
+            week.resolved += 1;
+        }
+        let weekly: Vec<WeeklyConflictBucket> = weeks.into_values().collect();
+        
+        // Least-squares slope of the weekly average resolution time
+        let points: Vec<(f64, f64)> = weekly.iter()
+            .filter(|week| week.resolved > 0)
+            .map(|week| ((week.week_start - weekly[0].week_start).num_weeks() as f64, week.average_resolution_time_minutes))
+            .collect();
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n.max(1.0);
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n.max(1.0);
+        let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let resolution_time_trend_minutes_per_week = if variance > 0.0 {
+            points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>() / variance
+        } else {
+            0.0
+        };
+        
+        let pattern_improvement = self.conflict_patterns().into_iter()
+            .filter_map(|pattern| {
+                let records: Vec<&ConflictResolutionRecord> = self.resolution_history.iter()
+                    .filter(|record| ConflictPattern::key_for(&record.conflict) == pattern.pattern_id)
+                    .collect();
+                if records.len() < 2 {
+                    return None;
+                }
+                let (earlier, recent) = records.split_at(records.len() / 2);
+                Some(PatternImprovement {
+                    pattern_id: pattern.pattern_id.clone(),
+                    earlier_success_rate: success_rate(earlier),
+                    recent_success_rate: success_rate(recent),
+                    earlier_resolution_time_minutes: average_resolution_time(earlier),
+                    recent_resolution_time_minutes: average_resolution_time(recent),
+                })
+            })
+            .collect();
+        
+        ConflictTrend {
+            weekly,
+            resolution_time_trend_minutes_per_week,
+            pattern_improvement,
+        }
+    }
+}
+
+/// Conflict metadata key holding the repository the conflict was detected in
+pub const REPOSITORY_METADATA_KEY: &str = "repository_path";
+
+/// Ceremony action type recommended when a hot file is opened
+pub const HOT_FILE_REVIEW_ACTION: &str = "hot_file_review";
+
+/// Storage tag of saved conflict patterns
+const CONFLICT_PATTERNS_TAG: &str = "conflict-patterns";
+
+fn patterns_filter() -> ResourceFilter {
+    ResourceFilter {
+        content_type: None,
+        tags: Some(vec![CONFLICT_PATTERNS_TAG.to_string()]),
+        is_private: None,
+        name_contains: None,
+        content_query: None,
+    }
+}
+
+/// Start (Monday 00:00 UTC) of the week containing `at`
+fn week_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let day = at.date_naive() - Duration::days(at.weekday().num_days_from_monday() as i64);
+    Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).expect("midnight exists"))
+}
+
+fn weekly_bucket(
+    weeks: &mut std::collections::BTreeMap<DateTime<Utc>, WeeklyConflictBucket>,
+    at: DateTime<Utc>,
+) -> &mut WeeklyConflictBucket {
+    let week_start = week_start(at);
+    weeks.entry(week_start).or_insert_with(|| WeeklyConflictBucket {
+        week_start,
+        ..WeeklyConflictBucket::default()
+    })
+}
+
+fn success_rate(records: &[&ConflictResolutionRecord]) -> f64 {
+    records.iter().filter(|record| record.outcome.success).count() as f64 / records.len() as f64
+}
+
+fn average_resolution_time(records: &[&ConflictResolutionRecord]) -> f64 {
+    records.iter().map(|record| record.resolution_time_minutes).sum::<u64>() as f64 / records.len() as f64
+}
+
+#[async_trait::async_trait]
+impl MemoryFootprint for GitConflictDetector {
+    async fn memory_footprint(&self) -> ComponentFootprint {
+        let conflicts: usize = self.conflicts_cache.values().map(Vec::len).sum();
+        let previews = self.merge_preview_cache.len();
+        ComponentFootprint {
+            component: "conflict_cache".to_string(),
+            entries: self.conflicts_cache.len() + previews,
+            approx_bytes: conflicts * std::mem::size_of::<GitConflict>()
+                + previews * std::mem::size_of::<MergePreview>(),
         }
     }
 }
@@ -850,6 +1423,169 @@ pub struct ConflictStatistics {
     pub conflict_type_distribution: HashMap<ConflictType, usize>,
     /// Number of patterns learned
     pub patterns_learned: usize,
+    /// How conflicts and their resolution develop over time
+    #[serde(default)]
+    pub trend: ConflictTrend,
+}
+
+/// How conflicts and their resolution develop over time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictTrend {
+    /// Conflicts and resolutions per week, oldest first
+    pub weekly: Vec<WeeklyConflictBucket>,
+    /// Change in weekly average resolution time; negative means faster
+    pub resolution_time_trend_minutes_per_week: f64,
+    /// Earlier versus recent resolutions of each pattern seen at least twice
+    pub pattern_improvement: Vec<PatternImprovement>,
+}
+
+/// Conflicts of one week
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WeeklyConflictBucket {
+    /// Monday 00:00 UTC starting the week
+    pub week_start: DateTime<Utc>,
+    /// Conflicts detected during the week
+    pub conflicts: usize,
+    /// Conflicts resolved during the week
+    pub resolved: usize,
+    /// Average time to resolve the week's resolved conflicts
+    pub average_resolution_time_minutes: f64,
+}
+
+/// Resolution of a pattern's first half of conflicts against its second half
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PatternImprovement {
+    /// Pattern concerned
+    pub pattern_id: String,
+    /// Success rate of the earlier resolutions
+    pub earlier_success_rate: f64,
+    /// Success rate of the recent resolutions
+    pub recent_success_rate: f64,
+    /// Average resolution time of the earlier resolutions
+    pub earlier_resolution_time_minutes: f64,
+    /// Average resolution time of the recent resolutions
+    pub recent_resolution_time_minutes: f64,
+}
+
+/// Conflict history of a single file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConflictHeatmapEntry {
+    /// File path relative to the repository
+    pub file_path: String,
+    /// Conflicts seen in the file
+    pub conflict_count: usize,
+    /// Average `ConflictSeverity::score` of those conflicts
+    pub avg_severity: f64,
+    /// Most frequent conflict type
+    pub most_common_type: ConflictType,
+    /// Share of recorded resolutions that succeeded (0.0 when none were recorded)
+    pub resolution_success_rate: f64,
+    /// Whether the conflict count is above the hot file threshold
+    pub is_hot: bool,
+}
+
+impl ConflictHeatmapEntry {
+    /// Ceremony action recommending a review before editing a hot file
+    ///
+    /// Returns `None` for files that are not hot.
+    pub fn recommendation(&self) -> Option<BasicCeremonyAction> {
+        if !self.is_hot {
+            return None;
+        }
+        let mut parameters = HashMap::new();
+        parameters.insert("file_path".to_string(), self.file_path.clone());
+        parameters.insert("conflict_count".to_string(), self.conflict_count.to_string());
+        parameters.insert("most_common_type".to_string(), format!("{:?}", self.most_common_type));
+        Some(BasicCeremonyAction {
+            action_type: HOT_FILE_REVIEW_ACTION.to_string(),
+            description: format!(
+                "{} has had {} conflicts; coordinate with other editors before changing it",
+                self.file_path, self.conflict_count
+            ),
+            parameters,
+        })
+    }
+}
+
+fn sorted_heatmap(heatmap: HashMap<String, ConflictHeatmapEntry>) -> Vec<ConflictHeatmapEntry> {
+    let mut entries: Vec<ConflictHeatmapEntry> = heatmap.into_values().collect();
+    entries.sort_by(|a, b| b.conflict_count.cmp(&a.conflict_count).then_with(|| a.file_path.cmp(&b.file_path)));
+    entries
+}
+
+/// Content type implied by a file path
+fn content_type_for_path(file_path: &str) -> ContentType {
+    let extension = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    
+    match extension {
+        "rs" | "py" | "js" | "ts" | "java" | "cpp" | "c" | "h" => ContentType::SourceCode,
+        "md" | "txt" | "rst" => ContentType::Documentation,
+        "json" | "yaml" | "yml" | "toml" | "ini" | "conf" => ContentType::Configuration,
+        "png" | "jpg" | "jpeg" | "gif" | "svg" => ContentType::Image,
+        _ => {
+            if file_path.ends_with(".bin") || file_path.contains("binary") {
+                ContentType::Binary
+            } else {
+                ContentType::Text
+            }
+        }
+    }
+}
+
+/// Merge `source` into `target` in memory and summarize the result
+fn build_merge_preview(
+    repo: &Repository,
+    source: &git2::Commit<'_>,
+    target: &git2::Commit<'_>,
+    source_ref: &str,
+    target_ref: &str,
+) -> Result<MergePreview> {
+    let merged = repo.merge_commits(target, source, None)?;
+    let mut conflicts = Vec::new();
+    if merged.has_conflicts() {
+        for conflict in merged.conflicts()? {
+            let conflict = conflict?;
+            conflicts.push(GitConflictDetector::preview_conflict(repo, &conflict, source_ref, target_ref)?);
+        }
+    }
+    
+    // Changes the source brings in relative to the common ancestor
+    let base_tree = match repo.merge_base(source.id(), target.id()) {
+        Ok(base) => Some(repo.find_commit(base)?.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(base_tree.as_ref(), Some(&source.tree()?), None)?;
+    
+    let mut changed_files = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let delta = diff.get_delta(index).expect("delta index within bounds");
+        let path = delta.new_file().path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (insertions, deletions) = match git2::Patch::from_diff(&diff, index)? {
+            Some(patch) => {
+                let (_, additions, removals) = patch.line_stats()?;
+                (additions, removals)
+            }
+            None => (0, 0),
+        };
+        changed_files.push(FileChangeStats { path, insertions, deletions });
+    }
+    
+    Ok(MergePreview {
+        source_oid: source.id().to_string(),
+        target_oid: target.id().to_string(),
+        is_clean: conflicts.is_empty(),
+        conflicts,
+        insertions: changed_files.iter().map(|f| f.insertions).sum(),
+        deletions: changed_files.iter().map(|f| f.deletions).sum(),
+        changed_files,
+        ceremony_required: false,
+    })
 }
 
 #[cfg(test)]
@@ -872,6 +1608,98 @@ mod tests {
         assert!(ConflictSeverity::Moderate > ConflictSeverity::Minor);
     }
     
+    /// Commit a single-file tree onto `branch` without touching any working tree
+    fn commit_file(repo: &Repository, branch: &str, parent: Option<git2::Oid>, path: &str, content: &str) -> git2::Oid {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder.insert(path, blob, 0o100644).unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let signature = git2::Signature::now("tester", "tester@example.com").unwrap();
+        let parents: Vec<git2::Commit> = parent.into_iter().map(|p| repo.find_commit(p).unwrap()).collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some(&format!("refs/heads/{}", branch)), &signature, &signature, "commit", &tree, &parent_refs).unwrap()
+    }
+    
+    fn status_snapshot(repo: &Repository) -> Vec<(String, git2::Status)> {
+        repo.statuses(None).unwrap().iter()
+            .map(|entry| (entry.path().unwrap_or_default().to_string(), entry.status()))
+            .collect()
+    }
+    
+    #[test]
+    fn test_preview_clean_merge_on_bare_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init_bare(dir.path()).unwrap();
+        let base = commit_file(&repo, "main", None, "lib.rs", "fn a() {}\n");
+        commit_file(&repo, "feature", Some(base), "lib.rs", "fn a() {}\nfn b() {}\n");
+        
+        let mut detector = GitConflictDetector::new(&GitManagerConfig::default()).unwrap();
+        let preview = detector.preview_merge(dir.path(), "feature", "main").unwrap();
+        
+        assert!(preview.is_clean);
+        assert!(preview.conflicts.is_empty());
+        assert_eq!(preview.changed_files.len(), 1);
+        assert_eq!(preview.insertions, 1);
+        assert_eq!(preview.deletions, 0);
+        
+        // Cached by commit pair
+        let again = detector.preview_merge(dir.path(), "refs/heads/feature", "main").unwrap();
+        assert_eq!(again.source_oid, preview.source_oid);
+        assert_eq!(detector.merge_preview_cache.len(), 1);
+    }
+    
+    #[test]
+    fn test_preview_conflicting_merge_leaves_repository_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let base = commit_file(&repo, "main", None, "notes.txt", "base\n");
+        let main_head = commit_file(&repo, "main", Some(base), "notes.txt", "ours\n");
+        commit_file(&repo, "feature", Some(base), "notes.txt", "theirs\n");
+        repo.set_head("refs/heads/main").unwrap();
+        
+        let status_before = status_snapshot(&repo);
+        
+        let mut detector = GitConflictDetector::new(&GitManagerConfig::default()).unwrap();
+        let preview = detector.preview_merge(dir.path(), "feature", "main").unwrap();
+        
+        assert!(!preview.is_clean);
+        assert_eq!(preview.conflicts.len(), 1);
+        let conflict = &preview.conflicts[0];
+        assert_eq!(conflict.file_path, "notes.txt");
+        assert_eq!(conflict.conflict_type, ConflictType::ContentConflict);
+        assert_eq!(conflict.conflict_content.ours, "ours\n");
+        assert_eq!(conflict.conflict_content.theirs, "theirs\n");
+        assert_eq!(conflict.conflict_content.base.as_deref(), Some("base\n"));
+        
+        assert_eq!(status_snapshot(&repo), status_before);
+        assert_eq!(repo.state(), git2::RepositoryState::Clean);
+        assert_eq!(repo.head().unwrap().target(), Some(main_head));
+        assert!(!repo.index().unwrap().has_conflicts());
+    }
+    
+    #[test]
+    fn test_constrained_profile_evicts_merge_previews() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init_bare(dir.path()).unwrap();
+        let base = commit_file(&repo, "main", None, "lib.rs", "fn a() {}\n");
+        
+        let config = ConflictDetectionConfig::for_profile(ResourceProfile::Constrained);
+        let limit = config.cache_size;
+        assert_eq!(limit, ResourceProfile::Constrained.limits().conflict_cache_size);
+        assert_eq!(ConflictDetectionConfig::for_profile(ResourceProfile::Standard).cache_size, 1000);
+        
+        let mut detector = GitConflictDetector::new(&GitManagerConfig::default()).unwrap()
+            .with_config(config);
+        let mut parent = base;
+        for i in 0..limit + 2 {
+            parent = commit_file(&repo, "feature", Some(parent), "lib.rs", &format!("fn a() {{}}\n// {}\n", i));
+            detector.preview_merge(dir.path(), &parent.to_string(), "main").unwrap();
+        }
+        
+        assert_eq!(detector.merge_preview_cache.len(), limit);
+        assert_eq!(detector.eviction_counters().get(EvictionKind::ConflictCache), 2);
+    }
+    
     #[test]
     fn test_content_type_determination() {
         let detector = GitConflictDetector::new(&GitManagerConfig::default()).unwrap();
@@ -916,4 +1744,164 @@ mod tests {
         assert_eq!(conflict.conflict_id, deserialized.conflict_id);
         assert_eq!(conflict.conflict_type, deserialized.conflict_type);
     }
+    
+    fn resolution_record(
+        file_path: &str,
+        resolution_type: ResolutionType,
+        success: bool,
+        minutes: u64,
+        at: DateTime<Utc>,
+    ) -> ConflictResolutionRecord {
+        let conflict = GitConflict {
+            conflict_id: Uuid::new_v4().to_string(),
+            conflict_type: ConflictType::ContentConflict,
+            severity: ConflictSeverity::Moderate,
+            file_path: file_path.to_string(),
+            location: ConflictLocation { start_line: 1, end_line: 1, start_column: None, end_column: None, context: None },
+            description: String::new(),
+            conflicting_refs: Vec::new(),
+            conflict_content: ConflictContent {
+                ours: String::new(),
+                theirs: String::new(),
+                base: None,
+                has_markers: true,
+                content_type: ContentType::SourceCode,
+            },
+            suggested_resolutions: Vec::new(),
+            metadata: HashMap::new(),
+            detected_at: at,
+            resolution_status: ConflictResolutionStatus::Resolved,
+        };
+        ConflictResolutionRecord {
+            record_id: Uuid::new_v4().to_string(),
+            conflict,
+            resolution: ConflictResolution {
+                resolution_id: Uuid::new_v4().to_string(),
+                resolution_type,
+                description: String::new(),
+                confidence: 0.7,
+                steps: Vec::new(),
+                estimated_effort: ResolutionEffort::Minimal,
+                risk_level: RiskLevel::Low,
+                required_expertise: Vec::new(),
+            },
+            outcome: ResolutionOutcome {
+                success,
+                description: String::new(),
+                quality_score: 1.0,
+                side_effects: Vec::new(),
+                follow_up_actions: Vec::new(),
+            },
+            resolution_time_minutes: minutes,
+            participants: Vec::new(),
+            lessons_learned: Vec::new(),
+            recorded_at: at,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_learned_patterns_boost_suggestions_and_persist() {
+        use crate::storage::MemoryStorage;
+        
+        let mut detector = GitConflictDetector::new(&GitManagerConfig::default()).unwrap();
+        // A Monday, then weekly resolutions that get faster
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let lockfiles = [
+            (ResolutionType::AcceptTheirs, true, 60),
+            (ResolutionType::AcceptOurs, false, 50),
+            (ResolutionType::AcceptTheirs, true, 30),
+            (ResolutionType::AcceptTheirs, true, 20),
+        ];
+        for (week, (resolution_type, success, minutes)) in lockfiles.into_iter().enumerate() {
+            let at = start + Duration::weeks(week as i64);
+            detector.record_resolution(resolution_record("deps/Cargo.lock", resolution_type, success, minutes, at));
+        }
+        detector.record_resolution(resolution_record("src/lib.rs", ResolutionType::AcceptOurs, true, 10, start));
+        
+        let patterns = detector.conflict_patterns();
+        assert_eq!(patterns.len(), 2);
+        let lockfile = patterns[0];
+        assert_eq!(lockfile.file_patterns, vec!["deps/*.lock".to_string()]);
+        assert_eq!((lockfile.frequency, lockfile.success_rate), (4, 0.75));
+        assert_eq!(lockfile.typical_resolutions, vec![ResolutionType::AcceptTheirs]);
+        
+        // A new conflict in the same place ranks the learned resolution first
+        let mut conflict = resolution_record("deps/other.lock", ResolutionType::Defer, false, 0, start).conflict;
+        detector.generate_resolutions(&mut conflict).await.unwrap();
+        assert_eq!(conflict.suggested_resolutions[0].resolution_type, ResolutionType::AcceptTheirs);
+        assert!(conflict.suggested_resolutions[0].confidence > 0.7);
+        assert_eq!(conflict.suggested_resolutions[1].confidence, 0.7);
+        
+        // Trend buckets: one per week, resolution time falling, lockfile pattern improving
+        let trend = detector.get_conflict_statistics().trend;
+        assert_eq!(trend.weekly.len(), 4);
+        assert_eq!(trend.weekly[0].week_start, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!((trend.weekly[0].conflicts, trend.weekly[0].resolved), (2, 2));
+        assert_eq!(trend.weekly[0].average_resolution_time_minutes, 35.0);
+        assert!(trend.resolution_time_trend_minutes_per_week < 0.0);
+        assert_eq!(trend.pattern_improvement.len(), 1);
+        let improvement = &trend.pattern_improvement[0];
+        assert_eq!((improvement.earlier_success_rate, improvement.recent_success_rate), (0.5, 1.0));
+        assert_eq!((improvement.earlier_resolution_time_minutes, improvement.recent_resolution_time_minutes), (55.0, 25.0));
+        
+        // Patterns survive a round trip through storage
+        let mut storage = MemoryStorage::new();
+        detector.save_patterns(&mut storage).await.unwrap();
+        detector.save_patterns(&mut storage).await.unwrap();
+        let mut restored = GitConflictDetector::new(&GitManagerConfig::default()).unwrap();
+        assert_eq!(restored.load_patterns(&storage).await.unwrap(), 2);
+        assert_eq!(restored.conflict_patterns()[0].typical_resolutions, vec![ResolutionType::AcceptTheirs]);
+        assert_eq!(storage.get_stats().total_resources, 1);
+    }
+    
+    #[test]
+    fn test_conflict_heatmap_marks_hot_files() {
+        let mut detector = GitConflictDetector::new(&GitManagerConfig::default()).unwrap()
+            .with_config(ConflictDetectionConfig {
+                heatmap: ConflictHeatmapConfig { hot_file_threshold: 2, window_days: 30 },
+                ..ConflictDetectionConfig::default()
+            });
+        let repository = Path::new("/repos/app");
+        let since = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let in_repo = |file_path: &str, success: bool, at: DateTime<Utc>| {
+            let mut record = resolution_record(file_path, ResolutionType::AcceptOurs, success, 10, at);
+            record.conflict.metadata.insert(REPOSITORY_METADATA_KEY.to_string(), "/repos/app".to_string());
+            record
+        };
+        
+        // Three resolved conflicts in the generated file, one of them failed
+        for success in [true, true, false] {
+            detector.record_resolution(in_repo("src/generated.rs", success, since + Duration::days(1)));
+        }
+        // One unresolved conflict in the same file, still cached, and one already counted
+        let mut open = in_repo("src/generated.rs", false, since + Duration::days(2)).conflict;
+        open.severity = ConflictSeverity::Blocking;
+        open.conflict_type = ConflictType::SemanticConflict;
+        let resolved_again = detector.resolution_history[0].conflict.clone();
+        detector.conflicts_cache.insert("/repos/app".to_string(), vec![open, resolved_again]);
+        // Ignored: too old, and another repository
+        detector.record_resolution(in_repo("src/lib.rs", true, since - Duration::days(1)));
+        detector.record_resolution(resolution_record("src/generated.rs", ResolutionType::AcceptOurs, true, 10, since));
+        detector.record_resolution(in_repo("README.md", true, since));
+        
+        let heatmap = detector.conflict_heatmap(repository, since);
+        assert_eq!(heatmap.len(), 2);
+        let generated = &heatmap["src/generated.rs"];
+        assert_eq!(generated.conflict_count, 4);
+        assert_eq!(generated.avg_severity, (2.0 * 3.0 + 5.0) / 4.0);
+        assert_eq!(generated.most_common_type, ConflictType::ContentConflict);
+        assert!((generated.resolution_success_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert!(generated.is_hot);
+        assert!(!heatmap["README.md"].is_hot);
+        assert!(heatmap["README.md"].recommendation().is_none());
+        
+        let action = generated.recommendation().unwrap();
+        assert_eq!(action.action_type, HOT_FILE_REVIEW_ACTION);
+        assert_eq!(action.parameters["file_path"], "src/generated.rs");
+        
+        let exported: Vec<ConflictHeatmapEntry> = serde_json::from_str(&detector.conflict_heatmap_json(repository, since).unwrap()).unwrap();
+        let order: Vec<&str> = exported.iter().map(|entry| entry.file_path.as_str()).collect();
+        assert_eq!(order, vec!["src/generated.rs", "README.md"]);
+        assert_eq!(detector.hot_files(repository, since).len(), 1);
+    }
 }
@@ -9,11 +9,15 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 use tracing::{debug, info, warn, error};
 use uuid::Uuid;
 
 use crate::attribution::Attribution;
 use super::{GitManagerConfig, GitOperationType};
+use super::workflow_integration::{
+    branch_matches, CeremonyStatus, CeremonyType, GitWorkflowIntegrator, OutcomeType,
+};
 
 /// Git hooks manager for WeaveMesh Core
 pub struct GitHooksManager {
@@ -25,6 +29,8 @@ pub struct GitHooksManager {
     execution_history: Vec<HookExecutionRecord>,
     /// Hook templates
     hook_templates: HashMap<GitHookType, String>,
+    /// Configuration of the installed pre-push ceremony hook
+    pre_push_config: Option<PrePushHookConfig>,
 }
 
 /// Configuration for git hooks
@@ -60,6 +66,39 @@ impl Default for GitHooksConfig {
     }
 }
 
+/// Configuration for the pre-push ceremony hook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrePushHookConfig {
+    /// Participants who must all approve the push
+    pub required_approvers: Vec<String>,
+    /// Ceremony held before the push
+    pub ceremony_type: CeremonyType,
+    /// Remote refs pushed without a ceremony; `*` matches any run of characters
+    pub skip_branches: Vec<String>,
+    /// Base URL of the node serving [`pre_push_router`](crate::http::pre_push_router),
+    /// overridden by `$WEAVEMESH_URL` when the hook runs
+    #[serde(default = "default_pre_push_endpoint")]
+    pub endpoint: String,
+    /// How long the hook waits for a ceremony before refusing the push
+    #[serde(default = "default_pre_push_timeout_seconds")]
+    pub approval_timeout_seconds: u64,
+}
+
+fn default_pre_push_endpoint() -> String {
+    "http://127.0.0.1:8080".to_string()
+}
+
+fn default_pre_push_timeout_seconds() -> u64 {
+    300
+}
+
+impl PrePushHookConfig {
+    /// Whether a remote ref bypasses the ceremony
+    pub fn skips(&self, remote_ref: &str) -> bool {
+        self.skip_branches.iter().any(|pattern| branch_matches(pattern, remote_ref))
+    }
+}
+
 /// Types of git hooks
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum GitHookType {
@@ -198,6 +237,9 @@ pub struct HookExecutionRecord {
     pub context: HookExecutionContext,
     /// Attribution information
     pub attribution: Option<Attribution>,
+    /// Ceremony the operation waits on, if any
+    #[serde(default)]
+    pub ceremony_id: Option<String>,
 }
 
 /// Hook execution status
@@ -250,6 +292,7 @@ impl GitHooksManager {
             installed_hooks: HashMap::new(),
             execution_history: Vec::new(),
             hook_templates,
+            pre_push_config: None,
         })
     }
     
@@ -333,6 +376,7 @@ impl GitHooksManager {
             stderr: String::new(),
             context,
             attribution,
+            ceremony_id: None,
         };
         
         // Check if hook is installed and enabled
@@ -390,17 +434,137 @@ impl GitHooksManager {
             debug!("Hook {:?} not installed, skipping", hook_type);
         }
         
-        // Store execution record
-        self.execution_history.push(record.clone());
-        
-        // Limit history size
+        self.record_execution(record.clone());
+        Ok(record)
+    }
+    
+    /// Store an execution record, trimming the oldest when history is full
+    fn record_execution(&mut self, record: HookExecutionRecord) {
+        self.execution_history.push(record);
         if self.execution_history.len() > self.config.max_execution_history {
             self.execution_history.drain(0..100); // Remove oldest 100 entries
         }
+    }
+    
+    /// Install a pre-push hook that blocks pushes until a ceremony approves them
+    ///
+    /// The hook asks the node at `config.endpoint` (or `$WEAVEMESH_URL`) to
+    /// start a ceremony for each pushed ref not matching `skip_branches`, then
+    /// polls it until the ceremony is decided. If no node answers the push is
+    /// allowed with a warning, so CI machines without WeaveMesh are not blocked.
+    pub async fn install_pre_push_hook(&mut self, repository_path: &Path, config: PrePushHookConfig) -> Result<()> {
+        let mut hook = self.create_attribution_hook(GitHookType::PrePush);
+        hook.name = "WeaveMesh Pre-push Ceremony".to_string();
+        hook.description = format!("Requires a {:?} ceremony before pushing", config.ceremony_type);
+        hook.script_content = pre_push_script(&config);
+        hook.config.on_failure = HookFailureBehavior::Abort;
+        hook.metadata.insert("ceremony_type".to_string(), format!("{:?}", config.ceremony_type));
+        hook.metadata.insert("required_approvers".to_string(), config.required_approvers.join(","));
+        
+        self.install_hook(repository_path, hook).await?;
+        self.pre_push_config = Some(config);
+        Ok(())
+    }
+    
+    /// Start the ceremony gating a push of `remote_refs`
+    ///
+    /// Returns a `Running` record carrying the ceremony ID when any ref needs
+    /// a ceremony, or a `Skipped` record when every ref matches `skip_branches`.
+    pub async fn pre_push_ceremony(
+        &mut self,
+        repository_path: &Path,
+        remote_refs: &[String],
+        integrator: &mut GitWorkflowIntegrator,
+        attribution: Option<Attribution>,
+    ) -> Result<HookExecutionRecord> {
+        let config = self.pre_push_config.clone()
+            .ok_or_else(|| anyhow::anyhow!("Pre-push ceremony hook is not installed"))?;
+        let gated: Vec<&String> = remote_refs.iter().filter(|r| !config.skips(r)).collect();
+        let started_at = Utc::now();
+        
+        let mut additional_context = HashMap::new();
+        additional_context.insert("remote_refs".to_string(), remote_refs.join(","));
+        let mut record = HookExecutionRecord {
+            execution_id: Uuid::new_v4().to_string(),
+            hook_type: GitHookType::PrePush,
+            repository_path: repository_path.to_path_buf(),
+            status: HookExecutionStatus::Skipped,
+            started_at,
+            ended_at: None,
+            duration_ms: None,
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            context: HookExecutionContext {
+                git_operation: Some(GitOperationType::Push),
+                commit_hash: None,
+                branch_name: gated.first().map(|r| r.trim_start_matches("refs/heads/").to_string()),
+                affected_files: Vec::new(),
+                author: attribution.as_ref()
+                    .and_then(|a| a.human_contributor.clone().or_else(|| a.ai_contributor.clone())),
+                commit_message: None,
+                additional_context,
+            },
+            attribution: attribution.clone(),
+            ceremony_id: None,
+        };
+        
+        if gated.is_empty() {
+            record.ended_at = Some(started_at);
+            record.duration_ms = Some(0);
+            debug!("All pushed refs bypass the pre-push ceremony");
+        } else {
+            let mut parameters = HashMap::new();
+            parameters.insert("repository_path".to_string(), repository_path.display().to_string());
+            parameters.insert("branch".to_string(), record.context.branch_name.clone().unwrap_or_default());
+            parameters.insert("remote_refs".to_string(), gated.iter().map(|r| r.as_str()).collect::<Vec<_>>().join(","));
+            let ceremony_id = integrator.initiate_ceremony(
+                config.ceremony_type.clone(),
+                &GitOperationType::Push,
+                &parameters,
+                config.required_approvers.clone(),
+                &attribution,
+            ).await?;
+            info!("Push of {} refs waits on ceremony {}", gated.len(), ceremony_id);
+            record.status = HookExecutionStatus::Running;
+            record.ceremony_id = Some(ceremony_id);
+        }
         
+        self.record_execution(record.clone());
         Ok(record)
     }
     
+    /// Whether a pre-push ceremony completed with every required approver agreeing to proceed
+    pub fn pre_push_approved(&self, integrator: &GitWorkflowIntegrator, ceremony_id: &str) -> bool {
+        let config = match &self.pre_push_config {
+            Some(config) => config,
+            None => return false,
+        };
+        let record = match integrator.ceremony_record(ceremony_id) {
+            Some(record) => record,
+            None => return false,
+        };
+        record.ceremony.status == CeremonyStatus::Completed
+            && record.ceremony.ceremony_type == config.ceremony_type
+            && record.final_outcome.as_ref().is_some_and(|outcome| {
+                outcome.outcome_type == OutcomeType::Proceed
+                    && config.required_approvers.iter().all(|a| outcome.agreed_participants.contains(a))
+            })
+    }
+    
+    /// Where a pre-push ceremony stands, or `None` for an unknown ceremony
+    pub fn pre_push_status(&self, integrator: &GitWorkflowIntegrator, ceremony_id: &str) -> Option<PrePushStatus> {
+        if integrator.get_ceremony(ceremony_id).is_some() {
+            return Some(PrePushStatus::Pending);
+        }
+        integrator.ceremony_record(ceremony_id)?;
+        Some(if self.pre_push_approved(integrator, ceremony_id) {
+            PrePushStatus::Approved
+        } else {
+            PrePushStatus::Rejected
+        })
+    }
+    
     /// Execute hook script
     async fn execute_hook_script(
         &self,
@@ -630,6 +794,151 @@ exit 0
     }
 }
 
+/// Where a ceremony gating a push stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrePushStatus {
+    /// Still running
+    Pending,
+    /// Completed with every required approver agreeing to proceed
+    Approved,
+    /// Ended any other way
+    Rejected,
+}
+
+impl PrePushStatus {
+    /// Word the pre-push hook matches on
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrePushStatus::Pending => "pending",
+            PrePushStatus::Approved => "approved",
+            PrePushStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// Pre-push ceremonies as served to the hook over HTTP
+#[async_trait::async_trait]
+pub trait PrePushSource: Send + Sync {
+    /// Start the ceremony gating a push of `remote_ref`, or `None` if the ref bypasses it
+    async fn start_pre_push(&self, remote_ref: &str) -> Result<Option<String>>;
+    
+    /// Where a ceremony started by [`start_pre_push`](Self::start_pre_push) stands
+    async fn pre_push_status(&self, ceremony_id: &str) -> Option<PrePushStatus>;
+}
+
+/// Pre-push ceremonies of one repository, on hooks and workflow state shared with the node
+pub struct PrePushGate {
+    repository_path: PathBuf,
+    hooks: Arc<tokio::sync::Mutex<GitHooksManager>>,
+    integrator: Arc<tokio::sync::Mutex<GitWorkflowIntegrator>>,
+}
+
+impl PrePushGate {
+    pub fn new(
+        repository_path: PathBuf,
+        hooks: Arc<tokio::sync::Mutex<GitHooksManager>>,
+        integrator: Arc<tokio::sync::Mutex<GitWorkflowIntegrator>>,
+    ) -> Self {
+        Self { repository_path, hooks, integrator }
+    }
+}
+
+#[async_trait::async_trait]
+impl PrePushSource for PrePushGate {
+    async fn start_pre_push(&self, remote_ref: &str) -> Result<Option<String>> {
+        let mut hooks = self.hooks.lock().await;
+        let mut integrator = self.integrator.lock().await;
+        let record = hooks
+            .pre_push_ceremony(&self.repository_path, &[remote_ref.to_string()], &mut integrator, None)
+            .await?;
+        Ok(record.ceremony_id)
+    }
+    
+    async fn pre_push_status(&self, ceremony_id: &str) -> Option<PrePushStatus> {
+        let hooks = self.hooks.lock().await;
+        let integrator = self.integrator.lock().await;
+        hooks.pre_push_status(&integrator, ceremony_id)
+    }
+}
+
+/// Quote a string for a POSIX shell
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Body of the pre-push ceremony hook
+///
+/// Git feeds the hook one `<local ref> <local sha> <remote ref> <remote sha>`
+/// line per pushed ref on stdin. Each gated ref is sent to the node's
+/// [`pre_push_router`](crate::http::pre_push_router), which answers with a
+/// ceremony ID (or nothing if the ref bypasses the ceremony) that is then
+/// polled until decided. Only an unreachable node (curl failing to resolve
+/// or connect) lets the push through without a ceremony; any other failure
+/// to start one, such as an HTTP error, blocks it.
+fn pre_push_script(config: &PrePushHookConfig) -> String {
+    let mut script = format!(r#"
+# WeaveMesh Pre-push Ceremony Hook
+# Blocks the push until a Sacred Alliance ceremony approves it
+
+WEAVEMESH_URL="${{WEAVEMESH_URL:-}}"
+[ -n "$WEAVEMESH_URL" ] || WEAVEMESH_URL={endpoint}
+TIMEOUT={timeout}
+POLL_INTERVAL=2
+if ! command -v curl >/dev/null 2>&1; then
+    echo "Warning: curl not found, pushing without a ceremony" >&2
+    exit 0
+fi
+
+while read -r local_ref local_sha remote_ref remote_sha; do
+"#,
+        endpoint = shell_quote(config.endpoint.trim_end_matches('/')),
+        timeout = config.approval_timeout_seconds,
+    );
+    if !config.skip_branches.is_empty() {
+        let patterns: Vec<String> = config.skip_branches.iter()
+            .map(|pattern| pattern.split('*').map(shell_quote).collect::<Vec<_>>().join("*"))
+            .collect();
+        script.push_str(&format!("    case \"$remote_ref\" in\n        {}) continue ;;\n    esac\n", patterns.join("|")));
+    }
+    script.push_str(r#"    ceremony=$(curl -fsS -X POST --data-urlencode "remote_ref=$remote_ref" "$WEAVEMESH_URL/git/pre-push")
+    started=$?
+    case "$started" in
+        0) ;;
+        6|7)
+            echo "Warning: no WeaveMesh node at $WEAVEMESH_URL, pushing $remote_ref without a ceremony" >&2
+            continue
+            ;;
+        *)
+            echo "Could not start a ceremony for $remote_ref at $WEAVEMESH_URL (curl exit $started)" >&2
+            exit 1
+            ;;
+    esac
+    [ -z "$ceremony" ] && continue
+    waited=0
+    while :; do
+        status=$(curl -fsS "$WEAVEMESH_URL/git/pre-push/$ceremony") || status=unreachable
+        case "$status" in
+            approved) break ;;
+            pending) ;;
+            *)
+                echo "Push of $remote_ref was not approved by ceremony $ceremony ($status)" >&2
+                exit 1
+                ;;
+        esac
+        if [ "$waited" -ge "$TIMEOUT" ]; then
+            echo "Push of $remote_ref timed out waiting for ceremony $ceremony" >&2
+            exit 1
+        fi
+        sleep "$POLL_INTERVAL"
+        waited=$((waited + POLL_INTERVAL))
+    done
+done
+
+exit 0
+"#);
+    script
+}
+
 /// Statistics about git hooks
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookStatistics {
@@ -677,4 +986,150 @@ mod tests {
         assert!(hook.config.enabled);
         assert_eq!(hook.interpreter, HookInterpreter::Shell);
     }
+    
+    fn pre_push_config() -> PrePushHookConfig {
+        PrePushHookConfig {
+            required_approvers: vec!["alice".to_string(), "bob".to_string()],
+            ceremony_type: CeremonyType::SecurityReview,
+            skip_branches: vec!["refs/heads/draft/*".to_string()],
+            endpoint: default_pre_push_endpoint(),
+            approval_timeout_seconds: 0,
+        }
+    }
+    
+    /// Ceremonies decided up front: `main` is rejected, `release` approved
+    /// and anything else stays pending
+    struct FixedCeremonies;
+    
+    #[async_trait::async_trait]
+    impl PrePushSource for FixedCeremonies {
+        async fn start_pre_push(&self, remote_ref: &str) -> Result<Option<String>> {
+            Ok(Some(remote_ref.trim_start_matches("refs/heads/").to_string()))
+        }
+        
+        async fn pre_push_status(&self, ceremony_id: &str) -> Option<PrePushStatus> {
+            Some(match ceremony_id {
+                "main" => PrePushStatus::Rejected,
+                "release" => PrePushStatus::Approved,
+                _ => PrePushStatus::Pending,
+            })
+        }
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_pre_push_hook_script() {
+        use std::io::Write;
+        use std::process::Stdio;
+        
+        let repo = tempfile::tempdir().unwrap();
+        let mut manager = GitHooksManager::new(&GitManagerConfig::default()).unwrap();
+        manager.install_pre_push_hook(repo.path(), pre_push_config()).await.unwrap();
+        let hook_path = repo.path().join(".git/hooks/pre-push");
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let router = crate::http::pre_push_router(Arc::new(FixedCeremonies));
+        let server = tokio::spawn(async move { axum::serve(listener, router).await });
+        
+        // The hook blocks on curl, so it runs off the runtime serving it
+        let push = |url: String, remote_ref: &'static str| {
+            let hook_path = hook_path.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut child = Command::new(&hook_path)
+                    .arg("origin")
+                    .env("WEAVEMESH_URL", url)
+                    .stdin(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .unwrap();
+                writeln!(child.stdin.take().unwrap(), "refs/heads/x abc123 {} 000000", remote_ref).unwrap();
+                child.wait_with_output().unwrap()
+            })
+        };
+        
+        assert!(push(url.clone(), "refs/heads/release").await.unwrap().status.success());
+        let rejected = push(url.clone(), "refs/heads/main").await.unwrap();
+        assert!(!rejected.status.success());
+        assert!(String::from_utf8_lossy(&rejected.stderr).contains("not approved"));
+        let pending = push(url.clone(), "refs/heads/feature").await.unwrap();
+        assert!(!pending.status.success());
+        assert!(String::from_utf8_lossy(&pending.stderr).contains("timed out"));
+        assert!(push(url.clone(), "refs/heads/draft/spike").await.unwrap().status.success());
+        
+        // A node answering with an HTTP error blocks the push
+        let failed = push(format!("{}/missing", url), "refs/heads/main").await.unwrap();
+        assert!(!failed.status.success());
+        assert!(String::from_utf8_lossy(&failed.stderr).contains("curl exit 22"));
+        
+        server.abort();
+        let unreachable = push("http://127.0.0.1:9".to_string(), "refs/heads/main").await.unwrap();
+        assert!(unreachable.status.success());
+        assert!(String::from_utf8_lossy(&unreachable.stderr).contains("Warning"));
+    }
+    
+    #[tokio::test]
+    async fn test_pre_push_ceremony_links_record_to_ceremony() {
+        use super::super::workflow_integration::CeremonyOutcome;
+        
+        let repo = tempfile::tempdir().unwrap();
+        let mut manager = GitHooksManager::new(&GitManagerConfig::default()).unwrap();
+        let mut integrator = GitWorkflowIntegrator::new(&GitManagerConfig::default()).unwrap();
+        let refs = |r: &[&str]| r.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        
+        assert!(manager.pre_push_ceremony(repo.path(), &refs(&["refs/heads/main"]), &mut integrator, None).await.is_err());
+        manager.install_pre_push_hook(repo.path(), pre_push_config()).await.unwrap();
+        
+        let skipped = manager.pre_push_ceremony(repo.path(), &refs(&["refs/heads/draft/a"]), &mut integrator, None).await.unwrap();
+        assert_eq!(skipped.status, HookExecutionStatus::Skipped);
+        assert_eq!(skipped.ceremony_id, None);
+        
+        let record = manager.pre_push_ceremony(
+            repo.path(),
+            &refs(&["refs/heads/draft/a", "refs/heads/main"]),
+            &mut integrator,
+            None,
+        ).await.unwrap();
+        assert_eq!(record.status, HookExecutionStatus::Running);
+        assert_eq!(record.context.branch_name.as_deref(), Some("main"));
+        let ceremony_id = record.ceremony_id.clone().unwrap();
+        let ceremony = integrator.get_ceremony(&ceremony_id).unwrap();
+        assert_eq!(ceremony.ceremony_type, CeremonyType::SecurityReview);
+        assert_eq!(ceremony.participants, vec!["alice", "bob"]);
+        assert!(!manager.pre_push_approved(&integrator, &ceremony_id));
+        assert_eq!(manager.pre_push_status(&integrator, &ceremony_id), Some(PrePushStatus::Pending));
+        assert_eq!(manager.pre_push_status(&integrator, "no-such-ceremony"), None);
+        
+        integrator.record_outcome(&ceremony_id, CeremonyOutcome {
+            outcome_id: "approve".to_string(),
+            outcome_type: OutcomeType::Proceed,
+            description: "Looks good".to_string(),
+            agreed_participants: vec!["alice".to_string(), "bob".to_string()],
+            disagreed_participants: Vec::new(),
+            confidence: 0.9,
+            actions: Vec::new(),
+            timestamp: Utc::now(),
+        }).unwrap();
+        integrator.update_ceremony_status(&ceremony_id, CeremonyStatus::Completed).await.unwrap();
+        assert!(manager.pre_push_approved(&integrator, &ceremony_id));
+        assert_eq!(manager.pre_push_status(&integrator, &ceremony_id), Some(PrePushStatus::Approved));
+        assert_eq!(manager.get_hook_statistics().total_executions, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_pre_push_gate_starts_and_reports_ceremonies() {
+        let repo = tempfile::tempdir().unwrap();
+        let mut manager = GitHooksManager::new(&GitManagerConfig::default()).unwrap();
+        manager.install_pre_push_hook(repo.path(), pre_push_config()).await.unwrap();
+        let hooks = Arc::new(tokio::sync::Mutex::new(manager));
+        let integrator = Arc::new(tokio::sync::Mutex::new(GitWorkflowIntegrator::new(&GitManagerConfig::default()).unwrap()));
+        let gate = PrePushGate::new(repo.path().to_path_buf(), hooks, integrator.clone());
+        
+        assert_eq!(gate.start_pre_push("refs/heads/draft/a").await.unwrap(), None);
+        let ceremony_id = gate.start_pre_push("refs/heads/main").await.unwrap().unwrap();
+        assert_eq!(gate.pre_push_status(&ceremony_id).await, Some(PrePushStatus::Pending));
+        
+        integrator.lock().await.update_ceremony_status(&ceremony_id, CeremonyStatus::Cancelled).await.unwrap();
+        assert_eq!(gate.pre_push_status(&ceremony_id).await, Some(PrePushStatus::Rejected));
+    }
 }
@@ -10,9 +10,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn, error};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::attribution::{Attribution, AttributionContext, BasicAttributionEngine, CollaborationType};
+use crate::compute::ComputePool;
+use crate::sacred_alliance::BasicCeremonyAction;
+use crate::security::{MemorySecurityAuditor, SecurityAuditor, SecurityEvent};
 
 pub mod operations;
 pub mod repository;
@@ -25,10 +29,19 @@ pub mod state_tracking;
 // Re-export key types for easier access
 pub use operations::{GitOperationsHandler, GitOperationsConfig, GitOperationResult, GitOperationMetrics};
 pub use repository::{RepositoryTracker, TrackedRepository, RepositoryState, RepositoryHealth};
-pub use attribution_integration::{GitAttributionEngine, GitAttributionContext};
-pub use workflow_integration::{GitWorkflowIntegrator, GitCeremony, CeremonyType, CeremonyStatus};
-pub use conflict_detection::{GitConflictDetector, GitConflict, ConflictSeverity, ConflictType};
-pub use hooks::{GitHooksManager, GitHook, GitHookType, HookExecutionRecord};
+pub use attribution_integration::{
+    GitAttributionEngine, GitAttributionContext, HistoryImportOptions, ImportProgress, ImportReport,
+    AttributionReport, HumanContributionSummary, AIContributionSummary, CollaborationEvent,
+};
+pub use workflow_integration::{GitWorkflowIntegrator, GitCeremony, CeremonyType, CeremonyStatus, BranchPolicy};
+pub use conflict_detection::{
+    GitConflictDetector, GitConflict, ConflictSeverity, ConflictType, MergePreview, FileChangeStats,
+    ResolutionStep, StepType, ConflictHeatmapConfig, ConflictHeatmapEntry, HOT_FILE_REVIEW_ACTION,
+};
+pub use hooks::{
+    GitHooksManager, GitHook, GitHookType, HookExecutionRecord, PrePushHookConfig, PrePushGate, PrePushSource,
+    PrePushStatus,
+};
 pub use state_tracking::{GitStateTracker, StateChangeEvent, StateChangeType};
 
 /// Git integration manager for WeaveMesh Core
@@ -49,6 +62,10 @@ pub struct GitManager {
     config: GitManagerConfig,
     /// Active repository sessions
     active_sessions: HashMap<String, GitSession>,
+    /// Audit log for policy violations
+    security_auditor: Box<dyn SecurityAuditor + Send + Sync>,
+    /// Pool merge previews run on, off the async runtime
+    compute_pool: ComputePool,
 }
 
 /// Configuration for git manager
@@ -70,6 +87,13 @@ pub struct GitManagerConfig {
     pub enable_ceremony_integration: bool,
     /// Enable attribution tracking
     pub enable_attribution_tracking: bool,
+    /// Completed operations kept per session; the oldest are dropped first
+    #[serde(default = "default_session_history_limit")]
+    pub session_history_limit: usize,
+}
+
+fn default_session_history_limit() -> usize {
+    100
 }
 
 impl Default for GitManagerConfig {
@@ -83,6 +107,7 @@ impl Default for GitManagerConfig {
             enable_auto_conflict_resolution: true,
             enable_ceremony_integration: true,
             enable_attribution_tracking: true,
+            session_history_limit: default_session_history_limit(),
         }
     }
 }
@@ -108,6 +133,9 @@ pub struct GitSession {
     pub state: GitSessionState,
     /// Active operations
     pub active_operations: Vec<GitOperation>,
+    /// Finished operations with their results, oldest first
+    #[serde(default)]
+    pub operation_history: Vec<GitOperation>,
     /// Session metadata
     pub metadata: HashMap<String, String>,
 }
@@ -152,6 +180,80 @@ pub struct GitOperation {
     pub attribution: Option<Attribution>,
     /// Ceremony associated with operation
     pub ceremony_id: Option<String>,
+    /// How to revert the operation, if it can be reverted
+    #[serde(default)]
+    pub undo: Option<UndoMetadata>,
+}
+
+/// Repository state needed to revert an operation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UndoMetadata {
+    /// HEAD before a commit, merge or rebase
+    PreviousHead { oid: String },
+    /// Stash commit created by a stash
+    Stash { stash_oid: String },
+    /// Branch checked out before a branch switch
+    PreviousBranch { branch: String },
+}
+
+impl GitSession {
+    /// Encode the whole session for persistence outside the manager
+    pub fn serialize_checkpoint(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+    
+    /// Whether the session holds its repository, so no other session may reattach to it
+    fn holds_repository(&self) -> bool {
+        !matches!(self.state, GitSessionState::Paused | GitSessionState::Terminating | GitSessionState::Ended)
+    }
+}
+
+impl GitOperation {
+    /// Whether the operation changed state shared with others and cannot be taken back
+    pub fn is_irreversible(&self) -> bool {
+        matches!(self.operation_type, GitOperationType::Push)
+    }
+    
+    /// Git steps that revert this operation, for the user to run
+    pub fn undo_steps(&self) -> Result<Vec<ResolutionStep>> {
+        if self.is_irreversible() {
+            return Err(anyhow::anyhow!(
+                "{:?} {} changed a shared remote and cannot be undone", self.operation_type, self.operation_id
+            ));
+        }
+        let undo = self.undo.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{:?} {} cannot be undone", self.operation_type, self.operation_id))?;
+        
+        let commands: Vec<(String, String)> = match (&self.operation_type, undo) {
+            (GitOperationType::Commit, UndoMetadata::PreviousHead { oid }) => vec![(
+                format!("Move HEAD back to {}, keeping the committed changes staged", oid),
+                format!("reset --soft {}", oid),
+            )],
+            (_, UndoMetadata::PreviousHead { oid }) => vec![(
+                format!("Move HEAD back to {}, keeping uncommitted local changes", oid),
+                format!("reset --keep {}", oid),
+            )],
+            (_, UndoMetadata::Stash { stash_oid }) => vec![
+                (format!("Restore the changes stashed in {}", stash_oid), format!("stash apply {}", stash_oid)),
+                ("Drop the restored stash entry".to_string(), "stash drop stash@{0}".to_string()),
+            ],
+            (_, UndoMetadata::PreviousBranch { branch }) => vec![(
+                format!("Check out {} again", branch),
+                format!("checkout {}", branch),
+            )],
+        };
+        
+        Ok(commands.into_iter().enumerate()
+            .map(|(index, (description, command))| ResolutionStep {
+                step_id: Uuid::new_v4().to_string(),
+                description,
+                step_type: StepType::GitCommand,
+                parameters: HashMap::from([("command".to_string(), command)]),
+                order: index + 1,
+                optional: false,
+            })
+            .collect())
+    }
 }
 
 /// Types of git operations
@@ -229,10 +331,13 @@ impl GitManager {
             state_tracker,
             config,
             active_sessions: HashMap::new(),
+            security_auditor: Box::new(MemorySecurityAuditor::default()),
+            compute_pool: ComputePool::default(),
         })
     }
     
     /// Start a new git session for a repository
+    #[tracing::instrument(skip(self))]
     pub async fn start_session(&mut self, repository_path: &Path, owner_id: &str) -> Result<GitSession> {
         info!("Starting git session for repository: {:?}", repository_path);
         
@@ -252,6 +357,7 @@ impl GitManager {
             last_activity: Utc::now(),
             state: GitSessionState::Active,
             active_operations: Vec::new(),
+            operation_history: Vec::new(),
             metadata: HashMap::new(),
         };
         
@@ -272,18 +378,20 @@ impl GitManager {
         debug!("Performing git operation: {:?} for session: {}", operation_type, session_id);
         
         // Clone session data to avoid borrowing conflicts
-        let (repository_path, session_attribution) = {
+        let (repository_path, owner_id, session_attribution) = {
             let session = self.active_sessions.get(session_id)
                 .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
-            (session.repository_path.clone(), attribution.clone())
+            (session.repository_path.clone(), session.owner_id.clone(), attribution.clone())
         };
         
         // Create attribution context for this operation
-        let attribution_context = GitAttributionContext::from_git_operation(
+        let operation_id = Uuid::new_v4().to_string();
+        let mut attribution_context = GitAttributionContext::from_git_operation(
             &operation_type,
             &parameters,
             &repository_path,
         );
+        attribution_context.operation_id = Some(operation_id.clone());
         
         // Analyze attribution if enabled
         let analyzed_attribution = if self.config.enable_attribution_tracking {
@@ -298,14 +406,26 @@ impl GitManager {
             session_attribution
         };
         
+        // Branch policies take precedence over the built-in ceremony rules
+        let policy_pending = self.workflow_integrator
+            .ceremony_for_branch_strategy(&operation_type, &parameters)
+            .map(|(pattern, policy)| {
+                (pattern.to_string(), !self.workflow_integrator.is_policy_satisfied(policy, &parameters))
+            });
+        
         // Check if ceremony is required
-        let ceremony_required = if self.config.enable_ceremony_integration {
-            self.workflow_integrator.is_ceremony_required(&operation_type, &parameters).await?
-        } else {
-            false
+        let ceremony_required = match (&policy_pending, self.config.enable_ceremony_integration) {
+            (Some((_, pending)), true) => *pending,
+            (None, true) => self.workflow_integrator.is_ceremony_required(&operation_type, &parameters).await?,
+            (_, false) => false,
         };
         
-        let operation_id = Uuid::new_v4().to_string();
+        if let Some((pattern, true)) = &policy_pending {
+            if !ceremony_required {
+                self.record_policy_violation(&owner_id, pattern, &operation_type, &parameters);
+            }
+        }
+        
         let mut operation = GitOperation {
             operation_id: operation_id.clone(),
             operation_type: operation_type.clone(),
@@ -320,6 +440,7 @@ impl GitManager {
             result: None,
             attribution: analyzed_attribution.clone(),
             ceremony_id: None,
+            undo: None,
         };
         
         // If ceremony is required, initiate it
@@ -332,18 +453,198 @@ impl GitManager {
             operation.ceremony_id = Some(ceremony_id);
         } else {
             // Perform the operation immediately
+            let before = self.capture_undo_state(&repository_path, &operation.operation_type).await;
             operation = self.execute_git_operation(&repository_path, operation).await?;
+            if operation.result.as_ref().is_some_and(|result| result.success) {
+                operation.undo = Self::undo_metadata(&repository_path, &operation.operation_type, before);
+            }
         }
         
-        // Update session with the operation
+        // Update session with the operation; finished ones go to the history
         if let Some(session) = self.active_sessions.get_mut(session_id) {
-            session.active_operations.push(operation.clone());
+            match operation.status {
+                GitOperationStatus::Completed | GitOperationStatus::Failed | GitOperationStatus::Cancelled => {
+                    if let (GitOperationType::SwitchBranch, Some(UndoMetadata::PreviousBranch { .. })) =
+                        (&operation.operation_type, &operation.undo)
+                    {
+                        if let Some(name) = operation.parameters.get("name") {
+                            session.current_branch = name.clone();
+                        }
+                    }
+                    session.operation_history.push(operation.clone());
+                    let excess = session.operation_history.len().saturating_sub(self.config.session_history_limit);
+                    session.operation_history.drain(..excess);
+                }
+                _ => session.active_operations.push(operation.clone()),
+            }
             session.last_activity = Utc::now();
         }
         
         Ok(operation)
     }
     
+    /// Contributions to the session's repository since `since`
+    ///
+    /// Walks the commits reachable from HEAD and joins each to the attribution
+    /// record of the session operation that made it, by operation ID. Commits
+    /// made outside the session count towards the totals but not towards
+    /// coverage.
+    pub fn attribution_report(&self, session_id: &str, since: DateTime<Utc>) -> Result<AttributionReport> {
+        let session = self.active_sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        let operations_by_commit: HashMap<&str, &str> = session.operation_history.iter()
+            .filter_map(|operation| {
+                let commit_hash = operation.result.as_ref()?.commit_hash.as_deref()?;
+                Some((commit_hash, operation.operation_id.as_str()))
+            })
+            .collect();
+        
+        let repo = git2::Repository::open(&session.repository_path)?;
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+        revwalk.push_head()?;
+        
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            let timestamp = match DateTime::from_timestamp(commit.time().seconds(), 0) {
+                Some(time) if time >= since => time,
+                _ => continue,
+            };
+            let parent_tree = match commit.parent_count() {
+                0 => None,
+                _ => Some(commit.parent(0)?.tree()?),
+            };
+            let stats = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?.stats()?;
+            
+            let commit_hash = commit.id().to_string();
+            let attribution = operations_by_commit.get(commit_hash.as_str())
+                .and_then(|operation_id| self.attribution_engine.record_for_operation(operation_id))
+                .map(|record| (record.operation_id.clone().unwrap_or_default(), record.attribution.clone()));
+            commits.push(attribution_integration::ReportCommit {
+                commit_hash,
+                timestamp,
+                lines_changed: stats.insertions() + stats.deletions(),
+                attribution,
+            });
+        }
+        
+        Ok(AttributionReport::from_commits(&commits))
+    }
+    
+    /// Hot files of the session's repository, most conflicted first
+    ///
+    /// Looks back over the heatmap window configured for the conflict
+    /// detector. Unknown sessions have no hot files.
+    pub fn report_hot_files(&self, session_id: &str) -> Vec<String> {
+        self.hot_file_entries(session_id)
+            .into_iter()
+            .map(|entry| entry.file_path)
+            .collect()
+    }
+    
+    /// Review ceremonies recommended for the hot files of a session's repository
+    ///
+    /// Handed to the IDE so opening one of these files surfaces the recommendation.
+    pub fn hot_file_recommendations(&self, session_id: &str) -> Vec<BasicCeremonyAction> {
+        self.hot_file_entries(session_id)
+            .iter()
+            .filter_map(ConflictHeatmapEntry::recommendation)
+            .collect()
+    }
+    
+    fn hot_file_entries(&self, session_id: &str) -> Vec<ConflictHeatmapEntry> {
+        let session = match self.active_sessions.get(session_id) {
+            Some(session) => session,
+            None => return Vec::new(),
+        };
+        let since = Utc::now() - chrono::Duration::days(self.conflict_detector.heatmap_config().window_days);
+        self.conflict_detector.hot_files(&session.repository_path, since)
+    }
+    
+    /// Finished operations of a session, oldest first
+    pub fn get_session_history(&self, session_id: &str) -> Option<&[GitOperation]> {
+        self.active_sessions.get(session_id).map(|session| session.operation_history.as_slice())
+    }
+    
+    /// Git steps reverting the most recent reversible operation of a session
+    ///
+    /// The steps are not run. Operations without undo metadata are skipped;
+    /// a push reached first is refused, since what it published cannot be
+    /// taken back and older operations are now shared.
+    pub fn suggest_undo(&self, session_id: &str) -> Result<Vec<ResolutionStep>> {
+        let history = self.get_session_history(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        let operation = history.iter().rev()
+            .filter(|operation| operation.status == GitOperationStatus::Completed)
+            .find(|operation| operation.is_irreversible() || operation.undo.is_some())
+            .ok_or_else(|| anyhow::anyhow!("Nothing to undo in session {}", session_id))?;
+        operation.undo_steps()
+    }
+    
+    /// HEAD or branch to record before an operation, if it can be reverted
+    async fn capture_undo_state(&self, repository_path: &Path, operation_type: &GitOperationType) -> Option<String> {
+        match operation_type {
+            GitOperationType::Commit | GitOperationType::Merge | GitOperationType::Rebase => {
+                let repo = git2::Repository::open(repository_path).ok()?;
+                let head = repo.head().ok()?.peel_to_commit().ok()?;
+                Some(head.id().to_string())
+            }
+            GitOperationType::SwitchBranch => self.operations_handler.get_current_branch(repository_path).await.ok(),
+            _ => None,
+        }
+    }
+    
+    /// Undo metadata of a successful operation from the state captured before it
+    fn undo_metadata(repository_path: &Path, operation_type: &GitOperationType, before: Option<String>) -> Option<UndoMetadata> {
+        match operation_type {
+            GitOperationType::Commit | GitOperationType::Merge | GitOperationType::Rebase => {
+                before.map(|oid| UndoMetadata::PreviousHead { oid })
+            }
+            GitOperationType::SwitchBranch => before.map(|branch| UndoMetadata::PreviousBranch { branch }),
+            GitOperationType::Stash => {
+                let repo = git2::Repository::open(repository_path).ok()?;
+                let stash_oid = repo.refname_to_id("refs/stash").ok()?;
+                Some(UndoMetadata::Stash { stash_oid: stash_oid.to_string() })
+            }
+            _ => None,
+        }
+    }
+    
+    /// Preview merging `source_ref` into `target_ref` without modifying the repository
+    ///
+    /// Reports would-be conflicts, changed-file statistics and whether the
+    /// merge would have to wait for a ceremony.
+    pub async fn preview_merge(&mut self, session_id: &str, source_ref: &str, target_ref: &str) -> Result<MergePreview> {
+        let repository_path = self.active_sessions.get(session_id)
+            .map(|session| session.repository_path.clone())
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        
+        let mut preview = self.conflict_detector
+            .preview_merge_on(&self.compute_pool, &CancellationToken::new(), &repository_path, source_ref, target_ref)
+            .await?;
+        
+        if self.config.enable_ceremony_integration {
+            let mut parameters = HashMap::new();
+            parameters.insert("source_branch".to_string(), source_ref.to_string());
+            parameters.insert("target_branch".to_string(), target_ref.to_string());
+            parameters.insert("files".to_string(), preview.changed_files.iter()
+                .map(|f| f.path.as_str())
+                .collect::<Vec<_>>()
+                .join(","));
+            if !preview.is_clean {
+                parameters.insert("conflict_details".to_string(), format!("{} conflicts", preview.conflicts.len()));
+            }
+            
+            preview.ceremony_required = match self.workflow_integrator.ceremony_for_branch_strategy(&GitOperationType::Merge, &parameters) {
+                Some(_) => true,
+                None => self.workflow_integrator.is_ceremony_required(&GitOperationType::Merge, &parameters).await?,
+            };
+        }
+        
+        Ok(preview)
+    }
+    
     /// Execute a git operation
     async fn execute_git_operation(&mut self, repository_path: &Path, mut operation: GitOperation) -> Result<GitOperation> {
         operation.status = GitOperationStatus::Running;
@@ -428,6 +729,53 @@ impl GitManager {
         Ok(operation)
     }
     
+    /// Record an operation that proceeded without the ceremony its branch policy requires
+    fn record_policy_violation(
+        &mut self,
+        owner_id: &str,
+        pattern: &str,
+        operation_type: &GitOperationType,
+        parameters: &HashMap<String, String>,
+    ) {
+        let branch = parameters.get("target_branch")
+            .or_else(|| parameters.get("branch"))
+            .map(|b| b.as_str())
+            .unwrap_or("unknown");
+        warn!("{:?} to {} bypassed branch policy {}", operation_type, branch, pattern);
+        
+        self.security_auditor.record_event(SecurityEvent::PolicyViolation {
+            user_id: Some(owner_id.to_string()),
+            policy: format!("branch:{}", pattern),
+            details: format!("{:?} to {} without required ceremony", operation_type, branch),
+            timestamp: Utc::now().timestamp(),
+        });
+    }
+    
+    /// Get the workflow integrator
+    pub fn workflow_integrator(&self) -> &GitWorkflowIntegrator {
+        &self.workflow_integrator
+    }
+    
+    /// Get the workflow integrator for registering branch policies
+    pub fn workflow_integrator_mut(&mut self) -> &mut GitWorkflowIntegrator {
+        &mut self.workflow_integrator
+    }
+    
+    /// Run merge previews on `pool`, shared with the node's other heavy work
+    pub fn set_compute_pool(&mut self, pool: ComputePool) {
+        self.compute_pool = pool;
+    }
+    
+    /// Replace the security audit log
+    pub fn set_security_auditor(&mut self, auditor: Box<dyn SecurityAuditor + Send + Sync>) {
+        self.security_auditor = auditor;
+    }
+    
+    /// Get the security audit log
+    pub fn security_auditor(&self) -> &dyn SecurityAuditor {
+        self.security_auditor.as_ref()
+    }
+    
     /// Check if operation can proceed with existing conflicts
     fn can_proceed_with_conflicts(&self, operation_type: &GitOperationType, conflicts: &[GitConflict]) -> bool {
         match operation_type {
@@ -447,6 +795,77 @@ impl GitManager {
         self.active_sessions.values().collect()
     }
     
+    /// Hand an in-progress session to a reconnecting client
+    ///
+    /// Paused sessions, and sessions interrupted mid-operation, become active
+    /// again; operations that were still running are recorded as cancelled.
+    /// Fails for ended or terminating sessions, and when another session
+    /// holds the same repository.
+    pub fn reattach_session(&mut self, session_id: &str, new_owner_id: &str) -> Result<GitSession> {
+        let session = self.active_sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        if matches!(session.state, GitSessionState::Ended | GitSessionState::Terminating) {
+            return Err(anyhow::anyhow!("Session {} has ended", session_id));
+        }
+        self.check_repository_free(session)?;
+        
+        let history_limit = self.config.session_history_limit;
+        let session = self.active_sessions.get_mut(session_id).expect("session checked above");
+        if session.state == GitSessionState::Operating {
+            let now = Utc::now();
+            for mut operation in session.active_operations.drain(..) {
+                operation.status = GitOperationStatus::Cancelled;
+                operation.completed_at = Some(now);
+                session.operation_history.push(operation);
+            }
+            let excess = session.operation_history.len().saturating_sub(history_limit);
+            session.operation_history.drain(..excess);
+        }
+        if matches!(session.state, GitSessionState::Paused | GitSessionState::Operating) {
+            session.state = GitSessionState::Active;
+        }
+        session.owner_id = new_owner_id.to_string();
+        session.last_activity = Utc::now();
+        
+        info!("Git session {} reattached by {}", session_id, new_owner_id);
+        Ok(session.clone())
+    }
+    
+    /// Fail if a session other than `session` holds its repository
+    fn check_repository_free(&self, session: &GitSession) -> Result<()> {
+        match self.active_sessions.values().find(|other| {
+            other.session_id != session.session_id
+                && other.repository_path == session.repository_path
+                && other.holds_repository()
+        }) {
+            Some(other) => Err(anyhow::anyhow!(
+                "Repository {:?} is held by session {}", session.repository_path, other.session_id
+            )),
+            None => Ok(()),
+        }
+    }
+    
+    /// Take over a session saved with [`GitSession::serialize_checkpoint`]
+    ///
+    /// The session keeps its state; use [`reattach_session`](Self::reattach_session)
+    /// to resume it. Ended sessions, sessions already present, and sessions
+    /// holding a repository another session holds are refused.
+    pub fn restore_from_checkpoint(&mut self, data: &[u8]) -> Result<GitSession> {
+        let session: GitSession = serde_json::from_slice(data)?;
+        if session.state == GitSessionState::Ended {
+            return Err(anyhow::anyhow!("Session {} has ended", session.session_id));
+        }
+        if self.active_sessions.contains_key(&session.session_id) {
+            return Err(anyhow::anyhow!("Session {} is already running", session.session_id));
+        }
+        if session.holds_repository() {
+            self.check_repository_free(&session)?;
+        }
+        self.active_sessions.insert(session.session_id.clone(), session.clone());
+        info!("Git session restored from checkpoint: {}", session.session_id);
+        Ok(session)
+    }
+    
     /// End a git session
     pub async fn end_session(&mut self, session_id: &str) -> Result<()> {
         if let Some(mut session) = self.active_sessions.remove(session_id) {
@@ -462,7 +881,7 @@ impl GitManager {
         GitManagerStatistics {
             active_sessions: self.active_sessions.len(),
             total_operations: self.active_sessions.values()
-                .map(|s| s.active_operations.len())
+                .map(|s| s.active_operations.len() + s.operation_history.len())
                 .sum(),
             repositories_tracked: self.repository_tracker.get_repository_count(),
             conflicts_detected: self.conflict_detector.get_total_conflicts_detected(),
@@ -519,6 +938,7 @@ mod tests {
             last_activity: Utc::now(),
             state: GitSessionState::Active,
             active_operations: Vec::new(),
+            operation_history: Vec::new(),
             metadata: HashMap::new(),
         };
         
@@ -530,4 +950,245 @@ mod tests {
         session.state = GitSessionState::Ended;
         assert_eq!(session.state, GitSessionState::Ended);
     }
+    
+    fn checkpoint_session(session_id: &str, repository: &str, state: GitSessionState) -> Vec<u8> {
+        GitSession {
+            session_id: session_id.to_string(),
+            repository_id: "repo".to_string(),
+            repository_path: PathBuf::from(repository),
+            current_branch: "main".to_string(),
+            owner_id: "crashed-client".to_string(),
+            started_at: Utc::now(),
+            last_activity: Utc::now() - chrono::Duration::hours(1),
+            state,
+            active_operations: Vec::new(),
+            operation_history: Vec::new(),
+            metadata: HashMap::new(),
+        }.serialize_checkpoint().unwrap()
+    }
+    
+    #[test]
+    fn test_reattach_session_from_checkpoint() {
+        let mut manager = GitManager::new(GitManagerConfig::default()).unwrap();
+        let mut interrupted: GitSession = serde_json::from_slice(&checkpoint_session("s1", "/repo", GitSessionState::Operating)).unwrap();
+        interrupted.active_operations.push(GitOperation {
+            operation_id: "op".to_string(),
+            operation_type: GitOperationType::Commit,
+            status: GitOperationStatus::Running,
+            parameters: HashMap::new(),
+            started_at: Utc::now(),
+            completed_at: None,
+            result: None,
+            attribution: None,
+            ceremony_id: None,
+            undo: None,
+        });
+        manager.restore_from_checkpoint(&interrupted.serialize_checkpoint().unwrap()).unwrap();
+        assert!(manager.restore_from_checkpoint(&interrupted.serialize_checkpoint().unwrap()).is_err());
+        assert!(manager.restore_from_checkpoint(&checkpoint_session("s0", "/repo", GitSessionState::Ended)).is_err());
+        
+        let session = manager.reattach_session("s1", "new-client").unwrap();
+        assert_eq!(session.state, GitSessionState::Active);
+        assert_eq!(session.owner_id, "new-client");
+        assert!(session.last_activity > interrupted.last_activity);
+        assert!(session.active_operations.is_empty());
+        assert_eq!(session.operation_history[0].status, GitOperationStatus::Cancelled);
+        assert!(manager.reattach_session("missing", "new-client").is_err());
+        
+        // A paused session cannot take a repository another session holds
+        manager.restore_from_checkpoint(&checkpoint_session("s2", "/repo", GitSessionState::Paused)).unwrap();
+        let err = manager.reattach_session("s2", "other-client").unwrap_err();
+        assert!(err.to_string().contains("held by session s1"));
+        manager.restore_from_checkpoint(&checkpoint_session("s3", "/other", GitSessionState::Paused)).unwrap();
+        assert_eq!(manager.reattach_session("s3", "other-client").unwrap().state, GitSessionState::Active);
+        
+        manager.restore_from_checkpoint(&checkpoint_session("s4", "/third", GitSessionState::Terminating)).unwrap();
+        assert!(manager.reattach_session("s4", "other-client").is_err());
+        
+        // Nor can a restored session that would hold it straight away
+        for state in [GitSessionState::Active, GitSessionState::Operating] {
+            let err = manager.restore_from_checkpoint(&checkpoint_session("s5", "/repo", state)).unwrap_err();
+            assert!(err.to_string().contains("held by session s1"));
+        }
+        assert!(manager.get_session("s5").is_none());
+    }
+    
+    /// Run a suggested step with the git CLI
+    fn run_step(repo_path: &Path, step: &ResolutionStep) {
+        let command = &step.parameters["command"];
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .args(command.split_whitespace())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {} failed", command);
+    }
+    
+    #[tokio::test]
+    async fn test_session_history_and_undo() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        std::fs::write(dir.path().join("README.md"), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let initial = repo.commit(Some("refs/heads/main"), &signature, &signature, "initial", &tree, &[]).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.branch("feature", &repo.find_commit(initial).unwrap(), false).unwrap();
+        
+        let mut manager = GitManager::new(GitManagerConfig::default()).unwrap();
+        let session = manager.start_session(dir.path(), "dev").await.unwrap();
+        
+        std::fs::write(dir.path().join("notes.md"), "notes\n").unwrap();
+        let commit = manager.perform_operation(
+            &session.session_id,
+            GitOperationType::Commit,
+            HashMap::from([("message".to_string(), "Add notes".to_string())]),
+            None,
+        ).await.unwrap();
+        let switch = manager.perform_operation(
+            &session.session_id,
+            GitOperationType::SwitchBranch,
+            HashMap::from([("name".to_string(), "feature".to_string())]),
+            None,
+        ).await.unwrap();
+        
+        let history = manager.get_session_history(&session.session_id).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].operation_id, commit.operation_id);
+        assert_eq!(history[0].undo, Some(UndoMetadata::PreviousHead { oid: initial.to_string() }));
+        assert!(history[0].result.as_ref().unwrap().commit_hash.is_some());
+        assert_eq!(history[1].operation_id, switch.operation_id);
+        assert_eq!(history[1].undo, Some(UndoMetadata::PreviousBranch { branch: "main".to_string() }));
+        assert_eq!(manager.get_session(&session.session_id).unwrap().current_branch, "feature");
+        
+        // Undoing the switch checks main out again
+        for step in manager.suggest_undo(&session.session_id).unwrap() {
+            run_step(dir.path(), &step);
+        }
+        assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+        
+        // Undoing the commit moves main back, keeping the change staged
+        for step in history[0].undo_steps().unwrap() {
+            run_step(dir.path(), &step);
+        }
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), initial);
+        assert!(repo.statuses(None).unwrap().iter()
+            .any(|entry| entry.path() == Some("notes.md") && entry.status().is_index_new()));
+        
+        // Pushes are never undone
+        let push = GitOperation {
+            operation_type: GitOperationType::Push,
+            ..history[0].clone()
+        };
+        assert!(push.undo_steps().is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_branch_policy_waits_for_ceremony_or_records_violation() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        std::fs::write(dir.path().join("README.md"), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let initial = repo.commit(Some("refs/heads/main"), &signature, &signature, "initial", &tree, &[]).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.branch("feature", &repo.find_commit(initial).unwrap(), false).unwrap();
+        
+        let policy = BranchPolicy {
+            source_branches: Vec::new(),
+            target_branches: vec!["main".to_string()],
+            required_ceremony: CeremonyType::MergeDecision,
+            min_approvers: 2,
+        };
+        let merge = HashMap::from([
+            ("source_branch".to_string(), "feature".to_string()),
+            ("target_branch".to_string(), "main".to_string()),
+        ]);
+        
+        // With ceremonies enabled, the merge waits for one instead of running
+        let mut manager = GitManager::new(GitManagerConfig::default()).unwrap();
+        manager.workflow_integrator_mut().register_branch_policy("main", policy.clone());
+        let session = manager.start_session(dir.path(), "dev").await.unwrap();
+        let waiting = manager.perform_operation(&session.session_id, GitOperationType::Merge, merge.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(waiting.status, GitOperationStatus::WaitingForCeremony);
+        assert!(waiting.ceremony_id.is_some());
+        assert!(waiting.result.is_none());
+        assert!(manager.security_auditor().get_user_events("dev").is_empty());
+        assert_eq!(repo.head().unwrap().peel_to_commit().unwrap().id(), initial);
+        
+        // Without them, the merge goes ahead and the bypass is audited
+        let mut manager = GitManager::new(GitManagerConfig {
+            enable_ceremony_integration: false,
+            ..GitManagerConfig::default()
+        }).unwrap();
+        manager.workflow_integrator_mut().register_branch_policy("main", policy);
+        let session = manager.start_session(dir.path(), "dev").await.unwrap();
+        let bypassed = manager.perform_operation(&session.session_id, GitOperationType::Merge, merge, None)
+            .await
+            .unwrap();
+        assert_ne!(bypassed.status, GitOperationStatus::WaitingForCeremony);
+        assert!(bypassed.ceremony_id.is_none());
+        let events = manager.security_auditor().get_user_events("dev");
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            events[0],
+            SecurityEvent::PolicyViolation { policy, details, .. }
+                if policy == "branch:main" && details == "Merge to main without required ceremony"
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_attribution_report_joins_commits_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        std::fs::write(dir.path().join("README.md"), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[]).unwrap();
+        let since = Utc::now() - chrono::Duration::hours(1);
+        
+        let mut manager = GitManager::new(GitManagerConfig::default()).unwrap();
+        let session = manager.start_session(dir.path(), "dev").await.unwrap();
+        let commit = |message: &str, human: &str, ai: Option<&str>| {
+            let mut parameters = HashMap::from([
+                ("message".to_string(), message.to_string()),
+                ("human_contributor".to_string(), human.to_string()),
+            ]);
+            if let Some(ai) = ai {
+                parameters.insert("ai_contributor".to_string(), ai.to_string());
+            }
+            parameters
+        };
+        
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        manager.perform_operation(&session.session_id, GitOperationType::Commit, commit("Add a", "alice", Some("assistant")), None).await.unwrap();
+        std::fs::write(dir.path().join("b.rs"), "fn c() {}\n").unwrap();
+        manager.perform_operation(&session.session_id, GitOperationType::Commit, commit("Add b", "bob", None), None).await.unwrap();
+        
+        let report = manager.attribution_report(&session.session_id, since).unwrap();
+        assert_eq!(report.commits_analyzed, 3);
+        assert_eq!(report.total_lines_changed, 4);
+        // The initial commit predates the session and has no record
+        assert!((report.attribution_coverage_pct - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.human_contributions.iter().map(|h| (h.contributor.as_str(), h.lines_changed)).collect::<Vec<_>>(),
+            vec![("alice", 2), ("bob", 1)]);
+        assert_eq!(report.ai_contributions[0].human_partners, vec!["alice".to_string()]);
+        assert_eq!(report.collaboration_events.len(), 1);
+        
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("3 commits, 4 lines changed, 66.7% attributed"));
+        assert!(markdown.contains("| assistant | 1 | 2 | alice |"));
+        
+        let later = manager.attribution_report(&session.session_id, Utc::now() + chrono::Duration::hours(1)).unwrap();
+        assert_eq!((later.commits_analyzed, later.attribution_coverage_pct), (0, 0.0));
+    }
 }
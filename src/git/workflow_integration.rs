@@ -26,7 +26,9 @@ pub struct GitWorkflowIntegrator {
     /// Workflow patterns
     workflow_patterns: HashMap<GitOperationType, WorkflowPattern>,
     /// Sacred Alliance provider
-    sacred_alliance: Option<Box<dyn SacredAllianceProvider>>,
+    sacred_alliance: Option<Box<dyn SacredAllianceProvider + Send + Sync>>,
+    /// Branch policies in registration order, keyed by branch pattern
+    branch_policies: Vec<(String, BranchPolicy)>,
 }
 
 /// Configuration for git workflow integration
@@ -59,6 +61,22 @@ impl Default for GitWorkflowConfig {
     }
 }
 
+/// Branching convention enforced through a ceremony
+///
+/// Branch names in `source_branches` and `target_branches` may use `*` as a
+/// wildcard; an empty list matches any branch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BranchPolicy {
+    /// Branches the change comes from
+    pub source_branches: Vec<String>,
+    /// Branches the change goes into
+    pub target_branches: Vec<String>,
+    /// Ceremony that must complete before the operation proceeds
+    pub required_ceremony: CeremonyType,
+    /// Minimum participants that must agree in the ceremony
+    pub min_approvers: usize,
+}
+
 /// Git ceremony for collaborative decision making
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitCeremony {
@@ -322,11 +340,92 @@ impl GitWorkflowIntegrator {
             ceremony_history: Vec::new(),
             workflow_patterns,
             sacred_alliance: None,
+            branch_policies: Vec::new(),
+        })
+    }
+    
+    /// Register a branch policy
+    ///
+    /// `pattern` is matched against the branch an operation changes (the
+    /// target branch of a merge, the pushed branch of a push). Registering
+    /// the same pattern again replaces its policy.
+    pub fn register_branch_policy(&mut self, pattern: &str, policy: BranchPolicy) {
+        if let Some(existing) = self.branch_policies.iter_mut().find(|(p, _)| p == pattern) {
+            existing.1 = policy;
+        } else {
+            self.branch_policies.push((pattern.to_string(), policy));
+        }
+        info!("Registered branch policy for pattern: {}", pattern);
+    }
+    
+    /// Current branch policies in registration order
+    pub fn policies(&self) -> Vec<(&str, BranchPolicy)> {
+        self.branch_policies
+            .iter()
+            .map(|(pattern, policy)| (pattern.as_str(), policy.clone()))
+            .collect()
+    }
+    
+    /// Find the branch policy governing a git operation
+    ///
+    /// Only merges and pushes are governed by branch policies. The first
+    /// registered policy that matches wins.
+    pub fn ceremony_for_branch_strategy(
+        &self,
+        operation_type: &GitOperationType,
+        parameters: &HashMap<String, String>,
+    ) -> Option<(&str, &BranchPolicy)> {
+        let (source, target) = match operation_type {
+            GitOperationType::Merge => (
+                parameters.get("source_branch"),
+                parameters.get("target_branch"),
+            ),
+            GitOperationType::Push => (
+                parameters.get("source_branch").or_else(|| parameters.get("branch")),
+                parameters.get("branch"),
+            ),
+            _ => return None,
+        };
+        let target = target?;
+        
+        let matches_any = |patterns: &[String], branch: Option<&String>| {
+            patterns.is_empty()
+                || branch.is_some_and(|b| patterns.iter().any(|p| branch_matches(p, b)))
+        };
+        
+        self.branch_policies
+            .iter()
+            .find(|(pattern, policy)| {
+                branch_matches(pattern, target)
+                    && matches_any(&policy.source_branches, source)
+                    && matches_any(&policy.target_branches, Some(target))
+            })
+            .map(|(pattern, policy)| (pattern.as_str(), policy))
+    }
+    
+    /// Check whether the ceremony referenced by `parameters["ceremony_id"]` satisfies a policy
+    ///
+    /// The ceremony must have completed with a `Proceed` outcome agreed by at
+    /// least `min_approvers` participants.
+    pub fn is_policy_satisfied(&self, policy: &BranchPolicy, parameters: &HashMap<String, String>) -> bool {
+        let ceremony_id = match parameters.get("ceremony_id") {
+            Some(id) => id,
+            None => return false,
+        };
+        
+        self.ceremony_history.iter().any(|record| {
+            record.ceremony.ceremony_id == *ceremony_id
+                && record.ceremony.status == CeremonyStatus::Completed
+                && record.ceremony.ceremony_type == policy.required_ceremony
+                && record.final_outcome.as_ref().is_some_and(|outcome| {
+                    outcome.outcome_type == OutcomeType::Proceed
+                        && outcome.agreed_participants.len() >= policy.min_approvers
+                })
         })
     }
     
     /// Set Sacred Alliance provider
-    pub fn set_sacred_alliance_provider(&mut self, provider: Box<dyn SacredAllianceProvider>) {
+    pub fn set_sacred_alliance_provider(&mut self, provider: Box<dyn SacredAllianceProvider + Send + Sync>) {
         self.sacred_alliance = Some(provider);
         info!("Sacred Alliance provider configured for git workflow integration");
     }
@@ -379,10 +478,21 @@ impl GitWorkflowIntegrator {
         parameters: &HashMap<String, String>,
         attribution: &Option<Attribution>,
     ) -> Result<String> {
-        let ceremony_id = Uuid::new_v4().to_string();
-        
         // Determine ceremony type
         let ceremony_type = self.determine_ceremony_type(operation_type, parameters);
+        self.initiate_ceremony(ceremony_type, operation_type, parameters, Vec::new(), attribution).await
+    }
+    
+    /// Initiate a ceremony of a given type with known participants
+    pub async fn initiate_ceremony(
+        &mut self,
+        ceremony_type: CeremonyType,
+        operation_type: &GitOperationType,
+        parameters: &HashMap<String, String>,
+        participants: Vec<String>,
+        attribution: &Option<Attribution>,
+    ) -> Result<String> {
+        let ceremony_id = Uuid::new_v4().to_string();
         
         // Create ceremony context
         let context = GitCeremonyContext {
@@ -400,18 +510,24 @@ impl GitWorkflowIntegrator {
             required_expertise: self.determine_required_expertise(operation_type, parameters),
         };
         
+        let mut metadata = HashMap::new();
+        if let Some((pattern, policy)) = self.ceremony_for_branch_strategy(operation_type, parameters) {
+            metadata.insert("branch_policy".to_string(), pattern.to_string());
+            metadata.insert("min_approvers".to_string(), policy.min_approvers.to_string());
+        }
+        
         // Create ceremony
         let ceremony = GitCeremony {
             ceremony_id: ceremony_id.clone(),
             ceremony_type,
             triggering_operation: operation_type.clone(),
             status: CeremonyStatus::Initiating,
-            participants: Vec::new(),
+            participants,
             context,
             started_at: Utc::now(),
             ended_at: None,
             outcomes: Vec::new(),
-            metadata: HashMap::new(),
+            metadata,
         };
         
         // Store ceremony
@@ -428,6 +544,10 @@ impl GitWorkflowIntegrator {
     
     /// Determine ceremony type based on operation
     fn determine_ceremony_type(&self, operation_type: &GitOperationType, parameters: &HashMap<String, String>) -> CeremonyType {
+        if let Some((_, policy)) = self.ceremony_for_branch_strategy(operation_type, parameters) {
+            return policy.required_ceremony.clone();
+        }
+        
         match operation_type {
             GitOperationType::ConflictResolution => CeremonyType::ConflictResolution,
             GitOperationType::Merge => {
@@ -489,7 +609,7 @@ impl GitWorkflowIntegrator {
     async fn initiate_sacred_alliance_ceremony(
         &self,
         ceremony_id: &str,
-        sacred_alliance: &Box<dyn SacredAllianceProvider>,
+        sacred_alliance: &Box<dyn SacredAllianceProvider + Send + Sync>,
     ) -> Result<()> {
         // This would integrate with the Sacred Alliance system
         // For now, we'll create a placeholder implementation
@@ -610,6 +730,19 @@ impl GitWorkflowIntegrator {
         self.active_ceremonies.get(ceremony_id)
     }
     
+    /// Record of a finished ceremony
+    pub fn ceremony_record(&self, ceremony_id: &str) -> Option<&GitCeremonyRecord> {
+        self.ceremony_history.iter().find(|record| record.ceremony.ceremony_id == ceremony_id)
+    }
+    
+    /// Add an outcome to an active ceremony
+    pub fn record_outcome(&mut self, ceremony_id: &str, outcome: CeremonyOutcome) -> Result<()> {
+        let ceremony = self.active_ceremonies.get_mut(ceremony_id)
+            .ok_or_else(|| anyhow::anyhow!("Ceremony not active: {}", ceremony_id))?;
+        ceremony.outcomes.push(outcome);
+        Ok(())
+    }
+    
     /// Update ceremony status
     pub async fn update_ceremony_status(&mut self, ceremony_id: &str, status: CeremonyStatus) -> Result<()> {
         if let Some(ceremony) = self.active_ceremonies.get_mut(ceremony_id) {
@@ -709,6 +842,28 @@ impl GitWorkflowIntegrator {
     }
 }
 
+/// Match a branch name against a pattern where `*` matches any run of characters
+pub(super) fn branch_matches(pattern: &str, branch: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == branch;
+    }
+    
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !branch.starts_with(first) || branch.len() < first.len() + last.len() || !branch.ends_with(last) {
+        return false;
+    }
+    
+    let mut remaining = &branch[first.len()..branch.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 /// Statistics about git workflow integration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitWorkflowStatistics {
@@ -755,6 +910,44 @@ mod tests {
         assert_eq!(urgency, CeremonyUrgency::High);
     }
     
+    #[test]
+    fn test_branch_policy_matching() {
+        let mut integrator = GitWorkflowIntegrator::new(&GitManagerConfig::default()).unwrap();
+        integrator.register_branch_policy("main", BranchPolicy {
+            source_branches: vec!["feature/*".to_string()],
+            target_branches: Vec::new(),
+            required_ceremony: CeremonyType::ArchitectureReview,
+            min_approvers: 2,
+        });
+        assert_eq!(integrator.policies().len(), 1);
+        
+        let mut params = HashMap::new();
+        params.insert("source_branch".to_string(), "feature/login".to_string());
+        params.insert("target_branch".to_string(), "main".to_string());
+        
+        let (pattern, policy) = integrator.ceremony_for_branch_strategy(&GitOperationType::Merge, &params).unwrap();
+        assert_eq!(pattern, "main");
+        assert_eq!(policy.min_approvers, 2);
+        assert_eq!(
+            integrator.determine_ceremony_type(&GitOperationType::Merge, &params),
+            CeremonyType::ArchitectureReview
+        );
+        
+        params.insert("source_branch".to_string(), "hotfix/crash".to_string());
+        assert!(integrator.ceremony_for_branch_strategy(&GitOperationType::Merge, &params).is_none());
+        assert!(integrator.ceremony_for_branch_strategy(&GitOperationType::Commit, &params).is_none());
+        assert!(!integrator.is_policy_satisfied(policy, &params));
+    }
+    
+    #[test]
+    fn test_branch_matches() {
+        assert!(branch_matches("main", "main"));
+        assert!(!branch_matches("main", "maintenance"));
+        assert!(branch_matches("release/*", "release/1.0"));
+        assert!(branch_matches("*/fix-*", "team/fix-login"));
+        assert!(!branch_matches("release/*", "feature/release"));
+    }
+    
     #[test]
     fn test_ceremony_serialization() {
         let ceremony = GitCeremony {
@@ -4,12 +4,18 @@
 //! primitives that enable group-aware communication. Context-specific
 //! behaviors are implemented through plugins.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use ring::digest;
+use ring::signature::{UnparsedPublicKey, ED25519};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::key_rotation::{key_fingerprint, NodeKeys};
+use crate::mesh::admission::AdmissionRegistry;
+
 /// Unique identifier for a group
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct GroupId(String);
@@ -69,6 +75,9 @@ pub struct GroupPattern {
     pub include_history: bool,
     /// Maximum number of historical messages to include
     pub history_limit: Option<usize>,
+    /// How long matching groups keep messages for replay; forever when None
+    #[serde(default)]
+    pub history_retention: Option<Duration>,
 }
 
 impl GroupPattern {
@@ -78,6 +87,7 @@ impl GroupPattern {
             pattern: pattern.to_string(),
             include_history: false,
             history_limit: None,
+            history_retention: None,
         }
     }
     
@@ -87,9 +97,16 @@ impl GroupPattern {
             pattern: pattern.to_string(),
             include_history: true,
             history_limit: limit,
+            history_retention: None,
         }
     }
     
+    /// Keep messages of matching groups for `retention`
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.history_retention = Some(retention);
+        self
+    }
+    
     /// Check if a group ID matches this pattern
     pub fn matches(&self, group_id: &GroupId) -> bool {
         // Simple pattern matching - can be enhanced by plugins
@@ -122,6 +139,19 @@ pub struct Message {
     pub priority: MessagePriority,
     /// Whether this message requires acknowledgment
     pub requires_ack: bool,
+    /// Whether the message is live or replayed from history
+    #[serde(default)]
+    pub envelope: MessageEnvelope,
+}
+
+/// How a message reached its recipient
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum MessageEnvelope {
+    /// Delivered as it was sent
+    #[default]
+    Live,
+    /// Replayed from group history to a late joiner
+    Replay { replayed_at: DateTime<Utc> },
 }
 
 /// Message priority levels
@@ -152,6 +182,23 @@ pub struct MessageResponse {
     pub timestamp: DateTime<Utc>,
     /// Response type
     pub response_type: ResponseType,
+    /// Priority of the message responded to
+    #[serde(default)]
+    pub priority: MessagePriority,
+}
+
+impl MessageResponse {
+    /// Respond to `message`, at the message's priority
+    pub fn to(message: &Message, sender: &str, content: String, response_type: ResponseType) -> Self {
+        Self {
+            message_id: message.id.clone(),
+            content,
+            sender: sender.to_string(),
+            timestamp: Utc::now(),
+            response_type,
+            priority: message.priority.clone(),
+        }
+    }
 }
 
 /// Types of message responses
@@ -194,6 +241,40 @@ pub enum GroupRole {
     Custom(String),
 }
 
+impl GroupRole {
+    /// Rank for comparing roles; custom roles rank with members
+    fn rank(&self) -> u8 {
+        match self {
+            GroupRole::Observer => 0,
+            GroupRole::Member | GroupRole::Custom(_) => 1,
+            GroupRole::Moderator => 2,
+            GroupRole::Administrator => 3,
+        }
+    }
+}
+
+/// Role kept when a participant is in both groups being merged with different roles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeConflictResolution {
+    /// The higher of the two roles
+    HigherRole,
+    /// The lower of the two roles
+    LowerRole,
+    /// The role from the first group
+    KeepGroupA,
+}
+
+impl MergeConflictResolution {
+    fn resolve(&self, a: &GroupRole, b: &GroupRole) -> GroupRole {
+        let keep_a = match self {
+            MergeConflictResolution::HigherRole => a.rank() >= b.rank(),
+            MergeConflictResolution::LowerRole => a.rank() <= b.rank(),
+            MergeConflictResolution::KeepGroupA => true,
+        };
+        if keep_a { a.clone() } else { b.clone() }
+    }
+}
+
 /// Permissions within a group
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupPermissions {
@@ -256,6 +337,451 @@ pub struct GroupSyncState {
     pub state_checksum: String,
     /// Timestamp of last sync
     pub last_sync: DateTime<Utc>,
+    /// Membership epoch of the local roster
+    #[serde(default)]
+    pub membership_epoch: u64,
+    /// When the roster was last confirmed in sync with a peer or snapshot
+    #[serde(default)]
+    pub last_verified: Option<DateTime<Utc>>,
+}
+
+/// A change to a group's roster
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum MembershipChange {
+    /// Member joined with a role
+    Join { member: String, role: GroupRole },
+    /// Member left or was removed
+    Leave { member: String },
+    /// Member's role changed
+    RoleChange { member: String, role: GroupRole },
+}
+
+impl MembershipChange {
+    /// Member the change applies to
+    pub fn member(&self) -> &str {
+        match self {
+            MembershipChange::Join { member, .. } => member,
+            MembershipChange::Leave { member } => member,
+            MembershipChange::RoleChange { member, .. } => member,
+        }
+    }
+}
+
+/// Roster change broadcast to the group, tagged with its epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipDelta {
+    /// Group identifier
+    pub group_id: GroupId,
+    /// Epoch this change produced
+    pub epoch: u64,
+    /// Participant that made the change
+    pub actor: String,
+    /// The change itself
+    pub change: MembershipChange,
+    /// When the change was made
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A member's roster entry
+///
+/// Entries are kept as inactive after a member leaves, so concurrent
+/// changes to the same member can still be ordered by (epoch, actor).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RosterEntry {
+    /// Member's role
+    pub role: GroupRole,
+    /// Whether the member is currently in the group
+    pub active: bool,
+    /// Epoch of the change that last touched this entry
+    pub epoch: u64,
+    /// Actor of the change that last touched this entry
+    pub actor: String,
+    /// Whether the member is gone for good but keeps its place and role
+    #[serde(default)]
+    pub dormant: bool,
+}
+
+impl RosterEntry {
+    fn supersedes(&self, epoch: u64, actor: &str) -> bool {
+        (self.epoch, self.actor.as_str()) >= (epoch, actor)
+    }
+}
+
+/// Full roster of a group, used to repair members that missed deltas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipSnapshot {
+    /// Group identifier
+    pub group_id: GroupId,
+    /// Epoch of the sender's roster
+    pub epoch: u64,
+    /// All entries, including inactive ones
+    pub entries: BTreeMap<String, RosterEntry>,
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+}
+
+/// Roster summary exchanged between member pairs to detect silent divergence
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MembershipDigest {
+    /// Group identifier
+    pub group_id: GroupId,
+    /// Epoch of the sender's roster
+    pub epoch: u64,
+    /// Hash of the sender's sorted active membership
+    pub membership_hash: String,
+}
+
+/// Membership sync traffic between group members
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MembershipMessage {
+    /// A roster change
+    Delta(MembershipDelta),
+    /// Request for a full snapshot from an administrator
+    SnapshotRequest { group_id: GroupId, requester: String, known_epoch: u64 },
+    /// A full snapshot
+    Snapshot(MembershipSnapshot),
+    /// Anti-entropy digest
+    Digest(MembershipDigest),
+}
+
+/// Metadata key holding the member ID in memberships passed to split predicates
+pub const MEMBER_ID_KEY: &str = "member_id";
+
+/// Notification addressed to a group member about changes to their groups
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MembershipEvent {
+    /// Some of `old_group`'s members were moved to `new_group`
+    GroupSplit { old_group: GroupId, new_group: GroupId },
+    /// `from_group` was merged into `to_group` and closed
+    GroupMerged { from_group: GroupId, to_group: GroupId },
+    /// The group was restored on `home` from a definition bundle, signed by `home`
+    GroupRehomed {
+        group_id: GroupId,
+        home: String,
+        snapshot: MembershipSnapshot,
+        settings: GroupSettings,
+        signer_public_key: Vec<u8>,
+        signature: Vec<u8>,
+    },
+    /// Invitation to join a group
+    Invitation(GroupInvitation),
+}
+
+/// Result of applying a delta to a roster
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeltaOutcome {
+    /// The change was applied
+    Applied,
+    /// A later change to the same member already won
+    Superseded,
+    /// Deltas between `expected` and `received` were missed; the delta was not applied
+    GapDetected { expected: u64, received: u64 },
+}
+
+/// Versioned roster of a group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRoster {
+    /// Group identifier
+    group_id: GroupId,
+    /// Highest epoch seen
+    epoch: u64,
+    /// Entries by member ID
+    entries: BTreeMap<String, RosterEntry>,
+    /// When the roster was last confirmed in sync
+    last_verified: Option<DateTime<Utc>>,
+    /// Whether a gap or divergence is waiting on a snapshot
+    awaiting_snapshot: bool,
+}
+
+impl GroupRoster {
+    /// Create an empty roster at epoch 0
+    pub fn new(group_id: GroupId) -> Self {
+        Self {
+            group_id,
+            epoch: 0,
+            entries: BTreeMap::new(),
+            last_verified: None,
+            awaiting_snapshot: false,
+        }
+    }
+    
+    /// Group identifier
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+    
+    /// Current membership epoch
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+    
+    /// When the roster was last confirmed in sync
+    pub fn last_verified(&self) -> Option<DateTime<Utc>> {
+        self.last_verified
+    }
+    
+    /// Whether the roster is waiting on a snapshot to repair a gap or divergence
+    pub fn awaiting_snapshot(&self) -> bool {
+        self.awaiting_snapshot
+    }
+    
+    /// Role of an active member
+    pub fn role_of(&self, member: &str) -> Option<&GroupRole> {
+        self.entries.get(member)
+            .filter(|entry| entry.active)
+            .map(|entry| &entry.role)
+    }
+    
+    /// Active members sorted by ID, leaving out dormant ones
+    pub fn members(&self) -> Vec<(&str, &GroupRole)> {
+        self.entries.iter()
+            .filter(|(_, entry)| entry.active && !entry.dormant)
+            .map(|(member, entry)| (member.as_str(), &entry.role))
+            .collect()
+    }
+    
+    /// Members that are gone for good, sorted by ID
+    pub fn dormant_members(&self) -> Vec<&str> {
+        self.entries.iter()
+            .filter(|(_, entry)| entry.active && entry.dormant)
+            .map(|(member, _)| member.as_str())
+            .collect()
+    }
+    
+    /// Record a local change, producing the delta to broadcast
+    pub fn record_change(&mut self, actor: &str, change: MembershipChange, now: DateTime<Utc>) -> MembershipDelta {
+        let delta = MembershipDelta {
+            group_id: self.group_id.clone(),
+            epoch: self.epoch + 1,
+            actor: actor.to_string(),
+            change,
+            timestamp: now,
+        };
+        self.merge_change(delta.epoch, &delta.actor, &delta.change);
+        self.epoch = delta.epoch;
+        delta
+    }
+    
+    /// Apply a delta received from another member
+    pub fn apply_delta(&mut self, delta: &MembershipDelta) -> DeltaOutcome {
+        if delta.epoch > self.epoch + 1 {
+            self.awaiting_snapshot = true;
+            return DeltaOutcome::GapDetected { expected: self.epoch + 1, received: delta.epoch };
+        }
+        
+        self.epoch = self.epoch.max(delta.epoch);
+        if self.merge_change(delta.epoch, &delta.actor, &delta.change) {
+            DeltaOutcome::Applied
+        } else {
+            DeltaOutcome::Superseded
+        }
+    }
+    
+    /// Apply a change if it orders after the member's current entry
+    fn merge_change(&mut self, epoch: u64, actor: &str, change: &MembershipChange) -> bool {
+        if let Some(entry) = self.entries.get(change.member()) {
+            if entry.supersedes(epoch, actor) {
+                return false;
+            }
+        }
+        
+        let role = match change {
+            MembershipChange::Join { role, .. } | MembershipChange::RoleChange { role, .. } => role.clone(),
+            MembershipChange::Leave { member } => self.entries.get(member)
+                .map(|entry| entry.role.clone())
+                .unwrap_or(GroupRole::Member),
+        };
+        let active = !matches!(change, MembershipChange::Leave { .. });
+        
+        self.entries.insert(change.member().to_string(), RosterEntry {
+            role,
+            active,
+            epoch,
+            actor: actor.to_string(),
+            dormant: false,
+        });
+        true
+    }
+    
+    /// Full snapshot of this roster
+    pub fn snapshot(&self, now: DateTime<Utc>) -> MembershipSnapshot {
+        MembershipSnapshot {
+            group_id: self.group_id.clone(),
+            epoch: self.epoch,
+            entries: self.entries.clone(),
+            taken_at: now,
+        }
+    }
+    
+    /// Merge a snapshot, keeping whichever entry orders later for each member
+    pub fn apply_snapshot(&mut self, snapshot: &MembershipSnapshot, now: DateTime<Utc>) {
+        for (member, entry) in &snapshot.entries {
+            let newer = match self.entries.get(member) {
+                Some(current) => !current.supersedes(entry.epoch, &entry.actor),
+                None => true,
+            };
+            if newer {
+                self.entries.insert(member.clone(), entry.clone());
+            }
+        }
+        self.epoch = self.epoch.max(snapshot.epoch);
+        self.awaiting_snapshot = false;
+        self.last_verified = Some(now);
+    }
+    
+    /// SHA-256 of the sorted active membership, hex encoded
+    pub fn membership_hash(&self) -> String {
+        let mut ctx = digest::Context::new(&digest::SHA256);
+        for (member, role) in self.members() {
+            ctx.update(member.as_bytes());
+            ctx.update(b"=");
+            ctx.update(format!("{:?}", role).as_bytes());
+            ctx.update(b"\n");
+        }
+        ctx.finish().as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+    
+    /// Anti-entropy digest of this roster
+    pub fn digest(&self) -> MembershipDigest {
+        MembershipDigest {
+            group_id: self.group_id.clone(),
+            epoch: self.epoch,
+            membership_hash: self.membership_hash(),
+        }
+    }
+    
+    /// Compare a peer's digest against this roster
+    ///
+    /// Returns true and marks the roster verified if the memberships match;
+    /// otherwise flags the roster as awaiting a snapshot.
+    pub fn check_digest(&mut self, digest: &MembershipDigest, now: DateTime<Utc>) -> bool {
+        if digest.membership_hash == self.membership_hash() {
+            self.last_verified = Some(now);
+            true
+        } else {
+            self.awaiting_snapshot = true;
+            false
+        }
+    }
+    
+    /// Administrator to request a snapshot from, preferring `preferred`
+    pub fn snapshot_source(&self, preferred: &str, exclude: &str) -> Option<String> {
+        if preferred != exclude && self.role_of(preferred) == Some(&GroupRole::Administrator) {
+            return Some(preferred.to_string());
+        }
+        self.members().into_iter()
+            .find(|(member, role)| *member != exclude && **role == GroupRole::Administrator)
+            .map(|(member, _)| member.to_string())
+    }
+    
+    /// Pick a random peer for an anti-entropy exchange
+    ///
+    /// `seed` is any random value; the caller supplies it so selection is
+    /// reproducible in tests.
+    pub fn pick_anti_entropy_peer(&self, self_id: &str, seed: u64) -> Option<String> {
+        let peers: Vec<&str> = self.members().into_iter()
+            .map(|(member, _)| member)
+            .filter(|member| *member != self_id)
+            .collect();
+        if peers.is_empty() {
+            return None;
+        }
+        Some(peers[(seed % peers.len() as u64) as usize].to_string())
+    }
+}
+
+/// Limits on a group
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroupQuotas {
+    /// Most active, non-dormant members
+    pub max_members: Option<usize>,
+    /// Largest message content in bytes
+    pub max_message_size: Option<usize>,
+}
+
+/// Configuration of a group kept alongside its roster
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupSettings {
+    /// Pattern members listen on
+    pub pattern: Option<GroupPattern>,
+    /// Limits on the group
+    pub quotas: GroupQuotas,
+    /// Context-specific configuration
+    pub metadata: HashMap<String, String>,
+}
+
+/// Everything needed to re-establish a group on another node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupDefinition {
+    /// Group identifier
+    pub group_id: GroupId,
+    /// Group configuration and quotas
+    pub settings: GroupSettings,
+    /// Permissions of the exporting administrator
+    pub admin_permissions: GroupPermissions,
+    /// Full roster with roles
+    pub roster: MembershipSnapshot,
+    /// When the definition was exported
+    pub exported_at: DateTime<Utc>,
+}
+
+/// A group definition signed by one of its administrators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupDefinitionBundle {
+    /// The definition
+    pub definition: GroupDefinition,
+    /// Administrator that signed it
+    pub signer: String,
+    /// Signer's public key
+    pub signer_public_key: Vec<u8>,
+    /// Signature over the canonical JSON of the definition
+    pub signature: Vec<u8>,
+}
+
+impl GroupDefinitionBundle {
+    /// Whether the signer administers the group and signed it with a trusted key
+    ///
+    /// `trusted_keys` maps node IDs to the fingerprints of their signing keys,
+    /// as in [`crate::mesh::security::SharedCredentials`].
+    pub fn verify(&self, trusted_keys: &HashMap<String, String>) -> bool {
+        let signed_by_admin = self.definition.roster.entries.get(&self.signer)
+            .is_some_and(|entry| entry.active && entry.role == GroupRole::Administrator);
+        signed_by_admin
+            && verify_signed(&self.definition, &self.signer, &self.signer_public_key, &self.signature, trusted_keys)
+    }
+}
+
+/// Canonical JSON of `value`
+///
+/// Object keys are sorted so the bytes do not depend on map order.
+fn canonical_json<T: Serialize>(value: &T) -> serde_json::Result<Vec<u8>> {
+    serde_json::to_vec(&serde_json::to_value(value)?)
+}
+
+/// Whether `signature` over `value` is by the key trusted for `signer`
+fn verify_signed<T: Serialize>(
+    value: &T,
+    signer: &str,
+    public_key: &[u8],
+    signature: &[u8],
+    trusted_keys: &HashMap<String, String>,
+) -> bool {
+    let key_trusted = trusted_keys.get(signer).is_some_and(|known| *known == key_fingerprint(public_key));
+    key_trusted
+        && canonical_json(value).is_ok_and(|payload| {
+            UnparsedPublicKey::new(&ED25519, public_key).verify(&payload, signature).is_ok()
+        })
+}
+
+/// How a group definition bundle is imported
+#[derive(Debug, Clone, Default)]
+pub struct GroupImportOptions {
+    /// Replace a group with the same ID that this node already has
+    pub force: bool,
+    /// Members known to be gone for good; they are marked dormant
+    pub dormant_members: Vec<String>,
+    /// Fingerprints of the signing keys trusted for each administrator
+    pub trusted_keys: HashMap<String, String>,
 }
 
 /// Universal group communication trait
@@ -315,6 +841,21 @@ pub enum GroupCommunicationError {
     
     #[error("Group communication not initialized")]
     NotInitialized,
+    
+    #[error("Group already exists: {0}")]
+    GroupExists(String),
+    
+    #[error("Group quota exceeded: {0}")]
+    QuotaExceeded(String),
+    
+    #[error("Invalid group definition bundle: {0}")]
+    InvalidBundle(String),
+    
+    #[error("Cannot merge groups: {0}")]
+    InvalidMerge(String),
+    
+    #[error("Node not admitted to the mesh: {0}")]
+    NotAdmitted(String),
 }
 
 /// Basic group communication implementation using WeaveMesh protocol
@@ -325,6 +866,18 @@ pub struct BasicGroupCommunication {
     memberships: HashMap<GroupId, GroupMembership>,
     /// Message history
     message_history: HashMap<GroupId, Vec<Message>>,
+    /// Versioned rosters for groups this node belongs to
+    rosters: HashMap<GroupId, GroupRoster>,
+    /// Events waiting to be delivered, with their recipient
+    outbox: Vec<(String, MembershipEvent)>,
+    /// Configuration and quotas of groups this node belongs to
+    settings: HashMap<GroupId, GroupSettings>,
+    /// Patterns setting how long group history is kept
+    retention_patterns: Vec<GroupPattern>,
+    /// Streams of participants that can receive replayed history
+    participant_streams: HashMap<GroupId, HashMap<String, mpsc::Sender<Message>>>,
+    /// Admission state; history is not replayed to unadmitted nodes
+    admission: Option<AdmissionRegistry>,
 }
 
 impl BasicGroupCommunication {
@@ -334,17 +887,548 @@ impl BasicGroupCommunication {
             node_id,
             memberships: HashMap::new(),
             message_history: HashMap::new(),
+            rosters: HashMap::new(),
+            outbox: Vec::new(),
+            settings: HashMap::new(),
+            retention_patterns: Vec::new(),
+            participant_streams: HashMap::new(),
+            admission: None,
         }
     }
     
+    /// Withhold group history from nodes a gatekeeper has not admitted
+    pub fn set_admission_registry(&mut self, admission: AdmissionRegistry) {
+        self.admission = Some(admission);
+    }
+    
     /// Add a group membership
     pub fn add_membership(&mut self, membership: GroupMembership) {
+        self.rosters.entry(membership.group_id.clone())
+            .or_insert_with(|| GroupRoster::new(membership.group_id.clone()));
         self.memberships.insert(membership.group_id.clone(), membership);
     }
     
     /// Remove a group membership
     pub fn remove_membership(&mut self, group_id: &GroupId) {
         self.memberships.remove(group_id);
+        self.rosters.remove(group_id);
+        self.settings.remove(group_id);
+        self.participant_streams.remove(group_id);
+    }
+    
+    /// Configuration and quotas of a group
+    pub fn group_settings(&self, group_id: &GroupId) -> Option<&GroupSettings> {
+        self.settings.get(group_id)
+    }
+    
+    /// Configure a group; needs `can_modify_group`
+    pub fn set_group_settings(&mut self, group_id: &GroupId, settings: GroupSettings) -> Result<(), GroupCommunicationError> {
+        let membership = self.memberships.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        if !membership.permissions.can_modify_group {
+            return Err(GroupCommunicationError::InsufficientPermissions);
+        }
+        self.settings.insert(group_id.clone(), settings);
+        Ok(())
+    }
+    
+    /// Roster of a group this node belongs to
+    pub fn roster(&self, group_id: &GroupId) -> Option<&GroupRoster> {
+        self.rosters.get(group_id)
+    }
+    
+    /// Change a group's roster, returning the delta to broadcast
+    ///
+    /// Joins need `can_invite_members`, removing someone else needs
+    /// `can_remove_members`, and role changes need `can_modify_group`.
+    pub fn change_membership(
+        &mut self,
+        group_id: &GroupId,
+        change: MembershipChange,
+        now: DateTime<Utc>,
+    ) -> Result<MembershipDelta, GroupCommunicationError> {
+        let membership = self.memberships.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        
+        let permitted = match &change {
+            MembershipChange::Join { .. } => membership.permissions.can_invite_members,
+            MembershipChange::Leave { member } => {
+                member == &self.node_id || membership.permissions.can_remove_members
+            }
+            MembershipChange::RoleChange { .. } => membership.permissions.can_modify_group,
+        };
+        if !permitted {
+            return Err(GroupCommunicationError::InsufficientPermissions);
+        }
+        
+        let roster = self.rosters.entry(group_id.clone())
+            .or_insert_with(|| GroupRoster::new(group_id.clone()));
+        let max_members = self.settings.get(group_id).and_then(|s| s.quotas.max_members);
+        if let (MembershipChange::Join { member, .. }, Some(max)) = (&change, max_members) {
+            let members = roster.members();
+            if !members.iter().any(|(m, _)| *m == member.as_str()) && members.len() >= max {
+                return Err(GroupCommunicationError::QuotaExceeded(format!("at most {} members", max)));
+            }
+        }
+        Ok(roster.record_change(&self.node_id, change, now))
+    }
+    
+    /// Anti-entropy digest to send to a random peer, with the chosen peer
+    pub fn anti_entropy_digest(&self, group_id: &GroupId, seed: u64) -> Option<(String, MembershipDigest)> {
+        let roster = self.rosters.get(group_id)?;
+        let peer = roster.pick_anti_entropy_peer(&self.node_id, seed)?;
+        Some((peer, roster.digest()))
+    }
+    
+    /// Handle membership sync traffic from `from`
+    ///
+    /// Returns a reply and its recipient, if any: a snapshot request to an
+    /// administrator when a gap or divergence is detected, or a snapshot when
+    /// this node is an administrator serving a request.
+    pub fn handle_membership_message(
+        &mut self,
+        from: &str,
+        message: MembershipMessage,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(String, MembershipMessage)>, GroupCommunicationError> {
+        let group_id = match &message {
+            MembershipMessage::Delta(delta) => delta.group_id.clone(),
+            MembershipMessage::SnapshotRequest { group_id, .. } => group_id.clone(),
+            MembershipMessage::Snapshot(snapshot) => snapshot.group_id.clone(),
+            MembershipMessage::Digest(digest) => digest.group_id.clone(),
+        };
+        let roster = self.rosters.get_mut(&group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        
+        let needs_snapshot = match message {
+            MembershipMessage::Delta(delta) => {
+                matches!(roster.apply_delta(&delta), DeltaOutcome::GapDetected { .. })
+            }
+            MembershipMessage::Digest(digest) => !roster.check_digest(&digest, now),
+            MembershipMessage::Snapshot(snapshot) => {
+                roster.apply_snapshot(&snapshot, now);
+                false
+            }
+            MembershipMessage::SnapshotRequest { requester, .. } => {
+                if roster.role_of(&self.node_id) != Some(&GroupRole::Administrator) {
+                    return Err(GroupCommunicationError::InsufficientPermissions);
+                }
+                return Ok(Some((requester, MembershipMessage::Snapshot(roster.snapshot(now)))));
+            }
+        };
+        
+        if !needs_snapshot {
+            return Ok(None);
+        }
+        
+        match roster.snapshot_source(from, &self.node_id) {
+            Some(admin) => Ok(Some((admin, MembershipMessage::SnapshotRequest {
+                group_id,
+                requester: self.node_id.clone(),
+                known_epoch: roster.epoch(),
+            }))),
+            None => {
+                tracing::warn!("No administrator known to repair roster of {}", group_id.as_str());
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Split off the members of a group for which `predicate` holds
+    ///
+    /// Matching members move to a new group with ID `new_group_name` and keep
+    /// their roles; their messages move with them. The original group keeps
+    /// its ID, the remaining members and their messages. This node stays in
+    /// both groups to administer them. Each member is presented to
+    /// `predicate` with their roster role and their ID under
+    /// [`MEMBER_ID_KEY`]. Every member of the original group is sent a
+    /// [`MembershipEvent::GroupSplit`], and moved members an invitation to
+    /// the new group. Nothing changes unless the whole split succeeds.
+    pub fn split(
+        &mut self,
+        group_id: &GroupId,
+        predicate: impl Fn(&GroupMembership) -> bool,
+        new_group_name: String,
+    ) -> Result<(GroupId, GroupId), GroupCommunicationError> {
+        let membership = self.memberships.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?
+            .clone();
+        if !membership.permissions.can_modify_group {
+            return Err(GroupCommunicationError::InsufficientPermissions);
+        }
+        let new_group = GroupId::new(&new_group_name);
+        if new_group == *group_id || self.memberships.contains_key(&new_group) || self.rosters.contains_key(&new_group) {
+            return Err(GroupCommunicationError::GroupExists(new_group_name));
+        }
+        let roster = self.rosters.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        
+        let now = Utc::now();
+        let members: Vec<(String, GroupRole)> = roster.members().into_iter()
+            .map(|(member, role)| (member.to_string(), role.clone()))
+            .collect();
+        let moving: Vec<(String, GroupRole)> = members.iter()
+            .filter(|(member, role)| {
+                let mut view = GroupMembership {
+                    group_id: group_id.clone(),
+                    role: role.clone(),
+                    permissions: GroupPermissions::default(),
+                    joined_at: now,
+                    is_active: true,
+                    metadata: HashMap::new(),
+                };
+                if *member == self.node_id {
+                    view.permissions = membership.permissions.clone();
+                    view.joined_at = membership.joined_at;
+                    view.metadata = membership.metadata.clone();
+                }
+                view.metadata.insert(MEMBER_ID_KEY.to_string(), member.clone());
+                predicate(&view)
+            })
+            .cloned()
+            .collect();
+        
+        // Rosters: moved members leave the original and join the new group
+        let mut new_roster = GroupRoster::new(new_group.clone());
+        if !moving.iter().any(|(member, _)| *member == self.node_id) {
+            new_roster.record_change(&self.node_id, MembershipChange::Join {
+                member: self.node_id.clone(),
+                role: membership.role.clone(),
+            }, now);
+        }
+        let roster = self.rosters.get_mut(group_id).expect("roster checked above");
+        for (member, role) in &moving {
+            new_roster.record_change(&self.node_id, MembershipChange::Join { member: member.clone(), role: role.clone() }, now);
+            if *member != self.node_id {
+                roster.record_change(&self.node_id, MembershipChange::Leave { member: member.clone() }, now);
+            }
+        }
+        
+        // History: messages follow their sender
+        if let Some(history) = self.message_history.remove(group_id) {
+            let (moved, kept): (Vec<Message>, Vec<Message>) = history.into_iter()
+                .partition(|message| message.sender != self.node_id && moving.iter().any(|(member, _)| *member == message.sender));
+            self.message_history.insert(group_id.clone(), kept);
+            if !moved.is_empty() {
+                self.message_history.insert(new_group.clone(), moved);
+            }
+        }
+        
+        self.rosters.insert(new_group.clone(), new_roster);
+        self.memberships.insert(new_group.clone(), GroupMembership {
+            group_id: new_group.clone(),
+            joined_at: now,
+            ..membership
+        });
+        
+        for (member, _) in &members {
+            if *member == self.node_id {
+                continue;
+            }
+            self.outbox.push((member.clone(), MembershipEvent::GroupSplit {
+                old_group: group_id.clone(),
+                new_group: new_group.clone(),
+            }));
+        }
+        for (member, role) in &moving {
+            if *member == self.node_id {
+                continue;
+            }
+            self.outbox.push((member.clone(), MembershipEvent::Invitation(GroupInvitation {
+                id: Uuid::new_v4(),
+                group_id: new_group.clone(),
+                inviter: self.node_id.clone(),
+                invitee: member.clone(),
+                role: role.clone(),
+                permissions: GroupPermissions::default(),
+                message: Some(format!("{} was split from {}", new_group.as_str(), group_id.as_str())),
+                created_at: now,
+                expires_at: None,
+                accepted: None,
+            })));
+        }
+        
+        Ok((group_id.clone(), new_group))
+    }
+    
+    /// Merge two groups into a new one and close both
+    ///
+    /// The new group gets a fresh ID derived from `merged_name`, the union of
+    /// both rosters and both histories in chronological order, without
+    /// duplicates. A participant in both groups with different roles gets
+    /// the role picked by `conflict_resolution`. The first group's settings
+    /// carry over. Every member of either group is sent a
+    /// [`MembershipEvent::GroupMerged`] per group they were in. This node
+    /// needs `can_modify_group` in both groups; nothing changes unless the
+    /// whole merge succeeds.
+    pub fn merge(
+        &mut self,
+        group_a: &GroupId,
+        group_b: &GroupId,
+        merged_name: String,
+        conflict_resolution: MergeConflictResolution,
+    ) -> Result<GroupId, GroupCommunicationError> {
+        if group_a == group_b {
+            return Err(GroupCommunicationError::InvalidMerge(format!("{} cannot be merged with itself", group_a.as_str())));
+        }
+        let mut rosters = Vec::new();
+        for group_id in [group_a, group_b] {
+            let membership = self.memberships.get(group_id)
+                .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+            if !membership.permissions.can_modify_group {
+                return Err(GroupCommunicationError::InsufficientPermissions);
+            }
+            rosters.push(self.rosters.get(group_id)
+                .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?);
+        }
+        
+        let merged = GroupId::new(&format!("{}-{}", merged_name, Uuid::new_v4().simple()));
+        let now = Utc::now();
+        
+        // Union of the rosters, one entry per participant
+        let mut roles: BTreeMap<String, GroupRole> = BTreeMap::new();
+        let mut notify: Vec<(String, GroupId)> = Vec::new();
+        for (roster, group_id) in rosters.iter().zip([group_a, group_b]) {
+            for (member, role) in roster.members() {
+                let role = match roles.get(member) {
+                    Some(existing) => conflict_resolution.resolve(existing, role),
+                    None => role.clone(),
+                };
+                roles.insert(member.to_string(), role);
+                if member != self.node_id {
+                    notify.push((member.to_string(), group_id.clone()));
+                }
+            }
+        }
+        let mut roster = GroupRoster::new(merged.clone());
+        for (member, role) in &roles {
+            roster.record_change(&self.node_id, MembershipChange::Join { member: member.clone(), role: role.clone() }, now);
+        }
+        if roster.role_of(&self.node_id).is_none() {
+            let role = self.memberships[group_a].role.clone();
+            roster.record_change(&self.node_id, MembershipChange::Join { member: self.node_id.clone(), role }, now);
+        }
+        
+        // Histories in chronological order, each message once
+        let mut history: Vec<Message> = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        for group_id in [group_a, group_b] {
+            for message in self.message_history.get(group_id).into_iter().flatten() {
+                if seen.insert(message.id.clone()) {
+                    history.push(message.clone());
+                }
+            }
+        }
+        history.sort_by_key(|message| message.timestamp);
+        
+        // Close both sources and open the merged group
+        let membership = self.memberships.remove(group_a).expect("membership checked above");
+        let settings = self.settings.remove(group_a);
+        for group_id in [group_a, group_b] {
+            self.remove_membership(group_id);
+            self.message_history.remove(group_id);
+        }
+        self.rosters.insert(merged.clone(), roster);
+        if let Some(settings) = settings {
+            self.settings.insert(merged.clone(), settings);
+        }
+        if !history.is_empty() {
+            self.message_history.insert(merged.clone(), history);
+        }
+        self.memberships.insert(merged.clone(), GroupMembership {
+            group_id: merged.clone(),
+            role: roles.get(&self.node_id).cloned().unwrap_or_else(|| membership.role.clone()),
+            joined_at: now,
+            ..membership
+        });
+        
+        for (member, from_group) in notify {
+            self.outbox.push((member, MembershipEvent::GroupMerged {
+                from_group,
+                to_group: merged.clone(),
+            }));
+        }
+        
+        Ok(merged)
+    }
+    
+    /// Export a group's definition for disaster recovery, signed with `keys`
+    ///
+    /// Only administrators can export.
+    pub fn export_group(&self, group_id: &GroupId, keys: &NodeKeys) -> Result<GroupDefinitionBundle, GroupCommunicationError> {
+        let membership = self.memberships.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        let roster = self.rosters.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        if roster.role_of(&self.node_id) != Some(&GroupRole::Administrator) {
+            return Err(GroupCommunicationError::InsufficientPermissions);
+        }
+        
+        let definition = GroupDefinition {
+            group_id: group_id.clone(),
+            settings: self.settings.get(group_id).cloned().unwrap_or_default(),
+            admin_permissions: membership.permissions.clone(),
+            roster: roster.snapshot(Utc::now()),
+            exported_at: Utc::now(),
+        };
+        let payload = canonical_json(&definition)
+            .map_err(|e| GroupCommunicationError::SerializationError(e.to_string()))?;
+        
+        Ok(GroupDefinitionBundle {
+            signature: keys.sign(&payload),
+            signer_public_key: keys.public_key().to_vec(),
+            signer: self.node_id.clone(),
+            definition,
+        })
+    }
+    
+    /// Re-establish a group from an exported definition on this node
+    ///
+    /// The group moves to a new epoch with this node as an administrator.
+    /// Roles and quotas are kept; `dormant_members` keep their entries but
+    /// no longer count as members. Every surviving member is sent a
+    /// [`MembershipEvent::GroupRehomed`] with the new roster, signed with
+    /// `keys`. A group this node already has is only replaced with `force`,
+    /// and then only from a bundle signed by one of its current administrators.
+    pub fn import_group(
+        &mut self,
+        bundle: GroupDefinitionBundle,
+        options: GroupImportOptions,
+        keys: &NodeKeys,
+    ) -> Result<GroupId, GroupCommunicationError> {
+        if !bundle.verify(&options.trusted_keys) {
+            return Err(GroupCommunicationError::InvalidBundle("signature does not verify against an administrator".to_string()));
+        }
+        let definition = bundle.definition;
+        let group_id = definition.group_id.clone();
+        if definition.roster.group_id != group_id {
+            return Err(GroupCommunicationError::InvalidBundle("roster is for another group".to_string()));
+        }
+        let known_admin = self.rosters.get(&group_id)
+            .is_none_or(|roster| roster.role_of(&bundle.signer) == Some(&GroupRole::Administrator));
+        if !known_admin {
+            return Err(GroupCommunicationError::InvalidBundle(format!("{} does not administer {}", bundle.signer, group_id.as_str())));
+        }
+        if !options.force && (self.memberships.contains_key(&group_id) || self.rosters.contains_key(&group_id)) {
+            return Err(GroupCommunicationError::GroupExists(group_id.as_str().to_string()));
+        }
+        
+        // Changes made by the import are stamped with the new epoch so they
+        // win over the exported entries wherever members merge them
+        let now = Utc::now();
+        let epoch = definition.roster.epoch + 1;
+        let mut roster = GroupRoster::new(group_id.clone());
+        roster.entries = definition.roster.entries.clone();
+        for member in &options.dormant_members {
+            if let Some(entry) = roster.entries.get_mut(member) {
+                if entry.active && *member != self.node_id {
+                    *entry = RosterEntry { dormant: true, epoch, actor: self.node_id.clone(), ..entry.clone() };
+                }
+            }
+        }
+        roster.entries.insert(self.node_id.clone(), RosterEntry {
+            role: GroupRole::Administrator,
+            active: true,
+            epoch,
+            actor: self.node_id.clone(),
+            dormant: false,
+        });
+        roster.epoch = epoch;
+        roster.last_verified = Some(now);
+        
+        let snapshot = roster.snapshot(now);
+        let payload = canonical_json(&(&group_id, &self.node_id, &snapshot, &definition.settings))
+            .map_err(|e| GroupCommunicationError::SerializationError(e.to_string()))?;
+        let signature = keys.sign(&payload);
+        for (member, _) in roster.members() {
+            if member != self.node_id {
+                self.outbox.push((member.to_string(), MembershipEvent::GroupRehomed {
+                    group_id: group_id.clone(),
+                    home: self.node_id.clone(),
+                    snapshot: snapshot.clone(),
+                    settings: definition.settings.clone(),
+                    signer_public_key: keys.public_key().to_vec(),
+                    signature: signature.clone(),
+                }));
+            }
+        }
+        
+        self.rosters.insert(group_id.clone(), roster);
+        self.settings.insert(group_id.clone(), definition.settings);
+        self.memberships.insert(group_id.clone(), GroupMembership {
+            group_id: group_id.clone(),
+            role: GroupRole::Administrator,
+            permissions: definition.admin_permissions,
+            joined_at: now,
+            is_active: true,
+            metadata: HashMap::new(),
+        });
+        tracing::info!("Imported group {} at epoch {}", group_id.as_str(), epoch);
+        
+        Ok(group_id)
+    }
+    
+    /// Move a group this node belongs to over to its new home
+    ///
+    /// Applies the roster and settings of a [`MembershipEvent::GroupRehomed`]
+    /// signed by `home` with the key `trusted_keys` holds for it; other events
+    /// are left to the caller. Returns whether the event was applied.
+    pub fn accept_rehome(
+        &mut self,
+        event: &MembershipEvent,
+        trusted_keys: &HashMap<String, String>,
+        now: DateTime<Utc>,
+    ) -> Result<bool, GroupCommunicationError> {
+        let (group_id, home, snapshot, settings, signer_public_key, signature) = match event {
+            MembershipEvent::GroupRehomed { group_id, home, snapshot, settings, signer_public_key, signature } => {
+                (group_id, home, snapshot, settings, signer_public_key, signature)
+            }
+            _ => return Ok(false),
+        };
+        let roster = self.rosters.get_mut(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        if snapshot.group_id != *group_id || snapshot.entries.get(home).map(|e| &e.role) != Some(&GroupRole::Administrator) {
+            return Err(GroupCommunicationError::InvalidBundle(format!("{} does not administer {}", home, group_id.as_str())));
+        }
+        if !verify_signed(&(group_id, home, snapshot, settings), home, signer_public_key, signature, trusted_keys) {
+            return Err(GroupCommunicationError::InvalidBundle(format!("re-home of {} is not signed by {}", group_id.as_str(), home)));
+        }
+        roster.apply_snapshot(snapshot, now);
+        self.settings.insert(group_id.clone(), settings.clone());
+        Ok(true)
+    }
+    
+    /// Promote `successor` to administrator and leave the group
+    ///
+    /// Used when this node retires; returns the deltas to broadcast, in order.
+    pub fn hand_off_administration(
+        &mut self,
+        group_id: &GroupId,
+        successor: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<MembershipDelta>, GroupCommunicationError> {
+        let roster = self.rosters.get(group_id)
+            .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
+        if roster.role_of(&self.node_id) != Some(&GroupRole::Administrator) {
+            return Err(GroupCommunicationError::InsufficientPermissions);
+        }
+        if roster.role_of(successor).is_none() {
+            return Err(GroupCommunicationError::NotAMember(format!("{} is not in {}", successor, group_id.as_str())));
+        }
+        
+        let promote = MembershipChange::RoleChange { member: successor.to_string(), role: GroupRole::Administrator };
+        let leave = MembershipChange::Leave { member: self.node_id.clone() };
+        let deltas = vec![
+            self.change_membership(group_id, promote, now)?,
+            self.change_membership(group_id, leave, now)?,
+        ];
+        self.memberships.remove(group_id);
+        Ok(deltas)
+    }
+    
+    /// Take the membership events waiting to be delivered, with their recipients
+    pub fn take_membership_events(&mut self) -> Vec<(String, MembershipEvent)> {
+        std::mem::take(&mut self.outbox)
     }
     
     /// Get message history for a group
@@ -354,13 +1438,110 @@ impl BasicGroupCommunication {
     
     /// Add a message to history
     pub fn add_message_to_history(&mut self, group_id: GroupId, message: Message) {
-        self.message_history.entry(group_id).or_insert_with(Vec::new).push(message);
+        let cutoff = self.retention_cutoff(&group_id, Utc::now());
+        let history = self.message_history.entry(group_id).or_default();
+        history.push(message);
+        if let Some(cutoff) = cutoff {
+            history.retain(|message| message.timestamp >= cutoff);
+        }
+    }
+    
+    /// Keep history of groups matching `pattern` for its `history_retention`
+    ///
+    /// The first registered pattern matching a group decides; a pattern
+    /// with the same string replaces the earlier one.
+    pub fn set_history_retention(&mut self, pattern: GroupPattern) {
+        match self.retention_patterns.iter_mut().find(|p| p.pattern == pattern.pattern) {
+            Some(existing) => *existing = pattern,
+            None => self.retention_patterns.push(pattern),
+        }
+        let now = Utc::now();
+        let groups: Vec<GroupId> = self.message_history.keys().cloned().collect();
+        for group_id in groups {
+            if let Some(cutoff) = self.retention_cutoff(&group_id, now) {
+                if let Some(history) = self.message_history.get_mut(&group_id) {
+                    history.retain(|message| message.timestamp >= cutoff);
+                }
+            }
+        }
+    }
+    
+    /// Oldest timestamp still retained for a group
+    fn retention_cutoff(&self, group_id: &GroupId, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let retention = self.retention_patterns.iter()
+            .find(|pattern| pattern.matches(group_id))
+            .and_then(|pattern| pattern.history_retention)?;
+        chrono::Duration::from_std(retention).ok().map(|retention| now - retention)
+    }
+    
+    /// Number of messages stored for replay in a group
+    pub fn history_size(&self, group_id: &GroupId) -> usize {
+        let cutoff = self.retention_cutoff(group_id, Utc::now());
+        self.message_history.get(group_id).map_or(0, |history| {
+            history.iter()
+                .filter(|message| cutoff.is_none_or(|cutoff| message.timestamp >= cutoff))
+                .count()
+        })
+    }
+    
+    /// Whether a participant may receive group messages
+    ///
+    /// Participants are named by node ID; other names are not subject to admission.
+    fn is_participant_admitted(&self, participant_id: &str) -> bool {
+        match (&self.admission, Uuid::parse_str(participant_id)) {
+            (Some(admission), Ok(node_id)) => admission.is_admitted(&node_id),
+            _ => true,
+        }
+    }
+    
+    /// Open the stream through which a participant receives live messages and replayed history
+    pub fn connect_participant(&mut self, group_id: &GroupId, participant_id: &str) -> MessageStream {
+        let (tx, rx) = mpsc::channel(100);
+        self.participant_streams.entry(group_id.clone())
+            .or_default()
+            .insert(participant_id.to_string(), tx);
+        rx
+    }
+    
+    /// Deliver stored group messages to a participant who joined late
+    ///
+    /// Messages since `since` (all retained ones when None) are sent in order
+    /// through the participant's stream, marked as replays. Returns how many
+    /// were delivered.
+    pub async fn replay_to_participant(
+        &self,
+        group_id: &GroupId,
+        participant_id: &str,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<usize, GroupCommunicationError> {
+        if !self.memberships.contains_key(group_id) {
+            return Err(GroupCommunicationError::NotAMember(group_id.as_str().to_string()));
+        }
+        if !self.is_participant_admitted(participant_id) {
+            return Err(GroupCommunicationError::NotAdmitted(participant_id.to_string()));
+        }
+        let stream = self.participant_streams.get(group_id)
+            .and_then(|streams| streams.get(participant_id))
+            .ok_or_else(|| GroupCommunicationError::DeliveryFailed(format!("{} has no open stream", participant_id)))?;
+        
+        let replayed_at = Utc::now();
+        let cutoff = self.retention_cutoff(group_id, replayed_at).max(since);
+        let history = self.message_history.get(group_id).map(Vec::as_slice).unwrap_or_default();
+        let mut delivered = 0;
+        for message in history.iter().filter(|message| cutoff.is_none_or(|cutoff| message.timestamp >= cutoff)) {
+            let mut replay = message.clone();
+            replay.envelope = MessageEnvelope::Replay { replayed_at };
+            stream.send(replay).await
+                .map_err(|_| GroupCommunicationError::DeliveryFailed(format!("{} closed its stream", participant_id)))?;
+            delivered += 1;
+        }
+        Ok(delivered)
     }
 }
 
 #[async_trait::async_trait]
 impl GroupCommunication for BasicGroupCommunication {
-    async fn talk(&self, group_id: GroupId, _message: Message) -> Result<(), GroupCommunicationError> {
+    async fn talk(&self, group_id: GroupId, message: Message) -> Result<(), GroupCommunicationError> {
         // Check if we're a member of the group
         let membership = self.memberships.get(&group_id)
             .ok_or_else(|| GroupCommunicationError::NotAMember(group_id.as_str().to_string()))?;
@@ -370,8 +1551,23 @@ impl GroupCommunication for BasicGroupCommunication {
             return Err(GroupCommunicationError::InsufficientPermissions);
         }
         
-        // In a real implementation, this would send the message through the mesh
-        // For now, we'll just validate the operation
+        let max_size = self.settings.get(&group_id).and_then(|s| s.quotas.max_message_size);
+        if let Some(max) = max_size {
+            if message.content.len() > max {
+                return Err(GroupCommunicationError::QuotaExceeded(format!("messages up to {} bytes", max)));
+            }
+        }
+        
+        // Live delivery to connected participants that have been admitted;
+        // a full or closed stream does not hold up the others
+        for (participant_id, stream) in self.participant_streams.get(&group_id).into_iter().flatten() {
+            if *participant_id != message.sender
+                && self.is_participant_admitted(participant_id)
+                && stream.try_send(message.clone()).is_err()
+            {
+                tracing::debug!("Dropped live message for {} in {}", participant_id, group_id.as_str());
+            }
+        }
         Ok(())
     }
     
@@ -421,12 +1617,15 @@ impl GroupCommunication for BasicGroupCommunication {
         }
         
         // Create basic sync state
+        let roster = self.rosters.get(&group_id);
         let sync_state = GroupSyncState {
-            group_id,
             vector_clock: HashMap::new(),
             last_message_id: None,
-            state_checksum: "basic".to_string(),
+            state_checksum: roster.map(|r| r.membership_hash()).unwrap_or_else(|| "basic".to_string()),
             last_sync: chrono::Utc::now(),
+            membership_epoch: roster.map(|r| r.epoch()).unwrap_or(0),
+            last_verified: roster.and_then(|r| r.last_verified()),
+            group_id,
         };
         
         Ok(sync_state)
@@ -480,12 +1679,23 @@ mod tests {
             metadata: HashMap::new(),
             priority: MessagePriority::Normal,
             requires_ack: false,
+            envelope: MessageEnvelope::Live,
         };
         
         assert_eq!(message.content, "Hello group!");
         assert_eq!(message.priority, MessagePriority::Normal);
     }
     
+    #[test]
+    fn test_responses_inherit_message_priority() {
+        let mut message = text("alice", "Prod is down");
+        message.priority = MessagePriority::Urgent;
+        
+        let response = MessageResponse::to(&message, "bob", "On it".to_string(), ResponseType::Reply);
+        assert_eq!(response.message_id, message.id);
+        assert_eq!(response.priority, MessagePriority::Urgent);
+    }
+    
     #[test]
     fn test_group_permissions() {
         let default_permissions = GroupPermissions::default();
@@ -536,4 +1746,409 @@ mod tests {
         let memberships = comm.get_memberships().await.unwrap();
         assert_eq!(memberships.len(), 0);
     }
+    
+    fn roster_node(node_id: &str, group_id: &GroupId, admin: bool) -> BasicGroupCommunication {
+        let mut comm = BasicGroupCommunication::new(node_id.to_string());
+        comm.add_membership(GroupMembership {
+            group_id: group_id.clone(),
+            role: if admin { GroupRole::Administrator } else { GroupRole::Member },
+            permissions: GroupPermissions {
+                can_invite_members: admin,
+                can_remove_members: admin,
+                can_modify_group: admin,
+                ..GroupPermissions::default()
+            },
+            joined_at: chrono::Utc::now(),
+            is_active: true,
+            metadata: HashMap::new(),
+        });
+        comm
+    }
+    
+    fn join(member: &str, role: GroupRole) -> MembershipChange {
+        MembershipChange::Join { member: member.to_string(), role }
+    }
+    
+    #[test]
+    fn test_gap_triggers_snapshot_repair() {
+        let group_id = GroupId::new("group/team");
+        let now = chrono::Utc::now();
+        let mut admin = roster_node("admin", &group_id, true);
+        let mut bob = roster_node("bob", &group_id, false);
+        let mut carol = roster_node("carol", &group_id, false);
+        
+        let mut deltas = Vec::new();
+        for change in [join("admin", GroupRole::Administrator), join("bob", GroupRole::Member), join("carol", GroupRole::Member)] {
+            deltas.push(admin.change_membership(&group_id, change, now).unwrap());
+        }
+        for delta in &deltas {
+            assert!(bob.handle_membership_message("admin", MembershipMessage::Delta(delta.clone()), now).unwrap().is_none());
+            assert!(carol.handle_membership_message("admin", MembershipMessage::Delta(delta.clone()), now).unwrap().is_none());
+        }
+        
+        // Carol misses the delta for dave
+        let dropped = admin.change_membership(&group_id, join("dave", GroupRole::Member), now).unwrap();
+        let next = admin.change_membership(&group_id, MembershipChange::Leave { member: "bob".to_string() }, now).unwrap();
+        bob.handle_membership_message("admin", MembershipMessage::Delta(dropped), now).unwrap();
+        bob.handle_membership_message("admin", MembershipMessage::Delta(next.clone()), now).unwrap();
+        
+        let (target, request) = carol.handle_membership_message("admin", MembershipMessage::Delta(next), now)
+            .unwrap()
+            .expect("gap should request a snapshot");
+        assert_eq!(target, "admin");
+        assert!(carol.roster(&group_id).unwrap().awaiting_snapshot());
+        assert_eq!(carol.roster(&group_id).unwrap().epoch(), 3);
+        
+        // Only administrators serve snapshots
+        assert!(matches!(
+            bob.handle_membership_message("carol", request.clone(), now),
+            Err(GroupCommunicationError::InsufficientPermissions)
+        ));
+        
+        let (recipient, snapshot) = admin.handle_membership_message("carol", request, now).unwrap().unwrap();
+        assert_eq!(recipient, "carol");
+        carol.handle_membership_message("admin", snapshot, now).unwrap();
+        
+        let repaired = carol.roster(&group_id).unwrap();
+        let reference = admin.roster(&group_id).unwrap();
+        assert!(!repaired.awaiting_snapshot());
+        assert_eq!(repaired.epoch(), 5);
+        assert_eq!(repaired.membership_hash(), reference.membership_hash());
+        assert!(repaired.role_of("dave").is_some());
+        assert!(repaired.role_of("bob").is_none());
+        assert_eq!(bob.roster(&group_id).unwrap().membership_hash(), reference.membership_hash());
+    }
+    
+    #[tokio::test]
+    async fn test_anti_entropy_detects_divergence() {
+        let group_id = GroupId::new("group/team");
+        let now = chrono::Utc::now();
+        let mut admin = roster_node("admin", &group_id, true);
+        let mut bob = roster_node("bob", &group_id, false);
+        
+        for change in [join("admin", GroupRole::Administrator), join("bob", GroupRole::Member)] {
+            let delta = admin.change_membership(&group_id, change, now).unwrap();
+            bob.handle_membership_message("admin", MembershipMessage::Delta(delta), now).unwrap();
+        }
+        
+        let (peer, digest) = admin.anti_entropy_digest(&group_id, 7).unwrap();
+        assert_eq!(peer, "bob");
+        assert!(bob.handle_membership_message("admin", MembershipMessage::Digest(digest), now).unwrap().is_none());
+        assert_eq!(bob.roster(&group_id).unwrap().last_verified(), Some(now));
+        
+        // A change at an already-seen epoch never reaches bob, so no gap is visible
+        let silent = admin.change_membership(&group_id, join("erin", GroupRole::Observer), now).unwrap();
+        let mut concurrent = silent.clone();
+        concurrent.actor = "other-admin".to_string();
+        concurrent.change = join("frank", GroupRole::Member);
+        admin.handle_membership_message("other-admin", MembershipMessage::Delta(concurrent.clone()), now).unwrap();
+        bob.handle_membership_message("other-admin", MembershipMessage::Delta(concurrent), now).unwrap();
+        assert_eq!(bob.roster(&group_id).unwrap().epoch(), admin.roster(&group_id).unwrap().epoch());
+        
+        let later = now + chrono::Duration::seconds(30);
+        let (_, digest) = admin.anti_entropy_digest(&group_id, 0).unwrap();
+        let (target, request) = bob.handle_membership_message("admin", MembershipMessage::Digest(digest), later)
+            .unwrap()
+            .expect("divergence should request a snapshot");
+        assert_eq!(target, "admin");
+        
+        let (_, snapshot) = admin.handle_membership_message("bob", request, later).unwrap().unwrap();
+        bob.handle_membership_message("admin", snapshot, later).unwrap();
+        assert_eq!(bob.roster(&group_id).unwrap().membership_hash(), admin.roster(&group_id).unwrap().membership_hash());
+        
+        let sync = bob.sync_state(group_id.clone()).await.unwrap();
+        assert_eq!(sync.membership_epoch, 3);
+        assert_eq!(sync.last_verified, Some(later));
+    }
+    
+    #[test]
+    fn test_split_partitions_members_and_history() {
+        let group_id = GroupId::new("group/team");
+        let now = chrono::Utc::now();
+        let mut admin = roster_node("admin", &group_id, true);
+        for change in [
+            join("admin", GroupRole::Administrator),
+            join("bob", GroupRole::Member),
+            join("carol", GroupRole::Moderator),
+            join("dave", GroupRole::Member),
+        ] {
+            admin.change_membership(&group_id, change, now).unwrap();
+        }
+        for sender in ["admin", "bob", "carol", "dave", "bob"] {
+            admin.add_message_to_history(group_id.clone(), Message {
+                id: MessageId::new(),
+                content: format!("from {}", sender),
+                sender: sender.to_string(),
+                timestamp: now,
+                metadata: HashMap::new(),
+                priority: MessagePriority::Normal,
+                requires_ack: false,
+                envelope: MessageEnvelope::Live,
+            });
+        }
+        
+        let (old_group, new_group) = admin.split(
+            &group_id,
+            |m| matches!(m.metadata.get(MEMBER_ID_KEY).map(String::as_str), Some("bob") | Some("carol")),
+            "group/team/frontend".to_string(),
+        ).unwrap();
+        assert_eq!(old_group, group_id);
+        assert_eq!(new_group, GroupId::new("group/team/frontend"));
+        
+        let old_roster = admin.roster(&old_group).unwrap();
+        let new_roster = admin.roster(&new_group).unwrap();
+        assert_eq!(old_roster.members().iter().map(|(m, _)| *m).collect::<Vec<_>>(), vec!["admin", "dave"]);
+        assert_eq!(new_roster.members().iter().map(|(m, _)| *m).collect::<Vec<_>>(), vec!["admin", "bob", "carol"]);
+        assert_eq!(new_roster.role_of("carol"), Some(&GroupRole::Moderator));
+        
+        // Every message lands in exactly one group
+        let senders = |id: &GroupId| admin.get_message_history(id).unwrap().iter()
+            .map(|m| m.sender.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(senders(&old_group), vec!["admin", "dave"]);
+        assert_eq!(senders(&new_group), vec!["bob", "carol", "bob"]);
+        
+        let events = admin.take_membership_events();
+        let splits = events.iter().filter(|(_, e)| matches!(e, MembershipEvent::GroupSplit { .. })).count();
+        let invited: Vec<&str> = events.iter()
+            .filter_map(|(to, e)| match e {
+                MembershipEvent::Invitation(invitation) if invitation.group_id == new_group => Some(to.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(splits, 3);
+        assert_eq!(invited, vec!["bob", "carol"]);
+        assert!(admin.take_membership_events().is_empty());
+        
+        // The new group's ID is taken now, and members cannot split
+        assert!(matches!(
+            admin.split(&group_id, |_| true, "group/team/frontend".to_string()),
+            Err(GroupCommunicationError::GroupExists(_))
+        ));
+        let mut bob = roster_node("bob", &group_id, false);
+        assert!(matches!(
+            bob.split(&group_id, |_| true, "group/other".to_string()),
+            Err(GroupCommunicationError::InsufficientPermissions)
+        ));
+    }
+    
+    #[test]
+    fn test_concurrent_changes_resolve_deterministically() {
+        let group_id = GroupId::new("group/team");
+        let now = chrono::Utc::now();
+        let mut base = GroupRoster::new(group_id.clone());
+        base.record_change("alice", join("bob", GroupRole::Member), now);
+        
+        // Two admins change bob concurrently at the same epoch
+        let remove = MembershipDelta {
+            group_id: group_id.clone(),
+            epoch: 2,
+            actor: "alice".to_string(),
+            change: MembershipChange::Leave { member: "bob".to_string() },
+            timestamp: now,
+        };
+        let promote = MembershipDelta {
+            group_id: group_id.clone(),
+            epoch: 2,
+            actor: "zed".to_string(),
+            change: MembershipChange::RoleChange { member: "bob".to_string(), role: GroupRole::Moderator },
+            timestamp: now,
+        };
+        
+        let mut first = base.clone();
+        assert_eq!(first.apply_delta(&remove), DeltaOutcome::Applied);
+        assert_eq!(first.apply_delta(&promote), DeltaOutcome::Applied);
+        
+        let mut second = base.clone();
+        assert_eq!(second.apply_delta(&promote), DeltaOutcome::Applied);
+        assert_eq!(second.apply_delta(&remove), DeltaOutcome::Superseded);
+        
+        assert_eq!(first.membership_hash(), second.membership_hash());
+        assert_eq!(first.role_of("bob"), Some(&GroupRole::Moderator));
+    }
+    
+    fn text(sender: &str, content: &str) -> Message {
+        Message {
+            id: MessageId::new(),
+            content: content.to_string(),
+            sender: sender.to_string(),
+            timestamp: chrono::Utc::now(),
+            metadata: HashMap::new(),
+            priority: MessagePriority::Normal,
+            requires_ack: false,
+            envelope: MessageEnvelope::Live,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_replay_history_to_late_joiner() {
+        let group_id = GroupId::new("group/team");
+        let mut admin = roster_node("admin", &group_id, true);
+        admin.set_history_retention(GroupPattern::new("group/**").with_retention(Duration::from_secs(3600)));
+        
+        let mut expired = text("admin", "before retention");
+        expired.timestamp = Utc::now() - chrono::Duration::hours(2);
+        admin.add_message_to_history(group_id.clone(), expired);
+        admin.add_message_to_history(group_id.clone(), text("admin", "earlier"));
+        let latest = text("admin", "latest");
+        let since = latest.timestamp;
+        admin.add_message_to_history(group_id.clone(), latest);
+        assert_eq!(admin.history_size(&group_id), 2);
+        
+        assert!(matches!(
+            admin.replay_to_participant(&group_id, "dave", None).await,
+            Err(GroupCommunicationError::DeliveryFailed(_))
+        ));
+        let mut stream = admin.connect_participant(&group_id, "dave");
+        assert_eq!(admin.replay_to_participant(&group_id, "dave", None).await.unwrap(), 2);
+        let replayed = stream.recv().await.unwrap();
+        assert_eq!(replayed.content, "earlier");
+        assert!(matches!(replayed.envelope, MessageEnvelope::Replay { .. }));
+        assert_eq!(stream.recv().await.unwrap().content, "latest");
+        
+        assert_eq!(admin.replay_to_participant(&group_id, "dave", Some(since)).await.unwrap(), 1);
+        assert_eq!(stream.recv().await.unwrap().content, "latest");
+    }
+    
+    #[tokio::test]
+    async fn test_export_import_rehomes_group() {
+        let group_id = GroupId::new("group/team");
+        let now = chrono::Utc::now();
+        let keys = NodeKeys::generate().unwrap();
+        let mut admin = roster_node("admin", &group_id, true);
+        let mut bob = roster_node("bob", &group_id, false);
+        
+        for change in [join("admin", GroupRole::Administrator), join("bob", GroupRole::Moderator), join("carol", GroupRole::Member)] {
+            let delta = admin.change_membership(&group_id, change, now).unwrap();
+            bob.handle_membership_message("admin", MembershipMessage::Delta(delta), now).unwrap();
+        }
+        admin.set_group_settings(&group_id, GroupSettings {
+            pattern: Some(GroupPattern::new("group/team")),
+            quotas: GroupQuotas { max_members: Some(4), max_message_size: Some(16) },
+            metadata: (0..16).map(|i| (format!("key-{}", i), format!("value-{}", i))).collect(),
+        }).unwrap();
+        let trusted = HashMap::from([("admin".to_string(), keys.fingerprint())]);
+        
+        // Only administrators export
+        assert!(matches!(bob.export_group(&group_id, &keys), Err(GroupCommunicationError::InsufficientPermissions)));
+        let bundle = admin.export_group(&group_id, &keys).unwrap();
+        assert!(bundle.verify(&trusted));
+        // The signature survives a round trip that rebuilds the settings map
+        let bundle: GroupDefinitionBundle = serde_json::from_slice(&serde_json::to_vec(&bundle).unwrap()).unwrap();
+        assert!(bundle.verify(&trusted));
+        let mut tampered = bundle.clone();
+        tampered.definition.settings.quotas.max_members = None;
+        assert!(!tampered.verify(&trusted));
+        
+        // A bundle self-signed by a key nobody trusts for the admin
+        let impostor = admin.export_group(&group_id, &NodeKeys::generate().unwrap()).unwrap();
+        assert!(!impostor.verify(&trusted));
+        assert!(!bundle.verify(&HashMap::new()));
+        
+        // The admin and carol are gone for good; a fresh node takes over
+        let recovery_keys = NodeKeys::generate().unwrap();
+        let mut recovery = BasicGroupCommunication::new("recovery".to_string());
+        let options = GroupImportOptions {
+            force: false,
+            dormant_members: vec!["admin".to_string(), "carol".to_string()],
+            trusted_keys: trusted.clone(),
+        };
+        for rejected in [tampered, impostor] {
+            assert!(matches!(
+                recovery.import_group(rejected, options.clone(), &recovery_keys),
+                Err(GroupCommunicationError::InvalidBundle(_))
+            ));
+        }
+        recovery.import_group(bundle.clone(), options.clone(), &recovery_keys).unwrap();
+        assert!(matches!(
+            recovery.import_group(bundle.clone(), options.clone(), &recovery_keys),
+            Err(GroupCommunicationError::GroupExists(_))
+        ));
+        recovery.import_group(bundle, GroupImportOptions { force: true, ..options }, &recovery_keys).unwrap();
+        
+        let roster = recovery.roster(&group_id).unwrap();
+        assert_eq!(roster.epoch(), 4);
+        assert_eq!(roster.dormant_members(), vec!["admin", "carol"]);
+        assert_eq!(roster.role_of("carol"), Some(&GroupRole::Member));
+        assert_eq!(roster.members(), vec![("bob", &GroupRole::Moderator), ("recovery", &GroupRole::Administrator)]);
+        
+        // Bob, the only survivor, is told to re-home
+        let events = recovery.take_membership_events();
+        assert!(events.iter().all(|(to, _)| to == "bob"));
+        let rehome = events.last().unwrap().1.clone();
+        let home_trusted = HashMap::from([("recovery".to_string(), recovery_keys.fingerprint())]);
+        assert!(bob.accept_rehome(&rehome, &HashMap::new(), now).is_err());
+        let MembershipEvent::GroupRehomed { group_id: rehomed, home, mut snapshot, settings, signer_public_key, signature } = rehome.clone() else {
+            panic!("expected a re-home event");
+        };
+        snapshot.entries.remove("bob");
+        let forged = MembershipEvent::GroupRehomed { group_id: rehomed, home, snapshot, settings, signer_public_key, signature };
+        assert!(bob.accept_rehome(&forged, &home_trusted, now).is_err());
+        assert!(bob.accept_rehome(&rehome, &home_trusted, now).unwrap());
+        assert_eq!(bob.roster(&group_id).unwrap().membership_hash(), recovery.roster(&group_id).unwrap().membership_hash());
+        assert_eq!(bob.roster(&group_id).unwrap().role_of("bob"), Some(&GroupRole::Moderator));
+        
+        // Messaging resumes under the same quotas
+        assert!(bob.talk(group_id.clone(), text("bob", "back online")).await.is_ok());
+        assert!(matches!(
+            bob.talk(group_id.clone(), text("bob", "this message is too long")).await,
+            Err(GroupCommunicationError::QuotaExceeded(_))
+        ));
+        recovery.change_membership(&group_id, join("dave", GroupRole::Member), now).unwrap();
+        recovery.change_membership(&group_id, join("erin", GroupRole::Member), now).unwrap();
+        assert!(matches!(
+            recovery.change_membership(&group_id, join("frank", GroupRole::Member), now),
+            Err(GroupCommunicationError::QuotaExceeded(_))
+        ));
+    }
+    
+    #[test]
+    fn test_merge_combines_rosters_and_histories() {
+        let (team_a, team_b) = (GroupId::new("group/a"), GroupId::new("group/b"));
+        let now = chrono::Utc::now();
+        let mut admin = roster_node("admin", &team_a, true);
+        admin.add_membership(roster_node("admin", &team_b, true).memberships.remove(&team_b).unwrap());
+        for change in [join("admin", GroupRole::Administrator), join("bob", GroupRole::Moderator), join("carol", GroupRole::Member)] {
+            admin.change_membership(&team_a, change, now).unwrap();
+        }
+        for change in [join("admin", GroupRole::Administrator), join("bob", GroupRole::Member), join("dave", GroupRole::Observer)] {
+            admin.change_membership(&team_b, change, now).unwrap();
+        }
+        let shared = text("bob", "in both");
+        for (group, message, secs) in [(&team_a, text("carol", "a1"), 2), (&team_b, text("dave", "b1"), 1), (&team_a, shared.clone(), 3), (&team_b, shared, 3)] {
+            admin.add_message_to_history(group.clone(), Message { timestamp: now + chrono::Duration::seconds(secs), ..message });
+        }
+        
+        assert!(matches!(
+            admin.merge(&team_a, &team_a, "group/ab".to_string(), MergeConflictResolution::HigherRole),
+            Err(GroupCommunicationError::InvalidMerge(_))
+        ));
+        let merged = admin.merge(&team_a, &team_b, "group/ab".to_string(), MergeConflictResolution::LowerRole).unwrap();
+        assert!(merged.as_str().starts_with("group/ab-"));
+        
+        let roster = admin.roster(&merged).unwrap();
+        assert_eq!(roster.members().iter().map(|(m, _)| *m).collect::<Vec<_>>(), vec!["admin", "bob", "carol", "dave"]);
+        assert_eq!(roster.role_of("bob"), Some(&GroupRole::Member));
+        let contents: Vec<&str> = admin.get_message_history(&merged).unwrap().iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["b1", "a1", "in both"]);
+        
+        // Both sources are closed and every member hears about each group they were in
+        assert!(admin.roster(&team_a).is_none() && admin.roster(&team_b).is_none());
+        let mut events: Vec<(String, String)> = admin.take_membership_events().into_iter()
+            .map(|(to, event)| match event {
+                MembershipEvent::GroupMerged { from_group, to_group } if to_group == merged => (to, from_group.as_str().to_string()),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        events.sort();
+        assert_eq!(events, vec![
+            ("bob".to_string(), "group/a".to_string()),
+            ("bob".to_string(), "group/b".to_string()),
+            ("carol".to_string(), "group/a".to_string()),
+            ("dave".to_string(), "group/b".to_string()),
+        ]);
+        
+        assert_eq!(MergeConflictResolution::HigherRole.resolve(&GroupRole::Member, &GroupRole::Moderator), GroupRole::Moderator);
+        assert_eq!(MergeConflictResolution::KeepGroupA.resolve(&GroupRole::Member, &GroupRole::Moderator), GroupRole::Member);
+    }
 }
@@ -4,10 +4,26 @@
 //! enabling web browsers, mobile apps, and other HTTP-based frontends to
 //! access WeaveMesh collaborative individuation capabilities.
 
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Form, Json, Router};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::api_tokens::{ApiScope, ApiTokenAuthority, ApiTokenError, ApiTokenRecord, NewApiToken};
+use crate::git::hooks::PrePushSource;
+use crate::financial::{FinancialSource, SpendForecast, SpendingLimits, SpendingPeriod, SpendingSummary};
+use crate::mesh::discovery::TrustLevel;
+use crate::mesh::metrics::{render_prometheus, MetricsSource};
+use crate::mesh::topology::{TopologyFilter, TopologyGraphFormat, TopologySource};
+use crate::protocol::{ChannelInfo, ChannelSource, ConnectionDiagnostics, DiagnosticsSource};
+use crate::situation::{BehaviorAdaptationRequest, PinMarker, SituationProviderRegistry, SituationState};
+use crate::WeaveMeshError;
+
 /// HTTP server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpConfig {
@@ -326,6 +342,448 @@ pub struct WeaverResponse {
     pub cost_tokens: u32,
 }
 
+/// Query parameters for `GET /mesh/topology`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TopologyQuery {
+    /// Output format, `dot` or `json` (default)
+    pub format: Option<String>,
+    /// Only include connected nodes
+    pub online_only: Option<bool>,
+    /// Minimum trust level
+    pub min_trust: Option<TrustLevel>,
+    /// Organization namespace
+    pub organization: Option<String>,
+}
+
+impl TopologyQuery {
+    /// Filter described by this query
+    pub fn filter(&self) -> TopologyFilter {
+        TopologyFilter {
+            online_only: self.online_only.unwrap_or(false),
+            min_trust: self.min_trust.clone(),
+            organization: self.organization.clone(),
+        }
+    }
+}
+
+/// Routes for mesh inspection, currently `GET /mesh/topology`
+pub fn mesh_router(source: Arc<dyn TopologySource>) -> Router {
+    Router::new()
+        .route("/mesh/topology", get(get_topology))
+        .with_state(source)
+}
+
+async fn get_topology(
+    State(source): State<Arc<dyn TopologySource>>,
+    Query(query): Query<TopologyQuery>,
+) -> Response {
+    let format = match query.format.as_deref().unwrap_or("json").parse::<TopologyGraphFormat>() {
+        Ok(format) => format,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(ApiError::new("BAD_REQUEST", &e))).into_response(),
+    };
+    
+    let graph = source.topology(&query.filter()).await;
+    match format {
+        TopologyGraphFormat::Dot => ([(header::CONTENT_TYPE, "text/vnd.graphviz")], graph.to_dot()).into_response(),
+        TopologyGraphFormat::Json => Json(graph).into_response(),
+    }
+}
+
+/// Routes for channel introspection, currently `GET /channels`
+pub fn channels_router(source: Arc<dyn ChannelSource>) -> Router {
+    Router::new()
+        .route("/channels", get(get_channels))
+        .with_state(source)
+}
+
+async fn get_channels(State(source): State<Arc<dyn ChannelSource>>) -> Json<Vec<ChannelInfo>> {
+    Json(source.channels().await)
+}
+
+/// Query parameters for `GET /diagnostics/ping/:node_id`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PingQuery {
+    /// How long to wait for the answer, 2000 if unset
+    pub timeout_ms: Option<u64>,
+}
+
+/// Routes for connectivity troubleshooting: `GET /diagnostics` and
+/// `GET /diagnostics/ping/:node_id`
+pub fn diagnostics_router(source: Arc<dyn DiagnosticsSource>) -> Router {
+    Router::new()
+        .route("/diagnostics", get(get_diagnostics))
+        .route("/diagnostics/ping/:node_id", get(ping_node))
+        .with_state(source)
+}
+
+async fn get_diagnostics(State(source): State<Arc<dyn DiagnosticsSource>>) -> Json<ConnectionDiagnostics> {
+    Json(source.connection_diagnostics().await)
+}
+
+async fn ping_node(
+    State(source): State<Arc<dyn DiagnosticsSource>>,
+    Path(node_id): Path<String>,
+    Query(query): Query<PingQuery>,
+) -> Response {
+    let timeout = std::time::Duration::from_millis(query.timeout_ms.unwrap_or(2000));
+    match source.ping_node(&node_id, timeout).await {
+        Ok(result) => Json(result).into_response(),
+        Err(e) => (StatusCode::BAD_GATEWAY, Json(ApiError::new("PING_FAILED", &e.to_string()))).into_response(),
+    }
+}
+
+/// Query parameters for `GET /financial/summary`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FinancialSummaryQuery {
+    /// Period to summarize, Monthly if unset
+    pub period: Option<SpendingPeriod>,
+}
+
+/// Spending summary with the end-of-period forecast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinancialSummaryResponse {
+    /// Spending over the rolling period
+    pub summary: SpendingSummary,
+    /// Projection for the current calendar period, if the period has an end
+    pub forecast: Option<SpendForecast>,
+}
+
+/// Routes for spend reporting and limits
+///
+/// `GET /financial/summary`, `GET /financial/limits` and `PUT /financial/limits`.
+/// Inconsistent limits are rejected with 400 and left unchanged.
+pub fn financial_router(source: Arc<dyn FinancialSource>) -> Router {
+    Router::new()
+        .route("/financial/summary", get(get_financial_summary))
+        .route("/financial/limits", get(get_spending_limits).put(put_spending_limits))
+        .with_state(source)
+}
+
+async fn get_financial_summary(
+    State(source): State<Arc<dyn FinancialSource>>,
+    Query(query): Query<FinancialSummaryQuery>,
+) -> Response {
+    let period = query.period.unwrap_or(SpendingPeriod::Monthly);
+    match source.spending_summary(period.clone()).await {
+        Ok(summary) => Json(FinancialSummaryResponse {
+            summary,
+            forecast: source.forecast(period).await,
+        }).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("INTERNAL_ERROR", &e.to_string()))).into_response(),
+    }
+}
+
+async fn get_spending_limits(State(source): State<Arc<dyn FinancialSource>>) -> Json<SpendingLimits> {
+    Json(source.spending_limits().await)
+}
+
+async fn put_spending_limits(
+    State(source): State<Arc<dyn FinancialSource>>,
+    Json(limits): Json<SpendingLimits>,
+) -> Response {
+    match source.set_spending_limits(limits.clone()).await {
+        Ok(()) => Json(limits).into_response(),
+        Err(WeaveMeshError::Configuration(message)) => {
+            (StatusCode::BAD_REQUEST, Json(ApiError::new("INVALID_LIMITS", &message))).into_response()
+        }
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("INTERNAL_ERROR", &e.to_string()))).into_response(),
+    }
+}
+
+/// Form posted by the pre-push hook to `POST /git/pre-push`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrePushRequest {
+    /// Remote ref being pushed
+    pub remote_ref: String,
+}
+
+/// Routes the pre-push hook talks to
+///
+/// `POST /git/pre-push` starts the ceremony for a ref and answers with its ID
+/// as plain text, or 204 when the ref bypasses the ceremony.
+/// `GET /git/pre-push/:ceremony_id` answers `pending`, `approved` or `rejected`.
+pub fn pre_push_router(source: Arc<dyn PrePushSource>) -> Router {
+    Router::new()
+        .route("/git/pre-push", post(start_pre_push))
+        .route("/git/pre-push/:ceremony_id", get(get_pre_push_status))
+        .with_state(source)
+}
+
+async fn start_pre_push(
+    State(source): State<Arc<dyn PrePushSource>>,
+    Form(request): Form<PrePushRequest>,
+) -> Response {
+    match source.start_pre_push(&request.remote_ref).await {
+        Ok(Some(ceremony_id)) => ceremony_id.into_response(),
+        Ok(None) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("INTERNAL_ERROR", &e.to_string()))).into_response(),
+    }
+}
+
+async fn get_pre_push_status(
+    State(source): State<Arc<dyn PrePushSource>>,
+    Path(ceremony_id): Path<String>,
+) -> Response {
+    match source.pre_push_status(&ceremony_id).await {
+        Some(status) => status.as_str().into_response(),
+        None => (StatusCode::NOT_FOUND, Json(ApiError::new("NOT_FOUND", &format!("Unknown ceremony {}", ceremony_id)))).into_response(),
+    }
+}
+
+/// Routes for Prometheus scraping, currently `GET /metrics`
+pub fn metrics_router(source: Arc<dyn MetricsSource>) -> Router {
+    Router::new()
+        .route("/metrics", get(get_metrics))
+        .with_state(source)
+}
+
+async fn get_metrics(State(source): State<Arc<dyn MetricsSource>>) -> Response {
+    let body = render_prometheus(&source.metrics().await);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response()
+}
+
+/// Bearer tokens of the operators allowed to pin situations
+#[derive(Debug, Clone, Default)]
+pub struct OperatorTokens {
+    actors: HashMap<String, String>,
+}
+
+impl OperatorTokens {
+    /// No operators
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Allow `token` to act as `actor`
+    pub fn with_operator(mut self, token: &str, actor: &str) -> Self {
+        self.actors.insert(token.to_string(), actor.to_string());
+        self
+    }
+    
+    /// Operator presenting the request's bearer token
+    fn actor(&self, headers: &HeaderMap) -> Option<String> {
+        self.actors.get(bearer_token(headers)?).cloned()
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let token = headers.get(header::AUTHORIZATION)?.to_str().ok()?.strip_prefix("Bearer ")?;
+    Some(token.trim())
+}
+
+/// Require a scoped API token on every route of `router`
+///
+/// The scope needed follows from the request path and method, see
+/// [`ApiScope::required_for`]. The authenticated token is available to
+/// handlers as an `Extension<ApiTokenRecord>`.
+///
+/// Tokens with an IP allowlist are checked against the peer address from
+/// `ConnectInfo<SocketAddr>`, so the router must be served through
+/// [`serve_with_connect_info`] (or `into_make_service_with_connect_info`).
+/// Without it such tokens are refused with a 500 configuration error.
+pub fn require_api_tokens(router: Router, authority: Arc<ApiTokenAuthority>) -> Router {
+    router.layer(axum::middleware::from_fn_with_state(authority, authorize_api_token))
+}
+
+/// Serve `router` on `listener`, exposing each peer address as `ConnectInfo<SocketAddr>`
+pub async fn serve_with_connect_info(listener: tokio::net::TcpListener, router: Router) -> std::io::Result<()> {
+    axum::serve(listener, router.into_make_service_with_connect_info::<std::net::SocketAddr>()).await
+}
+
+async fn authorize_api_token(
+    State(authority): State<Arc<ApiTokenAuthority>>,
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let scope = ApiScope::required_for(request.method().as_str(), request.uri().path());
+    let client = request.extensions()
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|info| info.0.ip());
+    let result = match bearer_token(request.headers()) {
+        Some(secret) => authority.authorize(secret, scope, client).await,
+        None => Err(ApiTokenError::Missing),
+    };
+    match result {
+        Ok(record) => {
+            request.extensions_mut().insert(record);
+            next.run(request).await
+        }
+        Err(e) => api_token_error(e),
+    }
+}
+
+fn api_token_error(error: ApiTokenError) -> Response {
+    match error {
+        ApiTokenError::MissingScope(scope) => {
+            let details = HashMap::from([("missing_scope".to_string(), serde_json::json!(scope))]);
+            let body = ApiError::new("FORBIDDEN", &error.to_string()).with_details(details);
+            (StatusCode::FORBIDDEN, Json(body)).into_response()
+        }
+        ApiTokenError::AddressNotAllowed(_) => {
+            (StatusCode::FORBIDDEN, Json(ApiError::new("FORBIDDEN", &error.to_string()))).into_response()
+        }
+        ApiTokenError::ClientAddressUnknown => {
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ApiError::new("CONFIGURATION_ERROR", &error.to_string()))).into_response()
+        }
+        ApiTokenError::InvalidRequest(_) => {
+            (StatusCode::BAD_REQUEST, Json(ApiError::new("BAD_REQUEST", &error.to_string()))).into_response()
+        }
+        ApiTokenError::Missing | ApiTokenError::Unknown | ApiTokenError::Revoked | ApiTokenError::Expired => {
+            (StatusCode::UNAUTHORIZED, Json(ApiError::new("UNAUTHORIZED", &error.to_string()))).into_response()
+        }
+    }
+}
+
+/// Response to `POST /tokens`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuedApiToken {
+    /// Bearer secret; shown only once
+    pub token: String,
+    pub record: ApiTokenRecord,
+}
+
+/// Routes for token management, all requiring the `admin` scope
+///
+/// - `GET /tokens` lists tokens with their last use
+/// - `POST /tokens` issues a token
+/// - `DELETE /tokens/:token_id` revokes a token
+pub fn api_token_router(authority: Arc<ApiTokenAuthority>) -> Router {
+    let router = Router::new()
+        .route("/tokens", get(list_api_tokens).post(issue_api_token))
+        .route("/tokens/:token_id", delete(revoke_api_token))
+        .with_state(Arc::clone(&authority));
+    require_api_tokens(router, authority)
+}
+
+async fn list_api_tokens(State(authority): State<Arc<ApiTokenAuthority>>) -> Json<Vec<ApiTokenRecord>> {
+    Json(authority.list().await)
+}
+
+async fn issue_api_token(
+    State(authority): State<Arc<ApiTokenAuthority>>,
+    axum::Extension(issuer): axum::Extension<ApiTokenRecord>,
+    Json(request): Json<NewApiToken>,
+) -> Response {
+    match authority.issue(request, Some(&issuer)).await {
+        Ok((token, record)) => (StatusCode::CREATED, Json(IssuedApiToken { token, record })).into_response(),
+        Err(e) => api_token_error(e),
+    }
+}
+
+async fn revoke_api_token(
+    State(authority): State<Arc<ApiTokenAuthority>>,
+    axum::Extension(revoker): axum::Extension<ApiTokenRecord>,
+    Path(token_id): Path<Uuid>,
+) -> Response {
+    match authority.revoke(token_id, &revoker.name).await {
+        Some(_) => StatusCode::NO_CONTENT.into_response(),
+        None => (StatusCode::NOT_FOUND, Json(ApiError::new("NOT_FOUND", "No such API token"))).into_response(),
+    }
+}
+
+/// Body of `POST /situations/pins`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinSituationRequest {
+    /// Situation to force
+    pub situation_id: String,
+    /// How long the pin holds
+    pub ttl_seconds: i64,
+    /// Why the situation is forced, for the audit trail
+    pub reason: String,
+    /// Adaptations the situation requests
+    #[serde(default)]
+    pub adaptations: Vec<String>,
+}
+
+/// Response to `POST /situations/pins`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinSituationResponse {
+    /// The pin as recorded
+    pub marker: PinMarker,
+    /// Adaptations requested on pinning
+    pub adaptation_requests: Vec<BehaviorAdaptationRequest>,
+}
+
+#[derive(Clone)]
+struct SituationAdminState {
+    registry: Arc<tokio::sync::RwLock<SituationProviderRegistry>>,
+    operators: Arc<OperatorTokens>,
+}
+
+/// Routes for operator situation overrides, authenticated by bearer token
+///
+/// - `GET /situations/pins` lists unexpired pins
+/// - `POST /situations/pins` pins a situation
+/// - `DELETE /situations/pins/:situation_id` clears a pin
+pub fn situation_router(
+    registry: Arc<tokio::sync::RwLock<SituationProviderRegistry>>,
+    operators: OperatorTokens,
+) -> Router {
+    Router::new()
+        .route("/situations/pins", get(list_pins).post(pin_situation))
+        .route("/situations/pins/:situation_id", delete(unpin_situation))
+        .with_state(SituationAdminState { registry, operators: Arc::new(operators) })
+}
+
+fn unauthorized() -> Response {
+    (StatusCode::UNAUTHORIZED, Json(ApiError::new("UNAUTHORIZED", "A valid operator token is required"))).into_response()
+}
+
+async fn list_pins(State(state): State<SituationAdminState>, headers: HeaderMap) -> Response {
+    if state.operators.actor(&headers).is_none() {
+        return unauthorized();
+    }
+    let registry = state.registry.read().await;
+    let pins: Vec<SituationState> = registry.pinned_situations().into_iter().cloned().collect();
+    Json(pins).into_response()
+}
+
+async fn pin_situation(
+    State(state): State<SituationAdminState>,
+    headers: HeaderMap,
+    Json(request): Json<PinSituationRequest>,
+) -> Response {
+    let Some(actor) = state.operators.actor(&headers) else {
+        return unauthorized();
+    };
+    let now = chrono::Utc::now();
+    let situation = SituationState {
+        situation_id: request.situation_id.clone(),
+        activated_at: now,
+        confidence: 1.0,
+        active_adaptations: request.adaptations,
+        last_update: now,
+        pinned: None,
+    };
+    
+    let mut registry = state.registry.write().await;
+    let ttl = chrono::Duration::seconds(request.ttl_seconds);
+    match registry.pin_situation(situation, ttl, &request.reason, &actor) {
+        Ok(adaptation_requests) => {
+            let marker = registry.pinned_situations().into_iter()
+                .find(|s| s.situation_id == request.situation_id)
+                .and_then(|s| s.pinned.clone())
+                .expect("situation was just pinned");
+            Json(PinSituationResponse { marker, adaptation_requests }).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ApiError::new("BAD_REQUEST", &e.to_string()))).into_response(),
+    }
+}
+
+async fn unpin_situation(
+    State(state): State<SituationAdminState>,
+    headers: HeaderMap,
+    Path(situation_id): Path<String>,
+) -> Response {
+    let Some(actor) = state.operators.actor(&headers) else {
+        return unauthorized();
+    };
+    match state.registry.write().await.unpin_situation(&situation_id, &actor) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ApiError::new("NOT_FOUND", &e.to_string()))).into_response(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -360,4 +818,371 @@ mod tests {
         let deserialized: GroupRole = serde_json::from_str(&serialized).unwrap();
         assert_eq!(role, deserialized);
     }
+
+    struct FixedTopology;
+
+    #[async_trait::async_trait]
+    impl TopologySource for FixedTopology {
+        async fn topology(&self, filter: &TopologyFilter) -> crate::mesh::topology::TopologyGraph {
+            let local = crate::mesh::manager::LocalNode::new();
+            let mut node = crate::mesh::manager::RemoteNode::new(
+                Uuid::from_u128(7),
+                crate::mesh::discovery::NodeCapabilities::default(),
+                TrustLevel::Basic,
+            );
+            node.connection_state = crate::mesh::manager::ConnectionState::Connected;
+            crate::mesh::topology::TopologyGraph::build(&local, &[node], &[], filter)
+        }
+    }
+
+    async fn fetch(uri: &str) -> (StatusCode, String, String) {
+        use tower::ServiceExt;
+
+        let response = mesh_router(Arc::new(FixedTopology))
+            .oneshot(axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let content_type = response.headers()
+            .get(header::CONTENT_TYPE)
+            .map(|v| v.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_topology_endpoint() {
+        let (status, content_type, body) = fetch("/mesh/topology?format=dot").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "text/vnd.graphviz");
+        assert!(body.starts_with("digraph mesh {"));
+
+        let (status, _, body) = fetch("/mesh/topology?format=json&min_trust=Verified").await;
+        assert_eq!(status, StatusCode::OK);
+        let graph: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 1);
+
+        let (status, _, _) = fetch("/mesh/topology?format=svg").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    struct FixedChannels;
+
+    #[async_trait::async_trait]
+    impl ChannelSource for FixedChannels {
+        async fn channels(&self) -> Vec<ChannelInfo> {
+            let mut registry = crate::protocol::ChannelRegistry::default();
+            registry.subscribe("weave/messages/general");
+            registry.list()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channels_endpoint() {
+        use tower::ServiceExt;
+
+        let response = channels_router(Arc::new(FixedChannels))
+            .oneshot(axum::http::Request::get("/channels").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let channels: Vec<ChannelInfo> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(channels.len(), 1);
+        assert_eq!(channels[0].name, "weave/messages/general");
+        assert_eq!(channels[0].subscriber_count, 1);
+        assert_eq!(channels[0].total_messages, 0);
+    }
+
+    #[tokio::test]
+    async fn test_api_token_scopes_per_endpoint_group() {
+        use crate::api_tokens::ApiTokenRegistry;
+        use tower::ServiceExt;
+
+        let authority = Arc::new(ApiTokenAuthority::new(ApiTokenRegistry::new()));
+        let (admin, _) = authority.issue(NewApiToken {
+            name: "ops".to_string(),
+            scopes: [ApiScope::Admin].into(),
+            ttl_seconds: 3600,
+            ip_allowlist: Vec::new(),
+        }, None).await.unwrap();
+
+        let api = require_api_tokens(
+            mesh_router(Arc::new(FixedTopology)).merge(diagnostics_router(Arc::new(FixedDiagnostics))),
+            Arc::clone(&authority),
+        ).merge(api_token_router(Arc::clone(&authority)));
+        let request = |method: &str, uri: &str, token: &str, body: String| {
+            axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(body))
+                .unwrap()
+        };
+        let get = |uri: &str, token: &str| request("GET", uri, token, String::new());
+
+        // Issue a visualizer token through the admin endpoint
+        let create = r#"{"name": "visualizer", "scopes": ["mesh:read"], "ttl_seconds": 600}"#.to_string();
+        let response = api.clone().oneshot(request("POST", "/tokens", &admin, create.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let issued: IssuedApiToken = serde_json::from_slice(&body).unwrap();
+        assert_eq!(issued.record.created_by, "ops");
+
+        let response = api.clone().oneshot(get("/mesh/topology", &issued.token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = api.clone().oneshot(get("/diagnostics", &issued.token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ApiError = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.details.unwrap()["missing_scope"], "debug:read");
+
+        // Only admin tokens manage tokens
+        let response = api.clone().oneshot(request("POST", "/tokens", &issued.token, create)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = api.clone().oneshot(get("/mesh/topology", "made-up")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Revocation applies to the very next request
+        let uri = format!("/tokens/{}", issued.record.token_id);
+        let response = api.clone().oneshot(request("DELETE", &uri, &admin, String::new())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = api.clone().oneshot(get("/mesh/topology", &issued.token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let tokens = authority.list().await;
+        let visualizer = tokens.iter().find(|t| t.name == "visualizer").unwrap();
+        assert_eq!(visualizer.use_count, 1);
+        assert!(visualizer.revoked_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_ip_allowlisted_token_needs_connect_info() {
+        use crate::api_tokens::ApiTokenRegistry;
+        use tower::ServiceExt;
+
+        let authority = Arc::new(ApiTokenAuthority::new(ApiTokenRegistry::new()));
+        let (token, _) = authority.issue(NewApiToken {
+            name: "visualizer".to_string(),
+            scopes: [ApiScope::MeshRead].into(),
+            ttl_seconds: 3600,
+            ip_allowlist: vec!["127.0.0.1".parse().unwrap()],
+        }, None).await.unwrap();
+        let api = require_api_tokens(mesh_router(Arc::new(FixedTopology)), authority);
+
+        // Served without connect info: a configuration error, not a silent refusal
+        let request = axum::http::Request::get("/mesh/topology")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = api.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ApiError = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.code, "CONFIGURATION_ERROR");
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_with_connect_info(listener, api));
+        let response = reqwest::Client::new()
+            .get(format!("http://{}/mesh/topology", addr))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
+    struct FixedDiagnostics;
+
+    #[async_trait::async_trait]
+    impl DiagnosticsSource for FixedDiagnostics {
+        async fn connection_diagnostics(&self) -> ConnectionDiagnostics {
+            ConnectionDiagnostics {
+                zenoh_mode: "peer".to_string(),
+                local_interfaces: vec!["10.0.0.5".to_string()],
+                scouting_status: crate::protocol::ScoutingStatus::Active,
+                known_peers: Vec::new(),
+                multicast_status: crate::protocol::MulticastStatus::Available,
+                last_error: None,
+            }
+        }
+
+        async fn ping_node(&self, node_id: &str, timeout: std::time::Duration) -> anyhow::Result<crate::protocol::PingResult> {
+            match Uuid::parse_str(node_id) {
+                Ok(node_id) => Ok(crate::protocol::PingResult { node_id, round_trip: timeout / 2, sent_at: chrono::Utc::now() }),
+                Err(_) => Err(anyhow::anyhow!("Unknown node: {}", node_id)),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_endpoints() {
+        use tower::ServiceExt;
+
+        let get = |uri: &str| diagnostics_router(Arc::new(FixedDiagnostics))
+            .oneshot(axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap());
+
+        let response = get("/diagnostics").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let diagnostics: ConnectionDiagnostics = serde_json::from_slice(&body).unwrap();
+        assert_eq!(diagnostics.zenoh_mode, "peer");
+
+        let node_id = Uuid::new_v4();
+        let response = get(&format!("/diagnostics/ping/{}?timeout_ms=100", node_id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let result: crate::protocol::PingResult = serde_json::from_slice(&body).unwrap();
+        assert_eq!(result.node_id, node_id);
+        assert_eq!(result.round_trip, std::time::Duration::from_millis(50));
+
+        let response = get("/diagnostics/ping/nobody").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn test_financial_summary_includes_forecast() {
+        use crate::financial::{FinancialManager, OperationType};
+        use tower::ServiceExt;
+
+        let mut manager = FinancialManager::with_defaults();
+        manager.record_operation("op-1".to_string(), OperationType::AI, 40, None, HashMap::new()).unwrap();
+        let source: Arc<dyn FinancialSource> = Arc::new(tokio::sync::RwLock::new(manager));
+
+        let response = financial_router(Arc::clone(&source))
+            .oneshot(axum::http::Request::get("/financial/summary?period=Daily").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let summary: FinancialSummaryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(summary.summary.net_spent, 40);
+        let forecast = summary.forecast.unwrap();
+        assert_eq!(forecast.period, SpendingPeriod::Daily);
+        assert_eq!(forecast.spent_to_date, 40);
+        assert_eq!(forecast.limit, Some(1000));
+
+        let response = financial_router(source)
+            .oneshot(axum::http::Request::get("/financial/summary?period=Total").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let summary: FinancialSummaryResponse = serde_json::from_slice(&body).unwrap();
+        assert!(summary.forecast.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_financial_limits_update() {
+        use crate::financial::FinancialManager;
+        use tower::ServiceExt;
+
+        let source: Arc<dyn FinancialSource> = Arc::new(tokio::sync::RwLock::new(FinancialManager::with_defaults()));
+        let put = |limits: SpendingLimits| {
+            axum::http::Request::put("/financial/limits")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(axum::body::Body::from(serde_json::to_vec(&limits).unwrap()))
+                .unwrap()
+        };
+
+        let raised = SpendingLimits { daily_limit: Some(2000), weekly_limit: Some(8000), ..SpendingLimits::default() };
+        let response = financial_router(Arc::clone(&source)).oneshot(put(raised)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let inverted = SpendingLimits { weekly_limit: Some(500), ..SpendingLimits::default() };
+        let response = financial_router(Arc::clone(&source)).oneshot(put(inverted)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: ApiError = serde_json::from_slice(&body).unwrap();
+        assert!(error.message.contains("daily_limit (1000) exceeds weekly_limit (500)"));
+
+        let response = financial_router(Arc::clone(&source))
+            .oneshot(axum::http::Request::get("/financial/limits").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let limits: SpendingLimits = serde_json::from_slice(&body).unwrap();
+        assert_eq!((limits.daily_limit, limits.weekly_limit), (Some(2000), Some(8000)));
+
+        let response = financial_router(source)
+            .oneshot(axum::http::Request::get("/financial/summary?period=weekly").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        use crate::mesh::metrics::{MetricSample, MetricType};
+        use tower::ServiceExt;
+
+        struct FixedMetrics;
+
+        #[async_trait::async_trait]
+        impl MetricsSource for FixedMetrics {
+            async fn metrics(&self) -> Vec<MetricSample> {
+                vec![MetricSample::new("weavemesh_plugin_audit_events_total", 2.0, MetricType::Counter)]
+            }
+        }
+
+        let response = metrics_router(Arc::new(FixedMetrics))
+            .oneshot(axum::http::Request::get("/metrics").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("# TYPE weavemesh_plugin_audit_events_total counter\n"));
+        assert!(text.contains("weavemesh_plugin_audit_events_total 2\n"));
+    }
+
+    #[tokio::test]
+    async fn test_situation_pin_endpoints_require_operator() {
+        use crate::situation::{RegistryConfig, SituationPinEvent};
+        use tower::ServiceExt;
+
+        let registry = Arc::new(tokio::sync::RwLock::new(SituationProviderRegistry::new(RegistryConfig::default())));
+        let router = situation_router(registry.clone(), OperatorTokens::new().with_operator("s3cret", "oncall-ana"));
+        let request = |method: &str, uri: &str, token: Option<&str>, body: &str| {
+            let mut builder = axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::CONTENT_TYPE, "application/json");
+            if let Some(token) = token {
+                builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            builder.body(axum::body::Body::from(body.to_string())).unwrap()
+        };
+        let pin = r#"{"situation_id":"degraded","ttl_seconds":600,"reason":"incident","adaptations":["shed-load"]}"#;
+
+        for token in [None, Some("wrong")] {
+            let response = router.clone().oneshot(request("POST", "/situations/pins", token, pin)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        assert!(registry.read().await.pinned_situations().is_empty());
+
+        let response = router.clone().oneshot(request("POST", "/situations/pins", Some("s3cret"), pin)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let pinned: PinSituationResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(pinned.marker.actor, "oncall-ana");
+        assert_eq!(pinned.adaptation_requests.len(), 1);
+
+        let response = router.clone().oneshot(request("GET", "/situations/pins", Some("s3cret"), "")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let pins: Vec<SituationState> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(pins[0].situation_id, "degraded");
+
+        let response = router.clone().oneshot(request("DELETE", "/situations/pins/degraded", Some("s3cret"), "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = router.oneshot(request("DELETE", "/situations/pins/degraded", Some("s3cret"), "")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(matches!(
+            registry.read().await.pin_audit_log().last().map(|e| &e.event),
+            Some(SituationPinEvent::Unpinned { actor, .. }) if actor == "oncall-ana"
+        ));
+    }
 }
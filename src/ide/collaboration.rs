@@ -293,6 +293,7 @@ impl CoreCollaborationManager {
     }
     
     /// Start a new collaboration session
+    #[tracing::instrument(skip_all, fields(session_type = ?session_type, participants = participants.len()))]
     pub async fn start_session(
         &mut self,
         session_type: CoreSessionType,
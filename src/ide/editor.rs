@@ -7,11 +7,14 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::attribution::Attribution;
+use crate::git::GitManager;
 use crate::group_communication::{GroupCommunication, GroupId, Message, MessageId};
-use crate::sacred_alliance::{SacredAllianceProvider, ChannelConfig};
+use crate::sacred_alliance::{BasicCeremonyAction, SacredAllianceProvider, ChannelConfig};
 
 /// Core programming language types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -59,6 +62,9 @@ pub struct CoreDocumentMetadata {
     pub collaboration_quality: f64,
     /// Sacred Alliance integration level (0.0 to 1.0)
     pub sacred_alliance_integration: f64,
+    /// Ceremony recommended before editing, set when the file is a conflict hot spot
+    #[serde(default)]
+    pub recommended_ceremony: Option<BasicCeremonyAction>,
 }
 
 impl Default for CoreDocumentMetadata {
@@ -69,6 +75,7 @@ impl Default for CoreDocumentMetadata {
             innovation_markers: Vec::new(),
             collaboration_quality: 0.5,
             sacred_alliance_integration: 0.5,
+            recommended_ceremony: None,
         }
     }
 }
@@ -177,7 +184,7 @@ pub enum CoreChangeType {
 }
 
 /// Core position in document
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CorePosition {
     pub line: usize,
     pub column: usize,
@@ -286,6 +293,33 @@ pub enum CoreResolutionStrategy {
     ManualResolution,
 }
 
+/// Source of the ceremonies recommended for conflict hot spots
+#[async_trait::async_trait]
+pub trait HotFileSource {
+    /// Current recommendations, each naming its file in a `file_path` parameter
+    async fn hot_file_recommendations(&self) -> Vec<BasicCeremonyAction>;
+}
+
+/// Hot files of a git session's repository
+pub struct GitSessionHotFiles {
+    git: Arc<RwLock<GitManager>>,
+    session_id: String,
+}
+
+impl GitSessionHotFiles {
+    /// Recommend reviews for the hot files of `session_id` in `git`
+    pub fn new(git: Arc<RwLock<GitManager>>, session_id: impl Into<String>) -> Self {
+        Self { git, session_id: session_id.into() }
+    }
+}
+
+#[async_trait::async_trait]
+impl HotFileSource for GitSessionHotFiles {
+    async fn hot_file_recommendations(&self) -> Vec<BasicCeremonyAction> {
+        self.git.read().await.hot_file_recommendations(&self.session_id)
+    }
+}
+
 /// Core editor engine
 pub struct CoreEditorEngine {
     /// Open documents
@@ -300,6 +334,10 @@ pub struct CoreEditorEngine {
     pub group_communication: Option<Box<dyn GroupCommunication + Send + Sync>>,
     /// Sacred Alliance provider
     pub sacred_alliance: Option<Box<dyn SacredAllianceProvider + Send + Sync>>,
+    /// Ceremonies recommended for conflict hot spots, keyed by repository-relative path
+    pub hot_file_recommendations: HashMap<String, BasicCeremonyAction>,
+    /// Where hot file recommendations are refreshed from as documents open
+    pub hot_file_source: Option<Box<dyn HotFileSource + Send + Sync>>,
 }
 
 impl CoreEditorEngine {
@@ -317,6 +355,8 @@ impl CoreEditorEngine {
             },
             group_communication: None,
             sacred_alliance: None,
+            hot_file_recommendations: HashMap::new(),
+            hot_file_source: None,
         }
     }
     
@@ -330,12 +370,44 @@ impl CoreEditorEngine {
         self.sacred_alliance = Some(provider);
     }
     
+    /// Replace the hot file recommendations, as from `GitManager::hot_file_recommendations`
+    ///
+    /// Actions without a `file_path` parameter are ignored.
+    pub fn set_hot_file_recommendations(&mut self, actions: Vec<BasicCeremonyAction>) {
+        self.hot_file_recommendations = actions.into_iter()
+            .filter_map(|action| Some((action.parameters.get("file_path")?.clone(), action)))
+            .collect();
+    }
+    
+    /// Refresh hot file recommendations from `source` whenever a document opens
+    pub fn set_hot_file_source(&mut self, source: Box<dyn HotFileSource + Send + Sync>) {
+        self.hot_file_source = Some(source);
+    }
+    
+    /// Follow the hot files of a git session
+    pub fn set_git_session(&mut self, git: Arc<RwLock<GitManager>>, session_id: &str) {
+        self.set_hot_file_source(Box::new(GitSessionHotFiles::new(git, session_id)));
+    }
+    
+    /// Recommendation for the hot file `path` refers to, if any
+    fn hot_file_recommendation(&self, path: &str) -> Option<BasicCeremonyAction> {
+        let path = std::path::Path::new(path);
+        self.hot_file_recommendations.iter()
+            .find(|(file_path, _)| path.ends_with(file_path))
+            .map(|(_, action)| action.clone())
+    }
+    
     /// Open a document for editing
     pub async fn open_document(&mut self, path: &str) -> Result<&CoreDocument> {
         // Read file content
         let content = tokio::fs::read_to_string(path).await
             .map_err(|e| anyhow::anyhow!("Failed to read file: {}", e))?;
         
+        if let Some(source) = &self.hot_file_source {
+            let actions = source.hot_file_recommendations().await;
+            self.set_hot_file_recommendations(actions);
+        }
+        
         // Detect language
         let language = Self::detect_language(path);
         
@@ -359,7 +431,10 @@ impl CoreEditorEngine {
             line_attributions,
             language,
             last_modified: Utc::now(),
-            metadata: CoreDocumentMetadata::default(),
+            metadata: CoreDocumentMetadata {
+                recommended_ceremony: self.hot_file_recommendation(path),
+                ..CoreDocumentMetadata::default()
+            },
         };
         
         // Create Sacred Alliance channel for document if enabled
@@ -488,6 +563,7 @@ impl CoreEditorEngine {
                     metadata: HashMap::new(),
                     priority: crate::group_communication::MessagePriority::Normal,
                     requires_ack: false,
+                    envelope: crate::group_communication::MessageEnvelope::Live,
                 };
                 
                 let group_id = GroupId::new(&format!("editor/{}", change.document_path));
@@ -692,6 +768,47 @@ mod tests {
         assert_eq!(document.line_attributions.len(), 3);
     }
     
+    /// Hot file source with recommendations that can change between opens
+    struct SharedHotFiles(Arc<std::sync::Mutex<Vec<BasicCeremonyAction>>>);
+    
+    #[async_trait::async_trait]
+    impl HotFileSource for SharedHotFiles {
+        async fn hot_file_recommendations(&self) -> Vec<BasicCeremonyAction> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_hot_file_recommendations_follow_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("generated.rs");
+        std::fs::write(&path, "fn generated() {}\n").unwrap();
+        let path = path.to_str().unwrap();
+        
+        let recommendations = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut editor = CoreEditorEngine::new();
+        editor.set_hot_file_source(Box::new(SharedHotFiles(recommendations.clone())));
+        assert!(editor.open_document(path).await.unwrap().metadata.recommended_ceremony.is_none());
+        
+        // A file that became hot is recommended the next time it opens
+        recommendations.lock().unwrap().push(BasicCeremonyAction {
+            action_type: crate::git::HOT_FILE_REVIEW_ACTION.to_string(),
+            description: "generated.rs has had 3 conflicts".to_string(),
+            parameters: HashMap::from([("file_path".to_string(), "generated.rs".to_string())]),
+        });
+        let recommended = editor.open_document(path).await.unwrap().metadata.recommended_ceremony.clone();
+        assert_eq!(recommended.unwrap().action_type, crate::git::HOT_FILE_REVIEW_ACTION);
+        
+        // A git session without conflicts has no hot files, and the editor can still move across tasks
+        let git = Arc::new(RwLock::new(GitManager::new(crate::git::GitManagerConfig::default()).unwrap()));
+        editor.set_git_session(git, "unknown-session");
+        let path = path.to_string();
+        let recommended = tokio::spawn(async move {
+            editor.open_document(&path).await.unwrap().metadata.recommended_ceremony.clone()
+        }).await.unwrap();
+        assert!(recommended.is_none());
+    }
+    
     #[tokio::test]
     async fn test_core_editor_content_changes() {
         let mut editor = CoreEditorEngine::new();
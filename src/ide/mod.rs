@@ -8,13 +8,21 @@ pub mod ceremony;
 pub mod collaboration;
 pub mod editor;
 pub mod project;
+pub mod screen_sharing;
 pub mod security;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use crate::sacred_alliance::{BasicSacredAllianceChannel, ChannelFeed};
+use crate::WeaveMeshError;
+use screen_sharing::{EditorState, ScreenShare};
+
 /// Core IDE integration manager
 #[derive(Debug)]
 pub struct CoreIdeManager {
@@ -43,11 +51,91 @@ pub struct IdeSession {
     /// Current Sacred Alliance channel
     pub alliance_channel: Option<String>,
     
+    /// Live channel behind `alliance_channel`
+    #[serde(skip)]
+    pub channel: Option<SessionChannel>,
+    
     /// Session state
     pub state: SessionState,
     
     /// Session metadata
     pub metadata: HashMap<String, String>,
+    
+    /// Participant currently sharing their editor, if any
+    #[serde(skip)]
+    pub screen_sharing: Option<ScreenShare>,
+}
+
+/// A session's Sacred Alliance channel and its live feed
+#[derive(Clone)]
+pub struct SessionChannel {
+    /// Channel shared by the session's participants
+    pub channel: Arc<RwLock<BasicSacredAllianceChannel>>,
+    feed: ChannelFeed,
+}
+
+impl SessionChannel {
+    /// Wrap `channel`, keeping a handle on its live feed
+    pub async fn new(channel: Arc<RwLock<BasicSacredAllianceChannel>>) -> Self {
+        let feed = channel.read().await.feed();
+        Self { channel, feed }
+    }
+}
+
+impl fmt::Debug for SessionChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionChannel").finish_non_exhaustive()
+    }
+}
+
+impl IdeSession {
+    /// Start broadcasting `sharer_id`'s editor state through the session's alliance channel
+    ///
+    /// Only one participant shares at a time; enabling again for the
+    /// current sharer is a no-op.
+    pub async fn enable_screen_sharing(&mut self, sharer_id: &str) -> Result<()> {
+        if !self.participants.iter().any(|p| p.id == sharer_id) {
+            return Err(anyhow::anyhow!("{} is not a participant in session {}", sharer_id, self.id));
+        }
+        match &self.screen_sharing {
+            Some(share) if share.sharer_id() == sharer_id => Ok(()),
+            Some(_) => Err(WeaveMeshError::SacredAllianceViolation("screen sharing already active".to_string()).into()),
+            None => {
+                let channel = self.channel.as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Session {} has no alliance channel", self.id))?;
+                self.screen_sharing = Some(ScreenShare::start(sharer_id, channel.channel.clone()).await?);
+                Ok(())
+            }
+        }
+    }
+    
+    /// Stop the active screen share, ending every subscriber's stream
+    pub fn disable_screen_sharing(&mut self) -> Result<()> {
+        if let Some(share) = self.screen_sharing.take() {
+            share.stop();
+        }
+        Ok(())
+    }
+    
+    /// Report the sharer's current editor state
+    pub fn update_editor_state(&self, state: EditorState) -> Result<()> {
+        let share = self.screen_sharing.as_ref().ok_or_else(|| anyhow::anyhow!("Screen sharing is not active"))?;
+        share.update(state);
+        Ok(())
+    }
+    
+    /// Participant currently sharing, if any
+    pub fn screen_sharer(&self) -> Option<&str> {
+        self.screen_sharing.as_ref().map(|share| share.sharer_id())
+    }
+    
+    /// Editor states shared in the session's alliance channel, if it has one
+    ///
+    /// Frames from other nodes arrive once the channel is bound to the mesh
+    /// or federated. The stream ends when the current share stops.
+    pub fn screen_sharing_subscriber(&self) -> Option<impl futures::Stream<Item = EditorState>> {
+        self.channel.as_ref().map(|channel| screen_sharing::editor_states(&channel.feed))
+    }
 }
 
 /// Types of IDE sessions
@@ -120,6 +208,7 @@ impl CoreIdeManager {
     }
     
     /// Start a new IDE session
+    #[tracing::instrument(skip_all, fields(session_type = ?session_type, participants = participants.len()))]
     pub async fn start_session(
         &mut self,
         session_type: SessionType,
@@ -136,8 +225,10 @@ impl CoreIdeManager {
             session_type,
             participants,
             alliance_channel: None,
+            channel: None,
             state: SessionState::Initializing,
             metadata: HashMap::new(),
+            screen_sharing: None,
         };
         
         self.sessions.insert(session_id, session);
@@ -161,10 +252,14 @@ impl CoreIdeManager {
         
         // Create Sacred Alliance channel
         let config = crate::sacred_alliance::ChannelConfig::default();
-        // Note: This would integrate with the actual Sacred Alliance provider
+        let mut channel = BasicSacredAllianceChannel::new(channel_id.clone(), config);
         
         if let Some(session) = self.sessions.get_mut(&session_id) {
+            for participant in &session.participants {
+                channel.add_participant(participant.clone())?;
+            }
             session.alliance_channel = Some(channel_id);
+            session.channel = Some(SessionChannel::new(Arc::new(RwLock::new(channel))).await);
         }
         
         Ok(())
@@ -179,6 +274,7 @@ impl CoreIdeManager {
     pub async fn end_session(&mut self, session_id: Uuid) -> Result<()> {
         if let Some(session) = self.sessions.get_mut(&session_id) {
             session.state = SessionState::Completed;
+            session.disable_screen_sharing()?;
             
             // Clean up Sacred Alliance channel
             if let Some(_channel_id) = &session.alliance_channel {
@@ -227,4 +323,70 @@ mod tests {
         manager.end_session(session_id).await.unwrap();
         assert_eq!(manager.list_active_sessions().len(), 0);
     }
+    
+    #[tokio::test]
+    async fn test_screen_sharing() {
+        use crate::sacred_alliance::{AllianceFederation, MessageFederationFilter};
+        use futures::StreamExt;
+        use screen_sharing::CursorPosition;
+        
+        let participants = || ["driver", "navigator"].iter().map(|id| Participant {
+            id: id.to_string(),
+            participant_type: ParticipantType::Human,
+            presence: PresenceStatus::Active,
+            capabilities: vec![],
+            joined_at: Utc::now(),
+        }).collect::<Vec<_>>();
+        let mut manager = CoreIdeManager::new().await.unwrap();
+        let session_id = manager.start_session(SessionType::PairProgramming, participants()).await.unwrap();
+        
+        // The navigator's node holds its own copy of the session, fed through federation
+        let mut remote_manager = CoreIdeManager::new().await.unwrap();
+        let remote_id = remote_manager.start_session(SessionType::PairProgramming, participants()).await.unwrap();
+        let remote = remote_manager.get_session(&remote_id).unwrap();
+        let federation = AllianceFederation::new();
+        let local_channel = manager.get_session(&session_id).unwrap().channel.clone().unwrap().channel;
+        let remote_channel = remote.channel.clone().unwrap().channel;
+        federation.register(local_channel.clone()).await;
+        federation.register(remote_channel.clone()).await;
+        let remote_channel_id = remote.alliance_channel.clone().unwrap();
+        let _link = local_channel.read().await.federate_with(&remote_channel_id, MessageFederationFilter::default()).unwrap();
+        
+        let session = manager.sessions.get_mut(&session_id).unwrap();
+        assert!(session.update_editor_state(state(0)).is_err());
+        session.enable_screen_sharing("driver").await.unwrap();
+        session.enable_screen_sharing("driver").await.unwrap();
+        let err = session.enable_screen_sharing("navigator").await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(WeaveMeshError::SacredAllianceViolation(reason)) if reason == "screen sharing already active"
+        ));
+        
+        let mut local_updates = Box::pin(session.screen_sharing_subscriber().unwrap());
+        let mut remote_updates = Box::pin(remote.screen_sharing_subscriber().unwrap());
+        fn state(line: usize) -> EditorState {
+            EditorState {
+                file: "src/lib.rs".to_string(),
+                visible_range: (0, 40),
+                cursor: CursorPosition { line, column: 4 },
+                selections: vec![],
+            }
+        }
+        // Rapid changes within one tick are coalesced into the latest
+        session.update_editor_state(state(1)).unwrap();
+        session.update_editor_state(state(2)).unwrap();
+        let timeout = std::time::Duration::from_secs(1);
+        assert_eq!(tokio::time::timeout(timeout, local_updates.next()).await.unwrap(), Some(state(2)));
+        assert_eq!(tokio::time::timeout(timeout, remote_updates.next()).await.unwrap(), Some(state(2)));
+        
+        // Frames are delivered live but not kept in either channel's history
+        assert!(local_channel.read().await.get_history().is_empty());
+        assert!(remote_channel.read().await.get_history().is_empty());
+        
+        session.disable_screen_sharing().unwrap();
+        assert_eq!(tokio::time::timeout(timeout, local_updates.next()).await.unwrap(), None);
+        assert_eq!(tokio::time::timeout(timeout, remote_updates.next()).await.unwrap(), None);
+        assert!(session.screen_sharer().is_none());
+        session.enable_screen_sharing("navigator").await.unwrap();
+    }
 }
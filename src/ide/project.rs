@@ -6,10 +6,14 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
+use crate::alliance_progression::{ceremony_completion_rate, AllianceAssessor, AllianceSignals, LevelAssessment};
 use crate::attribution::{Attribution, CollaborationType};
 use crate::group_communication::GroupId;
 use crate::sacred_alliance::SacredAllianceLevel;
@@ -58,6 +62,21 @@ pub struct CoreProjectConfig {
     pub collaboration: CoreProjectCollaborationConfig,
     /// Sacred Alliance settings
     pub sacred_alliance: CoreSacredAllianceConfig,
+    /// How often collaboration metrics are sampled into the history
+    #[serde(default = "default_metrics_sample_interval")]
+    pub metrics_sample_interval: Duration,
+    /// Most metric samples kept; the oldest are dropped first
+    #[serde(default = "default_metrics_history_capacity")]
+    pub metrics_history_capacity: usize,
+}
+
+fn default_metrics_sample_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_metrics_history_capacity() -> usize {
+    // A day of samples at the default interval
+    1440
 }
 
 /// Core build system types
@@ -274,6 +293,65 @@ pub struct CoreCollaborationMetrics {
     pub last_activity: DateTime<Utc>,
 }
 
+impl CoreCollaborationMetrics {
+    /// InfluxDB measurement the metrics are written to
+    pub const MEASUREMENT: &'static str = "collaboration_metrics";
+    
+    /// Numeric fields by name, in a fixed order
+    fn fields(&self) -> [(&'static str, f64); 6] {
+        [
+            ("collaboration_score", self.collaboration_score),
+            ("partnership_balance", self.partnership_balance),
+            ("attribution_transparency", self.attribution_transparency),
+            ("sacred_alliance_level", self.sacred_alliance_level),
+            ("active_contributors", self.active_contributors as f64),
+            ("completed_ceremonies", self.completed_ceremonies as f64),
+        ]
+    }
+    
+    /// One InfluxDB line protocol point, with nanosecond precision
+    ///
+    /// Tags are written sorted by key, as InfluxDB recommends; counts are
+    /// integer fields.
+    pub fn to_influxdb_line(&self, tags: &HashMap<String, String>, timestamp: DateTime<Utc>) -> String {
+        let mut line = Self::MEASUREMENT.to_string();
+        let mut tags: Vec<(&String, &String)> = tags.iter().collect();
+        tags.sort();
+        for (key, value) in tags {
+            line.push_str(&format!(",{}={}", escape_influx_tag(key), escape_influx_tag(value)));
+        }
+        let fields: Vec<String> = self.fields().iter()
+            .map(|(name, value)| match *name {
+                "active_contributors" | "completed_ceremonies" => format!("{}={}i", name, *value as u64),
+                _ => format!("{}={}", name, value),
+            })
+            .collect();
+        let nanos = timestamp.timestamp_nanos_opt().unwrap_or_default();
+        format!("{} {} {}", line, fields.join(","), nanos)
+    }
+    
+    /// Points in the Grafana JSON data source format
+    ///
+    /// One target per metric, each with `[value, unix milliseconds]` datapoints.
+    pub fn to_json_timeseries(points: &[(DateTime<Utc>, CoreCollaborationMetrics)]) -> serde_json::Value {
+        let names = CoreCollaborationMetrics::default().fields().map(|(name, _)| name);
+        let targets: Vec<serde_json::Value> = names.iter().enumerate()
+            .map(|(index, name)| {
+                let datapoints: Vec<serde_json::Value> = points.iter()
+                    .map(|(at, metrics)| serde_json::json!([metrics.fields()[index].1, at.timestamp_millis()]))
+                    .collect();
+                serde_json::json!({ "target": name, "datapoints": datapoints })
+            })
+            .collect();
+        serde_json::Value::Array(targets)
+    }
+}
+
+/// Escape commas, spaces and equals signs in an InfluxDB tag key or value
+fn escape_influx_tag(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(',', "\\,").replace(' ', "\\ ").replace('=', "\\=")
+}
+
 /// Core Sacred Alliance integration for a project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoreSacredAllianceIntegration {
@@ -334,6 +412,46 @@ pub struct CoreSacredAllianceMetrics {
     pub consistency_score: f64,
 }
 
+/// Configuration layer a setting came from, lowest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConfigLayer {
+    /// The manager's default configuration
+    Default,
+    /// The user's configuration file
+    User(PathBuf),
+    /// A `.weavemesh/workspace.toml` above the project
+    Workspace(PathBuf),
+    /// Detected from the project's files
+    Detected,
+    /// The project's `.weavemesh/project.toml`
+    Project(PathBuf),
+}
+
+/// A project's configuration after layering, with the source of each setting
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConfig {
+    /// The merged configuration
+    pub config: CoreProjectConfig,
+    /// Layer each setting came from, keyed by dotted path such as `security.content_filtering`
+    pub sources: BTreeMap<String, ConfigLayer>,
+}
+
+impl EffectiveConfig {
+    /// Layer a setting came from
+    pub fn source_of(&self, field: &str) -> Option<&ConfigLayer> {
+        self.sources.get(field)
+    }
+}
+
+/// Projects whose effective configuration changed on reload
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigChangeEvent {
+    /// Projects affected
+    pub affected_projects: Vec<Uuid>,
+    /// Settings that changed, per project
+    pub changed_fields: HashMap<Uuid, Vec<String>>,
+}
+
 /// Core project manager for handling multiple projects
 #[derive(Debug)]
 pub struct CoreProjectManager {
@@ -345,6 +463,98 @@ pub struct CoreProjectManager {
     pub recent_projects: Vec<Uuid>,
     /// Default project configuration
     pub default_config: CoreProjectConfig,
+    /// User-level configuration file, the lowest file layer
+    user_config_path: Option<PathBuf>,
+    /// Layered configuration of projects opened from disk
+    effective_configs: HashMap<Uuid, EffectiveConfig>,
+    config_events: broadcast::Sender<ConfigChangeEvent>,
+    /// Sampled collaboration metrics per project, oldest first
+    metrics_history: HashMap<Uuid, VecDeque<(DateTime<Utc>, CoreCollaborationMetrics)>>,
+}
+
+/// `weavemesh/config.toml` in the platform's user configuration directory
+pub fn default_user_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("weavemesh").join("config.toml"))
+}
+
+/// `.weavemesh/workspace.toml` files above and at `project_path`, farthest first
+fn workspace_config_paths(project_path: &Path) -> Vec<PathBuf> {
+    let start = project_path.canonicalize().unwrap_or_else(|_| project_path.to_path_buf());
+    let mut paths: Vec<PathBuf> = start.ancestors()
+        .map(|dir| dir.join(".weavemesh").join("workspace.toml"))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.reverse();
+    paths
+}
+
+fn read_toml(path: &Path) -> Result<toml::Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    toml::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Dotted paths and values of every non-table value in `value`
+fn config_leaves(value: &toml::Value, prefix: &str, out: &mut BTreeMap<String, toml::Value>) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (key, child) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                config_leaves(child, &path, out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// Merge `upper` over `base`, field by field, recording `layer` as the source of what it sets
+fn merge_config_layer(
+    base: &mut toml::Table,
+    upper: &toml::Table,
+    prefix: &str,
+    layer: &ConfigLayer,
+    sources: &mut BTreeMap<String, ConfigLayer>,
+) {
+    for (key, value) in upper {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        if let (Some(toml::Value::Table(base_table)), toml::Value::Table(upper_table)) = (base.get_mut(key), value) {
+            merge_config_layer(base_table, upper_table, &path, layer, sources);
+            continue;
+        }
+        
+        let nested = format!("{}.", path);
+        sources.retain(|field, _| *field != path && !field.starts_with(&nested));
+        let mut leaves = BTreeMap::new();
+        config_leaves(value, &path, &mut leaves);
+        sources.extend(leaves.into_keys().map(|field| (field, layer.clone())));
+        base.insert(key.clone(), value.clone());
+    }
+}
+
+/// Entries of `config` that differ from `base`, keeping nested tables sparse
+fn config_overrides(config: &toml::Table, base: &toml::Table) -> toml::Table {
+    let mut overrides = toml::Table::new();
+    for (key, value) in config {
+        match (value, base.get(key)) {
+            (toml::Value::Table(table), Some(toml::Value::Table(base_table))) => {
+                let nested = config_overrides(table, base_table);
+                if !nested.is_empty() {
+                    overrides.insert(key.clone(), toml::Value::Table(nested));
+                }
+            }
+            (value, Some(base_value)) if value == base_value => {}
+            (value, _) => {
+                overrides.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    overrides
 }
 
 impl CoreProjectManager {
@@ -355,9 +565,115 @@ impl CoreProjectManager {
             project_index: HashMap::new(),
             recent_projects: Vec::new(),
             default_config: CoreProjectConfig::default(),
+            user_config_path: default_user_config_path(),
+            effective_configs: HashMap::new(),
+            config_events: broadcast::channel(16).0,
+            metrics_history: HashMap::new(),
         }
     }
     
+    /// Read user-level configuration from `path` instead of the platform default
+    pub fn with_user_config_path(mut self, path: Option<PathBuf>) -> Self {
+        self.user_config_path = path;
+        self
+    }
+    
+    /// Effective configuration changes found by [`reload_config`](Self::reload_config)
+    pub fn subscribe_config_changes(&self) -> broadcast::Receiver<ConfigChangeEvent> {
+        self.config_events.subscribe()
+    }
+    
+    /// Layered configuration of a project opened from disk
+    pub fn get_effective_config(&self, project_id: &Uuid) -> Option<&EffectiveConfig> {
+        self.effective_configs.get(project_id)
+    }
+    
+    /// Merge the configuration layers of the project at `project_path`
+    ///
+    /// From lowest to highest: the default configuration, the user
+    /// configuration, workspace files from the farthest directory to the
+    /// closest, `detected` settings, then `project_config` from `project.toml`.
+    fn layer_config(
+        &self,
+        project_path: &Path,
+        detected: Option<toml::Table>,
+        project_config: Option<(PathBuf, toml::Table)>,
+    ) -> Result<EffectiveConfig> {
+        let mut merged = toml::Table::try_from(&self.default_config)?;
+        let mut sources = BTreeMap::new();
+        config_leaves(&toml::Value::Table(merged.clone()), "", &mut sources);
+        let mut sources: BTreeMap<String, ConfigLayer> = sources.into_keys()
+            .map(|field| (field, ConfigLayer::Default))
+            .collect();
+        
+        let mut layers = Vec::new();
+        if let Some(path) = self.user_config_path.as_ref().filter(|path| path.is_file()) {
+            layers.push((ConfigLayer::User(path.clone()), read_toml(path)?));
+        }
+        for path in workspace_config_paths(project_path) {
+            let value = read_toml(&path)?;
+            layers.push((ConfigLayer::Workspace(path), value));
+        }
+        if let Some(detected) = detected {
+            layers.push((ConfigLayer::Detected, toml::Value::Table(detected)));
+        }
+        if let Some((path, config)) = project_config {
+            layers.push((ConfigLayer::Project(path), toml::Value::Table(config)));
+        }
+        
+        for (layer, value) in &layers {
+            let table = value.as_table()
+                .ok_or_else(|| anyhow::anyhow!("Configuration layer {:?} is not a table", layer))?;
+            merge_config_layer(&mut merged, table, "", layer, &mut sources);
+        }
+        
+        let config = toml::Value::Table(merged).try_into()
+            .map_err(|e| anyhow::anyhow!("Invalid layered project config: {}", e))?;
+        Ok(EffectiveConfig { config, sources })
+    }
+    
+    /// Re-read every configuration layer of the projects opened from disk
+    ///
+    /// Projects whose effective configuration changed are updated and listed
+    /// in the returned event, which is also sent to subscribers when non-empty.
+    pub fn reload_config(&mut self) -> Result<ConfigChangeEvent> {
+        let mut event = ConfigChangeEvent::default();
+        let project_ids: Vec<Uuid> = self.effective_configs.keys().copied().collect();
+        for project_id in project_ids {
+            let Some(project) = self.projects.get(&project_id) else {
+                self.effective_configs.remove(&project_id);
+                continue;
+            };
+            let (_, effective) = self.load_layered(&project.root_path)?;
+            
+            let mut before = BTreeMap::new();
+            let mut after = BTreeMap::new();
+            config_leaves(&toml::Value::try_from(&self.effective_configs[&project_id].config)?, "", &mut before);
+            config_leaves(&toml::Value::try_from(&effective.config)?, "", &mut after);
+            let changed: Vec<String> = before.keys().chain(after.keys())
+                .filter(|field| before.get(*field) != after.get(*field))
+                .cloned()
+                .collect::<std::collections::BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            
+            if !changed.is_empty() {
+                if let Some(project) = self.projects.get_mut(&project_id) {
+                    project.config = effective.config.clone();
+                    project.last_modified = Utc::now();
+                }
+                event.affected_projects.push(project_id);
+                event.changed_fields.insert(project_id, changed);
+            }
+            self.effective_configs.insert(project_id, effective);
+        }
+        
+        if !event.affected_projects.is_empty() {
+            let _ = self.config_events.send(event.clone());
+        }
+        Ok(event)
+    }
+    
     /// Create a new project
     pub fn create_project(
         &mut self,
@@ -397,20 +713,38 @@ impl CoreProjectManager {
             return Ok(project.clone());
         }
         
-        // Try to load project configuration
-        let project = if let Ok(project) = self.load_project_config(&path) {
-            project
-        } else {
-            // Create new project from directory
-            self.create_project_from_directory(&path)?
-        };
+        // Load the project's configuration over its workspace and user layers
+        let (project, effective) = self.load_layered(&path)?;
         
         // Add to manager
+        self.effective_configs.insert(project.id, effective);
         self.add_project(project.clone());
         
         Ok(project)
     }
     
+    /// Project at `path` with its layered configuration
+    ///
+    /// Falls back to a project detected from the directory if `project.toml`
+    /// is missing or unreadable.
+    fn load_layered(&self, path: &PathBuf) -> Result<(CoreProject, EffectiveConfig)> {
+        if let Ok((mut project, project_config)) = self.load_project_config(path) {
+            let effective = self.layer_config(path, None, Some(project_config))?;
+            project.config = effective.config.clone();
+            return Ok((project, effective));
+        }
+        
+        let mut project = self.create_project_from_directory(path)?;
+        let mut detected = toml::Table::new();
+        detected.insert("languages".to_string(), toml::Value::try_from(&project.config.languages)?);
+        if let Some(build_system) = &project.config.build_system {
+            detected.insert("build_system".to_string(), toml::Value::try_from(build_system)?);
+        }
+        let effective = self.layer_config(path, Some(detected), None)?;
+        project.config = effective.config.clone();
+        Ok((project, effective))
+    }
+    
     /// Create project from existing directory
     fn create_project_from_directory(&self, path: &PathBuf) -> Result<CoreProject> {
         let name = path.file_name()
@@ -500,19 +834,34 @@ impl CoreProjectManager {
     }
     
     /// Load project configuration from file
-    fn load_project_config(&self, project_path: &PathBuf) -> Result<CoreProject> {
+    ///
+    /// The `config` table may set only some fields; the project is returned
+    /// with the default configuration alongside the table as written.
+    fn load_project_config(&self, project_path: &Path) -> Result<(CoreProject, (PathBuf, toml::Table))> {
         let config_path = project_path.join(".weavemesh").join("project.toml");
         
         let content = std::fs::read_to_string(&config_path)
             .map_err(|e| anyhow::anyhow!("Failed to read project config: {}", e))?;
         
-        let project: CoreProject = toml::from_str(&content)
+        let mut value: toml::Table = toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse project config: {}", e))?;
+        let project_config = match value.remove("config") {
+            Some(toml::Value::Table(config)) => config,
+            Some(_) => return Err(anyhow::anyhow!("Failed to parse project config: config is not a table")),
+            None => toml::Table::new(),
+        };
+        value.insert("config".to_string(), toml::Value::try_from(&self.default_config)?);
+        
+        let project: CoreProject = toml::Value::Table(value).try_into()
             .map_err(|e| anyhow::anyhow!("Failed to parse project config: {}", e))?;
         
-        Ok(project)
+        Ok((project, (config_path, project_config)))
     }
     
     /// Save project configuration to file
+    ///
+    /// Only settings that differ from the default, user and workspace layers
+    /// are written, so changes to those layers still reach the project.
     pub fn save_project_config(&self, project: &CoreProject) -> Result<()> {
         let config_path = project.root_path.join(".weavemesh").join("project.toml");
         
@@ -522,8 +871,16 @@ impl CoreProjectManager {
                 .map_err(|e| anyhow::anyhow!("Failed to create .weavemesh directory: {}", e))?;
         }
         
-        // Serialize project to TOML
-        let toml_content = toml::to_string_pretty(project)
+        // Serialize project to TOML, keeping only the project's own settings
+        let lower = self.layer_config(&project.root_path, None, None)?;
+        let mut value = toml::Table::try_from(project)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize project: {}", e))?;
+        let config = toml::Table::try_from(&project.config)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize project: {}", e))?;
+        let lower = toml::Table::try_from(&lower.config)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize project: {}", e))?;
+        value.insert("config".to_string(), toml::Value::Table(config_overrides(&config, &lower)));
+        let toml_content = toml::to_string_pretty(&value)
             .map_err(|e| anyhow::anyhow!("Failed to serialize project: {}", e))?;
         
         // Write to file
@@ -547,6 +904,7 @@ impl CoreProjectManager {
         }
         
         self.projects.insert(project.id, project);
+        self.sample_metrics(Utc::now());
     }
     
     /// Find project by path
@@ -560,7 +918,11 @@ impl CoreProjectManager {
     }
     
     /// Get mutable project by ID
+    ///
+    /// Metrics that are due are sampled first, so the history keeps the
+    /// values from before any change made through the reference.
     pub fn get_project_mut(&mut self, id: &Uuid) -> Option<&mut CoreProject> {
+        self.sample_metrics(Utc::now());
         self.projects.get_mut(id)
     }
     
@@ -582,6 +944,64 @@ impl CoreProjectManager {
             .collect()
     }
     
+    /// Sample the metrics of every project whose sample interval has passed
+    ///
+    /// The manager samples on its own whenever it adds or changes a project;
+    /// this samples at other times. Returns the number of projects sampled.
+    pub fn sample_metrics(&mut self, at: DateTime<Utc>) -> usize {
+        let mut sampled = 0;
+        for project in self.projects.values() {
+            let history = self.metrics_history.entry(project.id).or_default();
+            let due = match history.back() {
+                Some((last, _)) => (at - *last).to_std().is_ok_and(|elapsed| elapsed >= project.config.metrics_sample_interval),
+                None => true,
+            };
+            if !due {
+                continue;
+            }
+            history.push_back((at, project.collaboration_metrics.clone()));
+            while history.len() > project.config.metrics_history_capacity {
+                history.pop_front();
+            }
+            sampled += 1;
+        }
+        self.metrics_history.retain(|id, _| self.projects.contains_key(id));
+        sampled
+    }
+    
+    /// Sampled metrics of a project between `from` and `to`, inclusive, oldest first
+    pub fn metrics_history(&self, project_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<(DateTime<Utc>, CoreCollaborationMetrics)> {
+        self.metrics_history.get(&project_id)
+            .map(|history| history.iter()
+                .filter(|(at, _)| *at >= from && *at <= to)
+                .cloned()
+                .collect())
+            .unwrap_or_default()
+    }
+    
+    /// Shortest sample interval of any project, or the default one without projects
+    fn metrics_tick(&self) -> Duration {
+        self.projects.values()
+            .map(|project| project.config.metrics_sample_interval)
+            .min()
+            .unwrap_or(self.default_config.metrics_sample_interval)
+    }
+    
+    /// Spawn a background task sampling metrics into the history
+    ///
+    /// Only needed for samples while the manager is otherwise idle. Wakes at
+    /// the shortest project sample interval; each project is only sampled
+    /// once its own interval has passed.
+    pub fn spawn_metrics_sampler(manager: Arc<tokio::sync::RwLock<Self>>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                manager.write().await.sample_metrics(Utc::now());
+                let tick = manager.read().await.metrics_tick();
+                tokio::time::sleep(tick.max(Duration::from_millis(1))).await;
+            }
+        })
+    }
+    
     /// Update project collaboration metrics
     pub fn update_collaboration_metrics(
         &mut self,
@@ -610,6 +1030,7 @@ impl CoreProjectManager {
             // Update last activity
             project.collaboration_metrics.last_activity = Utc::now();
             project.last_modified = Utc::now();
+            self.sample_metrics(Utc::now());
         }
         
         Ok(())
@@ -659,6 +1080,7 @@ impl CoreProjectManager {
                 (project.collaboration_metrics.sacred_alliance_level + ceremony_impact).min(1.0);
             
             project.last_modified = Utc::now();
+            self.sample_metrics(Utc::now());
             
             return Ok(ceremony_id);
         }
@@ -666,6 +1088,31 @@ impl CoreProjectManager {
         Err(anyhow::anyhow!("Project not found"))
     }
     
+    /// Reassess a project's Sacred Alliance level
+    ///
+    /// Ceremony completion is measured from the project's recent ceremonies
+    /// when it has any; the other signals come from the caller.
+    pub fn assess_alliance_level(
+        &mut self,
+        project_id: &Uuid,
+        assessor: &mut AllianceAssessor,
+        mut signals: AllianceSignals,
+    ) -> Result<LevelAssessment> {
+        let project = self.projects.get_mut(project_id)
+            .ok_or_else(|| anyhow::anyhow!("Project not found"))?;
+        
+        if !project.sacred_alliance.recent_ceremonies.is_empty() {
+            signals.ceremony_completion = ceremony_completion_rate(&project.sacred_alliance.recent_ceremonies);
+        }
+        
+        let scope = project_id.to_string();
+        assessor.track(scope.clone(), project.sacred_alliance.level.clone());
+        let assessment = assessor.assess(&scope, signals, Utc::now());
+        project.sacred_alliance.apply_assessment(&assessment);
+        
+        Ok(assessment)
+    }
+    
     /// Get project collaboration status
     pub fn get_collaboration_status(&self, project_id: &Uuid) -> Option<CoreCollaborationStatus> {
         if let Some(project) = self.projects.get(project_id) {
@@ -751,6 +1198,8 @@ impl Default for CoreProjectConfig {
                 individuation_tracking: true,
                 collaboration_goals: Vec::new(),
             },
+            metrics_sample_interval: default_metrics_sample_interval(),
+            metrics_history_capacity: default_metrics_history_capacity(),
         }
     }
 }
@@ -769,6 +1218,21 @@ impl Default for CoreCollaborationMetrics {
     }
 }
 
+impl CoreSacredAllianceIntegration {
+    /// Adopt the level and signal metrics of an assessment
+    pub fn apply_assessment(&mut self, assessment: &LevelAssessment) {
+        self.level = assessment.level.clone();
+        self.metrics.consistency_score = assessment.signals.presence_consistency;
+        self.metrics.collaboration_enhancement = assessment.signals.attribution_balance;
+        if !self.recent_ceremonies.is_empty() {
+            self.metrics.average_ceremony_impact = self.recent_ceremonies
+                .iter()
+                .map(|c| c.collaboration_impact)
+                .sum::<f64>() / self.recent_ceremonies.len() as f64;
+        }
+    }
+}
+
 impl Default for CoreSacredAllianceIntegration {
     fn default() -> Self {
         Self {
@@ -893,4 +1357,163 @@ mod tests {
         assert_eq!(updated_project.sacred_alliance.recent_ceremonies[0].id, ceremony_id);
         assert_eq!(updated_project.collaboration_metrics.completed_ceremonies, 1);
     }
+    
+    #[test]
+    fn test_alliance_assessment() {
+        let mut manager = CoreProjectManager::new();
+        let mut assessor = AllianceAssessor::default();
+        let project = manager.create_project(
+            "Alliance Test".to_string(),
+            "Testing level assessment".to_string(),
+            env::temp_dir().join("test_alliance_project"),
+            None,
+        ).unwrap();
+        
+        manager.record_ceremony(
+            &project.id,
+            CeremonyType::MergeDecision,
+            vec!["human".to_string(), "ai".to_string()],
+            CoreCeremonyOutcome::Successful,
+            0.8,
+        ).unwrap();
+        
+        // Ceremony completion comes from the recorded ceremony, not the caller
+        let signals = AllianceSignals {
+            ceremony_completion: 0.0,
+            attribution_balance: 0.65,
+            conflict_resolution: 0.7,
+            presence_consistency: 0.9,
+        };
+        let assessment = manager.assess_alliance_level(&project.id, &mut assessor, signals).unwrap();
+        assert_eq!(assessment.signals.ceremony_completion, 1.0);
+        
+        let integration = &manager.get_project(&project.id).unwrap().sacred_alliance;
+        assert_eq!(integration.level, SacredAllianceLevel::Facilitator);
+        assert_eq!(integration.metrics.consistency_score, 0.9);
+        assert_eq!(integration.metrics.average_ceremony_impact, 0.8);
+    }
+    
+    #[test]
+    fn test_layered_workspace_config() {
+        let root = tempfile::tempdir().unwrap();
+        let team = root.path().join("team");
+        let project_dir = team.join("service");
+        std::fs::create_dir_all(project_dir.join(".weavemesh")).unwrap();
+        std::fs::create_dir_all(root.path().join(".weavemesh")).unwrap();
+        std::fs::create_dir_all(team.join(".weavemesh")).unwrap();
+        
+        let user_config = root.path().join("user.toml");
+        std::fs::write(&user_config, "[collaboration]\nmax_collaborators = 50\n[sacred_alliance.ceremony_preferences]\ninclude_gratitude = false\n").unwrap();
+        let root_workspace = root.path().join(".weavemesh").join("workspace.toml");
+        std::fs::write(&root_workspace, "[security]\ndefault_classification = \"Internal\"\n[collaboration]\nmax_collaborators = 20\n").unwrap();
+        let team_workspace = team.join(".weavemesh").join("workspace.toml");
+        std::fs::write(&team_workspace, "[collaboration]\nmax_collaborators = 5\n").unwrap();
+        
+        let mut manager = CoreProjectManager::new().with_user_config_path(Some(user_config.clone()));
+        let mut project = manager.open_project(project_dir.to_str().unwrap()).unwrap();
+        project.config.version_control.default_branch = "trunk".to_string();
+        manager.save_project_config(&project).unwrap();
+        
+        // Only the project's own setting is written to project.toml
+        let project_config = project_dir.join(".weavemesh").join("project.toml");
+        let written: toml::Table = toml::from_str(&std::fs::read_to_string(&project_config).unwrap()).unwrap();
+        assert_eq!(
+            written["config"],
+            toml::from_str::<toml::Value>("[version_control]\ndefault_branch = \"trunk\"\n").unwrap(),
+        );
+        
+        let mut manager = CoreProjectManager::new().with_user_config_path(Some(user_config.clone()));
+        let mut changes = manager.subscribe_config_changes();
+        let opened = manager.open_project(project_dir.to_str().unwrap()).unwrap();
+        assert_eq!(opened.id, project.id);
+        assert_eq!(opened.config.collaboration.max_collaborators, 5);
+        assert_eq!(opened.config.version_control.default_branch, "trunk");
+        assert!(!opened.config.sacred_alliance.ceremony_preferences.include_gratitude);
+        assert!(matches!(opened.config.security.default_classification, CoreClassification::Internal));
+        
+        let effective = manager.get_effective_config(&project.id).unwrap();
+        let workspace = |path: &PathBuf| ConfigLayer::Workspace(path.canonicalize().unwrap());
+        assert_eq!(effective.source_of("collaboration.max_collaborators"), Some(&workspace(&team_workspace)));
+        assert_eq!(effective.source_of("security.default_classification"), Some(&workspace(&root_workspace)));
+        assert_eq!(effective.source_of("version_control.default_branch"), Some(&ConfigLayer::Project(project_config.clone())));
+        assert_eq!(
+            effective.source_of("sacred_alliance.ceremony_preferences.include_gratitude"),
+            Some(&ConfigLayer::User(user_config.clone())),
+        );
+        assert_eq!(effective.source_of("collaboration.real_time_enabled"), Some(&ConfigLayer::Default));
+        
+        // Nothing changed on disk
+        assert!(manager.reload_config().unwrap().affected_projects.is_empty());
+        
+        std::fs::write(&root_workspace, "[security]\ndefault_classification = \"Sensitive\"\n[collaboration]\nmax_collaborators = 20\n").unwrap();
+        let event = manager.reload_config().unwrap();
+        assert_eq!(event.affected_projects, vec![project.id]);
+        assert_eq!(event.changed_fields[&project.id], vec!["security.default_classification".to_string()]);
+        assert_eq!(changes.try_recv().unwrap(), event);
+        assert!(matches!(
+            manager.get_project(&project.id).unwrap().config.security.default_classification,
+            CoreClassification::Sensitive
+        ));
+    }
+
+    #[test]
+    fn test_metrics_history_and_export() {
+        let mut manager = CoreProjectManager::new();
+        let mut config = CoreProjectConfig::default();
+        config.metrics_sample_interval = Duration::from_secs(60);
+        config.metrics_history_capacity = 3;
+        let project = manager.create_project(
+            "Metrics".to_string(),
+            String::new(),
+            env::temp_dir().join("metrics_project"),
+            Some(config),
+        ).unwrap();
+        
+        // Adding the project took the first sample
+        let start = Utc::now();
+        let initial = manager.metrics_history(project.id, start - chrono::Duration::hours(1), start);
+        assert_eq!(initial.len(), 1);
+        
+        // A change made through the manager is sampled once the interval has passed
+        let attribution = Attribution::new(
+            Some("alice".to_string()),
+            None,
+            CollaborationType::CoCreated,
+            0.75,
+        );
+        manager.update_collaboration_metrics(&project.id, &attribution).unwrap();
+        assert_eq!(manager.metrics_history(project.id, start - chrono::Duration::hours(1), start).len(), 1);
+        
+        for (offset, score) in [(0, 0.1), (30, 0.2), (60, 0.3), (120, 0.4), (180, 0.5)] {
+            manager.get_project_mut(&project.id).unwrap().collaboration_metrics.collaboration_score = score;
+            manager.sample_metrics(start + chrono::Duration::seconds(offset));
+        }
+        
+        // Samples before 60s came too early and the first was dropped for capacity
+        let history = manager.metrics_history(project.id, start, start + chrono::Duration::hours(1));
+        let scores: Vec<f64> = history.iter().map(|(_, m)| m.collaboration_score).collect();
+        assert_eq!(scores, vec![0.3, 0.4, 0.5]);
+        let window = manager.metrics_history(project.id, start, start + chrono::Duration::seconds(120));
+        assert_eq!(window.len(), 2);
+        assert!(manager.metrics_history(Uuid::new_v4(), start, start).is_empty());
+        
+        let mut tags = HashMap::new();
+        tags.insert("project".to_string(), "Metrics Core".to_string());
+        tags.insert("env".to_string(), "a=b".to_string());
+        let (at, metrics) = &history[0];
+        assert_eq!(
+            metrics.to_influxdb_line(&tags, *at),
+            format!(
+                "collaboration_metrics,env=a\\=b,project=Metrics\\ Core collaboration_score=0.3,partnership_balance=0.5,\
+                 attribution_transparency=0.75,sacred_alliance_level=0.5,active_contributors=0i,completed_ceremonies=0i {}",
+                at.timestamp_nanos_opt().unwrap()
+            )
+        );
+        
+        let series = CoreCollaborationMetrics::to_json_timeseries(&history);
+        let targets = series.as_array().unwrap();
+        assert_eq!(targets.len(), 6);
+        assert_eq!(targets[0]["target"], "collaboration_score");
+        assert_eq!(targets[0]["datapoints"][2], serde_json::json!([0.5, history[2].0.timestamp_millis()]));
+    }
 }
@@ -0,0 +1,146 @@
+//! Screen sharing for IDE sessions
+//!
+//! One participant at a time shares their editor state. The sharer's IDE
+//! reports each change; the latest state is sent into the session's
+//! Sacred Alliance channel on a fixed tick, so bursts of cursor movement
+//! are coalesced. Frames reach other nodes the way every channel message
+//! does, and subscribers read them from the channel's live feed.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::editor::CorePosition;
+use crate::sacred_alliance::{AllianceMessage, BasicSacredAllianceChannel, ChannelFeed, MessageContent};
+
+/// Position of a cursor or selection end
+pub type CursorPosition = CorePosition;
+
+/// How often the sharer's editor state is published
+pub const SCREEN_SHARING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What the sharing participant currently sees in their editor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorState {
+    /// File open in the active editor
+    pub file: String,
+    /// First and last visible line
+    pub visible_range: (usize, usize),
+    pub cursor: CursorPosition,
+    /// Selections as start and end positions
+    pub selections: Vec<(CursorPosition, CursorPosition)>,
+}
+
+/// Frame of a screen share sent through an alliance channel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreenShareUpdate {
+    /// The sharer's latest editor state
+    State(EditorState),
+    /// Sharing stopped
+    Ended,
+}
+
+/// An active screen share
+#[derive(Clone)]
+pub struct ScreenShare {
+    sharer_id: String,
+    updates: Arc<watch::Sender<Option<EditorState>>>,
+    stop: CancellationToken,
+}
+
+impl ScreenShare {
+    /// Start sending `sharer_id`'s editor state into `channel` every [`SCREEN_SHARING_INTERVAL`]
+    ///
+    /// The sharer must be a participant of the channel.
+    pub async fn start(sharer_id: &str, channel: Arc<RwLock<BasicSacredAllianceChannel>>) -> anyhow::Result<Self> {
+        if !channel.read().await.get_participants().iter().any(|p| p.id == sharer_id) {
+            return Err(anyhow::anyhow!("{} is not a participant in the alliance channel", sharer_id));
+        }
+        
+        let (updates, mut latest) = watch::channel(None::<EditorState>);
+        let stop = CancellationToken::new();
+        let sharer = sharer_id.to_string();
+        let stopped = stop.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(SCREEN_SHARING_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = stopped.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if latest.has_changed().unwrap_or(false) {
+                    let state = latest.borrow_and_update().clone();
+                    if let Some(state) = state {
+                        publish(&channel, &sharer, ScreenShareUpdate::State(state)).await;
+                    }
+                }
+            }
+            publish(&channel, &sharer, ScreenShareUpdate::Ended).await;
+        });
+        
+        Ok(Self {
+            sharer_id: sharer_id.to_string(),
+            updates: Arc::new(updates),
+            stop,
+        })
+    }
+    
+    /// Participant sharing their screen
+    pub fn sharer_id(&self) -> &str {
+        &self.sharer_id
+    }
+    
+    /// Record the sharer's current editor state for the next tick
+    pub fn update(&self, state: EditorState) {
+        self.updates.send_replace(Some(state));
+    }
+    
+    /// Stop sharing; subscribers' streams end once the channel delivers the final frame
+    pub fn stop(self) {
+        self.stop.cancel();
+    }
+}
+
+impl fmt::Debug for ScreenShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScreenShare").field("sharer_id", &self.sharer_id).finish()
+    }
+}
+
+/// Send one frame from `sharer` into `channel`
+async fn publish(channel: &RwLock<BasicSacredAllianceChannel>, sharer: &str, update: ScreenShareUpdate) {
+    let message = AllianceMessage {
+        id: Uuid::new_v4(),
+        sender: sharer.to_string(),
+        content: MessageContent::ScreenShare(update),
+        timestamp: Utc::now(),
+        metadata: Default::default(),
+        reply_to: None,
+        forwarded_from: None,
+    };
+    if let Err(e) = channel.write().await.send_message(message) {
+        tracing::warn!("Failed to publish screen share frame from {}: {}", sharer, e);
+    }
+}
+
+/// Editor states shared in the channel behind `feed`, ending when the share stops
+pub fn editor_states(feed: &ChannelFeed) -> impl futures::Stream<Item = EditorState> {
+    futures::stream::unfold(feed.subscribe(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(AllianceMessage { content: MessageContent::ScreenShare(update), .. }) => match update {
+                    ScreenShareUpdate::State(state) => return Some((state, receiver)),
+                    ScreenShareUpdate::Ended => return None,
+                },
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
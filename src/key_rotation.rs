@@ -0,0 +1,236 @@
+//! Node signing keys and their rotation
+//!
+//! Each node holds an Ed25519 key pair. When a key must be replaced, the node
+//! generates a new pair and signs the new public key with the old private key.
+//! The resulting [`KeyRotationRecord`] lets peers that trust the old key move
+//! their trust to the new one without any out-of-band exchange.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::storage::content_checksum;
+
+/// Fingerprint of a public key: hex SHA-256 of its bytes
+pub fn key_fingerprint(public_key: &[u8]) -> String {
+    content_checksum(public_key)
+}
+
+/// Ed25519 signing key pair of a node
+pub struct NodeKeys {
+    key_pair: Ed25519KeyPair,
+    /// PKCS#8 encoding, kept so the keys can be saved
+    pkcs8: Vec<u8>,
+}
+
+impl fmt::Debug for NodeKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeKeys").field("fingerprint", &self.fingerprint()).finish()
+    }
+}
+
+impl NodeKeys {
+    /// Generate a fresh key pair
+    pub fn generate() -> Result<Self> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("Failed to generate node key pair"))?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    /// Load a key pair from its PKCS#8 encoding
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|e| anyhow::anyhow!("Invalid node key pair: {}", e))?;
+        Ok(Self { key_pair, pkcs8: pkcs8.to_vec() })
+    }
+
+    /// Load the key pair saved at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let pkcs8 = std::fs::read(path)
+            .with_context(|| format!("Failed to read node keys {}", path.display()))?;
+        Self::from_pkcs8(&pkcs8)
+    }
+
+    /// Load the key pair saved at `path`, generating and saving one if there is none
+    pub fn load_or_generate(path: &Path) -> Result<Self> {
+        if path.exists() {
+            return Self::load(path);
+        }
+        let keys = Self::generate()?;
+        keys.save(path)?;
+        Ok(keys)
+    }
+
+    /// Save the key pair to `path`, readable by the owner only
+    ///
+    /// The file is written beside `path` and renamed into place, so a crash
+    /// leaves either the old keys or the new ones.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut partial = path.as_os_str().to_os_string();
+        partial.push(".partial");
+        let partial = Path::new(&partial);
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(partial)
+            .with_context(|| format!("Failed to write node keys {}", partial.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::io::Write::write_all(&mut file, &self.pkcs8)?;
+        file.sync_all()?;
+        std::fs::rename(partial, path)?;
+        Ok(())
+    }
+
+    /// Public key bytes
+    pub fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    /// Fingerprint of the public key
+    pub fn fingerprint(&self) -> String {
+        key_fingerprint(self.public_key())
+    }
+
+    /// Sign a message
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.key_pair.sign(message).as_ref().to_vec()
+    }
+
+    /// Replace these keys with `new_keys`, proving continuity with the old ones
+    pub fn rotate(&mut self, node_id: Uuid, new_keys: NodeKeys, rotated_at: DateTime<Utc>) -> KeyRotationRecord {
+        let old_public_key = self.public_key().to_vec();
+        let new_public_key = new_keys.public_key().to_vec();
+        let continuity_proof = self.sign(&continuity_message(node_id, &new_public_key, rotated_at));
+        *self = new_keys;
+
+        KeyRotationRecord {
+            node_id,
+            old_key_fingerprint: key_fingerprint(&old_public_key),
+            new_key_fingerprint: key_fingerprint(&new_public_key),
+            old_public_key,
+            new_public_key,
+            rotated_at,
+            continuity_proof,
+        }
+    }
+}
+
+/// Bytes signed by the old key to authorize a rotation
+fn continuity_message(node_id: Uuid, new_public_key: &[u8], rotated_at: DateTime<Utc>) -> Vec<u8> {
+    let mut message = b"weavemesh-key-rotation:".to_vec();
+    message.extend_from_slice(node_id.as_bytes());
+    message.extend_from_slice(new_public_key);
+    message.extend_from_slice(rotated_at.to_rfc3339().as_bytes());
+    message
+}
+
+/// Announcement that a node replaced its signing key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRotationRecord {
+    /// Node whose key was rotated
+    pub node_id: Uuid,
+    /// Fingerprint of the replaced key
+    pub old_key_fingerprint: String,
+    /// Fingerprint of the new key
+    pub new_key_fingerprint: String,
+    /// Replaced public key
+    pub old_public_key: Vec<u8>,
+    /// New public key
+    pub new_public_key: Vec<u8>,
+    /// When the key was rotated
+    pub rotated_at: DateTime<Utc>,
+    /// Signature over the new public key by the replaced private key
+    pub continuity_proof: Vec<u8>,
+}
+
+impl KeyRotationRecord {
+    /// Whether the holder of `old_pub_key` authorized this rotation
+    pub fn verify_continuity(&self, old_pub_key: &[u8]) -> bool {
+        key_fingerprint(old_pub_key) == self.old_key_fingerprint
+            && key_fingerprint(&self.new_public_key) == self.new_key_fingerprint
+            && UnparsedPublicKey::new(&ED25519, old_pub_key)
+                .verify(
+                    &continuity_message(self.node_id, &self.new_public_key, self.rotated_at),
+                    &self.continuity_proof,
+                )
+                .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::SharedCredentials;
+
+    #[test]
+    fn test_rotation_continuity() {
+        let node_id = Uuid::new_v4();
+        let mut keys = NodeKeys::generate().unwrap();
+        let old_public_key = keys.public_key().to_vec();
+
+        let record = keys.rotate(node_id, NodeKeys::generate().unwrap(), Utc::now());
+        assert_eq!(record.old_key_fingerprint, key_fingerprint(&old_public_key));
+        assert_eq!(record.new_key_fingerprint, keys.fingerprint());
+        assert!(record.verify_continuity(&old_public_key));
+
+        // Neither another key nor a swapped-in new key passes
+        assert!(!record.verify_continuity(&record.new_public_key));
+        let mut forged = record.clone();
+        forged.new_public_key = NodeKeys::generate().unwrap().public_key().to_vec();
+        forged.new_key_fingerprint = key_fingerprint(&forged.new_public_key);
+        assert!(!forged.verify_continuity(&old_public_key));
+    }
+
+    #[test]
+    fn test_credentials_follow_rotation() {
+        let node_id = Uuid::new_v4();
+        let mut keys = NodeKeys::generate().unwrap();
+        let mut credentials = SharedCredentials::default();
+        credentials.public_key_fingerprints.insert(node_id.to_string(), keys.fingerprint());
+
+        let first = keys.rotate(node_id, NodeKeys::generate().unwrap(), Utc::now());
+        let second = keys.rotate(node_id, NodeKeys::generate().unwrap(), Utc::now());
+
+        // Rotations apply in order only
+        assert!(!credentials.apply_key_rotation(&second));
+        assert!(credentials.apply_key_rotation(&first));
+        assert!(credentials.apply_key_rotation(&second));
+        assert_eq!(credentials.public_key_fingerprints[&node_id.to_string()], keys.fingerprint());
+    }
+
+    #[test]
+    fn test_keys_survive_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.key");
+
+        let keys = NodeKeys::load_or_generate(&path).unwrap();
+        assert_eq!(NodeKeys::load_or_generate(&path).unwrap().fingerprint(), keys.fingerprint());
+        assert!(!format!("{:?}", keys).contains("pkcs8"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        // Signatures from the loaded keys verify against the saved public key
+        let loaded = NodeKeys::load(&path).unwrap();
+        UnparsedPublicKey::new(&ED25519, keys.public_key()).verify(b"hello", &loaded.sign(b"hello")).unwrap();
+
+        std::fs::write(&path, b"not a key").unwrap();
+        assert!(NodeKeys::load(&path).is_err());
+    }
+}
@@ -38,7 +38,11 @@
 //! ```
 
 pub mod protocol;
+pub mod transport;
+pub mod key_rotation;
+pub mod managed_channel;
 pub mod sacred_alliance;
+pub mod alliance_progression;
 pub mod group_communication;
 pub mod node;
 pub mod attribution;
@@ -49,51 +53,84 @@ pub use security::*;
 pub mod financial;
 pub mod serialization;
 pub mod storage;
+pub mod backup;
 pub mod tokens;
+pub mod token_ledger;
 pub mod http;
+pub mod api_tokens;
 pub mod situation;
 pub mod git;
 pub mod ide;
 pub mod narrative;
+pub mod resource_profile;
+pub mod telemetry;
+pub mod compute;
+#[cfg(feature = "simulation")]
+pub mod simulation;
 
 // Re-export main types for convenience
 pub use protocol::{
     WeaveProtocol, WeaveConfig, WeaveResource, WeaveKeys,
-    MessageContent, NodeHeartbeat, BasicCeremonyEvent, 
-    BasicAttribution, CollaborationPattern,
+    MessageContent, BatchMessage, NodeHeartbeat, BasicCeremonyEvent, HeartbeatConfig, AdaptiveHeartbeat,
+    BasicAttribution, CollaborationPattern, ChannelInfo, ChannelRegistry, ChannelSource, SubscriptionId,
+    ProtocolDiagnostics, KEY_ROTATION_CONTEXT, ConnectionDiagnostics, DiagnosticsSource,
+    MulticastStatus, PeerInfo, PingResult, ScoutingStatus,
+};
+
+pub use transport::{LocalModeConfig, TransportEvent, TransportMode};
+
+pub use key_rotation::{KeyRotationRecord, NodeKeys, key_fingerprint};
+
+pub use managed_channel::{
+    ManagedChannel, ManagedChannelRegistry, ChannelPolicy, ChannelAccess, ChannelControl,
+    ChannelEvent, ChannelError, ChannelSignature, SignedChannelControl, sign_channel_message,
 };
 
 pub use sacred_alliance::{
     SacredAllianceProvider, SacredAllianceLevel, Participant, ParticipantType, PresenceStatus,
-    AllianceMessage, MessageContent as AllianceMessageContent,
-    BasicCeremonyAction, CodeContent, CollaborationIntent,
-    PresenceUpdate, ChannelConfig, AllianceStatistics,
-    BasicSacredAllianceChannel,
+    AllianceMessage, MessageContent as AllianceMessageContent, MessageId as AllianceMessageId,
+    BasicCeremonyAction, CodeContent, CodeReference, CodeDelivery, CollaborationIntent,
+    PresenceUpdate, ChannelConfig, AllianceStatistics, StatisticsTrend, TrendDirection,
+    BasicSacredAllianceChannel, AllianceError, MessagePolicy, ExportRange, ExportFormat, ChannelExport,
+    CeremonyDeferral, ChannelCeremony, ForwardedMessage, ApprovalMessage,
+    AllianceFederation, FederationHandle, MessageFederationFilter,
+};
+
+pub use alliance_progression::{
+    AllianceAssessor, AllianceSignal, AllianceSignals, LevelAssessment, ProgressionRules,
+    spawn_periodic_assessment,
 };
 
 pub use group_communication::{
-    GroupCommunication, GroupId, MessageId, GroupPattern, Message,
+    GroupCommunication, GroupId, MessageId, GroupPattern, Message, MessageEnvelope,
     MessagePriority, MessageResponse, ResponseType, MessageStream,
     GroupMembership, GroupRole, GroupPermissions, GroupInvitation,
     GroupSyncState, GroupCommunicationError, BasicGroupCommunication,
+    MembershipChange, MembershipDelta, MembershipSnapshot, MembershipDigest, MembershipMessage,
+    RosterEntry, GroupRoster, DeltaOutcome, MembershipEvent, MEMBER_ID_KEY,
+    GroupSettings, GroupQuotas, GroupDefinition, GroupDefinitionBundle, GroupImportOptions,
+    MergeConflictResolution,
 };
 
 pub use node::{
     Node, NodeId, NodeType, AIType, SystemType, SecurityLevel, NodeRole,
     NodeCapability, NodeConfig, NodeInfo, BasicNode, NodeError, NodeBuilder,
+    CapabilityDowngradePolicy, CapabilityDowngradeDecision, NodeEvent,
+    InteractionOutcome, PeerScore,
 };
 
 pub use attribution::{
     Attribution, AttributionId, CollaborationType, AttributionContext,
     AttributionConfig, AttributionAnalysis, BasicAttributionEngine,
     AttributionStatistics, AttributionError, AttributionBuilder,
+    LedgerExport as AttributionLedgerExport, LedgerEntry as AttributionLedgerEntry, IntegrityError,
 };
 
 pub use mesh::{
     MeshManager, MeshDiscovery, MeshNode, NodeCapabilities, TrustLevel,
     LocalNode, RemoteNode, MeshConfig, MeshState, MeshEvent, MeshMetrics,
     ConnectionState, TopologyChangeType, MeshError, MeshInterface,
-    MeshPlugin, PluginRegistry, MeshBuilder,
+    MeshPlugin, PluginRegistry, MeshBuilder, MetricSample, MetricType, MetricsSource,
     // Universal mesh components
     UniversalMeshNode, NodeEndpoint, EndpointType, NodeVersion, 
     NodeAnnouncement, NodeMetrics as MeshNodeMetrics,
@@ -103,14 +140,19 @@ pub use mesh::{
     SyncState, SyncConflict, ConflictType, ConflictDetails, ConflictSeverity,
     ConflictResolution, AccessControl, ContextAccess, Permission as MeshPermission,
     PermissionType, InstancePermissions, VisibilityLevel, ConflictInfo,
-    SessionStatus, CeremonyStatus,
+    SessionStatus, CeremonyStatus, TopologyGraph, TopologyGraphFormat, TopologyFilter,
+    PublicationWorkflow, PublicationPolicy, PublicationStatus, ReviewOutcome,
+    AdmissionConfig, AdmissionDecision, AdmissionRegistry, AdmissionStatus,
 };
 
 pub use networking::{
     ZenohSession, WeaveMeshMessage, MessageType, WeaveMeshTopics,
-    NodeDiscovery, DiscoveryConfig,
-    NodeCommunication, CommunicationConfig, OutgoingMessage, 
-    DeliveryOptions, CommunicationStats,
+    NodeDiscovery, DiscoveryConfig, DiscoveryBackend,
+    NodeCommunication, CommunicationConfig, OutgoingMessage, TraceContext,
+    DeliveryOptions, CommunicationStats, CommunicationRates, PayloadSummary, PayloadSummarizer, ContentClassifier,
+    PeerHandlers, UnhandledTypePolicy, EndpointRouter, EndpointScoringConfig,
+    PreferredEndpointChange, ClockSkewConfig, ClockSkewTracker, BroadcastScope,
+    ControlCommand, ControlEnvelope, ControlKind, NetworkingHealthReport, HealthServerHandle,
 };
 
 pub use security::{
@@ -121,19 +163,46 @@ pub use security::{
 pub use financial::{
     CostRecord, OperationType, SpendingLimits, SpendingPeriod, SpendingSummary,
     ApprovalResult, FinancialTracker, CostEstimator, SimpleCostEstimator,
-    FinancialManager,
+    FinancialManager, MeteringAggregator, MeteringConfig, UsageMeter, UsageSample,
+    UsageKind, ChargebackRecord, ApprovalChannel, ApprovalTicket, TicketOutcome,
+    ApprovalResolution, ChannelApprovalBridge, AllianceApprovalChannel, ApprovalGate, ForecastConfig, SpendForecast, ForecastAlert,
+    FinancialSource, SpendingSnapshot,
+};
+
+pub use resource_profile::{
+    ResourceProfile, ResourceLimits, EvictionCounters, EvictionKind, MemoryFootprint,
+    ComponentFootprint, MemoryEstimate, current_memory_estimate,
+};
+
+pub use telemetry::{TracingConfig, TracingFormat};
+
+pub use compute::{
+    ComputePool, ComputePoolConfig, ComputeCategory, CategoryLimits, CategoryMetrics, ComputeError,
 };
 
 pub use serialization::{serialize, deserialize, serialize_json, deserialize_json};
 
 pub use storage::{
-    Storage, ResourceMetadata as StorageResourceMetadata, AccessControl as StorageAccessControl, StoredResource,
-    ResourceFilter, StorageStats, MemoryStorage,
+    Storage, ResourceMetadata as StorageResourceMetadata, AccessControl as StorageAccessControl, StoredResource, SchemaViolation,
+    ResourceFilter, ResourceQueryCache, ContentQuery, StorageError, StorageStats, MemoryStorage, FileStorage, migrate_resources, StorageKey, content_checksum, content_checksum_on,
+};
+
+pub use backup::{
+    BackupComponent, BackupError, BackupManifest, BackupOptions, ComponentEntry, NodeBackup, RestoreOptions,
+    RestoreReport, read_manifest,
 };
 
 pub use tokens::{
     TokenPolicy, TokenAllocation, AllocationReason, TokenMetadata,
-    TokenAmount, PolicyId, ContributorId, SimpleTokenPolicy, TokenError,
+    TokenAmount, PolicyId, ContributorId, SimpleTokenPolicy, TokenError, TokenResult,
+};
+pub use token_ledger::{
+    TokenLedger, LedgerConfig, LedgerEntry, LedgerEntryKind, LedgerAccount, Posting,
+    LedgerCheckpoint, LedgerFilter, IntegrityReport,
+};
+
+pub use api_tokens::{
+    ApiScope, ApiTokenRecord, ApiTokenRegistry, ApiTokenAuthority, ApiTokenError, NewApiToken,
 };
 
 pub use situation::{
@@ -143,7 +212,8 @@ pub use situation::{
     BehaviorAdaptationRequest, BehaviorAdaptation, AdaptationType, UrgencyLevel,
     BehaviorChange, SituationInitData, EnvironmentInfo, ParticipantInfo,
     CommunicationPattern, TemporalSituation, NetworkTopology, SecuritySituation,
-    BasicSituationProvider,
+    BasicSituationProvider, SituationExplanation, SignalContribution,
+    PinMarker, SituationPinEvent, PinAuditEntry,
 };
 
 /// WeaveMesh Core version
@@ -183,6 +253,41 @@ pub enum WeaveMeshError {
     /// Generic error
     #[error("WeaveMesh error: {0}")]
     Generic(String),
+    
+    /// Error from an `anyhow`-based caller, kept intact with its chain
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl WeaveMeshError {
+    /// Convert into an `anyhow::Error`, preserving the error chain
+    ///
+    /// `anyhow` already converts any `std::error::Error` via `?`; this is the
+    /// explicit form and unwraps errors that originally came from `anyhow`.
+    pub fn into_anyhow(self) -> anyhow::Error {
+        match self {
+            WeaveMeshError::Other(error) => error,
+            other => anyhow::Error::new(other),
+        }
+    }
+    
+    /// Whether the error came from the token system
+    ///
+    /// Recognises converted [`TokenError`]s by their message prefix and
+    /// `anyhow` errors wrapping one.
+    pub fn is_token_related(&self) -> bool {
+        match self {
+            WeaveMeshError::Other(error) => error.downcast_ref::<TokenError>().is_some(),
+            WeaveMeshError::Protocol(message)
+            | WeaveMeshError::SacredAllianceViolation(message)
+            | WeaveMeshError::Network(message)
+            | WeaveMeshError::SystemError(message)
+            | WeaveMeshError::SecurityError(message)
+            | WeaveMeshError::Configuration(message)
+            | WeaveMeshError::Generic(message) => message.starts_with(TokenError::MESSAGE_PREFIX),
+            WeaveMeshError::Serialization(_) => false,
+        }
+    }
 }
 
 /// Result type for WeaveMesh operations
@@ -194,6 +299,11 @@ pub struct WeaveMeshBuilder {
     enable_sacred_alliance: bool,
     enable_heartbeat: bool,
     capabilities: Vec<String>,
+    resource_profile: ResourceProfile,
+    eviction_counters: std::sync::Arc<EvictionCounters>,
+    local_mode: LocalModeConfig,
+    channel_policy: utils::ChannelNamePolicy,
+    tracing: Option<telemetry::TracingConfig>,
 }
 
 impl Default for WeaveMeshBuilder {
@@ -203,6 +313,11 @@ impl Default for WeaveMeshBuilder {
             enable_sacred_alliance: true,
             enable_heartbeat: true,
             capabilities: vec!["basic-node".to_string()],
+            resource_profile: ResourceProfile::Standard,
+            eviction_counters: std::sync::Arc::new(EvictionCounters::new()),
+            local_mode: LocalModeConfig::default(),
+            channel_policy: utils::ChannelNamePolicy::default(),
+            tracing: None,
         }
     }
 }
@@ -219,6 +334,12 @@ impl WeaveMeshBuilder {
         self
     }
     
+    /// Keep the node's signing keys in `path`, so key rotations survive restarts
+    pub fn with_keys_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.config.keys_path = Some(path.into());
+        self
+    }
+    
     /// Enable or disable Sacred Alliance interface
     pub fn with_sacred_alliance(mut self, enable: bool) -> Self {
         self.enable_sacred_alliance = enable;
@@ -243,9 +364,123 @@ impl WeaveMeshBuilder {
         self
     }
     
+    /// Configure local-only mode: starting without Zenoh, falling back to
+    /// it when Zenoh fails to open, and journaling publishes for replay
+    pub fn with_local_mode(mut self, local_mode: LocalModeConfig) -> Self {
+        self.local_mode = local_mode;
+        self
+    }
+    
+    /// Naming rules for channels created or published to through the built protocol
+    pub fn with_channel_policy(mut self, policy: utils::ChannelNamePolicy) -> Self {
+        self.channel_policy = policy;
+        self
+    }
+    
+    /// Install a `tracing` subscriber with this configuration when building,
+    /// unless the application already installed one
+    pub fn with_tracing_config(mut self, config: telemetry::TracingConfig) -> Self {
+        self.tracing = Some(config);
+        self
+    }
+    
+    /// Export spans to the OTLP/HTTP collector at `endpoint`
+    ///
+    /// Uses the default tracing configuration unless one was set.
+    #[cfg(feature = "otlp")]
+    pub fn with_opentelemetry(mut self, endpoint: &str) -> Self {
+        self.tracing.get_or_insert_with(telemetry::TracingConfig::default).otlp_endpoint = Some(endpoint.to_string());
+        self
+    }
+    
+    /// Select the memory profile for this node
+    ///
+    /// The protocol from [`build`](Self::build) and every subsystem created
+    /// through the builder (`security_system`, `node_discovery`, ...) get the
+    /// profile's bounds and share `eviction_counters()`.
+    pub fn with_resource_profile(mut self, profile: ResourceProfile) -> Self {
+        self.resource_profile = profile;
+        self
+    }
+    
+    /// Selected memory profile
+    pub fn resource_profile(&self) -> ResourceProfile {
+        self.resource_profile
+    }
+    
+    /// In-memory bounds of the selected profile
+    pub fn resource_limits(&self) -> ResourceLimits {
+        self.resource_profile.limits()
+    }
+    
+    /// Eviction counters to share with subsystems built for this node
+    pub fn eviction_counters(&self) -> std::sync::Arc<EvictionCounters> {
+        std::sync::Arc::clone(&self.eviction_counters)
+    }
+    
+    /// Security system bounded by the selected profile
+    pub fn security_system(&self, node_id: uuid::Uuid) -> mesh::security::SecuritySystem {
+        let config = mesh::security::SecurityConfig::for_profile(self.resource_profile);
+        mesh::security::SecuritySystem::new(node_id, Some(config)).with_eviction_counters(self.eviction_counters())
+    }
+    
+    /// Node discovery bounded by the selected profile
+    pub fn node_discovery(
+        &self,
+        node_id: uuid::Uuid,
+        zenoh_session: std::sync::Arc<networking::ZenohSession>,
+    ) -> networking::NodeDiscovery {
+        let config = networking::DiscoveryConfig::for_profile(self.resource_profile);
+        networking::NodeDiscovery::new(node_id, zenoh_session, config).with_eviction_counters(self.eviction_counters())
+    }
+    
+    /// Node communication bounded by the selected profile
+    pub fn node_communication(
+        &self,
+        node_id: uuid::Uuid,
+        zenoh_session: std::sync::Arc<networking::ZenohSession>,
+    ) -> networking::NodeCommunication {
+        let config = networking::CommunicationConfig::for_profile(self.resource_profile);
+        networking::NodeCommunication::new(node_id, zenoh_session, config).with_eviction_counters(self.eviction_counters())
+    }
+    
+    /// Financial tracker bounded by the selected profile
+    pub fn financial_tracker(&self, limits: financial::SpendingLimits) -> financial::FinancialTracker {
+        financial::FinancialTracker::for_profile(limits, self.resource_profile).with_eviction_counters(self.eviction_counters())
+    }
+    
+    /// Git conflict detector whose caches are bounded by the selected profile
+    pub fn conflict_detector(&self, git_config: &git::GitManagerConfig) -> anyhow::Result<git::conflict_detection::GitConflictDetector> {
+        Ok(git::conflict_detection::GitConflictDetector::new(git_config)?
+            .with_config(git::conflict_detection::ConflictDetectionConfig::for_profile(self.resource_profile))
+            .with_eviction_counters(self.eviction_counters()))
+    }
+    
+    /// Compute pool sized for the selected profile
+    pub fn compute_pool(&self) -> compute::ComputePool {
+        compute::ComputePool::new(compute::ComputePoolConfig::for_profile(self.resource_profile))
+    }
+    
+    /// Protocol configuration with the profile's message size cap applied
+    pub fn effective_config(&self) -> WeaveConfig {
+        let mut config = self.config.clone();
+        if let Some(cap) = self.resource_limits().max_message_size {
+            config.max_message_size = config.max_message_size.min(cap);
+        }
+        config
+    }
+    
     /// Build the WeaveMesh protocol instance
     pub async fn build(self) -> anyhow::Result<WeaveProtocol> {
-        let protocol = WeaveProtocol::new(self.config).await?;
+        if let Some(tracing) = &self.tracing {
+            if !tracing.apply() {
+                tracing::debug!("Keeping the tracing subscriber installed by the application");
+            }
+        }
+        
+        let protocol = WeaveProtocol::new_with_local_mode(self.effective_config(), self.local_mode)
+            .await?
+            .with_channel_policy(self.channel_policy);
         
         if self.enable_heartbeat {
             protocol.start_heartbeat(self.capabilities).await?;
@@ -270,11 +505,69 @@ pub mod utils {
         Utc::now()
     }
     
+    /// Rules a channel name must follow
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct ChannelNamePolicy {
+        /// Longest allowed name, in bytes
+        pub max_length: usize,
+        /// Allow non-ASCII letters and digits
+        pub allow_unicode: bool,
+        /// Allow `.` besides `-` and `_`
+        pub allow_dots: bool,
+        /// Prefixes reserved for other tenants or the system
+        pub forbidden_prefixes: Vec<String>,
+    }
+    
+    impl Default for ChannelNamePolicy {
+        fn default() -> Self {
+            Self {
+                max_length: 64,
+                allow_unicode: true,
+                allow_dots: false,
+                forbidden_prefixes: Vec::new(),
+            }
+        }
+    }
+    
+    /// Why a channel name was rejected
+    #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+    pub enum ChannelNameError {
+        #[error("channel name is empty")]
+        Empty,
+        #[error("channel name is {actual} bytes, over the limit of {max}")]
+        TooLong { max: usize, actual: usize },
+        #[error("channel name contains forbidden character {0:?}")]
+        ForbiddenCharacter(char),
+        #[error("channel name uses reserved prefix {0:?}")]
+        ForbiddenPrefix(String),
+    }
+    
     /// Validate a channel name
     pub fn validate_channel_name(name: &str) -> bool {
-        !name.is_empty() && 
-        name.len() <= 64 && 
-        name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        validate_channel_name_with_policy(name, &ChannelNamePolicy::default()).is_ok()
+    }
+    
+    /// Validate a channel name against `policy`
+    pub fn validate_channel_name_with_policy(name: &str, policy: &ChannelNamePolicy) -> Result<(), ChannelNameError> {
+        if name.is_empty() {
+            return Err(ChannelNameError::Empty);
+        }
+        if name.len() > policy.max_length {
+            return Err(ChannelNameError::TooLong { max: policy.max_length, actual: name.len() });
+        }
+        let allowed = |c: char| {
+            (if policy.allow_unicode { c.is_alphanumeric() } else { c.is_ascii_alphanumeric() })
+                || c == '-'
+                || c == '_'
+                || (policy.allow_dots && c == '.')
+        };
+        if let Some(c) = name.chars().find(|&c| !allowed(c)) {
+            return Err(ChannelNameError::ForbiddenCharacter(c));
+        }
+        if let Some(prefix) = policy.forbidden_prefixes.iter().find(|p| name.starts_with(p.as_str())) {
+            return Err(ChannelNameError::ForbiddenPrefix(prefix.clone()));
+        }
+        Ok(())
     }
     
     /// Validate a participant ID
@@ -304,14 +597,101 @@ mod tests {
         assert!(builder.capabilities.contains(&"test".to_string()));
     }
     
+    #[test]
+    fn test_builder_resource_profile() {
+        let standard = WeaveMeshBuilder::new();
+        assert_eq!(standard.resource_profile(), ResourceProfile::Standard);
+        assert_eq!(standard.effective_config().max_message_size, WeaveConfig::default().max_message_size);
+        
+        let constrained = WeaveMeshBuilder::new().with_resource_profile(ResourceProfile::Constrained);
+        let limits = constrained.resource_limits();
+        assert_eq!(Some(constrained.effective_config().max_message_size), limits.max_message_size);
+        
+        let security = mesh::security::SecurityConfig::for_profile(constrained.resource_profile());
+        assert_eq!(security.max_events_in_memory, limits.max_security_events);
+        
+        let counters = constrained.eviction_counters();
+        counters.record(EvictionKind::CostRecord, 2);
+        assert_eq!(constrained.eviction_counters().get(EvictionKind::CostRecord), 2);
+        
+        // Subsystems created through the builder get the profile and share its counters
+        let tracker = constrained.financial_tracker(SpendingLimits::default());
+        assert_eq!(tracker.max_records(), limits.max_cost_records);
+        assert!(std::sync::Arc::ptr_eq(tracker.eviction_counters(), &counters));
+        let security = constrained.security_system(uuid::Uuid::new_v4());
+        assert!(std::sync::Arc::ptr_eq(security.eviction_counters(), &counters));
+        assert_eq!(constrained.compute_pool().config().pool_size, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_builder_local_mode() {
+        let protocol = WeaveMeshBuilder::new()
+            .with_heartbeat(false)
+            .with_local_mode(LocalModeConfig { start_local: true, ..LocalModeConfig::default() })
+            .build()
+            .await
+            .unwrap();
+        
+        let diagnostics = protocol.diagnostics().await;
+        assert_eq!(diagnostics.mode, TransportMode::LocalOnly);
+        assert!(matches!(diagnostics.mode_history.as_slice(), [TransportEvent::WentLocal { .. }]));
+        assert_eq!(protocol.discovered_nodes().await, vec![protocol.node_id()]);
+    }
+    
     #[test]
     fn test_utils() {
         assert!(utils::validate_channel_name("test-channel"));
         assert!(!utils::validate_channel_name(""));
         assert!(!utils::validate_channel_name("invalid channel name"));
+        assert!(utils::validate_channel_name("kanäle"));
+        assert!(!utils::validate_channel_name(&"a".repeat(65)));
         
         assert!(utils::validate_participant_id("user123"));
         assert!(!utils::validate_participant_id(""));
         assert!(!utils::validate_participant_id("invalid user id"));
     }
+    
+    #[test]
+    fn test_channel_name_policy() {
+        use utils::{validate_channel_name_with_policy as validate, ChannelNameError, ChannelNamePolicy};
+        let policy = ChannelNamePolicy {
+            max_length: 16,
+            allow_unicode: false,
+            allow_dots: true,
+            forbidden_prefixes: vec!["sys".to_string()],
+        };
+        
+        assert_eq!(validate("tenant-a.general", &policy), Ok(()));
+        assert_eq!(validate("", &policy), Err(ChannelNameError::Empty));
+        assert_eq!(validate("tenant-a.general-x", &policy), Err(ChannelNameError::TooLong { max: 16, actual: 18 }));
+        assert_eq!(validate("kanäle", &policy), Err(ChannelNameError::ForbiddenCharacter('ä')));
+        assert_eq!(validate("system", &policy), Err(ChannelNameError::ForbiddenPrefix("sys".to_string())));
+        assert_eq!(
+            validate("a.b", &ChannelNamePolicy::default()),
+            Err(ChannelNameError::ForbiddenCharacter('.'))
+        );
+    }
+    
+    #[test]
+    fn test_anyhow_conversion() {
+        fn weave_op() -> Result<()> {
+            Err(WeaveMeshError::Network("unreachable".to_string()))
+        }
+        
+        fn anyhow_op() -> anyhow::Result<()> {
+            weave_op()?;
+            Ok(())
+        }
+        
+        let err = anyhow_op().unwrap_err();
+        assert!(matches!(err.downcast_ref::<WeaveMeshError>(), Some(WeaveMeshError::Network(_))));
+        
+        let io = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+        let wrapped: WeaveMeshError = anyhow::Error::new(io).context("saving state").into();
+        assert!(matches!(wrapped, WeaveMeshError::Other(_)));
+        
+        let unwrapped = wrapped.into_anyhow();
+        assert_eq!(unwrapped.to_string(), "saving state");
+        assert!(unwrapped.chain().any(|e| e.downcast_ref::<std::io::Error>().is_some()));
+    }
 }
@@ -0,0 +1,684 @@
+//! Managed channels with explicit membership
+//!
+//! Plain WeaveProtocol channels are implicit: publishing to a name is all it
+//! takes. A managed channel is registered by an owner with a
+//! [`ChannelPolicy`] and keeps a roster of members. Only members may publish;
+//! other nodes' publishes are rejected locally and ignored by receivers, who
+//! record a [`ChannelEvent::RejectedPublish`]. Names that were never
+//! registered stay unmanaged and behave as before.
+//!
+//! The owner is the authority for the roster. It applies join and leave
+//! requests and announces the resulting [`ChannelControl::Roster`]; other
+//! nodes adopt the newest roster they have seen.
+//!
+//! Control messages travel as [`SignedChannelControl`]s and the roster
+//! carries each member's public key. Join and leave requests must be signed
+//! by the requesting node, rosters by the owner, and messages on a managed
+//! channel carry a [`ChannelSignature`] checked against the sender's key on
+//! the roster.
+
+use anyhow::Result;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use uuid::Uuid;
+
+use crate::key_rotation::NodeKeys;
+use crate::protocol::MessageContent;
+use crate::storage::{AccessControl, Storage};
+
+/// Name under which the registry is persisted
+const REGISTRY_RESOURCE_NAME: &str = "managed-channels";
+
+/// Who may join a managed channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChannelAccess {
+    /// Any node may join
+    #[default]
+    Open,
+    /// Only nodes invited by the owner may join
+    InviteOnly,
+}
+
+/// Rules of a managed channel
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ChannelPolicy {
+    /// Who may join
+    pub access: ChannelAccess,
+    /// Messages kept for replay to members; 0 keeps none
+    pub history_retention: usize,
+    /// Maximum members, including the owner (unbounded if None)
+    pub max_members: Option<usize>,
+}
+
+/// A managed channel and its roster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedChannel {
+    /// Channel name
+    pub name: String,
+    /// Node that created the channel and maintains the roster
+    pub owner: Uuid,
+    /// Channel rules
+    pub policy: ChannelPolicy,
+    /// Current members; the owner is always one
+    pub members: BTreeSet<Uuid>,
+    /// Nodes invited to an invite-only channel
+    pub invited: BTreeSet<Uuid>,
+    /// Public key of each member, registered when it joined
+    #[serde(default)]
+    pub member_keys: BTreeMap<Uuid, Vec<u8>>,
+    /// Incremented on every roster change
+    pub roster_version: u64,
+    /// Retained messages, oldest first
+    pub history: VecDeque<MessageContent>,
+}
+
+impl ManagedChannel {
+    /// Whether `node` is a member
+    pub fn is_member(&self, node: &Uuid) -> bool {
+        self.members.contains(node)
+    }
+
+    /// Roster announcement for this channel
+    pub fn roster(&self) -> ChannelControl {
+        ChannelControl::Roster {
+            channel: self.name.clone(),
+            owner: self.owner,
+            policy: self.policy.clone(),
+            members: self.members.clone(),
+            member_keys: self.member_keys.clone(),
+            version: self.roster_version,
+        }
+    }
+    
+    /// Whether `message` on this channel is signed by one of its members
+    pub fn verify_message(&self, message: &MessageContent) -> bool {
+        let Some(signature) = &message.channel_signature else {
+            return false;
+        };
+        let Some(public_key) = self.member_keys.get(&signature.node).filter(|_| self.is_member(&signature.node)) else {
+            return false;
+        };
+        message_signing_bytes(&self.name, message).is_ok_and(|bytes| {
+            UnparsedPublicKey::new(&ED25519, public_key).verify(&bytes, &signature.signature).is_ok()
+        })
+    }
+}
+
+/// Signature of a managed channel message by the node that published it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelSignature {
+    /// Publishing node
+    pub node: Uuid,
+    /// Signature over the channel name and the message without this field
+    pub signature: Vec<u8>,
+}
+
+/// Canonical bytes of `message` on `channel`, as signed by its publisher
+fn message_signing_bytes(channel: &str, message: &MessageContent) -> Result<Vec<u8>> {
+    let mut unsigned = message.clone();
+    unsigned.channel_signature = None;
+    let value = serde_json::to_value((channel, unsigned))?;
+    Ok(serde_json::to_vec(&value)?)
+}
+
+/// Sign `message` for publishing on the managed channel `channel` as `node`
+pub fn sign_channel_message(channel: &str, message: &mut MessageContent, node: Uuid, keys: &NodeKeys) -> Result<()> {
+    message.channel_signature = None;
+    let signature = keys.sign(&message_signing_bytes(channel, message)?);
+    message.channel_signature = Some(ChannelSignature { node, signature });
+    Ok(())
+}
+
+/// Control messages exchanged about a managed channel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelControl {
+    /// Current roster, announced by the owner
+    Roster {
+        channel: String,
+        owner: Uuid,
+        policy: ChannelPolicy,
+        members: BTreeSet<Uuid>,
+        #[serde(default)]
+        member_keys: BTreeMap<Uuid, Vec<u8>>,
+        version: u64,
+    },
+    /// A node asks the owner to join with the key it will sign messages with
+    JoinRequest { channel: String, node: Uuid, public_key: Vec<u8> },
+    /// A node tells the owner it is leaving
+    LeaveRequest { channel: String, node: Uuid },
+}
+
+impl ChannelControl {
+    /// Channel this control message is about
+    pub fn channel(&self) -> &str {
+        match self {
+            ChannelControl::Roster { channel, .. }
+            | ChannelControl::JoinRequest { channel, .. }
+            | ChannelControl::LeaveRequest { channel, .. } => channel,
+        }
+    }
+}
+
+/// A control message with the signature of the node that sent it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedChannelControl {
+    pub control: ChannelControl,
+    /// Public key of the sender
+    pub public_key: Vec<u8>,
+    /// Signature over the canonical JSON of `control`
+    pub signature: Vec<u8>,
+}
+
+impl SignedChannelControl {
+    /// Sign `control` with `keys`
+    pub fn sign(control: ChannelControl, keys: &NodeKeys) -> Result<Self> {
+        let signature = keys.sign(&serde_json::to_vec(&serde_json::to_value(&control)?)?);
+        Ok(Self { control, public_key: keys.public_key().to_vec(), signature })
+    }
+    
+    /// Whether the signature matches `public_key`
+    pub fn verify(&self) -> bool {
+        serde_json::to_value(&self.control)
+            .and_then(|value| serde_json::to_vec(&value))
+            .is_ok_and(|bytes| UnparsedPublicKey::new(&ED25519, &self.public_key).verify(&bytes, &self.signature).is_ok())
+    }
+}
+
+/// Membership changes and rejected traffic on managed channels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelEvent {
+    /// A node joined
+    MemberJoined { channel: String, node: Uuid },
+    /// A node left
+    MemberLeft { channel: String, node: Uuid },
+    /// A message from a non-member was dropped
+    RejectedPublish { channel: String, sender: Option<Uuid> },
+}
+
+/// Errors from managed channel operations
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ChannelError {
+    #[error("Channel already exists: {0}")]
+    AlreadyExists(String),
+
+    #[error("Channel is not managed: {0}")]
+    NotManaged(String),
+
+    #[error("Node {node} is not invited to {channel}")]
+    NotInvited { channel: String, node: Uuid },
+
+    #[error("Channel is full: {0}")]
+    Full(String),
+
+    #[error("Node {node} is not a member of {channel}")]
+    NotMember { channel: String, node: Uuid },
+
+    #[error("Only the owner may change {0}")]
+    NotOwner(String),
+
+    #[error("Control message for {0} is not signed by the node it speaks for")]
+    BadSignature(String),
+
+    #[error("Node {node} is registered on {channel} with another key")]
+    KeyMismatch { channel: String, node: Uuid },
+}
+
+/// Managed channels known to this node, by name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManagedChannelRegistry {
+    channels: HashMap<String, ManagedChannel>,
+    #[serde(skip)]
+    events: Vec<ChannelEvent>,
+    #[serde(skip)]
+    persisted_id: Option<String>,
+}
+
+impl ManagedChannelRegistry {
+    /// Register a managed channel owned by `owner`, who becomes its first member
+    pub fn create(&mut self, name: &str, owner: Uuid, owner_key: &[u8], policy: ChannelPolicy) -> Result<&ManagedChannel, ChannelError> {
+        if self.channels.contains_key(name) {
+            return Err(ChannelError::AlreadyExists(name.to_string()));
+        }
+        let channel = ManagedChannel {
+            name: name.to_string(),
+            owner,
+            policy,
+            members: BTreeSet::from([owner]),
+            invited: BTreeSet::new(),
+            member_keys: BTreeMap::from([(owner, owner_key.to_vec())]),
+            roster_version: 1,
+            history: VecDeque::new(),
+        };
+        Ok(self.channels.entry(name.to_string()).or_insert(channel))
+    }
+
+    /// A managed channel by name
+    pub fn get(&self, name: &str) -> Option<&ManagedChannel> {
+        self.channels.get(name)
+    }
+
+    /// Names of all managed channels
+    pub fn names(&self) -> impl Iterator<Item = String> + '_ {
+        self.channels.keys().cloned()
+    }
+
+    /// Whether `name` is a managed channel
+    pub fn is_managed(&self, name: &str) -> bool {
+        self.channels.contains_key(name)
+    }
+
+    /// Invite `node` to a channel; only the owner may invite
+    pub fn invite(&mut self, name: &str, by: Uuid, node: Uuid) -> Result<(), ChannelError> {
+        let channel = self.owned_mut(name, by)?;
+        channel.invited.insert(node);
+        Ok(())
+    }
+
+    /// Add `node` to the roster with its `public_key`, as the owner does on a join request
+    ///
+    /// Joining twice with the same key is not an error and leaves the roster
+    /// unchanged; a member cannot rejoin with another key.
+    pub fn join(&mut self, name: &str, node: Uuid, public_key: &[u8]) -> Result<&ManagedChannel, ChannelError> {
+        let channel = self
+            .channels
+            .get_mut(name)
+            .ok_or_else(|| ChannelError::NotManaged(name.to_string()))?;
+        if channel.members.contains(&node) {
+            if channel.member_keys.get(&node).is_some_and(|key| key.as_slice() != public_key) {
+                return Err(ChannelError::KeyMismatch { channel: name.to_string(), node });
+            }
+        } else {
+            if channel.policy.access == ChannelAccess::InviteOnly && !channel.invited.contains(&node) {
+                return Err(ChannelError::NotInvited { channel: name.to_string(), node });
+            }
+            if channel.policy.max_members.is_some_and(|max| channel.members.len() >= max) {
+                return Err(ChannelError::Full(name.to_string()));
+            }
+            channel.members.insert(node);
+            channel.member_keys.insert(node, public_key.to_vec());
+            channel.roster_version += 1;
+            self.events.push(ChannelEvent::MemberJoined { channel: name.to_string(), node });
+        }
+        Ok(&self.channels[name])
+    }
+
+    /// Remove `node` from the roster
+    ///
+    /// The owner cannot leave its own channel.
+    pub fn leave(&mut self, name: &str, node: Uuid) -> Result<&ManagedChannel, ChannelError> {
+        let channel = self
+            .channels
+            .get_mut(name)
+            .ok_or_else(|| ChannelError::NotManaged(name.to_string()))?;
+        if node == channel.owner {
+            return Err(ChannelError::NotOwner(name.to_string()));
+        }
+        if !channel.members.remove(&node) {
+            return Err(ChannelError::NotMember { channel: name.to_string(), node });
+        }
+        channel.member_keys.remove(&node);
+        channel.roster_version += 1;
+        self.events.push(ChannelEvent::MemberLeft { channel: name.to_string(), node });
+        Ok(&self.channels[name])
+    }
+
+    /// Adopt a roster announced by a channel's owner and signed with `signer_key`
+    ///
+    /// Rosters older than the one held are ignored, as are rosters for a
+    /// channel claiming a different owner than the one already known and
+    /// rosters not signed with the owner's key. Returns whether the roster
+    /// was adopted.
+    pub fn apply_roster(&mut self, roster: &ChannelControl, signer_key: &[u8]) -> bool {
+        let (name, owner, policy, members, member_keys, version) = match roster {
+            ChannelControl::Roster { channel, owner, policy, members, member_keys, version } => {
+                (channel, owner, policy, members, member_keys, version)
+            }
+            _ => return false,
+        };
+        let owner_key = self.channels.get(name)
+            .map_or(member_keys.get(owner), |channel| channel.member_keys.get(owner));
+        if owner_key.is_none_or(|key| key.as_slice() != signer_key) {
+            return false;
+        }
+        match self.channels.get_mut(name) {
+            Some(channel) => {
+                if channel.owner != *owner || channel.roster_version >= *version {
+                    return false;
+                }
+                for node in members.difference(&channel.members) {
+                    self.events.push(ChannelEvent::MemberJoined { channel: name.clone(), node: *node });
+                }
+                for node in channel.members.difference(members) {
+                    self.events.push(ChannelEvent::MemberLeft { channel: name.clone(), node: *node });
+                }
+                channel.policy = policy.clone();
+                channel.members = members.clone();
+                channel.member_keys = member_keys.clone();
+                channel.roster_version = *version;
+            }
+            None => {
+                self.channels.insert(
+                    name.clone(),
+                    ManagedChannel {
+                        name: name.clone(),
+                        owner: *owner,
+                        policy: policy.clone(),
+                        members: members.clone(),
+                        invited: BTreeSet::new(),
+                        member_keys: member_keys.clone(),
+                        roster_version: *version,
+                        history: VecDeque::new(),
+                    },
+                );
+            }
+        }
+        true
+    }
+
+    /// Apply a signed control message received on a channel's control key
+    ///
+    /// Rosters are adopted per [`apply_roster`](Self::apply_roster). When
+    /// `local` owns the channel, join requests signed with the key they
+    /// register and leave requests signed with the leaving member's key are
+    /// applied, and the new roster to announce is returned.
+    pub fn apply_control(&mut self, signed: &SignedChannelControl, local: Uuid) -> Result<Option<ChannelControl>, ChannelError> {
+        let channel = signed.control.channel().to_string();
+        if !signed.verify() {
+            return Err(ChannelError::BadSignature(channel));
+        }
+        if let ChannelControl::Roster { .. } = &signed.control {
+            self.apply_roster(&signed.control, &signed.public_key);
+            return Ok(None);
+        }
+        // Only the owner answers requests
+        if self.channels.get(&channel).is_none_or(|c| c.owner != local) {
+            return Ok(None);
+        }
+        let roster = match &signed.control {
+            ChannelControl::JoinRequest { node, public_key, .. } => {
+                if *public_key != signed.public_key {
+                    return Err(ChannelError::BadSignature(channel));
+                }
+                self.join(&channel, *node, public_key)?.roster()
+            }
+            ChannelControl::LeaveRequest { node, .. } => {
+                let registered = self.channels.get(&channel).and_then(|c| c.member_keys.get(node));
+                if registered.is_none_or(|key| *key != signed.public_key) {
+                    return Err(ChannelError::BadSignature(channel));
+                }
+                self.leave(&channel, *node)?.roster()
+            }
+            ChannelControl::Roster { .. } => return Ok(None),
+        };
+        Ok(Some(roster))
+    }
+
+    /// Check that `sender` may publish to `name`; unmanaged channels are open
+    pub fn check_publish(&self, name: &str, sender: Uuid) -> Result<(), ChannelError> {
+        match self.channels.get(name) {
+            Some(channel) if !channel.is_member(&sender) => {
+                Err(ChannelError::NotMember { channel: name.to_string(), node: sender })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Decide whether a message received on `name` should be delivered
+    ///
+    /// Messages on unmanaged channels are always delivered. On managed
+    /// channels the sender is the node whose [`ChannelSignature`] verifies
+    /// against its key on the roster; unsigned messages and messages from
+    /// non-members are dropped with a [`ChannelEvent::RejectedPublish`], and
+    /// accepted ones are retained per the channel's policy.
+    pub fn accept_message(&mut self, name: &str, message: &MessageContent) -> bool {
+        let channel = match self.channels.get_mut(name) {
+            Some(channel) => channel,
+            None => return true,
+        };
+        if !channel.verify_message(message) {
+            let sender = message.channel_signature.as_ref().map(|signature| signature.node);
+            self.events.push(ChannelEvent::RejectedPublish { channel: name.to_string(), sender });
+            return false;
+        }
+        if channel.policy.history_retention > 0 {
+            channel.history.push_back(message.clone());
+            while channel.history.len() > channel.policy.history_retention {
+                channel.history.pop_front();
+            }
+        }
+        true
+    }
+
+    /// Drain membership and rejection events recorded since the last call
+    pub fn take_events(&mut self) -> Vec<ChannelEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Persist the channels so policy and rosters survive an owner restart
+    ///
+    /// Replaces any copy previously persisted by this registry and returns
+    /// the new storage ID.
+    pub async fn persist<S: Storage>(&mut self, storage: &mut S) -> Result<String> {
+        let content = serde_json::to_vec(&*self)?;
+        let resource_id = storage.store_resource(
+            REGISTRY_RESOURCE_NAME.to_string(),
+            content,
+            "application/json".to_string(),
+            AccessControl::default(),
+            vec!["managed-channel".to_string()],
+        ).await?;
+
+        if let Some(previous) = self.persisted_id.replace(resource_id.clone()) {
+            let _ = storage.delete_resource(&previous).await;
+        }
+        Ok(resource_id)
+    }
+
+    /// Load a previously persisted registry
+    pub async fn load<S: Storage>(storage: &S, resource_id: &str) -> Result<Self> {
+        let content = storage.get_resource_content(resource_id).await?;
+        let mut registry: Self = serde_json::from_slice(&content)?;
+        registry.persisted_id = Some(resource_id.to_string());
+        Ok(registry)
+    }
+
+    fn owned_mut(&mut self, name: &str, by: Uuid) -> Result<&mut ManagedChannel, ChannelError> {
+        let channel = self
+            .channels
+            .get_mut(name)
+            .ok_or_else(|| ChannelError::NotManaged(name.to_string()))?;
+        if channel.owner != by {
+            return Err(ChannelError::NotOwner(name.to_string()));
+        }
+        Ok(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use chrono::Utc;
+
+    fn node() -> (Uuid, NodeKeys) {
+        (Uuid::new_v4(), NodeKeys::generate().unwrap())
+    }
+
+    fn message_from(channel: &str, sender: Option<(Uuid, &NodeKeys)>, text: &str) -> MessageContent {
+        let mut message = MessageContent {
+            id: Uuid::new_v4(),
+            sender: "someone".to_string(),
+            text: text.to_string(),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            channel_signature: None,
+        };
+        if let Some((node, keys)) = sender {
+            sign_channel_message(channel, &mut message, node, keys).unwrap();
+        }
+        message
+    }
+
+    #[test]
+    fn test_invite_only_channel_rejects_uninvited_nodes() {
+        let ((owner, owner_keys), (guest, guest_keys), (stranger, stranger_keys)) = (node(), node(), node());
+        let mut registry = ManagedChannelRegistry::default();
+        let policy = ChannelPolicy { access: ChannelAccess::InviteOnly, history_retention: 2, max_members: Some(2) };
+        registry.create("council", owner, owner_keys.public_key(), policy).unwrap();
+        assert_eq!(
+            registry.create("council", guest, guest_keys.public_key(), ChannelPolicy::default()).unwrap_err(),
+            ChannelError::AlreadyExists("council".to_string())
+        );
+
+        assert_eq!(
+            registry.join("council", stranger, stranger_keys.public_key()).unwrap_err(),
+            ChannelError::NotInvited { channel: "council".to_string(), node: stranger }
+        );
+        assert_eq!(registry.invite("council", guest, stranger).unwrap_err(), ChannelError::NotOwner("council".to_string()));
+        registry.invite("council", owner, guest).unwrap();
+        registry.invite("council", owner, stranger).unwrap();
+        registry.join("council", guest, guest_keys.public_key()).unwrap();
+        assert_eq!(
+            registry.join("council", stranger, stranger_keys.public_key()).unwrap_err(),
+            ChannelError::Full("council".to_string())
+        );
+        assert_eq!(
+            registry.join("council", guest, stranger_keys.public_key()).unwrap_err(),
+            ChannelError::KeyMismatch { channel: "council".to_string(), node: guest }
+        );
+
+        // Non-members are rejected locally and their messages dropped remotely
+        assert!(registry.check_publish("council", stranger).is_err());
+        assert!(!registry.accept_message("council", &message_from("council", Some((stranger, &stranger_keys)), "let me in")));
+        assert!(!registry.accept_message("council", &message_from("council", None, "anonymous")));
+        // Claiming a member's ID takes the member's key
+        assert!(!registry.accept_message("council", &message_from("council", Some((guest, &stranger_keys)), "it's me")));
+        let events = registry.take_events();
+        assert!(events.contains(&ChannelEvent::RejectedPublish { channel: "council".to_string(), sender: Some(stranger) }));
+        assert!(events.contains(&ChannelEvent::RejectedPublish { channel: "council".to_string(), sender: None }));
+        assert!(events.contains(&ChannelEvent::RejectedPublish { channel: "council".to_string(), sender: Some(guest) }));
+    }
+
+    #[test]
+    fn test_members_publish_and_history_is_retained() {
+        let ((owner, owner_keys), (member, member_keys)) = (node(), node());
+        let mut registry = ManagedChannelRegistry::default();
+        registry.create("general", owner, owner_keys.public_key(), ChannelPolicy { history_retention: 2, ..Default::default() }).unwrap();
+        registry.join("general", member, member_keys.public_key()).unwrap();
+
+        assert!(registry.check_publish("general", member).is_ok());
+        for text in ["one", "two", "three"] {
+            assert!(registry.accept_message("general", &message_from("general", Some((member, &member_keys)), text)));
+        }
+        let history: Vec<_> = registry.get("general").unwrap().history.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(history, vec!["two", "three"]);
+
+        // A signature does not carry over to another channel or edited text
+        registry.create("other", owner, owner_keys.public_key(), ChannelPolicy::default()).unwrap();
+        registry.join("other", member, member_keys.public_key()).unwrap();
+        let mut message = message_from("general", Some((member, &member_keys)), "hello");
+        assert!(!registry.accept_message("other", &message));
+        message.text = "edited".to_string();
+        assert!(!registry.accept_message("general", &message));
+
+        // Unmanaged channels keep working for everyone
+        let stranger = Uuid::new_v4();
+        assert!(!registry.is_managed("ad-hoc"));
+        assert!(registry.check_publish("ad-hoc", stranger).is_ok());
+        assert!(registry.accept_message("ad-hoc", &message_from("ad-hoc", None, "hi")));
+    }
+
+    #[test]
+    fn test_roster_updates_follow_owner() {
+        let ((owner, owner_keys), (member, member_keys)) = (node(), node());
+        let mut at_owner = ManagedChannelRegistry::default();
+        let mut at_member = ManagedChannelRegistry::default();
+        at_owner.create("general", owner, owner_keys.public_key(), ChannelPolicy::default()).unwrap();
+        assert!(!at_member.apply_roster(&at_owner.get("general").unwrap().roster(), member_keys.public_key()));
+        assert!(at_member.apply_roster(&at_owner.get("general").unwrap().roster(), owner_keys.public_key()));
+
+        let joined = at_owner.join("general", member, member_keys.public_key()).unwrap().roster();
+        assert!(at_member.apply_roster(&joined, owner_keys.public_key()));
+        assert!(at_member.get("general").unwrap().is_member(&member));
+        assert_eq!(at_member.take_events(), vec![ChannelEvent::MemberJoined { channel: "general".to_string(), node: member }]);
+
+        let left = at_owner.leave("general", member).unwrap().roster();
+        assert!(at_member.apply_roster(&left, owner_keys.public_key()));
+        assert!(!at_member.apply_roster(&joined, owner_keys.public_key()), "stale roster adopted");
+        assert!(!at_member.get("general").unwrap().is_member(&member));
+        assert_eq!(at_member.take_events(), vec![ChannelEvent::MemberLeft { channel: "general".to_string(), node: member }]);
+        assert_eq!(at_owner.leave("general", owner).unwrap_err(), ChannelError::NotOwner("general".to_string()));
+
+        // A roster claiming another owner does not take over the channel
+        let (impostor, impostor_keys) = node();
+        let roster = ChannelControl::Roster {
+            channel: "general".to_string(),
+            owner: impostor,
+            policy: ChannelPolicy::default(),
+            members: BTreeSet::new(),
+            member_keys: BTreeMap::from([(impostor, impostor_keys.public_key().to_vec())]),
+            version: 99,
+        };
+        assert!(!at_member.apply_roster(&roster, impostor_keys.public_key()));
+    }
+
+    #[test]
+    fn test_control_requests_must_be_signed_by_their_node() {
+        let ((owner, owner_keys), (member, member_keys), (stranger, stranger_keys)) = (node(), node(), node());
+        let mut registry = ManagedChannelRegistry::default();
+        registry.create("general", owner, owner_keys.public_key(), ChannelPolicy::default()).unwrap();
+
+        let join = |node: Uuid, keys: &NodeKeys| ChannelControl::JoinRequest {
+            channel: "general".to_string(),
+            node,
+            public_key: keys.public_key().to_vec(),
+        };
+        let roster = registry
+            .apply_control(&SignedChannelControl::sign(join(member, &member_keys), &member_keys).unwrap(), owner)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(roster, ChannelControl::Roster { ref members, .. } if members.contains(&member)));
+
+        // A join registering a key other than the signer's is refused
+        let forged_join = SignedChannelControl::sign(join(stranger, &member_keys), &stranger_keys).unwrap();
+        assert_eq!(registry.apply_control(&forged_join, owner).unwrap_err(), ChannelError::BadSignature("general".to_string()));
+
+        // Nobody else can make a member leave
+        let leave = ChannelControl::LeaveRequest { channel: "general".to_string(), node: member };
+        let forged_leave = SignedChannelControl::sign(leave.clone(), &stranger_keys).unwrap();
+        assert_eq!(registry.apply_control(&forged_leave, owner).unwrap_err(), ChannelError::BadSignature("general".to_string()));
+        let mut tampered = SignedChannelControl::sign(leave.clone(), &member_keys).unwrap();
+        tampered.control = ChannelControl::LeaveRequest { channel: "general".to_string(), node: stranger };
+        assert_eq!(registry.apply_control(&tampered, owner).unwrap_err(), ChannelError::BadSignature("general".to_string()));
+        assert!(registry.get("general").unwrap().is_member(&member));
+
+        // Requests are only answered by the owner
+        let signed_leave = SignedChannelControl::sign(leave, &member_keys).unwrap();
+        assert_eq!(registry.apply_control(&signed_leave, member).unwrap(), None);
+        registry.apply_control(&signed_leave, owner).unwrap().unwrap();
+        assert!(!registry.get("general").unwrap().is_member(&member));
+    }
+
+    #[tokio::test]
+    async fn test_registry_persists_policy_and_roster() {
+        let ((owner, owner_keys), (member, member_keys)) = (node(), node());
+        let mut storage = MemoryStorage::new();
+        let mut registry = ManagedChannelRegistry::default();
+        let policy = ChannelPolicy { access: ChannelAccess::InviteOnly, history_retention: 5, max_members: None };
+        registry.create("council", owner, owner_keys.public_key(), policy.clone()).unwrap();
+        registry.invite("council", owner, member).unwrap();
+        registry.join("council", member, member_keys.public_key()).unwrap();
+
+        let first = registry.persist(&mut storage).await.unwrap();
+        let second = registry.persist(&mut storage).await.unwrap();
+        assert!(storage.get_resource(&first).await.is_err());
+
+        let restored = ManagedChannelRegistry::load(&storage, &second).await.unwrap();
+        let channel = restored.get("council").unwrap();
+        assert_eq!(channel.policy, policy);
+        assert!(channel.is_member(&member));
+        assert_eq!(channel.member_keys.get(&member).map(Vec::as_slice), Some(member_keys.public_key()));
+        assert_eq!(channel.roster_version, 2);
+    }
+}
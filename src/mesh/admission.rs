@@ -0,0 +1,489 @@
+//! Mesh join admission control
+//!
+//! In environments above Open, a node reaching the transport is not yet a
+//! member: peers list it as pending and leave it out of group delivery,
+//! resource discovery and trust establishment. Gatekeeper nodes receive an
+//! [`AdmissionRequest`] and answer with a signed [`AdmissionDecision`] that
+//! every peer verifies against the gatekeeper keys in its configuration.
+//! Rejected nodes are dropped and their announcements refused from then on.
+//!
+//! The first node of a new mesh has nobody to approve it; with
+//! `bootstrap_first_node` it admits itself and, when no gatekeepers are
+//! configured, acts as the gatekeeper for the nodes that follow.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use super::discovery::NodeCapabilities;
+use crate::key_rotation::{key_fingerprint, NodeKeys};
+use crate::security::Environment;
+
+/// Who must approve new nodes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionConfig {
+    /// Whether new nodes wait for a gatekeeper's approval
+    pub require_approval: bool,
+    /// Gatekeeper nodes and the fingerprints of the keys they sign decisions with
+    pub gatekeepers: BTreeMap<Uuid, String>,
+    /// Whether the first node of a new mesh admits itself
+    pub bootstrap_first_node: bool,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            require_approval: false,
+            gatekeepers: BTreeMap::new(),
+            bootstrap_first_node: true,
+        }
+    }
+}
+
+impl AdmissionConfig {
+    /// Approval required in every environment above Open
+    pub fn for_environment(environment: &Environment) -> Self {
+        Self {
+            require_approval: !matches!(environment, Environment::Open),
+            ..Self::default()
+        }
+    }
+
+    /// Accept decisions from `node_id` signed with `public_key`
+    pub fn with_gatekeeper(mut self, node_id: Uuid, public_key: &[u8]) -> Self {
+        self.gatekeepers.insert(node_id, key_fingerprint(public_key));
+        self
+    }
+}
+
+/// Where a node stands with admission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AdmissionStatus {
+    /// Seen, waiting for a gatekeeper
+    Pending { requested_at: DateTime<Utc> },
+    /// Approved by a gatekeeper
+    Approved { approved_by: Uuid, decided_at: DateTime<Utc> },
+    /// Rejected by a gatekeeper
+    Rejected { rejected_by: Uuid, decided_at: DateTime<Utc>, reason: Option<String> },
+    /// First node of a new mesh, admitted without a decision
+    Bootstrapped { at: DateTime<Utc> },
+}
+
+impl AdmissionStatus {
+    /// When the node was approved, rejected or bootstrapped; None while pending
+    pub fn decided_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            AdmissionStatus::Pending { .. } => None,
+            AdmissionStatus::Approved { decided_at, .. } | AdmissionStatus::Rejected { decided_at, .. } => Some(*decided_at),
+            AdmissionStatus::Bootstrapped { at } => Some(*at),
+        }
+    }
+}
+
+/// Request sent to gatekeepers when a peer sees an unapproved node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdmissionRequest {
+    pub node_id: Uuid,
+    pub capabilities: NodeCapabilities,
+    /// Peer that saw the node
+    pub observed_by: Uuid,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// A gatekeeper's signed answer to an admission request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionDecision {
+    pub node_id: Uuid,
+    pub approved: bool,
+    pub decided_by: Uuid,
+    pub decided_at: DateTime<Utc>,
+    pub reason: Option<String>,
+    /// Key the decision is signed with
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl AdmissionDecision {
+    /// Sign a decision about `node_id` made by `decided_by`
+    pub fn sign(node_id: Uuid, approved: bool, reason: Option<String>, decided_by: Uuid, keys: &NodeKeys) -> Self {
+        let decided_at = Utc::now();
+        let signature = keys.sign(&Self::signed_bytes(node_id, approved, decided_by, decided_at, reason.as_deref()));
+        Self {
+            node_id,
+            approved,
+            decided_by,
+            decided_at,
+            reason,
+            public_key: keys.public_key().to_vec(),
+            signature,
+        }
+    }
+
+    fn signed_bytes(node_id: Uuid, approved: bool, decided_by: Uuid, decided_at: DateTime<Utc>, reason: Option<&str>) -> Vec<u8> {
+        format!(
+            "admission:{}:{}:{}:{}:{}",
+            node_id,
+            if approved { "approve" } else { "reject" },
+            decided_by,
+            decided_at.to_rfc3339(),
+            reason.unwrap_or(""),
+        ).into_bytes()
+    }
+
+    /// Whether the signature matches the enclosed public key
+    pub fn verify_signature(&self) -> bool {
+        let message = Self::signed_bytes(self.node_id, self.approved, self.decided_by, self.decided_at, self.reason.as_deref());
+        UnparsedPublicKey::new(&ED25519, &self.public_key).verify(&message, &self.signature).is_ok()
+    }
+}
+
+/// Why an admission decision was not honored
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum AdmissionError {
+    #[error("Node {0} is not a gatekeeper")]
+    NotAGatekeeper(Uuid),
+
+    #[error("Decision by {0} is not signed with its gatekeeper key")]
+    InvalidSignature(Uuid),
+
+    #[error("Decision about {0} is older than its current status")]
+    StaleDecision(Uuid),
+}
+
+/// Admission state of the nodes this node knows, shared by the subsystems that enforce it
+#[derive(Debug, Clone)]
+pub struct AdmissionRegistry {
+    inner: Arc<RwLock<RegistryState>>,
+}
+
+#[derive(Debug)]
+struct RegistryState {
+    config: AdmissionConfig,
+    statuses: HashMap<Uuid, AdmissionStatus>,
+}
+
+impl AdmissionRegistry {
+    pub fn new(config: AdmissionConfig) -> Self {
+        Self { inner: Arc::new(RwLock::new(RegistryState { config, statuses: HashMap::new() })) }
+    }
+
+    /// Whether new nodes need approval at all
+    pub fn is_required(&self) -> bool {
+        self.inner.read().unwrap().config.require_approval
+    }
+
+    /// Whether `node_id` may approve or reject nodes
+    pub fn is_gatekeeper(&self, node_id: &Uuid) -> bool {
+        self.inner.read().unwrap().config.gatekeepers.contains_key(node_id)
+    }
+
+    /// Current status of `node_id`, if it needed admission
+    pub fn status(&self, node_id: &Uuid) -> Option<AdmissionStatus> {
+        self.inner.read().unwrap().statuses.get(node_id).cloned()
+    }
+
+    /// Whether `node_id` is a full member
+    ///
+    /// Always true when approval is not required; gatekeepers are admitted
+    /// by configuration.
+    pub fn is_admitted(&self, node_id: &Uuid) -> bool {
+        let state = self.inner.read().unwrap();
+        !state.config.require_approval
+            || state.config.gatekeepers.contains_key(node_id)
+            || matches!(
+                state.statuses.get(node_id),
+                Some(AdmissionStatus::Approved { .. } | AdmissionStatus::Bootstrapped { .. })
+            )
+    }
+
+    /// Whether a gatekeeper rejected `node_id`
+    pub fn is_rejected(&self, node_id: &Uuid) -> bool {
+        matches!(self.status(node_id), Some(AdmissionStatus::Rejected { .. }))
+    }
+
+    /// Nodes waiting for a decision
+    pub fn pending(&self) -> Vec<Uuid> {
+        let state = self.inner.read().unwrap();
+        let mut pending: Vec<Uuid> = state.statuses.iter()
+            .filter(|(_, status)| matches!(status, AdmissionStatus::Pending { .. }))
+            .map(|(node_id, _)| *node_id)
+            .collect();
+        pending.sort();
+        pending
+    }
+
+    /// Record that `node_id` awaits a decision, returning whether it was new
+    ///
+    /// Nodes already decided, admitted by configuration, or seen while
+    /// approval is not required are left alone.
+    pub fn mark_pending(&self, node_id: Uuid) -> bool {
+        if self.is_admitted(&node_id) {
+            return false;
+        }
+        let mut state = self.inner.write().unwrap();
+        if state.statuses.contains_key(&node_id) {
+            return false;
+        }
+        state.statuses.insert(node_id, AdmissionStatus::Pending { requested_at: Utc::now() });
+        true
+    }
+
+    /// Admit `local_node_id` as the first node of a new mesh
+    ///
+    /// Only applies while approval is required, bootstrapping is enabled, no
+    /// other node is known and nobody has been admitted yet. Nodes configured
+    /// with other gatekeepers join an existing mesh and never bootstrap.
+    /// Without configured gatekeepers the node also becomes the gatekeeper,
+    /// signing with `keys`.
+    pub fn bootstrap(&self, local_node_id: Uuid, known_nodes: usize, keys: &NodeKeys) -> bool {
+        let mut state = self.inner.write().unwrap();
+        let already_admitted = state.statuses.values()
+            .any(|status| matches!(status, AdmissionStatus::Approved { .. } | AdmissionStatus::Bootstrapped { .. }));
+        let joins_existing_mesh = !state.config.gatekeepers.is_empty()
+            && !state.config.gatekeepers.contains_key(&local_node_id);
+        if !state.config.require_approval || !state.config.bootstrap_first_node || known_nodes > 0
+            || already_admitted || joins_existing_mesh
+        {
+            return false;
+        }
+        if state.config.gatekeepers.is_empty() {
+            state.config.gatekeepers.insert(local_node_id, keys.fingerprint());
+        }
+        state.statuses.insert(local_node_id, AdmissionStatus::Bootstrapped { at: Utc::now() });
+        true
+    }
+
+    /// Record a gatekeeper's decision, returning whether it changed the node's status
+    ///
+    /// Decisions made before the node's current status was decided are
+    /// rejected, so a replayed or delayed decision cannot undo a newer one.
+    pub fn apply_decision(&self, decision: &AdmissionDecision) -> Result<bool, AdmissionError> {
+        let mut state = self.inner.write().unwrap();
+        let fingerprint = state.config.gatekeepers.get(&decision.decided_by)
+            .ok_or(AdmissionError::NotAGatekeeper(decision.decided_by))?;
+        if *fingerprint != key_fingerprint(&decision.public_key) || !decision.verify_signature() {
+            return Err(AdmissionError::InvalidSignature(decision.decided_by));
+        }
+        let current = state.statuses.get(&decision.node_id).and_then(AdmissionStatus::decided_at);
+        if current.is_some_and(|decided_at| decision.decided_at < decided_at) {
+            return Err(AdmissionError::StaleDecision(decision.node_id));
+        }
+
+        let status = if decision.approved {
+            AdmissionStatus::Approved { approved_by: decision.decided_by, decided_at: decision.decided_at }
+        } else {
+            AdmissionStatus::Rejected {
+                rejected_by: decision.decided_by,
+                decided_at: decision.decided_at,
+                reason: decision.reason.clone(),
+            }
+        };
+        let previous = state.statuses.insert(decision.node_id, status.clone());
+        Ok(previous.as_ref() != Some(&status))
+    }
+}
+
+/// Reaches gatekeepers and peers with admission traffic
+#[async_trait::async_trait]
+pub trait AdmissionPeers: Send + Sync + std::fmt::Debug {
+    /// Deliver an admission request to the gatekeepers
+    async fn request_admission(&self, request: &AdmissionRequest) -> Result<()>;
+
+    /// Broadcast a decision to every peer
+    async fn publish_decision(&self, decision: &AdmissionDecision) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group_communication::{
+        BasicGroupCommunication, GroupCommunication, GroupCommunicationError, GroupId, GroupMembership,
+        GroupPermissions, GroupRole, Message, MessageEnvelope, MessageId, MessagePriority,
+    };
+    use crate::mesh::discovery::TrustLevel;
+    use crate::mesh::manager::{MeshConfig, MeshManager, RemoteNode};
+    use crate::mesh::metadata_visibility::{MetadataQuery, SignedMetadataQuery};
+    use crate::mesh::resource::{MeshResource, ResourceType, VisibilityLevel};
+    use crate::mesh::security::{SecuritySystem, TrustLevel as SecurityTrust};
+    use crate::{Attribution, CollaborationType};
+
+    /// Admission traffic between managers in this process
+    #[derive(Default)]
+    struct InProcessMesh {
+        managers: RwLock<Vec<Arc<MeshManager>>>,
+    }
+
+    impl std::fmt::Debug for InProcessMesh {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("InProcessMesh").field("managers", &self.managers.read().unwrap().len()).finish()
+        }
+    }
+
+    impl InProcessMesh {
+        async fn join(self: &Arc<Self>, config: MeshConfig) -> Arc<MeshManager> {
+            let mut manager = MeshManager::new(config).await.unwrap();
+            manager.set_admission_peers(self.clone());
+            manager.local_node.set_metadata("role".to_string(), "relay".to_string());
+            let manager = Arc::new(manager);
+            self.managers.write().unwrap().push(manager.clone());
+            manager
+        }
+
+        fn all(&self) -> Vec<Arc<MeshManager>> {
+            self.managers.read().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AdmissionPeers for InProcessMesh {
+        async fn request_admission(&self, request: &AdmissionRequest) -> Result<()> {
+            for manager in self.all() {
+                manager.receive_admission_request(request.clone());
+            }
+            Ok(())
+        }
+
+        async fn publish_decision(&self, decision: &AdmissionDecision) -> Result<()> {
+            for manager in self.all() {
+                manager.apply_admission_decision(decision).await?;
+            }
+            Ok(())
+        }
+    }
+
+    fn restricted() -> Environment {
+        Environment::Internal { organization_id: "acme".to_string() }
+    }
+
+    fn group_message(content: &str) -> Message {
+        Message {
+            id: MessageId::new(),
+            content: content.to_string(),
+            sender: "founder".to_string(),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            priority: MessagePriority::Normal,
+            requires_ack: false,
+            envelope: MessageEnvelope::Live,
+        }
+    }
+
+    fn shared_resource(id: &str) -> MeshResource {
+        let attribution = Attribution::new(Some("tester".to_string()), None, CollaborationType::HumanLed, 1.0);
+        let mut resource = MeshResource::new_universal(
+            id.to_string(),
+            format!("universal/{}/local/", id),
+            ResourceType::Communication { comm_type: "notes".to_string(), participants: Vec::new(), message_count: 0 },
+            attribution,
+        );
+        resource.access_control.visibility = VisibilityLevel::Public;
+        resource
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_admission_pending_approval_and_rejection() {
+        let mesh = Arc::new(InProcessMesh::default());
+        let gatekeeper_keys = NodeKeys::generate().unwrap();
+
+        // The first node bootstraps the mesh and becomes its gatekeeper
+        let founder = mesh.join(MeshConfig { admission: AdmissionConfig::for_environment(&restricted()), ..MeshConfig::default() }).await;
+        let founder_id = founder.local_node.id;
+        assert!(founder.bootstrap_admission(&gatekeeper_keys).await);
+        assert!(matches!(founder.admission_status(&founder_id), Some(AdmissionStatus::Bootstrapped { .. })));
+        assert!(founder.admission_registry().is_gatekeeper(&founder_id));
+        assert!(!founder.bootstrap_admission(&gatekeeper_keys).await);
+
+        // Later nodes are configured with the founder as gatekeeper
+        let config = || MeshConfig {
+            admission: AdmissionConfig::for_environment(&restricted()).with_gatekeeper(founder_id, gatekeeper_keys.public_key()),
+            ..MeshConfig::default()
+        };
+        let peer = mesh.join(config()).await;
+        assert!(!peer.bootstrap_admission(&NodeKeys::generate().unwrap()).await);
+        peer.add_node(RemoteNode::new(founder_id, NodeCapabilities::default(), TrustLevel::Trusted)).await.unwrap();
+        assert!(peer.is_admitted(&founder_id));
+
+        let mut requests = founder.subscribe_admission_requests();
+        let newcomer = Uuid::new_v4();
+        let newcomer_keys = NodeKeys::generate().unwrap();
+        let newcomer_node = RemoteNode::new(newcomer, NodeCapabilities::default(), TrustLevel::Basic)
+            .with_public_key(newcomer_keys.public_key());
+        peer.add_node(newcomer_node).await.unwrap();
+        let request = requests.recv().await.unwrap();
+        assert_eq!((request.node_id, request.observed_by), (newcomer, peer.local_node.id));
+
+        // Pending nodes are visible but excluded
+        assert_eq!(peer.pending_admissions(), vec![newcomer]);
+        assert!(peer.get_node(&newcomer).await.is_some());
+        let query = MetadataQuery { requester: newcomer, keys: vec!["role".to_string()] };
+        let query = SignedMetadataQuery::sign(query, &newcomer_keys).unwrap();
+        let response = peer.answer_metadata_query(&query).await;
+        assert!(response.entries.is_empty());
+        assert_eq!(response.denied, vec!["role".to_string()]);
+        let security = SecuritySystem::new(peer.local_node.id, None).with_admission_registry(peer.admission_registry());
+        assert!(security.establish_trust(newcomer, SecurityTrust::Basic, Vec::new()).await.is_err());
+        let group_id = GroupId::new("group/ops");
+        let mut group = BasicGroupCommunication::new(peer.local_node.id.to_string());
+        group.add_membership(GroupMembership {
+            group_id: group_id.clone(),
+            role: GroupRole::Member,
+            permissions: GroupPermissions::default(),
+            joined_at: Utc::now(),
+            is_active: true,
+            metadata: HashMap::new(),
+        });
+        group.set_admission_registry(peer.admission_registry());
+        let mut newcomer_stream = group.connect_participant(&group_id, &newcomer.to_string());
+        assert!(matches!(
+            group.replay_to_participant(&group_id, &newcomer.to_string(), None).await,
+            Err(GroupCommunicationError::NotAdmitted(_))
+        ));
+        group.talk(group_id.clone(), group_message("before approval")).await.unwrap();
+        assert!(newcomer_stream.try_recv().is_err());
+        peer.add_resource(shared_resource("notes")).await;
+        assert!(peer.discover_resources(&newcomer).await.is_empty());
+
+        // Only gatekeepers decide, and their decisions reach every peer
+        assert!(peer.decide_admission(newcomer, true, None, &NodeKeys::generate().unwrap()).await.is_err());
+        let forged = AdmissionDecision::sign(newcomer, true, None, founder_id, &NodeKeys::generate().unwrap());
+        assert!(peer.apply_admission_decision(&forged).await.is_err());
+        let stale = AdmissionDecision::sign(newcomer, false, Some("stale".to_string()), founder_id, &gatekeeper_keys);
+        founder.decide_admission(newcomer, true, None, &gatekeeper_keys).await.unwrap();
+        assert!(matches!(
+            peer.admission_status(&newcomer),
+            Some(AdmissionStatus::Approved { approved_by, .. }) if approved_by == founder_id
+        ));
+        assert!(peer.pending_admissions().is_empty());
+        assert!(security.establish_trust(newcomer, SecurityTrust::Basic, Vec::new()).await.is_ok());
+        assert_eq!(peer.answer_metadata_query(&query).await.entries["role"], "relay");
+        assert_eq!(group.replay_to_participant(&group_id, &newcomer.to_string(), None).await.unwrap(), 0);
+        assert_eq!(peer.discover_resources(&newcomer).await.len(), 1);
+        group.talk(group_id.clone(), group_message("after approval")).await.unwrap();
+        assert_eq!(newcomer_stream.recv().await.unwrap().content, "after approval");
+
+        // A rejection signed before the approval cannot undo it
+        let error = peer.apply_admission_decision(&stale).await.unwrap_err();
+        assert_eq!(error.downcast::<AdmissionError>().unwrap(), AdmissionError::StaleDecision(newcomer));
+        assert!(peer.is_admitted(&newcomer));
+
+        // Rejected nodes are dropped and refused from then on
+        let intruder = Uuid::new_v4();
+        peer.add_node(RemoteNode::new(intruder, NodeCapabilities::default(), TrustLevel::Basic)).await.unwrap();
+        founder.decide_admission(intruder, false, Some("unknown device".to_string()), &gatekeeper_keys).await.unwrap();
+        assert!(peer.get_node(&intruder).await.is_none());
+        assert!(peer.add_node(RemoteNode::new(intruder, NodeCapabilities::default(), TrustLevel::Basic)).await.is_err());
+    }
+
+    #[test]
+    fn test_open_environment_admits_everyone() {
+        let registry = AdmissionRegistry::new(AdmissionConfig::for_environment(&Environment::Open));
+        let node = Uuid::new_v4();
+        assert!(!registry.mark_pending(node));
+        assert!(registry.is_admitted(&node));
+        assert!(!registry.bootstrap(node, 0, &NodeKeys::generate().unwrap()));
+    }
+}
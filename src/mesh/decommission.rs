@@ -0,0 +1,487 @@
+//! Node decommissioning
+//!
+//! Retiring a node for good hands each resource it owns to a successor that
+//! accepts it, passes its group administrator roles on, and publishes a
+//! signed [`RetirementNotice`]. Peers delete a retired node instead of
+//! letting it expire, revoke their trust in it and refuse it if it rejoins.
+//! Progress is saved after every step, so an interrupted run resumes where
+//! it stopped.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::resource::MeshResource;
+use crate::group_communication::{GroupId, MembershipDelta};
+use crate::key_rotation::{key_fingerprint, NodeKeys};
+
+/// Metadata key linking trust events to the retirement that caused them
+pub const RETIREMENT_ID_METADATA_KEY: &str = "retirement_id";
+
+/// Who takes over what when a node retires
+#[derive(Debug, Clone, Default)]
+pub struct DecommissionTargets {
+    /// Successor for specific resources, by resource ID
+    pub resources: HashMap<String, Uuid>,
+    /// Successor for owned resources not listed in `resources`
+    pub default_successor: Option<Uuid>,
+    /// New administrator for each group this node administers, by group ID
+    pub group_admins: HashMap<String, String>,
+    /// Where progress is saved so an interrupted run can resume
+    pub progress_path: Option<PathBuf>,
+}
+
+impl DecommissionTargets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hand `resource_id` to `successor`
+    pub fn with_resource(mut self, resource_id: impl Into<String>, successor: Uuid) -> Self {
+        self.resources.insert(resource_id.into(), successor);
+        self
+    }
+
+    /// Hand every other owned resource to `successor`
+    pub fn with_default_successor(mut self, successor: Uuid) -> Self {
+        self.default_successor = Some(successor);
+        self
+    }
+
+    /// Make `successor` administrator of `group_id`
+    pub fn with_group_admin(mut self, group_id: impl Into<String>, successor: impl Into<String>) -> Self {
+        self.group_admins.insert(group_id.into(), successor.into());
+        self
+    }
+
+    /// Save progress to `path`, resuming from it if it exists
+    pub fn with_progress_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.progress_path = Some(path.into());
+        self
+    }
+
+    /// Successor for a resource, if any
+    pub fn successor_for(&self, resource_id: &str) -> Option<Uuid> {
+        self.resources.get(resource_id).copied().or(self.default_successor)
+    }
+}
+
+/// Offer of a resource to a successor, which answers with accept or decline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferOffer {
+    pub retirement_id: Uuid,
+    pub from_node: Uuid,
+    pub successor: Uuid,
+    /// The resource as it stands, still owned by `from_node`
+    pub resource: MeshResource,
+}
+
+/// Signed final departure of a retired node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetirementNotice {
+    pub retirement_id: Uuid,
+    pub node_id: Uuid,
+    pub retired_at: DateTime<Utc>,
+    /// Key the notice is signed with
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl RetirementNotice {
+    /// Sign a notice retiring `node_id`
+    pub fn sign(retirement_id: Uuid, node_id: Uuid, retired_at: DateTime<Utc>, keys: &NodeKeys) -> Self {
+        let signature = keys.sign(&Self::signed_bytes(retirement_id, node_id, retired_at));
+        Self { retirement_id, node_id, retired_at, public_key: keys.public_key().to_vec(), signature }
+    }
+
+    fn signed_bytes(retirement_id: Uuid, node_id: Uuid, retired_at: DateTime<Utc>) -> Vec<u8> {
+        format!("retire:{}:{}:{}", retirement_id, node_id, retired_at.to_rfc3339()).into_bytes()
+    }
+
+    /// Whether the signature matches the enclosed public key
+    ///
+    /// Callers that know the node's key should also compare
+    /// [`key_fingerprint`](Self::key_fingerprint) against it.
+    pub fn verify_signature(&self) -> bool {
+        let message = Self::signed_bytes(self.retirement_id, self.node_id, self.retired_at);
+        UnparsedPublicKey::new(&ED25519, &self.public_key).verify(&message, &self.signature).is_ok()
+    }
+
+    /// Fingerprint of the signing key
+    pub fn key_fingerprint(&self) -> String {
+        key_fingerprint(&self.public_key)
+    }
+}
+
+/// Reaches the rest of the mesh during a decommission
+#[async_trait::async_trait]
+pub trait DecommissionPeers: Send + Sync + std::fmt::Debug {
+    /// Offer a resource to its successor; `Ok(false)` means declined
+    async fn offer_resource(&self, offer: &TransferOffer) -> Result<bool>;
+
+    /// Broadcast a roster change of a group to its members
+    async fn broadcast_membership(&self, group_id: &GroupId, delta: &MembershipDelta) -> Result<()>;
+
+    /// Publish the final departure to every peer
+    async fn publish_retirement(&self, notice: &RetirementNotice) -> Result<()>;
+}
+
+/// Steps of a decommission completed so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecommissionProgress {
+    pub retirement_id: Uuid,
+    pub node_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    /// Resources accepted by their successor
+    pub transferred: BTreeMap<String, Uuid>,
+    /// Resources their successor declined; offered again on resume
+    pub declined: BTreeMap<String, Uuid>,
+    /// Owned resources with no successor
+    pub unassigned: BTreeSet<String>,
+    /// Groups handed to a new administrator
+    pub groups_handed_off: BTreeMap<String, String>,
+    /// Groups whose hand-off failed, with the reason
+    pub failed_groups: BTreeMap<String, String>,
+    /// The departure, once published
+    pub notice: Option<RetirementNotice>,
+}
+
+impl DecommissionProgress {
+    /// Fresh progress for retiring `node_id`
+    pub fn new(node_id: Uuid) -> Self {
+        Self {
+            retirement_id: Uuid::new_v4(),
+            node_id,
+            started_at: Utc::now(),
+            transferred: BTreeMap::new(),
+            declined: BTreeMap::new(),
+            unassigned: BTreeSet::new(),
+            groups_handed_off: BTreeMap::new(),
+            failed_groups: BTreeMap::new(),
+            notice: None,
+        }
+    }
+
+    /// Saved progress at `path`, if any
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        match std::fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write progress to `path`, replacing it atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Save to `path` if there is one
+    pub(crate) fn checkpoint(&self, path: Option<&Path>) -> Result<()> {
+        match path {
+            Some(path) => self.save(path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Outcome of a completed decommission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecommissionReport {
+    pub retirement_id: Uuid,
+    pub node_id: Uuid,
+    pub transferred: BTreeMap<String, Uuid>,
+    /// Resources left orphaned because the successor declined them
+    pub declined: BTreeMap<String, Uuid>,
+    pub unassigned: BTreeSet<String>,
+    pub groups_handed_off: BTreeMap<String, String>,
+    pub failed_groups: BTreeMap<String, String>,
+    pub notice: RetirementNotice,
+    /// Whether this run continued an interrupted one
+    pub resumed: bool,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl DecommissionReport {
+    pub(crate) fn from_progress(progress: DecommissionProgress, notice: RetirementNotice, resumed: bool) -> Self {
+        Self {
+            retirement_id: progress.retirement_id,
+            node_id: progress.node_id,
+            transferred: progress.transferred,
+            declined: progress.declined,
+            unassigned: progress.unassigned,
+            groups_handed_off: progress.groups_handed_off,
+            failed_groups: progress.failed_groups,
+            notice,
+            resumed,
+            completed_at: Utc::now(),
+        }
+    }
+
+    /// Whether every resource and group found a successor
+    pub fn is_clean(&self) -> bool {
+        self.declined.is_empty() && self.unassigned.is_empty() && self.failed_groups.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::group_communication::{
+        BasicGroupCommunication, GroupMembership, GroupPermissions, GroupRole, MembershipChange, MembershipMessage,
+    };
+    use crate::mesh::discovery::{NodeCapabilities, TrustLevel};
+    use crate::mesh::manager::{MeshConfig, MeshManager, RemoteNode};
+    use crate::mesh::resource::ResourceType;
+    use crate::mesh::security::{SecuritySystem, TrustEventType, TrustLevel as SecurityTrust};
+    use crate::{Attribution, CollaborationType};
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    struct InProcessPeers {
+        successor: Arc<MeshManager>,
+        security: Arc<SecuritySystem>,
+        member_view: Arc<Mutex<BasicGroupCommunication>>,
+        declined: HashSet<String>,
+        offers: AtomicUsize,
+        fail_publish: AtomicBool,
+    }
+
+    impl std::fmt::Debug for InProcessPeers {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("InProcessPeers").field("successor", &self.successor.local_node.id).finish()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DecommissionPeers for InProcessPeers {
+        async fn offer_resource(&self, offer: &TransferOffer) -> Result<bool> {
+            self.offers.fetch_add(1, Ordering::SeqCst);
+            if self.declined.contains(&offer.resource.id) {
+                return Ok(false);
+            }
+            Ok(self.successor.accept_resource_transfer(offer).await)
+        }
+
+        async fn broadcast_membership(&self, _group_id: &GroupId, delta: &MembershipDelta) -> Result<()> {
+            let mut view = self.member_view.lock().await;
+            view.handle_membership_message(&delta.actor, MembershipMessage::Delta(delta.clone()), Utc::now())?;
+            Ok(())
+        }
+
+        async fn publish_retirement(&self, notice: &RetirementNotice) -> Result<()> {
+            if self.fail_publish.load(Ordering::SeqCst) {
+                anyhow::bail!("network unreachable");
+            }
+            self.successor.apply_retirement(notice).await?;
+            self.security.apply_retirement(notice).await?;
+            Ok(())
+        }
+    }
+
+    fn group_node(node_id: &str, group_id: &GroupId, admin: bool) -> BasicGroupCommunication {
+        let mut comm = BasicGroupCommunication::new(node_id.to_string());
+        comm.add_membership(GroupMembership {
+            group_id: group_id.clone(),
+            role: if admin { GroupRole::Administrator } else { GroupRole::Member },
+            permissions: GroupPermissions {
+                can_invite_members: admin,
+                can_remove_members: admin,
+                can_modify_group: admin,
+                ..GroupPermissions::default()
+            },
+            joined_at: Utc::now(),
+            is_active: true,
+            metadata: HashMap::new(),
+        });
+        comm
+    }
+
+    fn owned_resource(id: &str, owner: Uuid) -> MeshResource {
+        let attribution = Attribution::new(Some("tester".to_string()), None, CollaborationType::HumanLed, 1.0);
+        let mut resource = MeshResource::new_universal(
+            id.to_string(),
+            format!("universal/{}@{}/local/", id, owner),
+            ResourceType::Communication { comm_type: "notes".to_string(), participants: Vec::new(), message_count: 0 },
+            attribution,
+        );
+        resource.access_control.owner = owner.to_string();
+        resource
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_full_decommission_resumes_after_interruption() {
+        let mut retiring = MeshManager::new(MeshConfig::default()).await.unwrap();
+        let successor = Arc::new(MeshManager::new(MeshConfig::default()).await.unwrap());
+        let retiring_id = retiring.local_node.id;
+        let successor_id = successor.local_node.id;
+        let keys = NodeKeys::generate().unwrap();
+
+        for id in ["doc", "notes", "scratch"] {
+            retiring.add_resource(owned_resource(id, retiring_id)).await;
+        }
+        let known = RemoteNode::new(retiring_id, NodeCapabilities::default(), TrustLevel::Trusted)
+            .with_public_key(keys.public_key());
+        successor.add_node(known).await.unwrap();
+        let security = Arc::new(SecuritySystem::new(successor_id, None));
+        security.establish_trust(retiring_id, SecurityTrust::Trusted, Vec::new()).await.unwrap();
+        assert!(security.record_public_key(retiring_id, keys.public_key()).await);
+
+        // The retiring node administers a group the successor belongs to
+        let group_id = GroupId::new("group/ops");
+        let mut admin_view = group_node(&retiring_id.to_string(), &group_id, true);
+        let member_view = Arc::new(Mutex::new(group_node(&successor_id.to_string(), &group_id, false)));
+        for (member, role) in [(retiring_id, GroupRole::Administrator), (successor_id, GroupRole::Member)] {
+            let change = MembershipChange::Join { member: member.to_string(), role };
+            let delta = admin_view.change_membership(&group_id, change, Utc::now()).unwrap();
+            member_view.lock().await
+                .handle_membership_message(&delta.actor, MembershipMessage::Delta(delta.clone()), Utc::now())
+                .unwrap();
+        }
+
+        let peers = Arc::new(InProcessPeers {
+            successor: successor.clone(),
+            security: security.clone(),
+            member_view: member_view.clone(),
+            declined: HashSet::from(["scratch".to_string()]),
+            offers: AtomicUsize::new(0),
+            fail_publish: AtomicBool::new(true),
+        });
+        retiring.set_decommission_peers(peers.clone());
+
+        let dir = tempfile::tempdir().unwrap();
+        let progress_path = dir.path().join("decommission.json");
+        let targets = || DecommissionTargets::new()
+            .with_resource("doc", successor_id)
+            .with_default_successor(successor_id)
+            .with_group_admin("group/ops", successor_id.to_string())
+            .with_progress_file(&progress_path);
+
+        // Interrupted before the departure went out
+        assert!(retiring.decommission(targets(), &keys, Some(&mut admin_view)).await.is_err());
+        let saved = DecommissionProgress::load(&progress_path).unwrap().unwrap();
+        assert_eq!(saved.transferred.len(), 2);
+        assert!(saved.groups_handed_off.contains_key("group/ops"));
+        assert!(saved.notice.is_none());
+        assert_eq!(peers.offers.load(Ordering::SeqCst), 3);
+
+        peers.fail_publish.store(false, Ordering::SeqCst);
+        let report = retiring.decommission(targets(), &keys, Some(&mut admin_view)).await.unwrap();
+        assert!(report.resumed);
+        assert_eq!(report.retirement_id, saved.retirement_id);
+        // Only the declined resource is offered again
+        assert_eq!(peers.offers.load(Ordering::SeqCst), 4);
+        assert_eq!(report.declined.keys().collect::<Vec<_>>(), vec!["scratch"]);
+        assert!(!report.is_clean());
+
+        // Ownership moved on both sides
+        for id in ["doc", "notes"] {
+            assert_eq!(successor.get_resource(id).await.unwrap().access_control.owner, successor_id.to_string());
+            assert_eq!(retiring.get_resource(id).await.unwrap().access_control.owner, successor_id.to_string());
+        }
+
+        // The successor administers the group and the retired node is gone
+        let view = member_view.lock().await;
+        let roster = view.roster(&group_id).unwrap();
+        assert_eq!(roster.role_of(&successor_id.to_string()), Some(&GroupRole::Administrator));
+        assert_eq!(roster.role_of(&retiring_id.to_string()), None);
+        drop(view);
+
+        // Trust is revoked with a reference to the retirement
+        assert_eq!(security.get_trust_level(retiring_id).await, SecurityTrust::Unknown);
+        let revocation = security.get_trust_history(retiring_id).await.pop().unwrap();
+        assert_eq!(revocation.event_type, TrustEventType::Revocation);
+        assert_eq!(
+            revocation.metadata.get(RETIREMENT_ID_METADATA_KEY),
+            Some(&report.retirement_id.to_string())
+        );
+
+        // Deleted, and refused on rejoin
+        assert!(successor.get_node(&retiring_id).await.is_none());
+        let rejoin = RemoteNode::new(retiring_id, NodeCapabilities::default(), TrustLevel::Basic);
+        assert!(successor.add_node(rejoin).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_retirement_requires_key_on_file() {
+        let manager = MeshManager::new(MeshConfig::default()).await.unwrap();
+        let security = SecuritySystem::new(manager.local_node.id, None);
+        let keys = NodeKeys::generate().unwrap();
+        let node_id = Uuid::new_v4();
+        manager.add_node(RemoteNode::new(node_id, NodeCapabilities::default(), TrustLevel::Trusted)).await.unwrap();
+        security.establish_trust(node_id, SecurityTrust::Trusted, Vec::new()).await.unwrap();
+
+        // Without a key on file, even a well-signed notice is refused
+        let notice = RetirementNotice::sign(Uuid::new_v4(), node_id, Utc::now(), &keys);
+        assert!(notice.verify_signature());
+        assert!(manager.apply_retirement(&notice).await.is_err());
+        assert!(!security.apply_retirement(&notice).await.unwrap());
+
+        // A notice self-signed by someone else's key is refused once a key is on file
+        manager.add_node(RemoteNode::new(node_id, NodeCapabilities::default(), TrustLevel::Trusted)
+            .with_public_key(keys.public_key())).await.unwrap();
+        assert!(security.record_public_key(node_id, keys.public_key()).await);
+        let forged = RetirementNotice::sign(Uuid::new_v4(), node_id, Utc::now(), &NodeKeys::generate().unwrap());
+        assert!(forged.verify_signature());
+        assert!(manager.apply_retirement(&forged).await.is_err());
+        assert!(!security.apply_retirement(&forged).await.unwrap());
+        assert!(manager.get_node(&node_id).await.is_some());
+        assert_eq!(security.get_trust_level(node_id).await, SecurityTrust::Trusted);
+
+        assert!(manager.apply_retirement(&notice).await.unwrap());
+        assert!(security.apply_retirement(&notice).await.unwrap());
+        assert!(manager.is_retired(&node_id).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_transfer_requires_offerer_ownership() {
+        let successor = MeshManager::new(MeshConfig::default()).await.unwrap();
+        let from_node = Uuid::new_v4();
+        let offer = |owner| TransferOffer {
+            retirement_id: Uuid::new_v4(),
+            from_node,
+            successor: successor.local_node.id,
+            resource: owned_resource("doc", owner),
+        };
+
+        assert!(!successor.accept_resource_transfer(&offer(Uuid::new_v4())).await);
+        assert!(successor.get_resource("doc").await.is_none());
+        assert!(successor.accept_resource_transfer(&offer(from_node)).await);
+        assert_eq!(successor.get_resource("doc").await.unwrap().access_control.owner, successor.local_node.id.to_string());
+    }
+
+    #[test]
+    fn test_retirement_notice_signature() {
+        let keys = NodeKeys::generate().unwrap();
+        let notice = RetirementNotice::sign(Uuid::new_v4(), Uuid::new_v4(), Utc::now(), &keys);
+        assert!(notice.verify_signature());
+        assert_eq!(notice.key_fingerprint(), keys.fingerprint());
+
+        let forged = RetirementNotice { node_id: Uuid::new_v4(), ..notice };
+        assert!(!forged.verify_signature());
+    }
+
+    #[test]
+    fn test_progress_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("decommission.json");
+        assert!(DecommissionProgress::load(&path).unwrap().is_none());
+
+        let mut progress = DecommissionProgress::new(Uuid::new_v4());
+        progress.transferred.insert("doc".to_string(), Uuid::new_v4());
+        progress.save(&path).unwrap();
+
+        let loaded = DecommissionProgress::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.retirement_id, progress.retirement_id);
+        assert_eq!(loaded.transferred, progress.transferred);
+    }
+}
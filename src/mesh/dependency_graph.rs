@@ -0,0 +1,590 @@
+//! Resource dependency tracking and impact analysis
+//!
+//! Resources declare the resources they depend on together with a version
+//! constraint. Each node keeps a [`DependencyGraph`] of the resources it
+//! knows about, merges the graphs other nodes announce, and answers which
+//! resources a change would affect. When a resource publishes a version that
+//! no longer satisfies a dependent's constraint, the dependency is flagged as
+//! broken and an event is addressed to the dependent's owner.
+//!
+//! [`MeshManager`](super::MeshManager) holds the node's graph: publishing a
+//! resource declares it and sends a [`DependencyAnnouncement`] through
+//! [`DependencyPeers`], and each node publishes the DependencyBroken events
+//! for the dependents it owns when it applies an announcement.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::events::{EventPayload, EventPriority, EventType, MeshEvent, ResourceEventType};
+use super::resource::MeshResource;
+
+/// Version of a resource, `MAJOR[.MINOR[.PATCH]]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ResourceVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl ResourceVersion {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self { major, minor, patch }
+    }
+}
+
+impl FromStr for ResourceVersion {
+    type Err = DependencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DependencyError::InvalidVersion(s.to_string());
+        let mut parts = s.trim().trim_start_matches('v').split('.');
+        let mut next = |required: bool| match parts.next() {
+            Some(part) => part.parse::<u64>().map_err(|_| invalid()),
+            None if required => Err(invalid()),
+            None => Ok(0),
+        };
+        let version = ResourceVersion::new(next(true)?, next(false)?, next(false)?);
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for ResourceVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Constraint on the version of a dependency
+///
+/// Comma-separated comparators, all of which must hold: `=1.2.3`, `>=1.0`,
+/// `<2`, `>1`, `<=1.4`, `^1.2` (same major), `~1.2` (same minor) or `*`.
+/// A bare version is read as `^`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionConstraint {
+    source: String,
+    comparators: Vec<(Comparator, ResourceVersion)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Comparator {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Caret,
+    Tilde,
+}
+
+impl VersionConstraint {
+    /// Constraint every version satisfies
+    pub fn any() -> Self {
+        Self { source: "*".to_string(), comparators: Vec::new() }
+    }
+
+    pub fn matches(&self, version: &ResourceVersion) -> bool {
+        self.comparators.iter().all(|(comparator, bound)| match comparator {
+            Comparator::Exact => version == bound,
+            Comparator::Greater => version > bound,
+            Comparator::GreaterEq => version >= bound,
+            Comparator::Less => version < bound,
+            Comparator::LessEq => version <= bound,
+            Comparator::Caret => version >= bound && version.major == bound.major,
+            Comparator::Tilde => version >= bound && version.major == bound.major && version.minor == bound.minor,
+        })
+    }
+}
+
+impl FromStr for VersionConstraint {
+    type Err = DependencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut comparators = Vec::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty() && *p != "*") {
+            let (comparator, version) = [
+                (">=", Comparator::GreaterEq),
+                ("<=", Comparator::LessEq),
+                (">", Comparator::Greater),
+                ("<", Comparator::Less),
+                ("=", Comparator::Exact),
+                ("^", Comparator::Caret),
+                ("~", Comparator::Tilde),
+            ]
+            .iter()
+            .find_map(|(prefix, comparator)| part.strip_prefix(prefix).map(|rest| (*comparator, rest)))
+            .unwrap_or((Comparator::Caret, part));
+            let version = version.parse().map_err(|_| DependencyError::InvalidConstraint(s.to_string()))?;
+            comparators.push((comparator, version));
+        }
+        Ok(Self { source: s.trim().to_string(), comparators })
+    }
+}
+
+impl TryFrom<String> for VersionConstraint {
+    type Error = DependencyError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<VersionConstraint> for String {
+    fn from(constraint: VersionConstraint) -> Self {
+        constraint.source
+    }
+}
+
+impl fmt::Display for VersionConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Declared dependency of a resource
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResourceDependency {
+    pub resource_id: String,
+    pub constraint: VersionConstraint,
+}
+
+impl ResourceDependency {
+    pub fn new(resource_id: &str, constraint: &str) -> Result<Self, DependencyError> {
+        Ok(Self { resource_id: resource_id.to_string(), constraint: constraint.parse()? })
+    }
+}
+
+/// A resource's entry in the dependency graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyEntry {
+    pub resource_id: String,
+    /// Node that owns the resource
+    pub owner_node: Uuid,
+    /// Published version, if the resource is versioned
+    pub version: Option<ResourceVersion>,
+    pub dependencies: Vec<ResourceDependency>,
+    /// When the owner last changed this entry
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Entries a node owns, for other nodes to merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyAnnouncement {
+    pub node_id: Uuid,
+    pub entries: Vec<DependencyEntry>,
+}
+
+/// Resource affected by a change to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpactedResource {
+    pub resource_id: String,
+    pub owner_node: Uuid,
+    /// 1 for direct dependents, 2 for their dependents, and so on
+    pub depth: usize,
+}
+
+/// Dependency whose constraint the published version does not satisfy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenDependency {
+    /// Resource declaring the dependency
+    pub dependent: String,
+    /// Owner of the dependent, who is notified
+    pub dependent_owner: Uuid,
+    pub dependency: String,
+    pub constraint: VersionConstraint,
+    pub found_version: ResourceVersion,
+}
+
+/// State of one declared dependency, as returned by queries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyStatus {
+    pub dependency: ResourceDependency,
+    /// Version of the dependency currently known, if any
+    pub resolved_version: Option<ResourceVersion>,
+    /// Whether the known version violates the constraint
+    pub broken: bool,
+}
+
+/// Errors from declaring dependencies
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DependencyError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+
+    #[error("Invalid version constraint: {0}")]
+    InvalidConstraint(String),
+
+    #[error("Dependency cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+/// Reaches the rest of the mesh with dependency announcements
+#[async_trait::async_trait]
+pub trait DependencyPeers: Send + Sync + std::fmt::Debug {
+    /// Deliver this node's entries to every peer
+    async fn announce_dependencies(&self, announcement: &DependencyAnnouncement) -> anyhow::Result<()>;
+}
+
+/// Dependency graph of the resources a node knows about
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    local_node: Uuid,
+    entries: HashMap<String, DependencyEntry>,
+}
+
+impl DependencyGraph {
+    pub fn new(local_node: Uuid) -> Self {
+        Self { local_node, entries: HashMap::new() }
+    }
+
+    /// Declare a local resource's version and dependencies
+    ///
+    /// Rejects declarations that would close a cycle. Returns events for
+    /// dependents the declared version breaks.
+    pub fn declare(
+        &mut self,
+        resource_id: &str,
+        version: Option<ResourceVersion>,
+        dependencies: Vec<ResourceDependency>,
+    ) -> Result<Vec<MeshEvent>, DependencyError> {
+        self.upsert(DependencyEntry {
+            resource_id: resource_id.to_string(),
+            owner_node: self.local_node,
+            version,
+            dependencies,
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// Declare a local resource from its metadata
+    pub fn declare_resource(&mut self, resource: &MeshResource) -> Result<Vec<MeshEvent>, DependencyError> {
+        let version = resource.metadata.version.as_deref().map(str::parse).transpose()?;
+        self.declare(&resource.id, version, resource.metadata.declared_dependencies.clone())
+    }
+
+    /// Publish a new version of a local resource
+    pub fn publish_version(&mut self, resource_id: &str, version: ResourceVersion) -> Result<Vec<MeshEvent>, DependencyError> {
+        let dependencies = self.entries.get(resource_id).map(|e| e.dependencies.clone()).unwrap_or_default();
+        self.declare(resource_id, Some(version), dependencies)
+    }
+
+    /// Entries owned by this node
+    pub fn announcement(&self) -> DependencyAnnouncement {
+        DependencyAnnouncement {
+            node_id: self.local_node,
+            entries: self.entries.values().filter(|e| e.owner_node == self.local_node).cloned().collect(),
+        }
+    }
+
+    /// Merge entries announced by another node
+    ///
+    /// Entries older than the known ones are ignored, as are entries that
+    /// would close a cycle and entries for resources this node owns.
+    /// Returns events for dependents broken by the announced versions.
+    pub fn merge(&mut self, announcement: &DependencyAnnouncement) -> Vec<MeshEvent> {
+        let mut events = Vec::new();
+        for entry in &announcement.entries {
+            if entry.owner_node != announcement.node_id {
+                continue;
+            }
+            if let Some(known) = self.entries.get(&entry.resource_id) {
+                if known.owner_node == self.local_node {
+                    tracing::warn!(
+                        "Ignoring entry for {} announced by {}: owned by this node",
+                        entry.resource_id, announcement.node_id
+                    );
+                    continue;
+                }
+                if known.updated_at >= entry.updated_at {
+                    continue;
+                }
+            }
+            match self.upsert(entry.clone()) {
+                Ok(broken) => events.extend(broken),
+                Err(e) => tracing::warn!("Ignoring announced dependencies of {}: {}", entry.resource_id, e),
+            }
+        }
+        events
+    }
+
+    /// Transitive dependents of `resource_id`, nearest first
+    pub fn get_impacted(&self, resource_id: &str) -> Vec<ImpactedResource> {
+        let mut impacted = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::from([resource_id]);
+        let mut queue = VecDeque::from([(resource_id, 0)]);
+        while let Some((current, depth)) = queue.pop_front() {
+            let mut dependents: Vec<&DependencyEntry> = self.dependents_of(current).collect();
+            dependents.sort_by(|a, b| a.resource_id.cmp(&b.resource_id));
+            for dependent in dependents {
+                if seen.insert(dependent.resource_id.as_str()) {
+                    impacted.push(ImpactedResource {
+                        resource_id: dependent.resource_id.clone(),
+                        owner_node: dependent.owner_node,
+                        depth: depth + 1,
+                    });
+                    queue.push_back((dependent.resource_id.as_str(), depth + 1));
+                }
+            }
+        }
+        impacted
+    }
+
+    /// Declared dependencies of `resource_id`, with broken ones flagged
+    pub fn dependencies_of(&self, resource_id: &str) -> Vec<DependencyStatus> {
+        let Some(entry) = self.entries.get(resource_id) else {
+            return Vec::new();
+        };
+        entry.dependencies.iter().map(|dependency| {
+            let resolved_version = self.entries.get(&dependency.resource_id).and_then(|d| d.version);
+            DependencyStatus {
+                broken: resolved_version.is_some_and(|v| !dependency.constraint.matches(&v)),
+                dependency: dependency.clone(),
+                resolved_version,
+            }
+        }).collect()
+    }
+
+    /// Every dependency whose constraint the known version violates
+    pub fn broken_dependencies(&self) -> Vec<BrokenDependency> {
+        let mut broken: Vec<BrokenDependency> = self.entries.keys()
+            .flat_map(|resource_id| self.broken_dependents(resource_id))
+            .collect();
+        broken.sort_by(|a, b| (&a.dependent, &a.dependency).cmp(&(&b.dependent, &b.dependency)));
+        broken
+    }
+
+    pub fn get(&self, resource_id: &str) -> Option<&DependencyEntry> {
+        self.entries.get(resource_id)
+    }
+
+    fn dependents_of<'a>(&'a self, resource_id: &'a str) -> impl Iterator<Item = &'a DependencyEntry> + 'a {
+        self.entries.values().filter(move |e| e.dependencies.iter().any(|d| d.resource_id == resource_id))
+    }
+
+    /// Dependents whose constraint on `resource_id` its known version violates
+    fn broken_dependents(&self, resource_id: &str) -> Vec<BrokenDependency> {
+        let Some(version) = self.entries.get(resource_id).and_then(|e| e.version) else {
+            return Vec::new();
+        };
+        self.dependents_of(resource_id)
+            .flat_map(|dependent| {
+                dependent.dependencies.iter()
+                    .filter(|d| d.resource_id == resource_id && !d.constraint.matches(&version))
+                    .map(move |d| BrokenDependency {
+                        dependent: dependent.resource_id.clone(),
+                        dependent_owner: dependent.owner_node,
+                        dependency: resource_id.to_string(),
+                        constraint: d.constraint.clone(),
+                        found_version: version,
+                    })
+            })
+            .collect()
+    }
+
+    fn upsert(&mut self, entry: DependencyEntry) -> Result<Vec<MeshEvent>, DependencyError> {
+        for dependency in &entry.dependencies {
+            if let Some(mut path) = self.path(&dependency.resource_id, &entry.resource_id) {
+                path.insert(0, entry.resource_id.clone());
+                return Err(DependencyError::Cycle(path));
+            }
+        }
+
+        let previous = self.entries.get(&entry.resource_id).and_then(|e| e.version);
+        let resource_id = entry.resource_id.clone();
+        let version_changed = previous != entry.version;
+        self.entries.insert(resource_id.clone(), entry);
+
+        // Only a new version can break dependents that were fine before
+        let broken = if version_changed { self.broken_dependents(&resource_id) } else { Vec::new() };
+        Ok(broken.into_iter().map(|broken| self.broken_event(broken)).collect())
+    }
+
+    /// Dependency path from `from` to `to`, if any
+    fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut parents: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        let mut seen: HashSet<&str> = HashSet::from([from]);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = vec![current.to_string()];
+                let mut node = current;
+                while let Some(parent) = parents.get(node) {
+                    path.push(parent.to_string());
+                    node = parent;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for dependency in self.entries.get(current).into_iter().flat_map(|e| &e.dependencies) {
+                let next = dependency.resource_id.as_str();
+                if seen.insert(next) {
+                    parents.insert(next, current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    fn broken_event(&self, broken: BrokenDependency) -> MeshEvent {
+        let metadata = HashMap::from([
+            ("dependency".to_string(), broken.dependency.clone()),
+            ("constraint".to_string(), broken.constraint.to_string()),
+            ("found_version".to_string(), broken.found_version.to_string()),
+        ]);
+        MeshEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            source_node: self.local_node,
+            event_type: EventType::Resource { resource_type: ResourceEventType::DependencyBroken },
+            payload: EventPayload::Resource {
+                resource_id: broken.dependent,
+                resource_type: "dependency".to_string(),
+                operation: "dependency_broken".to_string(),
+                affected_nodes: vec![broken.dependent_owner],
+                conflict_info: None,
+            },
+            metadata,
+            propagation_path: vec![self.local_node],
+            correlation_id: None,
+            priority: EventPriority::High,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::manager::{MeshConfig, MeshManager};
+    use crate::mesh::resource::ResourceType;
+    use crate::{Attribution, CollaborationType};
+    use std::sync::{Arc, RwLock};
+
+    /// Dependency announcements between managers in this process
+    #[derive(Default)]
+    struct InProcessMesh {
+        managers: RwLock<Vec<Arc<MeshManager>>>,
+    }
+
+    impl fmt::Debug for InProcessMesh {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("InProcessMesh").field("managers", &self.managers.read().unwrap().len()).finish()
+        }
+    }
+
+    impl InProcessMesh {
+        async fn join(self: &Arc<Self>) -> Arc<MeshManager> {
+            let mut manager = MeshManager::new(MeshConfig::default()).await.unwrap();
+            manager.set_dependency_peers(self.clone());
+            let manager = Arc::new(manager);
+            self.managers.write().unwrap().push(manager.clone());
+            manager
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DependencyPeers for InProcessMesh {
+        async fn announce_dependencies(&self, announcement: &DependencyAnnouncement) -> anyhow::Result<()> {
+            let managers = self.managers.read().unwrap().clone();
+            for manager in managers.iter().filter(|m| m.local_node.id != announcement.node_id) {
+                manager.apply_dependency_announcement(announcement).await;
+            }
+            Ok(())
+        }
+    }
+
+    fn resource(id: &str, version: Option<&str>, dependencies: Vec<ResourceDependency>) -> MeshResource {
+        let attribution = Attribution::new(Some("tester".to_string()), None, CollaborationType::HumanLed, 1.0);
+        let mut resource = MeshResource::new_universal(
+            id.to_string(),
+            format!("universal/{}/local/", id),
+            ResourceType::Communication { comm_type: "notes".to_string(), participants: Vec::new(), message_count: 0 },
+            attribution,
+        );
+        resource.metadata.version = version.map(str::to_string);
+        resource.metadata.declared_dependencies = dependencies;
+        resource
+    }
+
+    fn dependency(resource_id: &str, constraint: &str) -> ResourceDependency {
+        ResourceDependency::new(resource_id, constraint).unwrap()
+    }
+
+    #[test]
+    fn test_version_constraints() {
+        let v = |s: &str| s.parse::<ResourceVersion>().unwrap();
+        let c = |s: &str| s.parse::<VersionConstraint>().unwrap();
+        assert!(c("^1.2").matches(&v("1.9.0")));
+        assert!(!c("^1.2").matches(&v("2.0.0")));
+        assert!(!c("~1.2").matches(&v("1.3.0")));
+        assert!(c(">=1.0, <2").matches(&v("1.5")));
+        assert!(c("*").matches(&v("7")));
+        assert!(c("1.4").matches(&v("1.4.2")));
+        assert!("1.x".parse::<ResourceVersion>().is_err());
+        assert_eq!(serde_json::to_string(&c(">=1.0, <2")).unwrap(), "\">=1.0, <2\"");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_dependency_chain_across_nodes() {
+        let mesh = Arc::new(InProcessMesh::default());
+        let (node_a, node_b) = (mesh.join().await, mesh.join().await);
+        let (a, b) = (node_a.local_node.id, node_b.local_node.id);
+        let mut broken_at_b = node_b.subscribe_dependency_events();
+
+        // policy (A) <- template (B) <- agent (A)
+        node_a.publish_resource(resource("policy", Some("1.0.0"), vec![])).await.unwrap();
+        node_b.publish_resource(resource("template", Some("2.1.0"), vec![dependency("policy", "^1.0")])).await.unwrap();
+        node_a.publish_resource(resource("agent", None, vec![dependency("template", ">=2.0")])).await.unwrap();
+        assert!(node_b.get_resource("template").await.is_some());
+
+        for node in [&node_a, &node_b] {
+            assert_eq!(node.get_impacted("policy").await, vec![
+                ImpactedResource { resource_id: "template".to_string(), owner_node: b, depth: 1 },
+                ImpactedResource { resource_id: "agent".to_string(), owner_node: a, depth: 2 },
+            ]);
+        }
+
+        // A new major version of the policy breaks the template's constraint; only its owner is told
+        let mut broken_at_a = node_a.subscribe_dependency_events();
+        node_a.publish_resource(resource("policy", Some("2.0.0"), vec![])).await.unwrap();
+        let event = broken_at_b.try_recv().unwrap();
+        assert_eq!(event.event_type, EventType::Resource { resource_type: ResourceEventType::DependencyBroken });
+        assert!(matches!(
+            &event.payload,
+            EventPayload::Resource { resource_id, affected_nodes, .. } if resource_id == "template" && affected_nodes == &vec![b]
+        ));
+        assert!(broken_at_b.try_recv().is_err());
+        assert!(broken_at_a.try_recv().is_err());
+        let status = node_b.dependency_status("template").await;
+        assert!(status[0].broken);
+        assert_eq!(status[0].resolved_version, Some(ResourceVersion::new(2, 0, 0)));
+
+        // Another node cannot replace an entry the local node owns
+        node_b.apply_dependency_announcement(&DependencyAnnouncement {
+            node_id: a,
+            entries: vec![DependencyEntry {
+                resource_id: "template".to_string(),
+                owner_node: a,
+                version: Some(ResourceVersion::new(9, 0, 0)),
+                dependencies: vec![],
+                updated_at: Utc::now(),
+            }],
+        }).await;
+        assert_eq!(node_b.dependency_status("template").await, status);
+
+        // Closing the loop is rejected before anything is stored
+        let err = node_a.publish_resource(resource("policy", Some("2.0.0"), vec![dependency("agent", "*")])).await.unwrap_err();
+        assert_eq!(err.downcast_ref::<DependencyError>(), Some(&DependencyError::Cycle(vec![
+            "policy".to_string(), "agent".to_string(), "template".to_string(), "policy".to_string(),
+        ])));
+        assert!(node_a.get_resource("policy").await.unwrap().metadata.declared_dependencies.is_empty());
+    }
+}
@@ -12,6 +12,8 @@ use std::sync::Arc;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use super::topology::DotWriter;
+
 /// Universal mesh discovery and node management
 #[derive(Debug)]
 pub struct MeshDiscovery {
@@ -166,6 +168,31 @@ pub enum TrustLevel {
     HighlyTrusted,
 }
 
+impl TrustLevel {
+    /// Ordinal value of this trust level (0 = Unknown, 4 = HighlyTrusted)
+    pub fn ordinal(&self) -> u8 {
+        match self {
+            TrustLevel::Unknown => 0,
+            TrustLevel::Basic => 1,
+            TrustLevel::Verified => 2,
+            TrustLevel::Trusted => 3,
+            TrustLevel::HighlyTrusted => 4,
+        }
+    }
+}
+
+/// Number of nodes above which topology exports group nodes by role
+pub const TOPOLOGY_CLUSTER_THRESHOLD: usize = 50;
+
+/// Output format for topology exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyFormat {
+    /// Graphviz DOT language
+    Dot,
+    /// Mermaid graph diagram
+    Mermaid,
+}
+
 /// Discovery state
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DiscoveryState {
@@ -383,6 +410,156 @@ impl MeshDiscovery {
         self.config = config;
         debug!("Updated discovery configuration");
     }
+    
+    /// Export the known topology in the given format
+    pub fn topology_export(&self, format: TopologyFormat) -> String {
+        match format {
+            TopologyFormat::Dot => self.to_dot(),
+            TopologyFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+    
+    /// Render the known topology as a Graphviz DOT graph
+    ///
+    /// Nodes are green when online and red when offline; edges carry the
+    /// trust level ordinal as their weight. Meshes larger than
+    /// `TOPOLOGY_CLUSTER_THRESHOLD` are grouped into clusters by role.
+    pub fn to_dot(&self) -> String {
+        let nodes = self.sorted_nodes();
+        let mut dot = DotWriter::new();
+        let local_id = self.node_id.to_string();
+        dot.node(&local_id, &[&local_id, "(local)"], "fillcolor=lightblue");
+        
+        if nodes.len() > TOPOLOGY_CLUSTER_THRESHOLD {
+            for (index, (role, members)) in Self::group_by_role(&nodes).into_iter().enumerate() {
+                dot.cluster(index, &format!("{:?}", role), |dot| {
+                    for node in members {
+                        self.dot_node(dot, node);
+                    }
+                });
+            }
+        } else {
+            for node in &nodes {
+                self.dot_node(&mut dot, node);
+            }
+        }
+        
+        for (from, to, trust) in self.topology_edges(&nodes) {
+            dot.edge(from, to, Some(&format!("weight={}, label=\"{:?}\"", trust.ordinal(), trust)));
+        }
+        
+        dot.finish()
+    }
+    
+    /// Render the known topology as a Mermaid graph for Markdown embedding
+    pub fn to_mermaid(&self) -> String {
+        let nodes = self.sorted_nodes();
+        let mut out = String::from("graph LR\n");
+        out.push_str(&format!("    {}[\"{} (local)\"]\n", Self::mermaid_id(&self.node_id), self.node_id));
+        
+        if nodes.len() > TOPOLOGY_CLUSTER_THRESHOLD {
+            for (index, (role, members)) in Self::group_by_role(&nodes).into_iter().enumerate() {
+                out.push_str(&format!("    subgraph role{} [\"{:?}\"]\n", index, role));
+                for node in members {
+                    out.push_str(&format!("    {}", self.mermaid_node(node)));
+                }
+                out.push_str("    end\n");
+            }
+        } else {
+            for node in &nodes {
+                out.push_str(&self.mermaid_node(node));
+            }
+        }
+        
+        for (from, to, trust) in self.topology_edges(&nodes) {
+            out.push_str(&format!(
+                "    {} -->|{}| {}\n",
+                Self::mermaid_id(&from), trust.ordinal(), Self::mermaid_id(&to)
+            ));
+        }
+        
+        out.push_str("    classDef online fill:#9f9,stroke:#393\n");
+        out.push_str("    classDef offline fill:#f99,stroke:#933\n");
+        out
+    }
+    
+    /// Known nodes sorted by ID for deterministic output
+    fn sorted_nodes(&self) -> Vec<&MeshNode> {
+        let mut nodes: Vec<&MeshNode> = self.known_nodes.values().collect();
+        nodes.sort_by_key(|node| node.node_id);
+        nodes
+    }
+    
+    /// Group sorted nodes by role, keeping groups in a stable order
+    fn group_by_role<'a>(nodes: &[&'a MeshNode]) -> Vec<(ArchetypalRole, Vec<&'a MeshNode>)> {
+        let mut groups: Vec<(ArchetypalRole, Vec<&'a MeshNode>)> = Vec::new();
+        for node in nodes.iter().copied() {
+            match groups.iter_mut().find(|(role, _)| *role == node.archetypal_role) {
+                Some((_, members)) => members.push(node),
+                None => groups.push((node.archetypal_role.clone(), vec![node])),
+            }
+        }
+        groups.sort_by_key(|(role, _)| format!("{:?}", role));
+        groups
+    }
+    
+    /// Edges from this node to every known node, plus peer links from node metadata
+    ///
+    /// Peer links are read from a comma-separated `peers` metadata entry.
+    fn topology_edges(&self, nodes: &[&MeshNode]) -> Vec<(Uuid, Uuid, TrustLevel)> {
+        let mut edges = Vec::new();
+        for node in nodes {
+            edges.push((self.node_id, node.node_id, node.trust_level.clone()));
+        }
+        
+        for node in nodes {
+            let mut peers: Vec<Uuid> = node.metadata.get("peers")
+                .map(|peers| peers.split(',').filter_map(|p| Uuid::parse_str(p.trim()).ok()).collect())
+                .unwrap_or_default();
+            peers.sort();
+            
+            for peer in peers {
+                if let Some(peer_node) = self.known_nodes.get(&peer) {
+                    edges.push((node.node_id, peer, peer_node.trust_level.clone()));
+                }
+            }
+        }
+        
+        edges
+    }
+    
+    /// Whether a node has been seen within the node timeout
+    fn is_node_online(&self, node: &MeshNode) -> bool {
+        (Utc::now() - node.last_seen).num_seconds() < self.config.node_timeout as i64
+    }
+    
+    /// Display name of a node, falling back to a short ID
+    fn display_name(node: &MeshNode) -> String {
+        node.metadata.get("display_name")
+            .cloned()
+            .unwrap_or_else(|| node.node_id.to_string()[..8].to_string())
+    }
+    
+    fn dot_node(&self, dot: &mut DotWriter, node: &MeshNode) {
+        let color = if self.is_node_online(node) { "green" } else { "red" };
+        let node_id = node.node_id.to_string();
+        dot.node(&node_id, &[&Self::display_name(node), &node_id], &format!("fillcolor={}", color));
+    }
+    
+    fn mermaid_node(&self, node: &MeshNode) -> String {
+        let class = if self.is_node_online(node) { "online" } else { "offline" };
+        format!(
+            "    {}[\"{}<br/>{}\"]:::{}\n",
+            Self::mermaid_id(&node.node_id),
+            Self::display_name(node).replace('"', "'"),
+            node.node_id,
+            class
+        )
+    }
+    
+    fn mermaid_id(node_id: &Uuid) -> String {
+        format!("n{}", node_id.simple())
+    }
 }
 
 /// Discovery statistics
@@ -465,4 +642,59 @@ mod tests {
         assert_eq!(ArchetypalRole::Creator.communication_style(), CommunicationStyle::Creative);
         assert_eq!(ArchetypalRole::SacredPartnership.communication_style(), CommunicationStyle::Empathetic);
     }
+    
+    fn topology_node(id: u128, name: &str, role: ArchetypalRole, online: bool) -> MeshNode {
+        let mut metadata = HashMap::new();
+        metadata.insert("display_name".to_string(), name.to_string());
+        MeshNode {
+            node_id: Uuid::from_u128(id),
+            capabilities: NodeCapabilities::default(),
+            archetypal_role: role,
+            trust_level: TrustLevel::Verified,
+            last_seen: if online { Utc::now() } else { Utc::now() - chrono::Duration::hours(1) },
+            metadata,
+            context_data: HashMap::new(),
+        }
+    }
+    
+    #[test]
+    fn test_topology_export() {
+        let mut discovery = MeshDiscovery::new(Uuid::from_u128(1), NodeCapabilities::default(), None);
+        discovery.add_node(topology_node(3, "beta", ArchetypalRole::Sage, false));
+        discovery.add_node(topology_node(2, "alpha", ArchetypalRole::Creator, true));
+        
+        let dot = discovery.to_dot();
+        assert!(dot.starts_with("digraph mesh {"));
+        assert!(dot.contains("alpha"));
+        assert!(dot.contains("fillcolor=green"));
+        assert!(dot.contains("fillcolor=red"));
+        assert!(dot.contains("weight=2"));
+        assert!(dot.find("alpha").unwrap() < dot.find("beta").unwrap());
+        assert!(!dot.contains("subgraph"));
+        
+        // Deterministic output
+        assert_eq!(dot, discovery.topology_export(TopologyFormat::Dot));
+        
+        let mermaid = discovery.to_mermaid();
+        assert!(mermaid.starts_with("graph LR"));
+        assert!(mermaid.contains(":::online"));
+        assert!(mermaid.contains(":::offline"));
+        assert!(mermaid.contains("-->|2|"));
+    }
+    
+    #[test]
+    fn test_topology_export_clusters_large_mesh() {
+        let mut discovery = MeshDiscovery::new(Uuid::from_u128(1), NodeCapabilities::default(), None);
+        for i in 0..500u128 {
+            let role = if i % 2 == 0 { ArchetypalRole::Sage } else { ArchetypalRole::Hero };
+            discovery.add_node(topology_node(i + 2, &format!("node-{}", i), role, true));
+        }
+        
+        let dot = discovery.to_dot();
+        assert_eq!(dot.matches("subgraph cluster_").count(), 2);
+        assert_eq!(dot.matches(" -> ").count(), 500);
+        
+        let mermaid = discovery.to_mermaid();
+        assert_eq!(mermaid.matches("subgraph role").count(), 2);
+    }
 }
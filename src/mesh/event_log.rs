@@ -0,0 +1,315 @@
+//! Append-only log of mesh events
+//!
+//! Each published [`MeshEvent`] is written as one JSON line
+//! `{ "event": ..., "logged_at": ..., "sequence": n }`, so an incident can be
+//! re-processed later with [`MeshEvent::replay_from_log`]. Sequences keep
+//! counting across restarts and rotations. Rotated segments are gzipped next
+//! to the log as `<log>.<first>-<last>.gz`, and replay reads them as well.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::compute::{ComputeCategory, ComputePool};
+use super::events::{EventConfig, EventProvider, MeshEvent};
+
+/// One line of the event log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    /// The event as published
+    pub event: MeshEvent,
+    /// When the event was written
+    pub logged_at: DateTime<Utc>,
+    /// Position of the event in the log, starting at 1
+    pub sequence: u64,
+}
+
+/// Appends mesh events to a newline-delimited JSON file
+///
+/// Clones share the file and the sequence counter. Add a clone to an
+/// [`super::EventSystem`] as a provider to log everything it publishes.
+#[derive(Debug, Clone)]
+pub struct EventLogger {
+    path: PathBuf,
+    state: Arc<Mutex<LoggerState>>,
+    /// Segment size that triggers rotation, and the pool compressing it
+    auto_rotate: Option<(u64, ComputePool)>,
+}
+
+#[derive(Debug)]
+struct LoggerState {
+    file: File,
+    /// Sequence of the next event written
+    next_sequence: u64,
+    /// Sequence of the first event in the current segment
+    segment_start: Option<u64>,
+    /// Size of the current segment in bytes
+    segment_bytes: u64,
+}
+
+impl EventLogger {
+    /// Open the log at `path`, continuing its sequence if it already exists
+    pub fn open(path: &Path) -> Result<Self> {
+        let (segment_start, last_sequence, segment_bytes) = if path.exists() {
+            let mut first = None;
+            let mut last = None;
+            for entry in read_entries(path)? {
+                let entry = entry?;
+                first.get_or_insert(entry.sequence);
+                last = Some(entry.sequence);
+            }
+            (first, last, std::fs::metadata(path)?.len())
+        } else {
+            (None, None, 0)
+        };
+        let archived_last = archived_segments(path)?.last().map(|(_, _, last)| *last);
+        let next_sequence = last_sequence.or(archived_last).map_or(1, |last| last + 1);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open event log {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: Arc::new(Mutex::new(LoggerState { file, next_sequence, segment_start, segment_bytes })),
+            auto_rotate: None,
+        })
+    }
+
+    /// Rotate the log once a segment reaches `max_size_bytes` while handling events
+    ///
+    /// Segments are compressed on `pool`, so publishing never blocks on gzip.
+    pub fn with_auto_rotation(mut self, max_size_bytes: u64, pool: ComputePool) -> Self {
+        self.auto_rotate = Some((max_size_bytes, pool));
+        self
+    }
+
+    /// Path of the current segment
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `event`, returning its sequence number
+    pub fn log(&self, event: &MeshEvent) -> Result<u64> {
+        let mut state = self.state.lock().unwrap();
+        let entry = LoggedEvent {
+            event: event.clone(),
+            logged_at: Utc::now(),
+            sequence: state.next_sequence,
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        state.file.write_all(&line)?;
+
+        state.segment_start.get_or_insert(entry.sequence);
+        state.segment_bytes += line.len() as u64;
+        state.next_sequence += 1;
+        Ok(entry.sequence)
+    }
+
+    /// Compress the current segment into an archive once it reaches `max_size_bytes`
+    ///
+    /// Returns the archive written, if the segment was rotated.
+    pub fn rotate(&self, max_size_bytes: u64) -> Result<Option<PathBuf>> {
+        let mut state = self.state.lock().unwrap();
+        let Some(first) = state.segment_start else {
+            return Ok(None);
+        };
+        if state.segment_bytes < max_size_bytes {
+            return Ok(None);
+        }
+
+        let last = state.next_sequence - 1;
+        let archive = archive_path(&self.path, first, last);
+        let mut encoder = GzEncoder::new(File::create(&archive)?, Compression::default());
+        std::io::copy(&mut File::open(&self.path)?, &mut encoder)?;
+        encoder.finish()?.sync_all()?;
+
+        state.file.set_len(0)?;
+        state.segment_start = None;
+        state.segment_bytes = 0;
+        debug!("Rotated event log {} into {}", self.path.display(), archive.display());
+        Ok(Some(archive))
+    }
+
+    /// Archived segments of this log followed by the current one, oldest first
+    pub fn segments(&self) -> Result<Vec<PathBuf>> {
+        let mut segments: Vec<PathBuf> = archived_segments(&self.path)?
+            .into_iter()
+            .map(|(path, _, _)| path)
+            .collect();
+        segments.push(self.path.clone());
+        Ok(segments)
+    }
+}
+
+#[async_trait::async_trait]
+impl EventProvider for EventLogger {
+    fn name(&self) -> &str {
+        "event-log"
+    }
+
+    async fn initialize(&mut self, _config: &EventConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_event(&self, event: &MeshEvent) -> Result<()> {
+        self.log(event)?;
+        if let Some((max_size_bytes, pool)) = &self.auto_rotate {
+            let logger = self.clone();
+            let max_size_bytes = *max_size_bytes;
+            pool.run(ComputeCategory::Compression, &CancellationToken::new(), move || logger.rotate(max_size_bytes))
+                .await??;
+        }
+        Ok(())
+    }
+
+    async fn create_context_event(&self, _context_data: serde_json::Value) -> Result<MeshEvent> {
+        Err(anyhow::anyhow!("Event log does not create events"))
+    }
+
+    fn get_event_patterns(&self) -> Vec<String> {
+        vec!["*".to_string()]
+    }
+}
+
+impl MeshEvent {
+    /// Call `handler` with each event in a log segment, in order, returning how many there were
+    ///
+    /// Gzipped segments written by [`EventLogger::rotate`] are read as well.
+    pub fn replay_from_log(path: &Path, handler: impl Fn(MeshEvent) + Send) -> Result<usize> {
+        let mut count = 0;
+        for entry in read_entries(path)? {
+            handler(entry?.event);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Entries of a plain or gzipped log segment
+fn read_entries(path: &Path) -> Result<impl Iterator<Item = Result<LoggedEvent>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open event log {}", path.display()))?;
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let display = path.display().to_string();
+
+    Ok(BufReader::new(reader)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(move |(index, line)| {
+            let line = line?;
+            serde_json::from_str(&line)
+                .with_context(|| format!("Malformed event at {}:{}", display, index + 1))
+        }))
+}
+
+/// Where the segment holding sequences `first..=last` is archived
+fn archive_path(path: &Path, first: u64, last: u64) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{:012}-{:012}.gz", first, last));
+    path.with_file_name(name)
+}
+
+/// Archived segments of the log at `path` with their sequence ranges, oldest first
+fn archived_segments(path: &Path) -> Result<Vec<(PathBuf, u64, u64)>> {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Ok(Vec::new());
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = format!("{}.", file_name);
+    let mut segments = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(range) = name.to_str()
+            .and_then(|name| name.strip_prefix(&prefix))
+            .and_then(|rest| rest.strip_suffix(".gz"))
+        else {
+            continue;
+        };
+        if let Some((first, last)) = range.split_once('-') {
+            if let (Ok(first), Ok(last)) = (first.parse(), last.parse()) {
+                segments.push((entry.path(), first, last));
+            }
+        }
+    }
+    segments.sort_by_key(|(_, first, _)| *first);
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::events::{EventSystem, NodeLifecycleType};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_log_replay_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+        let logger = EventLogger::open(&path).unwrap();
+
+        let mut events = EventSystem::new(Uuid::new_v4(), None);
+        events.add_provider(Box::new(logger.clone()));
+        let node_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for node_id in &node_ids {
+            let event = events.create_node_event(NodeLifecycleType::NodeJoined, *node_id, None, None);
+            events.publish_event(event).await.unwrap();
+        }
+
+        let replayed = Mutex::new(Vec::new());
+        let count = MeshEvent::replay_from_log(&path, |event| replayed.lock().unwrap().push(event)).unwrap();
+        assert_eq!(count, 3);
+        let sources: Vec<Uuid> = replayed.into_inner().unwrap().iter()
+            .map(|event| match &event.payload {
+                crate::mesh::events::EventPayload::NodeLifecycle { node_id, .. } => *node_id,
+                other => panic!("unexpected payload {:?}", other),
+            })
+            .collect();
+        assert_eq!(sources, node_ids);
+
+        // Small segments are left alone; large ones are archived and emptied
+        assert!(logger.rotate(u64::MAX).unwrap().is_none());
+        let archive = logger.rotate(1).unwrap().expect("segment rotated");
+        assert_eq!(MeshEvent::replay_from_log(&archive, |_| {}).unwrap(), 3);
+        assert_eq!(MeshEvent::replay_from_log(&path, |_| {}).unwrap(), 0);
+
+        // Sequences continue after rotation and reopening
+        let event = events.create_node_event(NodeLifecycleType::NodeLeft, node_ids[0], None, None);
+        assert_eq!(logger.log(&event).unwrap(), 4);
+        drop(logger);
+        let reopened = EventLogger::open(&path).unwrap();
+        assert_eq!(reopened.log(&event).unwrap(), 5);
+        assert_eq!(reopened.segments().unwrap(), vec![archive, path.clone()]);
+
+        std::fs::write(&path, b"{not json}\n").unwrap();
+        assert!(MeshEvent::replay_from_log(&path, |_| {}).is_err());
+    }
+}
@@ -200,6 +200,9 @@ pub enum ResourceEventType {
     
     /// Resource synchronized
     ResourceSynchronized,
+    
+    /// A dependency published a version outside the resource's constraint
+    DependencyBroken,
 }
 
 /// Topology event types
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
@@ -43,6 +43,24 @@ pub enum HealthStatus {
     Unknown,
 }
 
+/// Stream of events received from now on, skipping any missed by a slow consumer
+fn broadcast_stream<T: Clone + Send + 'static>(
+    receiver: broadcast::Receiver<T>,
+    kind: &'static str,
+) -> impl futures::Stream<Item = T> {
+    futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Health {} subscriber lagged, skipped {} events", kind, skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
 /// Universal health monitoring service for mesh networks
 pub struct HealthMonitor {
     /// Local node ID
@@ -68,6 +86,42 @@ pub struct HealthMonitor {
     
     /// Health providers for context-specific monitoring
     providers: Vec<Box<dyn HealthProvider>>,
+    
+    /// Nodes whose health worsened
+    degradations: broadcast::Sender<HealthDegradationEvent>,
+    
+    /// Nodes whose health improved
+    recoveries: broadcast::Sender<HealthRecoveryEvent>,
+}
+
+/// A node's health got worse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthDegradationEvent {
+    /// Node concerned
+    pub node_id: Uuid,
+    /// Status before the change
+    pub previous_status: HealthStatus,
+    /// Status after the change
+    pub current_status: HealthStatus,
+    /// Issues of the current status
+    pub issues: Vec<HealthIssue>,
+    /// When the change was recorded
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A node's health got better
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthRecoveryEvent {
+    /// Node concerned
+    pub node_id: Uuid,
+    /// Status before the change
+    pub previous_status: HealthStatus,
+    /// Status after the change
+    pub current_status: HealthStatus,
+    /// Issues remaining in the current status
+    pub issues: Vec<HealthIssue>,
+    /// When the change was recorded
+    pub timestamp: DateTime<Utc>,
 }
 
 /// Detailed health status for a node
@@ -301,6 +355,14 @@ pub struct HealthConfig {
     
     /// Context-specific configuration
     pub context_config: HashMap<String, serde_json::Value>,
+    
+    /// Buffered degradation and recovery events per subscriber
+    #[serde(default = "default_event_channel_capacity")]
+    pub event_channel_capacity: usize,
+}
+
+fn default_event_channel_capacity() -> usize {
+    64
 }
 
 impl Default for HealthConfig {
@@ -317,6 +379,7 @@ impl Default for HealthConfig {
             error_rate_warning_threshold: 10.0,
             auto_issue_detection: true,
             context_config: HashMap::new(),
+            event_channel_capacity: default_event_channel_capacity(),
         }
     }
 }
@@ -386,6 +449,8 @@ impl HealthMonitor {
             })),
             task_handle: None,
             is_running: Arc::new(RwLock::new(false)),
+            degradations: broadcast::channel(config.event_channel_capacity.max(1)).0,
+            recoveries: broadcast::channel(config.event_channel_capacity.max(1)).0,
             config,
             providers: Vec::new(),
         }
@@ -478,10 +543,73 @@ impl HealthMonitor {
             })
         };
         
+        if let Some(HealthEvent::HealthStatusChanged { node_id, old_status, new_status }) = &event {
+            self.publish_change(*node_id, old_status, new_status);
+        }
+        
         health.insert(status.node_id, status);
         event
     }
     
+    /// Publish a degradation or recovery event for a status change
+    fn publish_change(&self, node_id: Uuid, previous: &HealthStatus, current: &HealthStatus) {
+        let worsened = match (previous, current) {
+            (_, HealthStatus::Unknown) => return,
+            (HealthStatus::Unknown, HealthStatus::Healthy) => false,
+            (HealthStatus::Unknown, _) => true,
+            _ => match current.score().partial_cmp(&previous.score()) {
+                Some(std::cmp::Ordering::Less) => true,
+                Some(std::cmp::Ordering::Greater) => false,
+                _ => return,
+            },
+        };
+        
+        let (previous_status, current_status, issues) = (previous.clone(), current.clone(), current.issues().to_vec());
+        let timestamp = Utc::now();
+        // Sending fails only when nobody is subscribed
+        if worsened {
+            let _ = self.degradations.send(HealthDegradationEvent { node_id, previous_status, current_status, issues, timestamp });
+        } else {
+            let _ = self.recoveries.send(HealthRecoveryEvent { node_id, previous_status, current_status, issues, timestamp });
+        }
+    }
+    
+    /// Stream of nodes whose health worsens from now on
+    pub fn degradation_events(&self) -> impl futures::Stream<Item = HealthDegradationEvent> {
+        broadcast_stream(self.degradations.subscribe(), "degradation")
+    }
+    
+    /// Stream of nodes whose health improves from now on
+    pub fn recovery_events(&self) -> impl futures::Stream<Item = HealthRecoveryEvent> {
+        broadcast_stream(self.recoveries.subscribe(), "recovery")
+    }
+    
+    /// Wait until a node is `Healthy`, failing after `timeout`
+    pub async fn wait_for_healthy(&self, node_id: Uuid, timeout: Duration) -> Result<()> {
+        // Subscribe before checking so a recovery in between is not missed
+        let mut recoveries = self.recoveries.subscribe();
+        let wait = async {
+            loop {
+                let healthy = self.get_node_health(node_id).await
+                    .is_some_and(|status| status.status == HealthStatus::Healthy);
+                if healthy {
+                    return;
+                }
+                loop {
+                    match recoveries.recv().await {
+                        Ok(event) if event.node_id == node_id && event.current_status == HealthStatus::Healthy => return,
+                        Ok(_) => {}
+                        // Missed events: check the status again
+                        Err(broadcast::error::RecvError::Lagged(_)) => break,
+                        Err(broadcast::error::RecvError::Closed) => std::future::pending::<()>().await,
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait).await
+            .map_err(|_| anyhow::anyhow!("Node {} not healthy after {:?}", node_id, timeout))
+    }
+    
     /// Perform a health check on a specific node
     pub async fn check_node_health(&self, node_id: Uuid) -> Result<HealthCheckResult> {
         let start_time = std::time::Instant::now();
@@ -691,6 +819,14 @@ impl HealthStatus {
         }
     }
     
+    /// Issues carried by the status
+    pub fn issues(&self) -> &[HealthIssue] {
+        match self {
+            HealthStatus::Degraded { issues, .. } | HealthStatus::Unhealthy { issues, .. } => issues,
+            _ => &[],
+        }
+    }
+    
     /// Check if the status indicates the node is available
     pub fn is_available(&self) -> bool {
         matches!(self, HealthStatus::Healthy | HealthStatus::Degraded { .. })
@@ -833,4 +969,56 @@ mod tests {
         assert!(retrieved.is_some());
         assert_eq!(retrieved.unwrap().node_id, test_status.node_id);
     }
+    
+    fn node_status(node_id: Uuid, status: HealthStatus) -> NodeHealthStatus {
+        NodeHealthStatus {
+            node_id,
+            status,
+            last_check: Utc::now(),
+            response_time_ms: 50.0,
+            metrics: NodeHealthMetrics::default(),
+            history: Vec::new(),
+            context_data: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_degradation_and_recovery_events() {
+        use futures::StreamExt;
+        
+        let monitor = Arc::new(HealthMonitor::new(Uuid::new_v4(), None));
+        let node = Uuid::new_v4();
+        let mut degradations = Box::pin(monitor.degradation_events());
+        let mut recoveries = Box::pin(monitor.recovery_events());
+        
+        let waiter = tokio::spawn({
+            let monitor = monitor.clone();
+            async move { monitor.wait_for_healthy(node, Duration::from_secs(5)).await }
+        });
+        
+        let issue = HealthIssue::HighCpuUsage { current: 95.0, threshold: 80.0 };
+        monitor.update_node_health(node_status(node, HealthStatus::Degraded {
+            issues: vec![issue.clone()],
+            severity: HealthSeverity::Medium,
+        })).await;
+        let degraded = degradations.next().await.unwrap();
+        assert_eq!((degraded.node_id, degraded.previous_status), (node, HealthStatus::Unknown));
+        assert_eq!(degraded.issues, vec![issue]);
+        
+        // Same score: no event; then recovery to healthy releases the waiter
+        monitor.update_node_health(node_status(node, HealthStatus::Degraded {
+            issues: Vec::new(),
+            severity: HealthSeverity::Medium,
+        })).await;
+        assert!(!waiter.is_finished());
+        monitor.update_node_health(node_status(node, HealthStatus::Healthy)).await;
+        let recovered = recoveries.next().await.unwrap();
+        assert_eq!(recovered.current_status, HealthStatus::Healthy);
+        assert!(recovered.issues.is_empty());
+        waiter.await.unwrap().unwrap();
+        
+        // Already healthy returns at once; an unknown node times out
+        monitor.wait_for_healthy(node, Duration::from_millis(10)).await.unwrap();
+        assert!(monitor.wait_for_healthy(Uuid::new_v4(), Duration::from_millis(10)).await.is_err());
+    }
 }
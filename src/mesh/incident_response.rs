@@ -0,0 +1,210 @@
+//! Automated incident response
+//!
+//! A [`PlaybookTrigger`] counts security events of one type at or above a
+//! severity; when `count_threshold` of them fall within `within`, the
+//! registered [`IncidentResponsePlaybook`] runs with those events. The
+//! [`SecuritySystem`](super::security::SecuritySystem) records every run as a
+//! `ConfigurationChange` event so responses leave an audit trail.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::security::{SecurityEvent, SecurityEventType, SecuritySeverity, TrustRevoker};
+
+/// How long [`NotifyAdminPlaybook`] waits for the endpoint by default
+pub const NOTIFY_ADMIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// When a playbook runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaybookTrigger {
+    /// Events counted towards the threshold
+    pub event_type: SecurityEventType,
+    /// Least severity counted
+    pub min_severity: SecuritySeverity,
+    /// Events needed within the window
+    pub count_threshold: u32,
+    /// Window the events must fall in
+    pub within: Duration,
+}
+
+impl PlaybookTrigger {
+    /// Whether `event` counts towards this trigger
+    pub fn matches(&self, event: &SecurityEvent) -> bool {
+        event.event_type == self.event_type && event.severity >= self.min_severity
+    }
+}
+
+/// Audit record of a playbook run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlaybookResult {
+    /// What the playbook did, in order
+    pub actions_taken: Vec<String>,
+    /// Whether the incident was handed to a person
+    pub escalated: bool,
+    /// Whether the incident needs no further response
+    pub resolved: bool,
+}
+
+/// Response to a triggered incident
+#[async_trait::async_trait]
+pub trait IncidentResponsePlaybook: Send + Sync {
+    /// Name recorded in the audit event
+    fn name(&self) -> &str;
+
+    /// Respond to the events that crossed the trigger's threshold
+    async fn execute(&self, events: Vec<SecurityEvent>) -> Result<PlaybookResult>;
+}
+
+/// A playbook with the recent events counted towards its trigger
+pub(crate) struct RegisteredPlaybook {
+    pub(crate) trigger: PlaybookTrigger,
+    pub(crate) playbook: Box<dyn IncidentResponsePlaybook + Send + Sync>,
+    window: Mutex<VecDeque<SecurityEvent>>,
+}
+
+impl RegisteredPlaybook {
+    pub(crate) fn new(trigger: PlaybookTrigger, playbook: Box<dyn IncidentResponsePlaybook + Send + Sync>) -> Self {
+        Self { trigger, playbook, window: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Count `event`, returning the events to respond to once the threshold is crossed
+    ///
+    /// Firing empties the window, so the next run needs a full threshold again.
+    pub(crate) fn observe(&self, event: &SecurityEvent) -> Option<Vec<SecurityEvent>> {
+        if !self.trigger.matches(event) {
+            return None;
+        }
+        let within = chrono::Duration::from_std(self.trigger.within).unwrap_or(chrono::Duration::MAX);
+        let mut window = self.window.lock().unwrap();
+        window.push_back(event.clone());
+        while window.front().is_some_and(|oldest| event.timestamp - oldest.timestamp > within) {
+            window.pop_front();
+        }
+        if window.len() < self.trigger.count_threshold.max(1) as usize {
+            return None;
+        }
+        Some(window.drain(..).collect())
+    }
+}
+
+/// Nodes in the events, other than this one, stop being trusted
+///
+/// Trust is revoked in the [`SecuritySystem`](super::security::SecuritySystem)
+/// the revoker came from, so every trust check sees the block.
+pub struct BlockNodePlaybook {
+    revoker: TrustRevoker,
+}
+
+impl BlockNodePlaybook {
+    /// Block playbook revoking trust through `revoker`
+    pub fn new(revoker: TrustRevoker) -> Self {
+        Self { revoker }
+    }
+}
+
+#[async_trait::async_trait]
+impl IncidentResponsePlaybook for BlockNodePlaybook {
+    fn name(&self) -> &str {
+        "block-node"
+    }
+
+    async fn execute(&self, events: Vec<SecurityEvent>) -> Result<PlaybookResult> {
+        let evidence: Vec<String> = events.iter().map(|event| event.event_id.to_string()).collect();
+        let mut nodes: Vec<_> = events.iter().flat_map(|event| event.involved_nodes.iter().copied()).collect();
+        nodes.sort();
+        nodes.dedup();
+
+        let mut result = PlaybookResult::default();
+        for node_id in nodes {
+            if self.revoker.revoke(node_id, "Blocked by incident response", evidence.clone()).await {
+                result.actions_taken.push(format!("blocked node {}", node_id));
+            }
+        }
+        result.resolved = true;
+        Ok(result)
+    }
+}
+
+/// Body posted by [`NotifyAdminPlaybook`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentNotification {
+    pub notified_at: DateTime<Utc>,
+    pub events: Vec<SecurityEvent>,
+}
+
+/// Posts the events as JSON to an administrator's endpoint
+///
+/// The post is sent in the background so a slow endpoint never holds up
+/// security logging; a failed post is logged rather than audited.
+pub struct NotifyAdminPlaybook {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl NotifyAdminPlaybook {
+    /// Notify `url`, giving up after [`NOTIFY_ADMIN_TIMEOUT`]
+    pub fn new(url: &str) -> Result<Self> {
+        Self::with_timeout(url, NOTIFY_ADMIN_TIMEOUT)
+    }
+
+    /// Notify `url`, giving up after `timeout`
+    pub fn with_timeout(url: &str, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self { url: url.to_string(), client })
+    }
+}
+
+#[async_trait::async_trait]
+impl IncidentResponsePlaybook for NotifyAdminPlaybook {
+    fn name(&self) -> &str {
+        "notify-admin"
+    }
+
+    async fn execute(&self, events: Vec<SecurityEvent>) -> Result<PlaybookResult> {
+        let notification = IncidentNotification { notified_at: Utc::now(), events };
+        let request = self.client
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .body(serde_json::to_vec(&notification)?);
+        let url = self.url.clone();
+        tokio::spawn(async move {
+            if let Err(e) = request.send().await.and_then(|response| response.error_for_status()) {
+                tracing::warn!("Failed to notify {}: {}", url, e);
+            }
+        });
+        Ok(PlaybookResult {
+            actions_taken: vec![format!("sent notification to {}", self.url)],
+            escalated: true,
+            resolved: false,
+        })
+    }
+}
+
+/// Writes the events to a JSON file in a directory, for later analysis
+pub struct SnapshotStatePlaybook(pub PathBuf);
+
+#[async_trait::async_trait]
+impl IncidentResponsePlaybook for SnapshotStatePlaybook {
+    fn name(&self) -> &str {
+        "snapshot-state"
+    }
+
+    async fn execute(&self, events: Vec<SecurityEvent>) -> Result<PlaybookResult> {
+        let taken_at = Utc::now();
+        let path = self.0.join(format!("incident-{}.json", taken_at.format("%Y%m%dT%H%M%S%.3fZ")));
+        tokio::fs::create_dir_all(&self.0).await?;
+        tokio::fs::write(&path, serde_json::to_vec_pretty(&IncidentNotification { notified_at: taken_at, events })?)
+            .await
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))?;
+        Ok(PlaybookResult {
+            actions_taken: vec![format!("wrote snapshot {}", path.display())],
+            escalated: false,
+            resolved: false,
+        })
+    }
+}
@@ -5,16 +5,46 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tokio::sync::{broadcast, RwLock};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 use zenoh::{Config, Session};
 
+use super::admission::{
+    AdmissionConfig, AdmissionDecision, AdmissionPeers, AdmissionRegistry, AdmissionRequest, AdmissionStatus,
+};
+use super::decommission::{
+    DecommissionPeers, DecommissionProgress, DecommissionReport, DecommissionTargets, RetirementNotice,
+    TransferOffer,
+};
+use super::dependency_graph::{
+    DependencyAnnouncement, DependencyGraph, DependencyPeers, DependencyStatus, ImpactedResource,
+};
 use super::discovery::{MeshDiscovery, NodeCapabilities, TrustLevel};
+use super::event_log::EventLogger;
+use super::metadata_visibility::{
+    answer_metadata_query, fetch_node_metadata, metadata_query_key, MetadataCache, MetadataQueryClient,
+    MetadataResponse, MetadataVisibility, MetadataVisibilityMap, SignedMetadataQuery,
+};
+use super::publication::{PublicationGate, PublicationState};
+use super::resource::{MeshResource, VisibilityLevel};
+use super::topology::{
+    TopologyDiff, TopologyDiffPublisher, TopologyFilter, TopologyGraph, TopologyGraphFormat, TopologySource,
+    ORGANIZATION_METADATA_KEY,
+};
+use super::verification::{AnnouncementBody, SignedAnnouncement};
 use super::MeshError;
+use crate::group_communication::{BasicGroupCommunication, GroupId};
+use crate::key_rotation::{key_fingerprint, NodeKeys};
+use crate::Attribution;
+
+/// Weight of each new sample in a node's smoothed latency
+const LATENCY_SMOOTHING: f64 = 0.2;
 
 /// Universal mesh manager for distributed networking
 #[derive(Debug)]
@@ -34,6 +64,48 @@ pub struct MeshManager {
     /// Mesh configuration
     pub config: MeshConfig,
     
+    /// Resources known to this node, by ID
+    pub resources: Arc<RwLock<HashMap<String, MeshResource>>>,
+    
+    /// Debounced diffs of the node table
+    topology_diffs: TopologyDiffPublisher,
+    
+    /// Restricted metadata fetched from other nodes
+    metadata_cache: RwLock<MetadataCache>,
+    
+    /// Channel for direct metadata queries
+    metadata_client: Option<Arc<dyn MetadataQueryClient>>,
+    
+    /// Channel to the rest of the mesh while decommissioning this node
+    decommission_peers: Option<Arc<dyn DecommissionPeers>>,
+    
+    /// Nodes that announced their retirement and may not rejoin
+    retired_nodes: Arc<RwLock<HashSet<Uuid>>>,
+    
+    /// Log of mesh events, once started
+    event_logger: std::sync::Mutex<Option<EventLogger>>,
+    
+    /// Admission state of known nodes
+    admission: AdmissionRegistry,
+    
+    /// Channel to gatekeepers and peers for admission traffic
+    admission_peers: Option<Arc<dyn AdmissionPeers>>,
+    
+    /// Admission requests received while this node is a gatekeeper
+    admission_requests: broadcast::Sender<AdmissionRequest>,
+    
+    /// Dependencies of the resources this node knows about
+    dependencies: RwLock<DependencyGraph>,
+    
+    /// Channel to peers for dependency announcements
+    dependency_peers: Option<Arc<dyn DependencyPeers>>,
+    
+    /// DependencyBroken events addressed to this node
+    dependency_events: broadcast::Sender<super::events::MeshEvent>,
+    
+    /// Review workflow resources must pass before they are exposed
+    publication: Option<Arc<RwLock<dyn PublicationGate>>>,
+    
     /// Mesh state
     state: MeshState,
 }
@@ -47,6 +119,12 @@ pub struct LocalNode {
     pub capabilities: NodeCapabilities,
     /// Node metadata
     pub metadata: HashMap<String, String>,
+    /// Visibility of metadata entries; untagged entries are Public
+    ///
+    /// Shared with [`NodeDiscovery`](crate::networking::NodeDiscovery) so
+    /// announcements leave out the same entries.
+    #[serde(default)]
+    pub metadata_visibility: MetadataVisibilityMap,
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 }
@@ -66,6 +144,18 @@ pub struct RemoteNode {
     pub metadata: HashMap<String, String>,
     /// Connection state
     pub connection_state: ConnectionState,
+    /// Smoothed round-trip latency in milliseconds, if measured
+    #[serde(default)]
+    pub avg_latency_ms: Option<f64>,
+    /// Whether the node is quarantined
+    #[serde(default)]
+    pub quarantined: bool,
+    /// Fingerprint of the signing key trusted for this node, if known
+    #[serde(default)]
+    pub public_key_fingerprint: Option<String>,
+    /// Organization from the node's last announcement signed with its trusted key
+    #[serde(default)]
+    pub verified_organization: Option<String>,
 }
 
 /// Connection state for remote nodes
@@ -98,6 +188,23 @@ pub struct MeshConfig {
     pub zenoh_config: Option<Config>,
     /// Custom configuration for extensions
     pub custom_config: HashMap<String, serde_json::Value>,
+    /// Window over which node table changes are merged into one topology diff
+    #[serde(default = "default_topology_diff_debounce")]
+    pub topology_diff_debounce: std::time::Duration,
+    /// How long restricted metadata fetched from other nodes is cached
+    #[serde(default = "default_restricted_metadata_ttl")]
+    pub restricted_metadata_ttl: std::time::Duration,
+    /// Whether new nodes need a gatekeeper's approval
+    #[serde(default)]
+    pub admission: AdmissionConfig,
+}
+
+fn default_topology_diff_debounce() -> std::time::Duration {
+    std::time::Duration::from_millis(500)
+}
+
+fn default_restricted_metadata_ttl() -> std::time::Duration {
+    std::time::Duration::from_secs(300)
 }
 
 impl Default for MeshConfig {
@@ -109,10 +216,96 @@ impl Default for MeshConfig {
             auto_reconnect: true,
             zenoh_config: None,
             custom_config: HashMap::new(),
+            topology_diff_debounce: default_topology_diff_debounce(),
+            restricted_metadata_ttl: default_restricted_metadata_ttl(),
+            admission: AdmissionConfig::default(),
         }
     }
 }
 
+impl MeshConfig {
+    /// Load a configuration from a YAML file
+    ///
+    /// Omitted keys keep their defaults; unknown keys are rejected.
+    pub fn from_yaml(path: &Path) -> Result<Self, MeshError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            MeshError::ConfigurationError(format!("failed to read {}: {}", path.display(), e))
+        })?;
+        Self::parse_yaml(&text)
+    }
+    
+    /// Parse a YAML configuration, overlaying it on the defaults
+    pub fn parse_yaml(text: &str) -> Result<Self, MeshError> {
+        let invalid = |e: &dyn std::fmt::Display| MeshError::ConfigurationError(e.to_string());
+        let overrides: serde_yaml::Mapping = match serde_yaml::from_str(text).map_err(|e| invalid(&e))? {
+            serde_yaml::Value::Mapping(map) => map,
+            serde_yaml::Value::Null => serde_yaml::Mapping::new(),
+            _ => return Err(MeshError::ConfigurationError("expected a mapping of keys".to_string())),
+        };
+        
+        let serde_yaml::Value::Mapping(mut merged) = serde_yaml::to_value(Self::default()).map_err(|e| invalid(&e))? else {
+            unreachable!("MeshConfig serializes to a mapping");
+        };
+        for (key, value) in overrides {
+            if !merged.contains_key(&key) {
+                let name = key.as_str().map(str::to_string).unwrap_or_else(|| format!("{:?}", key));
+                return Err(MeshError::ConfigurationError(format!("unknown key: {}", name)));
+            }
+            merged.insert(key, value);
+        }
+        serde_yaml::from_value(serde_yaml::Value::Mapping(merged)).map_err(|e| invalid(&e))
+    }
+    
+    /// Configuration from `{PREFIX}_*` environment variables over the defaults
+    ///
+    /// Reads `DISCOVERY_INTERVAL`, `MAX_NODES`, `CONNECTION_TIMEOUT`,
+    /// `AUTO_RECONNECT`, `TOPOLOGY_DIFF_DEBOUNCE_MS` and
+    /// `RESTRICTED_METADATA_TTL_SECS`. Unparseable values are logged and ignored.
+    pub fn from_env_prefix(prefix: &str) -> Self {
+        Self::from_vars(prefix, |name| std::env::var(name).ok())
+    }
+    
+    fn from_vars(prefix: &str, var: impl Fn(&str) -> Option<String>) -> Self {
+        fn read<T: std::str::FromStr>(prefix: &str, key: &str, var: &impl Fn(&str) -> Option<String>) -> Option<T> {
+            let name = format!("{}_{}", prefix, key);
+            let value = var(&name)?;
+            match value.trim().parse() {
+                Ok(parsed) => Some(parsed),
+                Err(_) => {
+                    warn!("Ignoring {}: cannot parse {:?}", name, value);
+                    None
+                }
+            }
+        }
+        
+        let mut config = Self::default();
+        if let Some(value) = read(prefix, "DISCOVERY_INTERVAL", &var) {
+            config.discovery_interval = value;
+        }
+        if let Some(value) = read(prefix, "MAX_NODES", &var) {
+            config.max_nodes = value;
+        }
+        if let Some(value) = read(prefix, "CONNECTION_TIMEOUT", &var) {
+            config.connection_timeout = value;
+        }
+        if let Some(value) = read(prefix, "AUTO_RECONNECT", &var) {
+            config.auto_reconnect = value;
+        }
+        if let Some(ms) = read(prefix, "TOPOLOGY_DIFF_DEBOUNCE_MS", &var) {
+            config.topology_diff_debounce = std::time::Duration::from_millis(ms);
+        }
+        if let Some(secs) = read(prefix, "RESTRICTED_METADATA_TTL_SECS", &var) {
+            config.restricted_metadata_ttl = std::time::Duration::from_secs(secs);
+        }
+        config
+    }
+    
+    /// The configuration as YAML, readable by [`MeshConfig::parse_yaml`]
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("MeshConfig serializes to YAML")
+    }
+}
+
 /// Mesh state
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MeshState {
@@ -163,6 +356,50 @@ pub enum MeshEvent {
     },
 }
 
+impl MeshEvent {
+    /// Variant name, used as the event log subcategory
+    fn kind(&self) -> &'static str {
+        match self {
+            MeshEvent::NodeJoined { .. } => "node_joined",
+            MeshEvent::NodeLeft { .. } => "node_left",
+            MeshEvent::ConnectionStateChanged { .. } => "connection_state_changed",
+            MeshEvent::TopologyChanged { .. } => "topology_changed",
+        }
+    }
+
+    fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            MeshEvent::NodeJoined { timestamp, .. }
+            | MeshEvent::NodeLeft { timestamp, .. }
+            | MeshEvent::ConnectionStateChanged { timestamp, .. }
+            | MeshEvent::TopologyChanged { timestamp, .. } => *timestamp,
+        }
+    }
+
+    /// Wrap this event in the record format written by [`EventLogger`]
+    ///
+    /// The original event is kept whole under the `mesh_event` payload key.
+    pub fn to_log_event(&self, source_node: Uuid) -> Result<super::events::MeshEvent> {
+        use super::events::{EventPayload, EventPriority, EventType};
+
+        let data = HashMap::from([("mesh_event".to_string(), serde_json::to_value(self)?)]);
+        Ok(super::events::MeshEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: self.timestamp(),
+            source_node,
+            event_type: EventType::Generic {
+                category: "mesh".to_string(),
+                subcategory: Some(self.kind().to_string()),
+            },
+            payload: EventPayload::Generic { data },
+            metadata: HashMap::new(),
+            propagation_path: vec![source_node],
+            correlation_id: None,
+            priority: EventPriority::Normal,
+        })
+    }
+}
+
 /// Types of topology changes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TopologyChangeType {
@@ -206,6 +443,7 @@ impl MeshManager {
         
         // Create local node
         let local_node = LocalNode::new();
+        let local_id = local_node.id;
         
         // Initialize discovery
         let discovery = MeshDiscovery::new(
@@ -214,12 +452,33 @@ impl MeshManager {
             None, // Use default discovery config
         );
         
+        let nodes = Arc::new(RwLock::new(HashMap::new()));
+        let topology_diffs = TopologyDiffPublisher::spawn(nodes.clone(), config.topology_diff_debounce).await;
+        let metadata_ttl = chrono::Duration::from_std(config.restricted_metadata_ttl)
+            .map_err(|e| MeshError::Generic(format!("Invalid restricted metadata TTL: {}", e)))?;
+        
+        let admission = AdmissionRegistry::new(config.admission.clone());
+        
         Ok(Self {
             session,
             local_node,
-            nodes: Arc::new(RwLock::new(HashMap::new())),
+            nodes,
             discovery,
             config,
+            resources: Arc::new(RwLock::new(HashMap::new())),
+            topology_diffs,
+            metadata_cache: RwLock::new(MetadataCache::new(metadata_ttl)),
+            metadata_client: None,
+            decommission_peers: None,
+            retired_nodes: Arc::new(RwLock::new(HashSet::new())),
+            event_logger: std::sync::Mutex::new(None),
+            admission,
+            admission_peers: None,
+            admission_requests: broadcast::channel(64).0,
+            dependencies: RwLock::new(DependencyGraph::new(local_id)),
+            dependency_peers: None,
+            dependency_events: broadcast::channel(64).0,
+            publication: None,
             state: MeshState::Stopped,
         })
     }
@@ -257,17 +516,28 @@ impl MeshManager {
     }
     
     /// Get current mesh metrics
+    ///
+    /// Lost and failed nodes are not counted as active; the response time is
+    /// the mean measured latency of connected nodes, or 0.0 if none is known.
     pub async fn get_metrics(&self) -> Result<MeshMetrics> {
         let nodes = self.nodes.read().await;
-        let active_nodes = nodes.len() + 1; // +1 for local node
-        let connected_nodes = nodes.values()
+        let active_nodes = nodes.values()
+            .filter(|node| !matches!(node.connection_state, ConnectionState::Lost { .. } | ConnectionState::Failed { .. }))
+            .count() + 1; // +1 for local node
+        let connected: Vec<&RemoteNode> = nodes.values()
             .filter(|node| node.connection_state == ConnectionState::Connected)
-            .count();
+            .collect();
+        let latencies: Vec<f64> = connected.iter().filter_map(|node| node.avg_latency_ms).collect();
+        let avg_response_time = if latencies.is_empty() {
+            0.0
+        } else {
+            latencies.iter().sum::<f64>() / latencies.len() as f64
+        };
         
         Ok(MeshMetrics {
             active_nodes,
-            connected_nodes,
-            avg_response_time: 50.0, // Would be calculated from actual metrics
+            connected_nodes: connected.len(),
+            avg_response_time,
             is_partitioned: false, // Would be determined from network analysis
             last_update: Utc::now(),
         })
@@ -286,10 +556,36 @@ impl MeshManager {
     }
     
     /// Add a new node to the mesh
+    ///
+    /// Where admission is required, a node not yet approved is added as
+    /// pending and an admission request goes to the gatekeepers.
     pub async fn add_node(&self, node: RemoteNode) -> Result<()> {
+        if self.is_retired(&node.id).await {
+            return Err(MeshError::NodeError(format!("Node {} is retired", node.id)).into());
+        }
+        if self.admission.is_rejected(&node.id) {
+            return Err(MeshError::NodeError(format!("Node {} was refused admission", node.id)).into());
+        }
         info!("Adding node to mesh: {}", node.id);
+        let request = self.admission.mark_pending(node.id).then(|| AdmissionRequest {
+            node_id: node.id,
+            capabilities: node.capabilities.clone(),
+            observed_by: self.local_node.id,
+            requested_at: Utc::now(),
+        });
         let mut nodes = self.nodes.write().await;
         nodes.insert(node.id, node);
+        self.topology_diffs.notify();
+        drop(nodes);
+        
+        if let Some(request) = request {
+            self.receive_admission_request(request.clone());
+            if let Some(peers) = &self.admission_peers {
+                if let Err(e) = peers.request_admission(&request).await {
+                    warn!("Failed to request admission for {}: {}", request.node_id, e);
+                }
+            }
+        }
         Ok(())
     }
     
@@ -297,7 +593,9 @@ impl MeshManager {
     pub async fn remove_node(&self, node_id: &Uuid) -> Result<()> {
         info!("Removing node from mesh: {}", node_id);
         let mut nodes = self.nodes.write().await;
-        nodes.remove(node_id);
+        if nodes.remove(node_id).is_some() {
+            self.topology_diffs.notify();
+        }
         Ok(())
     }
     
@@ -311,6 +609,9 @@ impl MeshManager {
         if let Some(node) = nodes.get_mut(node_id) {
             let old_state = node.connection_state.clone();
             node.connection_state = new_state.clone();
+            if old_state != new_state {
+                self.topology_diffs.notify();
+            }
             
             debug!(
                 "Node {} connection state changed: {:?} -> {:?}",
@@ -320,6 +621,509 @@ impl MeshManager {
         Ok(())
     }
     
+    /// Store a resource, replacing any with the same ID
+    pub async fn add_resource(&self, resource: MeshResource) {
+        let mut resources = self.resources.write().await;
+        resources.insert(resource.id.clone(), resource);
+    }
+    
+    /// Get a resource by ID
+    pub async fn get_resource(&self, resource_id: &str) -> Option<MeshResource> {
+        let resources = self.resources.read().await;
+        resources.get(resource_id).cloned()
+    }
+    
+    /// Fork a stored resource, attributing the changes to `modifier_attribution`
+    pub async fn fork_resource(&self, source_id: &str, modifier_attribution: Attribution) -> Result<MeshResource> {
+        let mut resources = self.resources.write().await;
+        let source = resources.get(source_id)
+            .ok_or_else(|| MeshError::Generic(format!("Resource not found: {}", source_id)))?;
+        
+        let fork = source.fork(Uuid::new_v4().to_string(), modifier_attribution);
+        resources.insert(fork.id.clone(), fork.clone());
+        debug!("Forked resource {} into {}", source_id, fork.id);
+        Ok(fork)
+    }
+    
+    /// Gate discovery and publication on `workflow`
+    ///
+    /// Resources in the workflow stay out of discovery and cannot be
+    /// published until the workflow has published them.
+    pub fn set_publication_workflow(&mut self, workflow: Arc<RwLock<dyn PublicationGate>>) {
+        self.publication = Some(workflow);
+    }
+    
+    /// Whether the publication workflow, if any, lets `resource_id` be exposed
+    async fn is_publishable(&self, resource_id: &str) -> bool {
+        let Some(workflow) = &self.publication else {
+            return true;
+        };
+        let state = workflow.read().await.publication_state(resource_id);
+        state.is_none_or(|state| state == PublicationState::Published)
+    }
+    
+    /// Resources `requester` may discover on this node
+    ///
+    /// Nodes awaiting admission discover nothing; admitted nodes see every
+    /// resource that is not private and not still in review.
+    pub async fn discover_resources(&self, requester: &Uuid) -> Vec<MeshResource> {
+        if !self.admission.is_admitted(requester) {
+            return Vec::new();
+        }
+        let candidates: Vec<MeshResource> = self.resources.read().await.values()
+            .filter(|resource| !matches!(resource.access_control.visibility, VisibilityLevel::Private))
+            .cloned()
+            .collect();
+        let mut resources = Vec::with_capacity(candidates.len());
+        for resource in candidates {
+            if self.is_publishable(&resource.id).await {
+                resources.push(resource);
+            }
+        }
+        resources.sort_by(|a, b| a.id.cmp(&b.id));
+        resources
+    }
+    
+    /// Ancestry of a resource through its fork links, starting with the resource itself
+    pub async fn lineage(&self, resource_id: &str) -> Vec<MeshResource> {
+        let resources = self.resources.read().await;
+        MeshResource::lineage_in(&resources, resource_id)
+    }
+    
+    /// Record a latency sample for a known node
+    pub async fn record_peer_latency(&self, node_id: &Uuid, sample_ms: f64) {
+        let mut nodes = self.nodes.write().await;
+        if let Some(node) = nodes.get_mut(node_id) {
+            node.record_latency(sample_ms);
+        }
+    }
+    
+    /// Mark a known node as quarantined or release it
+    pub async fn set_node_quarantined(&self, node_id: &Uuid, quarantined: bool) -> Result<()> {
+        let mut nodes = self.nodes.write().await;
+        let node = nodes.get_mut(node_id)
+            .ok_or_else(|| MeshError::NodeError(format!("Unknown node: {}", node_id)))?;
+        node.quarantined = quarantined;
+        Ok(())
+    }
+    
+    /// Use `client` for direct metadata queries to other nodes
+    pub fn set_metadata_client(&mut self, client: Arc<dyn MetadataQueryClient>) {
+        self.metadata_client = Some(client);
+    }
+    
+    /// Answer a metadata query about this node
+    ///
+    /// The requester is judged by its entry in the local node table, and
+    /// only if the query is signed with the key trusted for it; otherwise
+    /// it is answered as a stranger.
+    pub async fn answer_metadata_query(&self, signed: &SignedMetadataQuery) -> MetadataResponse {
+        let query = &signed.query;
+        if !self.admission.is_admitted(&query.requester) {
+            // Nodes awaiting admission learn nothing about this one
+            let denied = query.keys.iter().filter(|key| self.local_node.get_metadata(key).is_some()).cloned().collect();
+            return MetadataResponse { node_id: self.local_node.id, entries: HashMap::new(), denied };
+        }
+        let nodes = self.nodes.read().await;
+        answer_metadata_query(&self.local_node, signed.authenticated_requester(&nodes), query)
+    }
+    
+    /// Answer metadata queries sent to this node over Zenoh until the task is aborted
+    pub async fn serve_metadata_queries(self: &Arc<Self>) -> Result<tokio::task::JoinHandle<()>> {
+        let queryable = self.session
+            .declare_queryable(metadata_query_key(&self.local_node.id))
+            .await
+            .map_err(|e| MeshError::ZenohError(format!("Failed to serve metadata queries: {}", e)))?;
+        let manager = self.clone();
+        Ok(tokio::spawn(async move {
+            while let Ok(query) = queryable.recv_async().await {
+                let signed = query.payload()
+                    .and_then(|payload| serde_json::from_slice::<SignedMetadataQuery>(&payload.to_bytes()).ok());
+                let Some(signed) = signed else {
+                    debug!("Ignored malformed metadata query on {}", query.key_expr());
+                    continue;
+                };
+                let response = manager.answer_metadata_query(&signed).await;
+                let payload = match serde_json::to_vec(&response) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        warn!("Failed to encode metadata answer: {}", e);
+                        continue;
+                    }
+                };
+                if let Err(e) = query.reply(query.key_expr().clone(), payload).await {
+                    warn!("Failed to answer metadata query from {}: {}", signed.query.requester, e);
+                }
+            }
+        }))
+    }
+    
+    /// Record the organization a known node announced, if the announcement is signed with its trusted key
+    ///
+    /// Only organizations recorded this way count for Organization-tier metadata.
+    pub async fn apply_signed_announcement(&self, signed: &SignedAnnouncement) -> Result<bool> {
+        let mut nodes = self.nodes.write().await;
+        let node_id = serde_json::from_slice::<AnnouncementBody>(&signed.body)
+            .map_err(|e| MeshError::Generic(format!("Malformed announcement: {}", e)))?
+            .announcement
+            .node_id;
+        let node = nodes.get_mut(&node_id)
+            .ok_or_else(|| MeshError::NodeError(format!("Unknown node: {}", node_id)))?;
+        Ok(node.apply_signed_announcement(signed))
+    }
+    
+    /// Metadata entries of another node, including restricted ones this node is entitled to
+    ///
+    /// Keys from the node's announcement or the cache are served locally;
+    /// the rest are queried directly. Denied keys are listed in the response.
+    pub async fn get_node_metadata(&self, node_id: &Uuid, keys: &[String]) -> Result<MetadataResponse> {
+        let node = self.nodes.read().await.get(node_id).cloned()
+            .ok_or_else(|| MeshError::Generic(format!("Unknown node: {}", node_id)))?;
+        let client = self.metadata_client.as_ref()
+            .ok_or_else(|| MeshError::Generic("No metadata query client configured".to_string()))?;
+        fetch_node_metadata(&self.metadata_cache, client.as_ref(), self.local_node.id, &node, keys, Utc::now()).await
+    }
+    
+    /// Use `peers` to reach the mesh while decommissioning this node
+    pub fn set_decommission_peers(&mut self, peers: Arc<dyn DecommissionPeers>) {
+        self.decommission_peers = Some(peers);
+    }
+    
+    /// Retire this node for good
+    ///
+    /// Offers each resource this node owns to its successor, hands the groups
+    /// in `targets.group_admins` to their new administrators, then publishes a
+    /// notice signed with `keys`. Progress is saved to `targets.progress_path`
+    /// after every step, and a run with the same file resumes from it. Errors
+    /// reaching peers end the run early without losing completed steps.
+    pub async fn decommission(
+        &self,
+        targets: DecommissionTargets,
+        keys: &NodeKeys,
+        groups: Option<&mut BasicGroupCommunication>,
+    ) -> Result<DecommissionReport> {
+        let peers = self.decommission_peers.clone()
+            .ok_or_else(|| MeshError::Generic("No decommission peers configured".to_string()))?;
+        let node_id = self.local_node.id;
+        let path = targets.progress_path.as_deref();
+        let saved = match path {
+            Some(path) => DecommissionProgress::load(path)?,
+            None => None,
+        };
+        let resumed = saved.is_some();
+        let mut progress = match saved {
+            Some(progress) if progress.node_id != node_id => {
+                return Err(MeshError::ConfigurationError(format!(
+                    "Progress file belongs to node {}", progress.node_id
+                )).into());
+            }
+            Some(progress) => progress,
+            None => DecommissionProgress::new(node_id),
+        };
+        info!("Decommissioning node {} (retirement {})", node_id, progress.retirement_id);
+        
+        // Resources, including ones transferred before an interruption
+        let owner = node_id.to_string();
+        let mut owned: Vec<MeshResource> = self.resources.read().await.values()
+            .filter(|r| r.access_control.owner == owner || progress.transferred.contains_key(&r.id))
+            .cloned()
+            .collect();
+        owned.sort_by(|a, b| a.id.cmp(&b.id));
+        for resource in owned {
+            let successor = match progress.transferred.get(&resource.id) {
+                Some(successor) => *successor,
+                None => {
+                    let Some(successor) = targets.successor_for(&resource.id) else {
+                        progress.unassigned.insert(resource.id.clone());
+                        continue;
+                    };
+                    let offer = TransferOffer {
+                        retirement_id: progress.retirement_id,
+                        from_node: node_id,
+                        successor,
+                        resource: resource.clone(),
+                    };
+                    if !peers.offer_resource(&offer).await? {
+                        debug!("Successor {} declined resource {}", successor, resource.id);
+                        progress.declined.insert(resource.id.clone(), successor);
+                        progress.checkpoint(path)?;
+                        continue;
+                    }
+                    progress.declined.remove(&resource.id);
+                    progress.transferred.insert(resource.id.clone(), successor);
+                    progress.checkpoint(path)?;
+                    successor
+                }
+            };
+            if let Some(stored) = self.resources.write().await.get_mut(&resource.id) {
+                stored.access_control.owner = successor.to_string();
+                stored.modified_at = Utc::now();
+            }
+        }
+        
+        // Group administrator roles
+        let mut group_targets: Vec<_> = targets.group_admins.iter()
+            .filter(|(group, _)| !progress.groups_handed_off.contains_key(*group))
+            .collect();
+        group_targets.sort();
+        let mut groups = groups;
+        for (group, successor) in group_targets {
+            let Some(groups) = groups.as_deref_mut() else {
+                progress.failed_groups.insert(group.clone(), "no group communication provided".to_string());
+                continue;
+            };
+            let group_id = GroupId::new(group);
+            match groups.hand_off_administration(&group_id, successor, Utc::now()) {
+                Ok(deltas) => {
+                    progress.failed_groups.remove(group);
+                    progress.groups_handed_off.insert(group.clone(), successor.clone());
+                    progress.checkpoint(path)?;
+                    for delta in &deltas {
+                        peers.broadcast_membership(&group_id, delta).await?;
+                    }
+                }
+                Err(e) => {
+                    warn!("Could not hand off group {}: {}", group, e);
+                    progress.failed_groups.insert(group.clone(), e.to_string());
+                    progress.checkpoint(path)?;
+                }
+            }
+        }
+        
+        // Final departure; peers treat a repeated notice as a no-op
+        let notice = progress.notice.clone().unwrap_or_else(|| {
+            RetirementNotice::sign(progress.retirement_id, node_id, Utc::now(), keys)
+        });
+        peers.publish_retirement(&notice).await?;
+        progress.notice = Some(notice.clone());
+        progress.checkpoint(path)?;
+        self.retired_nodes.write().await.insert(node_id);
+        
+        info!("Node {} retired", node_id);
+        Ok(DecommissionReport::from_progress(progress, notice, resumed))
+    }
+    
+    /// Take over a resource offered by a retiring node
+    ///
+    /// Accepts offers naming this node as successor for resources the
+    /// offering node owns, storing the resource with this node as owner.
+    /// The return value is the accept message.
+    pub async fn accept_resource_transfer(&self, offer: &TransferOffer) -> bool {
+        if offer.successor != self.local_node.id
+            || offer.resource.access_control.owner != offer.from_node.to_string()
+            || self.is_retired(&offer.from_node).await
+            || !self.admission.is_admitted(&offer.from_node)
+        {
+            return false;
+        }
+        let mut resource = offer.resource.clone();
+        resource.access_control.owner = self.local_node.id.to_string();
+        resource.modified_at = Utc::now();
+        self.add_resource(resource).await;
+        true
+    }
+    
+    /// Delete a node that announced its retirement and refuse it from now on
+    ///
+    /// The notice must be signed with the key on file for the node. Returns
+    /// whether the node was newly retired.
+    pub async fn apply_retirement(&self, notice: &RetirementNotice) -> Result<bool> {
+        if self.is_retired(&notice.node_id).await {
+            return Ok(false);
+        }
+        let key_trusted = self.get_node(&notice.node_id).await
+            .and_then(|node| node.public_key_fingerprint)
+            .is_some_and(|known| known == notice.key_fingerprint());
+        if !key_trusted || !notice.verify_signature() {
+            return Err(MeshError::NodeError(format!("Invalid retirement notice for {}", notice.node_id)).into());
+        }
+        let newly_retired = self.retired_nodes.write().await.insert(notice.node_id);
+        self.remove_node(&notice.node_id).await?;
+        Ok(newly_retired)
+    }
+    
+    /// Whether a node has retired from the mesh
+    pub async fn is_retired(&self, node_id: &Uuid) -> bool {
+        self.retired_nodes.read().await.contains(node_id)
+    }
+    
+    /// Use `peers` to reach gatekeepers and peers with admission traffic
+    pub fn set_admission_peers(&mut self, peers: Arc<dyn AdmissionPeers>) {
+        self.admission_peers = Some(peers);
+    }
+    
+    /// Admission state shared with the security system and group communication
+    pub fn admission_registry(&self) -> AdmissionRegistry {
+        self.admission.clone()
+    }
+    
+    /// Admission status of a node, if it needed admission
+    pub fn admission_status(&self, node_id: &Uuid) -> Option<AdmissionStatus> {
+        self.admission.status(node_id)
+    }
+    
+    /// Whether a node is a full member of the mesh
+    pub fn is_admitted(&self, node_id: &Uuid) -> bool {
+        self.admission.is_admitted(node_id)
+    }
+    
+    /// Known nodes waiting for a gatekeeper's decision
+    pub fn pending_admissions(&self) -> Vec<Uuid> {
+        self.admission.pending()
+    }
+    
+    /// Admit this node as the first of a new mesh
+    ///
+    /// Applies only while no other node is known; see
+    /// [`AdmissionRegistry::bootstrap`]. Returns whether the node bootstrapped.
+    pub async fn bootstrap_admission(&self, keys: &NodeKeys) -> bool {
+        let known_nodes = self.nodes.read().await.len();
+        let bootstrapped = self.admission.bootstrap(self.local_node.id, known_nodes, keys);
+        if bootstrapped {
+            info!("Node {} bootstrapped a mesh requiring admission", self.local_node.id);
+        }
+        bootstrapped
+    }
+    
+    /// Admission requests received from now on, while this node is a gatekeeper
+    pub fn subscribe_admission_requests(&self) -> broadcast::Receiver<AdmissionRequest> {
+        self.admission_requests.subscribe()
+    }
+    
+    /// Take in an admission request; only gatekeepers pass it on to subscribers
+    pub fn receive_admission_request(&self, request: AdmissionRequest) {
+        if !self.admission.is_gatekeeper(&self.local_node.id) || self.admission.is_admitted(&request.node_id) {
+            return;
+        }
+        self.admission.mark_pending(request.node_id);
+        // No receivers is not an error; nobody reviews requests yet
+        let _ = self.admission_requests.send(request);
+    }
+    
+    /// Approve or reject a node as a gatekeeper, and broadcast the signed decision
+    pub async fn decide_admission(
+        &self,
+        node_id: Uuid,
+        approve: bool,
+        reason: Option<String>,
+        keys: &NodeKeys,
+    ) -> Result<AdmissionDecision> {
+        if !self.admission.is_gatekeeper(&self.local_node.id) {
+            return Err(MeshError::NodeError(format!("Node {} is not a gatekeeper", self.local_node.id)).into());
+        }
+        let decision = AdmissionDecision::sign(node_id, approve, reason, self.local_node.id, keys);
+        self.apply_admission_decision(&decision).await?;
+        if let Some(peers) = &self.admission_peers {
+            peers.publish_decision(&decision).await?;
+        }
+        Ok(decision)
+    }
+    
+    /// Honor a gatekeeper's decision, dropping rejected nodes
+    ///
+    /// Returns whether the decision changed the node's status.
+    pub async fn apply_admission_decision(&self, decision: &AdmissionDecision) -> Result<bool> {
+        let changed = self.admission.apply_decision(decision)?;
+        if !decision.approved {
+            self.remove_node(&decision.node_id).await?;
+        }
+        if changed {
+            info!(
+                "Node {} {} by gatekeeper {}",
+                decision.node_id,
+                if decision.approved { "admitted" } else { "refused" },
+                decision.decided_by,
+            );
+        }
+        Ok(changed)
+    }
+    
+    /// Use `peers` to announce this node's resource dependencies
+    pub fn set_dependency_peers(&mut self, peers: Arc<dyn DependencyPeers>) {
+        self.dependency_peers = Some(peers);
+    }
+    
+    /// Store a resource, declare its version and dependencies, and announce them
+    ///
+    /// A declaration that would close a dependency cycle is rejected before
+    /// anything is stored, as is a resource the publication workflow has
+    /// not published yet. Owners of dependents the new version breaks are
+    /// told as they apply the announcement.
+    pub async fn publish_resource(&self, resource: MeshResource) -> Result<()> {
+        if !self.is_publishable(&resource.id).await {
+            return Err(MeshError::Generic(format!("Resource still in review: {}", resource.id)).into());
+        }
+        let (broken, announcement) = {
+            let mut graph = self.dependencies.write().await;
+            let broken = graph.declare_resource(&resource)?;
+            (broken, graph.announcement())
+        };
+        self.add_resource(resource).await;
+        self.publish_dependency_events(broken);
+        if let Some(peers) = &self.dependency_peers {
+            peers.announce_dependencies(&announcement).await?;
+        }
+        Ok(())
+    }
+    
+    /// Merge another node's dependency announcement
+    ///
+    /// DependencyBroken events for resources this node owns go to
+    /// [`Self::subscribe_dependency_events`] and the event log.
+    pub async fn apply_dependency_announcement(&self, announcement: &DependencyAnnouncement) {
+        let broken = self.dependencies.write().await.merge(announcement);
+        self.publish_dependency_events(broken);
+    }
+    
+    /// DependencyBroken events for this node's resources, from now on
+    pub fn subscribe_dependency_events(&self) -> broadcast::Receiver<super::events::MeshEvent> {
+        self.dependency_events.subscribe()
+    }
+    
+    /// Resources a change to `resource_id` would affect, nearest first
+    pub async fn get_impacted(&self, resource_id: &str) -> Vec<ImpactedResource> {
+        self.dependencies.read().await.get_impacted(resource_id)
+    }
+    
+    /// Declared dependencies of `resource_id`, with broken ones flagged
+    pub async fn dependency_status(&self, resource_id: &str) -> Vec<DependencyStatus> {
+        self.dependencies.read().await.dependencies_of(resource_id)
+    }
+    
+    /// Publish the events addressed to this node; other owners raise their own
+    fn publish_dependency_events(&self, events: Vec<super::events::MeshEvent>) {
+        for event in events {
+            let addressed_here = matches!(
+                &event.payload,
+                super::events::EventPayload::Resource { affected_nodes, .. } if affected_nodes.contains(&self.local_node.id)
+            );
+            if !addressed_here {
+                continue;
+            }
+            if let Some(logger) = self.event_logger() {
+                if let Err(e) = logger.log(&event) {
+                    warn!("Failed to log dependency event {}: {}", event.event_id, e);
+                }
+            }
+            // No subscribers is not an error
+            let _ = self.dependency_events.send(event);
+        }
+    }
+    
+    /// Stream of debounced changes to the known nodes
+    ///
+    /// Each call returns an independent subscriber that sees diffs published
+    /// after it subscribed. Changes within `MeshConfig::topology_diff_debounce`
+    /// of each other arrive as one diff.
+    pub fn topology_diff_stream(&self) -> impl futures::Stream<Item = TopologyDiff> {
+        self.topology_diffs.subscribe()
+    }
+    
+    /// Export the mesh topology in the given format
+    pub async fn export_topology(&self, format: TopologyGraphFormat, filter: &TopologyFilter) -> Result<String> {
+        self.topology(filter).await.render(format)
+    }
+    
     /// Get mesh state
     pub fn get_state(&self) -> &MeshState {
         &self.state
@@ -344,9 +1148,29 @@ impl MeshManager {
         Ok(())
     }
     
+    /// Start appending mesh events to the newline-delimited JSON file at `path`
+    ///
+    /// An existing log is continued, and every event passed to
+    /// [`Self::broadcast_event`] is appended. Starting again replaces the
+    /// logger returned by [`Self::event_logger`].
+    pub fn start_event_logging(&self, path: &Path) -> Result<EventLogger> {
+        let logger = EventLogger::open(path)?;
+        *self.event_logger.lock().unwrap() = Some(logger.clone());
+        info!("Logging mesh events to {}", path.display());
+        Ok(logger)
+    }
+    
+    /// Event log started with [`Self::start_event_logging`], if any
+    pub fn event_logger(&self) -> Option<EventLogger> {
+        self.event_logger.lock().unwrap().clone()
+    }
+    
     /// Broadcast an event to the mesh
     pub async fn broadcast_event(&self, event: MeshEvent) -> Result<()> {
         debug!("Broadcasting mesh event: {:?}", event);
+        if let Some(logger) = self.event_logger() {
+            logger.log(&event.to_log_event(self.local_node.id)?)?;
+        }
         // Implementation would serialize and broadcast the event
         Ok(())
     }
@@ -359,6 +1183,7 @@ impl LocalNode {
             id: Uuid::new_v4(),
             capabilities: NodeCapabilities::default(),
             metadata: HashMap::new(),
+            metadata_visibility: MetadataVisibilityMap::default(),
             created_at: Utc::now(),
         }
     }
@@ -369,6 +1194,7 @@ impl LocalNode {
             id: Uuid::new_v4(),
             capabilities,
             metadata: HashMap::new(),
+            metadata_visibility: MetadataVisibilityMap::default(),
             created_at: Utc::now(),
         }
     }
@@ -382,6 +1208,22 @@ impl LocalNode {
     pub fn get_metadata(&self, key: &str) -> Option<&String> {
         self.metadata.get(key)
     }
+    
+    /// Set metadata restricted to a visibility tier
+    pub fn set_classified_metadata(&mut self, key: String, value: String, visibility: MetadataVisibility) {
+        self.metadata_visibility.set(&key, visibility);
+        self.metadata.insert(key, value);
+    }
+    
+    /// Visibility tier of a metadata entry
+    pub fn visibility_of(&self, key: &str) -> MetadataVisibility {
+        self.metadata_visibility.get(key)
+    }
+    
+    /// Metadata to include in announcements
+    pub fn announced_metadata(&self) -> HashMap<String, String> {
+        self.metadata_visibility.public_metadata(&self.metadata)
+    }
 }
 
 impl RemoteNode {
@@ -398,9 +1240,48 @@ impl RemoteNode {
             last_seen: Utc::now(),
             metadata: HashMap::new(),
             connection_state: ConnectionState::Disconnected,
+            avg_latency_ms: None,
+            quarantined: false,
+            public_key_fingerprint: None,
+            verified_organization: None,
         }
     }
     
+    /// Trust `public_key` as this node's signing key
+    pub fn with_public_key(mut self, public_key: &[u8]) -> Self {
+        self.public_key_fingerprint = Some(key_fingerprint(public_key));
+        self
+    }
+    
+    /// Take the organization from an announcement signed with this node's trusted key
+    ///
+    /// Returns false, leaving the node unchanged, if the announcement is not
+    /// about this node or not signed with its trusted key.
+    pub fn apply_signed_announcement(&mut self, signed: &SignedAnnouncement) -> bool {
+        if self.public_key_fingerprint.as_deref() != Some(key_fingerprint(&signed.public_key).as_str()) {
+            return false;
+        }
+        if UnparsedPublicKey::new(&ED25519, &signed.public_key).verify(&signed.body, &signed.signature).is_err() {
+            return false;
+        }
+        let Ok(body) = serde_json::from_slice::<AnnouncementBody>(&signed.body) else {
+            return false;
+        };
+        if body.announcement.node_id != self.id {
+            return false;
+        }
+        self.verified_organization = body.announcement.node_info.metadata.get(ORGANIZATION_METADATA_KEY).cloned();
+        true
+    }
+    
+    /// Fold a latency sample into the smoothed average
+    pub fn record_latency(&mut self, sample_ms: f64) {
+        self.avg_latency_ms = Some(match self.avg_latency_ms {
+            Some(avg) => avg * (1.0 - LATENCY_SMOOTHING) + sample_ms * LATENCY_SMOOTHING,
+            None => sample_ms,
+        });
+    }
+    
     /// Update the last seen timestamp
     pub fn update_last_seen(&mut self) {
         self.last_seen = Utc::now();
@@ -429,10 +1310,57 @@ impl RemoteNode {
     }
 }
 
+#[async_trait::async_trait]
+impl TopologySource for MeshManager {
+    async fn topology(&self, filter: &TopologyFilter) -> TopologyGraph {
+        let remote: Vec<RemoteNode> = self.nodes.read().await.values().cloned().collect();
+        TopologyGraph::build(&self.local_node, &remote, &self.discovery.get_all_nodes(), filter)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mesh_config_yaml() {
+        let config = MeshConfig::parse_yaml("discovery_interval: 5\nauto_reconnect: false\n").unwrap();
+        assert_eq!(config.discovery_interval, 5);
+        assert!(!config.auto_reconnect);
+        assert_eq!(config.max_nodes, MeshConfig::default().max_nodes);
+        
+        let round_trip = MeshConfig::parse_yaml(&config.to_yaml()).unwrap();
+        assert_eq!(round_trip.to_yaml(), config.to_yaml());
+        
+        match MeshConfig::parse_yaml("max_nodez: 3\n") {
+            Err(MeshError::ConfigurationError(message)) => assert_eq!(message, "unknown key: max_nodez"),
+            other => panic!("expected a configuration error, got {:?}", other),
+        }
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mesh.yaml");
+        std::fs::write(&path, "max_nodes: 7\n").unwrap();
+        assert_eq!(MeshConfig::from_yaml(&path).unwrap().max_nodes, 7);
+    }
+    
+    #[test]
+    fn test_mesh_config_env() {
+        let vars: HashMap<&str, &str> = [
+            ("WEAVE_DISCOVERY_INTERVAL", "12"),
+            ("WEAVE_MAX_NODES", "not-a-number"),
+            ("WEAVE_AUTO_RECONNECT", "false"),
+            ("WEAVE_TOPOLOGY_DIFF_DEBOUNCE_MS", "250"),
+            ("OTHER_CONNECTION_TIMEOUT", "1"),
+        ].into_iter().collect();
+        let config = MeshConfig::from_vars("WEAVE", |name| vars.get(name).map(|v| v.to_string()));
+        
+        assert_eq!(config.discovery_interval, 12);
+        assert_eq!(config.max_nodes, MeshConfig::default().max_nodes);
+        assert!(!config.auto_reconnect);
+        assert_eq!(config.topology_diff_debounce, std::time::Duration::from_millis(250));
+        assert_eq!(config.connection_timeout, MeshConfig::default().connection_timeout);
+    }
+    
     #[test]
     fn test_local_node_creation() {
         let node = LocalNode::new();
@@ -449,6 +1377,16 @@ mod tests {
         assert_eq!(node.id, id);
         assert_eq!(node.trust_level, TrustLevel::Basic);
         assert_eq!(node.connection_state, ConnectionState::Disconnected);
+        assert_eq!(node.avg_latency_ms, None);
+    }
+
+    #[test]
+    fn test_remote_node_latency_smoothing() {
+        let mut node = RemoteNode::new(Uuid::new_v4(), NodeCapabilities::default(), TrustLevel::Basic);
+        node.record_latency(100.0);
+        assert_eq!(node.avg_latency_ms, Some(100.0));
+        node.record_latency(200.0);
+        assert!((node.avg_latency_ms.unwrap() - 120.0).abs() < 1e-9);
     }
 
     #[test]
@@ -459,6 +1397,43 @@ mod tests {
         assert!(config.auto_reconnect);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_broadcast_events_are_logged() {
+        let manager = MeshManager::new(MeshConfig::default()).await.unwrap();
+        let event = |reason: &str| MeshEvent::NodeLeft {
+            node_id: Uuid::new_v4(),
+            reason: reason.to_string(),
+            timestamp: Utc::now(),
+        };
+        
+        // Nothing is written before logging starts
+        manager.broadcast_event(event("unlogged")).await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+        manager.start_event_logging(&path).unwrap();
+        manager.broadcast_event(event("first")).await.unwrap();
+        manager.broadcast_event(event("second")).await.unwrap();
+        
+        let logged = std::sync::Mutex::new(Vec::new());
+        let replayed = crate::mesh::events::MeshEvent::replay_from_log(&path, |event| {
+            logged.lock().unwrap().push(event);
+        }).unwrap();
+        assert_eq!(replayed, 2);
+        let reasons: Vec<String> = logged.into_inner().unwrap().into_iter().map(|event| {
+            assert_eq!(event.source_node, manager.local_node.id);
+            match event.payload {
+                crate::mesh::events::EventPayload::Generic { data } => {
+                    match serde_json::from_value(data["mesh_event"].clone()).unwrap() {
+                        MeshEvent::NodeLeft { reason, .. } => reason,
+                        other => panic!("unexpected event {:?}", other),
+                    }
+                }
+                other => panic!("unexpected payload {:?}", other),
+            }
+        }).collect();
+        assert_eq!(reasons, vec!["first", "second"]);
+    }
+    
     #[tokio::test]
     async fn test_mesh_manager_creation() {
         let config = MeshConfig::default();
@@ -0,0 +1,526 @@
+//! Visibility tiers for node metadata
+//!
+//! Metadata entries are Public unless tagged otherwise. Only the Public tier
+//! is included in announcements; Organization and Trusted entries are served
+//! on demand through a direct [`MetadataQuery`], signed by the requester.
+//! The answering node judges the requester by its own node table, never by
+//! claims in the query, and answers key by key so a requester gets what it
+//! is entitled to plus a list of denied keys.
+
+use chrono::{DateTime, Duration, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use zenoh::Session;
+
+use super::discovery::TrustLevel;
+use super::manager::{LocalNode, RemoteNode};
+use super::topology::ORGANIZATION_METADATA_KEY;
+use crate::key_rotation::{key_fingerprint, NodeKeys};
+
+/// Lowest trust level entitled to Trusted metadata
+pub const TRUSTED_METADATA_MIN_TRUST: TrustLevel = TrustLevel::Trusted;
+
+/// Who may see a metadata entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
+pub enum MetadataVisibility {
+    /// Included in announcements
+    #[default]
+    Public,
+    /// Served to nodes in the same organization
+    Organization,
+    /// Served to nodes at or above [`TRUSTED_METADATA_MIN_TRUST`]
+    Trusted,
+}
+
+impl MetadataVisibility {
+    /// Whether `requester` may see entries of this tier
+    ///
+    /// Unknown and quarantined requesters only see Public entries. The
+    /// Organization tier goes by the organization of the requester's last
+    /// verified announcement, not by its self-reported metadata.
+    pub fn permits(&self, requester: Option<&RemoteNode>, local_organization: Option<&str>) -> bool {
+        let requester = match requester {
+            Some(node) if !node.quarantined => node,
+            _ => return *self == MetadataVisibility::Public,
+        };
+        match self {
+            MetadataVisibility::Public => true,
+            MetadataVisibility::Organization => {
+                local_organization.is_some() && requester.verified_organization.as_deref() == local_organization
+            }
+            MetadataVisibility::Trusted => requester.trust_level >= TRUSTED_METADATA_MIN_TRUST,
+        }
+    }
+}
+
+/// Visibility tiers of a node's metadata entries
+///
+/// Clones share the same map, so the local node and the discovery service
+/// announcing it always agree on what is Public.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataVisibilityMap {
+    tiers: Arc<std::sync::RwLock<HashMap<String, MetadataVisibility>>>,
+}
+
+impl MetadataVisibilityMap {
+    /// Tag `key` with a visibility tier
+    pub fn set(&self, key: &str, visibility: MetadataVisibility) {
+        self.tiers.write().unwrap().insert(key.to_string(), visibility);
+    }
+
+    /// Visibility tier of `key`; untagged keys are Public
+    pub fn get(&self, key: &str) -> MetadataVisibility {
+        self.tiers.read().unwrap().get(key).copied().unwrap_or_default()
+    }
+
+    /// Entries of `metadata` in the Public tier
+    pub fn public_metadata(&self, metadata: &HashMap<String, String>) -> HashMap<String, String> {
+        let tiers = self.tiers.read().unwrap();
+        metadata
+            .iter()
+            .filter(|(key, _)| tiers.get(*key).copied().unwrap_or_default() == MetadataVisibility::Public)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl Serialize for MetadataVisibilityMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.tiers.read().unwrap().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MetadataVisibilityMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tiers = HashMap::<String, MetadataVisibility>::deserialize(deserializer)?;
+        Ok(Self { tiers: Arc::new(std::sync::RwLock::new(tiers)) })
+    }
+}
+
+/// Direct request for metadata entries of a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataQuery {
+    /// Node asking; authenticated by [`SignedMetadataQuery`]
+    pub requester: Uuid,
+    /// Keys wanted
+    pub keys: Vec<String>,
+}
+
+/// A [`MetadataQuery`] signed with the requester's node key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedMetadataQuery {
+    pub query: MetadataQuery,
+    /// Key the query is signed with
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl SignedMetadataQuery {
+    /// Sign `query` with the requester's keys
+    pub fn sign(query: MetadataQuery, keys: &NodeKeys) -> anyhow::Result<Self> {
+        let signature = keys.sign(&Self::signed_bytes(&query)?);
+        Ok(Self { query, public_key: keys.public_key().to_vec(), signature })
+    }
+
+    fn signed_bytes(query: &MetadataQuery) -> anyhow::Result<Vec<u8>> {
+        // Going through `Value` sorts map keys, so the encoding is stable
+        Ok(serde_json::to_vec(&serde_json::to_value(query)?)?)
+    }
+
+    /// The requester's entry in `nodes`, if the query is signed with the key trusted for it
+    ///
+    /// Queries that are unsigned, forged, or from unknown nodes yield `None`
+    /// and are answered as if from a stranger.
+    pub fn authenticated_requester<'a>(&self, nodes: &'a HashMap<Uuid, RemoteNode>) -> Option<&'a RemoteNode> {
+        let requester = nodes.get(&self.query.requester)?;
+        let fingerprint = key_fingerprint(&self.public_key);
+        if requester.public_key_fingerprint.as_deref() != Some(fingerprint.as_str()) {
+            return None;
+        }
+        let message = Self::signed_bytes(&self.query).ok()?;
+        UnparsedPublicKey::new(&ED25519, &self.public_key).verify(&message, &self.signature).ok()?;
+        Some(requester)
+    }
+}
+
+/// Answer to a [`MetadataQuery`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataResponse {
+    /// Node the metadata belongs to
+    pub node_id: Uuid,
+    /// Entries the requester is entitled to
+    pub entries: HashMap<String, String>,
+    /// Requested keys the requester is not entitled to
+    pub denied: Vec<String>,
+}
+
+/// Answer a metadata query about the local node
+///
+/// `requester` is the requester's entry in the local node table, if known.
+/// Keys the local node does not have are left out of both lists.
+pub fn answer_metadata_query(
+    local: &LocalNode,
+    requester: Option<&RemoteNode>,
+    query: &MetadataQuery,
+) -> MetadataResponse {
+    let local_organization = local.get_metadata(ORGANIZATION_METADATA_KEY).map(String::as_str);
+    let mut response = MetadataResponse { node_id: local.id, ..Default::default() };
+    for key in &query.keys {
+        let value = match local.get_metadata(key) {
+            Some(value) => value,
+            None => continue,
+        };
+        if local.visibility_of(key).permits(requester, local_organization) {
+            response.entries.insert(key.clone(), value.clone());
+        } else {
+            response.denied.push(key.clone());
+        }
+    }
+    response
+}
+
+/// Sends metadata queries to remote nodes
+#[async_trait::async_trait]
+pub trait MetadataQueryClient: Send + Sync + std::fmt::Debug {
+    /// Ask `node_id` for metadata entries
+    async fn query_metadata(&self, node_id: Uuid, query: MetadataQuery) -> anyhow::Result<MetadataResponse>;
+}
+
+/// Key expression a node answers metadata queries on
+pub fn metadata_query_key(node_id: &Uuid) -> String {
+    format!("weavemesh/metadata/{}", node_id)
+}
+
+/// Queries nodes serving [`metadata_query_key`] over Zenoh, signing each query
+#[derive(Debug, Clone)]
+pub struct ZenohMetadataQueryClient {
+    session: Arc<Session>,
+    keys: Arc<NodeKeys>,
+    timeout: std::time::Duration,
+}
+
+impl ZenohMetadataQueryClient {
+    /// Query through `session`, signing with the local node's `keys`
+    pub fn new(session: Arc<Session>, keys: Arc<NodeKeys>) -> Self {
+        Self { session, keys, timeout: std::time::Duration::from_secs(5) }
+    }
+
+    /// Give up on a query after `timeout`
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl MetadataQueryClient for ZenohMetadataQueryClient {
+    async fn query_metadata(&self, node_id: Uuid, query: MetadataQuery) -> anyhow::Result<MetadataResponse> {
+        let payload = serde_json::to_vec(&SignedMetadataQuery::sign(query, &self.keys)?)?;
+        let replies = self
+            .session
+            .get(metadata_query_key(&node_id))
+            .payload(payload)
+            .timeout(self.timeout)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to query metadata of {}: {}", node_id, e))?;
+        while let Ok(reply) = replies.recv_async().await {
+            let Ok(sample) = reply.result() else { continue };
+            let response: MetadataResponse = serde_json::from_slice(&sample.payload().to_bytes())?;
+            if response.node_id == node_id {
+                return Ok(response);
+            }
+        }
+        Err(anyhow::anyhow!("No metadata answer from {}", node_id))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedEntry {
+    value: String,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Restricted metadata fetched from other nodes, kept for a TTL
+///
+/// Denials are not cached, so a requester that gains trust or joins an
+/// organization sees the entries on its next query.
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    ttl: Duration,
+    entries: HashMap<Uuid, HashMap<String, CachedEntry>>,
+}
+
+impl MetadataCache {
+    /// Create an empty cache
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: HashMap::new() }
+    }
+
+    /// Store the granted entries of a response
+    pub fn insert(&mut self, response: &MetadataResponse, now: DateTime<Utc>) {
+        let node = self.entries.entry(response.node_id).or_default();
+        for (key, value) in &response.entries {
+            node.insert(key.clone(), CachedEntry { value: value.clone(), fetched_at: now });
+        }
+    }
+
+    /// Unexpired value of a key
+    pub fn get(&self, node_id: &Uuid, key: &str, now: DateTime<Utc>) -> Option<&str> {
+        self.entries
+            .get(node_id)?
+            .get(key)
+            .filter(|entry| now - entry.fetched_at < self.ttl)
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// Drop expired entries and nodes left with none
+    pub fn purge_expired(&mut self, now: DateTime<Utc>) {
+        let ttl = self.ttl;
+        for node in self.entries.values_mut() {
+            node.retain(|_, entry| now - entry.fetched_at < ttl);
+        }
+        self.entries.retain(|_, node| !node.is_empty());
+    }
+}
+
+/// Resolve metadata of `node` from its announcement, the cache, and then `client`
+///
+/// Only keys not found locally are queried; the granted ones are cached.
+pub async fn fetch_node_metadata(
+    cache: &RwLock<MetadataCache>,
+    client: &dyn MetadataQueryClient,
+    requester: Uuid,
+    node: &RemoteNode,
+    keys: &[String],
+    now: DateTime<Utc>,
+) -> anyhow::Result<MetadataResponse> {
+    let mut response = MetadataResponse { node_id: node.id, ..Default::default() };
+    let mut missing = Vec::new();
+    {
+        let cache = cache.read().await;
+        for key in keys {
+            let value = node.get_metadata(key).map(String::as_str).or_else(|| cache.get(&node.id, key, now));
+            match value {
+                Some(value) => {
+                    response.entries.insert(key.clone(), value.to_string());
+                }
+                None => missing.push(key.clone()),
+            }
+        }
+    }
+    if missing.is_empty() {
+        return Ok(response);
+    }
+
+    let fetched = client.query_metadata(node.id, MetadataQuery { requester, keys: missing }).await?;
+    {
+        let mut cache = cache.write().await;
+        cache.purge_expired(now);
+        cache.insert(&fetched, now);
+    }
+    response.entries.extend(fetched.entries);
+    response.denied = fetched.denied;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::discovery::NodeCapabilities;
+    use crate::mesh::manager::{MeshConfig, MeshManager};
+    use crate::mesh::node::{MeshNode, NodeAnnouncement};
+    use crate::mesh::verification::SignedAnnouncement;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn local() -> LocalNode {
+        let mut node = LocalNode::new();
+        node.set_metadata(ORGANIZATION_METADATA_KEY.to_string(), "acme".to_string());
+        node.set_metadata("region".to_string(), "eu-north".to_string());
+        node.set_classified_metadata("hostname".to_string(), "build-7.internal".to_string(), MetadataVisibility::Organization);
+        node.set_classified_metadata("cost_center".to_string(), "cc-4411".to_string(), MetadataVisibility::Trusted);
+        node
+    }
+
+    fn requester(org: &str, trust: TrustLevel) -> RemoteNode {
+        let mut node = RemoteNode::new(Uuid::new_v4(), NodeCapabilities::default(), trust);
+        node.verified_organization = Some(org.to_string());
+        node
+    }
+
+    async fn signed_announcement(node_id: Uuid, org: &str, keys: &NodeKeys) -> SignedAnnouncement {
+        let mut node_info = MeshNode::new_universal().await.unwrap().info;
+        node_info.metadata.insert(ORGANIZATION_METADATA_KEY.to_string(), org.to_string());
+        let announcement = NodeAnnouncement { node_id, node_info, timestamp: Utc::now() };
+        SignedAnnouncement::sign(announcement, 1, keys).unwrap()
+    }
+
+    /// Answers queries as `local` would, as seen by `requester`
+    #[derive(Debug)]
+    struct Loopback {
+        local: LocalNode,
+        requester: RemoteNode,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl MetadataQueryClient for Loopback {
+        async fn query_metadata(&self, _node_id: Uuid, query: MetadataQuery) -> anyhow::Result<MetadataResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(answer_metadata_query(&self.local, Some(&self.requester), &query))
+        }
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_announcements_carry_only_public_metadata() {
+        let announced = local().announced_metadata();
+        assert_eq!(announced.get("region").map(String::as_str), Some("eu-north"));
+        assert!(announced.contains_key(ORGANIZATION_METADATA_KEY));
+        assert!(!announced.contains_key("hostname"));
+        assert!(!announced.contains_key("cost_center"));
+    }
+
+    #[test]
+    fn test_query_answers_per_key_entitlement() {
+        let local = local();
+        let query = MetadataQuery { requester: Uuid::new_v4(), keys: keys(&["hostname", "cost_center", "region", "absent"]) };
+
+        let colleague = requester("acme", TrustLevel::Basic);
+        let answer = answer_metadata_query(&local, Some(&colleague), &query);
+        assert_eq!(answer.entries.get("hostname").map(String::as_str), Some("build-7.internal"));
+        assert!(answer.entries.contains_key("region"));
+        assert_eq!(answer.denied, keys(&["cost_center"]));
+
+        let partner = requester("globex", TrustLevel::HighlyTrusted);
+        let answer = answer_metadata_query(&local, Some(&partner), &query);
+        assert!(answer.entries.contains_key("cost_center"));
+        assert_eq!(answer.denied, keys(&["hostname"]));
+
+        let mut quarantined = colleague.clone();
+        quarantined.quarantined = true;
+        for requester in [None, Some(&quarantined)] {
+            let answer = answer_metadata_query(&local, requester, &query);
+            assert_eq!(answer.entries.keys().collect::<Vec<_>>(), vec!["region"]);
+            assert_eq!(answer.denied, keys(&["hostname", "cost_center"]));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_organization_tier_requires_verified_announcement() {
+        let local = local();
+        let query = MetadataQuery { requester: Uuid::new_v4(), keys: keys(&["hostname"]) };
+        let keys_of_node = NodeKeys::generate().unwrap();
+        let mut claimant = RemoteNode::new(Uuid::new_v4(), NodeCapabilities::default(), TrustLevel::Basic)
+            .with_public_key(keys_of_node.public_key());
+
+        // Self-reported organization metadata is not enough
+        claimant.set_metadata(ORGANIZATION_METADATA_KEY.to_string(), "acme".to_string());
+        assert_eq!(answer_metadata_query(&local, Some(&claimant), &query).denied, keys(&["hostname"]));
+
+        // Nor is an announcement signed with some other key
+        let forged = signed_announcement(claimant.id, "acme", &NodeKeys::generate().unwrap()).await;
+        assert!(!claimant.apply_signed_announcement(&forged));
+        assert_eq!(claimant.verified_organization, None);
+
+        let genuine = signed_announcement(claimant.id, "acme", &keys_of_node).await;
+        assert!(claimant.apply_signed_announcement(&genuine));
+        assert_eq!(claimant.verified_organization.as_deref(), Some("acme"));
+        assert!(answer_metadata_query(&local, Some(&claimant), &query).entries.contains_key("hostname"));
+    }
+
+    #[test]
+    fn test_signed_query_authenticates_requester() {
+        let requester_keys = NodeKeys::generate().unwrap();
+        let known = requester("acme", TrustLevel::Trusted).with_public_key(requester_keys.public_key());
+        let nodes = HashMap::from([(known.id, known.clone())]);
+        let query = MetadataQuery { requester: known.id, keys: keys(&["hostname"]) };
+
+        let signed = SignedMetadataQuery::sign(query.clone(), &requester_keys).unwrap();
+        assert_eq!(signed.authenticated_requester(&nodes).map(|node| node.id), Some(known.id));
+
+        // Claiming a known node's identity with another key
+        let impersonated = SignedMetadataQuery::sign(query.clone(), &NodeKeys::generate().unwrap()).unwrap();
+        assert!(impersonated.authenticated_requester(&nodes).is_none());
+
+        let mut tampered = signed;
+        tampered.query.keys.push("cost_center".to_string());
+        assert!(tampered.authenticated_requester(&nodes).is_none());
+    }
+
+    #[test]
+    fn test_visibility_map_is_shared_between_clones() {
+        let local = local();
+        let discovery_view = local.metadata_visibility.clone();
+        assert_eq!(discovery_view.get("hostname"), MetadataVisibility::Organization);
+
+        local.metadata_visibility.set("region", MetadataVisibility::Trusted);
+        assert!(!discovery_view.public_metadata(&local.metadata).contains_key("region"));
+
+        let restored: LocalNode = serde_json::from_str(&serde_json::to_string(&local).unwrap()).unwrap();
+        assert_eq!(restored.visibility_of("cost_center"), MetadataVisibility::Trusted);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_zenoh_client_queries_serving_node() {
+        let mut manager = MeshManager::new(MeshConfig::default()).await.unwrap();
+        manager.local_node = local();
+        let manager = Arc::new(manager);
+        let server = manager.serve_metadata_queries().await.unwrap();
+
+        let requester_keys = NodeKeys::generate().unwrap();
+        let colleague = requester("acme", TrustLevel::Basic).with_public_key(requester_keys.public_key());
+        manager.add_node(colleague.clone()).await.unwrap();
+
+        let client = ZenohMetadataQueryClient::new(manager.session.clone(), Arc::new(requester_keys));
+        let query = MetadataQuery { requester: colleague.id, keys: keys(&["hostname", "cost_center"]) };
+        let answer = client.query_metadata(manager.local_node.id, query.clone()).await.unwrap();
+        assert_eq!(answer.entries.get("hostname").map(String::as_str), Some("build-7.internal"));
+        assert_eq!(answer.denied, keys(&["cost_center"]));
+
+        // The same query signed by a stranger only gets Public entries
+        let stranger = ZenohMetadataQueryClient::new(manager.session.clone(), Arc::new(NodeKeys::generate().unwrap()));
+        let answer = stranger.query_metadata(manager.local_node.id, query).await.unwrap();
+        assert!(answer.entries.is_empty());
+        assert_eq!(answer.denied, keys(&["hostname", "cost_center"]));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_fetched_metadata_is_cached_until_ttl() {
+        let local = local();
+        let mut announced = RemoteNode::new(local.id, NodeCapabilities::default(), TrustLevel::Verified);
+        announced.metadata = local.announced_metadata();
+        let client = Loopback {
+            local,
+            requester: requester("acme", TrustLevel::Basic),
+            calls: AtomicUsize::new(0),
+        };
+        let cache = RwLock::new(MetadataCache::new(Duration::minutes(5)));
+        let wanted = keys(&["hostname", "cost_center", "region"]);
+        let now = Utc::now();
+
+        let first = fetch_node_metadata(&cache, &client, Uuid::new_v4(), &announced, &wanted, now).await.unwrap();
+        assert_eq!(first.entries.len(), 2);
+        assert_eq!(first.denied, keys(&["cost_center"]));
+        assert_eq!(client.calls.load(Ordering::SeqCst), 1);
+
+        // Public and cached keys are served locally
+        let cached = keys(&["hostname", "region"]);
+        let later = now + Duration::minutes(4);
+        let second = fetch_node_metadata(&cache, &client, Uuid::new_v4(), &announced, &cached, later).await.unwrap();
+        assert_eq!(second.entries, first.entries);
+        assert_eq!(client.calls.load(Ordering::SeqCst), 1);
+
+        let expired = now + Duration::minutes(6);
+        assert_eq!(cache.read().await.get(&announced.id, "hostname", expired), None);
+        fetch_node_metadata(&cache, &client, Uuid::new_v4(), &announced, &cached, expired).await.unwrap();
+        assert_eq!(client.calls.load(Ordering::SeqCst), 2);
+    }
+}
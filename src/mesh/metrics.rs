@@ -0,0 +1,159 @@
+//! Plugin metrics in Prometheus form
+//!
+//! Plugins report [`MetricSample`]s through [`MeshPlugin::metrics`]. The
+//! [`PluginRegistry`] prefixes each sample with `weavemesh_plugin_<name>_` so
+//! plugins cannot collide with each other or with core metrics, and
+//! [`render_prometheus`] turns the samples into the text exposition format
+//! served at `/metrics`.
+//!
+//! [`MeshPlugin::metrics`]: super::MeshPlugin::metrics
+//! [`PluginRegistry`]: super::PluginRegistry
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::PluginRegistry;
+
+/// Prefix of every plugin metric name
+pub const PLUGIN_METRIC_PREFIX: &str = "weavemesh_plugin_";
+
+/// Kind of a Prometheus metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricType {
+    /// Monotonically increasing value
+    Counter,
+    /// Value that can go up and down
+    Gauge,
+    /// Bucketed observations; samples carry the `_bucket`, `_sum` and `_count` series
+    Histogram,
+}
+
+impl MetricType {
+    /// Name used in `# TYPE` lines
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+            MetricType::Histogram => "histogram",
+        }
+    }
+}
+
+/// A single metric value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    /// Metric name, without the plugin prefix when reported by a plugin
+    pub name: String,
+    /// Label values
+    pub labels: HashMap<String, String>,
+    /// Sample value
+    pub value: f64,
+    /// Kind of metric
+    pub metric_type: MetricType,
+}
+
+impl MetricSample {
+    /// Unlabelled sample
+    pub fn new(name: impl Into<String>, value: f64, metric_type: MetricType) -> Self {
+        Self {
+            name: name.into(),
+            labels: HashMap::new(),
+            value,
+            metric_type,
+        }
+    }
+
+    /// Add a label
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Full name of a metric reported by `plugin`
+///
+/// Characters Prometheus does not allow in names become underscores.
+pub fn plugin_metric_name(plugin: &str, name: &str) -> String {
+    sanitize(&format!("{}{}_{}", PLUGIN_METRIC_PREFIX, plugin.to_ascii_lowercase(), name))
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect()
+}
+
+/// Render samples in the Prometheus text exposition format
+///
+/// Samples are grouped by name, each group under one `# TYPE` line taken
+/// from its first sample.
+pub fn render_prometheus(samples: &[MetricSample]) -> String {
+    let mut families: BTreeMap<&str, Vec<&MetricSample>> = BTreeMap::new();
+    for sample in samples {
+        families.entry(sample.name.as_str()).or_default().push(sample);
+    }
+
+    let mut out = String::new();
+    for (name, samples) in families {
+        out.push_str(&format!("# TYPE {} {}\n", name, samples[0].metric_type.as_str()));
+        for sample in samples {
+            let mut labels: Vec<_> = sample.labels.iter().collect();
+            labels.sort();
+            let labels: Vec<String> = labels
+                .into_iter()
+                .map(|(key, value)| format!("{}=\"{}\"", sanitize(key), escape_label(value)))
+                .collect();
+            if labels.is_empty() {
+                out.push_str(&format!("{} {}\n", name, sample.value));
+            } else {
+                out.push_str(&format!("{}{{{}}} {}\n", name, labels.join(","), sample.value));
+            }
+        }
+    }
+    out
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Source of metrics served at `/metrics`
+#[async_trait::async_trait]
+pub trait MetricsSource: Send + Sync {
+    /// Current samples
+    async fn metrics(&self) -> Vec<MetricSample>;
+}
+
+#[async_trait::async_trait]
+impl MetricsSource for tokio::sync::RwLock<PluginRegistry> {
+    async fn metrics(&self) -> Vec<MetricSample> {
+        self.read().await.collect_metrics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let samples = vec![
+            MetricSample::new(plugin_metric_name("Git-Sync", "pushes_total"), 3.0, MetricType::Counter)
+                .with_label("remote", "origin")
+                .with_label("note", "say \"hi\""),
+            MetricSample::new(plugin_metric_name("Git-Sync", "pushes_total"), 1.0, MetricType::Counter)
+                .with_label("remote", "backup"),
+            MetricSample::new("weavemesh_nodes", 2.5, MetricType::Gauge),
+        ];
+
+        let text = render_prometheus(&samples);
+        assert_eq!(
+            text,
+            "# TYPE weavemesh_nodes gauge\n\
+             weavemesh_nodes 2.5\n\
+             # TYPE weavemesh_plugin_git_sync_pushes_total counter\n\
+             weavemesh_plugin_git_sync_pushes_total{note=\"say \\\"hi\\\"\",remote=\"origin\"} 3\n\
+             weavemesh_plugin_git_sync_pushes_total{remote=\"backup\"} 1\n"
+        );
+    }
+}
@@ -4,17 +4,37 @@
 //! collaboration systems. This module contains universal primitives that can be
 //! extended by context-specific plugins.
 
+pub mod admission;
+pub mod decommission;
 pub mod discovery;
+pub mod event_log;
 pub mod events;
 pub mod health;
+pub mod incident_response;
 pub mod manager;
+pub mod metadata_visibility;
+pub mod metrics;
 pub mod node;
+pub mod publication;
 pub mod resource;
+pub mod dependency_graph;
 pub mod security;
+pub mod subscription;
+pub mod topology;
+pub mod verification;
+pub mod webhook;
 
 // Re-export key types for convenience
+pub use admission::{
+    AdmissionConfig, AdmissionDecision, AdmissionError, AdmissionPeers, AdmissionRegistry, AdmissionRequest,
+    AdmissionStatus,
+};
+pub use decommission::{
+    DecommissionTargets, DecommissionPeers, DecommissionProgress, DecommissionReport,
+    TransferOffer, RetirementNotice, RETIREMENT_ID_METADATA_KEY,
+};
 pub use discovery::{
-    MeshDiscovery, MeshNode, NodeCapabilities, TrustLevel, DiscoveryState
+    MeshDiscovery, MeshNode, NodeCapabilities, TrustLevel, DiscoveryState, TopologyFormat,
 };
 pub use events::{
     EventSystem, MeshEvent, EventType, EventPayload, EventPriority,
@@ -22,19 +42,40 @@ pub use events::{
     HealthEventType, SecurityEventType, PerformanceEventType, EventConfig,
     EventStatistics, EventProvider
 };
+pub use event_log::{EventLogger, LoggedEvent};
 pub use health::{
     HealthMonitor, HealthStatus, NodeHealthStatus, NodeHealthMetrics,
     HealthCheckResult, HealthIssue, HealthSeverity, PerformanceMetrics,
-    HealthConfig, HealthEvent, HealthProvider
+    HealthConfig, HealthEvent, HealthProvider, HealthDegradationEvent, HealthRecoveryEvent,
+};
+pub use incident_response::{
+    PlaybookTrigger, PlaybookResult, IncidentResponsePlaybook, BlockNodePlaybook, NotifyAdminPlaybook,
+    SnapshotStatePlaybook, IncidentNotification, NOTIFY_ADMIN_TIMEOUT,
 };
 pub use manager::{
     MeshManager, LocalNode, RemoteNode, MeshConfig, MeshState,
     MeshMetrics, ConnectionState, TopologyChangeType
 };
+pub use metadata_visibility::{
+    MetadataVisibility, MetadataVisibilityMap, MetadataQuery, SignedMetadataQuery, MetadataResponse,
+    MetadataCache, MetadataQueryClient, ZenohMetadataQueryClient, metadata_query_key, TRUSTED_METADATA_MIN_TRUST,
+};
+pub use metrics::{
+    MetricSample, MetricType, MetricsSource, PLUGIN_METRIC_PREFIX, plugin_metric_name, render_prometheus,
+};
 pub use node::{
     MeshNode as UniversalMeshNode, NodeInfo, NodeType, NodeCapability, NodeEndpoint,
     EndpointType, NodeVersion, NodeAnnouncement, NodeMetrics
 };
+pub use publication::{
+    PublicationWorkflow, PublicationPolicy, PublicationState, PublicationStatus,
+    PublicationGate, ResourceReview, ReviewOutcome,
+};
+pub use dependency_graph::{
+    DependencyGraph, DependencyEntry, DependencyAnnouncement, DependencyStatus, DependencyError,
+    DependencyPeers, ResourceDependency, ResourceVersion, VersionConstraint, ImpactedResource,
+    BrokenDependency,
+};
 pub use resource::{
     MeshResource, ResourceType, ResourceState, ResourceMetadata, QualityMetrics,
     CollaborationMetrics, ResourceInstance, InstanceState, ContextAdaptation,
@@ -44,11 +85,30 @@ pub use resource::{
     VisibilityLevel, ConflictInfo, SessionStatus, CeremonyStatus
 };
 pub use security::{
-    SecuritySystem, TrustRelationship, TrustEvent, TrustEventType,
+    SecuritySystem, TrustRevoker, TrustRelationship, TrustEvent, TrustEventType,
     SharedCredentials, TrustVerificationMethod, TrustBoundaries,
     SecurityPolicies, AuthenticationPolicy, AuthorizationRule, EncryptionPolicy,
     AccessControlPolicy, MonitoringPolicy, SecurityEvent, SecurityEventFilter,
-    SecuritySeverity, ResolutionStatus, SecurityConfig, SecurityProvider
+    SecuritySeverity, ResolutionStatus, SecurityConfig, SecurityProvider,
+    SecurityError, TrustSource, TrustSourceKind
+};
+pub use subscription::{
+    ResourceChange, ResourceChangeNotification, ResourceWatchMessage, WatchConfig,
+    WatchRecord, ResourceWatchRegistry, ResourceWatch, ResourceWatcher
+};
+pub use topology::{
+    TopologyGraphFormat, TopologyFilter, TopologyNode, TopologyEdge, TopologyGraph,
+    TopologySource, ORGANIZATION_METADATA_KEY, TopologyDiff, TopologyDiffPublisher,
+    MeshNodeSummary, ConnectionChange, NodeId,
+};
+pub use verification::{
+    AnnouncementBody, SignedAnnouncement, TrustedKey, TrustedKeys, VerificationCheck, CheckOutcome,
+    VerificationReport, IdentityFile, PrivateKeyMaterial, IdentitySummary,
+    verify_announcement, inspect_identity_file, config_fingerprint,
+};
+pub use webhook::{
+    WebhookTarget, WebhookFilter, PayloadTemplate, WebhookConfig, DeliveryStatus,
+    DeliveryOutcome, WebhookDispatcher, WebhookEventProvider, sign_payload, verify_signature
 };
 
 use anyhow::Result;
@@ -120,6 +180,14 @@ pub trait MeshPlugin: Send + Sync {
     
     /// Cleanup plugin resources
     async fn cleanup(&mut self) -> Result<()>;
+    
+    /// Plugin-specific metrics
+    ///
+    /// Names are reported without a prefix; the registry prepends
+    /// `weavemesh_plugin_<name>_`.
+    fn metrics(&self) -> Vec<MetricSample> {
+        vec![]
+    }
 }
 
 /// Plugin registry for managing mesh extensions
@@ -176,6 +244,20 @@ impl PluginRegistry {
         Ok(())
     }
     
+    /// Metrics from all plugins, named under each plugin's prefix
+    pub fn collect_metrics(&self) -> Vec<MetricSample> {
+        let mut names: Vec<&String> = self.plugins.keys().collect();
+        names.sort();
+        names.into_iter()
+            .flat_map(|name| {
+                self.plugins[name].metrics().into_iter().map(move |mut sample| {
+                    sample.name = plugin_metric_name(name, &sample.name);
+                    sample
+                })
+            })
+            .collect()
+    }
+    
     /// Cleanup all plugins
     pub async fn cleanup_all(&mut self) -> Result<()> {
         for plugin in self.plugins.values_mut() {
@@ -213,6 +295,16 @@ impl MeshBuilder {
         self
     }
     
+    /// Use the configuration in a YAML file
+    pub fn with_config_file(self, path: &std::path::Path) -> Result<Self, MeshError> {
+        Ok(self.with_config(MeshConfig::from_yaml(path)?))
+    }
+    
+    /// Use the configuration from `{prefix}_*` environment variables
+    pub fn with_env_config(self, prefix: &str) -> Self {
+        self.with_config(MeshConfig::from_env_prefix(prefix))
+    }
+    
     /// Add a plugin
     pub fn with_plugin(mut self, plugin: Box<dyn MeshPlugin>) -> Self {
         self.plugins.register_plugin(plugin);
@@ -319,6 +411,39 @@ mod tests {
         // Note: Would need a concrete plugin implementation to test registration
     }
 
+    struct CountingPlugin {
+        name: &'static str,
+        reports: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl MeshPlugin for CountingPlugin {
+        fn name(&self) -> &str { self.name }
+        fn version(&self) -> &str { "1.0.0" }
+        async fn initialize(&mut self, _config: &HashMap<String, serde_json::Value>) -> Result<()> { Ok(()) }
+        async fn handle_event(&self, _event: &MeshEvent) -> Result<()> { Ok(()) }
+        async fn cleanup(&mut self) -> Result<()> { Ok(()) }
+        
+        fn metrics(&self) -> Vec<MetricSample> {
+            if !self.reports {
+                return vec![];
+            }
+            vec![MetricSample::new("events_total", 4.0, MetricType::Counter).with_label("kind", "join")]
+        }
+    }
+
+    #[test]
+    fn test_collect_plugin_metrics() {
+        let mut registry = PluginRegistry::new();
+        registry.register_plugin(Box::new(CountingPlugin { name: "audit", reports: true }));
+        registry.register_plugin(Box::new(CountingPlugin { name: "quiet", reports: false }));
+        
+        let samples = registry.collect_metrics();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].name, "weavemesh_plugin_audit_events_total");
+        assert_eq!(samples[0].labels["kind"], "join");
+    }
+
     #[test]
     fn test_utils() {
         let node_id = generate_node_id();
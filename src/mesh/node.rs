@@ -192,6 +192,27 @@ pub enum EndpointType {
     
     /// Custom protocol endpoint
     Custom(String),
+    
+    /// Direct connection on the local network
+    Lan,
+    
+    /// Connection relayed through another node
+    Relay,
+}
+
+impl EndpointType {
+    /// Starting score for endpoints of this type before any traffic (0.0-1.0)
+    ///
+    /// Direct LAN paths are preferred over routed ones, and relays come last.
+    pub fn default_preference(&self) -> f64 {
+        match self {
+            EndpointType::Lan => 1.0,
+            EndpointType::WebSocket | EndpointType::Universal(_) | EndpointType::Custom(_) => 0.8,
+            EndpointType::HttpApi => 0.7,
+            EndpointType::ZenohRouter => 0.6,
+            EndpointType::Relay => 0.4,
+        }
+    }
 }
 
 /// Universal node version information
@@ -0,0 +1,603 @@
+//! # Resource Publication Workflow
+//!
+//! Shared assets such as templates, policies and prompts go through review
+//! before they become visible mesh-wide. A resource starts as a draft seen
+//! only by its owner and invited reviewers; reviewers approve, request
+//! changes or reject; once the number of approvals required for its
+//! `ResourceType` is reached the owner publishes it, which makes it
+//! `Available` and announces it to the mesh. Editing a draft starts a new
+//! revision and discards earlier reviews. Workflow state is persisted
+//! through a [`Storage`] backend so it survives restarts.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use super::events::{EventPayload, EventPriority, EventSystem, EventType, MeshEvent, ResourceEventType};
+use super::resource::{MeshResource, ResourceState, ResourceType};
+use super::MeshError;
+use crate::storage::{AccessControl as StorageAccessControl, ResourceFilter, Storage};
+use crate::Attribution;
+
+/// Storage tag marking persisted publication records
+pub const PUBLICATION_STORAGE_TAG: &str = "publication";
+
+/// Prefix of the resource metadata entries that carry reviews
+pub const REVIEW_METADATA_PREFIX: &str = "review.";
+
+/// Approvals needed before a resource can be published
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicationPolicy {
+    /// Approvals needed for resource types without an override
+    pub default_required_approvals: usize,
+    /// Approvals needed per `ResourceType::type_name`
+    pub required_approvals: HashMap<String, usize>,
+}
+
+impl Default for PublicationPolicy {
+    fn default() -> Self {
+        Self {
+            default_required_approvals: 1,
+            required_approvals: HashMap::new(),
+        }
+    }
+}
+
+impl PublicationPolicy {
+    /// Approvals needed to publish a resource of this type
+    pub fn required_for(&self, resource_type: &ResourceType) -> usize {
+        self.required_approvals
+            .get(resource_type.type_name())
+            .copied()
+            .unwrap_or(self.default_required_approvals)
+    }
+}
+
+/// A reviewer's verdict on a draft revision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReviewOutcome {
+    /// The revision may be published
+    Approve,
+    /// The revision needs changes before it can be published
+    RequestChanges { comments: String },
+    /// The draft should not be published
+    Reject { comments: String },
+}
+
+/// A review of one revision of a draft
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceReview {
+    /// Reviewer identifier
+    pub reviewer: String,
+    /// Verdict
+    pub outcome: ReviewOutcome,
+    /// Revision the review applies to
+    pub revision: u64,
+    /// Attribution for the review
+    pub attribution: Attribution,
+    /// When the review was submitted
+    pub reviewed_at: DateTime<Utc>,
+}
+
+/// Where a resource is in the publication workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PublicationState {
+    /// Awaiting reviews
+    Draft,
+    /// A reviewer asked for changes to the current revision
+    ChangesRequested,
+    /// A reviewer rejected the current revision
+    Rejected,
+    /// Enough approvals to publish
+    Approved,
+    /// Visible mesh-wide
+    Published,
+}
+
+/// Publication status of a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicationStatus {
+    /// Resource identifier
+    pub resource_id: String,
+    /// Workflow state
+    pub state: PublicationState,
+    /// Current draft revision
+    pub revision: u64,
+    /// Approvals of the current revision
+    pub approvals: usize,
+    /// Approvals needed to publish
+    pub required_approvals: usize,
+    /// Invited reviewers
+    pub reviewers: Vec<String>,
+    /// Reviews of the current revision
+    pub reviews: Vec<ResourceReview>,
+}
+
+/// Persisted workflow state of one resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PublicationRecord {
+    resource: MeshResource,
+    reviewers: Vec<String>,
+    revision: u64,
+    reviews: Vec<ResourceReview>,
+    published_at: Option<DateTime<Utc>>,
+}
+
+impl PublicationRecord {
+    fn owner(&self) -> &str {
+        &self.resource.access_control.owner
+    }
+
+    fn current_reviews(&self) -> impl Iterator<Item = &ResourceReview> {
+        self.reviews.iter().filter(move |r| r.revision == self.revision)
+    }
+
+    fn approvals(&self) -> usize {
+        self.current_reviews().filter(|r| r.outcome == ReviewOutcome::Approve).count()
+    }
+
+    fn state(&self, required_approvals: usize) -> PublicationState {
+        if self.published_at.is_some() {
+            return PublicationState::Published;
+        }
+        let outcomes: Vec<&ReviewOutcome> = self.current_reviews().map(|r| &r.outcome).collect();
+        if outcomes.iter().any(|o| matches!(o, ReviewOutcome::Reject { .. })) {
+            PublicationState::Rejected
+        } else if outcomes.iter().any(|o| matches!(o, ReviewOutcome::RequestChanges { .. })) {
+            PublicationState::ChangesRequested
+        } else if self.approvals() >= required_approvals {
+            PublicationState::Approved
+        } else {
+            PublicationState::Draft
+        }
+    }
+
+    /// Mirror the current reviews into the resource's custom metadata
+    fn sync_review_metadata(&mut self) {
+        self.resource.metadata.custom.retain(|key, _| !key.starts_with(REVIEW_METADATA_PREFIX));
+        let entries: Vec<(String, String)> = self.current_reviews()
+            .filter_map(|review| {
+                serde_json::to_string(review)
+                    .ok()
+                    .map(|json| (format!("{}{}", REVIEW_METADATA_PREFIX, review.reviewer), json))
+            })
+            .collect();
+        self.resource.metadata.custom.extend(entries);
+    }
+}
+
+/// Publication states consulted before a resource is exposed mesh-wide
+///
+/// [`MeshManager`](super::MeshManager) keeps resources that are still in
+/// review out of discovery and refuses to announce them.
+pub trait PublicationGate: Send + Sync + std::fmt::Debug {
+    /// Workflow state of a resource, if it is in the workflow
+    fn publication_state(&self, resource_id: &str) -> Option<PublicationState>;
+}
+
+/// Draft, review and publish workflow for mesh resources
+pub struct PublicationWorkflow<S: Storage> {
+    storage: S,
+    policy: PublicationPolicy,
+    records: HashMap<String, PublicationRecord>,
+    /// Storage key of each persisted record, by resource ID
+    storage_keys: HashMap<String, String>,
+    /// Event system and local node ID used to announce published resources
+    announcer: Option<(Arc<EventSystem>, Uuid)>,
+}
+
+impl<S: Storage> PublicationWorkflow<S> {
+    /// Restore the workflow from the records persisted in `storage`
+    pub async fn load(storage: S, policy: PublicationPolicy) -> Result<Self> {
+        let mut workflow = Self {
+            storage,
+            policy,
+            records: HashMap::new(),
+            storage_keys: HashMap::new(),
+            announcer: None,
+        };
+
+        let persisted = workflow.storage.list_resources(Some(ResourceFilter {
+            content_type: Some("application/json".to_string()),
+            tags: Some(vec![PUBLICATION_STORAGE_TAG.to_string()]),
+            is_private: None,
+            name_contains: None,
+            content_query: None,
+        }));
+        for metadata in persisted {
+            let content = workflow.storage.get_resource_content(&metadata.resource_id).await?;
+            let record: PublicationRecord = serde_json::from_slice(&content)?;
+            workflow.storage_keys.insert(record.resource.id.clone(), metadata.resource_id);
+            workflow.records.insert(record.resource.id.clone(), record);
+        }
+
+        debug!("Loaded {} publication records", workflow.records.len());
+        Ok(workflow)
+    }
+
+    /// Announce published resources on `events` as coming from `node_id`
+    pub fn with_announcer(mut self, events: Arc<EventSystem>, node_id: Uuid) -> Self {
+        self.announcer = Some((events, node_id));
+        self
+    }
+
+    /// Start the workflow for a resource as a draft owned by its access control owner
+    pub async fn create_draft(&mut self, mut resource: MeshResource, reviewers: Vec<String>) -> Result<()> {
+        if self.records.contains_key(&resource.id) {
+            return Err(MeshError::Generic(format!("Resource already in publication workflow: {}", resource.id)).into());
+        }
+
+        resource.state = ResourceState::Draft;
+        let resource_id = resource.id.clone();
+        self.records.insert(resource_id.clone(), PublicationRecord {
+            resource,
+            reviewers,
+            revision: 1,
+            reviews: Vec::new(),
+            published_at: None,
+        });
+        self.persist(&resource_id).await
+    }
+
+    /// Invite another reviewer; only the owner may invite
+    pub async fn invite_reviewer(&mut self, resource_id: &str, actor: &str, reviewer: String) -> Result<()> {
+        let record = self.owned_record_mut(resource_id, actor)?;
+        if !record.reviewers.contains(&reviewer) {
+            record.reviewers.push(reviewer);
+        }
+        self.persist(resource_id).await
+    }
+
+    /// Record a reviewer's verdict on the current revision, replacing their earlier one
+    pub async fn submit_review(
+        &mut self,
+        resource_id: &str,
+        reviewer: &str,
+        outcome: ReviewOutcome,
+        attribution: Attribution,
+        now: DateTime<Utc>,
+    ) -> Result<PublicationStatus> {
+        let record = self.record_mut(resource_id)?;
+        if record.published_at.is_some() {
+            return Err(MeshError::Generic(format!("Resource already published: {}", resource_id)).into());
+        }
+        if !record.reviewers.iter().any(|r| r == reviewer) {
+            return Err(MeshError::Generic(format!("{} is not a reviewer of {}", reviewer, resource_id)).into());
+        }
+
+        let revision = record.revision;
+        record.reviews.retain(|r| !(r.reviewer == reviewer && r.revision == revision));
+        record.reviews.push(ResourceReview {
+            reviewer: reviewer.to_string(),
+            outcome,
+            revision,
+            attribution,
+            reviewed_at: now,
+        });
+        record.sync_review_metadata();
+        self.persist(resource_id).await?;
+        self.get_publication_status(resource_id)
+            .ok_or_else(|| MeshError::Generic(format!("Resource not found: {}", resource_id)).into())
+    }
+
+    /// Edit a draft, starting a new revision that needs fresh reviews
+    pub async fn edit_draft<F>(&mut self, resource_id: &str, actor: &str, attribution: Attribution, edit: F) -> Result<()>
+    where
+        F: FnOnce(&mut MeshResource),
+    {
+        let record = self.owned_record_mut(resource_id, actor)?;
+        if record.published_at.is_some() {
+            return Err(MeshError::Generic(format!("Resource already published: {}", resource_id)).into());
+        }
+
+        edit(&mut record.resource);
+        record.resource.state = ResourceState::Draft;
+        record.resource.attribution_chain.push(attribution);
+        record.resource.modified_at = Utc::now();
+        record.revision += 1;
+        record.sync_review_metadata();
+        self.persist(resource_id).await
+    }
+
+    /// Publish an approved draft and announce it to the mesh; only the owner may publish
+    pub async fn publish(&mut self, resource_id: &str, actor: &str, now: DateTime<Utc>) -> Result<MeshResource> {
+        let required = self.required_approvals(resource_id)?;
+        let record = self.owned_record_mut(resource_id, actor)?;
+        let state = record.state(required);
+        if state != PublicationState::Approved {
+            return Err(MeshError::Generic(format!(
+                "Cannot publish {} in state {:?} ({} of {} approvals)",
+                resource_id, state, record.approvals(), required
+            )).into());
+        }
+
+        record.resource.state = ResourceState::Available;
+        record.resource.modified_at = now;
+        record.published_at = Some(now);
+        let resource = record.resource.clone();
+        self.persist(resource_id).await?;
+        self.announce(&resource).await;
+
+        info!("Published resource {} after review", resource_id);
+        Ok(resource)
+    }
+
+    /// Publication status of a resource, if it is in the workflow
+    pub fn get_publication_status(&self, resource_id: &str) -> Option<PublicationStatus> {
+        let record = self.records.get(resource_id)?;
+        let required_approvals = self.policy.required_for(&record.resource.resource_type);
+        Some(PublicationStatus {
+            resource_id: resource_id.to_string(),
+            state: record.state(required_approvals),
+            revision: record.revision,
+            approvals: record.approvals(),
+            required_approvals,
+            reviewers: record.reviewers.clone(),
+            reviews: record.current_reviews().cloned().collect(),
+        })
+    }
+
+    /// A resource as seen by `principal`; drafts are only visible to their owner and reviewers
+    pub fn get_resource(&self, resource_id: &str, principal: &str) -> Option<&MeshResource> {
+        let record = self.records.get(resource_id)?;
+        let visible = record.published_at.is_some()
+            || record.owner() == principal
+            || record.reviewers.iter().any(|r| r == principal);
+        if visible {
+            Some(&record.resource)
+        } else {
+            None
+        }
+    }
+
+    /// Published resources, the only ones visible in mesh-wide discovery
+    pub fn discoverable_resources(&self) -> Vec<&MeshResource> {
+        let mut resources: Vec<&MeshResource> = self.records
+            .values()
+            .filter(|r| r.published_at.is_some())
+            .map(|r| &r.resource)
+            .collect();
+        resources.sort_by(|a, b| a.id.cmp(&b.id));
+        resources
+    }
+
+    fn required_approvals(&self, resource_id: &str) -> Result<usize> {
+        self.records
+            .get(resource_id)
+            .map(|r| self.policy.required_for(&r.resource.resource_type))
+            .ok_or_else(|| MeshError::Generic(format!("Resource not found: {}", resource_id)).into())
+    }
+
+    fn record_mut(&mut self, resource_id: &str) -> Result<&mut PublicationRecord> {
+        self.records
+            .get_mut(resource_id)
+            .ok_or_else(|| MeshError::Generic(format!("Resource not found: {}", resource_id)).into())
+    }
+
+    fn owned_record_mut(&mut self, resource_id: &str, actor: &str) -> Result<&mut PublicationRecord> {
+        let record = self.record_mut(resource_id)?;
+        if record.owner() != actor {
+            return Err(MeshError::Generic(format!("Only the owner can change the publication of {}", resource_id)).into());
+        }
+        Ok(record)
+    }
+
+    /// Write a record to storage, replacing its previous version
+    async fn persist(&mut self, resource_id: &str) -> Result<()> {
+        let record = match self.records.get(resource_id) {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+        let content = serde_json::to_vec(record)?;
+        let key = self.storage.store_resource(
+            resource_id.to_string(),
+            content,
+            "application/json".to_string(),
+            StorageAccessControl::default(),
+            vec![PUBLICATION_STORAGE_TAG.to_string()],
+        ).await?;
+        if let Some(previous) = self.storage_keys.insert(resource_id.to_string(), key) {
+            self.storage.delete_resource(&previous).await?;
+        }
+        Ok(())
+    }
+
+    async fn announce(&self, resource: &MeshResource) {
+        let (events, node_id) = match &self.announcer {
+            Some((events, node_id)) => (events, *node_id),
+            None => return,
+        };
+        let event = MeshEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            source_node: node_id,
+            event_type: EventType::Resource { resource_type: ResourceEventType::ResourceCreated },
+            payload: EventPayload::Resource {
+                resource_id: resource.id.clone(),
+                resource_type: resource.resource_type.type_name().to_string(),
+                operation: "published".to_string(),
+                affected_nodes: vec![node_id],
+                conflict_info: None,
+            },
+            metadata: HashMap::new(),
+            propagation_path: vec![node_id],
+            correlation_id: None,
+            priority: EventPriority::Normal,
+        };
+        if let Err(e) = events.publish_event(event).await {
+            debug!("Failed to announce published resource {}: {}", resource.id, e);
+        }
+    }
+}
+
+impl<S: Storage> std::fmt::Debug for PublicationWorkflow<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PublicationWorkflow")
+            .field("policy", &self.policy)
+            .field("records", &self.records)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Storage> PublicationGate for PublicationWorkflow<S> {
+    fn publication_state(&self, resource_id: &str) -> Option<PublicationState> {
+        self.get_publication_status(resource_id).map(|status| status.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use crate::CollaborationType;
+
+    fn person(name: &str) -> Attribution {
+        Attribution::new(Some(name.to_string()), None, CollaborationType::Individual, 1.0)
+    }
+
+    fn prompt_template(id: &str) -> MeshResource {
+        let mut resource = MeshResource::new_universal(
+            id.to_string(),
+            format!("team/{}@alice/prompts/", id),
+            ResourceType::Knowledge {
+                domain: "prompts".to_string(),
+                knowledge_type: "template".to_string(),
+                confidence: 1.0,
+            },
+            person("alice"),
+        );
+        resource.access_control.owner = "alice".to_string();
+        resource
+    }
+
+    fn policy() -> PublicationPolicy {
+        PublicationPolicy {
+            default_required_approvals: 1,
+            required_approvals: [("Knowledge".to_string(), 2)].into_iter().collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_draft_review_publish() {
+        let events = Arc::new(EventSystem::new(Uuid::nil(), None));
+        let mut workflow = PublicationWorkflow::load(MemoryStorage::new(), policy()).await.unwrap()
+            .with_announcer(events.clone(), Uuid::nil());
+        let now = Utc::now();
+
+        workflow.create_draft(prompt_template("greeting"), vec!["bob".to_string()]).await.unwrap();
+        workflow.invite_reviewer("greeting", "alice", "carol".to_string()).await.unwrap();
+        assert!(workflow.invite_reviewer("greeting", "mallory", "mallory".to_string()).await.is_err());
+
+        // Drafts are private to the owner and reviewers
+        assert!(workflow.get_resource("greeting", "alice").is_some());
+        assert!(workflow.get_resource("greeting", "bob").is_some());
+        assert!(workflow.get_resource("greeting", "dave").is_none());
+        assert!(workflow.discoverable_resources().is_empty());
+
+        assert!(workflow.submit_review("greeting", "dave", ReviewOutcome::Approve, person("dave"), now).await.is_err());
+        let status = workflow.submit_review("greeting", "bob", ReviewOutcome::Approve, person("bob"), now).await.unwrap();
+        assert_eq!(status.state, PublicationState::Draft);
+        assert_eq!(status.required_approvals, 2);
+        assert!(workflow.publish("greeting", "alice", now).await.is_err());
+
+        let status = workflow.submit_review("greeting", "carol", ReviewOutcome::Approve, person("carol"), now).await.unwrap();
+        assert_eq!(status.state, PublicationState::Approved);
+        let resource = workflow.get_resource("greeting", "alice").unwrap();
+        assert!(resource.metadata.custom.contains_key("review.carol"));
+
+        assert!(workflow.publish("greeting", "bob", now).await.is_err());
+        let published = workflow.publish("greeting", "alice", now).await.unwrap();
+        assert!(matches!(published.state, ResourceState::Available));
+        assert_eq!(workflow.get_publication_status("greeting").unwrap().state, PublicationState::Published);
+        assert!(workflow.get_resource("greeting", "dave").is_some());
+        assert_eq!(workflow.discoverable_resources().len(), 1);
+        assert_eq!(events.get_event_history(Some("resource")).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_edit_resets_approvals_and_state_survives_restart() {
+        let mut workflow = PublicationWorkflow::load(MemoryStorage::new(), PublicationPolicy::default()).await.unwrap();
+        let now = Utc::now();
+
+        workflow.create_draft(prompt_template("policy"), vec!["bob".to_string()]).await.unwrap();
+        workflow.create_draft(prompt_template("rejected"), vec!["bob".to_string()]).await.unwrap();
+        workflow.submit_review("policy", "bob", ReviewOutcome::Approve, person("bob"), now).await.unwrap();
+        workflow.submit_review(
+            "rejected", "bob",
+            ReviewOutcome::Reject { comments: "Out of scope".to_string() },
+            person("bob"), now,
+        ).await.unwrap();
+
+        // Policy approvals are per type; the default is one
+        assert_eq!(workflow.get_publication_status("policy").unwrap().state, PublicationState::Approved);
+        workflow.edit_draft("policy", "alice", person("alice"), |r| {
+            r.metadata.description = Some("Revised".to_string());
+        }).await.unwrap();
+        let status = workflow.get_publication_status("policy").unwrap();
+        assert_eq!(status.state, PublicationState::Draft);
+        assert_eq!(status.revision, 2);
+        assert_eq!(status.approvals, 0);
+        assert!(workflow.publish("policy", "alice", now).await.is_err());
+        let resource = workflow.get_resource("policy", "alice").unwrap();
+        assert!(!resource.metadata.custom.keys().any(|k| k.starts_with(REVIEW_METADATA_PREFIX)));
+
+        assert_eq!(workflow.get_publication_status("rejected").unwrap().state, PublicationState::Rejected);
+        assert!(workflow.publish("rejected", "alice", now).await.is_err());
+
+        // Reload from the same storage
+        let PublicationWorkflow { storage, .. } = workflow;
+        let restored = PublicationWorkflow::load(storage, PublicationPolicy::default()).await.unwrap();
+        assert_eq!(restored.storage.list_resources(None).len(), 2);
+        let status = restored.get_publication_status("policy").unwrap();
+        assert_eq!(status.revision, 2);
+        assert_eq!(status.state, PublicationState::Draft);
+        assert_eq!(restored.get_publication_status("rejected").unwrap().state, PublicationState::Rejected);
+        assert!(restored.get_resource("rejected", "dave").is_none());
+        assert!(restored.discoverable_resources().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_drafts_never_appear_in_mesh_discovery() {
+        use crate::mesh::resource::VisibilityLevel;
+        use crate::mesh::{MeshConfig, MeshManager};
+
+        let workflow = PublicationWorkflow::load(MemoryStorage::new(), PublicationPolicy::default()).await.unwrap();
+        let workflow = Arc::new(tokio::sync::RwLock::new(workflow));
+        let mut manager = MeshManager::new(MeshConfig::default()).await.unwrap();
+        manager.set_publication_workflow(workflow.clone());
+        let requester = Uuid::new_v4();
+        let public = |id: &str| {
+            let mut resource = prompt_template(id);
+            resource.access_control.visibility = VisibilityLevel::Public;
+            resource
+        };
+        let discovered = || async {
+            manager.discover_resources(&requester).await.into_iter().map(|r| r.id).collect::<Vec<_>>()
+        };
+
+        // Drafts known to the manager are neither discovered nor announced
+        workflow.write().await.create_draft(public("greeting"), vec!["bob".to_string()]).await.unwrap();
+        let draft = workflow.read().await.get_resource("greeting", "alice").unwrap().clone();
+        manager.add_resource(draft.clone()).await;
+        assert!(discovered().await.is_empty());
+        assert!(manager.publish_resource(draft.clone()).await.is_err());
+
+        // Approval alone does not expose it
+        workflow.write().await.submit_review("greeting", "bob", ReviewOutcome::Approve, person("bob"), Utc::now()).await.unwrap();
+        assert!(discovered().await.is_empty());
+        assert!(manager.publish_resource(draft).await.is_err());
+
+        // Published through the workflow, it is announced and discovered
+        let published = workflow.write().await.publish("greeting", "alice", Utc::now()).await.unwrap();
+        manager.publish_resource(published).await.unwrap();
+        assert_eq!(discovered().await, vec!["greeting".to_string()]);
+
+        // Resources outside the workflow are not held back
+        manager.publish_resource(public("notes")).await.unwrap();
+        assert_eq!(discovered().await, vec!["greeting".to_string(), "notes".to_string()]);
+    }
+}
@@ -11,6 +11,7 @@ use std::path::PathBuf;
 use uuid::Uuid;
 
 use crate::{Attribution, WeaveMeshError};
+use super::dependency_graph::ResourceDependency;
 
 /// A universal resource in the WeaveMesh network
 /// 
@@ -45,6 +46,10 @@ pub struct MeshResource {
     /// Attribution for this resource
     pub attribution: Attribution,
     
+    /// Attributions for modifications made since creation, oldest first
+    #[serde(default)]
+    pub attribution_chain: Vec<Attribution>,
+    
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
     
@@ -136,9 +141,28 @@ pub enum ResourceType {
     },
 }
 
+impl ResourceType {
+    /// Name of the variant, or the type name of a custom resource
+    pub fn type_name(&self) -> &str {
+        match self {
+            ResourceType::Communication { .. } => "Communication",
+            ResourceType::Knowledge { .. } => "Knowledge",
+            ResourceType::Pattern { .. } => "Pattern",
+            ResourceType::CollaborativeSession { .. } => "CollaborativeSession",
+            ResourceType::SacredCeremony { .. } => "SacredCeremony",
+            ResourceType::FileSystem { .. } => "FileSystem",
+            ResourceType::Configuration { .. } => "Configuration",
+            ResourceType::Custom { type_name, .. } => type_name,
+        }
+    }
+}
+
 /// Current state of a universal resource
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ResourceState {
+    /// Resource is being drafted and visible only to its owner and reviewers
+    Draft,
+    
     /// Resource is available and synchronized
     Available,
     
@@ -209,6 +233,18 @@ pub struct ResourceMetadata {
     
     /// Collaborative individuation metrics
     pub collaboration_metrics: CollaborationMetrics,
+    
+    /// Resource this one was forked from, if any
+    #[serde(default)]
+    pub forked_from: Option<String>,
+    
+    /// Published version, `MAJOR[.MINOR[.PATCH]]`
+    #[serde(default)]
+    pub version: Option<String>,
+    
+    /// Dependencies with the versions they accept
+    #[serde(default)]
+    pub declared_dependencies: Vec<ResourceDependency>,
 }
 
 /// Universal quality metrics for a resource
@@ -729,6 +765,14 @@ pub enum CeremonyStatus {
     /// Ceremony is in progress
     InProgress,
     
+    /// Ceremony is paused and expected to resume later
+    Deferred {
+        /// Why the ceremony was deferred
+        reason: String,
+        /// When the ceremony is expected to resume, if known
+        expected_resume: Option<DateTime<Utc>>,
+    },
+    
     /// Ceremony completed successfully
     Completed,
     
@@ -780,6 +824,9 @@ impl MeshResource {
                     cross_context_score: 0.0,
                     last_collaboration: now,
                 },
+                forked_from: None,
+                version: None,
+                declared_dependencies: Vec::new(),
             },
             instances: Vec::new(),
             sync_status: SyncStatus {
@@ -798,11 +845,64 @@ impl MeshResource {
                 context_access: HashMap::new(),
             },
             attribution,
+            attribution_chain: Vec::new(),
             created_at: now,
             modified_at: now,
         }
     }
     
+    /// Create a derived copy of this resource under a new ID
+    ///
+    /// The fork keeps the source's content, metadata and attribution chain,
+    /// links back through `forked_from`, and records the modifier's
+    /// attribution. Instances and dependents stay with the source.
+    pub fn fork(&self, id: String, modifier_attribution: Attribution) -> Self {
+        let now = Utc::now();
+        let mut fork = self.clone();
+        
+        fork.id = id;
+        fork.metadata.forked_from = Some(self.id.clone());
+        fork.metadata.dependents.clear();
+        fork.attribution_chain.push(modifier_attribution);
+        fork.instances.clear();
+        fork.created_at = now;
+        fork.modified_at = now;
+        fork
+    }
+    
+    /// Depend on another resource, replacing any earlier declaration for it
+    pub fn declare_dependency(&mut self, dependency: ResourceDependency) {
+        self.metadata.declared_dependencies.retain(|d| d.resource_id != dependency.resource_id);
+        if !self.metadata.dependencies.contains(&dependency.resource_id) {
+            self.metadata.dependencies.push(dependency.resource_id.clone());
+        }
+        self.metadata.declared_dependencies.push(dependency);
+    }
+    
+    /// Check if this resource was forked directly from `other_id`
+    pub fn is_fork_of(&self, other_id: &str) -> bool {
+        self.metadata.forked_from.as_deref() == Some(other_id)
+    }
+    
+    /// Ancestry of a resource, starting with the resource itself and ending at its root
+    ///
+    /// Traversal stops at the first missing parent or repeated ID.
+    pub fn lineage_in(resources: &HashMap<String, MeshResource>, resource_id: &str) -> Vec<MeshResource> {
+        let mut lineage = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut current = resources.get(resource_id);
+        
+        while let Some(resource) = current {
+            if !seen.insert(resource.id.clone()) {
+                break;
+            }
+            lineage.push(resource.clone());
+            current = resource.metadata.forked_from.as_ref().and_then(|parent| resources.get(parent));
+        }
+        
+        lineage
+    }
+    
     /// Check if this resource matches a universal search pattern
     pub fn matches_pattern(&self, pattern: &str) -> bool {
         self.id.contains(pattern)
@@ -1088,4 +1188,45 @@ mod tests {
         assert!(matches!(resource.state, ResourceState::Available));
         assert_eq!(resource.metadata.collaboration_metrics.avg_collaboration_quality, 0.9);
     }
+
+    #[test]
+    fn test_fork_lineage() {
+        let author = Attribution::new(Some("alice".to_string()), None, CollaborationType::HumanLed, 1.0);
+        let mut source = MeshResource::new_universal(
+            "pattern-v1".to_string(),
+            "universal/pattern@alice/library/".to_string(),
+            ResourceType::Pattern {
+                pattern_type: "communication".to_string(),
+                complexity: 0.5,
+                confidence: 0.8,
+            },
+            author,
+        );
+        source.metadata.dependents.push("consumer".to_string());
+        
+        let bob = Attribution::new(Some("bob".to_string()), None, CollaborationType::HumanLed, 1.0);
+        let fork = source.fork("pattern-v2".to_string(), bob.clone());
+        assert!(fork.is_fork_of("pattern-v1"));
+        assert!(!source.is_fork_of("pattern-v2"));
+        assert_eq!(fork.attribution.id, source.attribution.id);
+        assert_eq!(fork.attribution_chain.len(), 1);
+        assert_eq!(fork.attribution_chain[0].id, bob.id);
+        assert!(fork.metadata.dependents.is_empty());
+        
+        let carol = Attribution::new(Some("carol".to_string()), None, CollaborationType::HumanLed, 1.0);
+        let grandchild = fork.fork("pattern-v3".to_string(), carol);
+        assert_eq!(grandchild.attribution_chain.len(), 2);
+        assert!(!grandchild.is_fork_of("pattern-v1"));
+        
+        let resources: HashMap<String, MeshResource> = [source, fork, grandchild]
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect();
+        let ids: Vec<String> = MeshResource::lineage_in(&resources, "pattern-v3")
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["pattern-v3", "pattern-v2", "pattern-v1"]);
+        assert!(MeshResource::lineage_in(&resources, "missing").is_empty());
+    }
 }
@@ -10,10 +10,25 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use super::admission::AdmissionRegistry;
+use super::decommission::{RetirementNotice, RETIREMENT_ID_METADATA_KEY};
+use super::incident_response::{IncidentResponsePlaybook, PlaybookResult, PlaybookTrigger, RegisteredPlaybook};
+use crate::key_rotation::{key_fingerprint, KeyRotationRecord};
+use crate::networking::node_communication::{CommunicationError, ControlHandler};
+use crate::networking::system_control::{ControlCommand, ControlEnvelope};
+use crate::networking::zenoh_integration::{MessageType, WeaveMeshMessage};
+use crate::protocol::KEY_ROTATION_CONTEXT;
+use crate::resource_profile::{
+    ComponentFootprint, EvictionCounters, EvictionKind, MemoryFootprint, ResourceProfile,
+};
+
+/// Events buffered per live subscriber before it starts missing events
+const EVENT_STREAM_CAPACITY: usize = 256;
+
 /// Universal mesh security system
 pub struct SecuritySystem {
     /// Local node ID
@@ -36,6 +51,56 @@ pub struct SecuritySystem {
     
     /// Running state
     is_running: Arc<RwLock<bool>>,
+    
+    /// Counts of events dropped by the in-memory limit
+    eviction_counters: Arc<EvictionCounters>,
+    
+    /// Live feed of logged events for subscribers
+    event_sender: broadcast::Sender<SecurityEvent>,
+    
+    /// Automated responses to repeated security events
+    playbooks: Vec<RegisteredPlaybook>,
+    
+    /// Admission state; unadmitted nodes cannot be trusted
+    admission: Option<AdmissionRegistry>,
+}
+
+/// Handle for revoking trust in partners, held by automated responses
+#[derive(Clone)]
+pub struct TrustRevoker {
+    local_node_id: Uuid,
+    trust_relationships: Arc<RwLock<HashMap<Uuid, TrustRelationship>>>,
+}
+
+impl TrustRevoker {
+    /// Drop a partner's trust to `Unknown`, recording a revocation with `evidence`
+    ///
+    /// Returns false for the local node, for partners without a trust
+    /// relationship and for partners whose trust is already revoked.
+    pub async fn revoke(&self, partner_id: Uuid, description: &str, evidence: Vec<String>) -> bool {
+        if partner_id == self.local_node_id {
+            return false;
+        }
+        let mut relationships = self.trust_relationships.write().await;
+        let Some(relationship) = relationships.get_mut(&partner_id) else {
+            return false;
+        };
+        if relationship.trust_level == TrustLevel::Unknown {
+            return false;
+        }
+        let trust_before = std::mem::replace(&mut relationship.trust_level, TrustLevel::Unknown);
+        relationship.trust_history.push(TrustEvent {
+            timestamp: Utc::now(),
+            event_type: TrustEventType::Revocation,
+            description: description.to_string(),
+            trust_before,
+            trust_after: TrustLevel::Unknown,
+            evidence,
+            metadata: HashMap::new(),
+        });
+        info!("Revoked trust in node {}: {}", partner_id, description);
+        true
+    }
 }
 
 /// Trust relationship between nodes
@@ -85,6 +150,84 @@ pub enum TrustLevel {
     HighlyTrusted,
 }
 
+impl TrustLevel {
+    /// Levels in ascending order, indexed by ordinal
+    const ORDERED: [TrustLevel; 5] = [
+        TrustLevel::Unknown,
+        TrustLevel::Basic,
+        TrustLevel::Verified,
+        TrustLevel::Trusted,
+        TrustLevel::HighlyTrusted,
+    ];
+    
+    /// Numeric ordinal, from Unknown (0) to HighlyTrusted (4)
+    pub fn ordinal(&self) -> u8 {
+        match self {
+            TrustLevel::Unknown => 0,
+            TrustLevel::Basic => 1,
+            TrustLevel::Verified => 2,
+            TrustLevel::Trusted => 3,
+            TrustLevel::HighlyTrusted => 4,
+        }
+    }
+    
+    /// Weighted mean of the levels' ordinals, rounded to the nearest level
+    ///
+    /// Empty input, or input whose weights are all zero, is `Unknown`.
+    pub fn weighted_average(levels: &[(TrustLevel, f64)]) -> Result<TrustLevel, SecurityError> {
+        if levels.iter().any(|(_, weight)| weight.is_nan() || *weight < 0.0) {
+            return Err(SecurityError::InvalidTrustWeight);
+        }
+        
+        let total: f64 = levels.iter().map(|(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return Ok(TrustLevel::Unknown);
+        }
+        let mean = levels.iter()
+            .map(|(level, weight)| level.ordinal() as f64 * weight)
+            .sum::<f64>() / total;
+        Ok(Self::ORDERED[(mean.round() as usize).min(Self::ORDERED.len() - 1)].clone())
+    }
+    
+    /// [`weighted_average`](Self::weighted_average), panicking on a negative weight
+    pub fn weighted_average_unchecked(levels: &[(TrustLevel, f64)]) -> TrustLevel {
+        Self::weighted_average(levels).expect("trust weights must be non-negative")
+    }
+}
+
+/// Where an opinion of a node's trustworthiness came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TrustSourceKind {
+    /// This node's own interactions with the partner
+    DirectInteraction,
+    
+    /// A third party's attestation
+    Attestation { attester: Uuid },
+    
+    /// Inferred through a node that trusts the partner
+    Transitive { via: Uuid },
+}
+
+/// One source's opinion of a node's trust level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustSource {
+    /// Where the opinion came from
+    pub kind: TrustSourceKind,
+    
+    /// Trust level the source reports
+    pub level: TrustLevel,
+    
+    /// Relative weight of this source; must not be negative
+    pub weight: f64,
+}
+
+/// Errors from security operations
+#[derive(Debug, thiserror::Error)]
+pub enum SecurityError {
+    #[error("Trust weights must be non-negative")]
+    InvalidTrustWeight,
+}
+
 /// Trust event in the relationship history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustEvent {
@@ -700,6 +843,17 @@ pub enum SecuritySeverity {
     Critical,
 }
 
+impl SecuritySeverity {
+    /// All severities, lowest first
+    pub const ALL: [SecuritySeverity; 5] = [
+        SecuritySeverity::Info,
+        SecuritySeverity::Low,
+        SecuritySeverity::Medium,
+        SecuritySeverity::High,
+        SecuritySeverity::Critical,
+    ];
+}
+
 /// Event resolution status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ResolutionStatus {
@@ -776,6 +930,16 @@ impl Default for SecurityConfig {
     }
 }
 
+impl SecurityConfig {
+    /// Default configuration with the in-memory bounds of a resource profile
+    pub fn for_profile(profile: ResourceProfile) -> Self {
+        Self {
+            max_events_in_memory: profile.limits().max_security_events,
+            ..Self::default()
+        }
+    }
+}
+
 impl SecuritySystem {
     /// Create a new security system
     pub fn new(
@@ -794,15 +958,46 @@ impl SecuritySystem {
             providers: Vec::new(),
             config,
             is_running: Arc::new(RwLock::new(false)),
+            eviction_counters: Arc::new(EvictionCounters::new()),
+            event_sender: broadcast::channel(EVENT_STREAM_CAPACITY).0,
+            playbooks: Vec::new(),
+            admission: None,
         }
     }
     
+    /// Refuse trust to nodes a gatekeeper has not admitted
+    pub fn with_admission_registry(mut self, admission: AdmissionRegistry) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+    
+    /// Record event evictions into shared counters
+    pub fn with_eviction_counters(mut self, counters: Arc<EvictionCounters>) -> Self {
+        self.eviction_counters = counters;
+        self
+    }
+    
+    /// Counters of events dropped by the in-memory limit
+    pub fn eviction_counters(&self) -> &Arc<EvictionCounters> {
+        &self.eviction_counters
+    }
+    
     /// Add a security provider for context-specific policies
     pub fn add_provider(&mut self, provider: Box<dyn SecurityProvider>) {
         info!("Adding security provider: {}", provider.name());
         self.providers.push(provider);
     }
     
+    /// Run `playbook` whenever events crossing `trigger`'s threshold are logged
+    pub fn register_playbook(
+        &mut self,
+        trigger: PlaybookTrigger,
+        playbook: Box<dyn IncidentResponsePlaybook + Send + Sync>,
+    ) {
+        info!("Registering incident response playbook: {}", playbook.name());
+        self.playbooks.push(RegisteredPlaybook::new(trigger, playbook));
+    }
+    
     /// Start the security system
     pub async fn start(&mut self) -> Result<()> {
         let mut is_running = self.is_running.write().await;
@@ -848,6 +1043,9 @@ impl SecuritySystem {
         initial_trust_level: TrustLevel,
         verification_methods: Vec<TrustVerificationMethod>,
     ) -> Result<()> {
+        if self.admission.as_ref().is_some_and(|admission| !admission.is_admitted(&partner_id)) {
+            return Err(anyhow::anyhow!("Node {} has not been admitted to the mesh", partner_id));
+        }
         let trust_relationship = TrustRelationship {
             partner_id,
             trust_level: initial_trust_level.clone(),
@@ -889,6 +1087,170 @@ impl SecuritySystem {
         Ok(())
     }
     
+    /// Record the fingerprint of a partner's signing key
+    ///
+    /// Returns false if there is no trust relationship with the partner.
+    pub async fn record_public_key(&self, partner_id: Uuid, public_key: &[u8]) -> bool {
+        let mut relationships = self.trust_relationships.write().await;
+        let Some(relationship) = relationships.get_mut(&partner_id) else {
+            return false;
+        };
+        let credentials = &mut relationship.shared_credentials;
+        credentials.public_key_fingerprints.insert(partner_id.to_string(), key_fingerprint(public_key));
+        credentials.last_updated = Utc::now();
+        true
+    }
+    
+    /// Apply a partner's key rotation to its shared credentials
+    ///
+    /// Rotations that do not chain from the key on file are logged as trust
+    /// violations and rejected.
+    pub async fn apply_key_rotation(&self, record: &KeyRotationRecord) -> Result<bool> {
+        let applied = match self.trust_relationships.write().await.get_mut(&record.node_id) {
+            Some(relationship) => relationship.shared_credentials.apply_key_rotation(record),
+            None => return Ok(false),
+        };
+        
+        let (event_type, description, severity) = if applied {
+            (SecurityEventType::KeyRotation, "Partner signing key rotated", SecuritySeverity::Info)
+        } else {
+            (SecurityEventType::TrustViolation, "Unverifiable key rotation rejected", SecuritySeverity::High)
+        };
+        self.log_security_event(SecurityEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type,
+            involved_nodes: vec![self.local_node_id, record.node_id],
+            description: description.to_string(),
+            severity,
+            response_actions: Vec::new(),
+            resolution_status: ResolutionStatus::Resolved,
+            metadata: HashMap::from([
+                ("old_key_fingerprint".to_string(), record.old_key_fingerprint.clone()),
+                ("new_key_fingerprint".to_string(), record.new_key_fingerprint.clone()),
+            ]),
+            related_events: Vec::new(),
+        }).await;
+        
+        Ok(applied)
+    }
+    
+    /// Apply the rotation announced by a [`KEY_ROTATION_CONTEXT`] control message
+    ///
+    /// Other messages are ignored, as are rotations sent by a node other than
+    /// the one whose key rotated. Returns whether a rotation was applied.
+    pub async fn handle_rekey_message(&self, message: &WeaveMeshMessage) -> Result<bool> {
+        if message.message_type != MessageType::SystemControl
+            || message.context.as_deref() != Some(KEY_ROTATION_CONTEXT)
+        {
+            return Ok(false);
+        }
+        let ControlCommand::Rekey { record } = ControlEnvelope::decode(&message.payload)?.command else {
+            return Ok(false);
+        };
+        self.apply_announced_rotation(&message.from_node, &record).await
+    }
+    
+    /// Apply a rotation announced by `from_node`, which must be the rotating node
+    async fn apply_announced_rotation(&self, from_node: &str, record: &KeyRotationRecord) -> Result<bool> {
+        if record.node_id.to_string() != from_node {
+            warn!("Ignoring rotation of {} announced by {}", record.node_id, from_node);
+            return Ok(false);
+        }
+        self.apply_key_rotation(record).await
+    }
+    
+    /// Revoke trust in a partner that announced its retirement
+    ///
+    /// The trust history gains a revocation referencing the retirement.
+    /// Notices that fail verification, or are not signed with the key on file
+    /// for the partner, are logged as trust violations and ignored.
+    pub async fn apply_retirement(&self, notice: &RetirementNotice) -> Result<bool> {
+        let node = notice.node_id.to_string();
+        let mut relationships = self.trust_relationships.write().await;
+        let Some(relationship) = relationships.get_mut(&notice.node_id) else {
+            return Ok(false);
+        };
+        
+        let fingerprint = notice.key_fingerprint();
+        let key_matches = relationship.shared_credentials.public_key_fingerprints
+            .get(&node)
+            .is_some_and(|known| *known == fingerprint);
+        let applied = key_matches && notice.verify_signature();
+        if applied {
+            let trust_before = std::mem::replace(&mut relationship.trust_level, TrustLevel::Unknown);
+            relationship.trust_history.push(TrustEvent {
+                timestamp: Utc::now(),
+                event_type: TrustEventType::Revocation,
+                description: "Partner node retired".to_string(),
+                trust_before,
+                trust_after: TrustLevel::Unknown,
+                evidence: vec![fingerprint.clone()],
+                metadata: HashMap::from([
+                    (RETIREMENT_ID_METADATA_KEY.to_string(), notice.retirement_id.to_string()),
+                ]),
+            });
+        }
+        drop(relationships);
+        
+        let (event_type, description, severity) = if applied {
+            let event_type = SecurityEventType::ContextSpecific {
+                context: "mesh".to_string(),
+                event_subtype: "retirement".to_string(),
+            };
+            (event_type, "Trust revoked for retired node", SecuritySeverity::Info)
+        } else {
+            (SecurityEventType::TrustViolation, "Unverifiable retirement notice rejected", SecuritySeverity::High)
+        };
+        self.log_security_event(SecurityEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type,
+            involved_nodes: vec![self.local_node_id, notice.node_id],
+            description: description.to_string(),
+            severity,
+            response_actions: Vec::new(),
+            resolution_status: ResolutionStatus::Resolved,
+            metadata: HashMap::from([
+                (RETIREMENT_ID_METADATA_KEY.to_string(), notice.retirement_id.to_string()),
+                ("key_fingerprint".to_string(), fingerprint),
+            ]),
+            related_events: Vec::new(),
+        }).await;
+        
+        Ok(applied)
+    }
+    
+    /// Handle for revoking trust outside the security system, e.g. from a playbook
+    pub fn trust_revoker(&self) -> TrustRevoker {
+        TrustRevoker {
+            local_node_id: self.local_node_id,
+            trust_relationships: Arc::clone(&self.trust_relationships),
+        }
+    }
+    
+    /// Trust history with a partner, oldest first
+    pub async fn get_trust_history(&self, partner_id: Uuid) -> Vec<TrustEvent> {
+        self.trust_relationships.read().await
+            .get(&partner_id)
+            .map(|relationship| relationship.trust_history.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Every trust relationship, for backups
+    pub async fn trust_relationships(&self) -> Vec<TrustRelationship> {
+        self.trust_relationships.read().await.values().cloned().collect()
+    }
+    
+    /// Replace the trust relationships with restored ones
+    ///
+    /// Admission is not checked: the relationships were established before.
+    pub async fn restore_trust_relationships(&self, relationships: Vec<TrustRelationship>) {
+        let mut current = self.trust_relationships.write().await;
+        *current = relationships.into_iter().map(|r| (r.partner_id, r)).collect();
+        info!("Restored {} trust relationships", current.len());
+    }
+    
     /// Verify trust relationship
     pub async fn verify_trust(&self, partner_id: Uuid) -> Result<bool> {
         let relationships = self.trust_relationships.read().await;
@@ -904,6 +1266,7 @@ impl SecuritySystem {
     }
     
     /// Check authorization for a resource
+    #[tracing::instrument(skip(self))]
     pub async fn check_authorization(
         &self,
         node_id: Uuid,
@@ -942,8 +1305,95 @@ impl SecuritySystem {
             .unwrap_or(TrustLevel::Unknown)
     }
     
+    /// Combine several sources' opinions of a partner into one trust level
+    ///
+    /// Sources with a negative or NaN weight are ignored. Without any usable
+    /// source the partner's recorded trust level is returned.
+    pub async fn aggregate_trust(&self, partner_id: Uuid, sources: &[TrustSource]) -> TrustLevel {
+        let levels: Vec<(TrustLevel, f64)> = sources.iter()
+            .filter(|source| {
+                let valid = source.weight >= 0.0;
+                if !valid {
+                    warn!("Ignoring trust source {:?} for {} with invalid weight {}", source.kind, partner_id, source.weight);
+                }
+                valid
+            })
+            .map(|source| (source.level.clone(), source.weight))
+            .collect();
+        
+        if levels.iter().all(|(_, weight)| *weight == 0.0) {
+            return self.get_trust_level(partner_id).await;
+        }
+        TrustLevel::weighted_average_unchecked(&levels)
+    }
+    
     /// Log security event
+    ///
+    /// Playbooks whose trigger the event completes run before this returns.
     pub async fn log_security_event(&self, event: SecurityEvent) {
+        self.record_security_event(event.clone()).await;
+        self.automated_incident_response(&event).await;
+    }
+    
+    /// Run the playbooks whose trigger `event` completes, returning their results
+    ///
+    /// Each run, successful or not, is recorded as a `ConfigurationChange`
+    /// event related to the events that triggered it. Those records never
+    /// trigger playbooks themselves.
+    pub async fn automated_incident_response(&self, event: &SecurityEvent) -> Vec<PlaybookResult> {
+        let mut results = Vec::new();
+        for registered in &self.playbooks {
+            let Some(events) = registered.observe(event) else {
+                continue;
+            };
+            let name = registered.playbook.name().to_string();
+            let related_events: Vec<Uuid> = events.iter().map(|e| e.event_id).collect();
+            let involved_nodes = {
+                let mut nodes: Vec<Uuid> = events.iter().flat_map(|e| e.involved_nodes.iter().copied()).collect();
+                nodes.sort();
+                nodes.dedup();
+                nodes
+            };
+            
+            let outcome = registered.playbook.execute(events).await;
+            let (description, severity, response_actions, resolution_status) = match &outcome {
+                Ok(result) => {
+                    let status = if result.resolved {
+                        ResolutionStatus::AutoResolved
+                    } else if result.escalated {
+                        ResolutionStatus::Escalated
+                    } else {
+                        ResolutionStatus::InProgress
+                    };
+                    (format!("Playbook {} ran", name), SecuritySeverity::Info, result.actions_taken.clone(), status)
+                }
+                Err(e) => {
+                    warn!("Incident response playbook {} failed: {}", name, e);
+                    (format!("Playbook {} failed: {}", name, e), SecuritySeverity::High, Vec::new(), ResolutionStatus::Failed)
+                }
+            };
+            self.record_security_event(SecurityEvent {
+                event_id: Uuid::new_v4(),
+                timestamp: Utc::now(),
+                event_type: SecurityEventType::ConfigurationChange,
+                involved_nodes,
+                description,
+                severity,
+                response_actions,
+                resolution_status,
+                metadata: HashMap::from([("playbook".to_string(), name)]),
+                related_events,
+            }).await;
+            
+            if let Ok(result) = outcome {
+                results.push(result);
+            }
+        }
+        results
+    }
+    
+    /// Store, broadcast and hand an event to providers, without running playbooks
+    async fn record_security_event(&self, event: SecurityEvent) {
         let mut events = self.security_events.write().await;
         events.push(event.clone());
         
@@ -951,9 +1401,13 @@ impl SecuritySystem {
         if events.len() > self.config.max_events_in_memory {
             let excess = events.len() - self.config.max_events_in_memory;
             events.drain(0..excess);
+            self.eviction_counters.record(EvictionKind::SecurityEvent, excess as u64);
         }
         drop(events);
         
+        // No receivers is not an error; nobody is subscribed yet
+        let _ = self.event_sender.send(event.clone());
+        
         // Process with security providers
         for provider in &self.providers {
             if let Err(e) = provider.handle_security_event(&event).await {
@@ -976,6 +1430,29 @@ impl SecuritySystem {
         }
     }
     
+    /// Stream events logged from now on that match `filter`
+    ///
+    /// A subscriber that falls more than the stream capacity behind skips
+    /// the missed events and keeps receiving new ones.
+    pub fn subscribe_to_events(&self, filter: SecurityEventFilter) -> impl futures::Stream<Item = SecurityEvent> {
+        let receiver = self.event_sender.subscribe();
+        futures::stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if filter.matches(&event) {
+                            return Some((event, (receiver, filter)));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Security event subscriber lagged, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+    
     /// Update security policies
     pub async fn update_policies(&self, policies: SecurityPolicies) -> Result<()> {
         let mut current_policies = self.security_policies.write().await;
@@ -997,8 +1474,16 @@ impl SecuritySystem {
     }
 }
 
+#[async_trait::async_trait]
+impl MemoryFootprint for SecuritySystem {
+    async fn memory_footprint(&self) -> ComponentFootprint {
+        let events = self.security_events.read().await.len();
+        ComponentFootprint::of::<SecurityEvent>("security_events", events)
+    }
+}
+
 /// Filter for security events
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SecurityEventFilter {
     /// Event types to include
     pub event_types: Option<Vec<SecurityEventType>>,
@@ -1014,9 +1499,76 @@ pub struct SecurityEventFilter {
     
     /// Resolution status
     pub resolution_status: Option<Vec<ResolutionStatus>>,
+    
+    /// Alternative filters; if any are set, an event must also match one of them
+    any_of: Vec<SecurityEventFilter>,
 }
 
 impl SecurityEventFilter {
+    /// Filter matching events that match any of `alternatives`
+    ///
+    /// No alternatives matches every event.
+    pub fn any_of(alternatives: Vec<SecurityEventFilter>) -> Self {
+        Self {
+            any_of: alternatives,
+            ..Self::default()
+        }
+    }
+    
+    /// Filter for severities between `min` and `max`, inclusive
+    pub fn by_severity_range(min: SecuritySeverity, max: SecuritySeverity) -> Self {
+        Self {
+            severities: Some(
+                SecuritySeverity::ALL.iter()
+                    .filter(|severity| **severity >= min && **severity <= max)
+                    .cloned()
+                    .collect(),
+            ),
+            ..Self::default()
+        }
+    }
+    
+    /// Filter for critical events only
+    pub fn critical_only() -> Self {
+        Self::by_severity_range(SecuritySeverity::Critical, SecuritySeverity::Critical)
+    }
+    
+    /// Filter for high and critical events
+    pub fn high_and_above() -> Self {
+        Self::by_severity_range(SecuritySeverity::High, SecuritySeverity::Critical)
+    }
+    
+    /// Filter matching events that match both filters
+    pub fn and(self, other: SecurityEventFilter) -> SecurityEventFilter {
+        let time_range = match (self.time_range, other.time_range) {
+            (Some((a_start, a_end)), Some((b_start, b_end))) => Some((a_start.max(b_start), a_end.min(b_end))),
+            (a, b) => a.or(b),
+        };
+        
+        // (a1 | a2) & (b1 | b2) distributes into the pairwise conjunctions
+        let any_of = match (self.any_of.is_empty(), other.any_of.is_empty()) {
+            (true, _) => other.any_of,
+            (_, true) => self.any_of,
+            _ => self.any_of.iter()
+                .flat_map(|a| other.any_of.iter().map(move |b| a.clone().and(b.clone())))
+                .collect(),
+        };
+        
+        SecurityEventFilter {
+            event_types: intersect(self.event_types, other.event_types),
+            severities: intersect(self.severities, other.severities),
+            time_range,
+            involved_nodes: intersect(self.involved_nodes, other.involved_nodes),
+            resolution_status: intersect(self.resolution_status, other.resolution_status),
+            any_of,
+        }
+    }
+    
+    /// Filter matching events that match either filter
+    pub fn or(self, other: SecurityEventFilter) -> SecurityEventFilter {
+        Self::any_of(vec![self, other])
+    }
+    
     /// Check if an event matches this filter
     pub fn matches(&self, event: &SecurityEvent) -> bool {
         if let Some(ref types) = self.event_types {
@@ -1049,7 +1601,15 @@ impl SecurityEventFilter {
             }
         }
         
-        true
+        self.any_of.is_empty() || self.any_of.iter().any(|alternative| alternative.matches(event))
+    }
+}
+
+/// Intersect two optional allow-lists, where None allows everything
+fn intersect<T: PartialEq>(a: Option<Vec<T>>, b: Option<Vec<T>>) -> Option<Vec<T>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.into_iter().filter(|item| b.contains(item)).collect()),
+        (a, b) => a.or(b),
     }
 }
 
@@ -1112,6 +1672,23 @@ impl Default for SecurityPolicies {
     }
 }
 
+impl SharedCredentials {
+    /// Move trust in a node's key to its rotated key
+    ///
+    /// The record must be signed by the key whose fingerprint is on file
+    /// for the node. Returns false, changing nothing, otherwise.
+    pub fn apply_key_rotation(&mut self, record: &KeyRotationRecord) -> bool {
+        let node = record.node_id.to_string();
+        let trusted = self.public_key_fingerprints.get(&node) == Some(&record.old_key_fingerprint);
+        if !trusted || !record.verify_continuity(&record.old_public_key) {
+            return false;
+        }
+        self.public_key_fingerprints.insert(node, record.new_key_fingerprint.clone());
+        self.last_updated = Utc::now();
+        true
+    }
+}
+
 impl Default for SharedCredentials {
     fn default() -> Self {
         Self {
@@ -1247,6 +1824,19 @@ impl Default for ResolutionStatus {
     }
 }
 
+/// Applies `Rekey` commands delivered to a node's control handlers
+#[async_trait::async_trait]
+impl ControlHandler for SecuritySystem {
+    async fn handle(&self, from_node: &str, command: ControlCommand) -> std::result::Result<Option<ControlCommand>, CommunicationError> {
+        if let ControlCommand::Rekey { record } = command {
+            self.apply_announced_rotation(from_node, &record)
+                .await
+                .map_err(|e| CommunicationError::HandlerError(e.to_string()))?;
+        }
+        Ok(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1259,6 +1849,43 @@ mod tests {
         assert!(TrustLevel::Basic > TrustLevel::Unknown);
     }
 
+    #[test]
+    fn test_trust_weighted_average() {
+        assert_eq!(TrustLevel::weighted_average(&[]).unwrap(), TrustLevel::Unknown);
+        // (3 * 2 + 1 * 1) / 3 = 2.33
+        assert_eq!(
+            TrustLevel::weighted_average_unchecked(&[(TrustLevel::Trusted, 2.0), (TrustLevel::Basic, 1.0)]),
+            TrustLevel::Verified,
+        );
+        // (4 + 3) / 2 = 3.5 rounds up
+        assert_eq!(
+            TrustLevel::weighted_average_unchecked(&[(TrustLevel::HighlyTrusted, 1.0), (TrustLevel::Trusted, 1.0)]),
+            TrustLevel::HighlyTrusted,
+        );
+        assert!(matches!(
+            TrustLevel::weighted_average(&[(TrustLevel::Trusted, 1.0), (TrustLevel::Basic, -0.5)]),
+            Err(SecurityError::InvalidTrustWeight)
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_aggregate_trust() {
+        let security_system = SecuritySystem::new(Uuid::new_v4(), None);
+        let partner_id = Uuid::new_v4();
+        security_system.establish_trust(partner_id, TrustLevel::Basic, vec![]).await.unwrap();
+        
+        let source = |kind, level, weight| TrustSource { kind, level, weight };
+        let sources = [
+            source(TrustSourceKind::DirectInteraction, TrustLevel::Trusted, 3.0),
+            source(TrustSourceKind::Attestation { attester: Uuid::new_v4() }, TrustLevel::HighlyTrusted, 1.0),
+            source(TrustSourceKind::Transitive { via: Uuid::new_v4() }, TrustLevel::Unknown, -1.0),
+        ];
+        assert_eq!(security_system.aggregate_trust(partner_id, &sources).await, TrustLevel::Trusted);
+        
+        // Without usable sources the recorded level stands
+        assert_eq!(security_system.aggregate_trust(partner_id, &sources[2..]).await, TrustLevel::Basic);
+    }
+    
     #[test]
     fn test_security_event_creation() {
         let event = SecurityEvent {
@@ -1300,6 +1927,7 @@ mod tests {
             time_range: None,
             involved_nodes: None,
             resolution_status: None,
+            ..SecurityEventFilter::default()
         };
         
         assert!(filter.matches(&event));
@@ -1310,11 +1938,81 @@ mod tests {
             time_range: None,
             involved_nodes: None,
             resolution_status: None,
+            ..SecurityEventFilter::default()
         };
         
         assert!(!filter2.matches(&event));
     }
 
+    fn event_with(event_type: SecurityEventType, severity: SecuritySeverity) -> SecurityEvent {
+        SecurityEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type,
+            involved_nodes: Vec::new(),
+            description: "test".to_string(),
+            severity,
+            response_actions: Vec::new(),
+            resolution_status: ResolutionStatus::Open,
+            metadata: HashMap::new(),
+            related_events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_severity_filter_composition() {
+        let medium_up = SecurityEventFilter::by_severity_range(SecuritySeverity::Medium, SecuritySeverity::Critical);
+        assert_eq!(
+            medium_up.severities,
+            Some(vec![SecuritySeverity::Medium, SecuritySeverity::High, SecuritySeverity::Critical])
+        );
+        assert_eq!(SecurityEventFilter::critical_only().severities, Some(vec![SecuritySeverity::Critical]));
+        
+        let auth_failure = event_with(SecurityEventType::AuthenticationFailure, SecuritySeverity::High);
+        let low_violation = event_with(SecurityEventType::PolicyViolation, SecuritySeverity::Low);
+        
+        let auth_only = SecurityEventFilter {
+            event_types: Some(vec![SecurityEventType::AuthenticationFailure]),
+            ..SecurityEventFilter::default()
+        };
+        
+        let both = SecurityEventFilter::high_and_above().and(auth_only.clone());
+        assert!(both.matches(&auth_failure));
+        assert!(!both.matches(&event_with(SecurityEventType::AuthenticationFailure, SecuritySeverity::Low)));
+        assert!(!SecurityEventFilter::critical_only().and(auth_only.clone()).matches(&auth_failure));
+        
+        let violations = SecurityEventFilter {
+            event_types: Some(vec![SecurityEventType::PolicyViolation]),
+            ..SecurityEventFilter::default()
+        };
+        let either = SecurityEventFilter::critical_only().or(violations.clone());
+        assert!(either.matches(&low_violation));
+        assert!(!either.matches(&auth_failure));
+        assert!(either.matches(&event_with(SecurityEventType::KeyRotation, SecuritySeverity::Critical)));
+        
+        // Conjunction of two disjunctions keeps both sides
+        let combined = either.and(auth_only.or(violations));
+        assert!(combined.matches(&low_violation));
+        assert!(!combined.matches(&auth_failure));
+        assert!(combined.matches(&event_with(SecurityEventType::AuthenticationFailure, SecuritySeverity::Critical)));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_to_events_filters_stream() {
+        use futures::StreamExt;
+        
+        let security_system = SecuritySystem::new(Uuid::new_v4(), None);
+        let stream = security_system.subscribe_to_events(SecurityEventFilter::high_and_above());
+        futures::pin_mut!(stream);
+        
+        security_system.log_security_event(event_with(SecurityEventType::AuthorizationCheck, SecuritySeverity::Info)).await;
+        let critical = event_with(SecurityEventType::TrustViolation, SecuritySeverity::Critical);
+        security_system.log_security_event(critical.clone()).await;
+        
+        let received = tokio::time::timeout(Duration::from_secs(1), stream.next()).await.unwrap().unwrap();
+        assert_eq!(received.event_id, critical.event_id);
+    }
+
     #[tokio::test]
     async fn test_security_system_creation() {
         let node_id = Uuid::new_v4();
@@ -1373,4 +2071,133 @@ mod tests {
         ).await.unwrap();
         assert!(!authorized);
     }
+    
+    fn test_event(description: &str) -> SecurityEvent {
+        SecurityEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: SecurityEventType::TrustEstablishment,
+            involved_nodes: vec![Uuid::new_v4()],
+            description: description.to_string(),
+            severity: SecuritySeverity::Info,
+            response_actions: Vec::new(),
+            resolution_status: ResolutionStatus::Open,
+            metadata: HashMap::new(),
+            related_events: Vec::new(),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_constrained_profile_evicts_events() {
+        let config = SecurityConfig::for_profile(ResourceProfile::Constrained);
+        let limit = config.max_events_in_memory;
+        assert_eq!(limit, ResourceProfile::Constrained.limits().max_security_events);
+        
+        let security_system = SecuritySystem::new(Uuid::new_v4(), Some(config));
+        for i in 0..limit + 10 {
+            security_system.log_security_event(test_event(&format!("event {}", i))).await;
+        }
+        
+        let events = security_system.get_security_events(None).await;
+        assert_eq!(events.len(), limit);
+        assert_eq!(events[0].description, "event 10");
+        assert_eq!(security_system.eviction_counters().get(EvictionKind::SecurityEvent), 10);
+        
+        let footprint = security_system.memory_footprint().await;
+        assert_eq!(footprint.entries, limit);
+    }
+    
+    #[tokio::test]
+    async fn test_standard_profile_keeps_default_bounds() {
+        let config = SecurityConfig::for_profile(ResourceProfile::Standard);
+        assert_eq!(config.max_events_in_memory, SecurityConfig::default().max_events_in_memory);
+        
+        let security_system = SecuritySystem::new(Uuid::new_v4(), Some(config));
+        let constrained_limit = ResourceProfile::Constrained.limits().max_security_events;
+        for i in 0..constrained_limit + 10 {
+            security_system.log_security_event(test_event(&format!("event {}", i))).await;
+        }
+        
+        assert_eq!(security_system.get_security_events(None).await.len(), constrained_limit + 10);
+        assert_eq!(security_system.eviction_counters().total(), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_playbooks_run_when_threshold_crossed() {
+        use crate::mesh::incident_response::{BlockNodePlaybook, SnapshotStatePlaybook};
+        
+        let local = Uuid::new_v4();
+        let attacker = Uuid::new_v4();
+        let snapshots = tempfile::tempdir().unwrap();
+        let mut security_system = SecuritySystem::new(local, None);
+        security_system.establish_trust(attacker, TrustLevel::Trusted, Vec::new()).await.unwrap();
+        let block = BlockNodePlaybook::new(security_system.trust_revoker());
+        let trigger = PlaybookTrigger {
+            event_type: SecurityEventType::AuthenticationFailure,
+            min_severity: SecuritySeverity::High,
+            count_threshold: 3,
+            within: Duration::from_secs(60),
+        };
+        security_system.register_playbook(trigger.clone(), Box::new(block));
+        security_system.register_playbook(trigger, Box::new(SnapshotStatePlaybook(snapshots.path().to_path_buf())));
+        
+        let failure = |severity| SecurityEvent {
+            event_type: SecurityEventType::AuthenticationFailure,
+            involved_nodes: vec![local, attacker],
+            severity,
+            ..test_event("auth failure")
+        };
+        let stale = SecurityEvent { timestamp: Utc::now() - chrono::Duration::minutes(5), ..failure(SecuritySeverity::Critical) };
+        security_system.log_security_event(stale).await;
+        security_system.log_security_event(failure(SecuritySeverity::Critical)).await;
+        security_system.log_security_event(failure(SecuritySeverity::Low)).await;
+        security_system.log_security_event(test_event("unrelated")).await;
+        assert_eq!(security_system.get_trust_level(attacker).await, TrustLevel::Trusted);
+        
+        // Only the recent, severe enough failures count towards the threshold
+        security_system.log_security_event(failure(SecuritySeverity::High)).await;
+        assert_eq!(security_system.get_trust_level(attacker).await, TrustLevel::Trusted);
+        security_system.log_security_event(failure(SecuritySeverity::Critical)).await;
+        assert_eq!(security_system.get_trust_level(attacker).await, TrustLevel::Unknown);
+        assert!(!security_system.verify_trust(attacker).await.unwrap());
+        let revocation = security_system.get_trust_history(attacker).await.pop().unwrap();
+        assert_eq!(revocation.event_type, TrustEventType::Revocation);
+        assert_eq!(revocation.evidence.len(), 3);
+        assert_eq!(std::fs::read_dir(snapshots.path()).unwrap().count(), 1);
+        
+        let audit = security_system.get_security_events(None).await.into_iter()
+            .filter(|e| e.event_type == SecurityEventType::ConfigurationChange)
+            .collect::<Vec<_>>();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0].metadata["playbook"], "block-node");
+        assert_eq!(audit[0].resolution_status, ResolutionStatus::AutoResolved);
+        assert_eq!(audit[0].related_events.len(), 3);
+        assert_eq!(audit[1].metadata["playbook"], "snapshot-state");
+    }
+    
+    #[tokio::test]
+    async fn test_rekey_messages_apply_only_from_rotating_node() {
+        use crate::key_rotation::NodeKeys;
+        use crate::networking::zenoh_integration::utils::create_message;
+        
+        let security = SecuritySystem::new(Uuid::new_v4(), None);
+        let partner = Uuid::new_v4();
+        let mut keys = NodeKeys::generate().unwrap();
+        security.establish_trust(partner, TrustLevel::Trusted, Vec::new()).await.unwrap();
+        assert!(security.record_public_key(partner, keys.public_key()).await);
+        let record = keys.rotate(partner, NodeKeys::generate().unwrap(), Utc::now());
+        let rekey = |from: Uuid, context: &str| create_message(
+            from,
+            None,
+            MessageType::SystemControl,
+            ControlEnvelope::new(ControlCommand::Rekey { record: record.clone() }).encode(),
+            Some(context.to_string()),
+        );
+        
+        assert!(!security.handle_rekey_message(&rekey(partner, "other")).await.unwrap());
+        assert!(!security.handle_rekey_message(&rekey(Uuid::new_v4(), KEY_ROTATION_CONTEXT)).await.unwrap());
+        assert!(security.handle_rekey_message(&rekey(partner, KEY_ROTATION_CONTEXT)).await.unwrap());
+        let relationship = security.trust_relationships().await.into_iter().find(|r| r.partner_id == partner).unwrap();
+        assert_eq!(relationship.shared_credentials.public_key_fingerprints[&partner.to_string()], keys.fingerprint());
+    }
 }
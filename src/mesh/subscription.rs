@@ -0,0 +1,615 @@
+//! Resource Change Subscriptions
+//!
+//! Lets a node watch a specific resource instead of polling for changes.
+//! The owning node keeps a registry of watchers and pushes change
+//! notifications to them over `NodeCommunication`; the watching node
+//! surfaces those notifications as a typed stream and can optionally store
+//! new versions in its local `Storage`.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tracing::debug;
+use uuid::Uuid;
+
+use super::resource::ResourceState;
+use crate::networking::node_communication::{
+    CommunicationError, DeliveryOptions, NodeCommunication, OutgoingMessage,
+};
+use crate::networking::zenoh_integration::MessageType;
+use crate::storage::{AccessControl as StorageAccessControl, Storage};
+
+/// Name under which the watcher registry is persisted
+const REGISTRY_RESOURCE_NAME: &str = "resource-watch-registry.json";
+
+/// A change to a watched resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ResourceChange {
+    /// A new version was published
+    NewVersion {
+        /// Version number
+        version: u64,
+        /// MIME type of the content
+        content_type: String,
+        /// Content, included only for watchers that asked for auto-fetch
+        content: Option<Vec<u8>>,
+    },
+    /// Resource metadata changed
+    MetadataChanged {
+        /// Names of the changed fields
+        fields: Vec<String>,
+    },
+    /// Resource moved to a new state
+    StateTransition {
+        /// Previous state
+        from: ResourceState,
+        /// New state
+        to: ResourceState,
+    },
+    /// Resource was deleted; no further notifications follow
+    Deleted,
+    /// Watcher lost access; no further notifications follow
+    AccessRevoked,
+}
+
+impl ResourceChange {
+    /// Whether this change ends the watch
+    pub fn is_final(&self) -> bool {
+        matches!(self, ResourceChange::Deleted | ResourceChange::AccessRevoked)
+    }
+}
+
+/// Change notification sent from owner to watcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceChangeNotification {
+    /// Watched resource
+    pub resource_id: String,
+    /// Node that owns the resource
+    pub owner_node: Uuid,
+    /// What changed
+    pub change: ResourceChange,
+    /// When the change happened
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Messages exchanged between watchers and owners
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ResourceWatchMessage {
+    /// Start watching a resource
+    Subscribe {
+        resource_id: String,
+        watcher: Uuid,
+        auto_fetch: bool,
+    },
+    /// Extend an existing watch
+    Renew {
+        resource_id: String,
+        watcher: Uuid,
+    },
+    /// Stop watching a resource
+    Unsubscribe {
+        resource_id: String,
+        watcher: Uuid,
+    },
+    /// Change notification for a watched resource
+    Notification(ResourceChangeNotification),
+}
+
+/// Watch configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// How long a watch lasts without renewal (seconds)
+    pub watch_ttl_secs: u64,
+    /// How long a watcher may be offline before its watches are dropped (seconds)
+    pub offline_timeout_secs: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            watch_ttl_secs: 300,
+            offline_timeout_secs: 600,
+        }
+    }
+}
+
+/// A watcher registered with the owning node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchRecord {
+    /// Watched resource
+    pub resource_id: String,
+    /// Watching node
+    pub watcher: Uuid,
+    /// Whether new versions should include their content
+    pub auto_fetch: bool,
+    /// When the watch was first registered
+    pub registered_at: DateTime<Utc>,
+    /// When the watch expires unless renewed
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Owner-side registry of resource watchers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceWatchRegistry {
+    /// Node owning the watched resources
+    owner_node: Uuid,
+    /// Watch configuration
+    config: WatchConfig,
+    /// Watches by resource ID, then watcher
+    watches: HashMap<String, HashMap<Uuid, WatchRecord>>,
+    /// Storage ID of the last persisted copy
+    #[serde(skip)]
+    persisted_id: Option<String>,
+}
+
+impl ResourceWatchRegistry {
+    /// Create an empty registry
+    pub fn new(owner_node: Uuid, config: WatchConfig) -> Self {
+        Self {
+            owner_node,
+            config,
+            watches: HashMap::new(),
+            persisted_id: None,
+        }
+    }
+
+    /// Handle a watch request from a remote node
+    pub fn handle_message(&mut self, message: ResourceWatchMessage, now: DateTime<Utc>) -> Result<()> {
+        match message {
+            ResourceWatchMessage::Subscribe { resource_id, watcher, auto_fetch } => {
+                self.register(&resource_id, watcher, auto_fetch, now);
+                Ok(())
+            }
+            ResourceWatchMessage::Renew { resource_id, watcher } => {
+                self.renew(&resource_id, &watcher, now)
+            }
+            ResourceWatchMessage::Unsubscribe { resource_id, watcher } => {
+                self.unregister(&resource_id, &watcher);
+                Ok(())
+            }
+            ResourceWatchMessage::Notification(_) => {
+                Err(anyhow::anyhow!("Owner registry does not accept notifications"))
+            }
+        }
+    }
+
+    /// Register a watcher for a resource
+    pub fn register(&mut self, resource_id: &str, watcher: Uuid, auto_fetch: bool, now: DateTime<Utc>) -> WatchRecord {
+        let record = WatchRecord {
+            resource_id: resource_id.to_string(),
+            watcher,
+            auto_fetch,
+            registered_at: now,
+            expires_at: now + chrono::Duration::seconds(self.config.watch_ttl_secs as i64),
+        };
+
+        self.watches
+            .entry(resource_id.to_string())
+            .or_default()
+            .insert(watcher, record.clone());
+
+        debug!("Node {} now watching resource {}", watcher, resource_id);
+        record
+    }
+
+    /// Extend a watch by another TTL period
+    pub fn renew(&mut self, resource_id: &str, watcher: &Uuid, now: DateTime<Utc>) -> Result<()> {
+        let ttl = chrono::Duration::seconds(self.config.watch_ttl_secs as i64);
+        let record = self.watches
+            .get_mut(resource_id)
+            .and_then(|watchers| watchers.get_mut(watcher))
+            .ok_or_else(|| anyhow::anyhow!("No watch for {} on {}", watcher, resource_id))?;
+
+        record.expires_at = now + ttl;
+        Ok(())
+    }
+
+    /// Remove a watcher from a resource
+    pub fn unregister(&mut self, resource_id: &str, watcher: &Uuid) -> Option<WatchRecord> {
+        let watchers = self.watches.get_mut(resource_id)?;
+        let removed = watchers.remove(watcher);
+        if watchers.is_empty() {
+            self.watches.remove(resource_id);
+        }
+        removed
+    }
+
+    /// Watchers of a resource, sorted by node ID
+    pub fn watchers(&self, resource_id: &str) -> Vec<Uuid> {
+        let mut watchers: Vec<Uuid> = self.watches
+            .get(resource_id)
+            .map(|w| w.keys().copied().collect())
+            .unwrap_or_default();
+        watchers.sort();
+        watchers
+    }
+
+    /// Build notifications for a change to a resource
+    ///
+    /// `has_access` is checked for every watcher; watchers that no longer
+    /// have access receive a final `AccessRevoked` notification and are
+    /// removed. A `Deleted` change removes all watches for the resource.
+    pub fn notify<F>(
+        &mut self,
+        resource_id: &str,
+        change: ResourceChange,
+        now: DateTime<Utc>,
+        has_access: F,
+    ) -> Vec<(Uuid, ResourceChangeNotification)>
+    where
+        F: Fn(&Uuid) -> bool,
+    {
+        let mut notifications = Vec::new();
+        let mut revoked = Vec::new();
+
+        for watcher in self.watchers(resource_id) {
+            let record = &self.watches[resource_id][&watcher];
+
+            let change = if !has_access(&watcher) {
+                revoked.push(watcher);
+                ResourceChange::AccessRevoked
+            } else {
+                match &change {
+                    ResourceChange::NewVersion { version, content_type, .. } if !record.auto_fetch => {
+                        ResourceChange::NewVersion {
+                            version: *version,
+                            content_type: content_type.clone(),
+                            content: None,
+                        }
+                    }
+                    other => other.clone(),
+                }
+            };
+
+            notifications.push((watcher, ResourceChangeNotification {
+                resource_id: resource_id.to_string(),
+                owner_node: self.owner_node,
+                change,
+                timestamp: now,
+            }));
+        }
+
+        if matches!(change, ResourceChange::Deleted) {
+            self.watches.remove(resource_id);
+        } else {
+            for watcher in revoked {
+                self.unregister(resource_id, &watcher);
+            }
+        }
+
+        notifications
+    }
+
+    /// Drop watches that were not renewed in time
+    pub fn cleanup_expired(&mut self, now: DateTime<Utc>) -> Vec<WatchRecord> {
+        self.remove_where(|record| record.expires_at <= now)
+    }
+
+    /// Drop watches of nodes that have been offline longer than the timeout
+    ///
+    /// `last_seen` maps watcher nodes to when they were last seen; watchers
+    /// missing from the map are left alone.
+    pub fn cleanup_offline(&mut self, last_seen: &HashMap<Uuid, DateTime<Utc>>, now: DateTime<Utc>) -> Vec<WatchRecord> {
+        let timeout = chrono::Duration::seconds(self.config.offline_timeout_secs as i64);
+        self.remove_where(|record| {
+            last_seen.get(&record.watcher).is_some_and(|seen| now - *seen > timeout)
+        })
+    }
+
+    /// Send notifications to their watchers
+    pub async fn send_notifications(
+        communication: &NodeCommunication,
+        notifications: Vec<(Uuid, ResourceChangeNotification)>,
+    ) -> Result<(), CommunicationError> {
+        for (watcher, notification) in notifications {
+            let payload = serde_json::to_vec(&ResourceWatchMessage::Notification(notification))
+                .map_err(|e| CommunicationError::SerializationError(e.to_string()))?;
+
+            communication.send_message(OutgoingMessage {
+                target_node: watcher,
+                message_type: MessageType::ResourceWatch,
+                payload,
+                options: DeliveryOptions::default(),
+                context: None,
+            }).await?;
+        }
+        Ok(())
+    }
+
+    /// Persist the registry so watches survive an owner restart
+    ///
+    /// Replaces any copy previously persisted by this registry and returns
+    /// the new storage ID.
+    pub async fn persist<S: Storage>(&mut self, storage: &mut S) -> Result<String> {
+        let content = serde_json::to_vec(&*self)?;
+        let resource_id = storage.store_resource(
+            REGISTRY_RESOURCE_NAME.to_string(),
+            content,
+            "application/json".to_string(),
+            StorageAccessControl::default(),
+            vec!["resource-watch".to_string()],
+        ).await?;
+
+        if let Some(previous) = self.persisted_id.replace(resource_id.clone()) {
+            let _ = storage.delete_resource(&previous).await;
+        }
+        Ok(resource_id)
+    }
+
+    /// Load a previously persisted registry
+    pub async fn load<S: Storage>(storage: &S, resource_id: &str) -> Result<Self> {
+        let content = storage.get_resource_content(resource_id).await?;
+        let mut registry: Self = serde_json::from_slice(&content)?;
+        registry.persisted_id = Some(resource_id.to_string());
+        Ok(registry)
+    }
+
+    fn remove_where<F: Fn(&WatchRecord) -> bool>(&mut self, predicate: F) -> Vec<WatchRecord> {
+        let mut removed = Vec::new();
+        for watchers in self.watches.values_mut() {
+            let expired: Vec<Uuid> = watchers.values()
+                .filter(|record| predicate(record))
+                .map(|record| record.watcher)
+                .collect();
+            for watcher in expired {
+                if let Some(record) = watchers.remove(&watcher) {
+                    removed.push(record);
+                }
+            }
+        }
+        self.watches.retain(|_, watchers| !watchers.is_empty());
+        removed
+    }
+}
+
+/// Stream of change notifications for one watched resource
+#[derive(Debug)]
+pub struct ResourceWatch {
+    resource_id: String,
+    receiver: mpsc::UnboundedReceiver<ResourceChangeNotification>,
+}
+
+impl ResourceWatch {
+    /// Watched resource
+    pub fn resource_id(&self) -> &str {
+        &self.resource_id
+    }
+
+    /// Wait for the next notification; `None` once the watch has ended
+    pub async fn next_change(&mut self) -> Option<ResourceChangeNotification> {
+        self.receiver.recv().await
+    }
+}
+
+impl futures::Stream for ResourceWatch {
+    type Item = ResourceChangeNotification;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// Watcher-side subscription manager
+#[derive(Debug)]
+pub struct ResourceWatcher {
+    /// This node's ID
+    node_id: Uuid,
+    /// Open streams by resource ID
+    streams: HashMap<String, Vec<mpsc::UnboundedSender<ResourceChangeNotification>>>,
+    /// Resources whose new versions are stored locally
+    auto_fetch: HashMap<String, bool>,
+}
+
+impl ResourceWatcher {
+    /// Create a watcher for this node
+    pub fn new(node_id: Uuid) -> Self {
+        Self {
+            node_id,
+            streams: HashMap::new(),
+            auto_fetch: HashMap::new(),
+        }
+    }
+
+    /// Subscribe to changes of a resource
+    ///
+    /// Send the message from `subscribe_request` to the owner to start
+    /// receiving notifications.
+    pub fn subscribe_resource(&mut self, resource_id: &str) -> ResourceWatch {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.streams.entry(resource_id.to_string()).or_default().push(sender);
+
+        ResourceWatch {
+            resource_id: resource_id.to_string(),
+            receiver,
+        }
+    }
+
+    /// Build the subscribe request for the owner of a resource
+    pub fn subscribe_request(&mut self, resource_id: &str, auto_fetch: bool) -> ResourceWatchMessage {
+        self.auto_fetch.insert(resource_id.to_string(), auto_fetch);
+        ResourceWatchMessage::Subscribe {
+            resource_id: resource_id.to_string(),
+            watcher: self.node_id,
+            auto_fetch,
+        }
+    }
+
+    /// Build a renewal for every resource with open streams
+    pub fn renewal_requests(&self) -> Vec<ResourceWatchMessage> {
+        let mut resource_ids: Vec<&String> = self.streams.keys().collect();
+        resource_ids.sort();
+        resource_ids.into_iter()
+            .map(|resource_id| ResourceWatchMessage::Renew {
+                resource_id: resource_id.clone(),
+                watcher: self.node_id,
+            })
+            .collect()
+    }
+
+    /// Deliver a notification to open streams, storing new versions when auto-fetch is on
+    ///
+    /// Returns the local storage ID of an auto-fetched version.
+    pub async fn handle_notification<S: Storage>(
+        &mut self,
+        notification: ResourceChangeNotification,
+        storage: &mut S,
+    ) -> Result<Option<String>> {
+        let mut stored_id = None;
+
+        if let ResourceChange::NewVersion { version, content_type, content: Some(content) } = &notification.change {
+            if self.auto_fetch.get(&notification.resource_id).copied().unwrap_or(false) {
+                let id = storage.store_resource(
+                    format!("{}@v{}", notification.resource_id, version),
+                    content.clone(),
+                    content_type.clone(),
+                    StorageAccessControl::default(),
+                    vec!["resource-watch".to_string(), notification.resource_id.clone()],
+                ).await?;
+                stored_id = Some(id);
+            }
+        }
+
+        let resource_id = notification.resource_id.clone();
+        let is_final = notification.change.is_final();
+
+        if let Some(senders) = self.streams.get_mut(&resource_id) {
+            senders.retain(|sender| sender.send(notification.clone()).is_ok());
+        }
+
+        if is_final {
+            // Dropping the senders ends the streams
+            self.streams.remove(&resource_id);
+            self.auto_fetch.remove(&resource_id);
+        }
+
+        Ok(stored_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use futures::StreamExt;
+
+    fn new_version(version: u64) -> ResourceChange {
+        ResourceChange::NewVersion {
+            version,
+            content_type: "text/plain".to_string(),
+            content: Some(format!("v{}", version).into_bytes()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_change_notification_and_auto_fetch() {
+        let owner = Uuid::new_v4();
+        let watcher_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        let mut registry = ResourceWatchRegistry::new(owner, WatchConfig::default());
+        let mut watcher = ResourceWatcher::new(watcher_id);
+        let mut storage = MemoryStorage::new();
+
+        let mut watch = watcher.subscribe_resource("doc");
+        registry.handle_message(watcher.subscribe_request("doc", true), now).unwrap();
+        assert_eq!(registry.watchers("doc"), vec![watcher_id]);
+
+        let notifications = registry.notify("doc", new_version(2), now, |_| true);
+        assert_eq!(notifications.len(), 1);
+
+        let (_, notification) = notifications.into_iter().next().unwrap();
+        let stored = watcher.handle_notification(notification, &mut storage).await.unwrap();
+
+        let received = watch.next().await.unwrap();
+        assert!(matches!(received.change, ResourceChange::NewVersion { version: 2, .. }));
+
+        let content = storage.get_resource_content(&stored.unwrap()).await.unwrap();
+        assert_eq!(content, b"v2");
+    }
+
+    #[tokio::test]
+    async fn test_content_stripped_without_auto_fetch() {
+        let mut registry = ResourceWatchRegistry::new(Uuid::new_v4(), WatchConfig::default());
+        registry.register("doc", Uuid::new_v4(), false, Utc::now());
+
+        let notifications = registry.notify("doc", new_version(1), Utc::now(), |_| true);
+        assert!(matches!(
+            notifications[0].1.change,
+            ResourceChange::NewVersion { content: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_renewal_and_expiry() {
+        let config = WatchConfig::default();
+        let ttl = chrono::Duration::seconds(config.watch_ttl_secs as i64);
+        let mut registry = ResourceWatchRegistry::new(Uuid::new_v4(), config);
+        let renewed = Uuid::new_v4();
+        let stale = Uuid::new_v4();
+        let now = Utc::now();
+
+        registry.register("doc", renewed, false, now);
+        registry.register("doc", stale, false, now);
+        registry.renew("doc", &renewed, now + ttl / 2).unwrap();
+
+        let removed = registry.cleanup_expired(now + ttl);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].watcher, stale);
+        assert_eq!(registry.watchers("doc"), vec![renewed]);
+
+        // Offline watchers are dropped after the timeout
+        let mut last_seen = HashMap::new();
+        last_seen.insert(renewed, now - chrono::Duration::hours(1));
+        assert_eq!(registry.cleanup_offline(&last_seen, now).len(), 1);
+        assert!(registry.watchers("doc").is_empty());
+        assert!(registry.renew("doc", &renewed, now).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_access_revocation() {
+        let mut registry = ResourceWatchRegistry::new(Uuid::new_v4(), WatchConfig::default());
+        let mut watcher = ResourceWatcher::new(Uuid::new_v4());
+        let mut storage = MemoryStorage::new();
+        let allowed = Uuid::new_v4();
+
+        let mut watch = watcher.subscribe_resource("doc");
+        registry.handle_message(watcher.subscribe_request("doc", false), Utc::now()).unwrap();
+        registry.register("doc", allowed, false, Utc::now());
+
+        let notifications = registry.notify("doc", ResourceChange::MetadataChanged {
+            fields: vec!["name".to_string()],
+        }, Utc::now(), |node| *node == allowed);
+
+        let revoked: Vec<_> = notifications.into_iter()
+            .filter(|(_, n)| matches!(n.change, ResourceChange::AccessRevoked))
+            .collect();
+        assert_eq!(revoked.len(), 1);
+        assert_eq!(registry.watchers("doc"), vec![allowed]);
+
+        for (_, notification) in revoked {
+            watcher.handle_notification(notification, &mut storage).await.unwrap();
+        }
+        assert!(matches!(watch.next().await.unwrap().change, ResourceChange::AccessRevoked));
+        assert!(watch.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_registry_survives_restart() {
+        let owner = Uuid::new_v4();
+        let mut storage = MemoryStorage::new();
+        let mut registry = ResourceWatchRegistry::new(owner, WatchConfig::default());
+        let watcher = Uuid::new_v4();
+        registry.register("doc", watcher, true, Utc::now());
+
+        let first = registry.persist(&mut storage).await.unwrap();
+        let second = registry.persist(&mut storage).await.unwrap();
+        assert!(storage.get_resource(&first).await.is_err());
+
+        let restored = ResourceWatchRegistry::load(&storage, &second).await.unwrap();
+        assert_eq!(restored.watchers("doc"), vec![watcher]);
+    }
+}
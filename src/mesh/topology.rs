@@ -0,0 +1,669 @@
+//! Mesh Topology Graphs
+//!
+//! Builds a filtered view of the mesh from the manager's node table and the
+//! discovery registry, and renders it as Graphviz DOT or as a JSON node/edge
+//! graph for web visualizers. Nodes and edges are sorted by ID so exports
+//! of large meshes can be diffed. Incremental changes to the node table are
+//! published as debounced [`TopologyDiff`]s.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::warn;
+use uuid::Uuid;
+
+use super::discovery::{MeshNode, TrustLevel};
+use super::manager::{ConnectionState, LocalNode, RemoteNode};
+
+/// Metadata key holding a node's organization namespace
+pub const ORGANIZATION_METADATA_KEY: &str = "organization";
+
+/// Output format for topology graph exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyGraphFormat {
+    /// Graphviz DOT language
+    Dot,
+    /// JSON object with `nodes` and `edges` arrays
+    Json,
+}
+
+impl FromStr for TopologyGraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" => Ok(TopologyGraphFormat::Dot),
+            "json" => Ok(TopologyGraphFormat::Json),
+            other => Err(format!("Unknown topology format: {}", other)),
+        }
+    }
+}
+
+/// Which remote nodes to include in a topology export
+///
+/// The local node is always included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TopologyFilter {
+    /// Only include connected nodes
+    pub online_only: bool,
+    /// Only include nodes at or above this trust level
+    pub min_trust: Option<TrustLevel>,
+    /// Only include nodes in this organization namespace
+    pub organization: Option<String>,
+}
+
+impl TopologyFilter {
+    fn includes(&self, node: &TopologyNode) -> bool {
+        if self.online_only && !node.online {
+            return false;
+        }
+        if let Some(ref min_trust) = self.min_trust {
+            if node.trust_level < *min_trust {
+                return false;
+            }
+        }
+        if let Some(ref organization) = self.organization {
+            if node.organization.as_ref() != Some(organization) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A node in a topology graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopologyNode {
+    /// Node identifier
+    pub id: Uuid,
+    /// Display name
+    pub label: String,
+    /// Whether this is the exporting node
+    pub local: bool,
+    /// Trust level
+    pub trust_level: TrustLevel,
+    /// Connection state as seen by the exporting node
+    pub connection_state: ConnectionState,
+    /// Whether the node is connected
+    pub online: bool,
+    /// Whether the node is quarantined
+    pub quarantined: bool,
+    /// Organization namespace, if known
+    pub organization: Option<String>,
+}
+
+/// A directed link between two nodes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TopologyEdge {
+    /// Source node
+    pub from: Uuid,
+    /// Target node
+    pub to: Uuid,
+    /// Average latency in milliseconds, if measured
+    pub latency_ms: Option<f64>,
+    /// Trust level of the target node
+    pub trust_level: TrustLevel,
+}
+
+/// Filtered, sorted topology of the mesh
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TopologyGraph {
+    /// Nodes sorted by ID, local node included
+    pub nodes: Vec<TopologyNode>,
+    /// Edges sorted by source then target
+    pub edges: Vec<TopologyEdge>,
+}
+
+impl TopologyGraph {
+    /// Build a graph from the node table and discovery registry
+    ///
+    /// Nodes in `remote` carry connection state, latency and quarantine;
+    /// nodes only in `discovered` appear as disconnected. Discovery trust
+    /// levels take precedence since trust updates land there. Peer links
+    /// are read from a comma-separated `peers` metadata entry.
+    pub fn build(
+        local: &LocalNode,
+        remote: &[RemoteNode],
+        discovered: &[&MeshNode],
+        filter: &TopologyFilter,
+    ) -> Self {
+        let discovered: BTreeMap<Uuid, &MeshNode> = discovered.iter().map(|node| (node.node_id, *node)).collect();
+        let remote: BTreeMap<Uuid, &RemoteNode> = remote.iter().map(|node| (node.id, node)).collect();
+
+        let mut nodes = BTreeMap::new();
+        nodes.insert(local.id, TopologyNode {
+            id: local.id,
+            label: display_name(local.id, &[&local.metadata]),
+            local: true,
+            trust_level: TrustLevel::HighlyTrusted,
+            connection_state: ConnectionState::Connected,
+            online: true,
+            quarantined: false,
+            organization: local.metadata.get(ORGANIZATION_METADATA_KEY).cloned(),
+        });
+
+        let ids: BTreeSet<Uuid> = remote.keys().chain(discovered.keys()).copied().collect();
+        for id in ids {
+            if id == local.id {
+                continue;
+            }
+            let known = remote.get(&id);
+            let seen = discovered.get(&id);
+            let metadata: Vec<_> = known.map(|n| &n.metadata).into_iter()
+                .chain(seen.map(|n| &n.metadata))
+                .collect();
+
+            let connection_state = known.map(|n| n.connection_state.clone()).unwrap_or(ConnectionState::Disconnected);
+            let node = TopologyNode {
+                id,
+                label: display_name(id, &metadata),
+                local: false,
+                trust_level: seen.map(|n| n.trust_level.clone())
+                    .or_else(|| known.map(|n| n.trust_level.clone()))
+                    .unwrap_or(TrustLevel::Unknown),
+                online: connection_state == ConnectionState::Connected,
+                connection_state,
+                quarantined: known.map(|n| n.quarantined).unwrap_or(false),
+                organization: metadata.iter().find_map(|m| m.get(ORGANIZATION_METADATA_KEY).cloned()),
+            };
+
+            if filter.includes(&node) {
+                nodes.insert(id, node);
+            }
+        }
+
+        let mut edges = BTreeMap::new();
+        for node in nodes.values().filter(|node| !node.local) {
+            edges.insert((local.id, node.id), TopologyEdge {
+                from: local.id,
+                to: node.id,
+                latency_ms: remote.get(&node.id).and_then(|n| n.avg_latency_ms),
+                trust_level: node.trust_level.clone(),
+            });
+
+            let peers = remote.get(&node.id).and_then(|n| n.metadata.get("peers"))
+                .or_else(|| discovered.get(&node.id).and_then(|n| n.metadata.get("peers")));
+            let peers = peers.map(|peers| peers.split(',').filter_map(|p| Uuid::parse_str(p.trim()).ok()).collect::<Vec<_>>())
+                .unwrap_or_default();
+
+            for peer in peers {
+                if let Some(peer_node) = nodes.get(&peer) {
+                    edges.entry((node.id, peer)).or_insert_with(|| TopologyEdge {
+                        from: node.id,
+                        to: peer,
+                        latency_ms: None,
+                        trust_level: peer_node.trust_level.clone(),
+                    });
+                }
+            }
+        }
+
+        Self {
+            nodes: nodes.into_values().collect(),
+            edges: edges.into_values().collect(),
+        }
+    }
+
+    /// Render in the given format
+    pub fn render(&self, format: TopologyGraphFormat) -> Result<String> {
+        match format {
+            TopologyGraphFormat::Dot => Ok(self.to_dot()),
+            TopologyGraphFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Render as a Graphviz DOT graph
+    ///
+    /// Fill color shows connection health (orange when quarantined), border
+    /// width shows trust, and edges are labeled with measured latency.
+    pub fn to_dot(&self) -> String {
+        let mut dot = DotWriter::new();
+
+        for node in &self.nodes {
+            let (fill, style) = if node.local {
+                ("lightblue", "filled")
+            } else if node.quarantined {
+                ("orange", "\"filled,dashed\"")
+            } else {
+                (health_color(&node.connection_state), "filled")
+            };
+            let trust = format!("{:?}", node.trust_level);
+            dot.node(
+                node.id,
+                &[&node.label, &trust],
+                &format!("fillcolor={}, style={}, penwidth={}", fill, style, node.trust_level.ordinal() + 1),
+            );
+        }
+
+        for edge in &self.edges {
+            let label = edge.latency_ms.map(|latency| format!("label=\"{:.1} ms\"", latency));
+            dot.edge(edge.from, edge.to, label.as_deref());
+        }
+
+        dot.finish()
+    }
+
+    /// Render as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Components that can produce a topology graph
+#[async_trait::async_trait]
+pub trait TopologySource: Send + Sync {
+    /// Current topology, filtered
+    async fn topology(&self, filter: &TopologyFilter) -> TopologyGraph;
+}
+
+/// Node identifier in topology diffs
+pub type NodeId = Uuid;
+
+/// Capacity of the topology diff broadcast channel
+const TOPOLOGY_DIFF_CAPACITY: usize = 64;
+
+/// Summary of a node added to the topology
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MeshNodeSummary {
+    /// Node identifier
+    pub id: NodeId,
+    /// Display name
+    pub label: String,
+    /// Trust level
+    pub trust_level: TrustLevel,
+    /// Connection state
+    pub connection_state: ConnectionState,
+}
+
+impl From<&RemoteNode> for MeshNodeSummary {
+    fn from(node: &RemoteNode) -> Self {
+        Self {
+            id: node.id,
+            label: display_name(node.id, &[&node.metadata]),
+            trust_level: node.trust_level.clone(),
+            connection_state: node.connection_state.clone(),
+        }
+    }
+}
+
+/// A known node whose connection state changed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConnectionChange {
+    /// Node identifier
+    pub node_id: NodeId,
+    /// State before the change
+    pub old_state: ConnectionState,
+    /// State after the change
+    pub new_state: ConnectionState,
+}
+
+/// Changes to the node table between two snapshots
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TopologyDiff {
+    /// Nodes that appeared, sorted by ID
+    pub added_nodes: Vec<MeshNodeSummary>,
+    /// Nodes that disappeared, sorted
+    pub removed_nodes: Vec<NodeId>,
+    /// Connection state changes of nodes present in both snapshots, sorted by node
+    pub connection_changes: Vec<ConnectionChange>,
+}
+
+impl TopologyDiff {
+    /// Differences going from `before` to `after`
+    pub fn between(before: &HashMap<Uuid, RemoteNode>, after: &HashMap<Uuid, RemoteNode>) -> Self {
+        let mut diff = TopologyDiff::default();
+        for (id, node) in after {
+            match before.get(id) {
+                None => diff.added_nodes.push(MeshNodeSummary::from(node)),
+                Some(old) if old.connection_state != node.connection_state => {
+                    diff.connection_changes.push(ConnectionChange {
+                        node_id: *id,
+                        old_state: old.connection_state.clone(),
+                        new_state: node.connection_state.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed_nodes = before.keys().filter(|id| !after.contains_key(id)).copied().collect();
+        
+        diff.added_nodes.sort_by_key(|n| n.id);
+        diff.removed_nodes.sort();
+        diff.connection_changes.sort_by_key(|c| c.node_id);
+        diff
+    }
+    
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty() && self.removed_nodes.is_empty() && self.connection_changes.is_empty()
+    }
+}
+
+/// Publishes debounced diffs of a node table to any number of subscribers
+///
+/// Callers signal changes with [`notify`](Self::notify). The first signal
+/// opens a window of `debounce`; when it closes, one diff against the last
+/// published snapshot is broadcast, so a burst of changes yields one diff.
+/// The background task ends when the publisher is dropped.
+#[derive(Debug)]
+pub struct TopologyDiffPublisher {
+    changes: mpsc::UnboundedSender<()>,
+    diffs: broadcast::Sender<TopologyDiff>,
+}
+
+impl TopologyDiffPublisher {
+    /// Start publishing diffs of `nodes` against their current state
+    pub async fn spawn(nodes: Arc<RwLock<HashMap<Uuid, RemoteNode>>>, debounce: Duration) -> Self {
+        let (changes, mut change_rx) = mpsc::unbounded_channel::<()>();
+        let (diffs, _) = broadcast::channel(TOPOLOGY_DIFF_CAPACITY);
+        let sender = diffs.clone();
+        
+        // Changes made before the task first runs still count
+        let mut snapshot = nodes.read().await.clone();
+        tokio::spawn(async move {
+            while change_rx.recv().await.is_some() {
+                tokio::time::sleep(debounce).await;
+                while change_rx.try_recv().is_ok() {}
+                
+                let current = nodes.read().await.clone();
+                let diff = TopologyDiff::between(&snapshot, &current);
+                snapshot = current;
+                if !diff.is_empty() {
+                    // No subscribers is not an error
+                    let _ = sender.send(diff);
+                }
+            }
+        });
+        
+        Self { changes, diffs }
+    }
+    
+    /// Signal that the node table may have changed
+    pub fn notify(&self) {
+        let _ = self.changes.send(());
+    }
+    
+    /// Stream of diffs published from now on
+    pub fn subscribe(&self) -> impl futures::Stream<Item = TopologyDiff> {
+        let receiver = self.diffs.subscribe();
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(diff) => return Some((diff, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Topology diff subscriber lagged, skipped {} diffs", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+/// Display name from the first metadata map that has one, falling back to a short ID
+fn display_name(id: Uuid, metadata: &[&std::collections::HashMap<String, String>]) -> String {
+    metadata.iter()
+        .find_map(|m| m.get("display_name").cloned())
+        .unwrap_or_else(|| id.to_string()[..8].to_string())
+}
+
+/// Graphviz DOT writer shared by the topology exporters
+///
+/// Identifiers and label rows are escaped; attributes are written as given.
+pub(crate) struct DotWriter {
+    out: String,
+    depth: usize,
+}
+
+impl DotWriter {
+    pub(crate) fn new() -> Self {
+        let mut dot = Self { out: String::from("digraph mesh {\n"), depth: 1 };
+        dot.line("rankdir=LR;");
+        dot.line("node [shape=box, style=filled];");
+        dot
+    }
+
+    /// Node `id` labeled with one row per entry of `rows`
+    pub(crate) fn node(&mut self, id: impl std::fmt::Display, rows: &[&str], attributes: &str) {
+        let label = rows.iter().map(|row| dot_escape(row)).collect::<Vec<_>>().join("\\n");
+        self.line(&format!("\"{}\" [label=\"{}\", {}];", dot_escape(&id.to_string()), label, attributes));
+    }
+
+    pub(crate) fn edge(&mut self, from: impl std::fmt::Display, to: impl std::fmt::Display, attributes: Option<&str>) {
+        let (from, to) = (dot_escape(&from.to_string()), dot_escape(&to.to_string()));
+        match attributes {
+            Some(attributes) => self.line(&format!("\"{}\" -> \"{}\" [{}];", from, to, attributes)),
+            None => self.line(&format!("\"{}\" -> \"{}\";", from, to)),
+        }
+    }
+
+    /// Subgraph `cluster_{index}` holding whatever `body` writes
+    pub(crate) fn cluster(&mut self, index: usize, label: &str, body: impl FnOnce(&mut Self)) {
+        self.line(&format!("subgraph cluster_{} {{", index));
+        self.depth += 1;
+        self.line(&format!("label=\"{}\";", dot_escape(label)));
+        body(self);
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.out.push_str("}\n");
+        self.out
+    }
+
+    fn line(&mut self, line: &str) {
+        self.out.push_str(&"    ".repeat(self.depth));
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+}
+
+/// Escape `value` for a quoted DOT string
+pub(crate) fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn health_color(state: &ConnectionState) -> &'static str {
+    match state {
+        ConnectionState::Connected => "green",
+        ConnectionState::Connecting => "yellow",
+        ConnectionState::Disconnected => "gray",
+        ConnectionState::Failed { .. } | ConnectionState::Lost { .. } => "red",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::discovery::{ArchetypalRole, NodeCapabilities};
+    use chrono::Utc;
+
+    fn local() -> LocalNode {
+        let mut metadata = HashMap::new();
+        metadata.insert("display_name".to_string(), "hub".to_string());
+        LocalNode {
+            id: Uuid::from_u128(1),
+            capabilities: NodeCapabilities::default(),
+            metadata,
+            metadata_visibility: Default::default(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn remote(id: u128, name: &str, org: &str, trust: TrustLevel, state: ConnectionState) -> RemoteNode {
+        let mut node = RemoteNode::new(Uuid::from_u128(id), NodeCapabilities::default(), trust);
+        node.connection_state = state;
+        node.set_metadata("display_name".to_string(), name.to_string());
+        node.set_metadata(ORGANIZATION_METADATA_KEY.to_string(), org.to_string());
+        node
+    }
+
+    fn fake_mesh() -> (Vec<RemoteNode>, Vec<MeshNode>) {
+        let mut alpha = remote(2, "alpha", "acme", TrustLevel::Trusted, ConnectionState::Connected);
+        alpha.record_latency(12.5);
+        alpha.set_metadata("peers".to_string(), Uuid::from_u128(3).to_string());
+        let mut beta = remote(3, "beta", "acme", TrustLevel::Basic, ConnectionState::Lost { last_error: None });
+        beta.quarantined = true;
+        let gamma = remote(4, "gamma", "other", TrustLevel::Verified, ConnectionState::Connected);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("display_name".to_string(), "delta".to_string());
+        let delta = MeshNode {
+            node_id: Uuid::from_u128(5),
+            capabilities: NodeCapabilities::default(),
+            archetypal_role: ArchetypalRole::Sage,
+            trust_level: TrustLevel::Unknown,
+            last_seen: Utc::now(),
+            metadata,
+            context_data: HashMap::new(),
+        };
+
+        (vec![gamma, beta, alpha], vec![delta])
+    }
+
+    fn build(filter: &TopologyFilter) -> TopologyGraph {
+        let (remote, discovered) = fake_mesh();
+        let discovered: Vec<&MeshNode> = discovered.iter().collect();
+        TopologyGraph::build(&local(), &remote, &discovered, filter)
+    }
+
+    #[test]
+    fn test_dot_snapshot() {
+        let dot = build(&TopologyFilter::default()).to_dot();
+        let expected = "\
+digraph mesh {
+    rankdir=LR;
+    node [shape=box, style=filled];
+    \"00000000-0000-0000-0000-000000000001\" [label=\"hub\\nHighlyTrusted\", fillcolor=lightblue, style=filled, penwidth=5];
+    \"00000000-0000-0000-0000-000000000002\" [label=\"alpha\\nTrusted\", fillcolor=green, style=filled, penwidth=4];
+    \"00000000-0000-0000-0000-000000000003\" [label=\"beta\\nBasic\", fillcolor=orange, style=\"filled,dashed\", penwidth=2];
+    \"00000000-0000-0000-0000-000000000004\" [label=\"gamma\\nVerified\", fillcolor=green, style=filled, penwidth=3];
+    \"00000000-0000-0000-0000-000000000005\" [label=\"delta\\nUnknown\", fillcolor=gray, style=filled, penwidth=1];
+    \"00000000-0000-0000-0000-000000000001\" -> \"00000000-0000-0000-0000-000000000002\" [label=\"12.5 ms\"];
+    \"00000000-0000-0000-0000-000000000001\" -> \"00000000-0000-0000-0000-000000000003\";
+    \"00000000-0000-0000-0000-000000000001\" -> \"00000000-0000-0000-0000-000000000004\";
+    \"00000000-0000-0000-0000-000000000001\" -> \"00000000-0000-0000-0000-000000000005\";
+    \"00000000-0000-0000-0000-000000000002\" -> \"00000000-0000-0000-0000-000000000003\";
+}
+";
+        assert_eq!(dot, expected);
+    }
+
+    #[test]
+    fn test_dot_escapes_backslashes_and_quotes() {
+        let mut local = local();
+        local.metadata.insert("display_name".to_string(), "C:\\mesh \"hub\"\\".to_string());
+        let graph = TopologyGraph::build(&local, &[], &[], &TopologyFilter::default());
+        assert!(graph.to_dot().contains("[label=\"C:\\\\mesh \\\"hub\\\"\\\\\\nHighlyTrusted\""));
+    }
+
+    #[test]
+    fn test_json_snapshot_with_filters() {
+        let filter = TopologyFilter {
+            online_only: true,
+            min_trust: Some(TrustLevel::Verified),
+            organization: Some("acme".to_string()),
+        };
+        let json = build(&filter).to_json().unwrap();
+        let expected = r#"{
+  "nodes": [
+    {
+      "id": "00000000-0000-0000-0000-000000000001",
+      "label": "hub",
+      "local": true,
+      "trust_level": "HighlyTrusted",
+      "connection_state": "Connected",
+      "online": true,
+      "quarantined": false,
+      "organization": null
+    },
+    {
+      "id": "00000000-0000-0000-0000-000000000002",
+      "label": "alpha",
+      "local": false,
+      "trust_level": "Trusted",
+      "connection_state": "Connected",
+      "online": true,
+      "quarantined": false,
+      "organization": "acme"
+    }
+  ],
+  "edges": [
+    {
+      "from": "00000000-0000-0000-0000-000000000001",
+      "to": "00000000-0000-0000-0000-000000000002",
+      "latency_ms": 12.5,
+      "trust_level": "Trusted"
+    }
+  ]
+}"#;
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn test_filters_drop_edges_to_excluded_nodes() {
+        let online = build(&TopologyFilter { online_only: true, ..TopologyFilter::default() });
+        let ids: Vec<u128> = online.nodes.iter().map(|n| n.id.as_u128()).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+        assert!(online.edges.iter().all(|e| e.to.as_u128() != 3));
+
+        let trusted = build(&TopologyFilter { min_trust: Some(TrustLevel::Basic), ..TopologyFilter::default() });
+        assert!(trusted.nodes.iter().all(|n| n.id.as_u128() != 5));
+
+        assert_eq!("DOT".parse::<TopologyGraphFormat>(), Ok(TopologyGraphFormat::Dot));
+        assert!("svg".parse::<TopologyGraphFormat>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_topology_diff_stream_debounces() {
+        use futures::StreamExt;
+
+        let nodes = Arc::new(RwLock::new(HashMap::new()));
+        let mut existing = RemoteNode::new(Uuid::from_u128(2), NodeCapabilities::default(), TrustLevel::Basic);
+        existing.connection_state = ConnectionState::Connecting;
+        nodes.write().await.insert(existing.id, existing.clone());
+
+        let publisher = TopologyDiffPublisher::spawn(nodes.clone(), Duration::from_millis(50)).await;
+        let first = publisher.subscribe();
+        let second = publisher.subscribe();
+        futures::pin_mut!(first);
+        futures::pin_mut!(second);
+
+        // A burst of changes inside one window yields a single diff
+        {
+            let mut table = nodes.write().await;
+            let added = RemoteNode::new(Uuid::from_u128(3), NodeCapabilities::default(), TrustLevel::Verified);
+            table.insert(added.id, added);
+            table.get_mut(&existing.id).unwrap().connection_state = ConnectionState::Connected;
+        }
+        publisher.notify();
+        publisher.notify();
+
+        let diff = first.next().await.unwrap();
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_nodes[0].id, Uuid::from_u128(3));
+        assert_eq!(diff.connection_changes, vec![ConnectionChange {
+            node_id: existing.id,
+            old_state: ConnectionState::Connecting,
+            new_state: ConnectionState::Connected,
+        }]);
+        assert!(diff.removed_nodes.is_empty());
+        assert_eq!(second.next().await.unwrap(), diff);
+
+        // A signal without a change is not published
+        publisher.notify();
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        nodes.write().await.remove(&existing.id);
+        publisher.notify();
+        let diff = first.next().await.unwrap();
+        assert_eq!(diff.removed_nodes, vec![existing.id]);
+        assert!(diff.added_nodes.is_empty());
+
+        assert!(TopologyDiff::default().is_empty());
+    }
+}
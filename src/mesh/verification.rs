@@ -0,0 +1,514 @@
+//! Offline verification of node announcements and identity files
+//!
+//! These functions need no running mesh or [`SecuritySystem`](super::SecuritySystem):
+//! they check a captured [`SignedAnnouncement`] against a set of trusted keys,
+//! or summarize an identity file, and produce serializable reports that can
+//! be attached to onboarding reviews or incident tickets.
+
+use anyhow::Result;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, ED25519};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::key_rotation::{key_fingerprint, NodeKeys};
+use crate::mesh::node::{NodeAnnouncement, NodeInfo};
+use crate::networking::node_discovery::{NodeAnnouncement as DiscoveryAnnouncement, NodeDiscovery};
+use crate::storage::content_checksum;
+
+/// Protocol major versions this build can verify
+pub const SUPPORTED_PROTOCOL_MAJOR_VERSIONS: &[u32] = &[1];
+
+/// Largest plausible jump from the last sequence number seen for a node
+pub const MAX_SEQUENCE_GAP: u64 = 1 << 20;
+
+/// Fingerprint of the configuration a node announces: its type, capabilities,
+/// endpoints and version
+pub fn config_fingerprint(info: &NodeInfo) -> Result<String> {
+    // Going through `Value` sorts map keys, so the encoding is stable
+    let config = serde_json::to_value((&info.node_type, &info.capabilities, &info.endpoints, &info.version))?;
+    Ok(content_checksum(&serde_json::to_vec(&config)?))
+}
+
+/// What a node signs when announcing itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncementBody {
+    /// The announcement
+    pub announcement: NodeAnnouncement,
+    /// Increases with every announcement the node sends
+    pub sequence: u64,
+    /// [`config_fingerprint`] of the announced node info
+    pub config_fingerprint: String,
+}
+
+/// A node announcement as captured on the wire
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedAnnouncement {
+    /// JSON-encoded [`AnnouncementBody`], exactly as signed
+    pub body: Vec<u8>,
+    /// Signer's Ed25519 public key
+    pub public_key: Vec<u8>,
+    /// Signature over `body`
+    pub signature: Vec<u8>,
+}
+
+impl SignedAnnouncement {
+    /// Sign an announcement with the node's keys
+    pub fn sign(announcement: NodeAnnouncement, sequence: u64, keys: &NodeKeys) -> Result<Self> {
+        let config_fingerprint = config_fingerprint(&announcement.node_info)?;
+        let body = serde_json::to_vec(&AnnouncementBody { announcement, sequence, config_fingerprint })?;
+        Ok(Self {
+            signature: keys.sign(&body),
+            public_key: keys.public_key().to_vec(),
+            body,
+        })
+    }
+
+    /// Wire encoding
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+}
+
+/// A node's trusted public key and the last announcement sequence seen from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedKey {
+    /// Ed25519 public key
+    pub public_key: Vec<u8>,
+    /// Highest sequence number already accepted, if any
+    pub last_sequence: Option<u64>,
+}
+
+/// Public keys announcements are verified against, by node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustedKeys {
+    pub keys: HashMap<Uuid, TrustedKey>,
+}
+
+impl TrustedKeys {
+    /// Trust `public_key` for `node_id`
+    pub fn with_key(mut self, node_id: Uuid, public_key: Vec<u8>) -> Self {
+        self.keys.insert(node_id, TrustedKey { public_key, last_sequence: None });
+        self
+    }
+
+    /// Record the last sequence number accepted from `node_id`
+    pub fn with_last_sequence(mut self, node_id: Uuid, sequence: u64) -> Self {
+        if let Some(key) = self.keys.get_mut(&node_id) {
+            key.last_sequence = Some(sequence);
+        }
+        self
+    }
+}
+
+/// One check performed on an announcement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VerificationCheck {
+    /// The bytes decode as a signed announcement
+    Decoding,
+    /// The signature is valid and made by a trusted key for the node
+    Signature,
+    /// The sequence number is newer than the last seen, by a plausible margin
+    Sequence,
+    /// The protocol version is one this build understands
+    ProtocolVersion,
+    /// The claimed config fingerprint matches the announced node info
+    ConfigFingerprint,
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CheckOutcome {
+    Passed,
+    Failed(String),
+    /// Not run because an earlier check failed
+    Skipped,
+}
+
+/// Everything learned from verifying one announcement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Node the announcement claims to be from
+    pub node_id: Option<Uuid>,
+    /// Fingerprint of the signing key
+    pub key_fingerprint: Option<String>,
+    /// Announced sequence number
+    pub sequence: Option<u64>,
+    /// Announced protocol version
+    pub protocol_version: Option<String>,
+    /// Outcome of every check, in the order run
+    pub checks: Vec<(VerificationCheck, CheckOutcome)>,
+    /// When the verification ran
+    pub verified_at: DateTime<Utc>,
+}
+
+impl VerificationReport {
+    /// Whether every check passed
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(|(_, outcome)| *outcome == CheckOutcome::Passed)
+    }
+
+    /// Outcome of one check
+    pub fn outcome(&self, check: VerificationCheck) -> Option<&CheckOutcome> {
+        self.checks.iter().find(|(c, _)| *c == check).map(|(_, outcome)| outcome)
+    }
+}
+
+/// Outcome of the signature check for a signature by `public_key` that is valid
+fn trusted_signer(trusted: Option<&TrustedKey>, public_key: &[u8]) -> CheckOutcome {
+    match trusted {
+        Some(key) if key.public_key == public_key => CheckOutcome::Passed,
+        Some(_) => CheckOutcome::Failed("signed by a key not trusted for this node".to_string()),
+        None => CheckOutcome::Failed("no trusted key for this node".to_string()),
+    }
+}
+
+/// Verify a signed discovery announcement, as broadcast by [`NodeDiscovery`]
+///
+/// These carry no sequence number, protocol version or config fingerprint,
+/// so only the signature is checked.
+fn verify_discovery_announcement(
+    announcement: &DiscoveryAnnouncement,
+    trusted_keys: &TrustedKeys,
+    mut report: VerificationReport,
+) -> VerificationReport {
+    report.checks.push((VerificationCheck::Decoding, CheckOutcome::Passed));
+    let node_id = announcement.node_info.node_id;
+    let public_key = announcement.decoded_public_key();
+    report.node_id = Some(node_id);
+    report.key_fingerprint = public_key.as_deref().map(key_fingerprint);
+
+    let signature = match public_key {
+        Some(key) if NodeDiscovery::verify_announcement(announcement, &key) => {
+            trusted_signer(trusted_keys.keys.get(&node_id), &key)
+        }
+        Some(_) => CheckOutcome::Failed("signature does not match the announcement".to_string()),
+        None => CheckOutcome::Failed("announcement is not signed".to_string()),
+    };
+    report.checks.push((VerificationCheck::Signature, signature));
+    report
+}
+
+/// Verify a captured announcement against `trusted_keys`
+///
+/// Accepts a [`SignedAnnouncement`] or a signed discovery announcement.
+/// Checks that fail do not stop the others, except that nothing is checked
+/// past an announcement that cannot be decoded.
+pub fn verify_announcement(bytes: &[u8], trusted_keys: &TrustedKeys) -> VerificationReport {
+    let mut report = VerificationReport {
+        node_id: None,
+        key_fingerprint: None,
+        sequence: None,
+        protocol_version: None,
+        checks: Vec::new(),
+        verified_at: Utc::now(),
+    };
+
+    let decoded = serde_json::from_slice::<SignedAnnouncement>(bytes)
+        .map_err(|e| e.to_string())
+        .and_then(|signed| {
+            let body = serde_json::from_slice::<AnnouncementBody>(&signed.body).map_err(|e| e.to_string())?;
+            Ok((signed, body))
+        });
+    let (signed, body) = match decoded {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            if let Ok(announcement) = serde_json::from_slice::<DiscoveryAnnouncement>(bytes) {
+                return verify_discovery_announcement(&announcement, trusted_keys, report);
+            }
+            report.checks.push((VerificationCheck::Decoding, CheckOutcome::Failed(e)));
+            for check in [
+                VerificationCheck::Signature,
+                VerificationCheck::Sequence,
+                VerificationCheck::ProtocolVersion,
+                VerificationCheck::ConfigFingerprint,
+            ] {
+                report.checks.push((check, CheckOutcome::Skipped));
+            }
+            return report;
+        }
+    };
+    report.checks.push((VerificationCheck::Decoding, CheckOutcome::Passed));
+
+    let node_id = body.announcement.node_id;
+    let version = &body.announcement.node_info.version.protocol_version;
+    report.node_id = Some(node_id);
+    report.key_fingerprint = Some(key_fingerprint(&signed.public_key));
+    report.sequence = Some(body.sequence);
+    report.protocol_version = Some(version.clone());
+    let trusted = trusted_keys.keys.get(&node_id);
+
+    let signature = if UnparsedPublicKey::new(&ED25519, &signed.public_key)
+        .verify(&signed.body, &signed.signature)
+        .is_err()
+    {
+        CheckOutcome::Failed("signature does not match the announcement".to_string())
+    } else {
+        trusted_signer(trusted, &signed.public_key)
+    };
+    report.checks.push((VerificationCheck::Signature, signature));
+
+    let sequence = match trusted.and_then(|key| key.last_sequence) {
+        Some(last) if body.sequence <= last => {
+            CheckOutcome::Failed(format!("sequence {} is not newer than {}", body.sequence, last))
+        }
+        Some(last) if body.sequence - last > MAX_SEQUENCE_GAP => {
+            CheckOutcome::Failed(format!("sequence {} jumps too far past {}", body.sequence, last))
+        }
+        _ => CheckOutcome::Passed,
+    };
+    report.checks.push((VerificationCheck::Sequence, sequence));
+
+    let major = version.split('.').next().and_then(|major| major.parse::<u32>().ok());
+    let protocol = match major {
+        Some(major) if SUPPORTED_PROTOCOL_MAJOR_VERSIONS.contains(&major) => CheckOutcome::Passed,
+        _ => CheckOutcome::Failed(format!("unsupported protocol version {}", version)),
+    };
+    report.checks.push((VerificationCheck::ProtocolVersion, protocol));
+
+    let fingerprint = match config_fingerprint(&body.announcement.node_info) {
+        Ok(actual) if actual == body.config_fingerprint => CheckOutcome::Passed,
+        Ok(_) => CheckOutcome::Failed("config fingerprint does not match the announced node info".to_string()),
+        Err(e) => CheckOutcome::Failed(e.to_string()),
+    };
+    report.checks.push((VerificationCheck::ConfigFingerprint, fingerprint));
+
+    report
+}
+
+/// Private key as stored in an identity file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivateKeyMaterial {
+    /// Whether `pkcs8` is encrypted at rest
+    pub encrypted: bool,
+    /// Base64 PKCS#8 document, encrypted if `encrypted` is set
+    pub pkcs8: String,
+}
+
+/// A node's identity as stored on disk (JSON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityFile {
+    pub node_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// Base64 Ed25519 public key
+    pub public_key: String,
+    /// Absent in public-only identity files
+    #[serde(default)]
+    pub private_key: Option<PrivateKeyMaterial>,
+}
+
+impl IdentityFile {
+    /// A new identity with an unencrypted key pair
+    pub fn generate(node_id: Uuid) -> Result<Self> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("Failed to generate node key pair"))?;
+        let keys = NodeKeys::from_pkcs8(pkcs8.as_ref())?;
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        Ok(Self {
+            node_id,
+            created_at: Utc::now(),
+            public_key: encode(keys.public_key()),
+            private_key: Some(PrivateKeyMaterial { encrypted: false, pkcs8: encode(pkcs8.as_ref()) }),
+        })
+    }
+
+    /// The same identity without its private key
+    pub fn public_only(&self) -> Self {
+        Self { private_key: None, ..self.clone() }
+    }
+
+    /// Write the identity to `path`, readable only by its owner on unix
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        // An existing file keeps its mode when opened; tighten it before the key goes in
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::io::Write::write_all(&mut file, &serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// What [`inspect_identity_file`] reports about an identity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdentitySummary {
+    pub node_id: Uuid,
+    /// [`key_fingerprint`] of the public key
+    pub public_key_fingerprint: String,
+    pub created_at: DateTime<Utc>,
+    /// Whether the file holds a private key
+    pub has_private_key: bool,
+    /// Whether that private key is encrypted at rest
+    pub private_key_encrypted: bool,
+}
+
+/// Identity file as read for inspection: the private key is skipped, not decoded
+#[derive(Deserialize)]
+struct InspectedIdentity {
+    node_id: Uuid,
+    created_at: DateTime<Utc>,
+    public_key: String,
+    #[serde(default)]
+    private_key: Option<InspectedPrivateKey>,
+}
+
+#[derive(Deserialize)]
+struct InspectedPrivateKey {
+    encrypted: bool,
+    #[serde(rename = "pkcs8")]
+    _pkcs8: IgnoredAny,
+}
+
+/// Summarize an identity file without loading its private key
+///
+/// The file is streamed through the parser and the key material is skipped
+/// as it is read, so it never ends up in a buffer or value of its own.
+pub fn inspect_identity_file(path: &Path) -> Result<IdentitySummary> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open identity file {}: {}", path.display(), e))?;
+    let identity: InspectedIdentity = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| anyhow::anyhow!("Failed to parse identity file {}: {}", path.display(), e))?;
+    let public_key = base64::engine::general_purpose::STANDARD
+        .decode(&identity.public_key)
+        .map_err(|e| anyhow::anyhow!("Invalid public key in {}: {}", path.display(), e))?;
+
+    Ok(IdentitySummary {
+        node_id: identity.node_id,
+        public_key_fingerprint: key_fingerprint(&public_key),
+        created_at: identity.created_at,
+        has_private_key: identity.private_key.is_some(),
+        private_key_encrypted: identity.private_key.is_some_and(|key| key.encrypted),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::node::MeshNode;
+
+    async fn announcement() -> NodeAnnouncement {
+        let node = MeshNode::new_universal().await.unwrap();
+        NodeAnnouncement { node_id: node.id, node_info: node.info, timestamp: Utc::now() }
+    }
+
+    #[tokio::test]
+    async fn test_verify_announcement() {
+        let keys = NodeKeys::generate().unwrap();
+        let announcement = announcement().await;
+        let node_id = announcement.node_id;
+        let trusted = TrustedKeys::default()
+            .with_key(node_id, keys.public_key().to_vec())
+            .with_last_sequence(node_id, 6);
+
+        let signed = SignedAnnouncement::sign(announcement.clone(), 7, &keys).unwrap();
+        let report = verify_announcement(&signed.to_bytes().unwrap(), &trusted);
+        assert!(report.is_valid(), "{:?}", report.checks);
+        assert_eq!(report.node_id, Some(node_id));
+        assert_eq!(report.key_fingerprint, Some(keys.fingerprint()));
+        // Reports round-trip for attaching to tickets
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<VerificationReport>(&json).unwrap().checks, report.checks);
+
+        // Tampered body: signature fails, and the config no longer matches its fingerprint
+        let mut tampered = signed.clone();
+        let mut body: AnnouncementBody = serde_json::from_slice(&tampered.body).unwrap();
+        body.announcement.node_info.capabilities.clear();
+        tampered.body = serde_json::to_vec(&body).unwrap();
+        let report = verify_announcement(&tampered.to_bytes().unwrap(), &trusted);
+        assert!(matches!(report.outcome(VerificationCheck::Signature), Some(CheckOutcome::Failed(_))));
+        assert!(matches!(report.outcome(VerificationCheck::ConfigFingerprint), Some(CheckOutcome::Failed(_))));
+
+        // Replayed older announcement
+        let stale = SignedAnnouncement::sign(announcement.clone(), 6, &keys).unwrap();
+        let report = verify_announcement(&stale.to_bytes().unwrap(), &trusted);
+        assert_eq!(report.outcome(VerificationCheck::Signature), Some(&CheckOutcome::Passed));
+        assert!(matches!(report.outcome(VerificationCheck::Sequence), Some(CheckOutcome::Failed(_))));
+        assert!(!report.is_valid());
+
+        // Validly signed by a key not on file for the node
+        let impostor = SignedAnnouncement::sign(announcement, 8, &NodeKeys::generate().unwrap()).unwrap();
+        let report = verify_announcement(&impostor.to_bytes().unwrap(), &trusted);
+        assert!(matches!(report.outcome(VerificationCheck::Signature), Some(CheckOutcome::Failed(_))));
+
+        // What discovery broadcasts verifies against the same keys
+        let info = crate::networking::node_discovery::utils::create_basic_node_info(node_id, "node".to_string(), "ctx".to_string());
+        let mut broadcast = DiscoveryAnnouncement {
+            node_info: info,
+            announcement_type: crate::networking::node_discovery::AnnouncementType::Join,
+            timestamp: Utc::now(),
+            public_key: None,
+            signature: None,
+        };
+        let unsigned = serde_json::to_vec(&broadcast).unwrap();
+        assert!(matches!(verify_announcement(&unsigned, &trusted).outcome(VerificationCheck::Signature), Some(CheckOutcome::Failed(_))));
+        broadcast.sign(&keys).unwrap();
+        let report = verify_announcement(&serde_json::to_vec(&broadcast).unwrap(), &trusted);
+        assert!(report.is_valid(), "{:?}", report.checks);
+        assert_eq!(report.key_fingerprint, Some(keys.fingerprint()));
+        broadcast.node_info.endpoints.push("tcp/6.6.6.6:7447".to_string());
+        let report = verify_announcement(&serde_json::to_vec(&broadcast).unwrap(), &trusted);
+        assert!(!report.is_valid());
+
+        let report = verify_announcement(b"not an announcement", &trusted);
+        assert!(matches!(report.outcome(VerificationCheck::Decoding), Some(CheckOutcome::Failed(_))));
+        assert_eq!(report.outcome(VerificationCheck::Signature), Some(&CheckOutcome::Skipped));
+    }
+
+    #[test]
+    fn test_inspect_identity_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let identity = IdentityFile::generate(Uuid::new_v4()).unwrap();
+        let public_key = base64::engine::general_purpose::STANDARD.decode(&identity.public_key).unwrap();
+
+        let keypair_path = dir.path().join("node.identity");
+        identity.save(&keypair_path).unwrap();
+        let summary = inspect_identity_file(&keypair_path).unwrap();
+        assert_eq!(summary.node_id, identity.node_id);
+        assert_eq!(summary.public_key_fingerprint, key_fingerprint(&public_key));
+        assert_eq!(summary.created_at, identity.created_at);
+        assert!(summary.has_private_key);
+        assert!(!summary.private_key_encrypted);
+
+        let public_path = dir.path().join("node.pub.identity");
+        identity.public_only().save(&public_path).unwrap();
+        let summary = inspect_identity_file(&public_path).unwrap();
+        assert!(!summary.has_private_key);
+        assert_eq!(summary.public_key_fingerprint, key_fingerprint(&public_key));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_identity_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let identity = IdentityFile::generate(Uuid::new_v4()).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        let created = dir.path().join("node.identity");
+        identity.save(&created).unwrap();
+        assert_eq!(mode(&created), 0o600);
+
+        // Overwriting a world-readable file tightens it
+        let existing = dir.path().join("old.identity");
+        std::fs::write(&existing, b"{}").unwrap();
+        std::fs::set_permissions(&existing, std::fs::Permissions::from_mode(0o644)).unwrap();
+        identity.save(&existing).unwrap();
+        assert_eq!(mode(&existing), 0o600);
+        assert_eq!(inspect_identity_file(&existing).unwrap().node_id, identity.node_id);
+    }
+}
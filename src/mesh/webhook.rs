@@ -0,0 +1,995 @@
+//! Outbound Webhook Integration
+//!
+//! Forwards mesh and security events to HTTP endpoints (Slack, PagerDuty,
+//! custom receivers) without writing a plugin. Events are taken from the
+//! [`EventSystem`](super::events::EventSystem) and
+//! [`SecuritySystem`](super::security::SecuritySystem) through a
+//! [`WebhookEventProvider`], queued without blocking, and delivered by a
+//! background [`WebhookDispatcher`] with per-target retry, backoff and
+//! circuit breaking. Payloads can be HMAC-SHA256 signed so receivers can
+//! verify their origin.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::events::{
+    EventConfig, EventPayload, EventPriority, EventProvider, EventType, MeshEvent,
+    SecurityEventType as MeshSecurityEventType, SecurityRiskLevel,
+};
+use super::security::{
+    SecurityConfig, SecurityEvent, SecurityEventType, SecurityProvider, SecuritySeverity,
+    TrustRelationship,
+};
+
+/// Header carrying the payload signature (`sha256=<hex>`)
+pub const SIGNATURE_HEADER: &str = "X-WeaveMesh-Signature";
+
+/// Header carrying the delivered event's ID
+pub const EVENT_ID_HEADER: &str = "X-WeaveMesh-Event";
+
+/// A webhook endpoint and the events it receives
+///
+/// The credentials are read from configuration but never serialized or
+/// printed.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WebhookTarget {
+    /// Unique target name, used for status tracking
+    pub name: String,
+    /// Endpoint URL
+    pub url: String,
+    /// Value of the `Authorization` header, if any
+    #[serde(default, skip_serializing)]
+    pub auth_header: Option<String>,
+    /// Secret for HMAC-SHA256 payload signing, if any
+    #[serde(default, skip_serializing)]
+    pub signing_secret: Option<String>,
+    /// Which events are delivered
+    pub filter: WebhookFilter,
+    /// How the request body is built
+    pub template: PayloadTemplate,
+}
+
+impl std::fmt::Debug for WebhookTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("WebhookTarget")
+            .field("name", &self.name)
+            .field("url", &self.url)
+            .field("auth_header", &redacted(&self.auth_header))
+            .field("signing_secret", &redacted(&self.signing_secret))
+            .field("filter", &self.filter)
+            .field("template", &self.template)
+            .finish()
+    }
+}
+
+impl WebhookTarget {
+    /// Target receiving every event as the generic JSON payload
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            auth_header: None,
+            signing_secret: None,
+            filter: WebhookFilter::default(),
+            template: PayloadTemplate::Generic,
+        }
+    }
+
+    /// Set the `Authorization` header value
+    pub fn with_auth_header(mut self, value: impl Into<String>) -> Self {
+        self.auth_header = Some(value.into());
+        self
+    }
+
+    /// Sign payloads with the given secret
+    pub fn with_signing_secret(mut self, secret: impl Into<String>) -> Self {
+        self.signing_secret = Some(secret.into());
+        self
+    }
+
+    /// Set the event filter
+    pub fn with_filter(mut self, filter: WebhookFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set the payload template
+    pub fn with_template(mut self, template: PayloadTemplate) -> Self {
+        self.template = template;
+        self
+    }
+}
+
+/// Event filter for a webhook target
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebhookFilter {
+    /// Event categories to deliver (see `EventType::category`); empty means all
+    pub categories: Vec<String>,
+    /// Minimum event priority to deliver
+    pub min_priority: Option<EventPriority>,
+    /// Minimum risk level for security events
+    pub min_risk_level: Option<SecurityRiskLevel>,
+}
+
+impl WebhookFilter {
+    /// Check if an event passes this filter
+    pub fn matches(&self, event: &MeshEvent) -> bool {
+        let category = event.event_type.category();
+        if !self.categories.is_empty() && !self.categories.iter().any(|c| c == category) {
+            return false;
+        }
+
+        if let Some(min_priority) = &self.min_priority {
+            if event.priority < *min_priority {
+                return false;
+            }
+        }
+
+        if let (Some(min_risk), EventPayload::Security { risk_level, .. }) = (&self.min_risk_level, &event.payload) {
+            if risk_level < min_risk {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Request body format for a webhook target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PayloadTemplate {
+    /// Generic JSON payload with summary fields and the full event
+    Generic,
+    /// Slack incoming-webhook message
+    Slack,
+    /// Custom JSON template
+    ///
+    /// `{{variable}}` placeholders in string values are substituted; a string
+    /// that is exactly `{{event}}` is replaced by the full event JSON.
+    /// Variables: `event_id`, `timestamp`, `source_node`, `category`,
+    /// `priority`, `summary`, `correlation_id` and `metadata.<key>`.
+    Custom(serde_json::Value),
+}
+
+impl PayloadTemplate {
+    /// Template for the generic JSON payload
+    pub fn generic_template() -> serde_json::Value {
+        serde_json::json!({
+            "event_id": "{{event_id}}",
+            "timestamp": "{{timestamp}}",
+            "source_node": "{{source_node}}",
+            "category": "{{category}}",
+            "priority": "{{priority}}",
+            "summary": "{{summary}}",
+            "event": "{{event}}",
+        })
+    }
+
+    /// Template for Slack incoming webhooks
+    pub fn slack_template() -> serde_json::Value {
+        serde_json::json!({
+            "text": "[{{priority}}] {{category}} event from {{source_node}}: {{summary}}",
+        })
+    }
+
+    /// Render the request body for an event
+    pub fn render(&self, event: &MeshEvent) -> serde_json::Value {
+        let template = match self {
+            PayloadTemplate::Generic => Self::generic_template(),
+            PayloadTemplate::Slack => Self::slack_template(),
+            PayloadTemplate::Custom(template) => template.clone(),
+        };
+
+        let variables = template_variables(event);
+        let event_json = serde_json::to_value(event).unwrap_or(serde_json::Value::Null);
+        substitute(template, &variables, &event_json)
+    }
+}
+
+/// Variables available to payload templates
+fn template_variables(event: &MeshEvent) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    variables.insert("event_id".to_string(), event.event_id.to_string());
+    variables.insert("timestamp".to_string(), event.timestamp.to_rfc3339());
+    variables.insert("source_node".to_string(), event.source_node.to_string());
+    variables.insert("category".to_string(), event.event_type.category().to_string());
+    variables.insert("priority".to_string(), format!("{:?}", event.priority));
+    variables.insert("summary".to_string(), event_summary(event));
+    variables.insert(
+        "correlation_id".to_string(),
+        event.correlation_id.map(|id| id.to_string()).unwrap_or_default(),
+    );
+    for (key, value) in &event.metadata {
+        variables.insert(format!("metadata.{}", key), value.clone());
+    }
+    variables
+}
+
+/// Replace placeholders in every string of a JSON template
+fn substitute(
+    value: serde_json::Value,
+    variables: &HashMap<String, String>,
+    event_json: &serde_json::Value,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(text) if text == "{{event}}" => event_json.clone(),
+        serde_json::Value::String(text) => {
+            let mut rendered = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                rendered.push_str(&rest[..start]);
+                match rest[start + 2..].find("}}") {
+                    Some(end) => {
+                        let name = rest[start + 2..start + 2 + end].trim();
+                        // Unknown variables render empty
+                        if let Some(value) = variables.get(name) {
+                            rendered.push_str(value);
+                        }
+                        rest = &rest[start + 2 + end + 2..];
+                    }
+                    None => {
+                        rendered.push_str(&rest[start..]);
+                        rest = "";
+                    }
+                }
+            }
+            rendered.push_str(rest);
+            serde_json::Value::String(rendered)
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items.into_iter().map(|item| substitute(item, variables, event_json)).collect(),
+        ),
+        serde_json::Value::Object(fields) => serde_json::Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, item)| (key, substitute(item, variables, event_json)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// One-line human-readable summary of an event
+fn event_summary(event: &MeshEvent) -> String {
+    match &event.payload {
+        EventPayload::NodeLifecycle { node_id, new_state, reason, .. } => match reason {
+            Some(reason) => format!("node {} is {} ({})", node_id, new_state, reason),
+            None => format!("node {} is {}", node_id, new_state),
+        },
+        EventPayload::Communication { protocol, status, error, .. } => match error {
+            Some(error) => format!("{} {}: {}", protocol, status, error),
+            None => format!("{} {}", protocol, status),
+        },
+        EventPayload::Resource { resource_id, operation, .. } => {
+            format!("{} on resource {}", operation, resource_id)
+        }
+        EventPayload::Topology { change_description, .. } => change_description.clone(),
+        EventPayload::Health { node_id, health_status, issues, .. } => {
+            if issues.is_empty() {
+                format!("node {} is {}", node_id, health_status)
+            } else {
+                format!("node {} is {}: {}", node_id, health_status, issues.join("; "))
+            }
+        }
+        EventPayload::Security { action, result, risk_level, .. } => {
+            format!("{} {} (risk: {:?})", action, result, risk_level)
+        }
+        EventPayload::Performance { metric_name, metric_value, metric_unit, .. } => {
+            format!("{} = {} {}", metric_name, metric_value, metric_unit)
+        }
+        EventPayload::ContextSpecific { context, .. } => format!("{} event", context),
+        EventPayload::Generic { .. } => format!("{} event", event.event_type.category()),
+    }
+}
+
+/// Sign a payload with HMAC-SHA256, formatted as `sha256=<hex>`
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, body);
+    let hex: String = tag.as_ref().iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Verify a `sha256=<hex>` signature in constant time
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let hex = match signature.strip_prefix("sha256=") {
+        Some(hex) if hex.len() % 2 == 0 => hex,
+        _ => return false,
+    };
+    let tag: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect();
+    match tag {
+        Some(tag) => {
+            let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+            hmac::verify(&key, body, &tag).is_ok()
+        }
+        None => false,
+    }
+}
+
+/// Webhook dispatcher configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// Configured targets
+    pub targets: Vec<WebhookTarget>,
+    /// Retries after a failed delivery attempt
+    pub max_retries: u32,
+    /// Delay before the first retry (milliseconds), doubled on each retry
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the retry delay (milliseconds)
+    pub max_backoff_ms: u64,
+    /// Consecutive failed deliveries before a target's circuit opens
+    pub failure_threshold: u32,
+    /// How often an open circuit lets a probe delivery through (milliseconds)
+    pub probe_interval_ms: u64,
+    /// Per-request timeout (milliseconds)
+    pub request_timeout_ms: u64,
+    /// Events buffered for delivery, both overall and per target; further
+    /// events are dropped
+    pub queue_capacity: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            max_retries: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            failure_threshold: 5,
+            probe_interval_ms: 60_000,
+            request_timeout_ms: 10_000,
+            queue_capacity: 1024,
+        }
+    }
+}
+
+/// Delivery state of one webhook target
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryStatus {
+    /// Last successful delivery
+    pub last_success: Option<DateTime<Utc>>,
+    /// Last failed delivery
+    pub last_failure: Option<DateTime<Utc>>,
+    /// Error from the last failed delivery
+    pub last_error: Option<String>,
+    /// Failed deliveries since the last success
+    pub consecutive_failures: u32,
+    /// When the circuit opened, if it is open
+    pub circuit_opened_at: Option<DateTime<Utc>>,
+    /// Last probe attempted while the circuit was open
+    pub last_probe: Option<DateTime<Utc>>,
+    /// Events delivered
+    pub delivered: u64,
+    /// Events that failed after all retries
+    pub failed: u64,
+    /// Events skipped because the circuit was open
+    pub skipped: u64,
+}
+
+impl DeliveryStatus {
+    /// Whether the circuit is open
+    pub fn is_circuit_open(&self) -> bool {
+        self.circuit_opened_at.is_some()
+    }
+}
+
+/// Result of dispatching one event to one target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    /// Delivered after the given number of attempts
+    Delivered { attempts: u32 },
+    /// Failed after the given number of attempts
+    Failed { attempts: u32, error: String },
+    /// Skipped because the target's circuit is open
+    CircuitOpen,
+}
+
+/// Delivers events to webhook targets in the background
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    /// Dispatcher configuration
+    config: Arc<WebhookConfig>,
+    /// HTTP client shared by deliveries
+    client: reqwest::Client,
+    /// Delivery state by target name
+    statuses: Arc<RwLock<HashMap<String, DeliveryStatus>>>,
+    /// Sending side of the event queue
+    sender: mpsc::Sender<MeshEvent>,
+    /// Receiving side of the event queue, taken by `start`
+    receiver: Arc<Mutex<Option<mpsc::Receiver<MeshEvent>>>>,
+    /// Events dropped because the queue was full
+    dropped: Arc<AtomicU64>,
+}
+
+impl WebhookDispatcher {
+    /// Create a dispatcher for the configured targets
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.request_timeout_ms))
+            .build()?;
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        let statuses = config
+            .targets
+            .iter()
+            .map(|target| (target.name.clone(), DeliveryStatus::default()))
+            .collect();
+
+        info!("Initializing webhook dispatcher with {} targets", config.targets.len());
+
+        Ok(Self {
+            config: Arc::new(config),
+            client,
+            statuses: Arc::new(RwLock::new(statuses)),
+            sender,
+            receiver: Arc::new(Mutex::new(Some(receiver))),
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Provider to register with an `EventSystem` or `SecuritySystem`
+    pub fn event_provider(&self) -> WebhookEventProvider {
+        WebhookEventProvider {
+            sender: self.sender.clone(),
+            dropped: Arc::clone(&self.dropped),
+        }
+    }
+
+    /// Start delivering queued events in the background
+    ///
+    /// Each target gets its own worker and bounded queue: deliveries to a
+    /// target run one at a time and in order, and a slow or failing target
+    /// never holds up the others. Events that find a target's queue full are
+    /// dropped for that target.
+    pub async fn start(&self) -> Result<()> {
+        let mut receiver = match self.receiver.lock().await.take() {
+            Some(receiver) => receiver,
+            None => return Err(anyhow::anyhow!("Webhook dispatcher already started")),
+        };
+
+        let mut workers = Vec::with_capacity(self.config.targets.len());
+        for target in &self.config.targets {
+            let target = target.clone();
+            let (sender, mut queue) = mpsc::channel::<Arc<MeshEvent>>(self.config.queue_capacity.max(1));
+            let dispatcher = self.clone();
+            tokio::spawn(async move {
+                while let Some(event) = queue.recv().await {
+                    dispatcher.deliver(&target, &event).await;
+                }
+            });
+            workers.push(sender);
+        }
+
+        let config = Arc::clone(&self.config);
+        let dropped = Arc::clone(&self.dropped);
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                let event = Arc::new(event);
+                for (target, worker) in config.targets.iter().zip(&workers) {
+                    if target.filter.matches(&event) && worker.try_send(Arc::clone(&event)).is_err() {
+                        dropped.fetch_add(1, Ordering::Relaxed);
+                        debug!("Webhook queue for {} full, dropped event {}", target.name, event.event_id);
+                    }
+                }
+            }
+        });
+
+        info!("Webhook dispatcher started");
+        Ok(())
+    }
+
+    /// Queue an event for delivery without waiting
+    ///
+    /// Returns false, and counts the event as dropped, if the queue is full.
+    pub fn enqueue(&self, event: MeshEvent) -> bool {
+        enqueue_event(&self.sender, &self.dropped, event)
+    }
+
+    /// Deliver an event to every matching target and wait for the outcomes
+    pub async fn dispatch(&self, event: &MeshEvent) -> Vec<(String, DeliveryOutcome)> {
+        let deliveries = self
+            .config
+            .targets
+            .iter()
+            .filter(|target| target.filter.matches(event))
+            .map(|target| async move { (target.name.clone(), self.deliver(target, event).await) });
+
+        futures::future::join_all(deliveries).await
+    }
+
+    /// Delivery state of a target
+    pub async fn status(&self, target_name: &str) -> Option<DeliveryStatus> {
+        self.statuses.read().await.get(target_name).cloned()
+    }
+
+    /// Delivery state of every target
+    pub async fn statuses(&self) -> HashMap<String, DeliveryStatus> {
+        self.statuses.read().await.clone()
+    }
+
+    /// Events dropped because the queue, or a target's queue, was full
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Deliver to one target, honouring its circuit and retrying with backoff
+    async fn deliver(&self, target: &WebhookTarget, event: &MeshEvent) -> DeliveryOutcome {
+        // An open circuit only lets a single probe through per interval
+        let probing = {
+            let mut statuses = self.statuses.write().await;
+            let status = statuses.entry(target.name.clone()).or_default();
+            if status.is_circuit_open() {
+                let now = Utc::now();
+                let last_attempt = status.last_probe.or(status.circuit_opened_at).unwrap_or(now);
+                let probe_due = (now - last_attempt).num_milliseconds() >= self.config.probe_interval_ms as i64;
+                if !probe_due {
+                    status.skipped += 1;
+                    return DeliveryOutcome::CircuitOpen;
+                }
+                status.last_probe = Some(now);
+                true
+            } else {
+                false
+            }
+        };
+
+        let body = match serde_json::to_vec(&target.template.render(event)) {
+            Ok(body) => body,
+            Err(e) => return self.record_failure(target, 0, e.to_string()).await,
+        };
+
+        let max_attempts = if probing { 1 } else { self.config.max_retries + 1 };
+        let mut backoff = self.config.initial_backoff_ms;
+        let mut attempts = 0;
+        let mut last_error = String::new();
+
+        while attempts < max_attempts {
+            if attempts > 0 {
+                tokio::time::sleep(Duration::from_millis(backoff)).await;
+                backoff = backoff.saturating_mul(2).min(self.config.max_backoff_ms);
+            }
+            attempts += 1;
+
+            match self.send(target, event, &body).await {
+                Ok(()) => {
+                    let mut statuses = self.statuses.write().await;
+                    let status = statuses.entry(target.name.clone()).or_default();
+                    if status.is_circuit_open() {
+                        info!("Webhook target {} recovered, closing circuit", target.name);
+                    }
+                    status.last_success = Some(Utc::now());
+                    status.consecutive_failures = 0;
+                    status.circuit_opened_at = None;
+                    status.last_probe = None;
+                    status.delivered += 1;
+                    return DeliveryOutcome::Delivered { attempts };
+                }
+                Err(e) => {
+                    debug!("Webhook delivery to {} failed (attempt {}): {}", target.name, attempts, e);
+                    last_error = e;
+                }
+            }
+        }
+
+        self.record_failure(target, attempts, last_error).await
+    }
+
+    /// Record a failed delivery, opening the circuit past the threshold
+    async fn record_failure(&self, target: &WebhookTarget, attempts: u32, error: String) -> DeliveryOutcome {
+        let mut statuses = self.statuses.write().await;
+        let status = statuses.entry(target.name.clone()).or_default();
+        status.last_failure = Some(Utc::now());
+        status.last_error = Some(error.clone());
+        status.consecutive_failures += 1;
+        status.failed += 1;
+
+        if !status.is_circuit_open() && status.consecutive_failures >= self.config.failure_threshold.max(1) {
+            warn!(
+                "Webhook target {} failed {} times in a row, opening circuit",
+                target.name, status.consecutive_failures
+            );
+            status.circuit_opened_at = Some(Utc::now());
+        }
+
+        DeliveryOutcome::Failed { attempts, error }
+    }
+
+    /// Send one HTTP request
+    async fn send(&self, target: &WebhookTarget, event: &MeshEvent, body: &[u8]) -> std::result::Result<(), String> {
+        let mut request = self
+            .client
+            .post(&target.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, event.event_id.to_string())
+            .body(body.to_vec());
+
+        if let Some(auth) = &target.auth_header {
+            request = request.header(reqwest::header::AUTHORIZATION, auth);
+        }
+        if let Some(secret) = &target.signing_secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, body));
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+}
+
+/// Queue an event, counting it as dropped if the queue is full or closed
+fn enqueue_event(sender: &mpsc::Sender<MeshEvent>, dropped: &AtomicU64, event: MeshEvent) -> bool {
+    match sender.try_send(event) {
+        Ok(()) => true,
+        Err(_) => {
+            dropped.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+}
+
+/// Event and security provider feeding a [`WebhookDispatcher`]
+///
+/// Handling an event only queues it, so webhook delivery never blocks
+/// event processing.
+pub struct WebhookEventProvider {
+    sender: mpsc::Sender<MeshEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl WebhookEventProvider {
+    /// Convert a security event into a mesh event for delivery
+    pub fn security_event_to_mesh_event(event: &SecurityEvent) -> MeshEvent {
+        let security_type = match event.event_type {
+            SecurityEventType::AuthenticationAttempt | SecurityEventType::AuthenticationFailure => {
+                MeshSecurityEventType::AuthenticationAttempt
+            }
+            SecurityEventType::AuthorizationCheck
+            | SecurityEventType::AuthorizationFailure
+            | SecurityEventType::UnauthorizedAccess => MeshSecurityEventType::AuthorizationCheck,
+            SecurityEventType::TrustViolation
+            | SecurityEventType::PolicyViolation
+            | SecurityEventType::SuspiciousActivity => MeshSecurityEventType::SecurityViolation,
+            SecurityEventType::TrustEstablishment => MeshSecurityEventType::TrustLevelChanged,
+            SecurityEventType::KeyRotation => MeshSecurityEventType::EncryptionEstablished,
+            SecurityEventType::ConfigurationChange | SecurityEventType::ContextSpecific { .. } => {
+                MeshSecurityEventType::SecurityAudit
+            }
+        };
+
+        let (priority, risk_level) = match event.severity {
+            SecuritySeverity::Info => (EventPriority::Low, SecurityRiskLevel::None),
+            SecuritySeverity::Low => (EventPriority::Low, SecurityRiskLevel::Low),
+            SecuritySeverity::Medium => (EventPriority::Normal, SecurityRiskLevel::Medium),
+            SecuritySeverity::High => (EventPriority::High, SecurityRiskLevel::High),
+            SecuritySeverity::Critical => (EventPriority::Critical, SecurityRiskLevel::Critical),
+        };
+
+        MeshEvent {
+            event_id: event.event_id,
+            timestamp: event.timestamp,
+            source_node: event.involved_nodes.first().copied().unwrap_or_else(Uuid::nil),
+            event_type: EventType::Security { security_type },
+            payload: EventPayload::Security {
+                principal: event.involved_nodes.get(1).map(|id| id.to_string()),
+                resource: None,
+                action: event.event_type.category().to_string(),
+                result: event.description.clone(),
+                risk_level,
+            },
+            metadata: event.metadata.clone(),
+            propagation_path: Vec::new(),
+            correlation_id: event.related_events.first().copied(),
+            priority,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventProvider for WebhookEventProvider {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn initialize(&mut self, _config: &EventConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_event(&self, event: &MeshEvent) -> Result<()> {
+        if !enqueue_event(&self.sender, &self.dropped, event.clone()) {
+            debug!("Webhook queue full, dropped event {}", event.event_id);
+        }
+        Ok(())
+    }
+
+    async fn create_context_event(&self, _context_data: serde_json::Value) -> Result<MeshEvent> {
+        Err(anyhow::anyhow!("Webhook provider does not create events"))
+    }
+
+    fn get_event_patterns(&self) -> Vec<String> {
+        vec!["*".to_string()]
+    }
+}
+
+#[async_trait::async_trait]
+impl SecurityProvider for WebhookEventProvider {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn initialize(&mut self, _config: &SecurityConfig) -> Result<()> {
+        Ok(())
+    }
+
+    async fn cleanup(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn handle_security_event(&self, event: &SecurityEvent) -> Result<()> {
+        let mesh_event = Self::security_event_to_mesh_event(event);
+        if !enqueue_event(&self.sender, &self.dropped, mesh_event) {
+            debug!("Webhook queue full, dropped security event {}", event.event_id);
+        }
+        Ok(())
+    }
+
+    async fn check_authorization(&self, _node_id: Uuid, _resource: &str, _action: &str) -> Result<bool> {
+        // Webhooks never grant access
+        Ok(false)
+    }
+
+    async fn validate_trust(&self, _relationship: &TrustRelationship) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn get_security_policies(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::events::{EventSystem, HealthEventType};
+    use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+    use std::sync::Mutex as StdMutex;
+
+    /// Local HTTP server recording requests and replying with scripted statuses
+    struct MockServer {
+        requests: StdMutex<Vec<(HeaderMap, Bytes)>>,
+        /// Statuses for successive requests; 200 once exhausted
+        responses: StdMutex<Vec<u16>>,
+    }
+
+    async fn record(State(server): State<Arc<MockServer>>, headers: HeaderMap, body: Bytes) -> StatusCode {
+        server.requests.lock().unwrap().push((headers, body));
+        let mut responses = server.responses.lock().unwrap();
+        let code = if responses.is_empty() { 200 } else { responses.remove(0) };
+        StatusCode::from_u16(code).unwrap()
+    }
+
+    async fn spawn_server(responses: Vec<u16>) -> (String, Arc<MockServer>) {
+        let server = Arc::new(MockServer {
+            requests: StdMutex::new(Vec::new()),
+            responses: StdMutex::new(responses),
+        });
+        let app = Router::new().route("/hook", post(record)).with_state(Arc::clone(&server));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (url, server)
+    }
+
+    fn fast_config(targets: Vec<WebhookTarget>) -> WebhookConfig {
+        WebhookConfig {
+            targets,
+            initial_backoff_ms: 5,
+            max_backoff_ms: 20,
+            request_timeout_ms: 2_000,
+            ..WebhookConfig::default()
+        }
+    }
+
+    fn health_event(priority: EventPriority) -> MeshEvent {
+        let mut event = EventSystem::new(Uuid::new_v4(), None)
+            .create_health_event(HealthEventType::IssueDetected, Uuid::new_v4(), "degraded".to_string(), None)
+            .with_priority(priority);
+        if let EventPayload::Health { issues, .. } = &mut event.payload {
+            issues.push("disk full".to_string());
+        }
+        event
+    }
+
+    fn security_event(severity: SecuritySeverity) -> MeshEvent {
+        WebhookEventProvider::security_event_to_mesh_event(&SecurityEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: SecurityEventType::UnauthorizedAccess,
+            involved_nodes: vec![Uuid::new_v4()],
+            description: "denied".to_string(),
+            severity,
+            response_actions: Vec::new(),
+            resolution_status: crate::mesh::security::ResolutionStatus::Open,
+            metadata: HashMap::new(),
+            related_events: Vec::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_filtering_by_category_and_severity() {
+        let (url, server) = spawn_server(Vec::new()).await;
+        let all = WebhookTarget::new("all", url.clone());
+        let security_high = WebhookTarget::new("security-high", url.clone()).with_filter(WebhookFilter {
+            categories: vec!["security".to_string()],
+            min_priority: None,
+            min_risk_level: Some(SecurityRiskLevel::High),
+        });
+        let dispatcher = WebhookDispatcher::new(fast_config(vec![all, security_high])).unwrap();
+
+        let outcomes = dispatcher.dispatch(&health_event(EventPriority::Critical)).await;
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].0, "all");
+
+        let outcomes = dispatcher.dispatch(&security_event(SecuritySeverity::Low)).await;
+        assert_eq!(outcomes.len(), 1);
+
+        let outcomes = dispatcher.dispatch(&security_event(SecuritySeverity::Critical)).await;
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|(_, outcome)| matches!(outcome, DeliveryOutcome::Delivered { attempts: 1 })));
+
+        assert_eq!(server.requests.lock().unwrap().len(), 4);
+        assert_eq!(dispatcher.status("security-high").await.unwrap().delivered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff() {
+        let (url, server) = spawn_server(vec![500, 503]).await;
+        let dispatcher = WebhookDispatcher::new(fast_config(vec![WebhookTarget::new("flaky", url)])).unwrap();
+
+        let outcomes = dispatcher.dispatch(&health_event(EventPriority::High)).await;
+        assert_eq!(outcomes[0].1, DeliveryOutcome::Delivered { attempts: 3 });
+        assert_eq!(server.requests.lock().unwrap().len(), 3);
+
+        let status = dispatcher.status("flaky").await.unwrap();
+        assert!(status.last_success.is_some());
+        assert_eq!(status.consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaks_and_probes() {
+        let (url, server) = spawn_server(vec![500; 6]).await;
+        let config = WebhookConfig {
+            max_retries: 0,
+            failure_threshold: 2,
+            probe_interval_ms: 50,
+            ..fast_config(vec![WebhookTarget::new("down", url)])
+        };
+        let dispatcher = WebhookDispatcher::new(config).unwrap();
+
+        for _ in 0..4 {
+            dispatcher.dispatch(&health_event(EventPriority::High)).await;
+        }
+        let status = dispatcher.status("down").await.unwrap();
+        assert!(status.is_circuit_open());
+        assert_eq!(status.failed, 2);
+        assert_eq!(status.skipped, 2);
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+
+        // Once the probe interval passes a single probe goes through
+        server.responses.lock().unwrap().clear();
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let outcomes = dispatcher.dispatch(&health_event(EventPriority::High)).await;
+        assert_eq!(outcomes[0].1, DeliveryOutcome::Delivered { attempts: 1 });
+        assert!(!dispatcher.status("down").await.unwrap().is_circuit_open());
+        assert_eq!(server.requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_signature_and_templates() {
+        let (url, server) = spawn_server(Vec::new()).await;
+        let target = WebhookTarget::new("signed", url)
+            .with_signing_secret("s3cret")
+            .with_auth_header("Bearer token")
+            .with_template(PayloadTemplate::Slack);
+        for shown in [format!("{:?}", target), serde_json::to_string(&target).unwrap()] {
+            assert!(!shown.contains("s3cret") && !shown.contains("Bearer token"));
+        }
+        let dispatcher = WebhookDispatcher::new(fast_config(vec![target])).unwrap();
+        let event = health_event(EventPriority::High);
+        dispatcher.dispatch(&event).await;
+
+        let requests = server.requests.lock().unwrap();
+        let (headers, body) = &requests[0];
+        let signature = headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        assert_eq!(signature, sign_payload("s3cret", body));
+        assert!(verify_signature("s3cret", body, signature));
+        assert!(!verify_signature("other", body, signature));
+        assert_eq!(headers.get("authorization").unwrap(), "Bearer token");
+
+        let payload: serde_json::Value = serde_json::from_slice(body).unwrap();
+        let text = payload["text"].as_str().unwrap();
+        assert!(text.starts_with("[High] health event from"));
+        assert!(text.contains("disk full"));
+
+        let custom = PayloadTemplate::Custom(serde_json::json!({
+            "id": "{{event_id}}",
+            "detail": { "who": "{{metadata.owner}}", "missing": "{{nope}}" },
+            "raw": "{{event}}",
+        }));
+        let rendered = custom.render(&event.clone().with_metadata("owner".to_string(), "ops".to_string()));
+        assert_eq!(rendered["id"], event.event_id.to_string());
+        assert_eq!(rendered["detail"]["who"], "ops");
+        assert_eq!(rendered["detail"]["missing"], "");
+        assert_eq!(rendered["raw"]["event_id"], event.event_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_event_system_delivery_does_not_block() {
+        let (url, server) = spawn_server(Vec::new()).await;
+        let dispatcher = WebhookDispatcher::new(fast_config(vec![WebhookTarget::new("all", url)])).unwrap();
+        let mut events = EventSystem::new(Uuid::new_v4(), None);
+        events.add_provider(Box::new(dispatcher.event_provider()));
+        dispatcher.start().await.unwrap();
+
+        events.publish_event(health_event(EventPriority::Normal)).await.unwrap();
+
+        for _ in 0..100 {
+            if !server.requests.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+        assert_eq!(dispatcher.dropped_events(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failing_target_does_not_hold_up_others() {
+        let (healthy_url, healthy) = spawn_server(Vec::new()).await;
+        let (down_url, down) = spawn_server(vec![500; 100]).await;
+        let config = WebhookConfig {
+            max_retries: 2,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 500,
+            queue_capacity: 2,
+            ..fast_config(vec![WebhookTarget::new("down", down_url), WebhookTarget::new("healthy", healthy_url)])
+        };
+        let dispatcher = WebhookDispatcher::new(config).unwrap();
+        dispatcher.start().await.unwrap();
+
+        for _ in 0..2 {
+            assert!(dispatcher.enqueue(health_event(EventPriority::Normal)));
+        }
+        for _ in 0..100 {
+            if healthy.requests.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(healthy.requests.lock().unwrap().len(), 2);
+
+        // The failing target is still retrying its first event, one request at a time
+        assert!(down.requests.lock().unwrap().len() <= 1);
+        assert_eq!(dispatcher.status("healthy").await.unwrap().delivered, 2);
+    }
+}
@@ -0,0 +1,216 @@
+//! Scoped broadcasts
+//!
+//! A broadcast names the audience it is meant for. Each scope has its own
+//! size and rate limits, tighter than those of direct messages, and sending
+//! to the whole mesh must be enabled explicitly. Receivers choose which
+//! scopes they accept.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::group_communication::GroupId;
+
+/// Context that unscoped broadcasts are sent to
+pub const LEGACY_BROADCAST_CONTEXT: &str = "general";
+
+/// Audience of a broadcast
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BroadcastScope {
+    /// Members of one group
+    Group(GroupId),
+    /// Nodes in one context
+    Context(String),
+    /// Nodes of the sender's organization
+    Organization,
+    /// Every node in the mesh; needs `allow_global`
+    Mesh,
+}
+
+impl BroadcastScope {
+    /// Scope of broadcasts sent without one
+    pub fn legacy(context: Option<String>) -> Self {
+        BroadcastScope::Context(context.unwrap_or_else(|| LEGACY_BROADCAST_CONTEXT.to_string()))
+    }
+
+    /// Context carried on the wire, if the scope names one
+    pub fn context(&self) -> Option<String> {
+        match self {
+            BroadcastScope::Group(group_id) => Some(group_id.as_str().to_string()),
+            BroadcastScope::Context(context) => Some(context.clone()),
+            BroadcastScope::Organization | BroadcastScope::Mesh => None,
+        }
+    }
+}
+
+/// Scopes a node has joined, shared with its receive path
+///
+/// Every node belongs to the mesh scope.
+#[derive(Debug, Clone, Default)]
+pub struct BroadcastMemberships {
+    scopes: Arc<RwLock<HashSet<BroadcastScope>>>,
+}
+
+impl BroadcastMemberships {
+    /// Memberships of a node that has joined only `scopes`
+    pub fn with_scopes(scopes: impl IntoIterator<Item = BroadcastScope>) -> Self {
+        Self { scopes: Arc::new(RwLock::new(scopes.into_iter().collect())) }
+    }
+
+    /// Join `scope`, returning whether it was newly joined
+    pub fn join(&self, scope: BroadcastScope) -> bool {
+        self.scopes.write().unwrap().insert(scope)
+    }
+
+    /// Leave `scope`, returning whether it had been joined
+    pub fn leave(&self, scope: &BroadcastScope) -> bool {
+        self.scopes.write().unwrap().remove(scope)
+    }
+
+    /// Whether broadcasts to `scope` are meant for this node
+    pub fn contains(&self, scope: &BroadcastScope) -> bool {
+        *scope == BroadcastScope::Mesh || self.scopes.read().unwrap().contains(scope)
+    }
+
+    /// Joined scopes
+    pub fn scopes(&self) -> Vec<BroadcastScope> {
+        self.scopes.read().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Size and rate limits of one kind of scope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopeLimit {
+    /// Largest payload in bytes
+    pub max_message_size: usize,
+    /// Sustained broadcasts per minute, per scope
+    pub messages_per_minute: u32,
+    /// Broadcasts allowed at once before the rate applies
+    pub burst: u32,
+}
+
+/// Limits for each kind of broadcast scope
+///
+/// Wider audiences get smaller messages and lower rates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BroadcastLimits {
+    pub group: ScopeLimit,
+    pub context: ScopeLimit,
+    pub organization: ScopeLimit,
+    pub mesh: ScopeLimit,
+}
+
+impl Default for BroadcastLimits {
+    fn default() -> Self {
+        Self {
+            group: ScopeLimit { max_message_size: 256 * 1024, messages_per_minute: 120, burst: 20 },
+            context: ScopeLimit { max_message_size: 128 * 1024, messages_per_minute: 60, burst: 10 },
+            organization: ScopeLimit { max_message_size: 64 * 1024, messages_per_minute: 30, burst: 5 },
+            mesh: ScopeLimit { max_message_size: 16 * 1024, messages_per_minute: 10, burst: 2 },
+        }
+    }
+}
+
+impl BroadcastLimits {
+    /// Limits applying to `scope`
+    pub fn for_scope(&self, scope: &BroadcastScope) -> &ScopeLimit {
+        match scope {
+            BroadcastScope::Group(_) => &self.group,
+            BroadcastScope::Context(_) => &self.context,
+            BroadcastScope::Organization => &self.organization,
+            BroadcastScope::Mesh => &self.mesh,
+        }
+    }
+}
+
+/// Token buckets limiting broadcasts, one per scope
+#[derive(Debug, Default)]
+pub struct BroadcastRateLimiter {
+    buckets: HashMap<BroadcastScope, (f64, Instant)>,
+}
+
+impl BroadcastRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a token for `scope` at `now`, returning false when none is left
+    pub fn try_acquire(&mut self, scope: &BroadcastScope, limit: &ScopeLimit, now: Instant) -> bool {
+        let capacity = limit.burst.max(1) as f64;
+        let per_second = limit.messages_per_minute as f64 / 60.0;
+        let (tokens, last) = self.buckets.entry(scope.clone()).or_insert((capacity, now));
+        let elapsed = now.saturating_duration_since(*last);
+        *tokens = (*tokens + elapsed.as_secs_f64() * per_second).min(capacity);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Forget scopes whose buckets have been full for `idle`
+    pub fn prune(&mut self, idle: Duration, now: Instant) {
+        self.buckets.retain(|_, (_, last)| now.saturating_duration_since(*last) < idle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_is_per_scope() {
+        let limit = ScopeLimit { max_message_size: 1024, messages_per_minute: 60, burst: 2 };
+        let mut limiter = BroadcastRateLimiter::new();
+        let team = BroadcastScope::Group(GroupId::new("group/team"));
+        let ops = BroadcastScope::Context("ops".to_string());
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(&team, &limit, start));
+        assert!(limiter.try_acquire(&team, &limit, start));
+        assert!(!limiter.try_acquire(&team, &limit, start));
+        assert!(limiter.try_acquire(&ops, &limit, start));
+
+        // One token comes back every second at 60 per minute
+        assert!(limiter.try_acquire(&team, &limit, start + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire(&team, &limit, start + Duration::from_secs(1)));
+
+        limiter.prune(Duration::from_secs(30), start + Duration::from_secs(31));
+        assert!(limiter.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_wider_scopes_have_tighter_limits() {
+        let limits = BroadcastLimits::default();
+        let sizes: Vec<usize> = [
+            BroadcastScope::Group(GroupId::new("g")),
+            BroadcastScope::Context("c".to_string()),
+            BroadcastScope::Organization,
+            BroadcastScope::Mesh,
+        ].iter().map(|scope| limits.for_scope(scope).max_message_size).collect();
+        assert!(sizes.windows(2).all(|pair| pair[0] > pair[1]));
+
+        assert_eq!(BroadcastScope::legacy(None), BroadcastScope::Context("general".to_string()));
+        assert_eq!(BroadcastScope::legacy(Some("ops".to_string())), BroadcastScope::Context("ops".to_string()));
+    }
+
+    #[test]
+    fn test_memberships() {
+        let team = BroadcastScope::Group(GroupId::new("group/team"));
+        let memberships = BroadcastMemberships::with_scopes([BroadcastScope::legacy(None)]);
+        let shared = memberships.clone();
+
+        assert!(memberships.contains(&BroadcastScope::Mesh));
+        assert!(!memberships.contains(&team));
+        assert!(shared.join(team.clone()));
+        assert!(memberships.contains(&team));
+        assert!(memberships.leave(&team));
+        assert!(!shared.contains(&team));
+        assert_eq!(memberships.scopes(), vec![BroadcastScope::legacy(None)]);
+    }
+}
@@ -0,0 +1,287 @@
+//! Peer clock offset estimation
+//!
+//! Heartbeats carry the sender's clock and, for each peer recently heard
+//! from, an echo of that peer's last heartbeat time and when it arrived.
+//! When a node finds its own heartbeat echoed back it has the four
+//! timestamps of an NTP exchange:
+//!
+//! - `t1`: it sent its heartbeat (its clock)
+//! - `t2`: the peer received it (peer clock)
+//! - `t3`: the peer sent the echoing heartbeat (peer clock)
+//! - `t4`: it received that heartbeat (its clock)
+//!
+//! and estimates the peer's offset as `((t2 - t1) + (t3 - t4)) / 2`. Offsets
+//! are smoothed per peer and used to translate peer timestamps into local
+//! time before TTL and freshness checks. Peers whose offset exceeds the
+//! configured threshold are flagged with a [`ClockSkewEvent`].
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// How clock offsets are estimated and judged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockSkewConfig {
+    /// Weight of the newest sample in the smoothed offset
+    pub smoothing: f64,
+    /// Offset (ms) beyond which a peer is flagged
+    pub max_offset_ms: i64,
+    /// Whether flagged peers are left out of time-sensitive protocols
+    pub exclude_excessive_skew: bool,
+}
+
+impl Default for ClockSkewConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: 0.2,
+            max_offset_ms: 2_000,
+            exclude_excessive_skew: false,
+        }
+    }
+}
+
+/// Echo of a peer's heartbeat, returned in the next heartbeat
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeEcho {
+    /// When the peer sent its heartbeat, by its clock
+    pub peer_sent_at: DateTime<Utc>,
+    /// When it arrived, by the echoing node's clock
+    pub received_at: DateTime<Utc>,
+}
+
+/// One offset measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetSample {
+    /// Peer clock minus local clock
+    pub offset: Duration,
+    /// Round-trip network delay
+    pub delay: Duration,
+}
+
+impl OffsetSample {
+    /// Offset and delay from the four timestamps of an exchange
+    pub fn from_exchange(t1: DateTime<Utc>, t2: DateTime<Utc>, t3: DateTime<Utc>, t4: DateTime<Utc>) -> Self {
+        Self {
+            offset: ((t2 - t1) + (t3 - t4)) / 2,
+            delay: (t4 - t1) - (t3 - t2),
+        }
+    }
+}
+
+/// Smoothed clock state of one peer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerClock {
+    /// Smoothed offset (peer clock minus local clock) in milliseconds
+    pub offset_ms: f64,
+    /// Delay of the latest exchange in milliseconds
+    pub last_delay_ms: i64,
+    /// Samples taken
+    pub samples: u64,
+    /// When the offset was last updated
+    pub updated_at: DateTime<Utc>,
+    /// Whether the offset is beyond the configured maximum
+    pub excessive: bool,
+}
+
+/// A peer's clock crossed the skew threshold, in either direction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockSkewEvent {
+    /// Peer concerned
+    pub peer: Uuid,
+    /// Smoothed offset in milliseconds
+    pub offset_ms: f64,
+    /// Configured maximum in milliseconds
+    pub max_offset_ms: i64,
+    /// Whether the offset is now beyond the maximum
+    pub excessive: bool,
+}
+
+/// Per-peer clock offsets
+#[derive(Debug)]
+pub struct ClockSkewTracker {
+    config: ClockSkewConfig,
+    peers: HashMap<Uuid, PeerClock>,
+    /// Last heartbeat heard from each peer, to echo back
+    heard: HashMap<Uuid, TimeEcho>,
+    events: broadcast::Sender<ClockSkewEvent>,
+}
+
+impl ClockSkewTracker {
+    /// Create a tracker with no peers
+    pub fn new(config: ClockSkewConfig) -> Self {
+        Self {
+            config,
+            peers: HashMap::new(),
+            heard: HashMap::new(),
+            events: broadcast::channel(64).0,
+        }
+    }
+
+    /// Peers crossing the skew threshold
+    pub fn subscribe(&self) -> broadcast::Receiver<ClockSkewEvent> {
+        self.events.subscribe()
+    }
+
+    /// Echoes to include in this node's next heartbeat
+    pub fn echoes(&self) -> HashMap<Uuid, TimeEcho> {
+        self.heard.clone()
+    }
+
+    /// Take in a heartbeat from `peer`
+    ///
+    /// `sent_at` is the peer's clock when sending; `echo` is the peer's echo
+    /// of this node's last heartbeat, if it had one.
+    pub fn observe_heartbeat(
+        &mut self,
+        peer: Uuid,
+        sent_at: DateTime<Utc>,
+        echo: Option<&TimeEcho>,
+        now: DateTime<Utc>,
+    ) -> Option<OffsetSample> {
+        self.heard.insert(peer, TimeEcho { peer_sent_at: sent_at, received_at: now });
+        let echo = echo?;
+        let sample = OffsetSample::from_exchange(echo.peer_sent_at, echo.received_at, sent_at, now);
+        self.record_sample(peer, sample, now);
+        Some(sample)
+    }
+
+    /// Fold an offset sample into a peer's smoothed offset
+    pub fn record_sample(&mut self, peer: Uuid, sample: OffsetSample, now: DateTime<Utc>) {
+        let offset_ms = sample.offset.num_milliseconds() as f64;
+        let smoothing = self.config.smoothing;
+        let clock = self.peers.entry(peer).or_insert(PeerClock {
+            offset_ms,
+            last_delay_ms: 0,
+            samples: 0,
+            updated_at: now,
+            excessive: false,
+        });
+        if clock.samples > 0 {
+            clock.offset_ms = clock.offset_ms * (1.0 - smoothing) + offset_ms * smoothing;
+        }
+        clock.samples += 1;
+        clock.last_delay_ms = sample.delay.num_milliseconds();
+        clock.updated_at = now;
+
+        let excessive = clock.offset_ms.abs() > self.config.max_offset_ms as f64;
+        if excessive != clock.excessive {
+            clock.excessive = excessive;
+            if excessive {
+                tracing::warn!("Clock of peer {} is off by {:.0} ms", peer, clock.offset_ms);
+            }
+            let _ = self.events.send(ClockSkewEvent {
+                peer,
+                offset_ms: clock.offset_ms,
+                max_offset_ms: self.config.max_offset_ms,
+                excessive,
+            });
+        }
+    }
+
+    /// Forget a peer
+    pub fn remove_peer(&mut self, peer: &Uuid) {
+        self.peers.remove(peer);
+        self.heard.remove(peer);
+    }
+
+    /// Smoothed offset of a peer's clock from ours, if measured
+    pub fn get_peer_clock_offset(&self, peer: &Uuid) -> Option<Duration> {
+        self.peers.get(peer).map(|clock| Duration::milliseconds(clock.offset_ms.round() as i64))
+    }
+
+    /// Clock state of every measured peer
+    pub fn peer_clocks(&self) -> &HashMap<Uuid, PeerClock> {
+        &self.peers
+    }
+
+    /// Whether a peer's clock is beyond the configured maximum offset
+    pub fn is_excessive(&self, peer: &Uuid) -> bool {
+        self.peers.get(peer).is_some_and(|clock| clock.excessive)
+    }
+
+    /// Whether a peer may take part in time-sensitive protocols such as leases
+    pub fn is_time_trusted(&self, peer: &Uuid) -> bool {
+        !(self.config.exclude_excessive_skew && self.is_excessive(peer))
+    }
+
+    /// A timestamp from `peer`'s clock in local time
+    pub fn to_local(&self, peer: &Uuid, peer_time: DateTime<Utc>) -> DateTime<Utc> {
+        peer_time - self.get_peer_clock_offset(peer).unwrap_or_else(Duration::zero)
+    }
+
+    /// Whether something `peer` stamped at `peer_time` with `ttl` has expired
+    pub fn is_expired(&self, peer: &Uuid, peer_time: DateTime<Utc>, ttl: Duration, now: DateTime<Utc>) -> bool {
+        now - self.to_local(peer, peer_time) > ttl
+    }
+}
+
+impl Default for ClockSkewTracker {
+    fn default() -> Self {
+        Self::new(ClockSkewConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A peer whose clock runs `skew` ahead, one-way delay `delay`
+    fn exchange(tracker: &mut ClockSkewTracker, peer: Uuid, start: DateTime<Utc>, skew: Duration, delay: Duration) {
+        // We heartbeat at t1; the peer receives it at t2 on its clock, then
+        // heartbeats back at t3 with an echo, which we receive at t4
+        let t1 = start;
+        let echo = TimeEcho { peer_sent_at: t1, received_at: t1 + delay + skew };
+        let t3 = echo.received_at + Duration::milliseconds(100);
+        let t4 = t3 - skew + delay;
+        tracker.observe_heartbeat(peer, t3, Some(&echo), t4);
+    }
+
+    #[test]
+    fn test_offset_estimation_and_correction() {
+        let mut tracker = ClockSkewTracker::new(ClockSkewConfig {
+            exclude_excessive_skew: true,
+            ..ClockSkewConfig::default()
+        });
+        let mut events = tracker.subscribe();
+        let (close, skewed) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = Utc::now();
+
+        for i in 0..5 {
+            let at = start + Duration::seconds(i * 30);
+            exchange(&mut tracker, close, at, Duration::milliseconds(300), Duration::milliseconds(20));
+            exchange(&mut tracker, skewed, at, Duration::seconds(-90), Duration::milliseconds(20));
+        }
+        assert_eq!(tracker.get_peer_clock_offset(&close), Some(Duration::milliseconds(300)));
+        assert_eq!(tracker.get_peer_clock_offset(&skewed), Some(Duration::seconds(-90)));
+        assert!(tracker.get_peer_clock_offset(&Uuid::new_v4()).is_none());
+
+        // A message stamped 50s ago by the slow clock is 140s old, past a 2 minute TTL
+        let now = start + Duration::minutes(10);
+        let stamped = now - Duration::seconds(90) - Duration::seconds(50);
+        assert!(!tracker.is_expired(&skewed, stamped, Duration::minutes(2), now));
+        let stamped = now - Duration::seconds(90) - Duration::seconds(140);
+        assert!(tracker.is_expired(&skewed, stamped, Duration::minutes(2), now));
+        assert_eq!(tracker.to_local(&close, now + Duration::milliseconds(300)), now);
+
+        // Only the skewed peer is flagged, once, and kept out of time-sensitive work
+        let event = events.try_recv().unwrap();
+        assert_eq!((event.peer, event.excessive), (skewed, true));
+        assert!(events.try_recv().is_err());
+        assert!(tracker.is_time_trusted(&close));
+        assert!(!tracker.is_time_trusted(&skewed));
+    }
+
+    #[test]
+    fn test_heartbeats_without_echo_only_record_echoes() {
+        let mut tracker = ClockSkewTracker::default();
+        let peer = Uuid::new_v4();
+        let now = Utc::now();
+
+        assert!(tracker.observe_heartbeat(peer, now - Duration::seconds(5), None, now).is_none());
+        assert!(tracker.get_peer_clock_offset(&peer).is_none());
+        assert_eq!(tracker.echoes()[&peer], TimeEcho { peer_sent_at: now - Duration::seconds(5), received_at: now });
+    }
+}
@@ -0,0 +1,352 @@
+//! DNS-SD (RFC 6763) service discovery
+//!
+//! Deployments without multicast can find peers through unicast DNS-SD. The
+//! local node is registered as a `_weavemesh._tcp` service instance named by
+//! its node ID, and its [`NodeInfo`] is carried in TXT records. Browsing
+//! decodes the TXT records of other instances back into [`NodeInfo`], so
+//! peers found this way go through the same registry path as Zenoh
+//! announcements.
+//!
+//! The DNS-SD transport is a [`DnsSdClient`]. [`MdnsSdClient`], the default,
+//! registers and browses over multicast DNS in the `local.` domain; unicast
+//! zones need a client for the site's DNS server.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::node_discovery::{DiscoveryError, NodeCapability, NodeInfo};
+
+/// Service type WeaveMesh nodes register under
+pub const DNS_SD_SERVICE_TYPE: &str = "_weavemesh._tcp";
+
+/// Version of the TXT record layout
+pub const TXT_VERSION: &str = "1";
+
+/// Longest `key=value` string a single TXT entry can hold
+const MAX_TXT_ENTRY_LEN: usize = 255;
+
+/// Prefix of TXT keys carrying node metadata
+const METADATA_PREFIX: &str = "m.";
+
+/// A service instance to register or one found by browsing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsSdService {
+    /// Instance name; the node ID for WeaveMesh nodes
+    pub instance_name: String,
+    /// Service type, e.g. [`DNS_SD_SERVICE_TYPE`]
+    pub service_type: String,
+    /// Domain, e.g. `local.` or a unicast zone
+    pub domain: String,
+    /// Port from the SRV record
+    pub port: u16,
+    /// TXT record entries as `key=value` strings
+    pub txt: Vec<String>,
+}
+
+/// Registers and browses DNS-SD services
+#[async_trait::async_trait]
+pub trait DnsSdClient: Send + Sync + std::fmt::Debug {
+    /// Register a service instance, replacing one of the same name
+    async fn register(&self, service: DnsSdService) -> Result<(), DiscoveryError>;
+
+    /// Remove a registered service instance
+    async fn unregister(&self, instance_name: &str, service_type: &str, domain: &str) -> Result<(), DiscoveryError>;
+
+    /// Service instances of `service_type` currently in `domain`
+    async fn browse(&self, service_type: &str, domain: &str) -> Result<Vec<DnsSdService>, DiscoveryError>;
+}
+
+/// [`DnsSdClient`] over multicast DNS, serving the `local.` domain
+///
+/// Browsing collects the instances resolved within `browse_window`; the
+/// daemon keeps a cache, so later browses also return instances seen before.
+pub struct MdnsSdClient {
+    daemon: mdns_sd::ServiceDaemon,
+    browse_window: Duration,
+}
+
+/// How long a browse waits for instances to resolve
+const DEFAULT_BROWSE_WINDOW: Duration = Duration::from_secs(2);
+
+impl MdnsSdClient {
+    /// Start an mDNS daemon
+    pub fn new() -> Result<Self, DiscoveryError> {
+        let daemon = mdns_sd::ServiceDaemon::new().map_err(mdns_error)?;
+        Ok(Self { daemon, browse_window: DEFAULT_BROWSE_WINDOW })
+    }
+
+    /// Wait `window` for instances to resolve on each browse
+    pub fn with_browse_window(mut self, window: Duration) -> Self {
+        self.browse_window = window;
+        self
+    }
+
+    /// `_service._tcp.local.` for `service_type` in `domain`
+    fn type_domain(service_type: &str, domain: &str) -> Result<String, DiscoveryError> {
+        let domain = domain.trim_end_matches('.');
+        if !domain.eq_ignore_ascii_case("local") {
+            return Err(DiscoveryError::ConfigurationError(format!(
+                "mDNS only serves the local. domain; supply a DNS-SD client for {}.",
+                domain
+            )));
+        }
+        Ok(format!("{}.local.", service_type.trim_end_matches('.')))
+    }
+}
+
+impl std::fmt::Debug for MdnsSdClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MdnsSdClient").field("browse_window", &self.browse_window).finish_non_exhaustive()
+    }
+}
+
+fn mdns_error(error: mdns_sd::Error) -> DiscoveryError {
+    DiscoveryError::NetworkError(format!("mDNS: {}", error))
+}
+
+#[async_trait::async_trait]
+impl DnsSdClient for MdnsSdClient {
+    async fn register(&self, service: DnsSdService) -> Result<(), DiscoveryError> {
+        let type_domain = Self::type_domain(&service.service_type, &service.domain)?;
+        let properties: Vec<(&str, &str)> = service
+            .txt
+            .iter()
+            .map(|entry| entry.split_once('=').unwrap_or((entry.as_str(), "")))
+            .collect();
+        let host_name = format!("{}.local.", service.instance_name);
+        let info = mdns_sd::ServiceInfo::new(
+            &type_domain,
+            &service.instance_name,
+            &host_name,
+            (),
+            service.port,
+            properties.as_slice(),
+        )
+        .map_err(mdns_error)?
+        .enable_addr_auto();
+        self.daemon.register(info).map_err(mdns_error)
+    }
+
+    async fn unregister(&self, instance_name: &str, service_type: &str, domain: &str) -> Result<(), DiscoveryError> {
+        let fullname = format!("{}.{}", instance_name, Self::type_domain(service_type, domain)?);
+        let status = self.daemon.unregister(&fullname).map_err(mdns_error)?;
+        // Wait for the goodbye packets, but do not fail if the daemon is gone
+        let _ = tokio::time::timeout(self.browse_window, status.recv_async()).await;
+        Ok(())
+    }
+
+    async fn browse(&self, service_type: &str, domain: &str) -> Result<Vec<DnsSdService>, DiscoveryError> {
+        let type_domain = Self::type_domain(service_type, domain)?;
+        let events = self.daemon.browse(&type_domain).map_err(mdns_error)?;
+        let deadline = tokio::time::Instant::now() + self.browse_window;
+        let mut found: HashMap<String, DnsSdService> = HashMap::new();
+        while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, events.recv_async()).await {
+            if let mdns_sd::ServiceEvent::ServiceResolved(info) = event {
+                let instance_name = info
+                    .get_fullname()
+                    .strip_suffix(&format!(".{}", type_domain))
+                    .unwrap_or(info.get_fullname())
+                    .to_string();
+                let txt = info
+                    .get_properties()
+                    .iter()
+                    .map(|property| format!("{}={}", property.key(), property.val_str()))
+                    .collect();
+                found.insert(instance_name.clone(), DnsSdService {
+                    instance_name,
+                    service_type: service_type.to_string(),
+                    domain: domain.to_string(),
+                    port: info.get_port(),
+                    txt,
+                });
+            }
+        }
+        let _ = self.daemon.stop_browse(&type_domain);
+        Ok(found.into_values().collect())
+    }
+}
+
+/// Service registration for a node
+///
+/// The port is taken from the node's first endpoint that has one.
+pub fn node_service(node_info: &NodeInfo, service_type: &str, domain: &str) -> DnsSdService {
+    let port = node_info
+        .endpoints
+        .iter()
+        .find_map(|endpoint| endpoint.rsplit(':').next().and_then(|p| p.parse().ok()))
+        .unwrap_or(0);
+    DnsSdService {
+        instance_name: node_info.node_id.to_string(),
+        service_type: service_type.to_string(),
+        domain: domain.to_string(),
+        port,
+        txt: encode_txt_records(node_info),
+    }
+}
+
+/// TXT record entries describing a node
+///
+/// Lists are stored under indexed keys since TXT keys must be unique.
+/// Entries longer than a TXT string can hold are left out.
+pub fn encode_txt_records(node_info: &NodeInfo) -> Vec<String> {
+    let mut entries = vec![
+        format!("txtvers={}", TXT_VERSION),
+        format!("node_id={}", node_info.node_id),
+        format!("name={}", node_info.display_name),
+        format!("context={}", node_info.context_id),
+    ];
+    entries.extend(
+        node_info
+            .capabilities
+            .iter()
+            .enumerate()
+            .map(|(i, capability)| format!("cap{}={}", i, encode_capability(capability))),
+    );
+    entries.extend(node_info.endpoints.iter().enumerate().map(|(i, endpoint)| format!("ep{}={}", i, endpoint)));
+    let mut metadata: Vec<_> = node_info.metadata.iter().collect();
+    metadata.sort();
+    entries.extend(metadata.into_iter().map(|(key, value)| format!("{}{}={}", METADATA_PREFIX, key, value)));
+    entries.retain(|entry| entry.len() <= MAX_TXT_ENTRY_LEN);
+    entries
+}
+
+/// Node described by TXT record entries, as first seen at `now`
+pub fn decode_txt_records(txt: &[String], now: DateTime<Utc>) -> Result<NodeInfo, DiscoveryError> {
+    let mut fields = HashMap::new();
+    for entry in txt {
+        let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+        // Only the first occurrence of a key counts (RFC 6763 section 6.4)
+        fields.entry(key.to_ascii_lowercase()).or_insert_with(|| value.to_string());
+    }
+
+    let node_id = fields
+        .get("node_id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or(DiscoveryError::InvalidNodeInfo)?;
+    let indexed = |prefix: &str| -> Vec<String> {
+        let mut values: Vec<(usize, &String)> = fields
+            .iter()
+            .filter_map(|(key, value)| key.strip_prefix(prefix)?.parse().ok().map(|i| (i, value)))
+            .collect();
+        values.sort();
+        values.into_iter().map(|(_, value)| value.clone()).collect()
+    };
+
+    Ok(NodeInfo {
+        node_id,
+        display_name: fields.get("name").cloned().unwrap_or_default(),
+        context_id: fields.get("context").cloned().unwrap_or_default(),
+        capabilities: indexed("cap").iter().map(|c| decode_capability(c)).collect(),
+        endpoints: indexed("ep"),
+        discovered_at: now,
+        last_seen: now,
+        is_online: true,
+        metadata: txt
+            .iter()
+            .filter_map(|entry| {
+                let (key, value) = entry.split_once('=')?;
+                Some((key.strip_prefix(METADATA_PREFIX)?.to_string(), value.to_string()))
+            })
+            .collect(),
+    })
+}
+
+fn encode_capability(capability: &NodeCapability) -> String {
+    match capability {
+        NodeCapability::ContextSpecific(name) => format!("context:{}", name),
+        NodeCapability::Custom(name) => format!("custom:{}", name),
+        other => serde_json::to_value(other)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default(),
+    }
+}
+
+fn decode_capability(encoded: &str) -> NodeCapability {
+    if let Some(name) = encoded.strip_prefix("context:") {
+        return NodeCapability::ContextSpecific(name.to_string());
+    }
+    if let Some(name) = encoded.strip_prefix("custom:") {
+        return NodeCapability::Custom(name.to_string());
+    }
+    serde_json::from_value(serde_json::Value::String(encoded.to_string()))
+        .unwrap_or_else(|_| NodeCapability::Custom(encoded.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networking::node_discovery::{AnnouncementType, NodeAnnouncement};
+
+    fn node() -> NodeInfo {
+        NodeInfo {
+            node_id: Uuid::new_v4(),
+            display_name: "Build Agent".to_string(),
+            context_id: "acme".to_string(),
+            capabilities: vec![
+                NodeCapability::GitIntegration,
+                NodeCapability::ContextSpecific("ci".to_string()),
+                NodeCapability::Custom("gpu".to_string()),
+            ],
+            endpoints: vec!["tcp/10.0.0.7:7447".to_string(), "udp/10.0.0.7:7448".to_string()],
+            discovered_at: Utc::now(),
+            last_seen: Utc::now(),
+            is_online: true,
+            metadata: HashMap::from([("region".to_string(), "eu-north".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_txt_records_decode_to_announcement_schema() {
+        let original = node();
+        let service = node_service(&original, DNS_SD_SERVICE_TYPE, "mesh.example.com.");
+        assert_eq!(service.instance_name, original.node_id.to_string());
+        assert_eq!(service.port, 7447);
+
+        let decoded = decode_txt_records(&service.txt, original.discovered_at).unwrap();
+        let mut expected = original.clone();
+        expected.last_seen = original.discovered_at;
+        let announce = |node_info| NodeAnnouncement {
+            node_info,
+            announcement_type: AnnouncementType::Join,
+            timestamp: original.discovered_at,
+            public_key: None,
+            signature: None,
+        };
+        assert_eq!(
+            serde_json::to_value(announce(decoded)).unwrap(),
+            serde_json::to_value(announce(expected)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_malformed_txt_records() {
+        let now = Utc::now();
+        let missing_id = vec!["txtvers=1".to_string(), "name=ghost".to_string()];
+        assert!(matches!(decode_txt_records(&missing_id, now), Err(DiscoveryError::InvalidNodeInfo)));
+
+        let id = Uuid::new_v4();
+        let txt = vec![format!("node_id={}", id), "NAME=first".to_string(), "name=second".to_string(), "flag".to_string()];
+        let decoded = decode_txt_records(&txt, now).unwrap();
+        assert_eq!(decoded.node_id, id);
+        assert_eq!(decoded.display_name, "first");
+
+        let mut oversized = node();
+        oversized.metadata.insert("blob".to_string(), "x".repeat(300));
+        assert!(encode_txt_records(&oversized).iter().all(|entry| !entry.starts_with("m.blob=")));
+    }
+
+    #[test]
+    fn test_mdns_serves_only_the_local_domain() {
+        assert_eq!(MdnsSdClient::type_domain(DNS_SD_SERVICE_TYPE, "local.").unwrap(), "_weavemesh._tcp.local.");
+        assert_eq!(MdnsSdClient::type_domain(DNS_SD_SERVICE_TYPE, "Local").unwrap(), "_weavemesh._tcp.local.");
+        assert!(matches!(
+            MdnsSdClient::type_domain(DNS_SD_SERVICE_TYPE, "mesh.example.com."),
+            Err(DiscoveryError::ConfigurationError(_))
+        ));
+    }
+}
@@ -0,0 +1,580 @@
+//! Endpoint scoring and failover for direct messages
+//!
+//! A peer can be reachable through several [`NodeEndpoint`]s. The
+//! [`EndpointScoreboard`] tracks delivery success and latency per
+//! (peer, endpoint) and keeps one preferred endpoint per peer. Endpoints
+//! start from their type's [`default_preference`](crate::mesh::node::EndpointType::default_preference),
+//! so LAN paths win over relays until traffic says otherwise.
+//!
+//! The preferred endpoint is sticky: it only changes when it is demoted after
+//! `failover_after` consecutive failures, or when another endpoint outscores
+//! it by `switch_margin`. Demoted endpoints are probed every
+//! `probe_interval_secs` and come back with a clean record once a probe
+//! succeeds. [`EndpointRouter`] drives a transport with this policy and
+//! publishes a [`PreferredEndpointChange`] whenever a peer's preferred
+//! endpoint moves.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::mesh::node::NodeEndpoint;
+use crate::networking::node_communication::CommunicationError;
+use crate::networking::zenoh_integration::WeaveMeshMessage;
+
+/// How endpoints are scored and failed over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointScoringConfig {
+    /// Consecutive failures after which an endpoint is demoted
+    pub failover_after: u32,
+    /// Seconds between recovery probes of a demoted endpoint
+    pub probe_interval_secs: u64,
+    /// Weight of the newest outcome in the success rate and latency averages
+    pub smoothing: f64,
+    /// Latency (ms) at which an endpoint's score is halved
+    pub reference_latency_ms: f64,
+    /// Score lead another endpoint needs to take over as preferred
+    pub switch_margin: f64,
+}
+
+impl Default for EndpointScoringConfig {
+    fn default() -> Self {
+        Self {
+            failover_after: 3,
+            probe_interval_secs: 30,
+            smoothing: 0.2,
+            reference_latency_ms: 100.0,
+            switch_margin: 0.1,
+        }
+    }
+}
+
+/// Identifier of an endpoint within a peer
+pub fn endpoint_key(endpoint: &NodeEndpoint) -> String {
+    format!("{:?}@{}:{}", endpoint.endpoint_type, endpoint.address, endpoint.port)
+}
+
+/// Delivery record of one (peer, endpoint)
+#[derive(Debug, Clone)]
+struct EndpointRecord {
+    endpoint: NodeEndpoint,
+    success_rate: f64,
+    avg_latency_ms: Option<f64>,
+    consecutive_failures: u32,
+    next_probe_at: Option<DateTime<Utc>>,
+}
+
+impl EndpointRecord {
+    fn new(endpoint: NodeEndpoint) -> Self {
+        Self {
+            endpoint,
+            success_rate: 1.0,
+            avg_latency_ms: None,
+            consecutive_failures: 0,
+            next_probe_at: None,
+        }
+    }
+
+    fn demoted(&self) -> bool {
+        self.next_probe_at.is_some()
+    }
+
+    fn score(&self, config: &EndpointScoringConfig) -> f64 {
+        let latency_factor = match self.avg_latency_ms {
+            Some(latency) => config.reference_latency_ms / (config.reference_latency_ms + latency.max(0.0)),
+            None => 1.0,
+        };
+        // Declared priority only breaks ties
+        self.endpoint.endpoint_type.default_preference() * self.success_rate * latency_factor
+            - self.endpoint.priority as f64 * 1e-4
+    }
+
+    fn record_latency(&mut self, latency: Duration, smoothing: f64) {
+        let sample = latency.as_secs_f64() * 1000.0;
+        self.avg_latency_ms = Some(match self.avg_latency_ms {
+            Some(average) => average * (1.0 - smoothing) + sample * smoothing,
+            None => sample,
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PeerEndpoints {
+    records: Vec<EndpointRecord>,
+    preferred: Option<usize>,
+}
+
+/// Score of one endpoint, for diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointScore {
+    /// Endpoint scored
+    pub endpoint: NodeEndpoint,
+    /// Current score; higher is better
+    pub score: f64,
+    /// Smoothed delivery success rate (0.0-1.0)
+    pub success_rate: f64,
+    /// Smoothed latency, if any delivery has been timed
+    pub avg_latency_ms: Option<f64>,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Whether the endpoint is demoted and awaiting a recovery probe
+    pub demoted: bool,
+    /// Whether this is the peer's preferred endpoint
+    pub preferred: bool,
+}
+
+/// A peer's preferred endpoint moved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreferredEndpointChange {
+    /// Peer affected
+    pub peer: Uuid,
+    /// Endpoint preferred before
+    pub previous: Option<NodeEndpoint>,
+    /// Endpoint preferred now
+    pub current: Option<NodeEndpoint>,
+    /// When the change happened
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Per-peer endpoint scores and preference
+#[derive(Debug, Clone, Default)]
+pub struct EndpointScoreboard {
+    config: EndpointScoringConfig,
+    peers: HashMap<Uuid, PeerEndpoints>,
+}
+
+impl EndpointScoreboard {
+    /// Create an empty scoreboard
+    pub fn new(config: EndpointScoringConfig) -> Self {
+        Self {
+            config,
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Set the endpoints a peer is reachable at
+    ///
+    /// Records of endpoints the peer still has are kept.
+    pub fn set_endpoints(&mut self, peer: Uuid, endpoints: Vec<NodeEndpoint>, now: DateTime<Utc>) -> Option<PreferredEndpointChange> {
+        let state = self.peers.entry(peer).or_default();
+        let previous = state.preferred.map(|i| state.records[i].endpoint.clone());
+        let mut old: HashMap<String, EndpointRecord> = state.records
+            .drain(..)
+            .map(|record| (endpoint_key(&record.endpoint), record))
+            .collect();
+        state.records = endpoints
+            .into_iter()
+            .map(|endpoint| match old.remove(&endpoint_key(&endpoint)) {
+                Some(record) => EndpointRecord { endpoint, ..record },
+                None => EndpointRecord::new(endpoint),
+            })
+            .collect();
+        state.preferred = previous
+            .as_ref()
+            .and_then(|endpoint| state.records.iter().position(|r| endpoint_key(&r.endpoint) == endpoint_key(endpoint)));
+        self.reselect(peer, previous, now)
+    }
+
+    /// Forget a peer
+    pub fn remove_peer(&mut self, peer: &Uuid) {
+        self.peers.remove(peer);
+    }
+
+    /// Whether any endpoint is known for a peer
+    pub fn has_endpoints(&self, peer: &Uuid) -> bool {
+        self.peers.get(peer).is_some_and(|state| !state.records.is_empty())
+    }
+
+    /// Endpoint direct messages to `peer` should use first
+    pub fn preferred(&self, peer: &Uuid) -> Option<&NodeEndpoint> {
+        let state = self.peers.get(peer)?;
+        state.preferred.map(|i| &state.records[i].endpoint)
+    }
+
+    /// Endpoints to try in order: the preferred one, then the rest best first,
+    /// demoted endpoints last
+    pub fn delivery_order(&self, peer: &Uuid) -> Vec<NodeEndpoint> {
+        let state = match self.peers.get(peer) {
+            Some(state) => state,
+            None => return Vec::new(),
+        };
+        let mut order: Vec<usize> = (0..state.records.len()).collect();
+        order.sort_by(|a, b| {
+            let (ra, rb) = (&state.records[*a], &state.records[*b]);
+            (Some(*b) == state.preferred)
+                .cmp(&(Some(*a) == state.preferred))
+                .then(ra.demoted().cmp(&rb.demoted()))
+                .then(rb.score(&self.config).total_cmp(&ra.score(&self.config)))
+        });
+        order.into_iter().map(|i| state.records[i].endpoint.clone()).collect()
+    }
+
+    /// Record a successful delivery
+    pub fn record_success(
+        &mut self,
+        peer: Uuid,
+        endpoint: &NodeEndpoint,
+        latency: Duration,
+        now: DateTime<Utc>,
+    ) -> Option<PreferredEndpointChange> {
+        let smoothing = self.config.smoothing;
+        self.update(peer, endpoint, now, |record| {
+            record.success_rate = record.success_rate * (1.0 - smoothing) + smoothing;
+            record.record_latency(latency, smoothing);
+            record.consecutive_failures = 0;
+        })
+    }
+
+    /// Record a failed delivery, demoting the endpoint after enough in a row
+    pub fn record_failure(&mut self, peer: Uuid, endpoint: &NodeEndpoint, now: DateTime<Utc>) -> Option<PreferredEndpointChange> {
+        let config = self.config.clone();
+        self.update(peer, endpoint, now, |record| {
+            record.success_rate *= 1.0 - config.smoothing;
+            record.consecutive_failures += 1;
+            if record.consecutive_failures >= config.failover_after && !record.demoted() {
+                record.next_probe_at = Some(now + chrono::Duration::seconds(config.probe_interval_secs as i64));
+            }
+        })
+    }
+
+    /// Record the outcome of a recovery probe
+    ///
+    /// A successful probe restores the endpoint with a clean record; a
+    /// failed one schedules the next probe.
+    pub fn record_probe(
+        &mut self,
+        peer: Uuid,
+        endpoint: &NodeEndpoint,
+        latency: Option<Duration>,
+        now: DateTime<Utc>,
+    ) -> Option<PreferredEndpointChange> {
+        let config = self.config.clone();
+        self.update(peer, endpoint, now, |record| match latency {
+            Some(latency) => {
+                let endpoint = record.endpoint.clone();
+                *record = EndpointRecord::new(endpoint);
+                record.record_latency(latency, config.smoothing);
+            }
+            None => {
+                record.next_probe_at = Some(now + chrono::Duration::seconds(config.probe_interval_secs as i64));
+            }
+        })
+    }
+
+    /// Demoted endpoints whose next probe is due
+    pub fn due_probes(&self, now: DateTime<Utc>) -> Vec<(Uuid, NodeEndpoint)> {
+        self.peers
+            .iter()
+            .flat_map(|(peer, state)| {
+                state.records
+                    .iter()
+                    .filter(move |record| record.next_probe_at.is_some_and(|at| at <= now))
+                    .map(move |record| (*peer, record.endpoint.clone()))
+            })
+            .collect()
+    }
+
+    /// Scores of every endpoint of every peer
+    pub fn scores(&self) -> HashMap<Uuid, Vec<EndpointScore>> {
+        self.peers
+            .iter()
+            .map(|(peer, state)| {
+                let scores = state.records
+                    .iter()
+                    .enumerate()
+                    .map(|(i, record)| EndpointScore {
+                        endpoint: record.endpoint.clone(),
+                        score: record.score(&self.config),
+                        success_rate: record.success_rate,
+                        avg_latency_ms: record.avg_latency_ms,
+                        consecutive_failures: record.consecutive_failures,
+                        demoted: record.demoted(),
+                        preferred: state.preferred == Some(i),
+                    })
+                    .collect();
+                (*peer, scores)
+            })
+            .collect()
+    }
+
+    fn update<F: FnOnce(&mut EndpointRecord)>(
+        &mut self,
+        peer: Uuid,
+        endpoint: &NodeEndpoint,
+        now: DateTime<Utc>,
+        apply: F,
+    ) -> Option<PreferredEndpointChange> {
+        let key = endpoint_key(endpoint);
+        let state = self.peers.get_mut(&peer)?;
+        let previous = state.preferred.map(|i| state.records[i].endpoint.clone());
+        apply(state.records.iter_mut().find(|record| endpoint_key(&record.endpoint) == key)?);
+        self.reselect(peer, previous, now)
+    }
+
+    /// Pick the preferred endpoint, keeping the current one unless it is
+    /// demoted or clearly beaten
+    fn reselect(&mut self, peer: Uuid, previous: Option<NodeEndpoint>, now: DateTime<Utc>) -> Option<PreferredEndpointChange> {
+        let config = &self.config;
+        let state = self.peers.get_mut(&peer)?;
+        let best = (0..state.records.len())
+            .filter(|i| !state.records[*i].demoted())
+            .max_by(|a, b| state.records[*a].score(config).total_cmp(&state.records[*b].score(config)))
+            .or_else(|| {
+                (0..state.records.len())
+                    .max_by(|a, b| state.records[*a].score(config).total_cmp(&state.records[*b].score(config)))
+            });
+
+        state.preferred = match (state.preferred, best) {
+            (Some(current), Some(best)) if !state.records[current].demoted() || state.records[best].demoted() => {
+                let lead = state.records[best].score(config) - state.records[current].score(config);
+                if lead > config.switch_margin { Some(best) } else { Some(current) }
+            }
+            (_, best) => best,
+        };
+
+        let current = state.preferred.map(|i| state.records[i].endpoint.clone());
+        let changed = match (&previous, &current) {
+            (Some(a), Some(b)) => endpoint_key(a) != endpoint_key(b),
+            (None, None) => false,
+            _ => true,
+        };
+        changed.then_some(PreferredEndpointChange {
+            peer,
+            previous,
+            current,
+            changed_at: now,
+        })
+    }
+}
+
+/// Sends to one endpoint of a peer
+#[async_trait::async_trait]
+pub trait EndpointTransport: Send + Sync {
+    /// Deliver a message through a specific endpoint
+    async fn send(&self, peer: Uuid, endpoint: &NodeEndpoint, message: &WeaveMeshMessage) -> Result<(), CommunicationError>;
+
+    /// Check whether an endpoint is reachable again
+    async fn probe(&self, peer: Uuid, endpoint: &NodeEndpoint) -> Result<(), CommunicationError>;
+}
+
+/// Delivers direct messages over the best-scoring endpoint, failing over as needed
+pub struct EndpointRouter {
+    transport: Arc<dyn EndpointTransport>,
+    scoreboard: Mutex<EndpointScoreboard>,
+    changes: broadcast::Sender<PreferredEndpointChange>,
+}
+
+impl std::fmt::Debug for EndpointRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EndpointRouter")
+            .field("scoreboard", &self.scoreboard)
+            .finish()
+    }
+}
+
+impl EndpointRouter {
+    /// Create a router over `transport`
+    pub fn new(transport: Arc<dyn EndpointTransport>, config: EndpointScoringConfig) -> Self {
+        Self {
+            transport,
+            scoreboard: Mutex::new(EndpointScoreboard::new(config)),
+            changes: broadcast::channel(64).0,
+        }
+    }
+
+    /// Changes of any peer's preferred endpoint
+    pub fn subscribe(&self) -> broadcast::Receiver<PreferredEndpointChange> {
+        self.changes.subscribe()
+    }
+
+    /// Set the endpoints a peer is reachable at
+    pub fn set_endpoints(&self, peer: Uuid, endpoints: Vec<NodeEndpoint>) {
+        let change = self.scoreboard.lock().unwrap().set_endpoints(peer, endpoints, Utc::now());
+        self.publish(change);
+    }
+
+    /// Whether any endpoint is known for a peer
+    pub fn has_endpoints(&self, peer: &Uuid) -> bool {
+        self.scoreboard.lock().unwrap().has_endpoints(peer)
+    }
+
+    /// Endpoint direct messages to `peer` currently go to first
+    pub fn preferred(&self, peer: &Uuid) -> Option<NodeEndpoint> {
+        self.scoreboard.lock().unwrap().preferred(peer).cloned()
+    }
+
+    /// Scores of every endpoint of every peer
+    pub fn scores(&self) -> HashMap<Uuid, Vec<EndpointScore>> {
+        self.scoreboard.lock().unwrap().scores()
+    }
+
+    /// Deliver a message to `peer`, trying its endpoints best first
+    ///
+    /// Returns the endpoint that took the message, or the last error if
+    /// none did.
+    pub async fn send(&self, peer: Uuid, message: &WeaveMeshMessage) -> Result<NodeEndpoint, CommunicationError> {
+        let order = self.scoreboard.lock().unwrap().delivery_order(&peer);
+        let mut last_error = CommunicationError::NetworkError(format!("no endpoints known for {}", peer));
+        for endpoint in order {
+            let started = Instant::now();
+            let result = self.transport.send(peer, &endpoint, message).await;
+            let change = {
+                let mut scoreboard = self.scoreboard.lock().unwrap();
+                match &result {
+                    Ok(()) => scoreboard.record_success(peer, &endpoint, started.elapsed(), Utc::now()),
+                    Err(_) => scoreboard.record_failure(peer, &endpoint, Utc::now()),
+                }
+            };
+            self.publish(change);
+            match result {
+                Ok(()) => return Ok(endpoint),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Probe every demoted endpoint that is due, returning how many recovered
+    pub async fn probe_demoted(&self) -> usize {
+        let due = self.scoreboard.lock().unwrap().due_probes(Utc::now());
+        let mut recovered = 0;
+        for (peer, endpoint) in due {
+            let started = Instant::now();
+            let latency = self.transport.probe(peer, &endpoint).await.ok().map(|_| started.elapsed());
+            recovered += latency.is_some() as usize;
+            let change = self.scoreboard.lock().unwrap().record_probe(peer, &endpoint, latency, Utc::now());
+            self.publish(change);
+        }
+        recovered
+    }
+
+    /// Probe demoted endpoints every `interval` until the router is dropped
+    pub fn start_probing(router: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let router = Arc::downgrade(router);
+        tokio::spawn(async move {
+            let mut timer = tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+                match router.upgrade() {
+                    Some(router) => {
+                        router.probe_demoted().await;
+                    }
+                    None => break,
+                }
+            }
+        })
+    }
+
+    fn publish(&self, change: Option<PreferredEndpointChange>) {
+        if let Some(change) = change {
+            tracing::info!(
+                peer = %change.peer,
+                endpoint = ?change.current.as_ref().map(endpoint_key),
+                "preferred endpoint changed"
+            );
+            let _ = self.changes.send(change);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::node::EndpointType;
+    use crate::networking::zenoh_integration::{utils::create_message, MessageType};
+    use std::collections::HashSet;
+
+    fn endpoint(endpoint_type: EndpointType, port: u16) -> NodeEndpoint {
+        NodeEndpoint {
+            endpoint_type,
+            address: "10.0.0.5".to_string(),
+            port,
+            secure: false,
+            priority: 0,
+        }
+    }
+
+    /// Transport where selected endpoints are down
+    #[derive(Default)]
+    struct MockTransport {
+        down: Mutex<HashSet<u16>>,
+        sent: Mutex<Vec<u16>>,
+    }
+
+    impl MockTransport {
+        fn set_down(&self, port: u16, down: bool) {
+            let mut set = self.down.lock().unwrap();
+            if down { set.insert(port); } else { set.remove(&port); }
+        }
+
+        fn check(&self, endpoint: &NodeEndpoint) -> Result<(), CommunicationError> {
+            match self.down.lock().unwrap().contains(&endpoint.port) {
+                true => Err(CommunicationError::NetworkError("unreachable".to_string())),
+                false => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EndpointTransport for MockTransport {
+        async fn send(&self, _peer: Uuid, endpoint: &NodeEndpoint, _message: &WeaveMeshMessage) -> Result<(), CommunicationError> {
+            self.sent.lock().unwrap().push(endpoint.port);
+            self.check(endpoint)
+        }
+
+        async fn probe(&self, _peer: Uuid, endpoint: &NodeEndpoint) -> Result<(), CommunicationError> {
+            self.check(endpoint)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_and_recovery() {
+        let transport = Arc::new(MockTransport::default());
+        let router = EndpointRouter::new(transport.clone(), EndpointScoringConfig {
+            probe_interval_secs: 0,
+            ..EndpointScoringConfig::default()
+        });
+        let mut changes = router.subscribe();
+        let peer = Uuid::new_v4();
+        let message = create_message(Uuid::new_v4(), Some(peer), MessageType::Collaboration, Vec::new(), None);
+
+        // LAN is preferred over the relay from the start
+        router.set_endpoints(peer, vec![endpoint(EndpointType::Relay, 2), endpoint(EndpointType::Lan, 1)]);
+        assert_eq!(router.preferred(&peer).unwrap().port, 1);
+        assert_eq!(changes.try_recv().unwrap().current.unwrap().port, 1);
+        assert_eq!(router.send(peer, &message).await.unwrap().port, 1);
+
+        // Failures fall through to the relay for delivery, but preference
+        // only moves after three in a row
+        transport.set_down(1, true);
+        for attempt in 1..=3 {
+            assert_eq!(router.send(peer, &message).await.unwrap().port, 2);
+            let expected = if attempt < 3 { 1 } else { 2 };
+            assert_eq!(router.preferred(&peer).unwrap().port, expected);
+        }
+        let change = changes.try_recv().unwrap();
+        assert_eq!((change.previous.unwrap().port, change.current.unwrap().port), (1, 2));
+        let scores = &router.scores()[&peer];
+        assert!(scores.iter().any(|s| s.endpoint.port == 1 && s.demoted && !s.preferred));
+
+        // The demoted LAN endpoint is no longer tried first
+        transport.sent.lock().unwrap().clear();
+        router.send(peer, &message).await.unwrap();
+        assert_eq!(*transport.sent.lock().unwrap(), vec![2]);
+
+        // A failed probe keeps it demoted; a successful one restores it
+        assert_eq!(router.probe_demoted().await, 0);
+        assert_eq!(router.preferred(&peer).unwrap().port, 2);
+        transport.set_down(1, false);
+        assert_eq!(router.probe_demoted().await, 1);
+        assert_eq!(router.preferred(&peer).unwrap().port, 1);
+        let change = changes.try_recv().unwrap();
+        assert_eq!((change.previous.unwrap().port, change.current.unwrap().port), (2, 1));
+    }
+}
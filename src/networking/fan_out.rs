@@ -0,0 +1,451 @@
+//! Scatter-gather requests under a latency budget
+//!
+//! [`FanOut`] sends a request to every target and returns as soon as a
+//! completeness target is met or the latency budget runs out, whichever
+//! comes first. Peers expected to be slow, going by their recorded latency,
+//! can be sent a hedged duplicate partway through the budget; whichever copy
+//! answers first wins. Answers arriving after the call has returned are
+//! counted in [`FanOutStats`] and still update the latency record.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::networking::node_communication::MessagePriority;
+
+/// Weight of the newest sample in the per-peer latency average
+const LATENCY_WEIGHT: f64 = 0.3;
+
+/// When a fan-out call may return and whether to hedge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOutPolicy {
+    /// Longest the caller will wait
+    pub budget: Duration,
+    /// Fraction of targets (0.0-1.0) whose answers are enough to return
+    pub completeness: f64,
+    /// Fraction of the budget after which slow peers get a hedged request;
+    /// `None` disables hedging
+    pub hedge_after: Option<f64>,
+    /// Most hedged requests per call
+    pub max_hedges: usize,
+    /// Priority every request is sent at, hedges included
+    #[serde(default)]
+    pub priority: MessagePriority,
+}
+
+impl Default for FanOutPolicy {
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_millis(500),
+            completeness: 0.8,
+            hedge_after: Some(0.5),
+            max_hedges: 2,
+            priority: MessagePriority::Normal,
+        }
+    }
+}
+
+impl FanOutPolicy {
+    /// Number of answers needed out of `targets`
+    pub fn required(&self, targets: usize) -> usize {
+        let required = (targets as f64 * self.completeness.clamp(0.0, 1.0)).ceil() as usize;
+        required.min(targets)
+    }
+}
+
+/// What a fan-out call gathered before returning
+#[derive(Debug)]
+pub struct FanOutResult<R> {
+    /// First answer from each peer that answered in time
+    pub responses: HashMap<Uuid, R>,
+    /// Peers whose every request failed
+    pub failed: Vec<Uuid>,
+    /// Peers still outstanding at return
+    pub pending: Vec<Uuid>,
+    /// Peers that were sent a hedged request
+    pub hedged: Vec<Uuid>,
+    /// Whether the completeness target was met
+    pub complete: bool,
+    /// Time from start to return
+    pub elapsed: Duration,
+}
+
+/// Counters across all calls of a [`FanOut`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanOutStats {
+    /// Fan-out calls made
+    pub calls: u64,
+    /// Requests sent, hedges included
+    pub requests_sent: u64,
+    /// Hedged duplicates sent
+    pub hedges_sent: u64,
+    /// Successful answers accepted before return
+    pub responses: u64,
+    /// Requests that returned an error
+    pub failures: u64,
+    /// Answers discarded because the peer had already answered
+    pub duplicates: u64,
+    /// Answers that arrived after the call returned
+    pub late_responses: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    calls: AtomicU64,
+    requests_sent: AtomicU64,
+    hedges_sent: AtomicU64,
+    responses: AtomicU64,
+    failures: AtomicU64,
+    duplicates: AtomicU64,
+    late_responses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    counters: Counters,
+    latencies: Mutex<HashMap<Uuid, Duration>>,
+}
+
+impl Shared {
+    fn record_latency(&self, peer: Uuid, latency: Duration) {
+        let mut latencies = self.latencies.lock().unwrap();
+        let average = match latencies.get(&peer) {
+            Some(previous) => previous.mul_f64(1.0 - LATENCY_WEIGHT) + latency.mul_f64(LATENCY_WEIGHT),
+            None => latency,
+        };
+        latencies.insert(peer, average);
+    }
+}
+
+/// One request's outcome
+struct Reply<R> {
+    peer: Uuid,
+    elapsed: Duration,
+    result: anyhow::Result<R>,
+}
+
+/// Budgeted, hedging scatter-gather with per-peer latency tracking
+#[derive(Debug, Clone, Default)]
+pub struct FanOut {
+    shared: Arc<Shared>,
+}
+
+impl FanOut {
+    /// Create a fan-out with no latency history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an observed round trip to a peer
+    pub fn record_latency(&self, peer: Uuid, latency: Duration) {
+        self.shared.record_latency(peer, latency);
+    }
+
+    /// Smoothed round trip to a peer, if any has been recorded
+    pub fn expected_latency(&self, peer: &Uuid) -> Option<Duration> {
+        self.shared.latencies.lock().unwrap().get(peer).copied()
+    }
+
+    /// Snapshot of the counters
+    pub fn stats(&self) -> FanOutStats {
+        let c = &self.shared.counters;
+        FanOutStats {
+            calls: c.calls.load(Ordering::Relaxed),
+            requests_sent: c.requests_sent.load(Ordering::Relaxed),
+            hedges_sent: c.hedges_sent.load(Ordering::Relaxed),
+            responses: c.responses.load(Ordering::Relaxed),
+            failures: c.failures.load(Ordering::Relaxed),
+            duplicates: c.duplicates.load(Ordering::Relaxed),
+            late_responses: c.late_responses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Send `request` to every target and gather answers under `policy`
+    ///
+    /// `request` is called with the peer and `policy.priority`, so hedged
+    /// duplicates go out, and are answered, at the priority of the original.
+    /// Requests run as detached tasks, so outstanding ones keep going after
+    /// this returns; their answers are tallied as late responses.
+    pub async fn request<R, F, Fut>(&self, targets: &[Uuid], policy: &FanOutPolicy, request: F) -> FanOutResult<R>
+    where
+        F: Fn(Uuid, MessagePriority) -> Fut,
+        Fut: Future<Output = anyhow::Result<R>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let start = Instant::now();
+        let deadline = start + policy.budget;
+        let counters = &self.shared.counters;
+        counters.calls.fetch_add(1, Ordering::Relaxed);
+
+        let mut seen = HashSet::new();
+        let peers: Vec<Uuid> = targets.iter().copied().filter(|p| seen.insert(*p)).collect();
+        let required = policy.required(peers.len());
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut in_flight: HashMap<Uuid, usize> = HashMap::new();
+        for peer in &peers {
+            spawn_request(*peer, policy.priority, &request, &tx);
+            in_flight.insert(*peer, 1);
+        }
+        counters.requests_sent.fetch_add(peers.len() as u64, Ordering::Relaxed);
+
+        let mut hedge_at = policy
+            .hedge_after
+            .map(|fraction| start + policy.budget.mul_f64(fraction.clamp(0.0, 1.0)));
+        let mut responses = HashMap::new();
+        let mut failed = Vec::new();
+        let mut hedged = Vec::new();
+
+        while responses.len() < required && responses.len() + failed.len() < peers.len() {
+            let wake = match hedge_at {
+                Some(at) => at.min(deadline),
+                None => deadline,
+            };
+            tokio::select! {
+                reply = rx.recv() => {
+                    let reply: Reply<R> = match reply {
+                        Some(reply) => reply,
+                        None => break,
+                    };
+                    if let Some(count) = in_flight.get_mut(&reply.peer) {
+                        *count -= 1;
+                    }
+                    match reply.result {
+                        Ok(value) => {
+                            self.shared.record_latency(reply.peer, reply.elapsed);
+                            match responses.entry(reply.peer) {
+                                Entry::Occupied(_) => {
+                                    counters.duplicates.fetch_add(1, Ordering::Relaxed);
+                                }
+                                Entry::Vacant(slot) => {
+                                    counters.responses.fetch_add(1, Ordering::Relaxed);
+                                    failed.retain(|p| *p != reply.peer);
+                                    slot.insert(value);
+                                }
+                            }
+                        }
+                        Err(_) => {
+                            counters.failures.fetch_add(1, Ordering::Relaxed);
+                            let exhausted = in_flight.get(&reply.peer).copied() == Some(0);
+                            if exhausted && !responses.contains_key(&reply.peer) && !failed.contains(&reply.peer) {
+                                failed.push(reply.peer);
+                            }
+                        }
+                    }
+                }
+                _ = tokio::time::sleep_until(wake) => {
+                    if Instant::now() >= deadline {
+                        break;
+                    }
+                    hedge_at = None;
+                    for peer in self.hedge_candidates(&peers, &responses, &failed, policy) {
+                        spawn_request(peer, policy.priority, &request, &tx);
+                        *in_flight.entry(peer).or_insert(0) += 1;
+                        hedged.push(peer);
+                    }
+                    counters.requests_sent.fetch_add(hedged.len() as u64, Ordering::Relaxed);
+                    counters.hedges_sent.fetch_add(hedged.len() as u64, Ordering::Relaxed);
+                }
+            }
+        }
+
+        // Tally whatever is still in flight once it lands
+        drop(tx);
+        let shared = self.shared.clone();
+        tokio::spawn(async move {
+            while let Some(reply) = rx.recv().await {
+                shared.counters.late_responses.fetch_add(1, Ordering::Relaxed);
+                if reply.result.is_ok() {
+                    shared.record_latency(reply.peer, reply.elapsed);
+                }
+            }
+        });
+
+        let pending = peers
+            .iter()
+            .filter(|p| !responses.contains_key(*p) && !failed.contains(*p))
+            .copied()
+            .collect();
+        FanOutResult {
+            complete: responses.len() >= required,
+            responses,
+            failed,
+            pending,
+            hedged,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    /// Unanswered peers with the slowest expected latency first
+    ///
+    /// Peers with no recorded latency are assumed to be the slowest.
+    fn hedge_candidates<R>(
+        &self,
+        peers: &[Uuid],
+        responses: &HashMap<Uuid, R>,
+        failed: &[Uuid],
+        policy: &FanOutPolicy,
+    ) -> Vec<Uuid> {
+        let mut candidates: Vec<(Uuid, Duration)> = peers
+            .iter()
+            .filter(|p| !responses.contains_key(*p) && !failed.contains(*p))
+            .map(|p| (*p, self.expected_latency(p).unwrap_or(Duration::MAX)))
+            .collect();
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.1));
+        candidates.into_iter().take(policy.max_hedges).map(|(p, _)| p).collect()
+    }
+}
+
+fn spawn_request<R, F, Fut>(peer: Uuid, priority: MessagePriority, request: &F, tx: &mpsc::UnboundedSender<Reply<R>>)
+where
+    F: Fn(Uuid, MessagePriority) -> Fut,
+    Fut: Future<Output = anyhow::Result<R>> + Send + 'static,
+    R: Send + 'static,
+{
+    let future = request(peer, priority);
+    let tx = tx.clone();
+    tokio::spawn(async move {
+        let sent = Instant::now();
+        let result = future.await;
+        let _ = tx.send(Reply { peer, elapsed: sent.elapsed(), result });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mock peers whose nth request takes the nth listed latency (the last repeats)
+    struct MockPeers {
+        latencies: Arc<HashMap<Uuid, Vec<u64>>>,
+        calls: Arc<Mutex<HashMap<Uuid, usize>>>,
+    }
+
+    impl MockPeers {
+        fn new(latencies: Vec<(Uuid, Vec<u64>)>) -> Self {
+            Self {
+                latencies: Arc::new(latencies.into_iter().collect()),
+                calls: Arc::default(),
+            }
+        }
+
+        fn request(&self, peer: Uuid) -> impl Future<Output = anyhow::Result<Uuid>> + Send + 'static {
+            let attempt = {
+                let mut calls = self.calls.lock().unwrap();
+                let count = calls.entry(peer).or_insert(0);
+                *count += 1;
+                *count - 1
+            };
+            let plan = &self.latencies[&peer];
+            let millis = plan[attempt.min(plan.len() - 1)];
+            async move {
+                tokio::time::sleep(Duration::from_millis(millis)).await;
+                Ok(peer)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_returns_at_completeness_threshold() {
+        let peers: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let slow = peers[4];
+        let mock = MockPeers::new(
+            peers.iter().map(|p| (*p, vec![if *p == slow { 400 } else { 10 }])).collect(),
+        );
+        let fan_out = FanOut::new();
+        let policy = FanOutPolicy {
+            budget: Duration::from_secs(2),
+            completeness: 0.8,
+            hedge_after: None,
+            max_hedges: 0,
+            ..FanOutPolicy::default()
+        };
+
+        let result = fan_out.request(&peers, &policy, |p, _| mock.request(p)).await;
+
+        assert!(result.complete);
+        assert_eq!(result.responses.len(), 4);
+        assert_eq!(result.pending, vec![slow]);
+        assert!(result.elapsed < Duration::from_millis(300));
+
+        // The straggler is still counted once it answers
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let stats = fan_out.stats();
+        assert_eq!(stats.responses, 4);
+        assert_eq!(stats.late_responses, 1);
+        assert!(fan_out.expected_latency(&slow).unwrap() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_hedging_reduces_tail_latency() {
+        let fast = Uuid::new_v4();
+        let slow = Uuid::new_v4();
+        // The slow peer's first path stalls; a retry goes through quickly
+        let plan = vec![(fast, vec![10]), (slow, vec![600, 10])];
+        let policy = FanOutPolicy {
+            budget: Duration::from_secs(2),
+            completeness: 1.0,
+            hedge_after: Some(0.05),
+            max_hedges: 1,
+            priority: MessagePriority::Critical,
+        };
+
+        let unhedged = FanOutPolicy { hedge_after: None, ..policy.clone() };
+        let mock = MockPeers::new(plan.clone());
+        let baseline = FanOut::new().request(&[fast, slow], &unhedged, |p, _| mock.request(p)).await;
+        assert!(baseline.complete);
+        assert!(baseline.elapsed >= Duration::from_millis(550));
+
+        let mock = MockPeers::new(plan);
+        let fan_out = FanOut::new();
+        let priorities = Arc::new(Mutex::new(Vec::new()));
+        let result = fan_out.request(&[fast, slow], &policy, |p, priority| {
+            priorities.lock().unwrap().push(priority);
+            mock.request(p)
+        }).await;
+        assert!(result.complete);
+        assert_eq!(result.hedged, vec![slow]);
+        // The hedge goes out at the original request's priority
+        assert_eq!(*priorities.lock().unwrap(), vec![MessagePriority::Critical; 3]);
+        assert!(result.elapsed < Duration::from_millis(400));
+        assert_eq!(fan_out.stats().hedges_sent, 1);
+    }
+
+    #[tokio::test]
+    async fn test_hedged_duplicates_are_deduplicated() {
+        let steady = Uuid::new_v4();
+        let flaky = Uuid::new_v4();
+        // Both of the flaky peer's copies land before the steady peer answers
+        let mock = MockPeers::new(vec![(steady, vec![300]), (flaky, vec![100])]);
+        let fan_out = FanOut::new();
+        fan_out.record_latency(steady, Duration::from_millis(50));
+        fan_out.record_latency(flaky, Duration::from_millis(200));
+        let policy = FanOutPolicy {
+            budget: Duration::from_secs(1),
+            completeness: 1.0,
+            hedge_after: Some(0.02),
+            max_hedges: 1,
+            ..FanOutPolicy::default()
+        };
+
+        let result = fan_out.request(&[steady, flaky, flaky], &policy, |p, _| mock.request(p)).await;
+
+        assert!(result.complete);
+        assert_eq!(result.hedged, vec![flaky]);
+        assert_eq!(result.responses.len(), 2);
+        assert_eq!(result.responses[&flaky], flaky);
+        let stats = fan_out.stats();
+        assert_eq!(stats.requests_sent, 3);
+        assert_eq!(stats.responses, 2);
+        assert_eq!(stats.duplicates, 1);
+        assert_eq!(stats.late_responses, 0);
+    }
+}
@@ -0,0 +1,179 @@
+//! Networking health reporting over plain HTTP
+//!
+//! [`NetworkingHealthReport`] summarizes whether the networking stack is up.
+//! [`serve`] answers `GET /health` with that report as JSON on a bare Tokio
+//! listener, so liveness probes work without the full `http` module. The
+//! status is 200 while networking is active and 503 otherwise.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::debug;
+
+use super::{NodeCommunication, NodeDiscovery, ZenohSession};
+
+/// Largest request head read before giving up on a connection
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// How long a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Health of the networking stack at one instant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkingHealthReport {
+    /// Whether networking has been started
+    pub is_active: bool,
+    /// Whether the Zenoh session is connected
+    pub zenoh_connected: bool,
+    /// Nodes known to discovery
+    pub nodes_discovered: usize,
+    /// When a message was last sent or received
+    pub last_message_at: Option<DateTime<Utc>>,
+    /// Each provider by name, with whether its last call succeeded
+    pub providers_healthy: Vec<(String, bool)>,
+}
+
+impl NetworkingHealthReport {
+    /// Report as a JSON HTTP response body
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("health report serializes")
+    }
+}
+
+/// Sources a health report is read from, shareable with the server task
+#[derive(Clone)]
+pub(crate) struct HealthProbe {
+    pub(crate) zenoh_session: Option<Arc<ZenohSession>>,
+    pub(crate) node_discovery: Option<Arc<NodeDiscovery>>,
+    pub(crate) node_communication: Option<Arc<NodeCommunication>>,
+    pub(crate) is_active: Arc<AtomicBool>,
+    pub(crate) provider_health: Arc<Mutex<Vec<(String, bool)>>>,
+}
+
+impl HealthProbe {
+    /// Current health report
+    pub(crate) async fn report(&self) -> NetworkingHealthReport {
+        let zenoh_connected = match &self.zenoh_session {
+            Some(session) => session.is_connected().await,
+            None => false,
+        };
+        let nodes_discovered = match &self.node_discovery {
+            Some(discovery) => discovery.get_all_nodes().await.len(),
+            None => 0,
+        };
+        let last_message_at = match &self.node_communication {
+            Some(comm) => comm.get_stats().await.last_message_at,
+            None => None,
+        };
+        NetworkingHealthReport {
+            is_active: self.is_active.load(Ordering::SeqCst),
+            zenoh_connected,
+            nodes_discovered,
+            last_message_at,
+            providers_healthy: self.provider_health.lock().expect("provider health lock poisoned").clone(),
+        }
+    }
+}
+
+/// Running health server; see [`serve`]
+#[derive(Debug)]
+pub struct HealthServerHandle {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl HealthServerHandle {
+    /// Address the server listens on, with the port resolved
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop accepting connections and drop those still being answered
+    ///
+    /// The listener is closed once this returns.
+    pub async fn stop(self) {
+        self.task.abort();
+        let _ = self.task.await;
+    }
+}
+
+/// Listen on `addr` and answer health requests from `probe`
+pub(crate) async fn serve(addr: &str, probe: HealthProbe) -> std::io::Result<HealthServerHandle> {
+    let listener = TcpListener::bind(addr).await?;
+    let local_addr = listener.local_addr()?;
+    let task = tokio::spawn(async move {
+        // Dropped with the accept loop, aborting connections in flight
+        let mut connections = JoinSet::new();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let (stream, peer) = match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            debug!("Health server accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let probe = probe.clone();
+                    connections.spawn(async move {
+                        if let Err(e) = respond(stream, &probe).await {
+                            debug!("Health request from {} failed: {}", peer, e);
+                        }
+                    });
+                }
+                Some(_) = connections.join_next() => {}
+            }
+        }
+    });
+    Ok(HealthServerHandle { local_addr, task })
+}
+
+async fn respond(mut stream: TcpStream, probe: &HealthProbe) -> std::io::Result<()> {
+    let head = match tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await {
+        Ok(head) => head?,
+        Err(_) => return Ok(()),
+    };
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default();
+
+    let (status, body) = match (method, path) {
+        ("GET", "/health") => {
+            let report = probe.report().await;
+            let status = if report.is_active { "200 OK" } else { "503 Service Unavailable" };
+            (status, report.to_json())
+        }
+        (_, "/health") => ("405 Method Not Allowed", String::new()),
+        _ => ("404 Not Found", String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Read up to the end of the request headers
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
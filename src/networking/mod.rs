@@ -7,6 +7,16 @@
 pub mod zenoh_integration;
 pub mod node_discovery;
 pub mod node_communication;
+pub mod trace_context;
+pub mod payload_summary;
+pub mod fan_out;
+pub mod dns_sd;
+pub mod endpoint_scoring;
+pub mod clock_skew;
+pub mod query_cache;
+pub mod broadcast_scope;
+pub mod system_control;
+pub mod health;
 
 // Re-export key types for convenience
 pub use zenoh_integration::{
@@ -15,16 +25,45 @@ pub use zenoh_integration::{
 };
 pub use node_discovery::{
     NodeDiscovery, DiscoveryConfig, NodeInfo, NodeCapability, NodeAnnouncement,
-    AnnouncementType, DiscoveryQuery, NodeFilter, DiscoveryError
+    AnnouncementType, DiscoveryQuery, NodeFilter, DiscoveryError, HeartbeatPayload,
+    DiscoveryBackend, NodeQueryCache, AnnouncementTrust, check_announcement_trust,
 };
+pub use dns_sd::{DnsSdClient, DnsSdService, MdnsSdClient, DNS_SD_SERVICE_TYPE};
 pub use node_communication::{
     NodeCommunication, CommunicationConfig, IncomingMessage, OutgoingMessage,
     DeliveryOptions, MessagePriority, MessageResult, CommunicationStats,
-    CommunicationError, MessageHandler
+    CommunicationStatsSnapshot, CommunicationRates, ResponseLatency, AllianceBindings,
+    CommunicationError, MessageHandler, AsyncMessageHandler, SyncHandler, HandlerOptions,
+    PeerHandlers, UnhandledTypePolicy, ControlHandler, ControlHandlers, RetryStrategy,
+    DEFAULT_RETRY_BASE_DELAY,
+    HANDLED_TYPES_METADATA_KEY, advertise_handled_types, advertised_handled_types,
 };
+pub use trace_context::TraceContext;
+pub use payload_summary::{
+    ContentClassifier, PayloadSummarizer, PayloadSummary, PayloadSummaryConfig
+};
+pub use clock_skew::{ClockSkewConfig, ClockSkewEvent, ClockSkewTracker, PeerClock, TimeEcho};
+pub use endpoint_scoring::{
+    EndpointRouter, EndpointScore, EndpointScoreboard, EndpointScoringConfig,
+    EndpointTransport, PreferredEndpointChange,
+};
+pub use fan_out::{FanOut, FanOutPolicy, FanOutResult, FanOutStats};
+pub use broadcast_scope::{
+    BroadcastLimits, BroadcastMemberships, BroadcastRateLimiter, BroadcastScope, ScopeLimit,
+    LEGACY_BROADCAST_CONTEXT,
+};
+pub use query_cache::{CachedQuery, QueryCache, QueryCacheConfig, QueryCacheStats};
+pub use system_control::{
+    ControlCommand, ControlEnvelope, ControlError, ControlKind, Lease, LeaseOperation, LeaseTable, TransferAborts,
+    SYSTEM_CONTROL_VERSION,
+};
+pub use health::{HealthServerHandle, NetworkingHealthReport};
+#[cfg(feature = "otlp")]
+pub use trace_context::{TraceExporter, FinishedSpan};
 
 use anyhow::Result;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 /// Universal networking interface for different contexts
@@ -125,7 +164,10 @@ pub struct NetworkingManager {
     providers: Vec<Box<dyn NetworkingProvider>>,
     
     /// Whether networking is active
-    is_active: bool,
+    is_active: Arc<AtomicBool>,
+    
+    /// Provider names, with whether each one's last call succeeded
+    provider_health: Arc<Mutex<Vec<(String, bool)>>>,
 }
 
 impl NetworkingManager {
@@ -136,7 +178,8 @@ impl NetworkingManager {
             node_discovery: None,
             node_communication: None,
             providers: Vec::new(),
-            is_active: false,
+            is_active: Arc::new(AtomicBool::new(false)),
+            provider_health: Arc::new(Mutex::new(Vec::new())),
         }
     }
     
@@ -178,7 +221,7 @@ impl NetworkingManager {
     
     /// Start networking
     pub async fn start(&mut self, node_info: NodeInfo) -> Result<(), NetworkingError> {
-        if self.is_active {
+        if self.is_active() {
             return Ok(());
         }
         
@@ -197,27 +240,27 @@ impl NetworkingManager {
         }
         
         // Initialize all providers
-        for provider in &mut self.providers {
-            provider.initialize(&serde_json::Value::Null)
-                .await
-                .map_err(|e| NetworkingError::ProviderError(e.to_string()))?;
+        for (index, provider) in self.providers.iter_mut().enumerate() {
+            let result = provider.initialize(&serde_json::Value::Null).await;
+            set_provider_health(&self.provider_health, index, result.is_ok());
+            result.map_err(|e| NetworkingError::ProviderError(e.to_string()))?;
         }
         
-        self.is_active = true;
+        self.is_active.store(true, Ordering::SeqCst);
         Ok(())
     }
     
     /// Stop networking
     pub async fn stop(&mut self) -> Result<(), NetworkingError> {
-        if !self.is_active {
+        if !self.is_active() {
             return Ok(());
         }
         
         // Cleanup all providers
-        for provider in &mut self.providers {
-            provider.cleanup()
-                .await
-                .map_err(|e| NetworkingError::ProviderError(e.to_string()))?;
+        for (index, provider) in self.providers.iter_mut().enumerate() {
+            let result = provider.cleanup().await;
+            set_provider_health(&self.provider_health, index, result.is_ok());
+            result.map_err(|e| NetworkingError::ProviderError(e.to_string()))?;
         }
         
         // Stop node discovery
@@ -234,12 +277,14 @@ impl NetworkingManager {
                 .map_err(|e| NetworkingError::StopFailed(e.to_string()))?;
         }
         
-        self.is_active = false;
+        self.is_active.store(false, Ordering::SeqCst);
         Ok(())
     }
     
     /// Register a networking provider
     pub fn register_provider(&mut self, provider: Box<dyn NetworkingProvider>) {
+        self.provider_health.lock().expect("provider health lock poisoned")
+            .push((provider.name().to_string(), true));
         self.providers.push(provider);
     }
     
@@ -280,8 +325,10 @@ impl NetworkingManager {
         }
         
         // Get provider stats
-        for provider in &self.providers {
-            if let Ok(provider_stats) = provider.get_network_stats().await {
+        for (index, provider) in self.providers.iter().enumerate() {
+            let result = provider.get_network_stats().await;
+            set_provider_health(&self.provider_health, index, result.is_ok());
+            if let Ok(provider_stats) = result {
                 // Combine provider stats (implementation specific)
                 stats.uptime_seconds = provider_stats.uptime_seconds.max(stats.uptime_seconds);
             }
@@ -292,17 +339,54 @@ impl NetworkingManager {
     
     /// Broadcast network event to all providers
     pub async fn broadcast_event(&self, event: NetworkEvent) -> Result<(), NetworkingError> {
-        for provider in &self.providers {
-            provider.handle_network_event(&event)
-                .await
-                .map_err(|e| NetworkingError::ProviderError(e.to_string()))?;
+        for (index, provider) in self.providers.iter().enumerate() {
+            let result = provider.handle_network_event(&event).await;
+            set_provider_health(&self.provider_health, index, result.is_ok());
+            result.map_err(|e| NetworkingError::ProviderError(e.to_string()))?;
         }
         Ok(())
     }
     
     /// Check if networking is active
     pub fn is_active(&self) -> bool {
-        self.is_active
+        self.is_active.load(Ordering::SeqCst)
+    }
+    
+    /// Current networking health, asking each provider for its statistics
+    pub async fn health_check(&self) -> NetworkingHealthReport {
+        for (index, provider) in self.providers.iter().enumerate() {
+            let healthy = provider.get_network_stats().await.is_ok();
+            set_provider_health(&self.provider_health, index, healthy);
+        }
+        self.health_probe().report().await
+    }
+    
+    /// Serve `GET /health` on `addr` until the handle is stopped
+    ///
+    /// The server sees the components initialized before it was started.
+    /// Provider health is as of each provider's last call, since providers
+    /// are not shared with the server task.
+    pub async fn start_health_server(&self, addr: &str) -> Result<HealthServerHandle, NetworkingError> {
+        health::serve(addr, self.health_probe())
+            .await
+            .map_err(|e| NetworkingError::HealthServerFailed(e.to_string()))
+    }
+    
+    fn health_probe(&self) -> health::HealthProbe {
+        health::HealthProbe {
+            zenoh_session: self.zenoh_session.clone(),
+            node_discovery: self.node_discovery.clone(),
+            node_communication: self.node_communication.clone(),
+            is_active: Arc::clone(&self.is_active),
+            provider_health: Arc::clone(&self.provider_health),
+        }
+    }
+}
+
+/// Record whether the provider at `index` answered its last call
+fn set_provider_health(provider_health: &Mutex<Vec<(String, bool)>>, index: usize, healthy: bool) {
+    if let Some(entry) = provider_health.lock().expect("provider health lock poisoned").get_mut(index) {
+        entry.1 = healthy;
     }
 }
 
@@ -335,6 +419,9 @@ pub enum NetworkingError {
     
     #[error("Not active")]
     NotActive,
+    
+    #[error("Health server failed: {0}")]
+    HealthServerFailed(String),
 }
 
 /// Utility functions for networking
@@ -480,4 +567,87 @@ mod tests {
         assert!(formatted.contains("100/95"));
         assert!(formatted.contains("25.5ms"));
     }
+
+    struct StubProvider {
+        name: &'static str,
+        healthy: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl NetworkingProvider for StubProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn initialize(&mut self, _config: &serde_json::Value) -> Result<()> {
+            Ok(())
+        }
+
+        async fn handle_network_event(&self, _event: &NetworkEvent) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_network_stats(&self) -> Result<NetworkStats> {
+            if self.healthy {
+                Ok(NetworkStats::default())
+            } else {
+                Err(anyhow::anyhow!("unreachable"))
+            }
+        }
+
+        async fn cleanup(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn http_get(addr: std::net::SocketAddr, request: &str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_health_check_and_server() {
+        let mut manager = NetworkingManager::new();
+        manager.register_provider(Box::new(StubProvider { name: "relay", healthy: true }));
+        manager.register_provider(Box::new(StubProvider { name: "bridge", healthy: false }));
+
+        let report = manager.health_check().await;
+        assert!(!report.is_active);
+        assert!(!report.zenoh_connected);
+        assert_eq!(report.nodes_discovered, 0);
+        assert_eq!(report.last_message_at, None);
+        assert_eq!(report.providers_healthy, vec![("relay".to_string(), true), ("bridge".to_string(), false)]);
+
+        let server = manager.start_health_server("127.0.0.1:0").await.unwrap();
+        let response = http_get(server.local_addr(), "GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 503"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let served: NetworkingHealthReport = serde_json::from_str(body).unwrap();
+        assert_eq!(served, report);
+
+        // Activation is visible to the running server
+        manager.is_active.store(true, Ordering::SeqCst);
+        let response = http_get(server.local_addr(), "GET /health?probe=liveness HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("Content-Type: application/json"));
+
+        let response = http_get(server.local_addr(), "GET /metrics HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404"));
+        let response = http_get(server.local_addr(), "POST /health HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405"));
+
+        // Stopping drops a connection still waiting for its request
+        use tokio::io::AsyncReadExt;
+        let addr = server.local_addr();
+        let mut idle = tokio::net::TcpStream::connect(addr).await.unwrap();
+        server.stop().await;
+        let mut buf = [0u8; 16];
+        let read = tokio::time::timeout(std::time::Duration::from_secs(1), idle.read(&mut buf)).await;
+        assert!(matches!(read, Ok(Ok(0)) | Ok(Err(_))));
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
 }
@@ -5,14 +5,29 @@
 //! different contexts.
 
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::{RwLock, mpsc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+use tokio::sync::{RwLock, Semaphore, broadcast, mpsc, watch};
+use tracing::Instrument;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::networking::broadcast_scope::{BroadcastLimits, BroadcastMemberships, BroadcastRateLimiter, BroadcastScope};
 use crate::networking::zenoh_integration::{ZenohSession, WeaveMeshMessage, MessageType, WeaveMeshTopics};
 use crate::networking::node_discovery::NodeInfo;
+use crate::networking::NetworkEvent;
+use crate::networking::endpoint_scoring::{EndpointRouter, EndpointScore};
+use crate::networking::trace_context::TraceContext;
+use crate::networking::payload_summary::{ContentClassifier, PayloadSummarizer, PayloadSummary, PayloadSummaryConfig};
+use crate::networking::system_control::{ControlCommand, ControlEnvelope, ControlKind, LeaseTable, TransferAborts};
+use crate::mesh::security::SecuritySystem;
+use crate::financial::{UsageKind, UsageMeter, UsageSample};
+use crate::sacred_alliance::{AllianceMessage, BasicSacredAllianceChannel};
+use crate::compute::ComputePool;
+use crate::resource_profile::{
+    ComponentFootprint, EvictionCounters, EvictionKind, MemoryFootprint, ResourceProfile,
+};
 
 /// Universal node communication manager
 /// 
@@ -33,7 +48,16 @@ pub struct NodeCommunication {
     config: CommunicationConfig,
     
     /// Active message handlers
-    message_handlers: Arc<RwLock<HashMap<MessageType, MessageHandler>>>,
+    message_handlers: Arc<RwLock<HashMap<MessageType, RegisteredHandler>>>,
+    
+    /// Subsystems receiving control commands, by kind
+    control_handlers: ControlHandlers,
+    
+    /// Outgoing transfers, aborted by `AbortTransfer` commands
+    transfers: TransferAborts,
+    
+    /// Leases granted through `LeaseOp` commands
+    leases: LeaseTable,
     
     /// Pending message acknowledgments
     pending_acks: Arc<RwLock<HashMap<String, PendingMessage>>>,
@@ -43,8 +67,63 @@ pub struct NodeCommunication {
     
     /// Whether communication is active
     is_active: Arc<RwLock<bool>>,
+    
+    /// Receiver of bytes sent and received, for usage-based costs
+    usage_meter: Option<Arc<dyn UsageMeter>>,
+    
+    /// Incoming message queues, one per priority
+    handler_queues: PriorityQueues,
+    
+    /// Receiving ends of the handler queues: one shared queue that every
+    /// handler worker takes its next message from, under the lock
+    shared_handler_queue: Arc<tokio::sync::Mutex<PriorityReceivers>>,
+    
+    /// Outgoing replies and retries, one queue per priority
+    outbound_queues: PriorityQueues,
+    
+    /// Receiving ends of the outbound queues, drained by the sender task
+    outbound_receivers: Arc<tokio::sync::Mutex<PriorityReceivers>>,
+    
+    /// Classifier deciding which payloads may be previewed in logs
+    content_classifier: Option<Arc<dyn ContentClassifier>>,
+    
+    /// Pool the classifier runs on, keeping it off the message runtime
+    compute_pool: Option<ComputePool>,
+    
+    /// Counts of pending messages dropped by the pending acknowledgment limit
+    eviction_counters: Arc<EvictionCounters>,
+    
+    /// Message types with a registered handler, watched for re-announcement
+    handled_types: watch::Sender<HashSet<MessageType>>,
+    
+    /// Message types each peer has advertised handlers for
+    peer_handlers: Arc<RwLock<PeerHandlers>>,
+    
+    /// Scored per-endpoint delivery for peers with known endpoints
+    endpoint_router: Option<Arc<EndpointRouter>>,
+    
+    /// Periodic stats snapshots for throughput rates
+    stats_history: Arc<std::sync::Mutex<StatsHistory>>,
+    
+    /// `Collaboration` handler feeding the bound Sacred Alliance channels
+    alliance_router: Arc<AllianceRouter>,
+    
+    /// Broadcast scopes this node has joined
+    broadcast_memberships: BroadcastMemberships,
+    
+    /// Per-scope token buckets for outgoing broadcasts
+    broadcast_limiter: Arc<std::sync::Mutex<BroadcastRateLimiter>>,
+    
+    /// Nodes given up on after exhausting their retries
+    network_events: broadcast::Sender<NetworkEvent>,
+    
+    /// Nodes already reported as left and not reached since
+    departed_nodes: Arc<std::sync::Mutex<HashSet<Uuid>>>,
 }
 
+/// Base delay of the global retry strategy
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
 /// Configuration for node communication
 #[derive(Debug, Clone)]
 pub struct CommunicationConfig {
@@ -65,6 +144,38 @@ pub struct CommunicationConfig {
     
     /// Whether to enable debug logging
     pub debug: bool,
+    
+    /// Number of worker tasks running message handlers from the shared
+    /// priority queues
+    pub handler_threads: usize,
+    
+    /// Maximum messages awaiting acknowledgment; the oldest fail first
+    /// when the limit is reached (unbounded if None)
+    pub max_pending_acks: Option<usize>,
+    
+    /// What payload summaries in logs may reveal
+    pub payload_summary: PayloadSummaryConfig,
+    
+    /// What to do when sending a type a peer has no handler for
+    pub unhandled_type_policy: UnhandledTypePolicy,
+    
+    /// Quiet period after a handler change before it is re-announced (milliseconds)
+    pub handler_announcement_debounce_ms: u64,
+    
+    /// Whether broadcasts may address the whole mesh
+    pub allow_global: bool,
+    
+    /// Size and rate limits of broadcasts, per scope
+    pub broadcast_limits: BroadcastLimits,
+    
+    /// Broadcast scopes delivered to handlers; all joined scopes when None
+    pub accepted_broadcast_scopes: Option<Vec<BroadcastScope>>,
+    
+    /// Organization this node belongs to, for organization broadcasts
+    pub organization: Option<String>,
+    
+    /// Retry strategies overriding the global one for specific nodes
+    pub per_node_retry_config: HashMap<Uuid, RetryStrategy>,
 }
 
 impl Default for CommunicationConfig {
@@ -76,13 +187,300 @@ impl Default for CommunicationConfig {
             require_acks: true,
             enable_encryption: true,
             debug: false,
+            handler_threads: 4,
+            max_pending_acks: None,
+            payload_summary: PayloadSummaryConfig::default(),
+            unhandled_type_policy: UnhandledTypePolicy::Warn,
+            handler_announcement_debounce_ms: 500,
+            allow_global: false,
+            broadcast_limits: BroadcastLimits::default(),
+            accepted_broadcast_scopes: None,
+            organization: None,
+            per_node_retry_config: HashMap::new(),
         }
     }
 }
 
-/// Message handler function type
+/// How often and how patiently sends to a node are retried
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryStrategy {
+    /// Retries after which the node is considered gone
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Factor applied to the delay after each retry
+    pub backoff_multiplier: f64,
+    /// Whether delays are randomized to between half and all of their value
+    pub jitter: bool,
+}
+
+impl RetryStrategy {
+    /// Five attempts, doubling from `base`, with jitter
+    pub fn exponential(base: Duration) -> RetryStrategy {
+        RetryStrategy { max_attempts: 5, base_delay: base, backoff_multiplier: 2.0, jitter: true }
+    }
+    
+    /// Delay before retry number `retry` (from 0)
+    ///
+    /// `jitter_seed` is expected in `[0.0, 1.0)` and ignored without jitter.
+    pub fn delay_for(&self, retry: u32, jitter_seed: f64) -> Duration {
+        let delay = self.base_delay.as_secs_f64() * self.backoff_multiplier.max(1.0).powi(retry as i32);
+        let delay = if self.jitter { delay * (0.5 + jitter_seed.clamp(0.0, 1.0) / 2.0) } else { delay };
+        Duration::try_from_secs_f64(delay).unwrap_or(Duration::MAX)
+    }
+}
+
+impl CommunicationConfig {
+    /// Retry strategy for sends to `node_id`
+    ///
+    /// Nodes without an override get `max_retries` attempts, doubling from
+    /// [`DEFAULT_RETRY_BASE_DELAY`].
+    pub fn retry_strategy_for(&self, node_id: &Uuid) -> RetryStrategy {
+        self.per_node_retry_config.get(node_id).cloned().unwrap_or(RetryStrategy {
+            max_attempts: self.max_retries,
+            ..RetryStrategy::exponential(DEFAULT_RETRY_BASE_DELAY)
+        })
+    }
+    
+    /// Default configuration with the message and acknowledgment bounds of a resource profile
+    pub fn for_profile(profile: ResourceProfile) -> Self {
+        let limits = profile.limits();
+        let defaults = Self::default();
+        
+        Self {
+            max_message_size: limits.max_message_size
+                .map_or(defaults.max_message_size, |cap| cap.min(defaults.max_message_size)),
+            max_pending_acks: limits.max_pending_acks,
+            ..defaults
+        }
+    }
+    
+    /// Check that a broadcast of `size` bytes may be sent to `scope`
+    ///
+    /// The mesh scope needs `allow_global`. A scope's size limit never
+    /// exceeds `max_message_size`.
+    pub fn check_broadcast(&self, scope: &BroadcastScope, size: usize) -> Result<(), CommunicationError> {
+        if *scope == BroadcastScope::Mesh && !self.allow_global {
+            return Err(CommunicationError::GlobalBroadcastDisabled);
+        }
+        let max_size = self.broadcast_limits.for_scope(scope).max_message_size.min(self.max_message_size);
+        if size > max_size {
+            return Err(CommunicationError::MessageTooLarge);
+        }
+        Ok(())
+    }
+    
+    /// Whether broadcasts to `scope` reach this node's handlers
+    pub fn accepts_broadcast(&self, scope: &BroadcastScope) -> bool {
+        self.accepted_broadcast_scopes.as_ref().is_none_or(|accepted| accepted.contains(scope))
+    }
+    
+    /// Whether a broadcast to `scope` with wire context `context` is for this node
+    ///
+    /// Organization broadcasts carry the sender's organization as context and
+    /// are only for nodes of the same one; other scopes must have been joined.
+    pub fn is_broadcast_member(&self, scope: &BroadcastScope, context: Option<&str>, memberships: &BroadcastMemberships) -> bool {
+        let in_organization = match scope {
+            BroadcastScope::Organization => self.organization.is_some() && self.organization.as_deref() == context,
+            _ => true,
+        };
+        in_organization && memberships.contains(scope)
+    }
+}
+
+/// Scope a received message was broadcast to, if it is a broadcast
+///
+/// Messages addressed to no node and without a scope are legacy broadcasts
+/// to their context.
+fn broadcast_scope_of(message: &WeaveMeshMessage) -> Option<BroadcastScope> {
+    match (&message.broadcast_scope, &message.to_node) {
+        (Some(scope), _) => Some(scope.clone()),
+        (None, None) => Some(BroadcastScope::legacy(message.context.clone())),
+        (None, Some(_)) => None,
+    }
+}
+
+/// Metadata key under which a node advertises the message types it handles
+pub const HANDLED_TYPES_METADATA_KEY: &str = "handled_message_types";
+
+/// What to do when sending a message type the peer has not advertised a handler for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnhandledTypePolicy {
+    /// Log a warning and send anyway
+    #[default]
+    Warn,
+    /// Fail the send with [`CommunicationError::UnhandledMessageType`]
+    Refuse,
+}
+
+/// Advertise `handled` in a node's announcement metadata
+pub fn advertise_handled_types(node_info: &mut NodeInfo, handled: &HashSet<MessageType>) {
+    let mut types: Vec<&MessageType> = handled.iter().collect();
+    types.sort_by_key(|message_type| format!("{:?}", message_type));
+    node_info.metadata.insert(
+        HANDLED_TYPES_METADATA_KEY.to_string(),
+        serde_json::to_string(&types).unwrap_or_default(),
+    );
+}
+
+/// Message types a node advertises handlers for, if it advertises them at all
+pub fn advertised_handled_types(node_info: &NodeInfo) -> Option<HashSet<MessageType>> {
+    node_info.metadata
+        .get(HANDLED_TYPES_METADATA_KEY)
+        .and_then(|encoded| serde_json::from_str(encoded).ok())
+}
+
+/// Wait for the next change to a watched value, then for `debounce` more
+///
+/// Changes made during the quiet period are coalesced into the returned
+/// value. Returns None once the sender is gone.
+pub async fn next_debounced<T: Clone>(receiver: &mut watch::Receiver<T>, debounce: Duration) -> Option<T> {
+    receiver.changed().await.ok()?;
+    tokio::time::sleep(debounce).await;
+    let latest = receiver.borrow_and_update().clone();
+    Some(latest)
+}
+
+/// Message types peers have advertised handlers for
+#[derive(Debug, Clone, Default)]
+pub struct PeerHandlers {
+    peers: HashMap<Uuid, HashSet<MessageType>>,
+}
+
+impl PeerHandlers {
+    /// Update a peer's entry from its announcement
+    ///
+    /// Peers that do not advertise handlers are forgotten, since nothing
+    /// is known about what they serve.
+    pub fn observe(&mut self, node_info: &NodeInfo) {
+        match advertised_handled_types(node_info) {
+            Some(handled) => {
+                self.peers.insert(node_info.node_id, handled);
+            }
+            None => {
+                self.peers.remove(&node_info.node_id);
+            }
+        }
+    }
+    
+    /// Drop a peer's entry
+    pub fn forget(&mut self, peer: &Uuid) {
+        self.peers.remove(peer);
+    }
+    
+    /// Whether `peer` has advertised a handler for `message_type`
+    pub fn handles(&self, peer: &Uuid, message_type: &MessageType) -> bool {
+        self.peers.get(peer).is_some_and(|handled| handled.contains(message_type))
+    }
+    
+    /// Whether `peer` has advertised its handlers and `message_type` is not among them
+    pub fn known_unhandled(&self, peer: &Uuid, message_type: &MessageType) -> bool {
+        self.peers.get(peer).is_some_and(|handled| !handled.contains(message_type))
+    }
+    
+    /// Apply `policy` to a send of `message_type` to `peer`
+    pub fn check_send(
+        &self,
+        peer: &Uuid,
+        message_type: &MessageType,
+        policy: UnhandledTypePolicy,
+    ) -> Result<(), CommunicationError> {
+        if !self.known_unhandled(peer, message_type) {
+            return Ok(());
+        }
+        match policy {
+            UnhandledTypePolicy::Warn => {
+                tracing::warn!(%peer, ?message_type, "peer has no handler for message type");
+                Ok(())
+            }
+            UnhandledTypePolicy::Refuse => Err(CommunicationError::UnhandledMessageType {
+                peer: *peer,
+                message_type: message_type.clone(),
+            }),
+        }
+    }
+}
+
+/// Synchronous message handler function type
+///
+/// Kept for closure-based registration, which runs through [`SyncHandler`].
+/// Handlers that await storage, the network or locks should implement
+/// [`AsyncMessageHandler`] instead.
 pub type MessageHandler = Box<dyn Fn(IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> + Send + Sync>;
 
+/// Handler for incoming messages of one type
+///
+/// A returned payload is sent back to the sender as the response; `None`
+/// is answered with an acknowledgment when acknowledgments are required.
+#[async_trait::async_trait]
+pub trait AsyncMessageHandler: Send + Sync {
+    /// Handle one message
+    async fn handle(&self, msg: IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError>;
+}
+
+/// Adapter running a synchronous closure as an [`AsyncMessageHandler`]
+pub struct SyncHandler<F>(pub F);
+
+#[async_trait::async_trait]
+impl<F> AsyncMessageHandler for SyncHandler<F>
+where
+    F: Fn(IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> + Send + Sync,
+{
+    async fn handle(&self, msg: IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> {
+        (self.0)(msg)
+    }
+}
+
+/// Limits a handler runs under
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandlerOptions {
+    /// Messages the handler may process at once
+    pub max_concurrency: usize,
+    
+    /// Time allowed per message, including waiting for a concurrency slot;
+    /// the sender is answered with a handler-timeout error after it
+    pub timeout: Duration,
+}
+
+impl Default for HandlerOptions {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 16,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A handler with its concurrency slots
+#[derive(Clone)]
+struct RegisteredHandler {
+    handler: Arc<dyn AsyncMessageHandler>,
+    permits: Arc<Semaphore>,
+    options: HandlerOptions,
+}
+
+impl RegisteredHandler {
+    fn new(handler: Arc<dyn AsyncMessageHandler>, options: HandlerOptions) -> Self {
+        Self {
+            handler,
+            permits: Arc::new(Semaphore::new(options.max_concurrency.max(1))),
+            options,
+        }
+    }
+    
+    /// Run the handler within its concurrency limit and timeout
+    async fn run(&self, incoming: IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> {
+        let timeout = self.options.timeout;
+        tokio::time::timeout(timeout, async {
+            let _permit = self.permits.acquire().await
+                .map_err(|_| CommunicationError::NotActive)?;
+            self.handler.handle(incoming).await
+        })
+        .await
+        .unwrap_or(Err(CommunicationError::HandlerTimeout(timeout)))
+    }
+}
+
 /// Incoming message with context
 #[derive(Debug, Clone)]
 pub struct IncomingMessage {
@@ -100,7 +498,7 @@ pub struct IncomingMessage {
 }
 
 /// Outgoing message with delivery options
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct OutgoingMessage {
     /// Target node ID
     pub target_node: Uuid,
@@ -118,6 +516,146 @@ pub struct OutgoingMessage {
     pub context: Option<String>,
 }
 
+impl OutgoingMessage {
+    /// A `Collaboration` message carrying `msg` to the alliance channel `channel_id` on `target_node`
+    pub fn for_alliance_channel(
+        target_node: Uuid,
+        channel_id: &str,
+        msg: &AllianceMessage,
+    ) -> Result<OutgoingMessage, CommunicationError> {
+        Ok(OutgoingMessage {
+            target_node,
+            message_type: MessageType::Collaboration,
+            payload: serde_json::to_vec(msg).map_err(|e| CommunicationError::SerializationError(e.to_string()))?,
+            options: DeliveryOptions::default(),
+            context: Some(channel_id.to_string()),
+        })
+    }
+    
+    /// A `SystemControl` message carrying `command` to `target_node`
+    pub fn control(target_node: Uuid, command: ControlCommand) -> OutgoingMessage {
+        OutgoingMessage {
+            target_node,
+            message_type: MessageType::SystemControl,
+            payload: ControlEnvelope::new(command).encode(),
+            options: DeliveryOptions { require_ack: false, ..DeliveryOptions::default() },
+            context: None,
+        }
+    }
+}
+
+/// Handler for the control commands of one [`ControlKind`]
+#[async_trait::async_trait]
+pub trait ControlHandler: Send + Sync {
+    /// Handle `command` from `from_node`, optionally answering with another command
+    async fn handle(&self, from_node: &str, command: ControlCommand) -> Result<Option<ControlCommand>, CommunicationError>;
+}
+
+/// Control handlers by the kind of command they receive
+pub type ControlHandlers = Arc<RwLock<HashMap<ControlKind, Arc<dyn ControlHandler>>>>;
+
+/// Channel a `Collaboration` message is routed to, with the message it carries
+type RoutedAllianceMessage = (Arc<RwLock<BasicSacredAllianceChannel>>, AllianceMessage);
+
+/// Sacred Alliance channels receiving `Collaboration` messages, keyed by context
+#[derive(Clone, Default)]
+pub struct AllianceBindings {
+    channels: Arc<std::sync::RwLock<HashMap<String, Arc<RwLock<BasicSacredAllianceChannel>>>>>,
+}
+
+impl AllianceBindings {
+    /// Route messages with context `channel_id` to `channel`, returning any channel it replaces
+    pub fn bind(
+        &self,
+        channel_id: &str,
+        channel: Arc<RwLock<BasicSacredAllianceChannel>>,
+    ) -> Option<Arc<RwLock<BasicSacredAllianceChannel>>> {
+        self.channels.write().unwrap().insert(channel_id.to_string(), channel)
+    }
+    
+    /// Stop routing messages to a channel, returning whether it was bound
+    pub fn unbind(&self, channel_id: &str) -> bool {
+        self.channels.write().unwrap().remove(channel_id).is_some()
+    }
+    
+    /// IDs of the bound channels
+    pub fn channel_ids(&self) -> Vec<String> {
+        self.channels.read().unwrap().keys().cloned().collect()
+    }
+    
+    /// Whether no channel is bound
+    pub fn is_empty(&self) -> bool {
+        self.channels.read().unwrap().is_empty()
+    }
+    
+    /// Channel bound to the message's context and the alliance message it carries
+    ///
+    /// None if the message has no context or no channel is bound to it.
+    fn route(
+        &self,
+        message: &WeaveMeshMessage,
+    ) -> Option<Result<RoutedAllianceMessage, CommunicationError>> {
+        let channel = self.channels.read().unwrap().get(message.context.as_deref()?)?.clone();
+        Some(serde_json::from_slice(&message.payload)
+            .map(|alliance_message| (channel, alliance_message))
+            .map_err(|e| CommunicationError::SerializationError(e.to_string())))
+    }
+    
+    /// Hand a message to the channel bound to its context
+    ///
+    /// The alliance message must name the sending node as its sender.
+    /// Returns whether a channel was bound.
+    pub async fn deliver(&self, message: &WeaveMeshMessage) -> Result<bool, CommunicationError> {
+        let (channel, alliance_message) = match self.route(message) {
+            Some(routed) => routed?,
+            None => return Ok(false),
+        };
+        if alliance_message.sender != message.from_node {
+            return Err(CommunicationError::SenderMismatch {
+                from_node: message.from_node.clone(),
+                sender: alliance_message.sender,
+            });
+        }
+        channel.write().await.send_message(alliance_message)
+            .map_err(|e| CommunicationError::HandlerError(e.to_string()))?;
+        Ok(true)
+    }
+}
+
+/// `Collaboration` handler delivering bound contexts to alliance channels
+#[derive(Default)]
+struct AllianceRouter {
+    bindings: AllianceBindings,
+    /// Handler for the contexts no channel is bound to
+    fallback: std::sync::RwLock<Option<Arc<dyn AsyncMessageHandler>>>,
+}
+
+#[async_trait::async_trait]
+impl AsyncMessageHandler for AllianceRouter {
+    async fn handle(&self, msg: IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> {
+        if self.bindings.deliver(&msg.message).await? {
+            return Ok(None);
+        }
+        let fallback = self.fallback.read().unwrap().clone();
+        match fallback {
+            Some(handler) => handler.handle(msg).await,
+            None => Err(CommunicationError::NoHandler),
+        }
+    }
+}
+
+impl std::fmt::Debug for OutgoingMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutgoingMessage")
+            .field("target_node", &self.target_node)
+            .field("message_type", &self.message_type)
+            .field("payload", &PayloadSummary::new(&self.payload))
+            .field("options", &self.options)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
 /// Message delivery options
 #[derive(Debug, Clone)]
 pub struct DeliveryOptions {
@@ -150,14 +688,159 @@ impl Default for DeliveryOptions {
 }
 
 /// Message priority levels
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MessagePriority {
     Low,
+    #[default]
     Normal,
     High,
     Critical,
 }
 
+impl MessagePriority {
+    /// All priorities, highest first
+    pub const ALL: [MessagePriority; 4] = [
+        MessagePriority::Critical,
+        MessagePriority::High,
+        MessagePriority::Normal,
+        MessagePriority::Low,
+    ];
+    
+    /// Handler priority for an incoming message of the given type
+    pub fn for_message_type(message_type: &MessageType) -> Self {
+        match message_type {
+            MessageType::SystemControl | MessageType::Error => MessagePriority::Critical,
+            MessageType::Heartbeat
+            | MessageType::NodeDiscovery
+            | MessageType::SacredAllianceValidation => MessagePriority::High,
+            MessageType::AttributionUpdate => MessagePriority::Low,
+            _ => MessagePriority::Normal,
+        }
+    }
+    
+    /// Priority a request is sent at
+    ///
+    /// The priority it was sent with, but never below that of its type.
+    pub fn for_request(requested: MessagePriority, message_type: &MessageType) -> Self {
+        requested.max(Self::for_message_type(message_type))
+    }
+    
+    /// Priority a message is queued and answered at
+    ///
+    /// The priority the message carries, if any, otherwise that of its type.
+    /// Requests carry [`Self::for_request`] and replies the priority of the
+    /// request they answer, so a Critical request to a Normal handler is
+    /// answered at Critical.
+    pub fn for_message(message: &WeaveMeshMessage) -> Self {
+        message.priority.unwrap_or_else(|| Self::for_message_type(&message.message_type))
+    }
+    
+    /// Capacity of the handler queue for this priority
+    pub fn queue_capacity(&self) -> usize {
+        match self {
+            MessagePriority::Critical | MessagePriority::High => 128,
+            MessagePriority::Normal => 1024,
+            MessagePriority::Low => 4096,
+        }
+    }
+}
+
+/// Sending ends of per-priority message queues
+#[derive(Debug, Clone)]
+struct PriorityQueues {
+    critical: mpsc::Sender<WeaveMeshMessage>,
+    high: mpsc::Sender<WeaveMeshMessage>,
+    normal: mpsc::Sender<WeaveMeshMessage>,
+    low: mpsc::Sender<WeaveMeshMessage>,
+    /// Messages dropped because their queue was full, by priority
+    dropped: Arc<std::sync::Mutex<HashMap<MessagePriority, u64>>>,
+}
+
+/// Receiving ends of per-priority message queues
+#[derive(Debug)]
+struct PriorityReceivers {
+    critical: mpsc::Receiver<WeaveMeshMessage>,
+    high: mpsc::Receiver<WeaveMeshMessage>,
+    normal: mpsc::Receiver<WeaveMeshMessage>,
+    low: mpsc::Receiver<WeaveMeshMessage>,
+}
+
+/// Create the bounded handler queues
+fn priority_queues() -> (PriorityQueues, PriorityReceivers) {
+    let (critical_tx, critical_rx) = mpsc::channel(MessagePriority::Critical.queue_capacity());
+    let (high_tx, high_rx) = mpsc::channel(MessagePriority::High.queue_capacity());
+    let (normal_tx, normal_rx) = mpsc::channel(MessagePriority::Normal.queue_capacity());
+    let (low_tx, low_rx) = mpsc::channel(MessagePriority::Low.queue_capacity());
+    
+    (
+        PriorityQueues {
+            critical: critical_tx,
+            high: high_tx,
+            normal: normal_tx,
+            low: low_tx,
+            dropped: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        },
+        PriorityReceivers { critical: critical_rx, high: high_rx, normal: normal_rx, low: low_rx },
+    )
+}
+
+impl PriorityQueues {
+    fn sender(&self, priority: MessagePriority) -> &mpsc::Sender<WeaveMeshMessage> {
+        match priority {
+            MessagePriority::Critical => &self.critical,
+            MessagePriority::High => &self.high,
+            MessagePriority::Normal => &self.normal,
+            MessagePriority::Low => &self.low,
+        }
+    }
+    
+    /// Queue a message by [`MessagePriority::for_message`]
+    ///
+    /// When the queue is full the message is dropped and counted, so the
+    /// network callback never blocks. Returns whether the message was queued.
+    fn enqueue(&self, message: WeaveMeshMessage) -> bool {
+        let priority = MessagePriority::for_message(&message);
+        match self.sender(priority).try_send(message) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(message)) => {
+                tracing::warn!(?priority, message_id = %message.message_id, "message queue full, dropping message");
+                if let Ok(mut dropped) = self.dropped.lock() {
+                    *dropped.entry(priority).or_insert(0) += 1;
+                }
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+    
+    /// Messages dropped because their queue was full, by priority
+    fn dropped(&self) -> HashMap<MessagePriority, u64> {
+        self.dropped.lock().map(|dropped| dropped.clone()).unwrap_or_default()
+    }
+    
+    /// Number of messages waiting in each queue
+    fn depths(&self) -> HashMap<MessagePriority, usize> {
+        MessagePriority::ALL
+            .iter()
+            .map(|priority| (*priority, priority.queue_capacity() - self.sender(*priority).capacity()))
+            .collect()
+    }
+}
+
+impl PriorityReceivers {
+    /// Next message, always preferring higher-priority queues
+    async fn next(&mut self) -> Option<WeaveMeshMessage> {
+        tokio::select! {
+            biased;
+            Some(message) = self.critical.recv() => Some(message),
+            Some(message) = self.high.recv() => Some(message),
+            Some(message) = self.normal.recv() => Some(message),
+            Some(message) = self.low.recv() => Some(message),
+            else => None,
+        }
+    }
+}
+
 /// Pending message awaiting acknowledgment
 #[derive(Debug, Clone)]
 struct PendingMessage {
@@ -175,6 +858,9 @@ struct PendingMessage {
     
     /// Channel to notify when acknowledged or failed
     response_sender: Option<mpsc::UnboundedSender<MessageResult>>,
+    
+    /// Originator span, closed when the acknowledgment arrives
+    span: Option<tracing::Span>,
 }
 
 /// Result of message delivery
@@ -191,6 +877,20 @@ pub enum MessageResult {
     
     /// Response received from target node
     Response(Vec<u8>),
+    
+    /// The target's handler did not finish within its timeout
+    HandlerTimedOut,
+}
+
+/// ID of the message a reply answers and the result it carries
+fn reply_result(command: ControlCommand) -> Option<(String, MessageResult)> {
+    match command {
+        ControlCommand::Ack { message_id } => Some((message_id, MessageResult::Delivered)),
+        ControlCommand::Response { message_id, payload } => Some((message_id, MessageResult::Response(payload))),
+        ControlCommand::HandlerError { message_id, reason } => Some((message_id, MessageResult::Failed(reason))),
+        ControlCommand::HandlerTimeout { message_id } => Some((message_id, MessageResult::HandlerTimedOut)),
+        _ => None,
+    }
 }
 
 /// Communication statistics
@@ -225,6 +925,172 @@ pub struct CommunicationStats {
     
     /// Messages by context
     pub messages_by_context: HashMap<String, u64>,
+    
+    /// Messages waiting for a handler, by priority
+    pub queue_depths: HashMap<MessagePriority, usize>,
+    
+    /// Incoming messages dropped because their handler queue was full, by priority
+    pub handler_queue_drops: HashMap<MessagePriority, u64>,
+    
+    /// Replies and retries dropped because their outbound queue was full, by priority
+    pub outbound_queue_drops: HashMap<MessagePriority, u64>,
+    
+    /// Endpoint scores per peer, when an endpoint router is set
+    pub endpoint_scores: HashMap<Uuid, Vec<EndpointScore>>,
+    
+    /// Round trips of acknowledged requests, by request priority
+    pub response_latency_by_priority: HashMap<MessagePriority, ResponseLatency>,
+    
+    /// Broadcasts dropped because this node does not accept their scope
+    pub broadcasts_out_of_scope: u64,
+    
+    /// `SystemControl` messages rejected as malformed or of an unknown version
+    pub malformed_control_messages: u64,
+    
+    /// Control commands refused because no subsystem handles their kind
+    pub unsupported_control_commands: u64,
+    
+    /// Retries of sends and unacknowledged messages, by target node
+    pub per_node_retry_counts: HashMap<Uuid, u32>,
+    
+    /// When a message was last sent or received
+    pub last_message_at: Option<DateTime<Utc>>,
+}
+
+/// Round trip times of requests at one priority
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResponseLatency {
+    /// Replies received
+    pub responses: u64,
+    /// Average time from sending a request to its reply, in milliseconds
+    pub avg_ms: f64,
+}
+
+impl ResponseLatency {
+    /// Add one round trip to the average
+    pub fn record(&mut self, latency_ms: f64) {
+        self.avg_ms = (self.avg_ms * self.responses as f64 + latency_ms) / (self.responses + 1) as f64;
+        self.responses += 1;
+    }
+}
+
+/// Cumulative counters of [`CommunicationStats`] at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommunicationStatsSnapshot {
+    /// When the counters were read
+    pub taken_at: DateTime<Utc>,
+    /// Total messages sent
+    pub messages_sent: u64,
+    /// Total messages received
+    pub messages_received: u64,
+    /// Messages successfully delivered
+    pub messages_delivered: u64,
+    /// Messages that failed delivery
+    pub messages_failed: u64,
+    /// Messages that timed out
+    pub messages_timed_out: u64,
+    /// Total bytes sent
+    pub bytes_sent: u64,
+    /// Total bytes received
+    pub bytes_received: u64,
+}
+
+/// Rates between two [`CommunicationStatsSnapshot`]s
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CommunicationRates {
+    /// Messages sent and received per second
+    pub messages_per_sec: f64,
+    /// Bytes sent and received per second
+    pub bytes_per_sec: f64,
+    /// Failed and timed out messages per message sent
+    pub error_rate: f64,
+    /// Delivered messages per message sent
+    pub success_rate: f64,
+    /// Time between the snapshots
+    pub interval_duration: Duration,
+}
+
+impl CommunicationStats {
+    /// Current counters, stamped with the current time
+    pub fn snapshot(&self) -> CommunicationStatsSnapshot {
+        self.snapshot_at(Utc::now())
+    }
+    
+    /// Current counters, stamped with `now`
+    pub fn snapshot_at(&self, now: DateTime<Utc>) -> CommunicationStatsSnapshot {
+        CommunicationStatsSnapshot {
+            taken_at: now,
+            messages_sent: self.messages_sent,
+            messages_received: self.messages_received,
+            messages_delivered: self.messages_delivered,
+            messages_failed: self.messages_failed,
+            messages_timed_out: self.messages_timed_out,
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+        }
+    }
+    
+    /// Rates over the interval between two snapshots
+    ///
+    /// Counters that went down, as after `reset_stats`, count as zero.
+    /// An empty or reversed interval yields zero rates.
+    pub fn delta(before: &CommunicationStatsSnapshot, after: &CommunicationStatsSnapshot) -> CommunicationRates {
+        let interval_duration = (after.taken_at - before.taken_at).to_std().unwrap_or_default();
+        let seconds = interval_duration.as_secs_f64();
+        if seconds <= 0.0 {
+            return CommunicationRates { interval_duration, ..CommunicationRates::default() };
+        }
+        
+        let diff = |a: u64, b: u64| b.saturating_sub(a) as f64;
+        let messages = diff(before.messages_sent, after.messages_sent)
+            + diff(before.messages_received, after.messages_received);
+        let bytes = diff(before.bytes_sent, after.bytes_sent)
+            + diff(before.bytes_received, after.bytes_received);
+        let sent = diff(before.messages_sent, after.messages_sent);
+        let per_sent = |count: f64| if sent > 0.0 { (count / sent).min(1.0) } else { 0.0 };
+        
+        CommunicationRates {
+            messages_per_sec: messages / seconds,
+            bytes_per_sec: bytes / seconds,
+            error_rate: per_sent(
+                diff(before.messages_failed, after.messages_failed)
+                    + diff(before.messages_timed_out, after.messages_timed_out),
+            ),
+            success_rate: per_sent(diff(before.messages_delivered, after.messages_delivered)),
+            interval_duration,
+        }
+    }
+}
+
+/// Recent stats snapshots, oldest first
+#[derive(Debug, Default)]
+struct StatsHistory {
+    snapshots: VecDeque<CommunicationStatsSnapshot>,
+}
+
+impl StatsHistory {
+    /// Most snapshots kept, whatever their age
+    const MAX_SNAPSHOTS: usize = 3600;
+    
+    /// Add a snapshot, dropping those older than `retention`
+    fn record(&mut self, snapshot: CommunicationStatsSnapshot, retention: Duration) {
+        let age = |s: &CommunicationStatsSnapshot| (snapshot.taken_at - s.taken_at).to_std().unwrap_or_default();
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > Self::MAX_SNAPSHOTS
+            || self.snapshots.front().is_some_and(|s| age(s) > retention)
+        {
+            self.snapshots.pop_front();
+        }
+    }
+    
+    /// Rates from the oldest snapshot within `window` of `current` up to `current`
+    fn rates(&self, current: &CommunicationStatsSnapshot, window: Duration) -> CommunicationRates {
+        let before = self.snapshots
+            .iter()
+            .find(|s| (current.taken_at - s.taken_at).to_std().unwrap_or_default() <= window)
+            .unwrap_or(current);
+        CommunicationStats::delta(before, current)
+    }
 }
 
 impl NodeCommunication {
@@ -234,14 +1100,121 @@ impl NodeCommunication {
         zenoh_session: Arc<ZenohSession>,
         config: CommunicationConfig,
     ) -> Self {
+        let (handler_queues, handler_receivers) = priority_queues();
+        let (outbound_queues, outbound_receivers) = priority_queues();
+        let transfers = TransferAborts::default();
+        let leases = LeaseTable::default();
+        let control_handlers: ControlHandlers = Arc::new(RwLock::new(HashMap::from([
+            (ControlKind::AbortTransfer, Arc::new(transfers.clone()) as Arc<dyn ControlHandler>),
+            (ControlKind::LeaseOp, Arc::new(leases.clone()) as Arc<dyn ControlHandler>),
+        ])));
+        let broadcast_memberships = BroadcastMemberships::with_scopes(
+            std::iter::once(BroadcastScope::legacy(None))
+                .chain(config.organization.as_ref().map(|_| BroadcastScope::Organization)),
+        );
+        
         Self {
             node_id,
             zenoh_session,
             config,
             message_handlers: Arc::new(RwLock::new(HashMap::new())),
+            control_handlers,
+            transfers,
+            leases,
             pending_acks: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(CommunicationStats::default())),
             is_active: Arc::new(RwLock::new(false)),
+            usage_meter: None,
+            handler_queues,
+            shared_handler_queue: Arc::new(tokio::sync::Mutex::new(handler_receivers)),
+            outbound_queues,
+            outbound_receivers: Arc::new(tokio::sync::Mutex::new(outbound_receivers)),
+            content_classifier: None,
+            compute_pool: None,
+            eviction_counters: Arc::new(EvictionCounters::new()),
+            handled_types: watch::channel(HashSet::new()).0,
+            peer_handlers: Arc::new(RwLock::new(PeerHandlers::default())),
+            endpoint_router: None,
+            stats_history: Arc::new(std::sync::Mutex::new(StatsHistory::default())),
+            alliance_router: Arc::new(AllianceRouter::default()),
+            broadcast_memberships,
+            broadcast_limiter: Arc::new(std::sync::Mutex::new(BroadcastRateLimiter::new())),
+            network_events: broadcast::channel(64).0,
+            departed_nodes: Arc::new(std::sync::Mutex::new(HashSet::new())),
+        }
+    }
+    
+    /// Record pending acknowledgment evictions into shared counters
+    pub fn with_eviction_counters(mut self, counters: Arc<EvictionCounters>) -> Self {
+        self.eviction_counters = counters;
+        self
+    }
+    
+    /// Deliver direct messages through scored endpoints where the target has any
+    ///
+    /// Targets without known endpoints still go through the Zenoh session.
+    pub fn with_endpoint_router(mut self, router: Arc<EndpointRouter>) -> Self {
+        self.endpoint_router = Some(router);
+        self
+    }
+    
+    /// Endpoint router, if set
+    pub fn endpoint_router(&self) -> Option<&Arc<EndpointRouter>> {
+        self.endpoint_router.as_ref()
+    }
+    
+    /// Counters of pending messages dropped by the pending acknowledgment limit
+    pub fn eviction_counters(&self) -> &Arc<EvictionCounters> {
+        &self.eviction_counters
+    }
+    
+    /// Report bytes sent and received per context to a usage meter
+    ///
+    /// Must be set before `start` for received bytes to be reported.
+    pub fn set_usage_meter(&mut self, meter: Arc<dyn UsageMeter>) {
+        self.usage_meter = Some(meter);
+    }
+    
+    /// Let payloads the classifier rates Open be previewed in logs
+    ///
+    /// Without a classifier, logged payloads show only size and hash.
+    pub fn set_content_classifier(&mut self, classifier: Arc<dyn ContentClassifier>) {
+        self.content_classifier = Some(classifier);
+    }
+    
+    /// Classify payloads on `pool` instead of the message-handling runtime
+    ///
+    /// Must be set before `start` for the handler workers to use it.
+    pub fn set_compute_pool(&mut self, pool: ComputePool) {
+        self.compute_pool = Some(pool);
+    }
+    
+    /// Classifier and pool used for logged payloads, if a classifier is set
+    fn payload_summarizer(&self) -> Option<PayloadSummarizer> {
+        let summarizer = PayloadSummarizer::new(self.content_classifier.clone()?);
+        Some(match &self.compute_pool {
+            Some(pool) => summarizer.with_compute_pool(pool.clone()),
+            None => summarizer,
+        })
+    }
+    
+    /// Log-safe summary of a payload under this node's classifier and config
+    pub async fn summarize_payload(&self, payload: &[u8]) -> PayloadSummary {
+        match self.payload_summarizer() {
+            Some(summarizer) => summarizer.summarize(payload, &self.config.payload_summary).await,
+            None => PayloadSummary::with_classifier(payload, None, &self.config.payload_summary),
+        }
+    }
+    
+    /// Report network usage to the usage meter, if any
+    fn meter_usage(&self, kind: UsageKind, bytes: usize, context: Option<&str>) {
+        if let Some(meter) = &self.usage_meter {
+            meter.record_usage(UsageSample {
+                kind,
+                bytes: bytes as u64,
+                context: context.map(|c| c.to_string()),
+                attribution: None,
+            });
         }
     }
     
@@ -252,10 +1225,15 @@ impl NodeCommunication {
         
         // Setup message handling
         self.setup_message_handling().await?;
+        for scope in self.broadcast_memberships.scopes() {
+            self.subscribe_broadcast_scope(&scope).await?;
+        }
         
         // Start background tasks
         self.start_ack_timeout_task().await;
         self.start_retry_task().await;
+        self.start_stats_snapshot_task().await;
+        self.start_outbound_task();
         
         if self.config.debug {
             println!("Node communication started for {}", self.node_id);
@@ -279,15 +1257,175 @@ impl NodeCommunication {
         Ok(())
     }
     
-    /// Register a message handler for a specific message type
+    /// Register a synchronous message handler for a specific message type
+    ///
+    /// The closure runs through [`SyncHandler`] with default [`HandlerOptions`].
     pub async fn register_handler<F>(&self, message_type: MessageType, handler: F)
     where
         F: Fn(IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> + Send + Sync + 'static,
     {
-        self.message_handlers.write().await.insert(
-            message_type,
-            Box::new(handler),
-        );
+        self.register_async_handler(message_type, SyncHandler(handler), HandlerOptions::default()).await;
+    }
+    
+    /// Register an async message handler for a specific message type
+    pub async fn register_async_handler<H>(&self, message_type: MessageType, handler: H, options: HandlerOptions)
+    where
+        H: AsyncMessageHandler + 'static,
+    {
+        let mut handlers = self.message_handlers.write().await;
+        handlers.insert(message_type, RegisteredHandler::new(Arc::new(handler), options));
+        self.handled_types.send_replace(handlers.keys().cloned().collect());
+    }
+    
+    /// Deliver `Collaboration` messages with context `channel_id` to an alliance channel
+    ///
+    /// Every binding (re)installs this node's single alliance router as the
+    /// `Collaboration` handler. It routes bound contexts to their channels
+    /// and passes everything else to the `Collaboration` handler registered
+    /// before the binding, if any. A handler registered after binding takes
+    /// over until the next binding makes it the router's fallback.
+    pub async fn bind_alliance_channel(
+        &self,
+        channel_id: &str,
+        alliance_channel: Arc<RwLock<BasicSacredAllianceChannel>>,
+    ) {
+        let mut handlers = self.message_handlers.write().await;
+        self.alliance_router.bindings.bind(channel_id, alliance_channel);
+        
+        let router: Arc<dyn AsyncMessageHandler> = self.alliance_router.clone();
+        let registered = match handlers.remove(&MessageType::Collaboration) {
+            Some(current) if std::ptr::addr_eq(Arc::as_ptr(&current.handler), Arc::as_ptr(&router)) => current,
+            current => {
+                *self.alliance_router.fallback.write().unwrap() = current.as_ref().map(|h| h.handler.clone());
+                let options = current.map(|h| h.options).unwrap_or_default();
+                RegisteredHandler::new(router, options)
+            }
+        };
+        handlers.insert(MessageType::Collaboration, registered);
+        self.handled_types.send_replace(handlers.keys().cloned().collect());
+    }
+    
+    /// Stop delivering messages to an alliance channel, returning whether it was bound
+    pub fn unbind_alliance_channel(&self, channel_id: &str) -> bool {
+        self.alliance_router.bindings.unbind(channel_id)
+    }
+    
+    /// Alliance channels bound to this node
+    pub fn alliance_bindings(&self) -> &AllianceBindings {
+        &self.alliance_router.bindings
+    }
+    
+    /// Remove the handler for a message type, returning whether one was registered
+    pub async fn unregister_handler(&self, message_type: &MessageType) -> bool {
+        let mut handlers = self.message_handlers.write().await;
+        let removed = handlers.remove(message_type).is_some();
+        if removed {
+            self.handled_types.send_replace(handlers.keys().cloned().collect());
+        }
+        removed
+    }
+    
+    /// Route control commands of `kind` to `handler`
+    ///
+    /// Replies to pending messages and pings are handled by this node itself;
+    /// handlers registered for [`ControlKind::Reply`] or [`ControlKind::Ping`]
+    /// are never called. `AbortTransfer` and `LeaseOp` go to [`Self::transfers`]
+    /// and [`Self::leases`] unless replaced here.
+    pub async fn register_control_handler(&self, kind: ControlKind, handler: Arc<dyn ControlHandler>) {
+        self.control_handlers.write().await.insert(kind, handler);
+    }
+    
+    /// Apply `Rekey` commands from peers to `security`'s trust relationships
+    pub async fn route_rekeys_to(&self, security: Arc<SecuritySystem>) {
+        self.register_control_handler(ControlKind::Rekey, security).await;
+    }
+    
+    /// Outgoing transfers peers may abort
+    pub fn transfers(&self) -> &TransferAborts {
+        &self.transfers
+    }
+    
+    /// Leases peers hold on this node
+    pub fn leases(&self) -> &LeaseTable {
+        &self.leases
+    }
+    
+    /// Message types this node has registered handlers for
+    pub fn handled_message_types(&self) -> HashSet<MessageType> {
+        self.handled_types.borrow().clone()
+    }
+    
+    /// Watch the registered message types, e.g. to re-announce them on change
+    ///
+    /// Pass to [`NodeDiscovery::advertise_handlers`](crate::networking::NodeDiscovery::advertise_handlers)
+    /// together with [`handler_announcement_debounce`](Self::handler_announcement_debounce).
+    pub fn watch_handled_types(&self) -> watch::Receiver<HashSet<MessageType>> {
+        self.handled_types.subscribe()
+    }
+    
+    /// Network events raised by this node's communication, such as
+    /// [`NetworkEvent::NodeLeft`] for nodes whose retries ran out
+    pub fn subscribe_network_events(&self) -> broadcast::Receiver<NetworkEvent> {
+        self.network_events.subscribe()
+    }
+    
+    /// Account for a failure to reach `node_id` after `retries` retries
+    ///
+    /// Returns how long to wait before trying again, or `None` once the
+    /// node's [`RetryStrategy`] is exhausted. The first time a node is
+    /// exhausted [`NetworkEvent::NodeLeft`] is emitted so reconnect handling
+    /// can take over; it is emitted again only after the node was reached.
+    pub async fn reconnect_on_failure(&self, node_id: Uuid, retries: u32) -> Option<Duration> {
+        let strategy = self.config.retry_strategy_for(&node_id);
+        Self::next_retry(
+            &strategy,
+            node_id,
+            retries,
+            &self.stats,
+            &self.network_events,
+            &self.departed_nodes,
+        ).await
+    }
+    
+    async fn next_retry(
+        strategy: &RetryStrategy,
+        node_id: Uuid,
+        retries: u32,
+        stats: &RwLock<CommunicationStats>,
+        network_events: &broadcast::Sender<NetworkEvent>,
+        departed_nodes: &std::sync::Mutex<HashSet<Uuid>>,
+    ) -> Option<Duration> {
+        if retries >= strategy.max_attempts {
+            if departed_nodes.lock().unwrap().insert(node_id) {
+                tracing::warn!("Giving up on node {} after {} retries", node_id, retries);
+                // No subscribers is not an error; nobody handles reconnects yet
+                let _ = network_events.send(NetworkEvent::NodeLeft { node_id: node_id.to_string() });
+            }
+            return None;
+        }
+        *stats.write().await.per_node_retry_counts.entry(node_id).or_insert(0) += 1;
+        let jitter_seed = (Uuid::new_v4().as_u128() % 10_000) as f64 / 10_000.0;
+        Some(strategy.delay_for(retries, jitter_seed))
+    }
+    
+    /// Quiet period before handler changes are re-announced
+    pub fn handler_announcement_debounce(&self) -> Duration {
+        Duration::from_millis(self.config.handler_announcement_debounce_ms)
+    }
+    
+    /// Update what a peer handles from its announcement
+    pub async fn observe_peer(&self, node_info: &NodeInfo) {
+        self.peer_handlers.write().await.observe(node_info);
+    }
+    
+    /// Forget what a departed peer handles
+    pub async fn forget_peer(&self, peer: &Uuid) {
+        self.peer_handlers.write().await.forget(peer);
+    }
+    
+    /// Whether `peer` has advertised a handler for `message_type`
+    pub async fn peer_handles(&self, peer: &Uuid, message_type: &MessageType) -> bool {
+        self.peer_handlers.read().await.handles(peer, message_type)
     }
     
     /// Send a message to another node
@@ -304,6 +1442,16 @@ impl NodeCommunication {
             return Err(CommunicationError::MessageTooLarge);
         }
         
+        self.peer_handlers.read().await.check_send(
+            &message.target_node,
+            &message.message_type,
+            self.config.unhandled_type_policy,
+        )?;
+        
+        // Open an originator span in the caller's trace, if any
+        let trace_context = TraceContext::current().map(|ctx| ctx.child());
+        let span = trace_context.as_ref().map(|ctx| ctx.span("weavemesh.send"));
+        
         // Create WeaveMesh message
         let weave_message = WeaveMeshMessage {
             from_node: self.node_id.to_string(),
@@ -313,6 +1461,9 @@ impl NodeCommunication {
             timestamp: Utc::now(),
             message_id: Uuid::new_v4().to_string(),
             context: message.context.clone(),
+            trace_context,
+            priority: Some(MessagePriority::for_request(message.options.priority, &message.message_type)),
+            broadcast_scope: None,
         };
         
         // Create response channel if acknowledgment is required
@@ -324,11 +1475,16 @@ impl NodeCommunication {
             (None, rx)
         };
         
-        // Send the message
-        let topic = WeaveMeshTopics::node_direct(message.target_node);
-        self.zenoh_session.publish(&topic, weave_message.clone())
-            .await
-            .map_err(|e| CommunicationError::NetworkError(e.to_string()))?;
+        // Send the message, retrying transient failures per the target's strategy
+        let mut retries = 0;
+        while let Err(e) = self.publish_direct(message.target_node, &weave_message).await {
+            match self.reconnect_on_failure(message.target_node, retries).await {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(e),
+            }
+            retries += 1;
+        }
+        self.departed_nodes.lock().unwrap().remove(&message.target_node);
         
         // Track pending acknowledgment if required
         if message.options.require_ack {
@@ -338,11 +1494,15 @@ impl NodeCommunication {
                 sent_at: Utc::now(),
                 retry_count: 0,
                 response_sender,
+                span,
             };
             
-            self.pending_acks.write().await.insert(
-                pending.message.message_id.clone(),
+            let mut pending_acks = self.pending_acks.write().await;
+            Self::track_pending(
+                &mut pending_acks,
                 pending,
+                self.config.max_pending_acks,
+                &self.eviction_counters,
             );
         }
         
@@ -350,7 +1510,9 @@ impl NodeCommunication {
         {
             let mut stats = self.stats.write().await;
             stats.messages_sent += 1;
+            stats.last_message_at = Some(Utc::now());
             stats.bytes_sent += message.payload.len() as u64;
+            self.meter_usage(UsageKind::NetworkEgress, message.payload.len(), message.context.as_deref());
             
             // Track by message type
             let type_key = format!("{:?}", message.message_type);
@@ -372,35 +1534,130 @@ impl NodeCommunication {
         Ok(response_receiver)
     }
     
+    /// Publish `message` to `target` once, through the endpoint router if it knows the target
+    async fn publish_direct(&self, target: Uuid, message: &WeaveMeshMessage) -> Result<(), CommunicationError> {
+        match &self.endpoint_router {
+            Some(router) if router.has_endpoints(&target) => router.send(target, message).await.map(|_| ()),
+            _ => {
+                let topic = WeaveMeshTopics::node_direct(target);
+                self.zenoh_session.publish(&topic, message.clone())
+                    .await
+                    .map_err(|e| CommunicationError::NetworkError(e.to_string()))
+            }
+        }
+    }
+    
     /// Send a broadcast message to all nodes
+    ///
+    /// Unscoped broadcasts go to the context they name, or to
+    /// [`LEGACY_BROADCAST_CONTEXT`](crate::networking::LEGACY_BROADCAST_CONTEXT).
+    #[deprecated(note = "use `broadcast` with an explicit `BroadcastScope`")]
     pub async fn broadcast_message(
         &self,
         message_type: MessageType,
         payload: Vec<u8>,
         context: Option<String>,
+    ) -> Result<(), CommunicationError> {
+        self.broadcast(BroadcastScope::legacy(context), message_type, payload).await
+    }
+    
+    /// Receive broadcasts to `scope`
+    ///
+    /// Nodes start out in the legacy context and, if configured, their
+    /// organization. Joining the organization scope needs `organization`.
+    pub async fn join_broadcast_scope(&self, scope: BroadcastScope) -> Result<(), CommunicationError> {
+        if *self.is_active.read().await {
+            self.subscribe_broadcast_scope(&scope).await?;
+        } else if scope == BroadcastScope::Organization && self.config.organization.is_none() {
+            return Err(CommunicationError::NoOrganization);
+        }
+        self.broadcast_memberships.join(scope);
+        Ok(())
+    }
+    
+    /// Stop receiving broadcasts to `scope`
+    pub async fn leave_broadcast_scope(&self, scope: &BroadcastScope) -> Result<(), CommunicationError> {
+        if self.broadcast_memberships.leave(scope) && *scope != BroadcastScope::Mesh {
+            if let Some(topic) = WeaveMeshTopics::scoped_broadcast(scope, self.config.organization.as_deref()) {
+                self.zenoh_session.unsubscribe(&topic)
+                    .await
+                    .map_err(|e| CommunicationError::NetworkError(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Broadcast scopes this node has joined
+    pub fn broadcast_scopes(&self) -> Vec<BroadcastScope> {
+        self.broadcast_memberships.scopes()
+    }
+    
+    /// Subscribe to the topic of `scope`; the mesh topic is left to discovery
+    async fn subscribe_broadcast_scope(&self, scope: &BroadcastScope) -> Result<(), CommunicationError> {
+        if *scope == BroadcastScope::Mesh {
+            return Ok(());
+        }
+        let topic = WeaveMeshTopics::scoped_broadcast(scope, self.config.organization.as_deref())
+            .ok_or(CommunicationError::NoOrganization)?;
+        self.zenoh_session.subscribe(&topic)
+            .await
+            .map_err(|e| CommunicationError::NetworkError(e.to_string()))
+    }
+    
+    /// Broadcast a message to the nodes in `scope`
+    ///
+    /// Each scope has its own size and rate limits; the mesh scope also
+    /// needs `allow_global`. Broadcasts go out on the scope's own topic and
+    /// only nodes that joined the scope deliver them.
+    pub async fn broadcast(
+        &self,
+        scope: BroadcastScope,
+        message_type: MessageType,
+        payload: Vec<u8>,
     ) -> Result<(), CommunicationError> {
         if !*self.is_active.read().await {
             return Err(CommunicationError::NotActive);
         }
         
-        // Validate message size
-        if payload.len() > self.config.max_message_size {
-            return Err(CommunicationError::MessageTooLarge);
+        self.config.check_broadcast(&scope, payload.len())?;
+        let context = match &scope {
+            BroadcastScope::Organization => Some(self.config.organization.clone().ok_or(CommunicationError::NoOrganization)?),
+            scope => scope.context(),
+        };
+        let topic = WeaveMeshTopics::scoped_broadcast(&scope, context.as_deref())
+            .ok_or(CommunicationError::NoOrganization)?;
+        {
+            let now = tokio::time::Instant::now();
+            let mut limiter = self.broadcast_limiter.lock().unwrap();
+            limiter.prune(Duration::from_secs(600), now);
+            if !limiter.try_acquire(&scope, self.config.broadcast_limits.for_scope(&scope), now) {
+                return Err(CommunicationError::BroadcastRateLimited(scope));
+            }
         }
         
-        // Send broadcast
-        self.zenoh_session.send_message(
-            Uuid::nil(), // Broadcast target
-            message_type.clone(),
-            payload.clone(),
-            context.clone(),
-        ).await.map_err(|e| CommunicationError::NetworkError(e.to_string()))?;
+        let message = WeaveMeshMessage {
+            from_node: self.node_id.to_string(),
+            to_node: None,
+            message_type: message_type.clone(),
+            payload: payload.clone(),
+            timestamp: Utc::now(),
+            message_id: Uuid::new_v4().to_string(),
+            context: context.clone(),
+            trace_context: TraceContext::current(),
+            priority: None,
+            broadcast_scope: Some(scope),
+        };
+        self.zenoh_session.publish(&topic, message)
+            .await
+            .map_err(|e| CommunicationError::NetworkError(e.to_string()))?;
         
         // Update statistics
         {
             let mut stats = self.stats.write().await;
             stats.messages_sent += 1;
+            stats.last_message_at = Some(Utc::now());
             stats.bytes_sent += payload.len() as u64;
+            self.meter_usage(UsageKind::NetworkEgress, payload.len(), context.as_deref());
             
             // Track by message type
             let type_key = format!("{:?}", message_type);
@@ -420,6 +1677,9 @@ impl NodeCommunication {
     }
     
     /// Send a context-specific message
+    ///
+    /// Receivers deliver it like a broadcast to the context's scope, so only
+    /// nodes that joined that scope handle it.
     pub async fn send_context_message(
         &self,
         context: &str,
@@ -445,6 +1705,9 @@ impl NodeCommunication {
             timestamp: Utc::now(),
             message_id: Uuid::new_v4().to_string(),
             context: Some(context.to_string()),
+            trace_context: TraceContext::current(),
+            priority: None,
+            broadcast_scope: None,
         };
         
         // Publish to context topic
@@ -457,8 +1720,10 @@ impl NodeCommunication {
         {
             let mut stats = self.stats.write().await;
             stats.messages_sent += 1;
+            stats.last_message_at = Some(Utc::now());
             stats.bytes_sent += payload.len() as u64;
             *stats.messages_by_context.entry(context.to_string()).or_insert(0) += 1;
+            self.meter_usage(UsageKind::NetworkEgress, payload.len(), Some(context));
         }
         
         Ok(())
@@ -466,7 +1731,24 @@ impl NodeCommunication {
     
     /// Get communication statistics
     pub async fn get_stats(&self) -> CommunicationStats {
-        self.stats.read().await.clone()
+        let mut stats = self.stats.read().await.clone();
+        stats.queue_depths = self.handler_queues.depths();
+        stats.handler_queue_drops = self.handler_queues.dropped();
+        stats.outbound_queue_drops = self.outbound_queues.dropped();
+        if let Some(router) = &self.endpoint_router {
+            stats.endpoint_scores = router.scores();
+        }
+        stats
+    }
+    
+    /// Throughput over the last `window`
+    ///
+    /// Rates are measured from the oldest snapshot inside the window; stats
+    /// are snapshotted every second while communication runs and kept for
+    /// an hour.
+    pub async fn throughput_stats(&self, window: Duration) -> CommunicationRates {
+        let current = self.stats.read().await.snapshot();
+        self.stats_history.lock().unwrap().rates(&current, window)
     }
     
     /// Reset communication statistics
@@ -479,48 +1761,164 @@ impl NodeCommunication {
         self.pending_acks.read().await.len()
     }
     
+    /// Track a message awaiting acknowledgment, failing the oldest ones
+    /// first if `max_pending` would be exceeded
+    fn track_pending(
+        pending_acks: &mut HashMap<String, PendingMessage>,
+        pending: PendingMessage,
+        max_pending: Option<usize>,
+        eviction_counters: &EvictionCounters,
+    ) {
+        if let Some(max_pending) = max_pending {
+            while !pending_acks.is_empty() && pending_acks.len() >= max_pending {
+                let oldest = pending_acks.iter()
+                    .min_by_key(|(_, p)| p.sent_at)
+                    .map(|(id, _)| id.clone());
+                let evicted = match oldest.and_then(|id| pending_acks.remove(&id)) {
+                    Some(evicted) => evicted,
+                    None => break,
+                };
+                
+                if let Some(sender) = evicted.response_sender {
+                    let _ = sender.send(MessageResult::Failed(
+                        "Evicted by pending acknowledgment limit".to_string(),
+                    ));
+                }
+                eviction_counters.record(EvictionKind::PendingAck, 1);
+            }
+        }
+        
+        pending_acks.insert(pending.message.message_id.clone(), pending);
+    }
+    
     /// Setup message handling from Zenoh
+    ///
+    /// Incoming messages are queued by priority and drained by
+    /// `handler_threads` workers sharing those queues, so a flood of
+    /// low-priority messages cannot starve critical ones.
     async fn setup_message_handling(&self) -> Result<(), CommunicationError> {
-        let message_handlers = Arc::clone(&self.message_handlers);
-        let pending_acks = Arc::clone(&self.pending_acks);
-        let stats = Arc::clone(&self.stats);
-        let node_id = self.node_id;
-        let config = self.config.clone();
+        let queues = self.handler_queues.clone();
+        let usage_meter = self.usage_meter.clone();
         
         self.zenoh_session.set_message_handler(move |message| {
-            let handlers = Arc::clone(&message_handlers);
-            let pending = Arc::clone(&pending_acks);
-            let stats = Arc::clone(&stats);
-            let node_id = node_id;
-            let config = config.clone();
-            
-            tokio::spawn(async move {
-                if let Err(e) = Self::handle_incoming_message(
-                    message, handlers, pending, stats, node_id, config
-                ).await {
-                    eprintln!("Error handling incoming message: {}", e);
-                }
-            });
+            if let Some(meter) = &usage_meter {
+                meter.record_usage(UsageSample {
+                    kind: UsageKind::NetworkIngress,
+                    bytes: message.payload.len() as u64,
+                    context: message.context.clone(),
+                    attribution: None,
+                });
+            }
             
+            queues.enqueue(message);
             Ok(())
         }).await;
         
+        self.start_handler_workers();
         Ok(())
     }
     
+    /// Start the worker tasks that run message handlers, all taking from one
+    /// shared priority queue
+    fn start_handler_workers(&self) {
+        for _ in 0..self.config.handler_threads.max(1) {
+            let receivers = Arc::clone(&self.shared_handler_queue);
+            let handlers = Arc::clone(&self.message_handlers);
+            let control_handlers = Arc::clone(&self.control_handlers);
+            let pending = Arc::clone(&self.pending_acks);
+            let stats = Arc::clone(&self.stats);
+            let node_id = self.node_id;
+            let config = self.config.clone();
+            let summarizer = self.payload_summarizer();
+            let outbound = self.outbound_queues.clone();
+            let memberships = self.broadcast_memberships.clone();
+            
+            tokio::spawn(async move {
+                loop {
+                    // Only the receiving is serialized; handlers run concurrently
+                    let message = match receivers.lock().await.next().await {
+                        Some(message) => message,
+                        None => break,
+                    };
+                    
+                    let reply = match Self::handle_incoming_message(
+                        message,
+                        Arc::clone(&handlers),
+                        Arc::clone(&control_handlers),
+                        Arc::clone(&pending),
+                        Arc::clone(&stats),
+                        node_id,
+                        config.clone(),
+                        summarizer.clone(),
+                        &memberships,
+                    ).await {
+                        Ok(reply) => reply,
+                        Err(e) => {
+                            eprintln!("Error handling incoming message: {}", e);
+                            continue;
+                        }
+                    };
+                    
+                    // Replies go out at the priority of the request they answer
+                    if let Some(reply) = reply {
+                        outbound.enqueue(reply);
+                    }
+                }
+            });
+        }
+    }
+    
+    /// Start the task that sends queued replies and retries, highest priority first
+    fn start_outbound_task(&self) {
+        let receivers = Arc::clone(&self.outbound_receivers);
+        let zenoh_session = Arc::clone(&self.zenoh_session);
+        
+        tokio::spawn(async move {
+            let mut receivers = receivers.lock().await;
+            while let Some(message) = receivers.next().await {
+                let Some(target) = message.to_node.as_deref().and_then(|node| Uuid::parse_str(node).ok()) else {
+                    continue;
+                };
+                let topic = WeaveMeshTopics::node_direct(target);
+                if let Err(e) = zenoh_session.publish(&topic, message).await {
+                    eprintln!("Error sending to {}: {}", target, e);
+                }
+            }
+        });
+    }
+    
     /// Handle incoming messages
+    ///
+    /// Returns the reply for the sender, if any: the handler's response, an
+    /// acknowledgment, or the handler's error or timeout.
     async fn handle_incoming_message(
         message: WeaveMeshMessage,
-        handlers: Arc<RwLock<HashMap<MessageType, MessageHandler>>>,
+        handlers: Arc<RwLock<HashMap<MessageType, RegisteredHandler>>>,
+        control_handlers: ControlHandlers,
         pending_acks: Arc<RwLock<HashMap<String, PendingMessage>>>,
         stats: Arc<RwLock<CommunicationStats>>,
         node_id: Uuid,
         config: CommunicationConfig,
-    ) -> Result<(), CommunicationError> {
+        summarizer: Option<PayloadSummarizer>,
+        memberships: &BroadcastMemberships,
+    ) -> Result<Option<WeaveMeshMessage>, CommunicationError> {
+        // Drop broadcasts to scopes this node is not in or has not opted into
+        if let Some(scope) = broadcast_scope_of(&message) {
+            let member = config.is_broadcast_member(&scope, message.context.as_deref(), memberships);
+            if !member || !config.accepts_broadcast(&scope) {
+                stats.write().await.broadcasts_out_of_scope += 1;
+                if config.debug {
+                    println!("Dropped broadcast {} to {:?}", message.message_id, scope);
+                }
+                return Ok(None);
+            }
+        }
+        
         // Update statistics
         {
             let mut stats = stats.write().await;
             stats.messages_received += 1;
+            stats.last_message_at = Some(Utc::now());
             stats.bytes_received += message.payload.len() as u64;
             
             // Track by message type
@@ -533,11 +1931,9 @@ impl NodeCommunication {
             }
         }
         
-        // Check if this is an acknowledgment for a pending message
-        if message.message_type == MessageType::SystemControl && 
-           message.payload.starts_with(b"ACK:") {
-            Self::handle_acknowledgment(message, pending_acks).await?;
-            return Ok(());
+        // Control messages never reach message handlers
+        if message.message_type == MessageType::SystemControl {
+            return Self::dispatch_control(message, control_handlers, pending_acks, stats, node_id).await;
         }
         
         // Create incoming message context
@@ -548,55 +1944,136 @@ impl NodeCommunication {
             requires_ack: config.require_acks,
         };
         
-        // Find and execute handler
-        let handlers = handlers.read().await;
-        if let Some(handler) = handlers.get(&message.message_type) {
-            match handler(incoming) {
-                Ok(response) => {
-                    // Send response if provided
-                    if let Some(response_data) = response {
-                        // Implementation would send response back to sender
-                        if config.debug {
-                            println!("Response sent for message {}", message.message_id);
-                        }
-                    }
-                    
-                    // Send acknowledgment if required
-                    if config.require_acks {
-                        // Implementation would send ACK back to sender
-                        if config.debug {
-                            println!("ACK sent for message {}", message.message_id);
-                        }
-                    }
+        // Continue the sender's trace as a child span, if one was propagated
+        let handler_context = message.trace_context.as_ref().map(|ctx| ctx.child());
+        let handler_span = handler_context.as_ref()
+            .map(|ctx| ctx.span("weavemesh.handle"))
+            .unwrap_or_else(tracing::Span::none);
+        
+        // Find and execute handler, without holding the registry across it
+        let handler = handlers.read().await.get(&message.message_type).cloned();
+        let Some(handler) = handler else {
+            if config.debug {
+                println!("No handler for message type: {:?}", message.message_type);
+            }
+            return Ok(None);
+        };
+        
+        let run = handler.run(incoming).instrument(handler_span.clone());
+        let result = match handler_context.clone() {
+            Some(ctx) => ctx.scope(run).await,
+            None => run.await,
+        };
+        
+        match result {
+            Ok(Some(response_data)) => {
+                if config.debug {
+                    println!("Response sent for message {}", message.message_id);
                 }
-                Err(e) => {
-                    eprintln!("Handler error for message {}: {}", message.message_id, e);
+                Ok(Some(utils::create_response_message(&message, node_id, response_data, handler_context)))
+            }
+            Ok(None) if config.require_acks => {
+                if config.debug {
+                    println!("ACK sent for message {}", message.message_id);
                 }
+                Ok(Some(utils::create_ack_message(&message, node_id, handler_context)))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => {
+                let payload = match &summarizer {
+                    Some(summarizer) => summarizer.summarize(&message.payload, &config.payload_summary).await,
+                    None => PayloadSummary::with_classifier(&message.payload, None, &config.payload_summary),
+                };
+                tracing::warn!(
+                    parent: &handler_span,
+                    message_id = %message.message_id,
+                    payload = %payload,
+                    "handler error: {}", e
+                );
+                Ok(Some(utils::create_error_message(&message, node_id, &e, handler_context)))
+            }
+        }
+    }
+    
+    /// Route a `SystemControl` message to the subsystem for its command
+    ///
+    /// Replies resolve pending messages and pings are answered here; other
+    /// commands go to the registered [`ControlHandler`] for their kind, and
+    /// are answered with a counted `HandlerError` when there is none.
+    /// Payloads that are not a valid envelope are counted and rejected.
+    async fn dispatch_control(
+        message: WeaveMeshMessage,
+        control_handlers: ControlHandlers,
+        pending_acks: Arc<RwLock<HashMap<String, PendingMessage>>>,
+        stats: Arc<RwLock<CommunicationStats>>,
+        node_id: Uuid,
+    ) -> Result<Option<WeaveMeshMessage>, CommunicationError> {
+        let envelope = match ControlEnvelope::decode(&message.payload) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                stats.write().await.malformed_control_messages += 1;
+                tracing::warn!(message_id = %message.message_id, from = %message.from_node, "rejected control message: {}", e);
+                return Err(CommunicationError::InvalidMessage);
             }
-        } else if config.debug {
-            println!("No handler for message type: {:?}", message.message_type);
+        };
+        
+        let kind = envelope.command.kind();
+        if let Some(pong) = envelope.command.pong(Utc::now()) {
+            return Ok(Some(utils::create_control_reply(&message, node_id, pong, None)));
+        }
+        if let Some((acked_id, result)) = reply_result(envelope.command.clone()) {
+            Self::handle_acknowledgment(message, acked_id, result, pending_acks, stats).await;
+            return Ok(None);
         }
         
-        Ok(())
+        let handler = control_handlers.read().await.get(&kind).cloned();
+        let Some(handler) = handler else {
+            stats.write().await.unsupported_control_commands += 1;
+            tracing::debug!(message_id = %message.message_id, "no handler for {:?} control commands", kind);
+            // Unsolicited pongs answer nothing and need no reply
+            if kind == ControlKind::Pong {
+                return Ok(None);
+            }
+            let refusal = ControlCommand::HandlerError {
+                message_id: message.message_id.clone(),
+                reason: format!("unsupported control command: {:?}", kind),
+            };
+            return Ok(Some(utils::create_control_reply(&message, node_id, refusal, None)));
+        };
+        let answer = handler.handle(&message.from_node, envelope.command).await?;
+        Ok(answer.map(|command| utils::create_control_reply(&message, node_id, command, None)))
     }
     
-    /// Handle acknowledgment messages
+    /// Resolve the pending message a reply answers
     async fn handle_acknowledgment(
         message: WeaveMeshMessage,
+        acked_id: String,
+        result: MessageResult,
         pending_acks: Arc<RwLock<HashMap<String, PendingMessage>>>,
-    ) -> Result<(), CommunicationError> {
-        // Extract message ID from ACK payload
-        let ack_payload = String::from_utf8_lossy(&message.payload);
-        if let Some(acked_id) = ack_payload.strip_prefix("ACK:") {
-            let mut pending = pending_acks.write().await;
-            if let Some(pending_msg) = pending.remove(acked_id) {
-                if let Some(sender) = pending_msg.response_sender {
-                    let _ = sender.send(MessageResult::Delivered);
-                }
+        stats: Arc<RwLock<CommunicationStats>>,
+    ) {
+        let mut pending = pending_acks.write().await;
+        if let Some(pending_msg) = pending.remove(&acked_id) {
+            // Measured from the first send, so retries count against the round trip
+            let latency_ms = (Utc::now() - pending_msg.message.timestamp).num_milliseconds().max(0) as f64;
+            stats.write().await
+                .response_latency_by_priority
+                .entry(MessagePriority::for_message(&pending_msg.message))
+                .or_default()
+                .record(latency_ms);
+            
+            // Close the originator span now that the remote side has finished
+            if let Some(span) = pending_msg.span {
+                let remote_span = message.trace_context.as_ref()
+                    .map(|ctx| ctx.span_id.as_str())
+                    .unwrap_or("");
+                tracing::debug!(parent: &span, remote_span_id = remote_span, "acknowledged");
+            }
+            
+            if let Some(sender) = pending_msg.response_sender {
+                let _ = sender.send(result);
             }
         }
-        
-        Ok(())
     }
     
     /// Start task to handle acknowledgment timeouts
@@ -637,12 +2114,35 @@ impl NodeCommunication {
         });
     }
     
+    /// Start task to snapshot stats for throughput rates
+    async fn start_stats_snapshot_task(&self) {
+        let stats = Arc::clone(&self.stats);
+        let history = Arc::clone(&self.stats_history);
+        let is_active = Arc::clone(&self.is_active);
+        
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
+            
+            while *is_active.read().await {
+                interval.tick().await;
+                let snapshot = stats.read().await.snapshot();
+                history.lock().unwrap().record(snapshot, Duration::from_secs(3600));
+            }
+        });
+    }
+    
     /// Start task to handle message retries
+    ///
+    /// Unacknowledged messages are resent every 15 seconds until their
+    /// target's [`RetryStrategy`] runs out of attempts.
     async fn start_retry_task(&self) {
         let pending_acks = Arc::clone(&self.pending_acks);
-        let zenoh_session = Arc::clone(&self.zenoh_session);
+        let outbound = self.outbound_queues.clone();
         let is_active = Arc::clone(&self.is_active);
-        let max_retries = self.config.max_retries;
+        let config = self.config.clone();
+        let stats = Arc::clone(&self.stats);
+        let network_events = self.network_events.clone();
+        let departed_nodes = Arc::clone(&self.departed_nodes);
         
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(
@@ -660,13 +2160,30 @@ impl NodeCommunication {
                     
                     for (msg_id, pending_msg) in pending.iter_mut() {
                         let elapsed = (now - pending_msg.sent_at).num_seconds();
+                        let Some(target) = pending_msg.message.to_node.as_deref()
+                            .and_then(|node| Uuid::parse_str(node).ok())
+                        else {
+                            continue;
+                        };
+                        if elapsed <= 15 {
+                            continue;
+                        }
                         
-                        // Retry if message is old enough and hasn't exceeded max retries
-                        if elapsed > 15 && pending_msg.retry_count < max_retries {
+                        // Retry if the target's strategy allows another attempt
+                        let strategy = config.retry_strategy_for(&target);
+                        let retry = Self::next_retry(
+                            &strategy,
+                            target,
+                            pending_msg.retry_count,
+                            &stats,
+                            &network_events,
+                            &departed_nodes,
+                        ).await;
+                        if retry.is_some() {
                             to_retry.push((msg_id.clone(), pending_msg.message.clone()));
                             pending_msg.retry_count += 1;
                             pending_msg.sent_at = now;
-                        } else if pending_msg.retry_count >= max_retries {
+                        } else {
                             to_remove.push(msg_id.clone());
                         }
                     }
@@ -680,16 +2197,11 @@ impl NodeCommunication {
                         }
                     }
                     
-                    // Retry messages
-                    drop(pending); // Release lock before async operations
+                    // Retries keep the original message's priority
+                    drop(pending);
                     
                     for (_msg_id, message) in to_retry {
-                        if let Some(to_node) = &message.to_node {
-                            if let Ok(target_node) = Uuid::parse_str(to_node) {
-                                let topic = WeaveMeshTopics::node_direct(target_node);
-                                let _ = zenoh_session.publish(&topic, message).await;
-                            }
-                        }
+                        outbound.enqueue(message);
                     }
                 }
             }
@@ -697,6 +2209,14 @@ impl NodeCommunication {
     }
 }
 
+#[async_trait::async_trait]
+impl MemoryFootprint for NodeCommunication {
+    async fn memory_footprint(&self) -> ComponentFootprint {
+        let pending = self.pending_acks.read().await.len();
+        ComponentFootprint::of::<PendingMessage>("pending_acks", pending)
+    }
+}
+
 /// Errors that can occur during communication
 #[derive(Debug, thiserror::Error)]
 pub enum CommunicationError {
@@ -724,8 +2244,32 @@ pub enum CommunicationError {
     #[error("No handler registered for message type")]
     NoHandler,
     
+    #[error("Handler timed out after {0:?}")]
+    HandlerTimeout(Duration),
+    
+    #[error("Node {peer} does not handle {message_type:?} messages")]
+    UnhandledMessageType {
+        peer: Uuid,
+        message_type: MessageType,
+    },
+    
     #[error("Encryption error: {0}")]
     EncryptionError(String),
+    
+    #[error("Broadcasts to the whole mesh need allow_global")]
+    GlobalBroadcastDisabled,
+    
+    #[error("Broadcast rate limit reached for {0:?}")]
+    BroadcastRateLimited(BroadcastScope),
+    
+    #[error("Organization broadcasts need an organization")]
+    NoOrganization,
+    
+    #[error("Message from {from_node} claims to be from {sender}")]
+    SenderMismatch {
+        from_node: String,
+        sender: String,
+    },
 }
 
 /// Utility functions for node communication
@@ -804,6 +2348,66 @@ pub mod utils {
         }
     }
     
+    /// Create an acknowledgment for a received message
+    ///
+    /// The acknowledgment carries the handler's trace context back to the
+    /// originator so its span can close with the remote timing.
+    pub fn create_ack_message(
+        original: &WeaveMeshMessage,
+        from_node: Uuid,
+        trace_context: Option<TraceContext>,
+    ) -> WeaveMeshMessage {
+        let ack = ControlCommand::Ack { message_id: original.message_id.clone() };
+        create_control_reply(original, from_node, ack, trace_context)
+    }
+    
+    /// Create a reply carrying a handler's response to a received message
+    pub fn create_response_message(
+        original: &WeaveMeshMessage,
+        from_node: Uuid,
+        response: Vec<u8>,
+        trace_context: Option<TraceContext>,
+    ) -> WeaveMeshMessage {
+        let command = ControlCommand::Response { message_id: original.message_id.clone(), payload: response };
+        create_control_reply(original, from_node, command, trace_context)
+    }
+    
+    /// Create a reply reporting that a handler failed or timed out
+    pub fn create_error_message(
+        original: &WeaveMeshMessage,
+        from_node: Uuid,
+        error: &CommunicationError,
+        trace_context: Option<TraceContext>,
+    ) -> WeaveMeshMessage {
+        let message_id = original.message_id.clone();
+        let command = match error {
+            CommunicationError::HandlerTimeout(_) => ControlCommand::HandlerTimeout { message_id },
+            error => ControlCommand::HandlerError { message_id, reason: error.to_string() },
+        };
+        create_control_reply(original, from_node, command, trace_context)
+    }
+    
+    /// Reply to `original` with a control command, at the priority `original` was handled at
+    pub fn create_control_reply(
+        original: &WeaveMeshMessage,
+        from_node: Uuid,
+        command: ControlCommand,
+        trace_context: Option<TraceContext>,
+    ) -> WeaveMeshMessage {
+        WeaveMeshMessage {
+            from_node: from_node.to_string(),
+            to_node: Some(original.from_node.clone()),
+            message_type: MessageType::SystemControl,
+            payload: ControlEnvelope::new(command).encode(),
+            timestamp: Utc::now(),
+            message_id: Uuid::new_v4().to_string(),
+            context: original.context.clone(),
+            trace_context,
+            priority: Some(MessagePriority::for_message(original)),
+            broadcast_scope: None,
+        }
+    }
+    
     /// Calculate message throughput from stats
     pub fn calculate_throughput(stats: &CommunicationStats, duration_seconds: u64) -> f64 {
         if duration_seconds == 0 {
@@ -842,6 +2446,128 @@ pub mod utils {
 mod tests {
     use super::*;
     use super::utils::*;
+    use crate::networking::system_control::LeaseOperation;
+    
+    #[tokio::test]
+    async fn test_priority_queues_drain_highest_first() {
+        let (queues, mut receivers) = priority_queues();
+        let node = Uuid::new_v4();
+        
+        for message_type in [
+            MessageType::AttributionUpdate,
+            MessageType::Collaboration,
+            MessageType::Heartbeat,
+            MessageType::SystemControl,
+            MessageType::Collaboration,
+        ] {
+            queues.enqueue(crate::networking::zenoh_integration::utils::create_message(
+                node, None, message_type, Vec::new(), None,
+            ));
+        }
+        
+        let depths = queues.depths();
+        assert_eq!(depths[&MessagePriority::Critical], 1);
+        assert_eq!(depths[&MessagePriority::High], 1);
+        assert_eq!(depths[&MessagePriority::Normal], 2);
+        assert_eq!(depths[&MessagePriority::Low], 1);
+        
+        let mut order = Vec::new();
+        for _ in 0..5 {
+            let message = receivers.next().await.unwrap();
+            order.push(MessagePriority::for_message_type(&message.message_type));
+        }
+        assert_eq!(order, vec![
+            MessagePriority::Critical,
+            MessagePriority::High,
+            MessagePriority::Normal,
+            MessagePriority::Normal,
+            MessagePriority::Low,
+        ]);
+        assert!(queues.depths().values().all(|depth| *depth == 0));
+    }
+    
+    #[tokio::test]
+    async fn test_full_priority_queue_drops_and_counts() {
+        let (queues, mut receivers) = priority_queues();
+        let node = Uuid::new_v4();
+        let heartbeat = || crate::networking::zenoh_integration::utils::create_message(
+            node, None, MessageType::Heartbeat, Vec::new(), None,
+        );
+        
+        let capacity = MessagePriority::High.queue_capacity();
+        for _ in 0..capacity {
+            assert!(queues.enqueue(heartbeat()));
+        }
+        assert!(!queues.enqueue(heartbeat()));
+        assert!(!queues.enqueue(heartbeat()));
+        assert_eq!(queues.dropped().get(&MessagePriority::High), Some(&2));
+        assert_eq!(queues.depths()[&MessagePriority::High], capacity);
+        
+        // Space frees up as the queue drains
+        receivers.next().await.unwrap();
+        assert!(queues.enqueue(heartbeat()));
+        assert_eq!(queues.dropped().get(&MessagePriority::High), Some(&2));
+    }
+    
+    fn pending_message(sent_at: DateTime<Utc>) -> (PendingMessage, mpsc::UnboundedReceiver<MessageResult>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let message = crate::networking::zenoh_integration::utils::create_message(
+            Uuid::new_v4(), None, MessageType::Collaboration, Vec::new(), None,
+        );
+        let pending = PendingMessage {
+            message,
+            options: DeliveryOptions::default(),
+            sent_at,
+            retry_count: 0,
+            response_sender: Some(tx),
+            span: None,
+        };
+        (pending, rx)
+    }
+    
+    #[test]
+    fn test_pending_ack_limit_fails_oldest() {
+        let config = CommunicationConfig::for_profile(ResourceProfile::Constrained);
+        let limits = ResourceProfile::Constrained.limits();
+        assert_eq!(config.max_pending_acks, limits.max_pending_acks);
+        assert_eq!(Some(config.max_message_size), limits.max_message_size);
+        
+        let mut pending_acks = HashMap::new();
+        let counters = EvictionCounters::new();
+        let now = Utc::now();
+        
+        let (oldest, mut oldest_rx) = pending_message(now - chrono::Duration::seconds(30));
+        let (newer, mut newer_rx) = pending_message(now - chrono::Duration::seconds(5));
+        let (incoming, _incoming_rx) = pending_message(now);
+        let newer_id = newer.message.message_id.clone();
+        
+        NodeCommunication::track_pending(&mut pending_acks, oldest, Some(2), &counters);
+        NodeCommunication::track_pending(&mut pending_acks, newer, Some(2), &counters);
+        NodeCommunication::track_pending(&mut pending_acks, incoming, Some(2), &counters);
+        
+        assert_eq!(pending_acks.len(), 2);
+        assert!(pending_acks.contains_key(&newer_id));
+        assert!(matches!(oldest_rx.try_recv(), Ok(MessageResult::Failed(_))));
+        assert!(newer_rx.try_recv().is_err());
+        assert_eq!(counters.get(EvictionKind::PendingAck), 1);
+    }
+    
+    #[test]
+    fn test_standard_profile_keeps_communication_defaults() {
+        let config = CommunicationConfig::for_profile(ResourceProfile::Standard);
+        let defaults = CommunicationConfig::default();
+        assert_eq!(config.max_message_size, defaults.max_message_size);
+        assert_eq!(config.max_pending_acks, None);
+        
+        let mut pending_acks = HashMap::new();
+        let counters = EvictionCounters::new();
+        for _ in 0..100 {
+            let (pending, _rx) = pending_message(Utc::now());
+            NodeCommunication::track_pending(&mut pending_acks, pending, config.max_pending_acks, &counters);
+        }
+        assert_eq!(pending_acks.len(), 100);
+        assert_eq!(counters.total(), 0);
+    }
     
     #[tokio::test]
     async fn test_communication_creation() {
@@ -923,6 +2649,38 @@ mod tests {
         assert_eq!(reliable.priority, MessagePriority::High);
     }
     
+    #[test]
+    fn test_stats_rates() {
+        let start = Utc::now();
+        let at = |secs: i64, sent: u64, delivered: u64, failed: u64| CommunicationStats {
+            messages_sent: sent,
+            messages_received: sent,
+            messages_delivered: delivered,
+            messages_failed: failed,
+            bytes_sent: sent * 100,
+            ..Default::default()
+        }
+        .snapshot_at(start + chrono::Duration::seconds(secs));
+        
+        let rates = CommunicationStats::delta(&at(0, 10, 10, 0), &at(10, 30, 28, 2));
+        assert_eq!(rates.interval_duration, Duration::from_secs(10));
+        assert!((rates.messages_per_sec - 4.0).abs() < 1e-9);
+        assert!((rates.bytes_per_sec - 200.0).abs() < 1e-9);
+        assert!((rates.success_rate - 0.9).abs() < 1e-9);
+        assert!((rates.error_rate - 0.1).abs() < 1e-9);
+        
+        // Only snapshots inside the window count, and old ones age out
+        let mut history = StatsHistory::default();
+        for (secs, sent) in [(0, 0), (50, 100), (60, 110)] {
+            history.record(at(secs, sent, sent, 0), Duration::from_secs(120));
+        }
+        let rates = history.rates(&at(70, 130, 130, 0), Duration::from_secs(30));
+        assert_eq!(rates.interval_duration, Duration::from_secs(20));
+        assert!((rates.messages_per_sec - 3.0).abs() < 1e-9);
+        history.record(at(200, 130, 130, 0), Duration::from_secs(120));
+        assert_eq!(history.snapshots.len(), 1);
+    }
+    
     #[test]
     fn test_stats_calculations() {
         let stats = CommunicationStats {
@@ -936,6 +2694,7 @@ mod tests {
             bytes_received: 9728,
             messages_by_type: HashMap::new(),
             messages_by_context: HashMap::new(),
+            ..Default::default()
         };
         
         let throughput = calculate_throughput(&stats, 60); // 60 seconds
@@ -951,4 +2710,676 @@ mod tests {
         assert_eq!(most_active_message_type(&empty_stats), None);
         assert_eq!(most_active_context(&empty_stats), None);
     }
+    
+    #[tokio::test]
+    async fn test_trace_context_propagation() {
+        let node_a = Uuid::new_v4();
+        let node_b = Uuid::new_v4();
+        let root = TraceContext::new_root(true);
+        
+        // Node A sends inside its traced request
+        let sent = root.clone().scope(async {
+            crate::networking::zenoh_integration::utils::create_message(
+                node_a, Some(node_b), MessageType::Collaboration, b"work".to_vec(), None,
+            )
+        }).await;
+        assert_eq!(sent.trace_context.as_ref(), Some(&root));
+        
+        // Node B handles it and observes a child context
+        let observed = Arc::new(std::sync::Mutex::new(None));
+        let handlers = Arc::new(RwLock::new(HashMap::new()));
+        {
+            let observed = Arc::clone(&observed);
+            handlers.write().await.insert(MessageType::Collaboration, RegisteredHandler::new(
+                Arc::new(SyncHandler(move |_incoming| {
+                    *observed.lock().unwrap() = TraceContext::current();
+                    Ok(None)
+                })),
+                HandlerOptions::default(),
+            ));
+        }
+        
+        NodeCommunication::handle_incoming_message(
+            sent.clone(),
+            handlers,
+            ControlHandlers::default(),
+            Arc::new(RwLock::new(HashMap::new())),
+            Arc::new(RwLock::new(CommunicationStats::default())),
+            node_b,
+            CommunicationConfig::default(),
+            None,
+            &BroadcastMemberships::default(),
+        ).await.unwrap();
+        
+        let remote = observed.lock().unwrap().clone().expect("handler ran without trace context");
+        assert_eq!(remote.trace_id, root.trace_id);
+        assert_eq!(remote.parent_span_id.as_deref(), Some(root.span_id.as_str()));
+        assert!(remote.is_sampled());
+        
+        // The ACK carries the remote context back and completes the pending send
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let pending_acks = Arc::new(RwLock::new(HashMap::new()));
+        pending_acks.write().await.insert(sent.message_id.clone(), PendingMessage {
+            message: sent.clone(),
+            options: DeliveryOptions::default(),
+            sent_at: Utc::now(),
+            retry_count: 0,
+            response_sender: Some(tx),
+            span: Some(root.span("weavemesh.send")),
+        });
+        
+        let ack = create_ack_message(&sent, node_b, Some(remote.clone()));
+        assert_eq!(ack.trace_context.as_ref().map(|c| c.trace_id.as_str()), Some(root.trace_id.as_str()));
+        
+        let (acked_id, result) = reply_result(ControlEnvelope::decode(&ack.payload).unwrap().command).unwrap();
+        let stats = Arc::new(RwLock::new(CommunicationStats::default()));
+        NodeCommunication::handle_acknowledgment(ack, acked_id, result, Arc::clone(&pending_acks), stats).await;
+        assert!(matches!(rx.recv().await, Some(MessageResult::Delivered)));
+        assert!(pending_acks.read().await.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_peer_handler_advertisement() {
+        let peer = Uuid::new_v4();
+        let mut peer_info = crate::networking::node_discovery::utils::create_basic_node_info(
+            peer, "peer".to_string(), "ctx".to_string(),
+        );
+        let mut view = PeerHandlers::default();
+        
+        // Nothing advertised: nothing known, sends are never refused
+        view.observe(&peer_info);
+        assert!(!view.handles(&peer, &MessageType::Collaboration));
+        assert!(view.check_send(&peer, &MessageType::Collaboration, UnhandledTypePolicy::Refuse).is_ok());
+        
+        // The peer registers a handler and re-announces
+        let (handled_tx, mut handled_rx) = watch::channel(HashSet::new());
+        handled_tx.send_replace(HashSet::from([MessageType::Collaboration]));
+        handled_tx.send_replace(HashSet::from([MessageType::Collaboration, MessageType::ResourceRequest]));
+        let debounced = next_debounced(&mut handled_rx, Duration::from_millis(5)).await.unwrap();
+        assert_eq!(debounced.len(), 2);
+        advertise_handled_types(&mut peer_info, &debounced);
+        view.observe(&peer_info);
+        assert!(view.handles(&peer, &MessageType::Collaboration));
+        assert!(view.handles(&peer, &MessageType::ResourceRequest));
+        
+        // Then unregisters one
+        handled_tx.send_replace(HashSet::from([MessageType::ResourceRequest]));
+        let debounced = next_debounced(&mut handled_rx, Duration::from_millis(5)).await.unwrap();
+        advertise_handled_types(&mut peer_info, &debounced);
+        view.observe(&peer_info);
+        assert!(!view.handles(&peer, &MessageType::Collaboration));
+        
+        assert!(view.check_send(&peer, &MessageType::Collaboration, UnhandledTypePolicy::Warn).is_ok());
+        let err = view.check_send(&peer, &MessageType::Collaboration, UnhandledTypePolicy::Refuse).unwrap_err();
+        assert!(matches!(
+            err,
+            CommunicationError::UnhandledMessageType { peer: p, message_type: MessageType::Collaboration } if p == peer
+        ));
+        assert!(view.check_send(&peer, &MessageType::ResourceRequest, UnhandledTypePolicy::Refuse).is_ok());
+    }
+    
+    /// Communication over an in-process Zenoh session
+    async fn local_communication(node_id: Uuid, config: CommunicationConfig) -> NodeCommunication {
+        let session = ZenohSession::new(node_id, crate::networking::zenoh_integration::ZenohConfig::default())
+            .await
+            .unwrap();
+        NodeCommunication::new(node_id, Arc::new(session), config)
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_control_commands_reach_built_in_subsystems() {
+        use crate::key_rotation::NodeKeys;
+        use crate::mesh::security::TrustLevel;
+        
+        let (sender, receiver) = (Uuid::new_v4(), Uuid::new_v4());
+        let comm = local_communication(receiver, CommunicationConfig::default()).await;
+        let security = Arc::new(SecuritySystem::new(receiver, None));
+        let receive = |command: ControlCommand| NodeCommunication::handle_incoming_message(
+            crate::networking::zenoh_integration::utils::create_message(
+                sender, Some(receiver), MessageType::SystemControl, ControlEnvelope::new(command).encode(), None,
+            ),
+            Arc::clone(&comm.message_handlers), Arc::clone(&comm.control_handlers), Arc::clone(&comm.pending_acks),
+            Arc::clone(&comm.stats), receiver, CommunicationConfig::default(), None, &comm.broadcast_memberships,
+        );
+        
+        let lease = |op| ControlCommand::LeaseOp { lease_id: "l1".to_string(), op };
+        assert!(receive(lease(LeaseOperation::Acquire { ttl_ms: 60_000 })).await.unwrap().is_none());
+        assert_eq!(comm.leases().lease("l1", Utc::now()).unwrap().holder, sender.to_string());
+        receive(lease(LeaseOperation::Release)).await.unwrap();
+        assert_eq!(comm.leases().lease("l1", Utc::now()), None);
+        
+        let transfer = comm.transfers().register("t1", &sender.to_string());
+        receive(ControlCommand::AbortTransfer { transfer_id: "t1".to_string(), reason: None }).await.unwrap();
+        assert!(transfer.is_cancelled());
+        
+        // Rekeys are refused until a security system is routed
+        let mut keys = NodeKeys::generate().unwrap();
+        let record = keys.rotate(sender, NodeKeys::generate().unwrap(), Utc::now());
+        let refusal = receive(ControlCommand::Rekey { record }).await.unwrap().expect("refusal");
+        assert!(matches!(
+            ControlEnvelope::decode(&refusal.payload).unwrap().command,
+            ControlCommand::HandlerError { reason, .. } if reason.contains("unsupported")
+        ));
+        assert_eq!(comm.stats.read().await.unsupported_control_commands, 1);
+        
+        comm.route_rekeys_to(security.clone()).await;
+        let mut keys = NodeKeys::generate().unwrap();
+        security.establish_trust(sender, TrustLevel::Trusted, Vec::new()).await.unwrap();
+        assert!(security.record_public_key(sender, keys.public_key()).await);
+        let record = keys.rotate(sender, NodeKeys::generate().unwrap(), Utc::now());
+        receive(ControlCommand::Rekey { record }).await.unwrap();
+        let relationship = security.trust_relationships().await.into_iter().find(|r| r.partner_id == sender).unwrap();
+        assert_eq!(relationship.shared_credentials.public_key_fingerprints[&sender.to_string()], keys.fingerprint());
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_alliance_channel_binding() {
+        use crate::sacred_alliance::{ChannelConfig, MessageContent, Participant, ParticipantType, PresenceStatus};
+        
+        let (sender, receiver) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut channel = BasicSacredAllianceChannel::new("retro".to_string(), ChannelConfig::default());
+        channel.add_participant(Participant {
+            id: sender.to_string(),
+            participant_type: ParticipantType::Human,
+            presence: PresenceStatus::Active,
+            capabilities: vec![],
+            joined_at: Utc::now(),
+        }).unwrap();
+        let channel = Arc::new(RwLock::new(channel));
+        
+        // A handler registered before binding still gets the unbound contexts
+        let config = CommunicationConfig { require_acks: false, ..CommunicationConfig::default() };
+        let comm = local_communication(receiver, config.clone()).await;
+        let passed_through = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        {
+            let passed_through = Arc::clone(&passed_through);
+            comm.register_handler(MessageType::Collaboration, move |_| {
+                passed_through.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(None)
+            }).await;
+        }
+        comm.bind_alliance_channel("retro", channel.clone()).await;
+        assert_eq!(comm.alliance_bindings().channel_ids(), vec!["retro"]);
+        
+        let alliance_message = |sender: String, text: &str| AllianceMessage {
+            id: Uuid::new_v4(),
+            sender,
+            content: MessageContent::Text(text.to_string()),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        };
+        let receive = |from: Uuid, context: Option<&str>, payload: Vec<u8>| {
+            let message = crate::networking::zenoh_integration::utils::create_message(
+                from, Some(receiver), MessageType::Collaboration, payload, context.map(str::to_string),
+            );
+            NodeCommunication::handle_incoming_message(
+                message, Arc::clone(&comm.message_handlers), ControlHandlers::default(),
+                Arc::clone(&comm.pending_acks), Arc::clone(&comm.stats), receiver, config.clone(), None,
+                &comm.broadcast_memberships,
+            )
+        };
+        let is_error = |reply: Option<WeaveMeshMessage>| reply.is_some_and(|reply| reply.message_type == MessageType::SystemControl);
+        
+        // Messages arrive in order, inline with their receipt
+        for text in ["first", "second"] {
+            let outgoing = OutgoingMessage::for_alliance_channel(receiver, "retro", &alliance_message(sender.to_string(), text)).unwrap();
+            assert_eq!((outgoing.target_node, outgoing.message_type.clone()), (receiver, MessageType::Collaboration));
+            assert!(!is_error(receive(sender, outgoing.context.as_deref(), outgoing.payload).await.unwrap()));
+        }
+        let texts: Vec<_> = channel.read().await.get_history().iter()
+            .filter_map(|m| match &m.content {
+                MessageContent::Text(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, vec!["first", "second"]);
+        
+        // A node speaking for someone else and a bad payload are answered with errors
+        let spoofed = serde_json::to_vec(&alliance_message(sender.to_string(), "spoofed")).unwrap();
+        assert!(is_error(receive(Uuid::new_v4(), Some("retro"), spoofed.clone()).await.unwrap()));
+        assert!(is_error(receive(sender, Some("retro"), b"not json".to_vec()).await.unwrap()));
+        assert_eq!(channel.read().await.get_history().len(), 2);
+        
+        // Other contexts reach the replaced handler
+        assert!(!is_error(receive(sender, Some("other"), spoofed.clone()).await.unwrap()));
+        assert!(!is_error(receive(sender, None, spoofed.clone()).await.unwrap()));
+        assert_eq!(passed_through.load(std::sync::atomic::Ordering::SeqCst), 2);
+        
+        // A handler registered after binding becomes the fallback on the next binding
+        let replaced = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        {
+            let replaced = Arc::clone(&replaced);
+            comm.register_handler(MessageType::Collaboration, move |_| {
+                replaced.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(None)
+            }).await;
+        }
+        let standup = Arc::new(RwLock::new(BasicSacredAllianceChannel::new("standup".to_string(), ChannelConfig::default())));
+        comm.bind_alliance_channel("standup", standup).await;
+        let outgoing = OutgoingMessage::for_alliance_channel(receiver, "retro", &alliance_message(sender.to_string(), "third")).unwrap();
+        assert!(!is_error(receive(sender, outgoing.context.as_deref(), outgoing.payload).await.unwrap()));
+        assert_eq!(channel.read().await.get_history().len(), 3);
+        assert!(!is_error(receive(sender, Some("other"), spoofed).await.unwrap()));
+        assert_eq!(replaced.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(passed_through.load(std::sync::atomic::Ordering::SeqCst), 2);
+        
+        assert!(comm.unbind_alliance_channel("retro"));
+        assert!(comm.unbind_alliance_channel("standup"));
+        assert!(comm.alliance_bindings().is_empty());
+    }
+    
+    struct SlowEcho(Duration);
+    
+    #[async_trait::async_trait]
+    impl AsyncMessageHandler for SlowEcho {
+        async fn handle(&self, msg: IncomingMessage) -> Result<Option<Vec<u8>>, CommunicationError> {
+            tokio::time::sleep(self.0).await;
+            Ok(Some(msg.message.payload))
+        }
+    }
+    
+    /// Reply carried by a `SystemControl` payload, in envelope or legacy form
+    fn parse_reply(payload: &[u8]) -> Option<(String, MessageResult)> {
+        reply_result(ControlEnvelope::decode(payload).ok()?.command)
+    }
+    
+    /// Send `payload` from a fresh node to a receiver running `handler`, returning what the sender sees
+    async fn round_trip(handler: RegisteredHandler, payload: &[u8]) -> MessageResult {
+        let (sender, receiver) = (Uuid::new_v4(), Uuid::new_v4());
+        let sent = crate::networking::zenoh_integration::utils::create_message(
+            sender, Some(receiver), MessageType::ResourceRequest, payload.to_vec(), None,
+        );
+        let handlers = Arc::new(RwLock::new(HashMap::from([(MessageType::ResourceRequest, handler)])));
+        let stats = || Arc::new(RwLock::new(CommunicationStats::default()));
+        
+        let reply = NodeCommunication::handle_incoming_message(
+            sent.clone(), handlers, ControlHandlers::default(), Arc::new(RwLock::new(HashMap::new())), stats(),
+            receiver, CommunicationConfig::default(), None, &BroadcastMemberships::default(),
+        ).await.unwrap().expect("receiver replies");
+        assert_eq!(reply.to_node, Some(sender.to_string()));
+        
+        let (mut pending, mut rx) = pending_message(Utc::now());
+        pending.message = sent.clone();
+        let pending_acks = Arc::new(RwLock::new(HashMap::from([(sent.message_id.clone(), pending)])));
+        let none = NodeCommunication::handle_incoming_message(
+            reply, Arc::new(RwLock::new(HashMap::new())), ControlHandlers::default(), pending_acks, stats(),
+            sender, CommunicationConfig::default(), None, &BroadcastMemberships::default(),
+        ).await.unwrap();
+        assert!(none.is_none());
+        rx.recv().await.unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_async_handler_responses_and_timeouts() {
+        let quick = RegisteredHandler::new(Arc::new(SlowEcho(Duration::from_millis(20))), HandlerOptions::default());
+        assert!(matches!(round_trip(quick, b"ping:1").await, MessageResult::Response(r) if r == b"ping:1"));
+        
+        let slow = RegisteredHandler::new(Arc::new(SlowEcho(Duration::from_secs(5))), HandlerOptions {
+            max_concurrency: 1,
+            timeout: Duration::from_millis(20),
+        });
+        assert!(matches!(round_trip(slow, b"ping").await, MessageResult::HandlerTimedOut));
+        
+        let failing = RegisteredHandler::new(
+            Arc::new(SyncHandler(|_| Err(CommunicationError::HandlerError("busy".to_string())))),
+            HandlerOptions::default(),
+        );
+        assert!(matches!(round_trip(failing, b"ping").await, MessageResult::Failed(r) if r.contains("busy")));
+    }
+    
+    #[tokio::test]
+    async fn test_broadcast_scopes() {
+        let team = BroadcastScope::Group(crate::group_communication::GroupId::new("group/team"));
+        let ops = BroadcastScope::Context("ops".to_string());
+        
+        // Mesh-wide broadcasts are opt-in, and wider scopes allow less
+        let config = CommunicationConfig::default();
+        assert!(matches!(config.check_broadcast(&BroadcastScope::Mesh, 10), Err(CommunicationError::GlobalBroadcastDisabled)));
+        let global = CommunicationConfig { allow_global: true, ..CommunicationConfig::default() };
+        assert!(global.check_broadcast(&BroadcastScope::Mesh, 10).is_ok());
+        let mesh_limit = global.broadcast_limits.mesh.max_message_size;
+        assert!(matches!(global.check_broadcast(&BroadcastScope::Mesh, mesh_limit + 1), Err(CommunicationError::MessageTooLarge)));
+        assert!(global.check_broadcast(&team, mesh_limit + 1).is_ok());
+        assert!(mesh_limit < config.max_message_size);
+        
+        // Receivers drop and count broadcasts outside the scopes they accept
+        let receiver = Uuid::new_v4();
+        let config = CommunicationConfig { accepted_broadcast_scopes: Some(vec![team.clone()]), ..CommunicationConfig::default() };
+        let handled = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let handlers = {
+            let handled = Arc::clone(&handled);
+            Arc::new(RwLock::new(HashMap::from([(
+                MessageType::Collaboration,
+                RegisteredHandler::new(Arc::new(SyncHandler(move |_| {
+                    handled.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Ok(None)
+                })), HandlerOptions::default()),
+            )])))
+        };
+        let stats = Arc::new(RwLock::new(CommunicationStats::default()));
+        let config = CommunicationConfig { require_acks: false, ..config };
+        let memberships = BroadcastMemberships::with_scopes([team.clone(), ops.clone()]);
+        let receive = |message: WeaveMeshMessage, config: CommunicationConfig, memberships: BroadcastMemberships| {
+            let (handlers, stats) = (Arc::clone(&handlers), Arc::clone(&stats));
+            async move {
+                NodeCommunication::handle_incoming_message(
+                    message, handlers, ControlHandlers::default(), Arc::new(RwLock::new(HashMap::new())),
+                    stats, receiver, config, None, &memberships,
+                ).await.unwrap();
+            }
+        };
+        let broadcast = |scope: Option<BroadcastScope>, context: Option<String>| {
+            let mut message = crate::networking::zenoh_integration::utils::create_message(
+                Uuid::new_v4(), None, MessageType::Collaboration, b"news".to_vec(), context,
+            );
+            message.broadcast_scope = scope;
+            message
+        };
+        for scope in [team.clone(), ops.clone(), BroadcastScope::Mesh] {
+            receive(broadcast(Some(scope.clone()), scope.context()), config.clone(), memberships.clone()).await;
+        }
+        assert_eq!(handled.load(std::sync::atomic::Ordering::SeqCst), 1);
+        
+        // Without an opt-in list, only joined scopes and the sender's organization are delivered
+        let open = CommunicationConfig {
+            organization: Some("acme".to_string()),
+            accepted_broadcast_scopes: None,
+            ..config
+        };
+        let memberships = BroadcastMemberships::with_scopes([ops.clone(), BroadcastScope::Organization]);
+        let other_team = BroadcastScope::Group(crate::group_communication::GroupId::new("group/other"));
+        for (scope, context) in [
+            (other_team.clone(), other_team.context()),
+            (BroadcastScope::Organization, Some("globex".to_string())),
+            (BroadcastScope::Organization, Some("acme".to_string())),
+            (ops.clone(), ops.context()),
+        ] {
+            receive(broadcast(Some(scope), context), open.clone(), memberships.clone()).await;
+        }
+        assert_eq!(handled.load(std::sync::atomic::Ordering::SeqCst), 3);
+        
+        let stats = stats.read().await;
+        assert_eq!((stats.messages_received, stats.broadcasts_out_of_scope), (3, 4));
+    }
+    
+    #[tokio::test]
+    async fn test_legacy_broadcasts_are_scoped_by_context() {
+        // Broadcasts from the deprecated `broadcast_message` go to the legacy context's topic
+        assert_eq!(
+            WeaveMeshTopics::scoped_broadcast(&BroadcastScope::legacy(None), None).as_deref(),
+            Some("weavemesh/broadcast/contexts/general"),
+        );
+        assert_eq!(WeaveMeshTopics::scoped_broadcast(&BroadcastScope::Organization, None), None);
+        
+        // Unscoped broadcasts, as older nodes send them, only reach members of their context
+        let handled = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let handlers = {
+            let handled = Arc::clone(&handled);
+            Arc::new(RwLock::new(HashMap::from([(
+                MessageType::Collaboration,
+                RegisteredHandler::new(Arc::new(SyncHandler(move |_| {
+                    handled.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Ok(None)
+                })), HandlerOptions::default()),
+            )])))
+        };
+        let stats = Arc::new(RwLock::new(CommunicationStats::default()));
+        let config = CommunicationConfig { require_acks: false, ..CommunicationConfig::default() };
+        let memberships = BroadcastMemberships::with_scopes([BroadcastScope::legacy(None)]);
+        for context in [None, Some("general".to_string()), Some("ops".to_string())] {
+            let message = crate::networking::zenoh_integration::utils::create_message(
+                Uuid::new_v4(), None, MessageType::Collaboration, b"news".to_vec(), context,
+            );
+            assert!(message.broadcast_scope.is_none());
+            NodeCommunication::handle_incoming_message(
+                message, Arc::clone(&handlers), ControlHandlers::default(), Arc::new(RwLock::new(HashMap::new())),
+                Arc::clone(&stats), Uuid::new_v4(), config.clone(), None, &memberships,
+            ).await.unwrap();
+        }
+        assert_eq!(handled.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(stats.read().await.broadcasts_out_of_scope, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_replies_inherit_request_priority() {
+        let (sender, receiver) = (Uuid::new_v4(), Uuid::new_v4());
+        let typed_request = |message_type, priority| {
+            let mut message = crate::networking::zenoh_integration::utils::create_message(
+                sender, Some(receiver), message_type, b"ping".to_vec(), None,
+            );
+            message.priority = Some(priority);
+            message
+        };
+        let request = |priority| typed_request(MessageType::Collaboration, priority);
+        let handlers = Arc::new(RwLock::new(HashMap::from([(
+            MessageType::Collaboration,
+            RegisteredHandler::new(Arc::new(SyncHandler(|_| Ok(None))), HandlerOptions::default()),
+        )])));
+        let memberships = BroadcastMemberships::default();
+        let receive = |message: WeaveMeshMessage| NodeCommunication::handle_incoming_message(
+            message, Arc::clone(&handlers), ControlHandlers::default(), Arc::new(RwLock::new(HashMap::new())),
+            Arc::new(RwLock::new(CommunicationStats::default())), receiver, CommunicationConfig::default(), None,
+            &memberships,
+        );
+        
+        // Requests go out at their delivery priority, never below their type's
+        assert_eq!(MessagePriority::for_request(MessagePriority::Critical, &MessageType::Collaboration), MessagePriority::Critical);
+        assert_eq!(MessagePriority::for_request(MessagePriority::Normal, &MessageType::Heartbeat), MessagePriority::High);
+        
+        // The receiver's transport is saturated: ACKs of Normal requests are still queued
+        let (outbound, mut outbound_rx) = priority_queues();
+        let (inbound, mut inbound_rx) = priority_queues();
+        let mut sent = HashMap::new();
+        for _ in 0..20 {
+            let normal = request(MessagePriority::Normal);
+            sent.insert(normal.message_id.clone(), normal.clone());
+            outbound.enqueue(receive(normal).await.unwrap().unwrap());
+            inbound.enqueue(request(MessagePriority::Normal));
+        }
+        
+        // A Critical request to a Normal handler overtakes queued Normal traffic in both directions
+        let critical = request(MessagePriority::Critical);
+        sent.insert(critical.message_id.clone(), critical.clone());
+        inbound.enqueue(critical.clone());
+        let handled = inbound_rx.next().await.unwrap();
+        assert_eq!(handled.message_id, critical.message_id);
+        let ack = receive(handled).await.unwrap().unwrap();
+        assert_eq!(ack.priority, Some(MessagePriority::Critical));
+        assert_eq!(MessagePriority::for_message(&ack), MessagePriority::Critical);
+        outbound.enqueue(ack);
+        
+        // The queued Normal requests are answered at Normal
+        let normal = inbound_rx.next().await.unwrap();
+        assert_eq!(receive(normal).await.unwrap().unwrap().priority, Some(MessagePriority::Normal));
+        
+        let first_out = outbound_rx.next().await.unwrap();
+        assert_eq!(parse_reply(&first_out.payload).unwrap().0, critical.message_id);
+        let second_out = outbound_rx.next().await.unwrap();
+        assert_eq!(second_out.priority, Some(MessagePriority::Normal));
+        
+        // The originator measures round trips by request priority
+        let stats = Arc::new(RwLock::new(CommunicationStats::default()));
+        let pending_acks = Arc::new(RwLock::new(HashMap::new()));
+        for reply in [&first_out, &second_out] {
+            let (id, _) = parse_reply(&reply.payload).unwrap();
+            let (mut pending, _rx) = pending_message(Utc::now());
+            pending.message = sent[&id].clone();
+            pending_acks.write().await.insert(id, pending);
+        }
+        for reply in [first_out, second_out] {
+            NodeCommunication::handle_incoming_message(
+                reply, Arc::new(RwLock::new(HashMap::new())), ControlHandlers::default(), Arc::clone(&pending_acks), Arc::clone(&stats),
+                sender, CommunicationConfig::default(), None, &BroadcastMemberships::default(),
+            ).await.unwrap();
+        }
+        let latencies = stats.read().await.response_latency_by_priority.clone();
+        assert_eq!(latencies[&MessagePriority::Critical].responses, 1);
+        assert_eq!(latencies[&MessagePriority::Normal].responses, 1);
+        assert!(pending_acks.read().await.is_empty());
+    }
+    
+    struct RecordingControl(std::sync::Mutex<Vec<ControlCommand>>);
+    
+    #[async_trait::async_trait]
+    impl ControlHandler for RecordingControl {
+        async fn handle(&self, _from_node: &str, command: ControlCommand) -> Result<Option<ControlCommand>, CommunicationError> {
+            self.0.lock().unwrap().push(command);
+            Ok(None)
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_control_dispatch() {
+        let (sender, receiver) = (Uuid::new_v4(), Uuid::new_v4());
+        let control = |payload: Vec<u8>| crate::networking::zenoh_integration::utils::create_message(
+            sender, Some(receiver), MessageType::SystemControl, payload, None,
+        );
+        let rekeys = Arc::new(RecordingControl(Default::default()));
+        let aborts = Arc::new(RecordingControl(Default::default()));
+        let control_handlers: ControlHandlers = Arc::new(RwLock::new(HashMap::from([
+            (ControlKind::Rekey, rekeys.clone() as Arc<dyn ControlHandler>),
+            (ControlKind::AbortTransfer, aborts.clone() as Arc<dyn ControlHandler>),
+        ])));
+        let stats = Arc::new(RwLock::new(CommunicationStats::default()));
+        let pending_acks = Arc::new(RwLock::new(HashMap::new()));
+        let memberships = BroadcastMemberships::default();
+        let receive = |message| NodeCommunication::handle_incoming_message(
+            message, Arc::new(RwLock::new(HashMap::new())), Arc::clone(&control_handlers),
+            Arc::clone(&pending_acks), Arc::clone(&stats), receiver, CommunicationConfig::default(), None,
+            &memberships,
+        );
+        
+        // Commands reach the subsystem registered for their kind
+        let abort = ControlCommand::AbortTransfer { transfer_id: "t1".to_string(), reason: None };
+        assert!(receive(control(ControlEnvelope::new(abort.clone()).encode())).await.unwrap().is_none());
+        assert_eq!(*aborts.0.lock().unwrap(), vec![abort]);
+        assert!(rekeys.0.lock().unwrap().is_empty());
+        
+        // Without a registered handler the command is refused
+        let lease = ControlCommand::LeaseOp { lease_id: "l1".to_string(), op: LeaseOperation::Release };
+        let refusal = receive(control(ControlEnvelope::new(lease).encode())).await.unwrap().expect("refusal");
+        assert!(matches!(ControlEnvelope::decode(&refusal.payload).unwrap().command, ControlCommand::HandlerError { .. }));
+        assert_eq!(stats.read().await.unsupported_control_commands, 1);
+        
+        // Pings are answered directly with a pong
+        let ping = ControlCommand::Ping { nonce: 9, sent_at: Utc::now() };
+        let reply = receive(control(ControlEnvelope::new(ping).encode())).await.unwrap().expect("pong");
+        assert_eq!(reply.to_node, Some(sender.to_string()));
+        assert!(matches!(ControlEnvelope::decode(&reply.payload).unwrap().command, ControlCommand::Pong { nonce: 9, .. }));
+        
+        // Both envelope and legacy acknowledgments resolve pending messages
+        let formats: [fn(&str) -> Vec<u8>; 2] = [
+            |id| ControlEnvelope::new(ControlCommand::Ack { message_id: id.to_string() }).encode(),
+            |id| format!("ACK:{}", id).into_bytes(),
+        ];
+        for payload in formats {
+            let (pending, mut rx) = pending_message(Utc::now());
+            let id = pending.message.message_id.clone();
+            pending_acks.write().await.insert(id.clone(), pending);
+            assert!(receive(control(payload(&id))).await.unwrap().is_none());
+            assert!(matches!(rx.recv().await.unwrap(), MessageResult::Delivered));
+        }
+        assert!(pending_acks.read().await.is_empty());
+        
+        // Anything else is rejected and counted
+        for payload in [b"ACK".to_vec(), b"PING:1".to_vec(), br#"{"version":7,"command":{"command":"ack"}}"#.to_vec()] {
+            assert!(matches!(receive(control(payload)).await, Err(CommunicationError::InvalidMessage)));
+        }
+        assert_eq!(stats.read().await.malformed_control_messages, 3);
+    }
+    
+    #[tokio::test]
+    async fn test_per_node_retry_strategies() {
+        let (flaky, steady) = (Uuid::new_v4(), Uuid::new_v4());
+        let strategy = RetryStrategy { max_attempts: 3, jitter: false, ..RetryStrategy::exponential(Duration::from_millis(100)) };
+        let config = CommunicationConfig {
+            per_node_retry_config: HashMap::from([(flaky, strategy.clone())]),
+            ..CommunicationConfig::default()
+        };
+        assert_eq!(config.retry_strategy_for(&flaky), strategy);
+        assert_eq!(config.retry_strategy_for(&steady).max_attempts, config.max_retries);
+        
+        assert_eq!(strategy.delay_for(0, 0.0), Duration::from_millis(100));
+        assert_eq!(strategy.delay_for(2, 0.9), Duration::from_millis(400));
+        let jittered = RetryStrategy::exponential(Duration::from_millis(100));
+        assert_eq!(jittered.delay_for(1, 0.0), Duration::from_millis(100));
+        assert!(jittered.delay_for(1, 0.99) <= Duration::from_millis(200));
+        
+        let stats = RwLock::new(CommunicationStats::default());
+        let (events, mut received) = broadcast::channel(4);
+        let departed = std::sync::Mutex::new(HashSet::new());
+        let retry = |retries| NodeCommunication::next_retry(&strategy, flaky, retries, &stats, &events, &departed);
+        assert_eq!(retry(0).await, Some(Duration::from_millis(100)));
+        assert_eq!(retry(1).await, Some(Duration::from_millis(200)));
+        assert_eq!(retry(2).await, Some(Duration::from_millis(400)));
+        assert!(received.try_recv().is_err());
+        
+        // Failing after the third retry exhausts the strategy and reports
+        // the node gone, once
+        assert_eq!(retry(3).await, None);
+        assert!(matches!(received.try_recv(), Ok(NetworkEvent::NodeLeft { node_id }) if node_id == flaky.to_string()));
+        assert_eq!(retry(3).await, None);
+        assert!(received.try_recv().is_err());
+        assert_eq!(stats.read().await.per_node_retry_counts[&flaky], 3);
+    }
+    
+    /// Endpoint transport that never delivers
+    #[derive(Default)]
+    struct UnreachableTransport {
+        sends: std::sync::Mutex<u32>,
+    }
+    
+    #[async_trait::async_trait]
+    impl crate::networking::EndpointTransport for UnreachableTransport {
+        async fn send(&self, _peer: Uuid, _endpoint: &crate::mesh::NodeEndpoint, _message: &WeaveMeshMessage) -> Result<(), CommunicationError> {
+            *self.sends.lock().unwrap() += 1;
+            Err(CommunicationError::NetworkError("unreachable".to_string()))
+        }
+        
+        async fn probe(&self, _peer: Uuid, _endpoint: &crate::mesh::NodeEndpoint) -> Result<(), CommunicationError> {
+            Err(CommunicationError::NetworkError("unreachable".to_string()))
+        }
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_send_message_retries_then_reports_node_left_once() {
+        let (local, target) = (Uuid::new_v4(), Uuid::new_v4());
+        let transport = Arc::new(UnreachableTransport::default());
+        let router = Arc::new(EndpointRouter::new(transport.clone(), Default::default()));
+        router.set_endpoints(target, vec![crate::mesh::NodeEndpoint {
+            endpoint_type: crate::mesh::EndpointType::Lan,
+            address: "10.0.0.5".to_string(),
+            port: 7447,
+            secure: false,
+            priority: 0,
+        }]);
+        let strategy = RetryStrategy { max_attempts: 2, jitter: false, ..RetryStrategy::exponential(Duration::from_millis(10)) };
+        let config = CommunicationConfig {
+            per_node_retry_config: HashMap::from([(target, strategy)]),
+            ..CommunicationConfig::default()
+        };
+        let comm = local_communication(local, config).await.with_endpoint_router(router);
+        *comm.is_active.write().await = true;
+        let mut events = comm.subscribe_network_events();
+        let message = || OutgoingMessage {
+            target_node: target,
+            message_type: MessageType::Collaboration,
+            payload: Vec::new(),
+            options: DeliveryOptions { require_ack: false, ..DeliveryOptions::default() },
+            context: None,
+        };
+        
+        // The first attempt and both retries fail before giving up
+        assert!(matches!(comm.send_message(message()).await, Err(CommunicationError::NetworkError(_))));
+        assert_eq!(*transport.sends.lock().unwrap(), 3);
+        assert!(matches!(events.try_recv(), Ok(NetworkEvent::NodeLeft { node_id }) if node_id == target.to_string()));
+        
+        // Further failures to the same node are not reported again
+        assert!(comm.send_message(message()).await.is_err());
+        assert_eq!(*transport.sends.lock().unwrap(), 6);
+        assert!(events.try_recv().is_err());
+        assert_eq!(comm.get_stats().await.per_node_retry_counts[&target], 4);
+    }
 }
@@ -4,13 +4,27 @@
 //! enabling dynamic mesh formation and resource sharing across different contexts.
 
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::{RwLock, watch};
 use serde::{Deserialize, Serialize};
+use base64::Engine;
 use chrono::{DateTime, Utc};
+use ring::signature::{UnparsedPublicKey, ED25519};
 use uuid::Uuid;
 
+use crate::key_rotation::NodeKeys;
+use crate::mesh::verification::{TrustedKey, TrustedKeys};
+use crate::networking::clock_skew::{ClockSkewConfig, ClockSkewEvent, ClockSkewTracker, TimeEcho};
+use crate::mesh::metadata_visibility::{MetadataVisibility, MetadataVisibilityMap};
+use crate::networking::node_communication::{advertise_handled_types, next_debounced};
+use crate::networking::query_cache::{CachedQuery, QueryCache, QueryCacheConfig, QueryCacheStats};
+use crate::networking::dns_sd::{decode_txt_records, node_service, DnsSdClient, DnsSdService, MdnsSdClient, DNS_SD_SERVICE_TYPE};
 use crate::networking::zenoh_integration::{ZenohSession, WeaveMeshMessage, MessageType, WeaveMeshTopics};
+use crate::protocol::{AdaptiveHeartbeat, HeartbeatConfig};
+use crate::resource_profile::{
+    ComponentFootprint, EvictionCounters, EvictionKind, MemoryFootprint, ResourceProfile,
+};
 
 /// Universal node discovery and registration manager
 /// 
@@ -33,8 +47,92 @@ pub struct NodeDiscovery {
     /// Discovery configuration
     config: DiscoveryConfig,
     
+    /// Heartbeat intervals declared by each node (seconds)
+    declared_intervals: Arc<RwLock<HashMap<Uuid, u64>>>,
+    
     /// Whether discovery is currently active
     is_active: Arc<RwLock<bool>>,
+    
+    /// Counts of nodes dropped by the registry size limit
+    eviction_counters: Arc<EvictionCounters>,
+    
+    /// Visibility of this node's metadata entries; only Public ones are announced
+    metadata_visibility: MetadataVisibilityMap,
+    
+    /// Client for the DNS-SD backend; mDNS unless one is supplied
+    dns_sd_client: std::sync::OnceLock<Arc<dyn DnsSdClient>>,
+    
+    /// Offsets of peer clocks, measured through heartbeats
+    clock_skew: Arc<RwLock<ClockSkewTracker>>,
+    
+    /// Recent results of mesh-wide node queries
+    query_cache: Arc<NodeQueryCache>,
+    
+    /// Key signing this node's announcements
+    signing_key: Option<Arc<NodeKeys>>,
+    
+    /// Announcement public keys of other nodes, pinned on first use
+    known_public_keys: Arc<RwLock<TrustedKeys>>,
+    
+    /// Announcement interval, adapted to the online nodes and their churn
+    heartbeat: Arc<RwLock<AdaptiveHeartbeat>>,
+}
+
+/// Cache of mesh-wide node query results
+pub type NodeQueryCache = QueryCache<NodeFilter, Vec<NodeInfo>>;
+
+/// Mechanism used to announce this node and find peers
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DiscoveryBackend {
+    /// Announcements over Zenoh
+    #[default]
+    Zenoh,
+    
+    /// DNS-SD registration and browsing, for networks where Zenoh scouting
+    /// does not reach
+    DnsSd {
+        /// Service type, normally [`DNS_SD_SERVICE_TYPE`]
+        service_type: String,
+        /// Domain to register and browse in; `local.` uses mDNS unless a
+        /// client is supplied with [`NodeDiscovery::with_dns_sd_client`]
+        domain: String,
+    },
+    
+    /// Several backends feeding one registry keyed by node ID
+    Hybrid(Vec<DiscoveryBackend>),
+}
+
+impl DiscoveryBackend {
+    /// DNS-SD backend using the WeaveMesh service type
+    pub fn dns_sd(domain: impl Into<String>) -> Self {
+        DiscoveryBackend::DnsSd {
+            service_type: DNS_SD_SERVICE_TYPE.to_string(),
+            domain: domain.into(),
+        }
+    }
+    
+    /// The Zenoh and DNS-SD backends in use, with Hybrid nesting flattened
+    /// and duplicates removed
+    pub fn leaves(&self) -> Vec<DiscoveryBackend> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&mut leaves);
+        leaves
+    }
+    
+    fn collect_leaves(&self, leaves: &mut Vec<DiscoveryBackend>) {
+        match self {
+            DiscoveryBackend::Hybrid(backends) => {
+                for backend in backends {
+                    backend.collect_leaves(leaves);
+                }
+            }
+            leaf => {
+                if !leaves.contains(leaf) {
+                    leaves.push(leaf.clone());
+                }
+            }
+        }
+    }
 }
 
 /// Configuration for node discovery
@@ -47,10 +145,29 @@ pub struct DiscoveryConfig {
     pub discovery_timeout: u64,
     
     /// How long to keep inactive nodes in registry (seconds)
+    ///
+    /// Used as the liveness timeout for nodes that have not declared
+    /// their heartbeat interval.
     pub node_timeout: u64,
     
+    /// Number of missed heartbeats before a node is marked offline
+    pub missed_heartbeats_before_offline: u32,
+    
     /// Whether to enable debug logging
     pub debug: bool,
+    
+    /// Maximum nodes kept in the registry; the least recently seen are
+    /// evicted first (unbounded if None)
+    pub max_nodes: Option<usize>,
+    
+    /// How nodes are announced and discovered
+    pub backend: DiscoveryBackend,
+    
+    /// Peer clock offset estimation
+    pub clock_skew: ClockSkewConfig,
+    
+    /// Reject unsigned announcements from nodes without a pinned key
+    pub require_signed_announcements: bool,
 }
 
 impl Default for DiscoveryConfig {
@@ -59,11 +176,68 @@ impl Default for DiscoveryConfig {
             announcement_interval: 30,
             discovery_timeout: 10,
             node_timeout: 300, // 5 minutes
+            missed_heartbeats_before_offline: 3,
             debug: false,
+            max_nodes: None,
+            backend: DiscoveryBackend::Zenoh,
+            clock_skew: ClockSkewConfig::default(),
+            require_signed_announcements: false,
         }
     }
 }
 
+impl DiscoveryConfig {
+    /// Default configuration with the registry bound of a resource profile
+    pub fn for_profile(profile: ResourceProfile) -> Self {
+        Self {
+            max_nodes: profile.limits().max_discovered_nodes,
+            ..Self::default()
+        }
+    }
+    
+    /// Default configuration discovering peers through DNS-SD in `domain`
+    pub fn dns_sd(domain: impl Into<String>) -> Self {
+        Self {
+            backend: DiscoveryBackend::dns_sd(domain),
+            ..Self::default()
+        }
+    }
+    
+    /// Liveness timeout for a node given its declared heartbeat interval (seconds)
+    pub fn liveness_timeout(&self, declared_interval_secs: Option<u64>) -> u64 {
+        match declared_interval_secs {
+            Some(interval) if interval > 0 => {
+                interval * self.missed_heartbeats_before_offline.max(1) as u64
+            }
+            _ => self.node_timeout,
+        }
+    }
+    
+    /// Adaptive heartbeat bounds around `announcement_interval`
+    pub fn heartbeat_config(&self) -> HeartbeatConfig {
+        let defaults = HeartbeatConfig::default();
+        HeartbeatConfig {
+            base_interval_secs: self.announcement_interval,
+            max_interval_secs: defaults.max_interval_secs.max(self.announcement_interval),
+            missed_heartbeats: self.missed_heartbeats_before_offline,
+            ..defaults
+        }
+    }
+}
+
+/// Payload of a discovery heartbeat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatPayload {
+    /// Interval until the sender's next heartbeat (seconds)
+    pub interval_secs: u64,
+    /// Sender's clock when sending
+    #[serde(default)]
+    pub sent_at: Option<DateTime<Utc>>,
+    /// Echoes of the last heartbeat heard from each peer, for clock offsets
+    #[serde(default)]
+    pub echoes: HashMap<Uuid, TimeEcho>,
+}
+
 /// Information about a discovered node
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeInfo {
@@ -146,6 +320,95 @@ pub struct NodeAnnouncement {
     
     /// Timestamp of announcement
     pub timestamp: DateTime<Utc>,
+    
+    /// Sender's Ed25519 public key, base64
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    
+    /// Ed25519 signature over the canonical body, base64
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl NodeAnnouncement {
+    /// Canonical JSON of the announcement without its signature
+    ///
+    /// Object keys are sorted so the bytes do not depend on map order.
+    pub fn signing_bytes(&self) -> Result<Vec<u8>, DiscoveryError> {
+        let unsigned = NodeAnnouncement { signature: None, ..self.clone() };
+        let value = serde_json::to_value(&unsigned)
+            .map_err(|e| DiscoveryError::SerializationError(e.to_string()))?;
+        serde_json::to_vec(&value).map_err(|e| DiscoveryError::SerializationError(e.to_string()))
+    }
+    
+    /// Sign with the node's `keys`, embedding their public key
+    pub fn sign(&mut self, keys: &NodeKeys) -> Result<(), DiscoveryError> {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        self.public_key = Some(encode(keys.public_key()));
+        self.signature = Some(encode(&keys.sign(&self.signing_bytes()?)));
+        Ok(())
+    }
+    
+    /// Public key the announcement claims to be signed with
+    pub fn decoded_public_key(&self) -> Option<Vec<u8>> {
+        let key = self.public_key.as_ref()?;
+        base64::engine::general_purpose::STANDARD.decode(key).ok()
+    }
+}
+
+/// Outcome of checking an announcement against the public key registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnnouncementTrust {
+    /// Signed with the node's pinned key
+    Verified,
+    /// First signed announcement from the node; its key is now pinned
+    PinnedOnFirstUse,
+    /// Unsigned, from a node without a pinned key
+    Unsigned,
+    /// Not acceptable, with the reason
+    Rejected(String),
+}
+
+impl AnnouncementTrust {
+    pub fn is_accepted(&self) -> bool {
+        !matches!(self, AnnouncementTrust::Rejected(_))
+    }
+}
+
+/// Check `announcement` against `known_keys`, pinning the key of nodes
+/// seen for the first time
+///
+/// Once a node's key is pinned every announcement claiming to be from it
+/// must carry a valid signature by that key, whichever backend it came through.
+pub fn check_announcement_trust(
+    announcement: &NodeAnnouncement,
+    known_keys: &mut TrustedKeys,
+    require_signed: bool,
+) -> AnnouncementTrust {
+    let node_id = announcement.node_info.node_id;
+    if let Some(pinned) = known_keys.keys.get(&node_id) {
+        return if NodeDiscovery::verify_announcement(announcement, &pinned.public_key) {
+            AnnouncementTrust::Verified
+        } else {
+            AnnouncementTrust::Rejected(format!("Announcement from {} is not signed by its pinned key", node_id))
+        };
+    }
+    
+    if announcement.signature.is_none() {
+        return if require_signed {
+            AnnouncementTrust::Rejected(format!("Unsigned announcement from untrusted node {}", node_id))
+        } else {
+            AnnouncementTrust::Unsigned
+        };
+    }
+    
+    match announcement.decoded_public_key() {
+        Some(key) if NodeDiscovery::verify_announcement(announcement, &key) => {
+            known_keys.keys.insert(node_id, TrustedKey { public_key: key, last_sequence: None });
+            AnnouncementTrust::PinnedOnFirstUse
+        }
+        _ => AnnouncementTrust::Rejected(format!("Invalid signature on announcement from {}", node_id)),
+    }
 }
 
 /// Types of node announcements
@@ -202,6 +465,72 @@ pub struct NodeFilter {
     pub metadata_filters: HashMap<String, String>,
 }
 
+
+impl CachedQuery for NodeFilter {
+    type Item = NodeInfo;
+    
+    /// Capabilities and metadata sorted, so equal filters share a key
+    fn cache_key(&self) -> String {
+        let mut capabilities: Vec<String> = self.required_capabilities.iter()
+            .map(|capability| format!("{:?}", capability))
+            .collect();
+        capabilities.sort();
+        capabilities.dedup();
+        let mut metadata: Vec<(&String, &String)> = self.metadata_filters.iter().collect();
+        metadata.sort();
+        format!(
+            "context={:?};capabilities={:?};online={};name={:?};metadata={:?}",
+            self.context_id,
+            capabilities,
+            self.online_only,
+            self.name_pattern.as_ref().map(|pattern| pattern.to_lowercase()),
+            metadata,
+        )
+    }
+    
+    /// Check if a node matches the filter
+    fn matches(&self, node: &NodeInfo) -> bool {
+        // Check context ID
+        if let Some(ref context_id) = self.context_id {
+            if &node.context_id != context_id {
+                return false;
+            }
+        }
+        
+        // Check online status
+        if self.online_only && !node.is_online {
+            return false;
+        }
+        
+        // Check required capabilities
+        for required_cap in &self.required_capabilities {
+            if !node.capabilities.contains(required_cap) {
+                return false;
+            }
+        }
+        
+        // Check name pattern
+        if let Some(ref pattern) = self.name_pattern {
+            if !node.display_name.to_lowercase().contains(&pattern.to_lowercase()) {
+                return false;
+            }
+        }
+        
+        // Check metadata filters
+        for (key, value) in &self.metadata_filters {
+            if let Some(node_value) = node.metadata.get(key) {
+                if node_value != value {
+                    return false;
+                }
+            } else {
+                return false;
+            }
+        }
+        
+        true
+    }
+}
+
 impl NodeDiscovery {
     /// Create a new node discovery manager
     pub fn new(
@@ -213,27 +542,138 @@ impl NodeDiscovery {
             node_id,
             zenoh_session,
             node_registry: Arc::new(RwLock::new(HashMap::new())),
-            config,
+            declared_intervals: Arc::new(RwLock::new(HashMap::new())),
             is_active: Arc::new(RwLock::new(false)),
+            eviction_counters: Arc::new(EvictionCounters::new()),
+            metadata_visibility: MetadataVisibilityMap::default(),
+            dns_sd_client: std::sync::OnceLock::new(),
+            clock_skew: Arc::new(RwLock::new(ClockSkewTracker::new(config.clock_skew.clone()))),
+            query_cache: Arc::new(NodeQueryCache::new(QueryCacheConfig::default())),
+            signing_key: None,
+            known_public_keys: Arc::new(RwLock::new(TrustedKeys::default())),
+            heartbeat: Arc::new(RwLock::new(AdaptiveHeartbeat::new(config.heartbeat_config()))),
+            config,
         }
     }
     
+    /// Announce according to `visibility`, usually the local node's
+    /// [`metadata_visibility`](crate::mesh::LocalNode::metadata_visibility)
+    pub fn with_metadata_visibility(mut self, visibility: MetadataVisibilityMap) -> Self {
+        self.metadata_visibility = visibility;
+        self
+    }
+    
+    /// Sign this node's announcements with its identity keys
+    ///
+    /// Signed announcements can also be checked offline with
+    /// [`verify_announcement`](crate::mesh::verification::verify_announcement).
+    pub fn with_signing_key(mut self, keys: Arc<NodeKeys>) -> Self {
+        self.signing_key = Some(keys);
+        self
+    }
+    
+    /// Whether `announcement` carries a valid signature by `public_key`
+    pub fn verify_announcement(announcement: &NodeAnnouncement, public_key: &[u8]) -> bool {
+        let signature = match announcement.signature.as_ref()
+            .and_then(|signature| base64::engine::general_purpose::STANDARD.decode(signature).ok())
+        {
+            Some(signature) => signature,
+            None => return false,
+        };
+        let message = match announcement.signing_bytes() {
+            Ok(message) => message,
+            Err(_) => return false,
+        };
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&message, &signature)
+            .is_ok()
+    }
+    
+    /// Public keys pinned for other nodes
+    pub async fn known_public_keys(&self) -> TrustedKeys {
+        self.known_public_keys.read().await.clone()
+    }
+    
+    /// Pin `public_key` for `node_id` ahead of its first announcement
+    pub async fn trust_public_key(&self, node_id: Uuid, public_key: Vec<u8>) {
+        self.known_public_keys.write().await.keys.insert(node_id, TrustedKey { public_key, last_sequence: None });
+    }
+    
+    /// Use `client` for the DNS-SD backend
+    pub fn with_dns_sd_client(mut self, client: Arc<dyn DnsSdClient>) -> Self {
+        self.dns_sd_client = std::sync::OnceLock::from(client);
+        self
+    }
+    
+    /// Smoothed offset of a peer's clock from this node's, if measured
+    pub async fn get_peer_clock_offset(&self, peer: &Uuid) -> Option<chrono::Duration> {
+        self.clock_skew.read().await.get_peer_clock_offset(peer)
+    }
+    
+    /// Peers whose clocks cross the configured skew threshold
+    pub async fn subscribe_clock_skew(&self) -> tokio::sync::broadcast::Receiver<ClockSkewEvent> {
+        self.clock_skew.read().await.subscribe()
+    }
+    
+    /// Shared clock offsets, for correcting peer timestamps elsewhere
+    pub fn clock_skew(&self) -> &Arc<RwLock<ClockSkewTracker>> {
+        &self.clock_skew
+    }
+    
+    /// Record registry evictions into shared counters
+    pub fn with_eviction_counters(mut self, counters: Arc<EvictionCounters>) -> Self {
+        self.eviction_counters = counters;
+        self
+    }
+    
+    /// Counters of nodes dropped by the registry size limit
+    pub fn eviction_counters(&self) -> &Arc<EvictionCounters> {
+        &self.eviction_counters
+    }
+    
+    /// Serve repeated mesh-wide queries from a cache with these settings
+    pub fn with_query_cache(mut self, config: QueryCacheConfig) -> Self {
+        self.query_cache = Arc::new(NodeQueryCache::new(config));
+        self
+    }
+    
+    /// Hits, misses and stale results served by the query cache
+    pub fn query_cache_stats(&self) -> QueryCacheStats {
+        self.query_cache.stats()
+    }
+    
     /// Start the discovery process
     pub async fn start(
         &self,
         node_info: NodeInfo,
     ) -> Result<(), DiscoveryError> {
+        let backends = self.config.backend.leaves();
+        if backends.iter().any(|b| matches!(b, DiscoveryBackend::DnsSd { .. })) && self.dns_sd_client.get().is_none() {
+            let _ = self.dns_sd_client.set(Arc::new(MdnsSdClient::new()?));
+        }
+        
         // Mark as active
         *self.is_active.write().await = true;
         
-        // Subscribe to discovery topics
-        self.setup_subscriptions().await?;
-        
-        // Announce this node's presence
-        self.announce_node(node_info, AnnouncementType::Join).await?;
-        
-        // Start periodic announcement task
-        self.start_announcement_task().await;
+        for backend in &backends {
+            match backend {
+                DiscoveryBackend::Zenoh => {
+                    // Subscribe to discovery topics
+                    self.setup_subscriptions().await?;
+                    
+                    // Announce this node's presence
+                    self.announce_node(node_info.clone(), AnnouncementType::Join).await?;
+                    
+                    // Start periodic announcement task
+                    self.start_announcement_task().await;
+                }
+                DiscoveryBackend::DnsSd { service_type, domain } => {
+                    self.register_dns_sd(node_info.clone(), service_type, domain).await?;
+                    self.start_dns_sd_browse_task(service_type.clone(), domain.clone()).await;
+                }
+                DiscoveryBackend::Hybrid(_) => {}
+            }
+        }
         
         // Start cleanup task for inactive nodes
         self.start_cleanup_task().await;
@@ -250,9 +690,21 @@ impl NodeDiscovery {
         // Mark as inactive
         *self.is_active.write().await = false;
         
-        // Announce that we're leaving
-        if let Some(node_info) = self.get_own_node_info().await {
-            self.announce_node(node_info, AnnouncementType::Leave).await?;
+        for backend in self.config.backend.leaves() {
+            match backend {
+                DiscoveryBackend::Zenoh => {
+                    // Announce that we're leaving
+                    if let Some(node_info) = self.get_own_node_info().await {
+                        self.announce_node(node_info, AnnouncementType::Leave).await?;
+                    }
+                }
+                DiscoveryBackend::DnsSd { service_type, domain } => {
+                    if let Some(client) = self.dns_sd_client.get() {
+                        client.unregister(&self.node_id.to_string(), &service_type, &domain).await?;
+                    }
+                }
+                DiscoveryBackend::Hybrid(_) => {}
+            }
         }
         
         // Clear the registry
@@ -279,16 +731,48 @@ impl NodeDiscovery {
     pub async fn find_nodes(&self, filter: NodeFilter) -> Vec<NodeInfo> {
         let registry = self.node_registry.read().await;
         registry.values()
-            .filter(|node| self.matches_filter(node, &filter))
+            .filter(|node| filter.matches(node))
             .cloned()
             .collect()
     }
     
     /// Query the mesh for nodes matching criteria
     pub async fn query_nodes(&self, filter: NodeFilter) -> Result<Vec<NodeInfo>, DiscoveryError> {
+        Self::scatter_gather(
+            self.node_id,
+            Arc::clone(&self.zenoh_session),
+            Arc::clone(&self.node_registry),
+            self.config.discovery_timeout,
+            filter,
+        ).await
+    }
+    
+    /// Query the mesh for nodes, reusing recent results of the same query
+    ///
+    /// Results within the cache TTL are returned without touching the
+    /// network; older ones are returned while a refresh runs in the
+    /// background. `fresh` always queries the mesh.
+    pub async fn query_nodes_cached(&self, filter: NodeFilter, fresh: bool) -> Result<Vec<NodeInfo>, DiscoveryError> {
+        let node_id = self.node_id;
+        let zenoh_session = Arc::clone(&self.zenoh_session);
+        let node_registry = Arc::clone(&self.node_registry);
+        let timeout = self.config.discovery_timeout;
+        self.query_cache.get_or_fetch(&filter, fresh, move |filter| {
+            Self::scatter_gather(node_id, Arc::clone(&zenoh_session), Arc::clone(&node_registry), timeout, filter)
+        }).await
+    }
+    
+    /// Broadcast a discovery query and collect matching nodes
+    async fn scatter_gather(
+        node_id: Uuid,
+        zenoh_session: Arc<ZenohSession>,
+        node_registry: Arc<RwLock<HashMap<Uuid, NodeInfo>>>,
+        discovery_timeout: u64,
+        filter: NodeFilter,
+    ) -> Result<Vec<NodeInfo>, DiscoveryError> {
         let query = DiscoveryQuery {
             query_id: Uuid::new_v4().to_string(),
-            from_node: self.node_id,
+            from_node: node_id,
             filter,
             timestamp: Utc::now(),
         };
@@ -297,16 +781,20 @@ impl NodeDiscovery {
             .map_err(|e| DiscoveryError::SerializationError(e.to_string()))?;
         
         // Broadcast the query
-        self.zenoh_session.broadcast_message(
+        zenoh_session.broadcast_message(
             MessageType::NodeDiscovery,
             payload,
         ).await.map_err(|e| DiscoveryError::NetworkError(e.to_string()))?;
         
         // Wait for responses (simplified - in practice we'd collect responses)
-        tokio::time::sleep(tokio::time::Duration::from_secs(self.config.discovery_timeout)).await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(discovery_timeout)).await;
         
         // Return current registry matches
-        Ok(self.find_nodes(query.filter).await)
+        let registry = node_registry.read().await;
+        Ok(registry.values()
+            .filter(|node| query.filter.matches(node))
+            .cloned()
+            .collect())
     }
     
     /// Get nodes in the same context
@@ -338,13 +826,52 @@ impl NodeDiscovery {
     /// Check if a node is currently online
     pub async fn is_node_online(&self, node_id: &Uuid) -> bool {
         if let Some(node_info) = self.get_node_info(node_id).await {
-            node_info.is_online && 
-            (Utc::now() - node_info.last_seen).num_seconds() < self.config.node_timeout as i64
+            let declared = self.declared_intervals.read().await.get(node_id).copied();
+            Self::is_live(&node_info, declared, &self.config, Utc::now())
         } else {
             false
         }
     }
     
+    /// Current effective announcement interval (without jitter)
+    ///
+    /// Adapts to the number of online nodes, shortening while nodes join
+    /// and leave.
+    pub async fn announcement_interval(&self) -> Duration {
+        let now = Utc::now();
+        Self::observe_online_nodes(
+            self.node_id, &self.node_registry, &self.declared_intervals, &self.heartbeat, &self.config, now,
+        ).await;
+        Duration::from_secs(self.heartbeat.write().await.base_interval_secs(now))
+    }
+    
+    /// Whether a node marked online is still within its liveness timeout
+    fn is_live(node_info: &NodeInfo, declared_interval_secs: Option<u64>, config: &DiscoveryConfig, now: DateTime<Utc>) -> bool {
+        let timeout = config.liveness_timeout(declared_interval_secs);
+        node_info.is_online && (now - node_info.last_seen).num_seconds() < timeout as i64
+    }
+    
+    /// Feed the other nodes currently online into `heartbeat`
+    async fn observe_online_nodes(
+        own_id: Uuid,
+        node_registry: &RwLock<HashMap<Uuid, NodeInfo>>,
+        declared_intervals: &RwLock<HashMap<Uuid, u64>>,
+        heartbeat: &RwLock<AdaptiveHeartbeat>,
+        config: &DiscoveryConfig,
+        now: DateTime<Utc>,
+    ) {
+        let online: Vec<Uuid> = {
+            let registry = node_registry.read().await;
+            let intervals = declared_intervals.read().await;
+            registry.values()
+                .filter(|node| node.node_id != own_id)
+                .filter(|node| Self::is_live(node, intervals.get(&node.node_id).copied(), config, now))
+                .map(|node| node.node_id)
+                .collect()
+        };
+        heartbeat.write().await.observe_peers(online, now);
+    }
+    
     /// Update node capabilities
     pub async fn update_capabilities(&self, capabilities: Vec<NodeCapability>) -> Result<(), DiscoveryError> {
         if let Some(mut node_info) = self.get_own_node_info().await {
@@ -363,6 +890,50 @@ impl NodeDiscovery {
         Ok(())
     }
     
+    /// Keep this node's advertised message handlers in step with `handled`
+    ///
+    /// Announces the current handlers, then re-announces after each change
+    /// once `debounce` has passed without further changes. Runs until the
+    /// watched sender is dropped or discovery stops.
+    pub async fn advertise_handlers(
+        &self,
+        mut handled: watch::Receiver<HashSet<MessageType>>,
+        debounce: Duration,
+    ) -> Result<(), DiscoveryError> {
+        let current = handled.borrow_and_update().clone();
+        self.announce_handlers(&current).await?;
+        
+        while let Some(current) = next_debounced(&mut handled, debounce).await {
+            if !*self.is_active.read().await {
+                break;
+            }
+            self.announce_handlers(&current).await?;
+        }
+        Ok(())
+    }
+    
+    /// Record handled message types in this node's info and announce them
+    async fn announce_handlers(&self, handled: &HashSet<MessageType>) -> Result<(), DiscoveryError> {
+        let node_info = {
+            let mut registry = self.node_registry.write().await;
+            match registry.get_mut(&self.node_id) {
+                Some(node_info) => {
+                    advertise_handled_types(node_info, handled);
+                    node_info.clone()
+                }
+                None => return Ok(()),
+            }
+        };
+        self.announce_node(node_info, AnnouncementType::CapabilityUpdate).await
+    }
+    
+    /// Tag a metadata key with a visibility tier
+    ///
+    /// Entries above Public are left out of announcements from now on.
+    pub fn set_metadata_visibility(&self, key: &str, visibility: MetadataVisibility) {
+        self.metadata_visibility.set(key, visibility);
+    }
+    
     /// Setup Zenoh subscriptions for discovery
     async fn setup_subscriptions(&self) -> Result<(), DiscoveryError> {
         // Subscribe to discovery announcements
@@ -383,14 +954,25 @@ impl NodeDiscovery {
         
         // Set up message handler
         let node_registry = Arc::clone(&self.node_registry);
+        let declared_intervals = Arc::clone(&self.declared_intervals);
+        let eviction_counters = Arc::clone(&self.eviction_counters);
+        let clock_skew = Arc::clone(&self.clock_skew);
+        let query_cache = Arc::clone(&self.query_cache);
+        let known_public_keys = Arc::clone(&self.known_public_keys);
+        let node_id = self.node_id;
         let config = self.config.clone();
         
         self.zenoh_session.set_message_handler(move |message| {
             let registry = Arc::clone(&node_registry);
+            let intervals = Arc::clone(&declared_intervals);
+            let counters = Arc::clone(&eviction_counters);
+            let clock_skew = Arc::clone(&clock_skew);
+            let query_cache = Arc::clone(&query_cache);
+            let known_public_keys = Arc::clone(&known_public_keys);
             let config = config.clone();
             
             tokio::spawn(async move {
-                if let Err(e) = Self::handle_discovery_message(message, registry, config).await {
+                if let Err(e) = Self::handle_discovery_message(node_id, message, registry, intervals, counters, clock_skew, query_cache, known_public_keys, config).await {
                     eprintln!("Error handling discovery message: {}", e);
                 }
             });
@@ -403,19 +985,58 @@ impl NodeDiscovery {
     
     /// Handle incoming discovery messages
     async fn handle_discovery_message(
+        own_id: Uuid,
         message: WeaveMeshMessage,
         node_registry: Arc<RwLock<HashMap<Uuid, NodeInfo>>>,
+        declared_intervals: Arc<RwLock<HashMap<Uuid, u64>>>,
+        eviction_counters: Arc<EvictionCounters>,
+        clock_skew: Arc<RwLock<ClockSkewTracker>>,
+        query_cache: Arc<NodeQueryCache>,
+        known_public_keys: Arc<RwLock<TrustedKeys>>,
         config: DiscoveryConfig,
     ) -> Result<(), DiscoveryError> {
         match message.message_type {
             MessageType::NodeDiscovery => {
                 if let Ok(announcement) = serde_json::from_slice::<NodeAnnouncement>(&message.payload) {
-                    Self::handle_node_announcement(announcement, node_registry, config).await?;
+                    // Judge freshness by the sender's clock corrected to ours
+                    let sender = announcement.node_info.node_id;
+                    let max_age = chrono::Duration::seconds(config.liveness_timeout(None) as i64);
+                    if clock_skew.read().await.is_expired(&sender, announcement.timestamp, max_age, Utc::now()) {
+                        if config.debug {
+                            println!("Ignoring stale announcement from {}", sender);
+                        }
+                        return Ok(());
+                    }
+                    
+                    if !Self::is_announcement_trusted(&announcement, &known_public_keys, &config).await {
+                        return Ok(());
+                    }
+                    
+                    let evicted = Self::handle_node_announcement(announcement, node_registry, &eviction_counters, &query_cache, config).await?;
+                    if !evicted.is_empty() {
+                        let mut intervals = declared_intervals.write().await;
+                        for node_id in evicted {
+                            intervals.remove(&node_id);
+                        }
+                    }
                 }
             }
             MessageType::Heartbeat => {
                 // Update last seen time for the node
                 if let Ok(node_id) = Uuid::parse_str(&message.from_node) {
+                    // Track the sender-declared interval for liveness checks
+                    if let Ok(payload) = serde_json::from_slice::<HeartbeatPayload>(&message.payload) {
+                        declared_intervals.write().await.insert(node_id, payload.interval_secs);
+                        if let Some(sent_at) = payload.sent_at {
+                            clock_skew.write().await.observe_heartbeat(
+                                node_id,
+                                sent_at,
+                                payload.echoes.get(&own_id),
+                                Utc::now(),
+                            );
+                        }
+                    }
+                    
                     let mut registry = node_registry.write().await;
                     if let Some(node_info) = registry.get_mut(&node_id) {
                         node_info.last_seen = Utc::now();
@@ -431,13 +1052,40 @@ impl NodeDiscovery {
         Ok(())
     }
     
+    /// Whether `announcement` passes [`check_announcement_trust`]
+    ///
+    /// Every backend's announcements go through here before reaching the registry.
+    async fn is_announcement_trusted(
+        announcement: &NodeAnnouncement,
+        known_public_keys: &RwLock<TrustedKeys>,
+        config: &DiscoveryConfig,
+    ) -> bool {
+        let trust = check_announcement_trust(
+            announcement,
+            &mut *known_public_keys.write().await,
+            config.require_signed_announcements,
+        );
+        if let AnnouncementTrust::Rejected(reason) = &trust {
+            if config.debug {
+                println!("Ignoring announcement: {}", reason);
+            }
+        }
+        trust.is_accepted()
+    }
+    
     /// Handle node announcement
+    ///
+    /// Returns the nodes evicted to keep the registry within `max_nodes`.
     async fn handle_node_announcement(
         announcement: NodeAnnouncement,
         node_registry: Arc<RwLock<HashMap<Uuid, NodeInfo>>>,
+        eviction_counters: &EvictionCounters,
+        query_cache: &NodeQueryCache,
         config: DiscoveryConfig,
-    ) -> Result<(), DiscoveryError> {
+    ) -> Result<Vec<Uuid>, DiscoveryError> {
         let mut registry = node_registry.write().await;
+        let mut evicted = Vec::new();
+        let announced_id = announcement.node_info.node_id;
         
         match announcement.announcement_type {
             AnnouncementType::Join | AnnouncementType::Heartbeat | 
@@ -461,6 +1109,27 @@ impl NodeDiscovery {
                     
                     registry.insert(node_id, updated);
                 } else {
+                    // Make room by evicting the least recently seen nodes
+                    if let Some(max_nodes) = config.max_nodes {
+                        while !registry.is_empty() && registry.len() >= max_nodes {
+                            let oldest = registry.values()
+                                .min_by_key(|info| info.last_seen)
+                                .map(|info| info.node_id);
+                            match oldest {
+                                Some(oldest) => {
+                                    registry.remove(&oldest);
+                                    evicted.push(oldest);
+                                }
+                                None => break,
+                            }
+                        }
+                        eviction_counters.record(EvictionKind::DiscoveredNode, evicted.len() as u64);
+                        
+                        if config.debug && !evicted.is_empty() {
+                            println!("Evicted {} least recently seen nodes", evicted.len());
+                        }
+                    }
+                    
                     // Add new node
                     registry.insert(node_id, node_info);
                 }
@@ -472,31 +1141,44 @@ impl NodeDiscovery {
                 }
             }
             AnnouncementType::Leave => {
-                if let Some(mut node_info) = registry.get_mut(&announcement.node_info.node_id) {
+                if let Some(node_info) = registry.get_mut(&announced_id) {
                     node_info.is_online = false;
                     node_info.last_seen = Utc::now();
                 }
                 
                 if config.debug {
-                    println!("Node {} left the mesh", announcement.node_info.node_id);
+                    println!("Node {} left the mesh", announced_id);
                 }
             }
         }
         
-        Ok(())
+        // Drop cached queries whose results this announcement changes
+        let announced = registry.get(&announced_id).cloned();
+        query_cache.invalidate_where(|filter, nodes| {
+            nodes.iter().any(|node| node.node_id == announced_id || evicted.contains(&node.node_id))
+                || announced.as_ref().is_some_and(|node| filter.matches(node))
+        });
+        
+        Ok(evicted)
     }
     
     /// Announce this node to the mesh
     async fn announce_node(
         &self,
-        node_info: NodeInfo,
+        mut node_info: NodeInfo,
         announcement_type: AnnouncementType,
     ) -> Result<(), DiscoveryError> {
-        let announcement = NodeAnnouncement {
+        node_info.metadata = self.metadata_visibility.public_metadata(&node_info.metadata);
+        let mut announcement = NodeAnnouncement {
             node_info,
             announcement_type,
             timestamp: Utc::now(),
+            public_key: None,
+            signature: None,
         };
+        if let Some(keys) = &self.signing_key {
+            announcement.sign(keys)?;
+        }
         
         let payload = serde_json::to_vec(&announcement)
             .map_err(|e| DiscoveryError::SerializationError(e.to_string()))?;
@@ -509,28 +1191,152 @@ impl NodeDiscovery {
         Ok(())
     }
     
-    /// Start periodic announcement task
-    async fn start_announcement_task(&self) {
-        let zenoh_session = Arc::clone(&self.zenoh_session);
+    /// Register this node as a DNS-SD service instance
+    async fn register_dns_sd(
+        &self,
+        mut node_info: NodeInfo,
+        service_type: &str,
+        domain: &str,
+    ) -> Result<(), DiscoveryError> {
+        let client = self.dns_sd_client.get().ok_or(DiscoveryError::NotActive)?;
+        node_info.metadata = self.metadata_visibility.public_metadata(&node_info.metadata);
+        client.register(node_service(&node_info, service_type, domain)).await
+    }
+    
+    /// Start periodic DNS-SD browsing, feeding peers into the registry
+    async fn start_dns_sd_browse_task(&self, service_type: String, domain: String) {
+        let client = match self.dns_sd_client.get() {
+            Some(client) => Arc::clone(client),
+            None => return,
+        };
+        let node_registry = Arc::clone(&self.node_registry);
+        let declared_intervals = Arc::clone(&self.declared_intervals);
+        let eviction_counters = Arc::clone(&self.eviction_counters);
+        let query_cache = Arc::clone(&self.query_cache);
+        let known_public_keys = Arc::clone(&self.known_public_keys);
         let is_active = Arc::clone(&self.is_active);
-        let interval = self.config.announcement_interval;
-        let node_id = self.node_id;
+        let config = self.config.clone();
+        let own_id = self.node_id;
         
         tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(
-                tokio::time::Duration::from_secs(interval)
+                tokio::time::Duration::from_secs(config.announcement_interval)
             );
             
             while *is_active.read().await {
                 interval_timer.tick().await;
                 
                 if *is_active.read().await {
-                    // Send heartbeat
-                    let _ = zenoh_session.broadcast_message(
-                        MessageType::Heartbeat,
-                        Vec::new(),
-                    ).await;
+                    match client.browse(&service_type, &domain).await {
+                        Ok(services) => {
+                            let result = Self::handle_dns_sd_services(
+                                services,
+                                own_id,
+                                Arc::clone(&node_registry),
+                                &eviction_counters,
+                                &query_cache,
+                                &known_public_keys,
+                                config.clone(),
+                            ).await;
+                            match result {
+                                Ok(evicted) if !evicted.is_empty() => {
+                                    let mut intervals = declared_intervals.write().await;
+                                    for node_id in evicted {
+                                        intervals.remove(&node_id);
+                                    }
+                                }
+                                Ok(_) => {}
+                                Err(e) => eprintln!("Error handling DNS-SD browse results: {}", e),
+                            }
+                        }
+                        Err(e) => eprintln!("DNS-SD browse failed: {}", e),
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Feed DNS-SD browse results into the registry as heartbeat announcements
+    ///
+    /// Instances whose TXT records do not describe a node are skipped, as is
+    /// this node's own registration. The records are unsigned, so they are
+    /// refused for nodes with a pinned key and when signatures are required.
+    /// Returns the nodes evicted to make room.
+    async fn handle_dns_sd_services(
+        services: Vec<DnsSdService>,
+        own_id: Uuid,
+        node_registry: Arc<RwLock<HashMap<Uuid, NodeInfo>>>,
+        eviction_counters: &EvictionCounters,
+        query_cache: &NodeQueryCache,
+        known_public_keys: &RwLock<TrustedKeys>,
+        config: DiscoveryConfig,
+    ) -> Result<Vec<Uuid>, DiscoveryError> {
+        let now = Utc::now();
+        let mut evicted = Vec::new();
+        for service in services {
+            let node_info = match decode_txt_records(&service.txt, now) {
+                Ok(node_info) if node_info.node_id != own_id => node_info,
+                Ok(_) => continue,
+                Err(_) => {
+                    if config.debug {
+                        println!("Ignoring DNS-SD instance {} without node information", service.instance_name);
+                    }
+                    continue;
                 }
+            };
+            let announcement = NodeAnnouncement {
+                node_info,
+                announcement_type: AnnouncementType::Heartbeat,
+                timestamp: now,
+                public_key: None,
+                signature: None,
+            };
+            if !Self::is_announcement_trusted(&announcement, known_public_keys, &config).await {
+                continue;
+            }
+            evicted.extend(Self::handle_node_announcement(
+                announcement,
+                Arc::clone(&node_registry),
+                eviction_counters,
+                query_cache,
+                config.clone(),
+            ).await?);
+        }
+        Ok(evicted)
+    }
+    
+    /// Start periodic announcement task
+    ///
+    /// Each heartbeat declares the adaptive interval until the next one.
+    async fn start_announcement_task(&self) {
+        let own_id = self.node_id;
+        let zenoh_session = Arc::clone(&self.zenoh_session);
+        let is_active = Arc::clone(&self.is_active);
+        let node_registry = Arc::clone(&self.node_registry);
+        let declared_intervals = Arc::clone(&self.declared_intervals);
+        let heartbeat = Arc::clone(&self.heartbeat);
+        let config = self.config.clone();
+        let clock_skew = Arc::clone(&self.clock_skew);
+        
+        tokio::spawn(async move {
+            while *is_active.read().await {
+                let now = Utc::now();
+                Self::observe_online_nodes(own_id, &node_registry, &declared_intervals, &heartbeat, &config, now).await;
+                let jitter_seed = (Uuid::new_v4().as_u128() % 10_000) as f64 / 10_000.0;
+                let next = heartbeat.write().await.next_interval(now, jitter_seed);
+                
+                // Send heartbeat declaring our interval
+                let payload = serde_json::to_vec(&HeartbeatPayload {
+                    interval_secs: next.as_secs().max(1),
+                    sent_at: Some(now),
+                    echoes: clock_skew.read().await.echoes(),
+                }).unwrap_or_default();
+                let _ = zenoh_session.broadcast_message(
+                    MessageType::Heartbeat,
+                    payload,
+                ).await;
+                
+                tokio::time::sleep(next).await;
             }
         });
     }
@@ -538,8 +1344,10 @@ impl NodeDiscovery {
     /// Start cleanup task for inactive nodes
     async fn start_cleanup_task(&self) {
         let node_registry = Arc::clone(&self.node_registry);
+        let query_cache = Arc::clone(&self.query_cache);
+        let declared_intervals = Arc::clone(&self.declared_intervals);
         let is_active = Arc::clone(&self.is_active);
-        let timeout = self.config.node_timeout;
+        let config = self.config.clone();
         let debug = self.config.debug;
         
         tokio::spawn(async move {
@@ -552,11 +1360,13 @@ impl NodeDiscovery {
                 
                 if *is_active.read().await {
                     let mut registry = node_registry.write().await;
+                    let mut intervals = declared_intervals.write().await;
                     let now = Utc::now();
                     let mut to_remove = Vec::new();
                     
                     for (node_id, node_info) in registry.iter_mut() {
                         let seconds_since_seen = (now - node_info.last_seen).num_seconds();
+                        let timeout = config.liveness_timeout(intervals.get(node_id).copied());
                         
                         if seconds_since_seen > timeout as i64 {
                             if node_info.is_online {
@@ -573,12 +1383,18 @@ impl NodeDiscovery {
                         }
                     }
                     
-                    for node_id in to_remove {
-                        registry.remove(&node_id);
+                    for node_id in &to_remove {
+                        registry.remove(node_id);
+                        intervals.remove(node_id);
                         if debug {
                             println!("Node {} removed from registry", node_id);
                         }
                     }
+                    if !to_remove.is_empty() {
+                        query_cache.invalidate_where(|_, nodes| {
+                            nodes.iter().any(|node| to_remove.contains(&node.node_id))
+                        });
+                    }
                 }
             }
         });
@@ -588,47 +1404,13 @@ impl NodeDiscovery {
     async fn get_own_node_info(&self) -> Option<NodeInfo> {
         self.node_registry.read().await.get(&self.node_id).cloned()
     }
-    
-    /// Check if a node matches the given filter
-    fn matches_filter(&self, node: &NodeInfo, filter: &NodeFilter) -> bool {
-        // Check context ID
-        if let Some(ref context_id) = filter.context_id {
-            if &node.context_id != context_id {
-                return false;
-            }
-        }
-        
-        // Check online status
-        if filter.online_only && !node.is_online {
-            return false;
-        }
-        
-        // Check required capabilities
-        for required_cap in &filter.required_capabilities {
-            if !node.capabilities.contains(required_cap) {
-                return false;
-            }
-        }
-        
-        // Check name pattern
-        if let Some(ref pattern) = filter.name_pattern {
-            if !node.display_name.to_lowercase().contains(&pattern.to_lowercase()) {
-                return false;
-            }
-        }
-        
-        // Check metadata filters
-        for (key, value) in &filter.metadata_filters {
-            if let Some(node_value) = node.metadata.get(key) {
-                if node_value != value {
-                    return false;
-                }
-            } else {
-                return false;
-            }
-        }
-        
-        true
+}
+
+#[async_trait::async_trait]
+impl MemoryFootprint for NodeDiscovery {
+    async fn memory_footprint(&self) -> ComponentFootprint {
+        let nodes = self.node_registry.read().await.len();
+        ComponentFootprint::of::<NodeInfo>("discovered_nodes", nodes)
     }
 }
 
@@ -764,19 +1546,12 @@ mod tests {
             metadata_filters: HashMap::new(),
         };
         
-        let discovery = NodeDiscovery {
-            node_id: Uuid::new_v4(),
-            zenoh_session: Arc::new(unsafe { std::mem::zeroed() }), // Mock for test
-            node_registry: Arc::new(RwLock::new(HashMap::new())),
-            config: DiscoveryConfig::default(),
-            is_active: Arc::new(RwLock::new(false)),
-        };
-        
-        assert!(discovery.matches_filter(&node_info, &context_filter));
+       
+        assert!(context_filter.matches(&node_info));
         
         // Test capability filter
         let capability_filter = capability_filter(vec![NodeCapability::ResourceStorage]);
-        assert!(discovery.matches_filter(&node_info, &capability_filter));
+        assert!(capability_filter.matches(&node_info));
         
         // Test name pattern filter
         let name_filter = NodeFilter {
@@ -787,7 +1562,7 @@ mod tests {
             metadata_filters: HashMap::new(),
         };
         
-        assert!(discovery.matches_filter(&node_info, &name_filter));
+        assert!(name_filter.matches(&node_info));
     }
     
     #[test]
@@ -864,4 +1639,234 @@ mod tests {
         assert_eq!(cap_filter.required_capabilities.len(), 1);
         assert!(cap_filter.online_only);
     }
+    
+    #[test]
+    fn test_liveness_timeout_tracks_declared_interval() {
+        let config = DiscoveryConfig::default();
+        
+        assert_eq!(config.liveness_timeout(None), config.node_timeout);
+        assert_eq!(config.liveness_timeout(Some(0)), config.node_timeout);
+        assert_eq!(config.liveness_timeout(Some(5)), 15);
+        assert_eq!(config.liveness_timeout(Some(120)), 360);
+        
+        let payload = serde_json::to_vec(&HeartbeatPayload { interval_secs: 42, sent_at: None, echoes: HashMap::new() }).unwrap();
+        let decoded: HeartbeatPayload = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(config.liveness_timeout(Some(decoded.interval_secs)), 126);
+    }
+
+    #[tokio::test]
+    async fn test_announcement_interval_follows_joins_and_leaves() {
+        let config = DiscoveryConfig::default();
+        let registry = Arc::new(RwLock::new(HashMap::new()));
+        let intervals = RwLock::new(HashMap::new());
+        let counters = EvictionCounters::new();
+        let cache = NodeQueryCache::new(QueryCacheConfig::default());
+        let heartbeat = RwLock::new(AdaptiveHeartbeat::new(config.heartbeat_config()));
+        let own_id = Uuid::new_v4();
+        let observe = |now| NodeDiscovery::observe_online_nodes(own_id, &registry, &intervals, &heartbeat, &config, now);
+    
+        // Alone, the node announces at the configured interval
+        let now = Utc::now();
+        observe(now).await;
+        assert_eq!(heartbeat.write().await.base_interval_secs(now), config.announcement_interval);
+    
+        // Joining nodes shorten it
+        for i in 0..3 {
+            let node = create_basic_node_info(Uuid::new_v4(), format!("Node {}", i), "ctx".to_string());
+            intervals.write().await.insert(node.node_id, 10);
+            NodeDiscovery::handle_node_announcement(join(node), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        }
+        observe(now).await;
+        assert_eq!(heartbeat.write().await.recent_churn(now), 3);
+        assert!(heartbeat.write().await.base_interval_secs(now) < config.announcement_interval);
+    
+        // Nodes silent past their declared interval count as leaving
+        let later = now + chrono::Duration::seconds(config.heartbeat_config().churn_window_secs as i64 + 1);
+        observe(later).await;
+        assert_eq!(heartbeat.write().await.recent_churn(later), 3);
+        let interval = heartbeat.write().await.base_interval_secs(later);
+        assert!(interval < config.announcement_interval);
+        assert!(interval >= config.heartbeat_config().min_interval_secs);
+    }
+    
+    fn join(node_info: NodeInfo) -> NodeAnnouncement {
+        NodeAnnouncement {
+            node_info,
+            announcement_type: AnnouncementType::Join,
+            timestamp: Utc::now(),
+            public_key: None,
+            signature: None,
+        }
+    }
+    
+    #[test]
+    fn test_signed_announcements_pin_keys_on_first_use() {
+        let (owner, forger) = (NodeKeys::generate().unwrap(), NodeKeys::generate().unwrap());
+        let node = create_basic_node_info(Uuid::new_v4(), "Signed".to_string(), "ctx".to_string());
+        
+        let mut signed = join(node.clone());
+        signed.sign(&owner).unwrap();
+        assert!(NodeDiscovery::verify_announcement(&signed, owner.public_key()));
+        assert!(!NodeDiscovery::verify_announcement(&signed, forger.public_key()));
+        
+        let mut tampered = signed.clone();
+        tampered.node_info.display_name = "Impostor".to_string();
+        assert!(!NodeDiscovery::verify_announcement(&tampered, owner.public_key()));
+        
+        // Unsigned announcements pass only while signatures are optional
+        let mut keys = TrustedKeys::default();
+        assert_eq!(check_announcement_trust(&join(node.clone()), &mut keys, false), AnnouncementTrust::Unsigned);
+        assert!(!check_announcement_trust(&join(node.clone()), &mut keys, true).is_accepted());
+        
+        // The first signed announcement pins the key
+        assert_eq!(check_announcement_trust(&signed, &mut keys, true), AnnouncementTrust::PinnedOnFirstUse);
+        assert_eq!(keys.keys[&node.node_id].public_key, owner.public_key());
+        assert_eq!(check_announcement_trust(&signed, &mut keys, true), AnnouncementTrust::Verified);
+        
+        // Afterwards neither unsigned nor differently signed announcements are accepted
+        let mut forged = join(node.clone());
+        forged.sign(&forger).unwrap();
+        assert!(!check_announcement_trust(&forged, &mut keys, false).is_accepted());
+        assert!(!check_announcement_trust(&join(node), &mut keys, false).is_accepted());
+    }
+    
+    #[tokio::test]
+    async fn test_constrained_registry_evicts_least_recently_seen() {
+        let config = DiscoveryConfig {
+            max_nodes: Some(2),
+            ..DiscoveryConfig::for_profile(ResourceProfile::Constrained)
+        };
+        assert_eq!(
+            DiscoveryConfig::for_profile(ResourceProfile::Constrained).max_nodes,
+            ResourceProfile::Constrained.limits().max_discovered_nodes
+        );
+        
+        let registry = Arc::new(RwLock::new(HashMap::new()));
+        let counters = EvictionCounters::new();
+        let cache = NodeQueryCache::new(QueryCacheConfig::default());
+        let stale = create_basic_node_info(Uuid::new_v4(), "Stale".to_string(), "ctx".to_string());
+        let fresh = create_basic_node_info(Uuid::new_v4(), "Fresh".to_string(), "ctx".to_string());
+        let newcomer = create_basic_node_info(Uuid::new_v4(), "New".to_string(), "ctx".to_string());
+        let (stale_id, fresh_id, newcomer_id) = (stale.node_id, fresh.node_id, newcomer.node_id);
+        
+        NodeDiscovery::handle_node_announcement(join(stale), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        NodeDiscovery::handle_node_announcement(join(fresh), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        registry.write().await.get_mut(&stale_id).unwrap().last_seen = Utc::now() - chrono::Duration::minutes(10);
+        
+        let evicted = NodeDiscovery::handle_node_announcement(join(newcomer), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        
+        assert_eq!(evicted, vec![stale_id]);
+        let registry = registry.read().await;
+        assert_eq!(registry.len(), 2);
+        assert!(registry.contains_key(&fresh_id));
+        assert!(registry.contains_key(&newcomer_id));
+        assert_eq!(counters.get(EvictionKind::DiscoveredNode), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_standard_registry_is_unbounded() {
+        let config = DiscoveryConfig::for_profile(ResourceProfile::Standard);
+        assert_eq!(config.max_nodes, None);
+        
+        let registry = Arc::new(RwLock::new(HashMap::new()));
+        let counters = EvictionCounters::new();
+        let cache = NodeQueryCache::new(QueryCacheConfig::default());
+        for i in 0..100 {
+            let node = create_basic_node_info(Uuid::new_v4(), format!("Node {}", i), "ctx".to_string());
+            NodeDiscovery::handle_node_announcement(join(node), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        }
+        
+        assert_eq!(registry.read().await.len(), 100);
+        assert_eq!(counters.total(), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_hybrid_backends_share_one_registry() {
+        let hybrid = DiscoveryBackend::Hybrid(vec![
+            DiscoveryBackend::Zenoh,
+            DiscoveryBackend::Hybrid(vec![DiscoveryBackend::dns_sd("mesh.example.com."), DiscoveryBackend::Zenoh]),
+        ]);
+        assert_eq!(hybrid.leaves(), vec![DiscoveryBackend::Zenoh, DiscoveryBackend::dns_sd("mesh.example.com.")]);
+        assert_eq!(DiscoveryConfig::dns_sd("local.").backend, DiscoveryBackend::dns_sd("local."));
+        
+        let config = DiscoveryConfig { backend: hybrid, ..DiscoveryConfig::default() };
+        let registry = Arc::new(RwLock::new(HashMap::new()));
+        let counters = EvictionCounters::new();
+        let cache = NodeQueryCache::new(QueryCacheConfig::default());
+        let own = create_basic_node_info(Uuid::new_v4(), "Self".to_string(), "ctx".to_string());
+        let mut peer = create_basic_node_info(Uuid::new_v4(), "Peer".to_string(), "ctx".to_string());
+        peer.endpoints = vec!["tcp/10.0.0.2:7447".to_string()];
+        
+        // The peer is seen over Zenoh and DNS-SD; our own registration is ignored
+        NodeDiscovery::handle_node_announcement(join(peer.clone()), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        let mut renamed = peer.clone();
+        renamed.display_name = "Peer (dns-sd)".to_string();
+        let services = vec![
+            node_service(&renamed, DNS_SD_SERVICE_TYPE, "mesh.example.com."),
+            node_service(&own, DNS_SD_SERVICE_TYPE, "mesh.example.com."),
+            DnsSdService {
+                instance_name: "printer".to_string(),
+                service_type: DNS_SD_SERVICE_TYPE.to_string(),
+                domain: "mesh.example.com.".to_string(),
+                port: 631,
+                txt: vec!["txtvers=1".to_string()],
+            },
+        ];
+        let keys = RwLock::new(TrustedKeys::default());
+        let browse = |services| NodeDiscovery::handle_dns_sd_services(
+            services, own.node_id, Arc::clone(&registry), &counters, &cache, &keys, config.clone(),
+        );
+        browse(services).await.unwrap();
+        
+        {
+            let registry = registry.read().await;
+            assert_eq!(registry.len(), 1);
+            assert_eq!(registry[&peer.node_id].display_name, "Peer (dns-sd)");
+            assert_eq!(registry[&peer.node_id].endpoints, peer.endpoints);
+        }
+        
+        // Once the peer's key is pinned, unsigned DNS-SD records cannot overwrite it
+        keys.write().await.keys.insert(peer.node_id, TrustedKey { public_key: NodeKeys::generate().unwrap().public_key().to_vec(), last_sequence: None });
+        let mut forged = peer.clone();
+        forged.endpoints = vec!["tcp/6.6.6.6:7447".to_string()];
+        browse(vec![node_service(&forged, DNS_SD_SERVICE_TYPE, "mesh.example.com.")]).await.unwrap();
+        assert_eq!(registry.read().await[&peer.node_id].endpoints, peer.endpoints);
+    }
+    
+    #[tokio::test]
+    async fn test_announcements_invalidate_cached_queries() {
+        let config = DiscoveryConfig::default();
+        let registry = Arc::new(RwLock::new(HashMap::new()));
+        let counters = EvictionCounters::new();
+        let cache = NodeQueryCache::new(QueryCacheConfig::default());
+        let storage = create_basic_node_info(Uuid::new_v4(), "Storage".to_string(), "ctx".to_string());
+        NodeDiscovery::handle_node_announcement(join(storage.clone()), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        
+        let fetch_from = |registry: Arc<RwLock<HashMap<Uuid, NodeInfo>>>| move |filter: NodeFilter| {
+            let registry = Arc::clone(&registry);
+            async move {
+                let registry = registry.read().await;
+                Ok::<_, DiscoveryError>(registry.values().filter(|node| filter.matches(node)).cloned().collect::<Vec<_>>())
+            }
+        };
+        let everyone = online_nodes_filter();
+        let collaborators = capability_filter(vec![NodeCapability::Collaboration]);
+        cache.get_or_fetch(&everyone, false, fetch_from(Arc::clone(&registry))).await.unwrap();
+        cache.get_or_fetch(&collaborators, false, fetch_from(Arc::clone(&registry))).await.unwrap();
+        assert_eq!(cache.len(), 2);
+        
+        // A joining node only invalidates queries that would select it
+        let mut builder = create_basic_node_info(Uuid::new_v4(), "Builder".to_string(), "ctx".to_string());
+        builder.capabilities = vec![NodeCapability::GitIntegration];
+        NodeDiscovery::handle_node_announcement(join(builder), Arc::clone(&registry), &counters, &cache, config.clone()).await.unwrap();
+        assert_eq!(cache.len(), 1);
+        
+        // A leaving node invalidates queries whose results contained it
+        let collaborators_found = cache.get_or_fetch(&collaborators, false, fetch_from(Arc::clone(&registry))).await.unwrap();
+        assert_eq!(collaborators_found.len(), 1);
+        let leave = NodeAnnouncement { announcement_type: AnnouncementType::Leave, ..join(storage) };
+        NodeDiscovery::handle_node_announcement(leave, Arc::clone(&registry), &counters, &cache, config).await.unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().invalidations, 2);
+    }
 }
@@ -0,0 +1,248 @@
+//! Log-safe summaries of message payloads
+//!
+//! Payloads may carry classified or regulated content, so they are never
+//! logged directly. A [`PayloadSummary`] records the size, a content-type
+//! hint and a SHA-256 prefix for correlating log lines. A short preview is
+//! included only when a [`ContentClassifier`] rates the payload as
+//! [`CoreClassification::Public`]; unclassified payloads are treated as
+//! sensitive.
+
+use std::fmt;
+use std::sync::Arc;
+
+use ring::digest;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::compute::{ComputeCategory, ComputeError, ComputePool};
+use crate::ide::security::CoreClassification;
+
+/// Hex characters of the SHA-256 digest kept for correlation
+const HASH_PREFIX_LEN: usize = 16;
+
+/// Rates the sensitivity of payloads
+pub trait ContentClassifier: Send + Sync {
+    /// Classify a payload, given a hint of its content type
+    fn classify(&self, payload: &[u8], content_type: &str) -> CoreClassification;
+}
+
+/// Controls what payload summaries reveal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadSummaryConfig {
+    /// Whether Public payloads get a preview
+    pub preview_enabled: bool,
+    /// Maximum preview length in characters
+    pub preview_len: usize,
+}
+
+impl Default for PayloadSummaryConfig {
+    fn default() -> Self {
+        Self {
+            preview_enabled: true,
+            preview_len: 32,
+        }
+    }
+}
+
+/// What may be logged about a payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayloadSummary {
+    /// Payload size in bytes
+    pub size: usize,
+    /// Content type guessed from the bytes
+    pub content_type: &'static str,
+    /// Classification, if a classifier was available
+    pub classification: Option<CoreClassification>,
+    /// Truncated preview, only for Public payloads
+    pub preview: Option<String>,
+    /// SHA-256 prefix for correlating log lines
+    pub hash: String,
+}
+
+impl PayloadSummary {
+    /// Summarize a payload without a classifier; no preview is included
+    pub fn new(payload: &[u8]) -> Self {
+        Self::with_classifier(payload, None, &PayloadSummaryConfig::default())
+    }
+
+    /// Summarize a payload, previewing it only if `classifier` rates it Public
+    pub fn with_classifier(
+        payload: &[u8],
+        classifier: Option<&dyn ContentClassifier>,
+        config: &PayloadSummaryConfig,
+    ) -> Self {
+        let content_type = content_type_hint(payload);
+        let classification = classifier.map(|c| c.classify(payload, content_type));
+        let preview = match classification {
+            Some(CoreClassification::Public) if config.preview_enabled => {
+                Some(safe_preview(payload, config.preview_len))
+            }
+            _ => None,
+        };
+        let hash = digest::digest(&digest::SHA256, payload)
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()[..HASH_PREFIX_LEN]
+            .to_string();
+
+        Self {
+            size: payload.len(),
+            content_type,
+            classification,
+            preview,
+            hash,
+        }
+    }
+
+    /// [`with_classifier`](Self::with_classifier) run on a compute pool
+    ///
+    /// Classifiers may be expensive, so large or frequent payloads should be
+    /// summarized here rather than on the runtime handling messages.
+    pub async fn summarize_on(
+        pool: &ComputePool,
+        cancel: &CancellationToken,
+        payload: Vec<u8>,
+        classifier: Option<Arc<dyn ContentClassifier>>,
+        config: PayloadSummaryConfig,
+    ) -> Result<Self, ComputeError> {
+        pool.run(ComputeCategory::Classification, cancel, move || {
+            Self::with_classifier(&payload, classifier.as_deref(), &config)
+        })
+        .await
+    }
+}
+
+/// A classifier and the compute pool it runs on
+///
+/// Without a pool the classifier runs on the calling task.
+#[derive(Clone)]
+pub struct PayloadSummarizer {
+    classifier: Arc<dyn ContentClassifier>,
+    pool: Option<ComputePool>,
+}
+
+impl PayloadSummarizer {
+    /// Summarize with `classifier` on the calling task
+    pub fn new(classifier: Arc<dyn ContentClassifier>) -> Self {
+        Self { classifier, pool: None }
+    }
+
+    /// Run classification on `pool`
+    pub fn with_compute_pool(mut self, pool: ComputePool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Summarize a payload, falling back to an unclassified summary if the pool fails
+    pub async fn summarize(&self, payload: &[u8], config: &PayloadSummaryConfig) -> PayloadSummary {
+        let Some(pool) = &self.pool else {
+            return PayloadSummary::with_classifier(payload, Some(self.classifier.as_ref()), config);
+        };
+        let summary = PayloadSummary::summarize_on(
+            pool,
+            &CancellationToken::new(),
+            payload.to_vec(),
+            Some(self.classifier.clone()),
+            config.clone(),
+        )
+        .await;
+        summary.unwrap_or_else(|e| {
+            tracing::warn!("payload classification failed: {}", e);
+            PayloadSummary::with_classifier(payload, None, config)
+        })
+    }
+}
+
+impl fmt::Display for PayloadSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes {} sha256:{}", self.size, self.content_type, self.hash)?;
+        if let Some(classification) = &self.classification {
+            write!(f, " {:?}", classification)?;
+        }
+        if let Some(preview) = &self.preview {
+            write!(f, " preview={:?}", preview)?;
+        }
+        Ok(())
+    }
+}
+
+/// Guess the content type of a payload from its bytes
+fn content_type_hint(payload: &[u8]) -> &'static str {
+    if payload.is_empty() {
+        return "empty";
+    }
+    match std::str::from_utf8(payload) {
+        Ok(text) if serde_json::from_str::<serde_json::Value>(text).is_ok() => "application/json",
+        Ok(_) => "text/plain",
+        Err(_) => "application/octet-stream",
+    }
+}
+
+/// First `max_chars` characters of a payload with control characters escaped
+fn safe_preview(payload: &[u8], max_chars: usize) -> String {
+    let text = String::from_utf8_lossy(payload);
+    let mut preview: String = text
+        .chars()
+        .take(max_chars)
+        .flat_map(|c| if c.is_control() { c.escape_default().collect::<Vec<_>>() } else { vec![c] })
+        .collect();
+    if text.chars().count() > max_chars {
+        preview.push('…');
+    }
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rates anything mentioning "SECRET" as restricted
+    struct KeywordClassifier;
+
+    impl ContentClassifier for KeywordClassifier {
+        fn classify(&self, payload: &[u8], _content_type: &str) -> CoreClassification {
+            if String::from_utf8_lossy(payload).contains("SECRET") {
+                CoreClassification::Restricted
+            } else {
+                CoreClassification::Public
+            }
+        }
+    }
+
+    #[test]
+    fn test_classified_payload_is_never_rendered() {
+        let payload = b"SECRET launch codes 0000";
+        let summary = PayloadSummary::with_classifier(
+            payload,
+            Some(&KeywordClassifier),
+            &PayloadSummaryConfig::default(),
+        );
+        let rendered = format!("{} {:?}", summary, summary);
+
+        assert!(!rendered.contains("SECRET"));
+        assert!(!rendered.contains("launch"));
+        assert!(rendered.contains(&summary.hash));
+        assert_eq!(summary.hash.len(), HASH_PREFIX_LEN);
+        assert_eq!(summary.classification, Some(CoreClassification::Restricted));
+
+        // Without a classifier nothing is previewed either
+        let unclassified = PayloadSummary::new(payload);
+        assert!(!unclassified.to_string().contains("SECRET"));
+        assert_eq!(unclassified.hash, summary.hash);
+    }
+
+    #[test]
+    fn test_open_payload_shows_truncated_preview() {
+        let config = PayloadSummaryConfig { preview_enabled: true, preview_len: 5 };
+        let summary = PayloadSummary::with_classifier(b"hello\nworld", Some(&KeywordClassifier), &config);
+
+        assert_eq!(summary.content_type, "text/plain");
+        assert_eq!(summary.preview.as_deref(), Some("hello…"));
+        assert!(summary.to_string().contains("preview=\"hello…\""));
+
+        let disabled = PayloadSummaryConfig { preview_enabled: false, ..config };
+        let summary = PayloadSummary::with_classifier(b"hello", Some(&KeywordClassifier), &disabled);
+        assert_eq!(summary.preview, None);
+    }
+}
@@ -0,0 +1,356 @@
+//! Cache for repeated mesh-wide queries
+//!
+//! Dashboards and routing logic repeat the same scatter-gather queries every
+//! few seconds. [`QueryCache`] answers from results younger than the TTL,
+//! answers from older results within the stale window while refreshing them
+//! in the background, and drops entries as soon as an event makes them wrong.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+/// A query whose results can be cached
+pub trait CachedQuery: Clone + Send + Sync + 'static {
+    /// What the query selects, such as a node or a resource
+    type Item;
+
+    /// Key equal for every query with the same meaning
+    fn cache_key(&self) -> String;
+
+    /// Whether `item` is selected by this query
+    fn matches(&self, item: &Self::Item) -> bool;
+}
+
+/// How long cached results are served
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryCacheConfig {
+    /// Age below which results are served without refreshing
+    pub ttl: Duration,
+    /// Further age during which results are served while a refresh runs
+    pub stale_while_revalidate: Duration,
+    /// Most queries cached; the least recently fetched go first
+    pub max_entries: usize,
+}
+
+impl Default for QueryCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(5),
+            stale_while_revalidate: Duration::from_secs(30),
+            max_entries: 256,
+        }
+    }
+}
+
+/// Cache behaviour so far
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryCacheStats {
+    /// Served from results within the TTL
+    pub hits: u64,
+    /// Fetched because nothing usable was cached
+    pub misses: u64,
+    /// Served from expired results while refreshing them
+    pub stale_served: u64,
+    /// Fetched because the caller asked for fresh results
+    pub bypassed: u64,
+    /// Background refreshes completed
+    pub refreshes: u64,
+    /// Entries dropped by events
+    pub invalidations: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stale_served: AtomicU64,
+    bypassed: AtomicU64,
+    refreshes: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+struct Entry<Q, V> {
+    query: Q,
+    value: V,
+    fetched_at: Instant,
+    refreshing: bool,
+}
+
+enum Lookup<V> {
+    Fresh(V),
+    Stale { value: V, refresh: bool },
+    Missing,
+}
+
+type Entries<Q, V> = Arc<Mutex<HashMap<String, Entry<Q, V>>>>;
+
+/// Results of recent queries, keyed by normalized query
+pub struct QueryCache<Q: CachedQuery, V> {
+    config: QueryCacheConfig,
+    entries: Entries<Q, V>,
+    counters: Arc<Counters>,
+}
+
+impl<Q: CachedQuery, V: Clone + Send + 'static> QueryCache<Q, V> {
+    pub fn new(config: QueryCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Results of `query`, from the cache when possible
+    ///
+    /// With `fresh` the cache is bypassed and `fetch` always runs; its
+    /// result still replaces the cached one. Stale results start a
+    /// background refresh through `fetch`.
+    pub async fn get_or_fetch<F, Fut, E>(&self, query: &Q, fresh: bool, fetch: F) -> Result<V, E>
+    where
+        F: Fn(Q) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<V, E>> + Send + 'static,
+    {
+        let key = query.cache_key();
+        if fresh {
+            self.counters.bypassed.fetch_add(1, Ordering::Relaxed);
+        } else {
+            match self.lookup(&key) {
+                Lookup::Fresh(value) => {
+                    self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(value);
+                }
+                Lookup::Stale { value, refresh } => {
+                    self.counters.stale_served.fetch_add(1, Ordering::Relaxed);
+                    if refresh {
+                        self.spawn_refresh(key, query.clone(), fetch);
+                    }
+                    return Ok(value);
+                }
+                Lookup::Missing => {
+                    self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        let value = fetch(query.clone()).await?;
+        Self::store(&self.entries, self.config.max_entries, key, query.clone(), value.clone());
+        Ok(value)
+    }
+
+    /// Classify the cached entry for `key`, claiming its refresh when stale
+    fn lookup(&self, key: &str) -> Lookup<V> {
+        let mut entries = self.entries.lock().unwrap();
+        let Some(entry) = entries.get_mut(key) else {
+            return Lookup::Missing;
+        };
+        let age = entry.fetched_at.elapsed();
+        if age < self.config.ttl {
+            Lookup::Fresh(entry.value.clone())
+        } else if age < self.config.ttl + self.config.stale_while_revalidate {
+            let refresh = !entry.refreshing;
+            entry.refreshing = true;
+            Lookup::Stale { value: entry.value.clone(), refresh }
+        } else {
+            Lookup::Missing
+        }
+    }
+
+    fn spawn_refresh<F, Fut, E>(&self, key: String, query: Q, fetch: F)
+    where
+        F: Fn(Q) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<V, E>> + Send + 'static,
+    {
+        let entries = Arc::clone(&self.entries);
+        let counters = Arc::clone(&self.counters);
+        let max_entries = self.config.max_entries;
+        tokio::spawn(async move {
+            let result = fetch(query.clone()).await;
+            let mut guard = entries.lock().unwrap();
+            // An entry invalidated during the refresh stays invalidated
+            let Some(entry) = guard.get_mut(&key) else {
+                return;
+            };
+            entry.refreshing = false;
+            if let Ok(value) = result {
+                drop(guard);
+                Self::store(&entries, max_entries, key, query, value);
+                counters.refreshes.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    }
+
+    fn store(entries: &Entries<Q, V>, max_entries: usize, key: String, query: Q, value: V) {
+        let mut entries = entries.lock().unwrap();
+        entries.insert(key, Entry { query, value, fetched_at: Instant::now(), refreshing: false });
+        while entries.len() > max_entries {
+            let oldest = entries.iter()
+                .min_by_key(|(_, entry)| entry.fetched_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+
+    /// Drop cached queries that select `item`
+    pub fn invalidate_matching(&self, item: &Q::Item) -> usize {
+        self.invalidate_where(|query, _| query.matches(item))
+    }
+
+    /// Drop cached queries for which `predicate` holds on the query and its results
+    pub fn invalidate_where(&self, predicate: impl Fn(&Q, &V) -> bool) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| !predicate(&entry.query, &entry.value));
+        let dropped = before - entries.len();
+        self.counters.invalidations.fetch_add(dropped as u64, Ordering::Relaxed);
+        dropped
+    }
+
+    /// Drop every cached query
+    pub fn clear(&self) {
+        let dropped = {
+            let mut entries = self.entries.lock().unwrap();
+            let dropped = entries.len();
+            entries.clear();
+            dropped
+        };
+        self.counters.invalidations.fetch_add(dropped as u64, Ordering::Relaxed);
+    }
+
+    /// Number of cached queries
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Counters of cache behaviour
+    pub fn stats(&self) -> QueryCacheStats {
+        QueryCacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            stale_served: self.counters.stale_served.load(Ordering::Relaxed),
+            bypassed: self.counters.bypassed.load(Ordering::Relaxed),
+            refreshes: self.counters.refreshes.load(Ordering::Relaxed),
+            invalidations: self.counters.invalidations.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    #[derive(Clone)]
+    struct Tagged(String);
+
+    impl CachedQuery for Tagged {
+        type Item = String;
+
+        fn cache_key(&self) -> String {
+            self.0.clone()
+        }
+
+        fn matches(&self, item: &String) -> bool {
+            item == &self.0
+        }
+    }
+
+    /// Fetch returning `tag-N` on the Nth call
+    fn counting_fetch(calls: Arc<AtomicUsize>) -> impl Fn(Tagged) -> std::future::Ready<Result<String, ()>> + Send + Sync + 'static {
+        move |query: Tagged| {
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            std::future::ready(Ok(format!("{}-{}", query.0, n)))
+        }
+    }
+
+    fn cache(ttl_ms: u64, stale_ms: u64) -> QueryCache<Tagged, String> {
+        QueryCache::new(QueryCacheConfig {
+            ttl: Duration::from_millis(ttl_ms),
+            stale_while_revalidate: Duration::from_millis(stale_ms),
+            max_entries: 8,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_hits_within_ttl_and_bypass() {
+        let cache = cache(60_000, 0);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let gpu = Tagged("gpu".to_string());
+
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-1".to_string()));
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-1".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Asking for fresh results always fetches, and the result is cached
+        assert_eq!(cache.get_or_fetch(&gpu, true, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-2".to_string()));
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-2".to_string()));
+
+        assert_eq!(cache.stats(), QueryCacheStats { hits: 2, misses: 1, bypassed: 1, ..Default::default() });
+    }
+
+    #[tokio::test]
+    async fn test_stale_results_refresh_in_background() {
+        let cache = cache(20, 60_000);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let gpu = Tagged("gpu".to_string());
+
+        cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        // The stale result is served at once while the refresh runs
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-1".to_string()));
+        for _ in 0..50 {
+            if cache.stats().refreshes == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-2".to_string()));
+
+        let stats = cache.stats();
+        assert_eq!((stats.misses, stats.stale_served, stats.refreshes, stats.hits), (1, 1, 1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_expired_results_are_misses() {
+        let cache = cache(10, 10);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let gpu = Tagged("gpu".to_string());
+
+        cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-2".to_string()));
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidation() {
+        let cache = cache(60_000, 0);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let gpu = Tagged("gpu".to_string());
+        let disk = Tagged("disk".to_string());
+
+        cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await.unwrap();
+        cache.get_or_fetch(&disk, false, counting_fetch(Arc::clone(&calls))).await.unwrap();
+
+        assert_eq!(cache.invalidate_matching(&"gpu".to_string()), 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get_or_fetch(&gpu, false, counting_fetch(Arc::clone(&calls))).await, Ok("gpu-3".to_string()));
+
+        assert_eq!(cache.invalidate_where(|_, value| value.starts_with("disk")), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().invalidations, 3);
+    }
+}
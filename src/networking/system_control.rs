@@ -0,0 +1,392 @@
+//! Typed control messages carried by `SystemControl` payloads
+//!
+//! Every control payload is a JSON [`ControlEnvelope`]: a format version and
+//! one [`ControlCommand`]. Replies to pending requests (acknowledgments,
+//! handler responses and failures) are commands like any other, so control
+//! traffic is dispatched by variant rather than by sniffing string prefixes.
+//!
+//! Peers that predate the envelope still reply with `ACK:<id>`,
+//! `RESP:<id>:<bytes>`, `ERR:<id>:<reason>` or `TIMEOUT:<id>`. Those are
+//! accepted by [`ControlEnvelope::decode`] while
+//! [`ControlEnvelope::LEGACY_REPLIES_ACCEPTED`] is set, and anything else that
+//! is not a valid envelope is rejected.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+use crate::key_rotation::KeyRotationRecord;
+use crate::networking::node_communication::{CommunicationError, ControlHandler};
+
+/// Current control envelope format
+pub const SYSTEM_CONTROL_VERSION: u32 = 1;
+
+/// A versioned control command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlEnvelope {
+    /// Format of `command`
+    pub version: u32,
+    /// The command itself
+    pub command: ControlCommand,
+}
+
+/// Control subcommands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// The handler for `message_id` finished without a response
+    Ack { message_id: String },
+    /// The handler for `message_id` responded with `payload`
+    Response { message_id: String, payload: Vec<u8> },
+    /// The handler for `message_id` failed
+    HandlerError { message_id: String, reason: String },
+    /// The handler for `message_id` did not finish in time
+    HandlerTimeout { message_id: String },
+    /// Liveness probe, answered with a [`ControlCommand::Pong`]
+    Ping { nonce: u64, sent_at: DateTime<Utc> },
+    /// Answer to a ping, with the prober's and the responder's timestamps
+    Pong {
+        nonce: u64,
+        ping_sent_at: DateTime<Utc>,
+        ping_received_at: DateTime<Utc>,
+        pong_sent_at: DateTime<Utc>,
+    },
+    /// The sender replaced its signing key
+    Rekey { record: KeyRotationRecord },
+    /// Stop sending the chunks of a transfer
+    AbortTransfer { transfer_id: String, reason: Option<String> },
+    /// Acquire, renew or release a lease
+    LeaseOp { lease_id: String, op: LeaseOperation },
+}
+
+/// Operations on a lease
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum LeaseOperation {
+    Acquire { ttl_ms: u64 },
+    Renew { ttl_ms: u64 },
+    Release,
+}
+
+/// Which subsystem a control command is routed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ControlKind {
+    /// Acknowledgments, responses and handler failures for pending requests
+    Reply,
+    Ping,
+    Pong,
+    Rekey,
+    AbortTransfer,
+    LeaseOp,
+}
+
+/// Why a control payload was rejected
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ControlError {
+    #[error("Malformed control payload: {0}")]
+    Malformed(String),
+
+    #[error("Unsupported control version {0}")]
+    UnsupportedVersion(u32),
+}
+
+impl ControlCommand {
+    /// Subsystem this command is routed to
+    pub fn kind(&self) -> ControlKind {
+        match self {
+            ControlCommand::Ack { .. }
+            | ControlCommand::Response { .. }
+            | ControlCommand::HandlerError { .. }
+            | ControlCommand::HandlerTimeout { .. } => ControlKind::Reply,
+            ControlCommand::Ping { .. } => ControlKind::Ping,
+            ControlCommand::Pong { .. } => ControlKind::Pong,
+            ControlCommand::Rekey { .. } => ControlKind::Rekey,
+            ControlCommand::AbortTransfer { .. } => ControlKind::AbortTransfer,
+            ControlCommand::LeaseOp { .. } => ControlKind::LeaseOp,
+        }
+    }
+
+    /// ID of the request this command replies to, if it is a reply
+    pub fn reply_to(&self) -> Option<&str> {
+        match self {
+            ControlCommand::Ack { message_id }
+            | ControlCommand::Response { message_id, .. }
+            | ControlCommand::HandlerError { message_id, .. }
+            | ControlCommand::HandlerTimeout { message_id } => Some(message_id),
+            _ => None,
+        }
+    }
+
+    /// The pong answering this command, if it is a ping
+    pub fn pong(&self, received_at: DateTime<Utc>) -> Option<ControlCommand> {
+        match self {
+            ControlCommand::Ping { nonce, sent_at } => Some(ControlCommand::Pong {
+                nonce: *nonce,
+                ping_sent_at: *sent_at,
+                ping_received_at: received_at,
+                pong_sent_at: Utc::now(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl ControlEnvelope {
+    /// Whether pre-envelope reply payloads are still accepted
+    pub const LEGACY_REPLIES_ACCEPTED: bool = true;
+
+    /// Wrap `command` in the current envelope format
+    pub fn new(command: ControlCommand) -> Self {
+        Self { version: SYSTEM_CONTROL_VERSION, command }
+    }
+
+    /// Serialize for a `SystemControl` payload
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("control envelopes always serialize")
+    }
+
+    /// Parse a `SystemControl` payload
+    pub fn decode(payload: &[u8]) -> Result<Self, ControlError> {
+        if Self::LEGACY_REPLIES_ACCEPTED {
+            if let Some(command) = parse_legacy_reply(payload) {
+                return Ok(Self::new(command));
+            }
+        }
+
+        // Check the version before the command, so newer commands report as such
+        let value: serde_json::Value = serde_json::from_slice(payload)
+            .map_err(|e| ControlError::Malformed(e.to_string()))?;
+        let version = value.get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| ControlError::Malformed("missing version".to_string()))?;
+        if version != SYSTEM_CONTROL_VERSION as u64 {
+            return Err(ControlError::UnsupportedVersion(version as u32));
+        }
+        serde_json::from_value(value).map_err(|e| ControlError::Malformed(e.to_string()))
+    }
+}
+
+/// Outgoing transfers that their receiver may abort with [`ControlCommand::AbortTransfer`]
+///
+/// Senders register a transfer and stop once its token is cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct TransferAborts {
+    transfers: Arc<Mutex<HashMap<String, (String, CancellationToken)>>>,
+}
+
+impl TransferAborts {
+    /// Track a transfer to `receiver`, returning the token cancelled when it is aborted
+    pub fn register(&self, transfer_id: &str, receiver: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.transfers.lock().unwrap()
+            .insert(transfer_id.to_string(), (receiver.to_string(), token.clone()));
+        token
+    }
+
+    /// Stop tracking a transfer that ended
+    pub fn finish(&self, transfer_id: &str) {
+        self.transfers.lock().unwrap().remove(transfer_id);
+    }
+
+    /// Abort a transfer on behalf of `from_node`; only its receiver may abort it
+    pub fn abort(&self, from_node: &str, transfer_id: &str) -> bool {
+        let mut transfers = self.transfers.lock().unwrap();
+        if !transfers.get(transfer_id).is_some_and(|(receiver, _)| receiver == from_node) {
+            return false;
+        }
+        if let Some((_, token)) = transfers.remove(transfer_id) {
+            token.cancel();
+        }
+        true
+    }
+}
+
+#[async_trait::async_trait]
+impl ControlHandler for TransferAborts {
+    async fn handle(&self, from_node: &str, command: ControlCommand) -> Result<Option<ControlCommand>, CommunicationError> {
+        if let ControlCommand::AbortTransfer { transfer_id, reason } = command {
+            if self.abort(from_node, &transfer_id) {
+                tracing::info!(%transfer_id, from = %from_node, ?reason, "transfer aborted by receiver");
+            } else {
+                tracing::debug!(%transfer_id, from = %from_node, "ignoring abort of unknown or foreign transfer");
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// A lease held by a node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lease {
+    /// Node holding the lease
+    pub holder: String,
+    /// When the lease lapses unless renewed
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Leases this node grants through [`ControlCommand::LeaseOp`]
+///
+/// A lease has one holder until it is released or expires; only the holder
+/// may renew or release it.
+#[derive(Debug, Clone, Default)]
+pub struct LeaseTable {
+    leases: Arc<Mutex<HashMap<String, Lease>>>,
+}
+
+impl LeaseTable {
+    /// Apply `op` from `from_node` at `now`, returning whether it took effect
+    pub fn apply(&self, from_node: &str, lease_id: &str, op: &LeaseOperation, now: DateTime<Utc>) -> bool {
+        let mut leases = self.leases.lock().unwrap();
+        let live = leases.get(lease_id).filter(|lease| lease.expires_at > now);
+        let held_by_sender = live.is_some_and(|lease| lease.holder == from_node);
+        let expiry = |ttl_ms: u64| now + chrono::Duration::milliseconds(ttl_ms.min(i64::MAX as u64) as i64);
+        match op {
+            LeaseOperation::Acquire { ttl_ms } if live.is_none() || held_by_sender => {
+                leases.insert(lease_id.to_string(), Lease { holder: from_node.to_string(), expires_at: expiry(*ttl_ms) });
+                true
+            }
+            LeaseOperation::Renew { ttl_ms } if held_by_sender => {
+                if let Some(lease) = leases.get_mut(lease_id) {
+                    lease.expires_at = expiry(*ttl_ms);
+                }
+                true
+            }
+            LeaseOperation::Release if held_by_sender => {
+                leases.remove(lease_id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The live lease `lease_id`, if any
+    pub fn lease(&self, lease_id: &str, now: DateTime<Utc>) -> Option<Lease> {
+        self.leases.lock().unwrap().get(lease_id).filter(|lease| lease.expires_at > now).cloned()
+    }
+}
+
+#[async_trait::async_trait]
+impl ControlHandler for LeaseTable {
+    async fn handle(&self, from_node: &str, command: ControlCommand) -> Result<Option<ControlCommand>, CommunicationError> {
+        if let ControlCommand::LeaseOp { lease_id, op } = command {
+            if !self.apply(from_node, &lease_id, &op, Utc::now()) {
+                tracing::debug!(%lease_id, from = %from_node, ?op, "lease operation refused");
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Reply in the pre-envelope `PREFIX:<id>[:<rest>]` format
+fn parse_legacy_reply(payload: &[u8]) -> Option<ControlCommand> {
+    let id_and_rest = |rest: &[u8]| -> Option<(String, Vec<u8>)> {
+        let split = rest.iter().position(|b| *b == b':')?;
+        Some((String::from_utf8(rest[..split].to_vec()).ok()?, rest[split + 1..].to_vec()))
+    };
+    let id = |rest: &[u8]| String::from_utf8(rest.to_vec()).ok();
+
+    if let Some(rest) = payload.strip_prefix(b"ACK:") {
+        Some(ControlCommand::Ack { message_id: id(rest)? })
+    } else if let Some(rest) = payload.strip_prefix(b"TIMEOUT:") {
+        Some(ControlCommand::HandlerTimeout { message_id: id(rest)? })
+    } else if let Some(rest) = payload.strip_prefix(b"RESP:") {
+        let (message_id, payload) = id_and_rest(rest)?;
+        Some(ControlCommand::Response { message_id, payload })
+    } else if let Some(rest) = payload.strip_prefix(b"ERR:") {
+        let (message_id, reason) = id_and_rest(rest)?;
+        Some(ControlCommand::HandlerError {
+            message_id,
+            reason: String::from_utf8_lossy(&reason).into_owned(),
+        })
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_rotation::NodeKeys;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_every_command_round_trips() {
+        let now = Utc::now();
+        let node_id = Uuid::new_v4();
+        let record = NodeKeys::generate().unwrap().rotate(node_id, NodeKeys::generate().unwrap(), now);
+        let commands = vec![
+            ControlCommand::Ack { message_id: "m1".to_string() },
+            ControlCommand::Response { message_id: "m2".to_string(), payload: b"ok:1".to_vec() },
+            ControlCommand::HandlerError { message_id: "m3".to_string(), reason: "busy".to_string() },
+            ControlCommand::HandlerTimeout { message_id: "m4".to_string() },
+            ControlCommand::Ping { nonce: 7, sent_at: now },
+            ControlCommand::Pong { nonce: 7, ping_sent_at: now, ping_received_at: now, pong_sent_at: now },
+            ControlCommand::Rekey { record },
+            ControlCommand::AbortTransfer { transfer_id: "t1".to_string(), reason: Some("cancelled".to_string()) },
+            ControlCommand::LeaseOp { lease_id: "l1".to_string(), op: LeaseOperation::Acquire { ttl_ms: 500 } },
+            ControlCommand::LeaseOp { lease_id: "l1".to_string(), op: LeaseOperation::Renew { ttl_ms: 500 } },
+            ControlCommand::LeaseOp { lease_id: "l1".to_string(), op: LeaseOperation::Release },
+        ];
+        for command in commands {
+            let envelope = ControlEnvelope::new(command);
+            assert_eq!(ControlEnvelope::decode(&envelope.encode()).unwrap(), envelope);
+        }
+    }
+
+    #[test]
+    fn test_malformed_and_legacy_payloads() {
+        assert_eq!(
+            ControlEnvelope::decode(b"ACK:m1").unwrap().command,
+            ControlCommand::Ack { message_id: "m1".to_string() },
+        );
+        assert_eq!(
+            ControlEnvelope::decode(b"RESP:m2:a:b").unwrap().command,
+            ControlCommand::Response { message_id: "m2".to_string(), payload: b"a:b".to_vec() },
+        );
+
+        assert!(matches!(ControlEnvelope::decode(b"PING:1"), Err(ControlError::Malformed(_))));
+        assert!(matches!(ControlEnvelope::decode(br#"{"command":"ack","message_id":"m1"}"#), Err(ControlError::Malformed(_))));
+        assert!(matches!(
+            ControlEnvelope::decode(br#"{"version":1,"command":{"command":"shutdown"}}"#),
+            Err(ControlError::Malformed(_))
+        ));
+        assert_eq!(
+            ControlEnvelope::decode(br#"{"version":2,"command":{"command":"shutdown"}}"#),
+            Err(ControlError::UnsupportedVersion(2)),
+        );
+    }
+
+    #[test]
+    fn test_leases_have_one_holder_until_released_or_expired() {
+        let leases = LeaseTable::default();
+        let now = Utc::now();
+        let acquire = LeaseOperation::Acquire { ttl_ms: 1000 };
+
+        assert!(leases.apply("a", "l1", &acquire, now));
+        assert!(!leases.apply("b", "l1", &acquire, now));
+        assert!(!leases.apply("b", "l1", &LeaseOperation::Release, now));
+        assert!(leases.apply("a", "l1", &LeaseOperation::Renew { ttl_ms: 5000 }, now));
+        assert_eq!(leases.lease("l1", now + chrono::Duration::seconds(2)).unwrap().holder, "a");
+
+        // Once expired, anyone may take it and the old holder can no longer renew
+        let later = now + chrono::Duration::seconds(6);
+        assert!(!leases.apply("a", "l1", &LeaseOperation::Renew { ttl_ms: 1000 }, later));
+        assert!(leases.apply("b", "l1", &acquire, later));
+        assert!(leases.apply("b", "l1", &LeaseOperation::Release, later));
+        assert_eq!(leases.lease("l1", later), None);
+    }
+
+    #[test]
+    fn test_only_the_receiver_aborts_a_transfer() {
+        let transfers = TransferAborts::default();
+        let token = transfers.register("t1", "receiver");
+
+        assert!(!transfers.abort("someone-else", "t1"));
+        assert!(!token.is_cancelled());
+        assert!(transfers.abort("receiver", "t1"));
+        assert!(token.is_cancelled());
+        assert!(!transfers.abort("receiver", "t1"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Cross-node Trace Context Propagation for WeaveMesh
+//!
+//! This module carries W3C `traceparent`-style trace context on
+//! `WeaveMeshMessage`s so that a request hopping through several nodes
+//! produces one connected trace. It works with plain `tracing` spans: the
+//! trace and span identifiers are recorded as span fields, so any
+//! `tracing-subscriber` layer can correlate them. Exporters (e.g. OTLP)
+//! plug in through the `TraceExporter` trait behind the `otlp` feature.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+tokio::task_local! {
+    /// Trace context of the span currently executing on this task
+    static CURRENT_TRACE: TraceContext;
+}
+
+/// W3C trace-context version emitted by this implementation
+const TRACEPARENT_VERSION: &str = "00";
+
+/// Trace context propagated between nodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// Trace identifier shared by every span in the trace (32 hex chars)
+    pub trace_id: String,
+
+    /// Identifier of the span this context belongs to (16 hex chars)
+    pub span_id: String,
+
+    /// Identifier of the parent span, if any (16 hex chars)
+    pub parent_span_id: Option<String>,
+
+    /// Trace flags; bit 0 is the sampling decision
+    pub flags: u8,
+}
+
+impl TraceContext {
+    /// Sampled flag as defined by W3C trace context
+    pub const FLAG_SAMPLED: u8 = 0x01;
+
+    /// Start a new root trace with the given sampling decision
+    pub fn new_root(sampled: bool) -> Self {
+        Self {
+            trace_id: format!("{:032x}", Uuid::new_v4().as_u128()),
+            span_id: new_span_id(),
+            parent_span_id: None,
+            flags: if sampled { Self::FLAG_SAMPLED } else { 0 },
+        }
+    }
+
+    /// Create a child context in the same trace, inheriting the sampling decision
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id.clone(),
+            span_id: new_span_id(),
+            parent_span_id: Some(self.span_id.clone()),
+            flags: self.flags,
+        }
+    }
+
+    /// Whether the originator decided to sample this trace
+    pub fn is_sampled(&self) -> bool {
+        self.flags & Self::FLAG_SAMPLED != 0
+    }
+
+    /// Trace context of the currently executing task, if any
+    pub fn current() -> Option<Self> {
+        CURRENT_TRACE.try_with(|ctx| ctx.clone()).ok()
+    }
+
+    /// Run a future with this context as the current trace context
+    pub async fn scope<F: std::future::Future>(self, future: F) -> F::Output {
+        CURRENT_TRACE.scope(self, future).await
+    }
+
+    /// Run a closure with this context as the current trace context
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        CURRENT_TRACE.sync_scope(self, f)
+    }
+
+    /// Create a `tracing` span for this context
+    ///
+    /// Unsampled contexts produce a disabled span so downstream nodes
+    /// honor the originator's sampling decision.
+    pub fn span(&self, name: &'static str) -> tracing::Span {
+        if !self.is_sampled() {
+            return tracing::Span::none();
+        }
+
+        tracing::info_span!(
+            "weavemesh",
+            otel.name = name,
+            trace_id = %self.trace_id,
+            span_id = %self.span_id,
+            parent_span_id = self.parent_span_id.as_deref().unwrap_or(""),
+        )
+    }
+
+    /// Encode as a W3C `traceparent` header value
+    pub fn to_traceparent(&self) -> String {
+        format!("{}-{}-{}-{:02x}", TRACEPARENT_VERSION, self.trace_id, self.span_id, self.flags)
+    }
+
+    /// Decode a W3C `traceparent` header value
+    ///
+    /// The decoded context describes the remote span; use `child()` to
+    /// create the local span beneath it.
+    pub fn from_traceparent(value: &str) -> Option<Self> {
+        let parts: Vec<&str> = value.trim().split('-').collect();
+        if parts.len() != 4 || parts[0] != TRACEPARENT_VERSION {
+            return None;
+        }
+
+        let (trace_id, span_id) = (parts[1], parts[2]);
+        if !is_hex_id(trace_id, 32) || !is_hex_id(span_id, 16) {
+            return None;
+        }
+
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: None,
+            flags: u8::from_str_radix(parts[3], 16).ok()?,
+        })
+    }
+}
+
+/// Finished span handed to an exporter
+#[cfg(feature = "otlp")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinishedSpan {
+    /// Context of the span
+    pub context: TraceContext,
+
+    /// Span name
+    pub name: String,
+
+    /// Node that recorded the span
+    pub node_id: String,
+
+    /// Span duration in milliseconds
+    pub duration_ms: f64,
+}
+
+/// Integration point for exporting spans to an external collector (e.g. OTLP)
+#[cfg(feature = "otlp")]
+pub trait TraceExporter: Send + Sync {
+    /// Export a finished span
+    fn export(&self, span: FinishedSpan);
+}
+
+/// Generate a random 64-bit span identifier
+fn new_span_id() -> String {
+    format!("{:016x}", Uuid::new_v4().as_u128() as u64)
+}
+
+/// Check that an identifier is lowercase hex of the given length and not all zeros
+fn is_hex_id(id: &str, len: usize) -> bool {
+    id.len() == len
+        && id.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+        && id.chars().any(|c| c != '0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_roundtrip() {
+        let ctx = TraceContext::new_root(true);
+        let header = ctx.to_traceparent();
+
+        let decoded = TraceContext::from_traceparent(&header).unwrap();
+        assert_eq!(decoded.trace_id, ctx.trace_id);
+        assert_eq!(decoded.span_id, ctx.span_id);
+        assert!(decoded.is_sampled());
+
+        assert!(TraceContext::from_traceparent("garbage").is_none());
+        assert!(TraceContext::from_traceparent(
+            "00-00000000000000000000000000000000-0000000000000000-01"
+        ).is_none());
+    }
+
+    #[test]
+    fn test_child_inherits_trace_and_sampling() {
+        let root = TraceContext::new_root(false);
+        let child = root.child();
+
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_span_id.as_deref(), Some(root.span_id.as_str()));
+        assert_ne!(child.span_id, root.span_id);
+        assert!(!child.is_sampled());
+        assert!(child.span("handler").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_current_scope() {
+        assert!(TraceContext::current().is_none());
+
+        let ctx = TraceContext::new_root(true);
+        let seen = ctx.clone().scope(async { TraceContext::current() }).await;
+        assert_eq!(seen, Some(ctx));
+    }
+}
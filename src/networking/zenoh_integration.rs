@@ -13,6 +13,11 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::networking::broadcast_scope::BroadcastScope;
+use crate::networking::node_communication::MessagePriority;
+use crate::networking::trace_context::TraceContext;
+use crate::networking::payload_summary::PayloadSummary;
+
 /// Universal Zenoh session wrapper for mesh nodes
 /// 
 /// Each node gets its own ZenohSession that:
@@ -86,7 +91,9 @@ pub enum ZenohMode {
 pub type MessageHandler = Box<dyn Fn(WeaveMeshMessage) -> Result<(), ZenohError> + Send + Sync>;
 
 /// Universal WeaveMesh message format for Zenoh communication
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `Debug` shows a [`PayloadSummary`] instead of the payload bytes.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WeaveMeshMessage {
     /// Source node ID
     pub from_node: String,
@@ -108,6 +115,36 @@ pub struct WeaveMeshMessage {
     
     /// Context information (for context-specific routing)
     pub context: Option<String>,
+    
+    /// Distributed trace context of the sending span
+    #[serde(default)]
+    pub trace_context: Option<TraceContext>,
+    
+    /// Priority the sender assigned, inherited by replies; `None` means the
+    /// priority of the message type
+    #[serde(default)]
+    pub priority: Option<MessagePriority>,
+    
+    /// Audience of a broadcast; `None` for direct messages and unscoped traffic
+    #[serde(default)]
+    pub broadcast_scope: Option<BroadcastScope>,
+}
+
+impl std::fmt::Debug for WeaveMeshMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeaveMeshMessage")
+            .field("from_node", &self.from_node)
+            .field("to_node", &self.to_node)
+            .field("message_type", &self.message_type)
+            .field("payload", &PayloadSummary::new(&self.payload))
+            .field("timestamp", &self.timestamp)
+            .field("message_id", &self.message_id)
+            .field("context", &self.context)
+            .field("trace_context", &self.trace_context)
+            .field("priority", &self.priority)
+            .field("broadcast_scope", &self.broadcast_scope)
+            .finish()
+    }
 }
 
 /// Universal message types in WeaveMesh
@@ -137,6 +174,9 @@ pub enum MessageType {
     /// Heartbeat for connection health
     Heartbeat,
     
+    /// Resource watch subscription or change notification
+    ResourceWatch,
+    
     /// Context-specific message
     ContextSpecific(String),
     
@@ -285,6 +325,9 @@ impl ZenohSession {
             timestamp: Utc::now(),
             message_id: Uuid::new_v4().to_string(),
             context,
+            trace_context: TraceContext::current(),
+            priority: None,
+            broadcast_scope: None,
         };
         
         // Send to the node's direct topic
@@ -306,6 +349,9 @@ impl ZenohSession {
             timestamp: Utc::now(),
             message_id: Uuid::new_v4().to_string(),
             context: None,
+            trace_context: TraceContext::current(),
+            priority: None,
+            broadcast_scope: None,
         };
         
         // Broadcast to all nodes
@@ -407,6 +453,19 @@ impl WeaveMeshTopics {
         format!("weavemesh/contexts/{}/{}", context, subtopic)
     }
     
+    /// Topic broadcasts to `scope` are published on
+    ///
+    /// Organization broadcasts go to the topic of `organization`, the
+    /// sender's, and have none without it.
+    pub fn scoped_broadcast(scope: &BroadcastScope, organization: Option<&str>) -> Option<String> {
+        match scope {
+            BroadcastScope::Group(group_id) => Some(format!("weavemesh/broadcast/groups/{}", group_id.as_str())),
+            BroadcastScope::Context(context) => Some(format!("weavemesh/broadcast/contexts/{}", context)),
+            BroadcastScope::Organization => organization.map(|org| format!("weavemesh/broadcast/organizations/{}", org)),
+            BroadcastScope::Mesh => Some(Self::BROADCAST.to_string()),
+        }
+    }
+    
     /// Get resource topic for a resource ID
     pub fn resource(resource_id: &str) -> String {
         format!("weavemesh/resources/{}", resource_id)
@@ -529,6 +588,9 @@ pub mod utils {
             timestamp: Utc::now(),
             message_id: Uuid::new_v4().to_string(),
             context,
+            trace_context: TraceContext::current(),
+            priority: None,
+            broadcast_scope: None,
         }
     }
     
@@ -580,6 +642,9 @@ mod tests {
             timestamp: Utc::now(),
             message_id: "test-message-id".to_string(),
             context: Some("test-context".to_string()),
+            trace_context: None,
+            priority: None,
+            broadcast_scope: None,
         };
         
         let encoded = ZenohSession::encode_message(&message).unwrap();
@@ -634,6 +699,9 @@ mod tests {
             timestamp: Utc::now(),
             message_id: "msg1".to_string(),
             context: None,
+            trace_context: None,
+            priority: None,
+            broadcast_scope: None,
         };
         
         let direct_msg = WeaveMeshMessage {
@@ -644,6 +712,9 @@ mod tests {
             timestamp: Utc::now(),
             message_id: "msg2".to_string(),
             context: Some("test".to_string()),
+            trace_context: None,
+            priority: None,
+            broadcast_scope: None,
         };
         
         assert!(is_broadcast(&broadcast_msg));
@@ -5,10 +5,12 @@
 //! systems, or hybrid combinations. Context-specific behaviors are
 //! implemented through plugins.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
 
 /// Unique identifier for any node in the WeaveMesh
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -107,7 +109,7 @@ pub enum NodeRole {
 }
 
 /// Capabilities that a node can advertise
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum NodeCapability {
     // Universal capabilities
     ResourceStorage,
@@ -142,6 +144,137 @@ pub enum NodeCapability {
     Custom(String),
 }
 
+impl NodeCapability {
+    /// Capabilities this one cannot work without
+    pub fn dependencies(&self) -> Vec<NodeCapability> {
+        match self {
+            NodeCapability::AttributionTracking
+            | NodeCapability::KnowledgeRetrieval
+            | NodeCapability::AuditLogging => vec![NodeCapability::ResourceStorage],
+            NodeCapability::Authentication => vec![NodeCapability::Encryption],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// How a node reacts when one of its capabilities fails
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CapabilityDowngradePolicy {
+    /// Disable the capability and everything depending on it
+    #[default]
+    DisableDependents,
+    /// Retry after `delay`, disabling once `max_attempts` failures have been seen
+    Retry { delay: Duration, max_attempts: u32 },
+    /// Treat the failure as fatal
+    Propagate,
+}
+
+/// What to do about a failed capability
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapabilityDowngradeDecision {
+    /// These capabilities were disabled: the failed one first, then its dependents
+    Disable(Vec<NodeCapability>),
+    /// Try the capability again after the delay
+    Retry(Duration),
+    /// Surface the failure as a fatal error
+    Propagate,
+}
+
+/// Events emitted by a node
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeEvent {
+    /// A capability was disabled after a failure
+    CapabilityDowngraded {
+        capability: NodeCapability,
+        reason: String,
+        affected_dependents: Vec<NodeCapability>,
+    },
+    /// A disabled capability and the dependents it took down were re-enabled
+    CapabilityRestored {
+        capability: NodeCapability,
+        restored_dependents: Vec<NodeCapability>,
+    },
+    /// A peer's score crossed `NodeConfig::min_peer_score_for_operations`
+    PeerScoreChanged {
+        peer_id: String,
+        previous_score: f64,
+        new_score: f64,
+    },
+}
+
+/// Result of one interaction with a peer
+#[derive(Debug, Clone, PartialEq)]
+pub enum InteractionOutcome {
+    /// The peer answered after `latency_ms`
+    Success { latency_ms: u64 },
+    /// The peer answered with an error
+    Failure(String),
+    /// The peer did not answer in time
+    Timeout,
+}
+
+/// Latency at which a peer's latency factor drops to one half
+const PEER_REFERENCE_LATENCY_MS: f64 = 200.0;
+
+/// Share of a peer's score coming from its success rate; the rest is latency
+const PEER_SUCCESS_WEIGHT: f64 = 0.7;
+
+/// Reliability of a peer, smoothed over its recent interactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerScore {
+    /// Overall score from 0.0 (unusable) to 1.0
+    pub score: f64,
+    /// Moving average of successful interactions, from 0.0 to 1.0
+    pub success_rate: f64,
+    /// Moving average of latency over successful interactions
+    pub avg_latency_ms: f64,
+    /// Interactions recorded
+    pub interactions: u64,
+    /// Successful interactions, whose latencies make up the average
+    #[serde(default)]
+    pub latency_samples: u64,
+    /// When the last interaction was recorded
+    pub last_interaction: Option<DateTime<Utc>>,
+}
+
+impl Default for PeerScore {
+    /// Score of a peer with no history, trusted until it misbehaves
+    fn default() -> Self {
+        Self {
+            score: 1.0,
+            success_rate: 1.0,
+            avg_latency_ms: 0.0,
+            interactions: 0,
+            latency_samples: 0,
+            last_interaction: None,
+        }
+    }
+}
+
+impl PeerScore {
+    /// Fold `outcome` into the averages, weighting it by `smoothing`
+    fn record(&mut self, outcome: &InteractionOutcome, smoothing: f64) {
+        let succeeded = matches!(outcome, InteractionOutcome::Success { .. });
+        let sample = if succeeded { 1.0 } else { 0.0 };
+        self.success_rate = smoothing * sample + (1.0 - smoothing) * self.success_rate;
+        if let InteractionOutcome::Success { latency_ms } = outcome {
+            let latency = *latency_ms as f64;
+            self.avg_latency_ms = if self.latency_samples == 0 {
+                latency
+            } else {
+                smoothing * latency + (1.0 - smoothing) * self.avg_latency_ms
+            };
+            self.latency_samples += 1;
+        }
+        // Latency only counts for the share of interactions that were answered,
+        // so a peer that never answers cannot earn it with an empty average
+        let latency_factor = self.success_rate * PEER_REFERENCE_LATENCY_MS / (PEER_REFERENCE_LATENCY_MS + self.avg_latency_ms);
+        self.score = PEER_SUCCESS_WEIGHT * self.success_rate + (1.0 - PEER_SUCCESS_WEIGHT) * latency_factor;
+        self.interactions += 1;
+        self.last_interaction = Some(Utc::now());
+    }
+}
+
 /// Basic node configuration
 #[derive(Debug, Clone)]
 pub struct NodeConfig {
@@ -168,6 +301,15 @@ pub struct NodeConfig {
     
     /// Whether to enable debug logging
     pub debug_mode: bool,
+    
+    /// Default reaction to a failed capability
+    pub downgrade_strategy: CapabilityDowngradePolicy,
+    
+    /// Lowest peer score at which a peer is used for important operations
+    pub min_peer_score_for_operations: f64,
+    
+    /// Weight of the newest interaction in peer score averages, from 0.0 to 1.0
+    pub peer_score_smoothing: f64,
 }
 
 impl Default for NodeConfig {
@@ -185,6 +327,9 @@ impl Default for NodeConfig {
             ],
             metadata: HashMap::new(),
             debug_mode: false,
+            downgrade_strategy: CapabilityDowngradePolicy::default(),
+            min_peer_score_for_operations: 0.5,
+            peer_score_smoothing: 0.2,
         }
     }
 }
@@ -254,6 +399,20 @@ pub struct BasicNode {
     
     /// Whether this node is currently active
     pub is_active: bool,
+    
+    /// Disabled capabilities, each with the failed capability that took it down
+    disabled_capabilities: HashMap<NodeCapability, NodeCapability>,
+    
+    /// Failures seen per capability since it last worked
+    capability_failures: HashMap<NodeCapability, u32>,
+    
+    /// Per-capability overrides of `config.downgrade_strategy`
+    downgrade_overrides: HashMap<NodeCapability, CapabilityDowngradePolicy>,
+    
+    /// Reliability of each peer this node has interacted with
+    peer_scores: HashMap<String, PeerScore>,
+    
+    events: broadcast::Sender<NodeEvent>,
 }
 
 impl BasicNode {
@@ -267,9 +426,162 @@ impl BasicNode {
             created_at: now,
             last_activity: now,
             is_active: false,
+            disabled_capabilities: HashMap::new(),
+            capability_failures: HashMap::new(),
+            downgrade_overrides: HashMap::new(),
+            peer_scores: HashMap::new(),
+            events: broadcast::channel(64).0,
         }
     }
     
+    /// Subscribe to node events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
+    
+    /// Use `policy` instead of the configured default when `capability` fails
+    pub fn set_downgrade_policy(&mut self, capability: NodeCapability, policy: CapabilityDowngradePolicy) {
+        self.downgrade_overrides.insert(capability, policy);
+    }
+    
+    /// Whether the node provides `cap` and it has not been disabled
+    ///
+    /// Components should check this before relying on a capability.
+    pub fn is_capability_active(&self, cap: NodeCapability) -> bool {
+        self.has_capability(&cap) && !self.disabled_capabilities.contains_key(&cap)
+    }
+    
+    /// Capabilities provided and not disabled
+    pub fn active_capabilities(&self) -> Vec<NodeCapability> {
+        self.config.capabilities.iter()
+            .filter(|cap| !self.disabled_capabilities.contains_key(cap))
+            .cloned()
+            .collect()
+    }
+    
+    /// Decide how to handle a failure of `cap` and apply the decision
+    ///
+    /// Disabling takes down every provided capability that depends on `cap`,
+    /// directly or transitively, and emits [`NodeEvent::CapabilityDowngraded`].
+    pub fn on_capability_failure(&mut self, cap: NodeCapability, error: &NodeError) -> CapabilityDowngradeDecision {
+        let failures = self.capability_failures.entry(cap.clone()).or_insert(0);
+        *failures += 1;
+        let failures = *failures;
+        
+        let policy = self.downgrade_overrides.get(&cap)
+            .unwrap_or(&self.config.downgrade_strategy)
+            .clone();
+        match policy {
+            CapabilityDowngradePolicy::Propagate => CapabilityDowngradeDecision::Propagate,
+            CapabilityDowngradePolicy::Retry { delay, max_attempts } if failures < max_attempts => {
+                CapabilityDowngradeDecision::Retry(delay)
+            }
+            _ => {
+                let dependents = self.dependents_of(&cap);
+                for disabled in std::iter::once(&cap).chain(&dependents) {
+                    self.disabled_capabilities.entry(disabled.clone()).or_insert_with(|| cap.clone());
+                }
+                tracing::warn!("Capability {:?} disabled: {} (dependents: {:?})", cap, error, dependents);
+                let _ = self.events.send(NodeEvent::CapabilityDowngraded {
+                    capability: cap.clone(),
+                    reason: error.to_string(),
+                    affected_dependents: dependents.clone(),
+                });
+                CapabilityDowngradeDecision::Disable(std::iter::once(cap).chain(dependents).collect())
+            }
+        }
+    }
+    
+    /// Note that `cap` works again, clearing its failures
+    ///
+    /// If it was disabled by its own failure, it and the dependents it took
+    /// down are re-enabled. Returns the re-enabled capabilities.
+    pub fn restore_capability(&mut self, cap: NodeCapability) -> Vec<NodeCapability> {
+        self.capability_failures.remove(&cap);
+        if self.disabled_capabilities.get(&cap) != Some(&cap) {
+            return Vec::new();
+        }
+        
+        let restored: Vec<NodeCapability> = self.config.capabilities.iter()
+            .filter(|c| self.disabled_capabilities.get(c) == Some(&cap))
+            .cloned()
+            .collect();
+        self.disabled_capabilities.retain(|_, cause| *cause != cap);
+        let _ = self.events.send(NodeEvent::CapabilityRestored {
+            capability: cap.clone(),
+            restored_dependents: restored.iter().filter(|c| **c != cap).cloned().collect(),
+        });
+        restored
+    }
+    
+    /// Provided capabilities depending on `cap`, directly or transitively
+    fn dependents_of(&self, cap: &NodeCapability) -> Vec<NodeCapability> {
+        let mut affected: HashSet<NodeCapability> = HashSet::from([cap.clone()]);
+        let mut dependents = Vec::new();
+        loop {
+            let next: Vec<NodeCapability> = self.config.capabilities.iter()
+                .filter(|c| !affected.contains(c))
+                .filter(|c| c.dependencies().iter().any(|d| affected.contains(d)))
+                .cloned()
+                .collect();
+            if next.is_empty() {
+                return dependents;
+            }
+            affected.extend(next.iter().cloned());
+            dependents.extend(next);
+        }
+    }
+    
+    /// Record how an interaction with `peer_id` went
+    ///
+    /// Emits [`NodeEvent::PeerScoreChanged`] when the peer's score crosses
+    /// `config.min_peer_score_for_operations` in either direction.
+    pub fn record_peer_interaction(&mut self, peer_id: &str, outcome: InteractionOutcome) {
+        let threshold = self.config.min_peer_score_for_operations;
+        let smoothing = self.config.peer_score_smoothing.clamp(0.0, 1.0);
+        let peer = self.peer_scores.entry(peer_id.to_string()).or_default();
+        let previous_score = peer.score;
+        peer.record(&outcome, smoothing);
+        let new_score = peer.score;
+        
+        if (previous_score >= threshold) != (new_score >= threshold) {
+            tracing::debug!("Peer {} score moved from {:.2} to {:.2}", peer_id, previous_score, new_score);
+            let _ = self.events.send(NodeEvent::PeerScoreChanged {
+                peer_id: peer_id.to_string(),
+                previous_score,
+                new_score,
+            });
+        }
+    }
+    
+    /// Current score of `peer_id`; peers without history get the default
+    pub fn peer_score(&self, peer_id: &str) -> PeerScore {
+        self.peer_scores.get(peer_id).cloned().unwrap_or_default()
+    }
+    
+    /// Known peers, best score first
+    pub fn ranked_peers(&self) -> Vec<(String, PeerScore)> {
+        let mut peers: Vec<(String, PeerScore)> = self.peer_scores.iter()
+            .map(|(id, score)| (id.clone(), score.clone()))
+            .collect();
+        peers.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then_with(|| a.0.cmp(&b.0)));
+        peers
+    }
+    
+    /// Whether `peer_id` scores high enough for important operations
+    pub fn is_peer_eligible(&self, peer_id: &str) -> bool {
+        self.peer_score(peer_id).score >= self.config.min_peer_score_for_operations
+    }
+    
+    /// Known peers eligible for important operations, best score first
+    pub fn eligible_peers(&self) -> Vec<(String, PeerScore)> {
+        let threshold = self.config.min_peer_score_for_operations;
+        self.ranked_peers()
+            .into_iter()
+            .filter(|(_, score)| score.score >= threshold)
+            .collect()
+    }
+    
     /// Start the node
     pub fn start(&mut self) -> Result<(), NodeError> {
         self.is_active = true;
@@ -302,7 +614,13 @@ impl BasicNode {
     }
     
     /// Check if this node can collaborate with another node
+    ///
+    /// Peers scoring below `config.min_peer_score_for_operations` are refused.
     pub fn can_collaborate_with(&self, other: &dyn Node) -> bool {
+        if !self.is_peer_eligible(&other.id().as_string()) {
+            return false;
+        }
+        
         // Same organization
         if self.organization_id() == other.organization_id() {
             return true;
@@ -356,7 +674,7 @@ impl Node for BasicNode {
             node_type: self.config.node_type.clone(),
             role: self.config.role.clone(),
             security_level: self.config.security_level.clone(),
-            capabilities: self.config.capabilities.clone(),
+            capabilities: self.active_capabilities(),
             metadata: self.config.metadata.clone(),
             created_at: self.created_at,
             last_activity: self.last_activity,
@@ -386,10 +704,114 @@ pub enum NodeError {
     #[error("Network error: {0}")]
     NetworkError(String),
     
+    #[error("Invalid {field}: {reason}")]
+    InvalidField { field: &'static str, reason: String },
+    
     #[error("Generic node error: {0}")]
     Generic(String),
 }
 
+/// Environment variable holding the node's display name
+pub const ENV_NODE_NAME: &str = "WEAVEMESH_NODE_NAME";
+/// Environment variable holding the node type, e.g. `human`, `ai:llm:gpt-4`, `system:database`
+pub const ENV_NODE_TYPE: &str = "WEAVEMESH_NODE_TYPE";
+/// Environment variable holding the organization ID
+pub const ENV_ORGANIZATION: &str = "WEAVEMESH_ORGANIZATION";
+/// Environment variable holding the security level, e.g. `confidential`
+pub const ENV_SECURITY_LEVEL: &str = "WEAVEMESH_SECURITY_LEVEL";
+/// Environment variable holding comma-separated capability names
+pub const ENV_CAPABILITIES: &str = "WEAVEMESH_CAPABILITIES";
+
+/// Lowercase a name and drop separators so `Code_Generation` matches `CodeGeneration`
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn invalid(field: &'static str, reason: &str) -> NodeError {
+    NodeError::InvalidField { field, reason: reason.to_string() }
+}
+
+/// Parse a node type such as `human`, `hybrid`, `ai`, `ai:llm:gpt-4`, `ai:agent:reviewer` or `system:cloud`
+fn parse_node_type(value: &str) -> Result<NodeType, NodeError> {
+    let mut parts = value.splitn(3, ':');
+    let kind = normalize_name(parts.next().unwrap_or_default());
+    let subtype = parts.next().map(normalize_name);
+    let name = parts.next().map(|n| n.trim().to_string());
+    
+    let node_type = match (kind.as_str(), subtype.as_deref(), name) {
+        ("human", None, None) => NodeType::Human,
+        ("hybrid", None, None) => NodeType::Hybrid,
+        ("ai", None, None) | ("ai", Some("assistant"), None) => NodeType::AI(AIType::Assistant),
+        ("ai", Some("autonomous"), None) => NodeType::AI(AIType::Autonomous),
+        ("ai", Some("llm"), Some(name)) => NodeType::AI(AIType::LLM(name)),
+        ("ai", Some("agent"), Some(name)) => NodeType::AI(AIType::Agent(name)),
+        ("system", Some("database"), None) => NodeType::System(SystemType::Database),
+        ("system", Some("api"), None) => NodeType::System(SystemType::API),
+        ("system", Some("iotdevice"), None) | ("system", Some("iot"), None) => NodeType::System(SystemType::IoTDevice),
+        ("system", Some("legacy"), None) => NodeType::System(SystemType::Legacy),
+        ("system", Some("cloud"), None) => NodeType::System(SystemType::Cloud),
+        _ => return Err(invalid("node_type", "unknown type")),
+    };
+    Ok(node_type)
+}
+
+/// Parse a security level; custom levels are written `custom:<name>`
+fn parse_security_level(value: &str) -> Result<SecurityLevel, NodeError> {
+    if let Some((prefix, name)) = value.split_once(':') {
+        if normalize_name(prefix) == "custom" && !name.trim().is_empty() {
+            return Ok(SecurityLevel::Custom(name.trim().to_string()));
+        }
+    }
+    
+    match normalize_name(value).as_str() {
+        "public" => Ok(SecurityLevel::Public),
+        "internal" => Ok(SecurityLevel::Internal),
+        "confidential" => Ok(SecurityLevel::Confidential),
+        "secret" => Ok(SecurityLevel::Secret),
+        "topsecret" => Ok(SecurityLevel::TopSecret),
+        _ => Err(invalid("security_level", "unknown level")),
+    }
+}
+
+/// Parse a capability name case-insensitively; custom capabilities are written `custom:<name>`
+fn parse_capability(value: &str) -> Result<NodeCapability, NodeError> {
+    if let Some((prefix, name)) = value.split_once(':') {
+        if normalize_name(prefix) == "custom" && !name.trim().is_empty() {
+            return Ok(NodeCapability::Custom(name.trim().to_string()));
+        }
+    }
+    
+    let capability = match normalize_name(value).as_str() {
+        "resourcestorage" => NodeCapability::ResourceStorage,
+        "attributiontracking" => NodeCapability::AttributionTracking,
+        "collaboration" => NodeCapability::Collaboration,
+        "naturallanguageprocessing" => NodeCapability::NaturalLanguageProcessing,
+        "codegeneration" => NodeCapability::CodeGeneration,
+        "dataanalysis" => NodeCapability::DataAnalysis,
+        "patternrecognition" => NodeCapability::PatternRecognition,
+        "knowledgeretrieval" => NodeCapability::KnowledgeRetrieval,
+        "databaseaccess" => NodeCapability::DatabaseAccess,
+        "apiintegration" => NodeCapability::APIIntegration,
+        "sensordata" => NodeCapability::SensorData,
+        "computeresources" => NodeCapability::ComputeResources,
+        "medicaldataprocessing" => NodeCapability::MedicalDataProcessing,
+        "legaldocumentanalysis" => NodeCapability::LegalDocumentAnalysis,
+        "financialmodeling" => NodeCapability::FinancialModeling,
+        "scientificcomputation" => NodeCapability::ScientificComputation,
+        "encryption" => NodeCapability::Encryption,
+        "authentication" => NodeCapability::Authentication,
+        "auditlogging" => NodeCapability::AuditLogging,
+        _ => return Err(NodeError::InvalidField {
+            field: "capabilities",
+            reason: format!("unknown capability '{}'", value.trim()),
+        }),
+    };
+    Ok(capability)
+}
+
 /// Node builder for easy configuration
 pub struct NodeBuilder {
     config: NodeConfig,
@@ -409,6 +831,45 @@ impl NodeBuilder {
         Self::default()
     }
     
+    /// Create a builder configured from `WEAVEMESH_*` environment variables
+    ///
+    /// Unset or empty variables keep the `NodeBuilder::default()` values.
+    pub fn from_env() -> Result<Self, NodeError> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+    
+    /// Create a builder from `WEAVEMESH_*` variables supplied by `lookup`
+    pub fn from_lookup<F>(lookup: F) -> Result<Self, NodeError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let get = |key: &str| lookup(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let mut builder = Self::default();
+        
+        if let Some(name) = get(ENV_NODE_NAME) {
+            builder = builder.with_display_name(&name);
+        }
+        if let Some(org) = get(ENV_ORGANIZATION) {
+            builder = builder.with_organization(&org);
+        }
+        if let Some(node_type) = get(ENV_NODE_TYPE) {
+            builder = builder.with_node_type(parse_node_type(&node_type)?);
+        }
+        if let Some(level) = get(ENV_SECURITY_LEVEL) {
+            builder = builder.with_security_level(parse_security_level(&level)?);
+        }
+        if let Some(capabilities) = get(ENV_CAPABILITIES) {
+            let capabilities = capabilities.split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(parse_capability)
+                .collect::<Result<Vec<_>, _>>()?;
+            builder = builder.with_capabilities(capabilities);
+        }
+        
+        Ok(builder)
+    }
+    
     /// Set the display name
     pub fn with_display_name(mut self, name: &str) -> Self {
         self.config.display_name = name.to_string();
@@ -551,6 +1012,14 @@ mod tests {
         
         assert!(node1.can_collaborate_with(&node2));
         assert!(node2.can_collaborate_with(&node1));
+        
+        // A peer that keeps failing is no longer collaborated with
+        let mut node1 = node1;
+        for _ in 0..5 {
+            node1.record_peer_interaction(&node2.id().as_string(), InteractionOutcome::Timeout);
+        }
+        assert!(!node1.can_collaborate_with(&node2));
+        assert!(node2.can_collaborate_with(&node1));
     }
     
     #[test]
@@ -591,4 +1060,149 @@ mod tests {
         assert!(SecurityLevel::Confidential < SecurityLevel::Secret);
         assert!(SecurityLevel::Secret < SecurityLevel::TopSecret);
     }
+    
+    #[test]
+    fn test_capability_downgrade() {
+        let mut node = NodeBuilder::new()
+            .with_capabilities(vec![
+                NodeCapability::ResourceStorage,
+                NodeCapability::AttributionTracking,
+                NodeCapability::AuditLogging,
+                NodeCapability::Collaboration,
+            ])
+            .build();
+        let mut events = node.subscribe_events();
+        let error = NodeError::Generic("storage backend unavailable".to_string());
+        
+        let decision = node.on_capability_failure(NodeCapability::ResourceStorage, &error);
+        assert_eq!(decision, CapabilityDowngradeDecision::Disable(vec![
+            NodeCapability::ResourceStorage,
+            NodeCapability::AttributionTracking,
+            NodeCapability::AuditLogging,
+        ]));
+        assert!(!node.is_capability_active(NodeCapability::AttributionTracking));
+        assert!(node.is_capability_active(NodeCapability::Collaboration));
+        assert_eq!(node.get_node_info().capabilities, vec![NodeCapability::Collaboration]);
+        assert_eq!(events.try_recv().unwrap(), NodeEvent::CapabilityDowngraded {
+            capability: NodeCapability::ResourceStorage,
+            reason: error.to_string(),
+            affected_dependents: vec![NodeCapability::AttributionTracking, NodeCapability::AuditLogging],
+        });
+        
+        // A dependent recovering on its own does not outlive its dependency
+        assert!(node.restore_capability(NodeCapability::AttributionTracking).is_empty());
+        assert_eq!(node.restore_capability(NodeCapability::ResourceStorage).len(), 3);
+        assert!(node.is_capability_active(NodeCapability::AttributionTracking));
+        
+        // Retries run out before disabling; propagation leaves everything active
+        node.set_downgrade_policy(NodeCapability::Collaboration, CapabilityDowngradePolicy::Retry {
+            delay: Duration::from_secs(1),
+            max_attempts: 2,
+        });
+        assert_eq!(
+            node.on_capability_failure(NodeCapability::Collaboration, &error),
+            CapabilityDowngradeDecision::Retry(Duration::from_secs(1))
+        );
+        assert_eq!(
+            node.on_capability_failure(NodeCapability::Collaboration, &error),
+            CapabilityDowngradeDecision::Disable(vec![NodeCapability::Collaboration])
+        );
+        node.config.downgrade_strategy = CapabilityDowngradePolicy::Propagate;
+        assert_eq!(
+            node.on_capability_failure(NodeCapability::ResourceStorage, &error),
+            CapabilityDowngradeDecision::Propagate
+        );
+        assert!(node.is_capability_active(NodeCapability::ResourceStorage));
+    }
+    
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+    
+    #[test]
+    fn test_builder_from_env() {
+        let node = NodeBuilder::from_lookup(env(&[
+            (ENV_NODE_NAME, "edge-7"),
+            (ENV_NODE_TYPE, "ai:llm:local-llama"),
+            (ENV_ORGANIZATION, "acme"),
+            (ENV_SECURITY_LEVEL, "Confidential"),
+            (ENV_CAPABILITIES, "code_generation, DATAANALYSIS,custom:gpu-inference"),
+        ])).unwrap().build();
+        
+        assert_eq!(node.display_name(), "edge-7");
+        assert_eq!(node.organization_id(), "acme");
+        assert_eq!(node.node_type(), &NodeType::AI(AIType::LLM("local-llama".to_string())));
+        assert_eq!(node.security_level(), &SecurityLevel::Confidential);
+        assert_eq!(node.capabilities(), &[
+            NodeCapability::CodeGeneration,
+            NodeCapability::DataAnalysis,
+            NodeCapability::Custom("gpu-inference".to_string()),
+        ]);
+    }
+    
+    #[test]
+    fn test_builder_from_env_defaults_and_errors() {
+        let node = NodeBuilder::from_lookup(env(&[(ENV_NODE_NAME, "  ")])).unwrap().build();
+        let defaults = NodeConfig::default();
+        assert_eq!(node.display_name(), defaults.display_name);
+        assert_eq!(node.capabilities(), defaults.capabilities.as_slice());
+        
+        let system = NodeBuilder::from_lookup(env(&[(ENV_NODE_TYPE, "System:IoT")])).unwrap().build();
+        assert_eq!(system.node_type(), &NodeType::System(SystemType::IoTDevice));
+        
+        let err = NodeBuilder::from_lookup(env(&[(ENV_NODE_TYPE, "robot")])).err().unwrap();
+        assert!(matches!(err, NodeError::InvalidField { field: "node_type", ref reason } if reason == "unknown type"));
+        
+        let err = NodeBuilder::from_lookup(env(&[(ENV_CAPABILITIES, "Telepathy")])).err().unwrap();
+        assert!(matches!(err, NodeError::InvalidField { field: "capabilities", .. }));
+    }
+    
+    #[test]
+    fn test_peer_scoring() {
+        let mut node = BasicNode::new(NodeConfig::default());
+        let mut events = node.subscribe_events();
+        assert_eq!(node.peer_score("unknown"), PeerScore::default());
+        assert!(node.is_peer_eligible("unknown"));
+        
+        for _ in 0..5 {
+            node.record_peer_interaction("fast", InteractionOutcome::Success { latency_ms: 20 });
+            node.record_peer_interaction("slow", InteractionOutcome::Success { latency_ms: 2_000 });
+        }
+        node.record_peer_interaction("flaky", InteractionOutcome::Timeout);
+        node.record_peer_interaction("flaky", InteractionOutcome::Failure("refused".to_string()));
+        node.record_peer_interaction("flaky", InteractionOutcome::Failure("refused".to_string()));
+        
+        let ranked: Vec<String> = node.ranked_peers().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ranked, vec!["fast", "slow", "flaky"]);
+        assert_eq!(node.peer_score("fast").interactions, 5);
+        assert!(events.try_recv().is_err());
+        
+        // Repeated failures push a peer below the operations threshold
+        for _ in 0..3 {
+            node.record_peer_interaction("flaky", InteractionOutcome::Timeout);
+        }
+        assert!(!node.is_peer_eligible("flaky"));
+        let eligible: Vec<String> = node.eligible_peers().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(eligible, vec!["fast", "slow"]);
+        match events.try_recv().unwrap() {
+            NodeEvent::PeerScoreChanged { peer_id, previous_score, new_score } => {
+                assert_eq!(peer_id, "flaky");
+                assert!(previous_score >= 0.5 && new_score < 0.5);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(events.try_recv().is_err());
+        
+        // A 0 ms sample is a real sample, and failures are not latency samples
+        node.record_peer_interaction("local", InteractionOutcome::Success { latency_ms: 0 });
+        node.record_peer_interaction("local", InteractionOutcome::Success { latency_ms: 100 });
+        assert!((node.peer_score("local").avg_latency_ms - 20.0).abs() < 1e-9);
+        node.record_peer_interaction("late", InteractionOutcome::Timeout);
+        node.record_peer_interaction("late", InteractionOutcome::Success { latency_ms: 40 });
+        assert_eq!(node.peer_score("late").avg_latency_ms, 40.0);
+        assert_eq!(node.peer_score("late").latency_samples, 1);
+    }
 }
@@ -0,0 +1,244 @@
+//! Resource profiles for memory-constrained nodes
+//!
+//! A [`ResourceProfile`] picks coordinated in-memory bounds for the
+//! subsystems that keep history (security events, cost records, conflict
+//! caches, discovered nodes, pending acknowledgments). Evictions forced by
+//! those bounds are counted in [`EvictionCounters`] so callers can tell when
+//! history is being lost.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Memory profile a node runs under
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ResourceProfile {
+    /// Default bounds, suited to desktops and servers
+    #[default]
+    Standard,
+    /// Reduced bounds for small edge and embedded devices
+    Constrained,
+}
+
+impl ResourceProfile {
+    /// In-memory bounds for this profile
+    pub fn limits(&self) -> ResourceLimits {
+        match self {
+            ResourceProfile::Standard => ResourceLimits {
+                max_security_events: 10000,
+                max_cost_records: 10000,
+                conflict_cache_size: 1000,
+                max_discovered_nodes: None,
+                max_pending_acks: None,
+                max_message_size: None,
+            },
+            ResourceProfile::Constrained => ResourceLimits {
+                max_security_events: 256,
+                max_cost_records: 256,
+                conflict_cache_size: 16,
+                max_discovered_nodes: Some(64),
+                max_pending_acks: Some(32),
+                max_message_size: Some(64 * 1024),
+            },
+        }
+    }
+}
+
+/// In-memory bounds derived from a [`ResourceProfile`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Security events kept by the security system
+    pub max_security_events: usize,
+    /// Cost records kept by the financial tracker
+    pub max_cost_records: usize,
+    /// Entries kept in each git conflict cache
+    pub conflict_cache_size: usize,
+    /// Nodes kept in the discovery registry (unbounded if None)
+    pub max_discovered_nodes: Option<usize>,
+    /// Messages awaiting acknowledgment (unbounded if None)
+    pub max_pending_acks: Option<usize>,
+    /// Cap on message size in bytes (configured value is kept if None)
+    pub max_message_size: Option<usize>,
+}
+
+/// Structures whose entries can be evicted by resource bounds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum EvictionKind {
+    /// Security events dropped from the in-memory log
+    SecurityEvent,
+    /// Cost records dropped from the financial tracker
+    CostRecord,
+    /// Entries dropped from the git conflict and merge preview caches
+    ConflictCache,
+    /// Nodes dropped from the discovery registry
+    DiscoveredNode,
+    /// Messages dropped while awaiting acknowledgment
+    PendingAck,
+}
+
+/// Counts of entries evicted by resource bounds
+///
+/// Share one instance (behind an `Arc`) across subsystems to see all
+/// evictions for a node in one place.
+#[derive(Debug, Default)]
+pub struct EvictionCounters {
+    security_events: AtomicU64,
+    cost_records: AtomicU64,
+    conflict_cache: AtomicU64,
+    discovered_nodes: AtomicU64,
+    pending_acks: AtomicU64,
+}
+
+impl EvictionCounters {
+    /// Create zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn counter(&self, kind: EvictionKind) -> &AtomicU64 {
+        match kind {
+            EvictionKind::SecurityEvent => &self.security_events,
+            EvictionKind::CostRecord => &self.cost_records,
+            EvictionKind::ConflictCache => &self.conflict_cache,
+            EvictionKind::DiscoveredNode => &self.discovered_nodes,
+            EvictionKind::PendingAck => &self.pending_acks,
+        }
+    }
+
+    /// Record `count` evictions of the given kind
+    pub fn record(&self, kind: EvictionKind, count: u64) {
+        if count > 0 {
+            self.counter(kind).fetch_add(count, Ordering::Relaxed);
+        }
+    }
+
+    /// Evictions recorded for the given kind
+    pub fn get(&self, kind: EvictionKind) -> u64 {
+        self.counter(kind).load(Ordering::Relaxed)
+    }
+
+    /// Evictions recorded across all kinds
+    pub fn total(&self) -> u64 {
+        [
+            EvictionKind::SecurityEvent,
+            EvictionKind::CostRecord,
+            EvictionKind::ConflictCache,
+            EvictionKind::DiscoveredNode,
+            EvictionKind::PendingAck,
+        ]
+        .iter()
+        .map(|kind| self.get(*kind))
+        .sum()
+    }
+}
+
+/// Approximate memory held by one component
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComponentFootprint {
+    /// Component name
+    pub component: String,
+    /// Number of entries held
+    pub entries: usize,
+    /// Approximate bytes held by those entries
+    pub approx_bytes: usize,
+}
+
+impl ComponentFootprint {
+    /// Footprint of `entries` values of type `T`, ignoring heap contents
+    pub fn of<T>(component: &str, entries: usize) -> Self {
+        Self {
+            component: component.to_string(),
+            entries,
+            approx_bytes: entries * std::mem::size_of::<T>(),
+        }
+    }
+}
+
+/// Components that can report their approximate memory usage
+#[async_trait::async_trait]
+pub trait MemoryFootprint: Send + Sync {
+    /// Approximate memory currently held
+    async fn memory_footprint(&self) -> ComponentFootprint;
+}
+
+/// Approximate memory usage summed over components
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryEstimate {
+    /// Per-component footprints
+    pub components: Vec<ComponentFootprint>,
+}
+
+impl MemoryEstimate {
+    /// Total approximate bytes across components
+    pub fn total_bytes(&self) -> usize {
+        self.components.iter().map(|c| c.approx_bytes).sum()
+    }
+
+    /// Total entries across components
+    pub fn total_entries(&self) -> usize {
+        self.components.iter().map(|c| c.entries).sum()
+    }
+}
+
+/// Sum the approximate memory usage of the given components
+///
+/// Sizes are shallow (`size_of` per entry), so strings and nested
+/// collections are undercounted; use this for trends, not exact accounting.
+pub async fn current_memory_estimate(components: &[&dyn MemoryFootprint]) -> MemoryEstimate {
+    let mut estimate = MemoryEstimate::default();
+    for component in components {
+        estimate.components.push(component.memory_footprint().await);
+    }
+    estimate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(usize);
+
+    #[async_trait::async_trait]
+    impl MemoryFootprint for Fixed {
+        async fn memory_footprint(&self) -> ComponentFootprint {
+            ComponentFootprint::of::<u64>("fixed", self.0)
+        }
+    }
+
+    #[test]
+    fn test_constrained_limits_are_tighter() {
+        let standard = ResourceProfile::Standard.limits();
+        let constrained = ResourceProfile::Constrained.limits();
+
+        assert_eq!(standard.max_security_events, 10000);
+        assert_eq!(standard.max_discovered_nodes, None);
+        assert!(constrained.max_security_events < standard.max_security_events);
+        assert!(constrained.max_cost_records < standard.max_cost_records);
+        assert!(constrained.conflict_cache_size < standard.conflict_cache_size);
+        assert!(constrained.max_discovered_nodes.is_some());
+        assert!(constrained.max_pending_acks.is_some());
+        assert!(constrained.max_message_size.is_some());
+    }
+
+    #[test]
+    fn test_eviction_counters() {
+        let counters = EvictionCounters::new();
+        counters.record(EvictionKind::SecurityEvent, 3);
+        counters.record(EvictionKind::DiscoveredNode, 1);
+        counters.record(EvictionKind::CostRecord, 0);
+
+        assert_eq!(counters.get(EvictionKind::SecurityEvent), 3);
+        assert_eq!(counters.get(EvictionKind::CostRecord), 0);
+        assert_eq!(counters.total(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_current_memory_estimate_sums_components() {
+        let a = Fixed(2);
+        let b = Fixed(3);
+        let estimate = current_memory_estimate(&[&a, &b]).await;
+
+        assert_eq!(estimate.components.len(), 2);
+        assert_eq!(estimate.total_entries(), 5);
+        assert_eq!(estimate.total_bytes(), 5 * std::mem::size_of::<u64>());
+    }
+}
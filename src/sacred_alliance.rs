@@ -6,9 +6,19 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
+use crate::alliance_progression::{AllianceAssessor, AllianceSignals, LevelAssessment};
+use crate::ide::screen_sharing::ScreenShareUpdate;
+use crate::mesh::CeremonyStatus;
+use crate::storage::{AccessControl as StorageAccessControl, Storage};
+use crate::WeaveMeshError;
+
+/// Identifier of an alliance message
+pub type MessageId = Uuid;
+
 /// Sacred Alliance participation level
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SacredAllianceLevel {
@@ -90,7 +100,7 @@ pub enum PresenceStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AllianceMessage {
     /// Message identifier
-    pub id: Uuid,
+    pub id: MessageId,
     /// Sender of the message
     pub sender: String,
     /// Message content
@@ -99,6 +109,91 @@ pub struct AllianceMessage {
     pub timestamp: DateTime<Utc>,
     /// Message metadata
     pub metadata: HashMap<String, String>,
+    /// Message this one replies to, if any
+    #[serde(default)]
+    pub reply_to: Option<MessageId>,
+    /// Channel this message was federated from, if any
+    #[serde(default)]
+    pub forwarded_from: Option<String>,
+}
+
+impl AllianceMessage {
+    /// Metadata key holding the participant that last forwarded the message
+    pub const FORWARDED_BY: &'static str = "forwarded_by";
+    /// Metadata key holding every participant the message was forwarded through
+    pub const FORWARDING_CHAIN: &'static str = "forwarding_chain";
+    
+    /// Forward this message through an intermediary participant
+    ///
+    /// `transform` replaces the content as delivered (e.g. a facilitator
+    /// rephrasing a technical message); the original author stays the sender.
+    pub fn forward(&self, via: &str, transform: Option<MessageContent>) -> ForwardedMessage {
+        ForwardedMessage {
+            transform_applied: transform.is_some(),
+            content: transform.unwrap_or_else(|| self.content.clone()),
+            original: self.clone(),
+            forwarded_by: via.to_string(),
+            forwarded_at: Utc::now(),
+        }
+    }
+    
+    /// Participants this message has been forwarded through, oldest first
+    pub fn forwarding_chain(&self) -> Vec<&str> {
+        self.metadata.get(Self::FORWARDING_CHAIN)
+            .map(|chain| chain.split(',').filter(|hop| !hop.is_empty()).collect())
+            .unwrap_or_default()
+    }
+    
+    /// Participant that last forwarded this message, if any
+    pub fn forwarded_by(&self) -> Option<&str> {
+        self.metadata.get(Self::FORWARDED_BY).map(|via| via.as_str())
+    }
+}
+
+/// Message routed through an intermediary participant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardedMessage {
+    /// Message as written by its author
+    pub original: AllianceMessage,
+    /// Participant that forwarded the message
+    pub forwarded_by: String,
+    /// Whether the forwarder replaced the content
+    pub transform_applied: bool,
+    /// When the message was forwarded
+    pub forwarded_at: DateTime<Utc>,
+    /// Content as delivered
+    pub content: MessageContent,
+}
+
+impl ForwardedMessage {
+    /// Convert into the message delivered to the channel
+    ///
+    /// The original author remains the sender; the forwarding hop is
+    /// appended to the message's forwarding chain.
+    pub fn into_message(self) -> AllianceMessage {
+        let mut chain: Vec<String> = self.original.forwarding_chain()
+            .into_iter()
+            .map(|hop| hop.to_string())
+            .collect();
+        chain.push(self.forwarded_by.clone());
+        
+        let mut metadata = self.original.metadata.clone();
+        metadata.insert(AllianceMessage::FORWARDED_BY.to_string(), self.forwarded_by);
+        metadata.insert(AllianceMessage::FORWARDING_CHAIN.to_string(), chain.join(","));
+        metadata.insert("original_message_id".to_string(), self.original.id.to_string());
+        metadata.insert("forwarded_at".to_string(), self.forwarded_at.to_rfc3339());
+        metadata.insert("transform_applied".to_string(), self.transform_applied.to_string());
+        
+        AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: self.original.sender,
+            content: self.content,
+            timestamp: self.original.timestamp,
+            metadata,
+            reply_to: self.original.reply_to,
+            forwarded_from: self.original.forwarded_from,
+        }
+    }
 }
 
 /// Content of an alliance message
@@ -110,8 +205,62 @@ pub enum MessageContent {
     Ceremony(BasicCeremonyAction),
     /// Code or technical content
     Code(CodeContent),
+    /// Code too large to send inline, stored as a resource
+    CodeReference(CodeReference),
     /// Presence update
     Presence(PresenceUpdate),
+    /// Approval request, decision or outcome
+    Approval(ApprovalMessage),
+    /// Shared editor state; delivered live but never kept in history
+    ScreenShare(ScreenShareUpdate),
+}
+
+/// Structured approval traffic for operations that need a human decision
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ApprovalMessage {
+    /// A ticket awaiting a decision
+    Request {
+        ticket_id: Uuid,
+        operation: String,
+        estimated_cost: u64,
+        currency: String,
+        requester: String,
+        expires_at: DateTime<Utc>,
+    },
+    /// A participant's decision on a ticket
+    Decision {
+        ticket_id: Uuid,
+        approve: bool,
+        note: Option<String>,
+    },
+    /// Final outcome of a ticket, attributed to the decider
+    Resolved {
+        ticket_id: Uuid,
+        approved: bool,
+        decided_by: String,
+    },
+    /// A decision was ignored because its sender may not decide
+    Unauthorized {
+        ticket_id: Uuid,
+        participant: String,
+    },
+    /// The ticket expired without a decision
+    Expired {
+        ticket_id: Uuid,
+    },
+}
+
+impl ApprovalMessage {
+    /// Ticket this message refers to
+    pub fn ticket_id(&self) -> Uuid {
+        match self {
+            ApprovalMessage::Request { ticket_id, .. }
+            | ApprovalMessage::Decision { ticket_id, .. }
+            | ApprovalMessage::Resolved { ticket_id, .. }
+            | ApprovalMessage::Unauthorized { ticket_id, .. }
+            | ApprovalMessage::Expired { ticket_id } => *ticket_id,
+        }
+    }
 }
 
 /// Basic ceremonial action in the alliance
@@ -125,6 +274,86 @@ pub struct BasicCeremonyAction {
     pub parameters: HashMap<String, String>,
 }
 
+impl BasicCeremonyAction {
+    /// Action type for deferring a ceremony
+    pub const DEFER: &'static str = "defer";
+    
+    /// Action type for completing a ceremony
+    pub const COMPLETE: &'static str = "complete";
+    
+    /// Parameter naming the ceremony an action belongs to
+    pub const CEREMONY_ID: &'static str = "ceremony_id";
+    
+    /// Create an action that defers a ceremony without cancelling it
+    pub fn defer(
+        ceremony_id: &str,
+        reason: String,
+        resume_at: Option<DateTime<Utc>>,
+        defer_until_participant: Option<String>,
+    ) -> Self {
+        let mut parameters = HashMap::new();
+        parameters.insert(Self::CEREMONY_ID.to_string(), ceremony_id.to_string());
+        if let Some(resume_at) = resume_at {
+            parameters.insert("resume_at".to_string(), resume_at.to_rfc3339());
+        }
+        if let Some(participant) = defer_until_participant {
+            parameters.insert("defer_until_participant".to_string(), participant);
+        }
+        
+        Self {
+            action_type: Self::DEFER.to_string(),
+            description: reason,
+            parameters,
+        }
+    }
+    
+    /// Ceremony this action belongs to, if any
+    pub fn ceremony_id(&self) -> Option<&str> {
+        self.parameters.get(Self::CEREMONY_ID).map(String::as_str)
+    }
+    
+    /// Parse this action as a deferral
+    pub fn deferral(&self) -> Option<CeremonyDeferral> {
+        if self.action_type != Self::DEFER {
+            return None;
+        }
+        
+        Some(CeremonyDeferral {
+            reason: self.description.clone(),
+            resume_at: self.parameters.get("resume_at")
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc)),
+            defer_until_participant: self.parameters.get("defer_until_participant").cloned(),
+        })
+    }
+}
+
+/// Conditions under which a deferred ceremony resumes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CeremonyDeferral {
+    /// Why the ceremony was deferred
+    pub reason: String,
+    /// Resume automatically at this time
+    pub resume_at: Option<DateTime<Utc>>,
+    /// Resume once this participant rejoins
+    pub defer_until_participant: Option<String>,
+}
+
+/// Ceremony tracked by a channel, including its intermediate state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelCeremony {
+    /// Ceremony identifier
+    pub ceremony_id: String,
+    /// Current status
+    pub status: CeremonyStatus,
+    /// Votes cast so far, keyed by participant
+    pub votes: HashMap<String, String>,
+    /// Messages exchanged as part of the ceremony
+    pub messages: Vec<Uuid>,
+    /// Active deferral, if the ceremony is deferred
+    pub deferral: Option<CeremonyDeferral>,
+}
+
 /// Code content with collaborative context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeContent {
@@ -138,6 +367,69 @@ pub struct CodeContent {
     pub intent: CollaborationIntent,
 }
 
+/// Lightweight stand-in for a large [`CodeContent`] kept in storage
+///
+/// Members of the channel fetch the full content with
+/// [`BasicSacredAllianceChannel::fetch_code`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeReference {
+    /// Storage resource holding the full `CodeContent`
+    pub resource_id: String,
+    /// First lines of the code with a truncation marker
+    pub preview: String,
+    /// Size of the code in bytes
+    pub size: usize,
+    /// Number of lines in the code
+    pub line_count: usize,
+    /// Programming language
+    pub language: String,
+    /// Explanation or context
+    pub explanation: Option<String>,
+    /// Collaboration intent
+    pub intent: CollaborationIntent,
+}
+
+/// Longest preview kept in a reference, in characters
+const MAX_PREVIEW_CHARS: usize = 2000;
+
+impl CodeReference {
+    /// First `max_lines` lines of `code`, followed by a marker naming how
+    /// many lines were left out, written as a comment in `language`
+    pub fn preview(code: &str, language: &str, max_lines: usize) -> String {
+        let total = code.lines().count();
+        let mut preview: String = code.lines().take(max_lines).collect::<Vec<_>>().join("\n");
+        let mut shown = total.min(max_lines);
+        if preview.chars().count() > MAX_PREVIEW_CHARS {
+            preview = preview.chars().take(MAX_PREVIEW_CHARS).collect();
+            shown = preview.lines().count().saturating_sub(1);
+        }
+        if shown < total {
+            let note = format!("... {} more lines", total - shown);
+            let marker = match language.to_ascii_lowercase().as_str() {
+                "python" | "py" | "ruby" | "rb" | "shell" | "sh" | "bash" | "yaml" | "yml" | "toml" | "r" | "perl" => format!("# {}", note),
+                "sql" | "lua" | "haskell" | "hs" => format!("-- {}", note),
+                "html" | "xml" | "svg" | "markdown" | "md" => format!("<!-- {} -->", note),
+                "css" => format!("/* {} */", note),
+                "diff" | "patch" | "text" | "txt" | "" => note,
+                _ => format!("// {}", note),
+            };
+            preview.push('\n');
+            preview.push_str(&marker);
+        }
+        preview
+    }
+}
+
+/// When receiving runtimes fetch referenced code
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CodeDelivery {
+    /// Only when a participant asks for it
+    #[default]
+    Lazy,
+    /// As soon as the reference is delivered
+    Eager,
+}
+
 /// Intent behind code sharing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CollaborationIntent {
@@ -173,6 +465,30 @@ pub struct ChannelConfig {
     pub auto_archive: bool,
     /// Archive threshold (days)
     pub archive_after_days: u32,
+    /// Maximum number of replies between a thread root and its deepest reply
+    #[serde(default = "default_max_thread_depth")]
+    pub max_thread_depth: usize,
+    /// Largest code sent inline; bigger code is stored and referenced
+    #[serde(default = "default_max_inline_code_bytes")]
+    pub max_inline_code_bytes: usize,
+    /// Lines of code shown in a reference's preview
+    #[serde(default = "default_code_preview_lines")]
+    pub code_preview_lines: usize,
+    /// When referenced code is fetched for receivers
+    #[serde(default)]
+    pub code_delivery: CodeDelivery,
+}
+
+fn default_max_thread_depth() -> usize {
+    32
+}
+
+fn default_max_inline_code_bytes() -> usize {
+    16 * 1024
+}
+
+fn default_code_preview_lines() -> usize {
+    20
 }
 
 impl Default for ChannelConfig {
@@ -181,6 +497,10 @@ impl Default for ChannelConfig {
             max_participants: 10,
             auto_archive: true,
             archive_after_days: 30,
+            max_thread_depth: default_max_thread_depth(),
+            max_inline_code_bytes: default_max_inline_code_bytes(),
+            code_preview_lines: default_code_preview_lines(),
+            code_delivery: CodeDelivery::Lazy,
         }
     }
 }
@@ -196,6 +516,469 @@ pub struct AllianceStatistics {
     pub total_messages: usize,
     /// Distribution of message types
     pub message_type_distribution: HashMap<String, usize>,
+    /// Share of ceremonies with activity that were completed, if any had activity
+    #[serde(default)]
+    pub ceremony_completion_rate: Option<f64>,
+    /// Share of earlier participants still taking part, if there were any
+    #[serde(default)]
+    pub participant_retention_rate: Option<f64>,
+    /// Mean quality score of non-presence messages in `[0, 1]`, if there were any
+    #[serde(default)]
+    pub message_quality: Option<f64>,
+    /// Messages forwarded to federated channels
+    #[serde(default)]
+    pub federated_messages_forwarded: u64,
+}
+
+/// Overall direction of collaboration quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendDirection {
+    Improving,
+    Stable,
+    Degrading,
+    /// Too few windows with activity to fit a trend
+    InsufficientData,
+}
+
+/// Linear trends of collaboration metrics across statistics windows
+///
+/// Slopes are per window; positive slopes mean improvement.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatisticsTrend {
+    pub ceremony_completion_rate_slope: f64,
+    pub participant_retention_rate_slope: f64,
+    pub message_quality_slope: f64,
+    /// Mean coefficient of determination of the fitted metrics, in `[0, 1]`
+    pub trend_significance: f64,
+    /// Metrics with enough windows of data to be fitted
+    pub fitted_metrics: usize,
+    /// Window snapshots, oldest first
+    pub windows: Vec<AllianceStatistics>,
+}
+
+impl StatisticsTrend {
+    /// Fewest windows with data a metric needs to be fitted
+    pub const MIN_WINDOWS: usize = 3;
+    /// Combined slope below which the trend counts as stable
+    pub const STABLE_SLOPE: f64 = 0.01;
+    /// Significance below which the trend counts as stable
+    pub const MIN_SIGNIFICANCE: f64 = 0.5;
+    
+    /// Fit trends to a series of snapshots, oldest first
+    pub fn from_windows(windows: Vec<AllianceStatistics>) -> Self {
+        let metrics: [fn(&AllianceStatistics) -> Option<f64>; 3] = [
+            |w| w.ceremony_completion_rate,
+            |w| w.participant_retention_rate,
+            |w| w.message_quality,
+        ];
+        let fits: Vec<Option<(f64, f64)>> = metrics.iter()
+            .map(|metric| {
+                let points: Vec<(f64, f64)> = windows.iter()
+                    .enumerate()
+                    .filter_map(|(i, w)| metric(w).map(|y| (i as f64, y)))
+                    .collect();
+                (points.len() >= Self::MIN_WINDOWS).then(|| linear_fit(&points))
+            })
+            .collect();
+        
+        let fitted: Vec<f64> = fits.iter().flatten().map(|(_, r_squared)| *r_squared).collect();
+        let slope = |i: usize| fits[i].map_or(0.0, |(slope, _)| slope);
+        Self {
+            ceremony_completion_rate_slope: slope(0),
+            participant_retention_rate_slope: slope(1),
+            message_quality_slope: slope(2),
+            trend_significance: if fitted.is_empty() { 0.0 } else { fitted.iter().sum::<f64>() / fitted.len() as f64 },
+            fitted_metrics: fitted.len(),
+            windows,
+        }
+    }
+    
+    /// Single summary of the fitted slopes
+    pub fn overall_direction(&self) -> TrendDirection {
+        if self.fitted_metrics == 0 {
+            return TrendDirection::InsufficientData;
+        }
+        let combined = self.ceremony_completion_rate_slope
+            + self.participant_retention_rate_slope
+            + self.message_quality_slope;
+        if self.trend_significance < Self::MIN_SIGNIFICANCE || combined.abs() < Self::STABLE_SLOPE {
+            TrendDirection::Stable
+        } else if combined > 0.0 {
+            TrendDirection::Improving
+        } else {
+            TrendDirection::Degrading
+        }
+    }
+}
+
+/// Least-squares slope and coefficient of determination of `points`
+fn linear_fit(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let syy: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    if sxx == 0.0 {
+        return (0.0, 0.0);
+    }
+    let slope = sxy / sxx;
+    // A flat series is perfectly explained by a flat line
+    let r_squared = if syy == 0.0 { 1.0 } else { (sxy * sxy) / (sxx * syy) };
+    (slope, r_squared)
+}
+
+/// Quality score of a message in `[0, 1]`; presence updates and screen shares have none
+fn message_quality(content: &MessageContent) -> Option<f64> {
+    match content {
+        MessageContent::Text(text) => Some((text.split_whitespace().count() as f64 / 20.0).min(1.0)),
+        MessageContent::Code(code) => Some(if code.explanation.is_some() { 1.0 } else { 0.5 }),
+        MessageContent::CodeReference(code) => Some(if code.explanation.is_some() { 1.0 } else { 0.5 }),
+        MessageContent::Ceremony(_) | MessageContent::Approval(_) => Some(1.0),
+        MessageContent::Presence(_) | MessageContent::ScreenShare(_) => None,
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Errors specific to Sacred Alliance channels
+#[derive(Debug, thiserror::Error)]
+pub enum AllianceError {
+    /// The channel has been archived and no longer accepts messages
+    #[error("Channel is archived: {0}")]
+    ChannelArchived(String),
+    /// Referenced content is only available to members of the channel
+    #[error("{participant} is not a member of channel {channel_id}")]
+    NotAMember { participant: String, channel_id: String },
+    /// The channel is not registered with a federation
+    #[error("Channel is not federated: {0}")]
+    NotFederated(String),
+    /// Federating would forward messages back to their source
+    #[error("Federating {source_channel} with {target_channel} would form a cycle")]
+    FederationCycle { source_channel: String, target_channel: String },
+}
+
+/// Redaction policy applied to messages at send time and on export
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessagePolicy {
+    /// Terms that must never appear in stored or exported messages
+    pub redacted_terms: Vec<String>,
+    /// Replacement text for redacted terms
+    pub replacement: String,
+}
+
+impl MessagePolicy {
+    /// Create a policy redacting the given terms
+    pub fn redacting(terms: Vec<String>) -> Self {
+        Self {
+            redacted_terms: terms,
+            replacement: "[REDACTED]".to_string(),
+        }
+    }
+    
+    /// Apply the policy to a message, returning the redacted copy
+    pub fn apply(&self, message: &AllianceMessage) -> AllianceMessage {
+        let mut redacted = message.clone();
+        let mut changed = false;
+        
+        match &mut redacted.content {
+            MessageContent::Text(text) => changed |= self.redact(text),
+            MessageContent::Code(code) => {
+                changed |= self.redact(&mut code.code);
+                if let Some(explanation) = code.explanation.as_mut() {
+                    changed |= self.redact(explanation);
+                }
+            }
+            MessageContent::CodeReference(code) => {
+                changed |= self.redact(&mut code.preview);
+                if let Some(explanation) = code.explanation.as_mut() {
+                    changed |= self.redact(explanation);
+                }
+            }
+            MessageContent::Ceremony(action) => changed |= self.redact(&mut action.description),
+            MessageContent::Presence(update) => {
+                if let Some(text) = update.message.as_mut() {
+                    changed |= self.redact(text);
+                }
+            }
+            MessageContent::Approval(approval) => match approval {
+                ApprovalMessage::Request { operation, .. } => changed |= self.redact(operation),
+                ApprovalMessage::Decision { note: Some(note), .. } => changed |= self.redact(note),
+                _ => {}
+            },
+            MessageContent::ScreenShare(_) => {}
+        }
+        
+        if changed {
+            redacted.metadata.insert("redacted".to_string(), "true".to_string());
+        }
+        redacted
+    }
+    
+    fn redact(&self, text: &mut String) -> bool {
+        let mut changed = false;
+        for term in self.redacted_terms.iter().filter(|t| !t.is_empty()) {
+            if text.contains(term.as_str()) {
+                *text = text.replace(term.as_str(), &self.replacement);
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Time range of channel history to export
+#[derive(Debug, Clone, Default)]
+pub struct ExportRange {
+    /// Include messages at or after this time
+    pub start: Option<DateTime<Utc>>,
+    /// Include messages before this time
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl ExportRange {
+    /// Export the complete history
+    pub fn all() -> Self {
+        Self::default()
+    }
+    
+    /// Export messages in `[start, end)`
+    pub fn between(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self { start: Some(start), end: Some(end) }
+    }
+    
+    /// Check whether a timestamp falls inside the range
+    pub fn contains(&self, timestamp: &DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| *timestamp >= start)
+            && self.end.is_none_or(|end| *timestamp < end)
+    }
+}
+
+/// Output format for channel exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Structured JSON with every message and its metadata
+    Json,
+    /// Rendered Markdown transcript grouped by day
+    Markdown,
+}
+
+/// Full-fidelity structured export of a channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelExport {
+    /// Channel identifier
+    pub channel_id: String,
+    /// When the export was produced
+    pub exported_at: DateTime<Utc>,
+    /// Participants at export time
+    pub participants: Vec<Participant>,
+    /// Messages in chronological order
+    pub messages: Vec<AllianceMessage>,
+}
+
+/// Which messages a federation forwards, and how
+#[derive(Default)]
+pub struct MessageFederationFilter {
+    /// Message types forwarded (`text`, `code`, `ceremony`, ...); all if empty
+    pub allow_types: Vec<String>,
+    /// Message types never forwarded, even if allowed
+    pub deny_types: Vec<String>,
+    /// Rewrites each forwarded message before delivery
+    pub transform: Option<Box<dyn Fn(AllianceMessage) -> AllianceMessage + Send + Sync>>,
+}
+
+impl MessageFederationFilter {
+    /// Whether `message` passes the type lists
+    pub fn matches(&self, message: &AllianceMessage) -> bool {
+        let message_type = message_type_name(&message.content);
+        (self.allow_types.is_empty() || self.allow_types.iter().any(|t| t == message_type))
+            && !self.deny_types.iter().any(|t| t == message_type)
+    }
+}
+
+/// One directed federation between two channels
+struct FederationLink {
+    source: String,
+    target: String,
+    filter: MessageFederationFilter,
+}
+
+#[derive(Default)]
+struct FederationState {
+    channels: HashMap<String, std::sync::Weak<tokio::sync::RwLock<BasicSacredAllianceChannel>>>,
+    links: HashMap<Uuid, std::sync::Arc<FederationLink>>,
+    /// Forwarded messages whose target was busy, with their source and target
+    pending: Vec<PendingForward>,
+}
+
+/// A forwarded message waiting for its target channel
+struct PendingForward {
+    source: String,
+    target: String,
+    message: AllianceMessage,
+}
+
+/// Channels that may forward messages to each other
+///
+/// Forwarding happens as a message is sent. When a target channel is locked
+/// elsewhere at that moment, the message is queued and delivered on the
+/// next forward or by [`AllianceFederation::flush`].
+#[derive(Clone, Default)]
+pub struct AllianceFederation {
+    state: std::sync::Arc<std::sync::Mutex<FederationState>>,
+}
+
+impl AllianceFederation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Make `channel` available for federation under its ID
+    pub async fn register(&self, channel: std::sync::Arc<tokio::sync::RwLock<BasicSacredAllianceChannel>>) {
+        let channel_id = {
+            let mut guard = channel.write().await;
+            guard.federation = Some(self.clone());
+            guard.channel_id.clone()
+        };
+        self.state.lock().unwrap().channels.insert(channel_id, std::sync::Arc::downgrade(&channel));
+    }
+    
+    /// Add a link from `source` to `target`, refusing cycles
+    fn link(&self, source: &str, target: &str, filter: MessageFederationFilter) -> Result<FederationHandle> {
+        let mut state = self.state.lock().unwrap();
+        if !state.channels.contains_key(target) {
+            return Err(AllianceError::NotFederated(target.to_string()).into());
+        }
+        
+        // A path from the target back to the source would bring messages home
+        let mut reachable = HashSet::from([target.to_string()]);
+        let mut frontier = vec![target.to_string()];
+        while let Some(channel) = frontier.pop() {
+            for link in state.links.values().filter(|link| link.source == channel) {
+                if reachable.insert(link.target.clone()) {
+                    frontier.push(link.target.clone());
+                }
+            }
+        }
+        if reachable.contains(source) {
+            return Err(AllianceError::FederationCycle {
+                source_channel: source.to_string(),
+                target_channel: target.to_string(),
+            }.into());
+        }
+        
+        let link_id = Uuid::new_v4();
+        state.links.insert(link_id, std::sync::Arc::new(FederationLink {
+            source: source.to_string(),
+            target: target.to_string(),
+            filter,
+        }));
+        Ok(FederationHandle { federation: self.clone(), link_id })
+    }
+    
+    /// Forward a message sent in `source` along its links, returning how many were forwarded
+    fn forward(&self, source: &str, message: &AllianceMessage) -> u64 {
+        let links: Vec<_> = self.state.lock().unwrap().links.values()
+            .filter(|link| link.source == source && link.filter.matches(message))
+            .cloned()
+            .collect();
+        for link in &links {
+            let mut forwarded = message.clone();
+            forwarded.forwarded_from = Some(source.to_string());
+            if let Some(transform) = &link.filter.transform {
+                forwarded = transform(forwarded);
+            }
+            self.state.lock().unwrap().pending.push(PendingForward {
+                source: source.to_string(),
+                target: link.target.clone(),
+                message: forwarded,
+            });
+        }
+        self.deliver_pending();
+        links.len() as u64
+    }
+    
+    /// Whether messages sent in `source` are forwarded to `target`
+    fn is_linked(&self, source: &str, target: &str) -> bool {
+        self.state.lock().unwrap().links.values().any(|link| link.source == source && link.target == target)
+    }
+    
+    /// Registered channel with `channel_id`, if it still exists
+    fn channel(&self, channel_id: &str) -> Option<std::sync::Arc<tokio::sync::RwLock<BasicSacredAllianceChannel>>> {
+        self.state.lock().unwrap().channels.get(channel_id).and_then(|channel| channel.upgrade())
+    }
+    
+    /// Deliver queued messages to targets not locked elsewhere
+    fn deliver_pending(&self) {
+        let pending = std::mem::take(&mut self.state.lock().unwrap().pending);
+        for forward in pending {
+            let Some(channel) = self.channel(&forward.target) else {
+                continue;
+            };
+            let busy = match channel.try_write() {
+                Ok(mut guard) => {
+                    if let Err(e) = guard.deliver_federated(&forward.source, forward.message.clone()) {
+                        tracing::warn!("Failed to deliver federated message to {}: {}", forward.target, e);
+                    }
+                    false
+                }
+                Err(_) => true,
+            };
+            if busy {
+                self.state.lock().unwrap().pending.push(forward);
+            }
+        }
+    }
+    
+    /// Deliver every queued message, waiting for busy channels
+    pub async fn flush(&self) {
+        loop {
+            let pending = std::mem::take(&mut self.state.lock().unwrap().pending);
+            if pending.is_empty() {
+                return;
+            }
+            for forward in pending {
+                let Some(channel) = self.channel(&forward.target) else {
+                    continue;
+                };
+                let result = channel.write().await.deliver_federated(&forward.source, forward.message);
+                if let Err(e) = result {
+                    tracing::warn!("Failed to deliver federated message to {}: {}", forward.target, e);
+                }
+            }
+        }
+    }
+}
+
+/// A live federation; dropping it stops the forwarding
+pub struct FederationHandle {
+    federation: AllianceFederation,
+    link_id: Uuid,
+}
+
+impl Drop for FederationHandle {
+    fn drop(&mut self) {
+        self.federation.state.lock().unwrap().links.remove(&self.link_id);
+    }
+}
+
+/// Messages a slow feed subscriber may fall behind before skipping ahead
+const FEED_CAPACITY: usize = 64;
+
+/// Live stream of the messages a channel accepts
+#[derive(Clone)]
+pub struct ChannelFeed {
+    sender: broadcast::Sender<AllianceMessage>,
+}
+
+impl ChannelFeed {
+    /// Receive every message accepted from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AllianceMessage> {
+        self.sender.subscribe()
+    }
 }
 
 /// Basic Sacred Alliance channel implementation
@@ -208,6 +991,22 @@ pub struct BasicSacredAllianceChannel {
     history: Vec<AllianceMessage>,
     /// Channel configuration
     config: ChannelConfig,
+    /// Redaction policy applied to messages
+    message_policy: Option<MessagePolicy>,
+    /// Whether the channel has been archived
+    archived: bool,
+    /// Ceremonies started in this channel
+    ceremonies: HashMap<String, ChannelCeremony>,
+    /// Participant every message is routed through
+    forwarding_via: Option<String>,
+    /// Federation this channel is registered with
+    federation: Option<AllianceFederation>,
+    /// Messages forwarded to federated channels
+    federated_messages_forwarded: u64,
+    /// Accepted messages, for live subscribers
+    feed: broadcast::Sender<AllianceMessage>,
+    /// Level from the latest assessment
+    level: SacredAllianceLevel,
 }
 
 impl BasicSacredAllianceChannel {
@@ -218,9 +1017,53 @@ impl BasicSacredAllianceChannel {
             participants: Vec::new(),
             history: Vec::new(),
             config,
+            message_policy: None,
+            archived: false,
+            ceremonies: HashMap::new(),
+            forwarding_via: None,
+            federation: None,
+            federated_messages_forwarded: 0,
+            feed: broadcast::channel(FEED_CAPACITY).0,
+            level: SacredAllianceLevel::default(),
         }
     }
     
+    /// Live stream of accepted messages, including ones not kept in history
+    pub fn feed(&self) -> ChannelFeed {
+        ChannelFeed { sender: self.feed.clone() }
+    }
+    
+    /// Forward messages matching `filter` to the channel `other_channel_id`
+    ///
+    /// Both channels must be registered with the same [`AllianceFederation`].
+    /// The federation lasts until the returned handle is dropped.
+    pub fn federate_with(&self, other_channel_id: &str, filter: MessageFederationFilter) -> Result<FederationHandle> {
+        let federation = self.federation.as_ref()
+            .ok_or_else(|| AllianceError::NotFederated(self.channel_id.clone()))?;
+        federation.link(&self.channel_id, other_channel_id, filter)
+    }
+    
+    /// Route all messages through `via_participant`
+    ///
+    /// Messages from other participants are delivered as forwarded by the
+    /// intermediary. Messages the intermediary already forwarded are
+    /// delivered as-is; a message that passed through it earlier in its
+    /// chain is a forwarding loop and is dropped.
+    pub fn enable_forwarding(&mut self, via_participant: &str) {
+        self.forwarding_via = Some(via_participant.to_string());
+    }
+    
+    /// Set the redaction policy applied to sent and exported messages
+    pub fn with_message_policy(mut self, policy: MessagePolicy) -> Self {
+        self.message_policy = Some(policy);
+        self
+    }
+    
+    /// Check whether the channel has been archived
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+    
     /// Add a participant to the alliance
     pub fn add_participant(&mut self, participant: Participant) -> Result<()> {
         if self.participants.len() >= self.config.max_participants {
@@ -232,21 +1075,413 @@ impl BasicSacredAllianceChannel {
             return Err(anyhow::anyhow!("Participant already in alliance"));
         }
         
+        let participant_id = participant.id.clone();
         self.participants.push(participant);
+        self.resume_waiting_for(&participant_id);
         Ok(())
     }
     
     /// Send a message to the alliance
     pub fn send_message(&mut self, message: AllianceMessage) -> Result<()> {
+        if self.archived {
+            return Err(AllianceError::ChannelArchived(self.channel_id.clone()).into());
+        }
+        
         // Validate sender is a participant
         if !self.participants.iter().any(|p| p.id == message.sender) {
             return Err(anyhow::anyhow!("Sender not in alliance"));
         }
+        if let Some(parent_id) = message.reply_to {
+            self.check_reply(message.id, parent_id)?;
+        }
+        self.accept_message(message)
+    }
+    
+    /// Accept a message forwarded from the federated channel `source_channel`
+    ///
+    /// Its sender is a participant of the source channel, so membership is
+    /// not checked here; the link from the source to this channel is.
+    pub(crate) fn deliver_federated(&mut self, source_channel: &str, message: AllianceMessage) -> Result<()> {
+        if self.archived {
+            return Err(AllianceError::ChannelArchived(self.channel_id.clone()).into());
+        }
+        let linked = self.federation.as_ref().is_some_and(|federation| federation.is_linked(source_channel, &self.channel_id));
+        if !linked {
+            return Err(AllianceError::NotFederated(source_channel.to_string()).into());
+        }
+        self.accept_message(message)
+    }
+    
+    /// Record a message that passed the sender checks
+    fn accept_message(&mut self, message: AllianceMessage) -> Result<()> {
+        let message = match &self.message_policy {
+            Some(policy) => policy.apply(&message),
+            None => message,
+        };
+        
+        let message = match &self.forwarding_via {
+            Some(via) if message.sender != *via && message.forwarded_by() != Some(via.as_str()) => {
+                if message.forwarding_chain().contains(&via.as_str()) {
+                    tracing::warn!(
+                        "Dropping message {} in channel {}: already forwarded through {}",
+                        message.id, self.channel_id, via
+                    );
+                    return Ok(());
+                }
+                message.forward(via, None).into_message()
+            }
+            _ => message,
+        };
         
+        match &message.content {
+            MessageContent::Ceremony(action) => self.track_ceremony(&message, action),
+            MessageContent::Presence(update) => {
+                if let Some(p) = self.participants.iter_mut().find(|p| p.id == message.sender) {
+                    p.presence = update.status.clone();
+                }
+                if matches!(update.status, PresenceStatus::Active | PresenceStatus::Present) {
+                    self.resume_waiting_for(&message.sender);
+                }
+            }
+            _ => {}
+        }
+        
+        if let Some(federation) = &self.federation {
+            self.federated_messages_forwarded += federation.forward(&self.channel_id, &message);
+        }
+        if self.feed.receiver_count() > 0 {
+            // Subscribers dropping between the check and the send is not an error
+            let _ = self.feed.send(message.clone());
+        }
+        if matches!(message.content, MessageContent::ScreenShare(_)) {
+            return Ok(());
+        }
         self.history.push(message);
         Ok(())
     }
     
+    /// Send a message, storing oversized code as a resource first
+    ///
+    /// Code larger than `max_inline_code_bytes` is stored with access
+    /// limited to this channel, and the message carries a [`CodeReference`]
+    /// in its place.
+    pub async fn send_message_with_storage<S: Storage>(&mut self, storage: &mut S, mut message: AllianceMessage) -> Result<()> {
+        if self.archived {
+            return Err(AllianceError::ChannelArchived(self.channel_id.clone()).into());
+        }
+        if !self.participants.iter().any(|p| p.id == message.sender) {
+            return Err(anyhow::anyhow!("Sender not in alliance"));
+        }
+        
+        if let MessageContent::Code(code) = &message.content {
+            if code.code.len() > self.config.max_inline_code_bytes {
+                let code = match &self.message_policy {
+                    Some(policy) => match policy.apply(&message).content {
+                        MessageContent::Code(code) => code,
+                        _ => code.clone(),
+                    },
+                    None => code.clone(),
+                };
+                message.content = MessageContent::CodeReference(self.store_code(storage, &code).await?);
+            }
+        }
+        self.send_message(message)
+    }
+    
+    async fn store_code<S: Storage>(&self, storage: &mut S, code: &CodeContent) -> Result<CodeReference> {
+        let access_control = StorageAccessControl {
+            is_private: true,
+            allowed_nodes: self.participants.iter().map(|p| p.id.clone()).collect(),
+            allowed_groups: vec![self.channel_id.clone()],
+            is_public: false,
+        };
+        let resource_id = storage.store_resource(
+            format!("{}-code-{}", self.channel_id, Uuid::new_v4()),
+            serde_json::to_vec(code)?,
+            "application/json".to_string(),
+            access_control,
+            vec!["sacred-alliance".to_string(), "code".to_string()],
+        ).await?;
+        
+        Ok(CodeReference {
+            resource_id,
+            preview: CodeReference::preview(&code.code, &code.language, self.config.code_preview_lines),
+            size: code.code.len(),
+            line_count: code.code.lines().count(),
+            language: code.language.clone(),
+            explanation: code.explanation.clone(),
+            intent: code.intent.clone(),
+        })
+    }
+    
+    /// Fetch the full code behind a reference on behalf of `participant_id`
+    ///
+    /// Only current members of this channel may fetch, and only code that
+    /// was shared in this channel.
+    pub async fn fetch_code<S: Storage>(&self, storage: &S, participant_id: &str, reference: &CodeReference) -> Result<CodeContent> {
+        let not_a_member = || AllianceError::NotAMember {
+            participant: participant_id.to_string(),
+            channel_id: self.channel_id.clone(),
+        };
+        if !self.participants.iter().any(|p| p.id == participant_id) {
+            return Err(not_a_member().into());
+        }
+        
+        let resource = storage.get_resource(&reference.resource_id).await?;
+        if !resource.metadata.access_control.allowed_groups.contains(&self.channel_id) {
+            return Err(not_a_member().into());
+        }
+        Ok(serde_json::from_slice(&resource.content)?)
+    }
+    
+    /// Message as delivered to `participant_id`
+    ///
+    /// With eager delivery, referenced code is fetched and inlined; with
+    /// lazy delivery the reference is passed on unchanged.
+    pub async fn deliver<S: Storage>(&self, storage: &S, participant_id: &str, message: &AllianceMessage) -> Result<AllianceMessage> {
+        let mut delivered = message.clone();
+        if let (CodeDelivery::Eager, MessageContent::CodeReference(reference)) = (&self.config.code_delivery, &message.content) {
+            delivered.content = MessageContent::Code(self.fetch_code(storage, participant_id, reference).await?);
+        }
+        Ok(delivered)
+    }
+    
+    /// Get a ceremony tracked by this channel
+    pub fn get_ceremony(&self, ceremony_id: &str) -> Option<&ChannelCeremony> {
+        self.ceremonies.get(ceremony_id)
+    }
+    
+    /// Resume a deferred ceremony, keeping its votes and messages
+    pub fn resume_ceremony(&mut self, ceremony_id: &str) -> Result<()> {
+        let ceremony = self.ceremonies.get(ceremony_id)
+            .ok_or_else(|| anyhow::anyhow!("Ceremony not found: {}", ceremony_id))?;
+        
+        let deferral = match (&ceremony.status, &ceremony.deferral) {
+            (CeremonyStatus::Deferred { .. }, Some(deferral)) => deferral.clone(),
+            _ => return Err(anyhow::anyhow!("Ceremony is not deferred: {}", ceremony_id)),
+        };
+        
+        if let Some(participant_id) = &deferral.defer_until_participant {
+            if !self.is_participant_present(participant_id) {
+                return Err(anyhow::anyhow!(
+                    "Ceremony {} is waiting for participant {}", ceremony_id, participant_id
+                ));
+            }
+        }
+        
+        if let Some(ceremony) = self.ceremonies.get_mut(ceremony_id) {
+            ceremony.status = CeremonyStatus::InProgress;
+            ceremony.deferral = None;
+        }
+        Ok(())
+    }
+    
+    /// Spawn a background task that resumes a deferred ceremony at its `resume_at` time
+    pub fn spawn_auto_resume(
+        channel: std::sync::Arc<tokio::sync::RwLock<Self>>,
+        ceremony_id: String,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        let resume_at = channel.try_read().ok()?
+            .ceremonies.get(&ceremony_id)?
+            .deferral.as_ref()?
+            .resume_at?;
+        
+        Some(tokio::spawn(async move {
+            let delay = (resume_at - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(delay).await;
+            
+            if let Err(e) = channel.write().await.resume_ceremony(&ceremony_id) {
+                tracing::warn!("Failed to auto-resume ceremony {}: {}", ceremony_id, e);
+            }
+        }))
+    }
+    
+    /// Record a ceremony action against its ceremony
+    fn track_ceremony(&mut self, message: &AllianceMessage, action: &BasicCeremonyAction) {
+        let ceremony_id = match action.ceremony_id() {
+            Some(id) => id,
+            None => return,
+        };
+        
+        let ceremony = self.ceremonies.entry(ceremony_id.to_string())
+            .or_insert_with(|| ChannelCeremony {
+                ceremony_id: ceremony_id.to_string(),
+                status: CeremonyStatus::InProgress,
+                votes: HashMap::new(),
+                messages: Vec::new(),
+                deferral: None,
+            });
+        
+        ceremony.messages.push(message.id);
+        
+        if let Some(vote) = action.parameters.get("vote") {
+            ceremony.votes.insert(message.sender.clone(), vote.clone());
+        }
+        
+        if action.action_type == BasicCeremonyAction::COMPLETE {
+            ceremony.status = CeremonyStatus::Completed;
+            ceremony.deferral = None;
+        } else if let Some(deferral) = action.deferral() {
+            ceremony.status = CeremonyStatus::Deferred {
+                reason: deferral.reason.clone(),
+                expected_resume: deferral.resume_at,
+            };
+            ceremony.deferral = Some(deferral);
+        }
+    }
+    
+    /// Resume ceremonies waiting for a participant that has rejoined
+    fn resume_waiting_for(&mut self, participant_id: &str) {
+        let waiting: Vec<String> = self.ceremonies.values()
+            .filter(|c| c.deferral.as_ref()
+                .and_then(|d| d.defer_until_participant.as_deref()) == Some(participant_id))
+            .map(|c| c.ceremony_id.clone())
+            .collect();
+        
+        for ceremony_id in waiting {
+            let _ = self.resume_ceremony(&ceremony_id);
+        }
+    }
+    
+    /// Check whether a participant is in the channel and not away or offline
+    fn is_participant_present(&self, participant_id: &str) -> bool {
+        self.participants.iter().any(|p| {
+            p.id == participant_id
+                && matches!(p.presence, PresenceStatus::Active | PresenceStatus::Present)
+        })
+    }
+    
+    /// Export channel history within `range` in the requested format
+    pub fn export(&self, range: ExportRange, format: ExportFormat) -> Result<String> {
+        let mut messages: Vec<AllianceMessage> = self.history.iter()
+            .filter(|m| range.contains(&m.timestamp))
+            .map(|m| match &self.message_policy {
+                Some(policy) => policy.apply(m),
+                None => m.clone(),
+            })
+            .collect();
+        messages.sort_by_key(|m| m.timestamp);
+        
+        match format {
+            ExportFormat::Json => {
+                let export = ChannelExport {
+                    channel_id: self.channel_id.clone(),
+                    exported_at: Utc::now(),
+                    participants: self.participants.clone(),
+                    messages,
+                };
+                Ok(serde_json::to_string_pretty(&export)?)
+            }
+            ExportFormat::Markdown => Ok(self.render_markdown(&messages)),
+        }
+    }
+    
+    /// Freeze the channel, store its full history and release it from memory
+    ///
+    /// Returns the storage resource ID of the archived JSON export.
+    pub async fn archive<S: Storage>(&mut self, storage: &mut S) -> Result<String> {
+        if self.archived {
+            return Err(AllianceError::ChannelArchived(self.channel_id.clone()).into());
+        }
+        
+        let export = self.export(ExportRange::all(), ExportFormat::Json)?;
+        let resource_id = storage.store_resource(
+            format!("{}-archive.json", self.channel_id),
+            export.into_bytes(),
+            "application/json".to_string(),
+            StorageAccessControl::default(),
+            vec!["sacred-alliance".to_string(), "archive".to_string()],
+        ).await?;
+        
+        self.archived = true;
+        self.history = Vec::new();
+        Ok(resource_id)
+    }
+    
+    /// Render messages as a Markdown transcript grouped by day
+    fn render_markdown(&self, messages: &[AllianceMessage]) -> String {
+        let mut out = format!("# Sacred Alliance channel: {}\n", self.channel_id);
+        let mut current_day = None;
+        
+        for message in messages {
+            let day = message.timestamp.date_naive();
+            if current_day != Some(day) {
+                out.push_str(&format!("\n## {}\n\n", day.format("%Y-%m-%d")));
+                current_day = Some(day);
+            }
+            
+            let label = match self.participants.iter().find(|p| p.id == message.sender) {
+                Some(p) => format!("{} ({:?})", p.id, p.participant_type),
+                None => message.sender.clone(),
+            };
+            let time = message.timestamp.format("%H:%M:%S");
+            
+            match &message.content {
+                MessageContent::Text(text) => {
+                    out.push_str(&format!("**{}** {}: {}\n\n", time, label, text));
+                }
+                MessageContent::Ceremony(action) => {
+                    out.push_str(&format!(
+                        "> **Ceremony `{}`** by {} at {}: {}\n\n",
+                        action.action_type, label, time, action.description
+                    ));
+                }
+                MessageContent::Code(code) => {
+                    out.push_str(&format!("**{}** {} shared code ({:?}):\n\n", time, label, code.intent));
+                    if let Some(explanation) = &code.explanation {
+                        out.push_str(&format!("{}\n\n", explanation));
+                    }
+                    out.push_str(&format!("```{}\n{}\n```\n\n", code.language, code.code));
+                }
+                MessageContent::CodeReference(code) => {
+                    out.push_str(&format!(
+                        "**{}** {} shared code ({:?}, {} lines, resource `{}`):\n\n",
+                        time, label, code.intent, code.line_count, code.resource_id
+                    ));
+                    if let Some(explanation) = &code.explanation {
+                        out.push_str(&format!("{}\n\n", explanation));
+                    }
+                    out.push_str(&format!("```{}\n{}\n```\n\n", code.language, code.preview));
+                }
+                MessageContent::Presence(update) => {
+                    out.push_str(&format!("_{} {} is now {:?}", time, label, update.status));
+                    if let Some(text) = &update.message {
+                        out.push_str(&format!(": {}", text));
+                    }
+                    out.push_str("_\n\n");
+                }
+                MessageContent::Approval(approval) => {
+                    let text = match approval {
+                        ApprovalMessage::Request { operation, estimated_cost, currency, requester, .. } => format!(
+                            "approval requested by {} for {} ({} {})",
+                            requester, operation, estimated_cost, currency
+                        ),
+                        ApprovalMessage::Decision { approve, .. } => {
+                            if *approve { "approved".to_string() } else { "denied".to_string() }
+                        }
+                        ApprovalMessage::Resolved { approved, decided_by, .. } => format!(
+                            "ticket {} by {}",
+                            if *approved { "approved" } else { "denied" },
+                            decided_by
+                        ),
+                        ApprovalMessage::Unauthorized { participant, .. } => {
+                            format!("{} is not authorized to decide", participant)
+                        }
+                        ApprovalMessage::Expired { .. } => "ticket expired".to_string(),
+                    };
+                    out.push_str(&format!(
+                        "> **Approval `{}`** {} at {}: {}\n\n",
+                        approval.ticket_id(), label, time, text
+                    ));
+                }
+                // Never kept in history
+                MessageContent::ScreenShare(_) => {}
+            }
+        }
+        
+        out
+    }
+    
     /// Get channel participants
     pub fn get_participants(&self) -> &[Participant] {
         &self.participants
@@ -257,6 +1492,94 @@ impl BasicSacredAllianceChannel {
         &self.history
     }
     
+    /// Reply to a message in this channel, returning the reply's ID
+    pub fn send_reply(
+        &mut self,
+        channel_id: &str,
+        parent_message_id: MessageId,
+        sender: &str,
+        content: MessageContent,
+    ) -> Result<MessageId> {
+        self.check_channel(channel_id)?;
+        let reply = AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: sender.to_string(),
+            content,
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: Some(parent_message_id),
+            forwarded_from: None,
+        };
+        
+        // Forwarding delivers a copy under a new ID
+        let before = self.history.len();
+        self.send_message(reply)?;
+        match self.history.get(before) {
+            Some(delivered) => Ok(delivered.id),
+            None => Err(anyhow::anyhow!("Reply to {} was dropped", parent_message_id)),
+        }
+    }
+    
+    /// Get a message and every reply beneath it, in chronological order
+    pub fn get_thread(&self, channel_id: &str, root_message_id: MessageId) -> Result<Vec<AllianceMessage>> {
+        self.check_channel(channel_id)?;
+        if !self.history.iter().any(|m| m.id == root_message_id) {
+            return Err(anyhow::anyhow!("Message not found: {}", root_message_id));
+        }
+        
+        let mut in_thread = std::collections::HashSet::from([root_message_id]);
+        let mut frontier = vec![root_message_id];
+        while let Some(parent_id) = frontier.pop() {
+            for reply in self.history.iter().filter(|m| m.reply_to == Some(parent_id)) {
+                if !in_thread.insert(reply.id) {
+                    return Err(WeaveMeshError::SacredAllianceViolation("circular thread".to_string()).into());
+                }
+                frontier.push(reply.id);
+            }
+        }
+        
+        let mut thread: Vec<AllianceMessage> = self.history.iter()
+            .filter(|m| in_thread.contains(&m.id))
+            .cloned()
+            .collect();
+        thread.sort_by_key(|m| m.timestamp);
+        Ok(thread)
+    }
+    
+    fn check_channel(&self, channel_id: &str) -> Result<()> {
+        if channel_id != self.channel_id {
+            return Err(anyhow::anyhow!("Unknown channel: {}", channel_id));
+        }
+        Ok(())
+    }
+    
+    /// Validate that `message_id` may reply to `parent_id`
+    ///
+    /// Walks up from the parent to the thread root; meeting the reply itself
+    /// on the way would close a cycle.
+    fn check_reply(&self, message_id: MessageId, parent_id: MessageId) -> Result<()> {
+        let mut depth = 0;
+        let mut current = Some(parent_id);
+        while let Some(id) = current {
+            if id == message_id {
+                return Err(WeaveMeshError::SacredAllianceViolation("circular thread".to_string()).into());
+            }
+            let parent = match self.history.iter().find(|m| m.id == id) {
+                Some(parent) => parent,
+                None if id == parent_id => return Err(anyhow::anyhow!("Message not found: {}", parent_id)),
+                None => break,
+            };
+            depth += 1;
+            if depth > self.config.max_thread_depth {
+                return Err(WeaveMeshError::SacredAllianceViolation(format!(
+                    "thread deeper than {} replies", self.config.max_thread_depth
+                )).into());
+            }
+            current = parent.reply_to;
+        }
+        Ok(())
+    }
+    
     /// Get alliance statistics
     pub fn get_statistics(&self) -> AllianceStatistics {
         let total_messages = self.history.len();
@@ -266,22 +1589,175 @@ impl BasicSacredAllianceChannel {
         
         let mut message_types = HashMap::new();
         for message in &self.history {
-            let msg_type = match &message.content {
-                MessageContent::Text(_) => "text",
-                MessageContent::Ceremony(_) => "ceremony",
-                MessageContent::Code(_) => "code",
-                MessageContent::Presence(_) => "presence",
-            };
-            *message_types.entry(msg_type.to_string()).or_insert(0) += 1;
+            *message_types.entry(message_type_name(&message.content).to_string()).or_insert(0) += 1;
         }
         
+        let completed = self.ceremonies.values()
+            .filter(|c| matches!(c.status, CeremonyStatus::Completed))
+            .count();
+        let total_participants = self.participants.len();
+        
         AllianceStatistics {
-            total_participants: self.participants.len(),
+            total_participants,
             active_participants,
             total_messages,
             message_type_distribution: message_types,
+            ceremony_completion_rate: (!self.ceremonies.is_empty())
+                .then(|| completed as f64 / self.ceremonies.len() as f64),
+            participant_retention_rate: (total_participants > 0)
+                .then(|| active_participants as f64 / total_participants as f64),
+            message_quality: mean(self.history.iter().filter_map(|m| message_quality(&m.content))),
+            federated_messages_forwarded: self.federated_messages_forwarded,
+        }
+    }
+    
+    /// Statistics of the messages sent in `(start, end]`
+    ///
+    /// Active participants are the senders in the window, and retention is
+    /// the share of `previous_senders` among them.
+    fn window_statistics(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        previous_senders: &HashSet<&str>,
+    ) -> AllianceStatistics {
+        let in_window: Vec<&AllianceMessage> = self.history.iter()
+            .filter(|m| m.timestamp > start && m.timestamp <= end)
+            .collect();
+        let senders: HashSet<&str> = in_window.iter().map(|m| m.sender.as_str()).collect();
+        
+        let mut message_types = HashMap::new();
+        for message in &in_window {
+            *message_types.entry(message_type_name(&message.content).to_string()).or_insert(0) += 1;
+        }
+        
+        // Ceremonies active in the window, and whether they completed by its end
+        let ceremony_ids: HashSet<&str> = in_window.iter()
+            .filter_map(|m| match &m.content {
+                MessageContent::Ceremony(action) => action.ceremony_id(),
+                _ => None,
+            })
+            .collect();
+        let completed = ceremony_ids.iter()
+            .filter(|id| self.history.iter().any(|m| m.timestamp <= end && matches!(
+                &m.content,
+                MessageContent::Ceremony(a) if a.action_type == BasicCeremonyAction::COMPLETE && a.ceremony_id() == Some(**id)
+            )))
+            .count();
+        
+        AllianceStatistics {
+            total_participants: self.participants.iter().filter(|p| p.joined_at <= end).count(),
+            active_participants: senders.len(),
+            total_messages: in_window.len(),
+            message_type_distribution: message_types,
+            ceremony_completion_rate: (!ceremony_ids.is_empty())
+                .then(|| completed as f64 / ceremony_ids.len() as f64),
+            participant_retention_rate: (!previous_senders.is_empty())
+                .then(|| previous_senders.intersection(&senders).count() as f64 / previous_senders.len() as f64),
+            message_quality: mean(in_window.iter().filter_map(|m| message_quality(&m.content))),
+            federated_messages_forwarded: 0,
+        }
+    }
+    
+    /// Trends over the last `window_count` windows of `window_size`, ending now
+    pub fn statistics_trend(&self, window_count: usize, window_size: std::time::Duration) -> StatisticsTrend {
+        self.statistics_trend_at(Utc::now(), window_count, window_size)
+    }
+    
+    /// Trends over the last `window_count` windows of `window_size`, ending at `now`
+    pub fn statistics_trend_at(
+        &self,
+        now: DateTime<Utc>,
+        window_count: usize,
+        window_size: std::time::Duration,
+    ) -> StatisticsTrend {
+        let Ok(size) = chrono::Duration::from_std(window_size) else {
+            return StatisticsTrend::default();
+        };
+        let senders_between = |start: DateTime<Utc>, end: DateTime<Utc>| -> HashSet<&str> {
+            self.history.iter()
+                .filter(|m| m.timestamp > start && m.timestamp <= end)
+                .map(|m| m.sender.as_str())
+                .collect()
+        };
+        
+        let windows = (0..window_count)
+            .rev()
+            .map(|i| {
+                let end = now - size * i as i32;
+                let start = end - size;
+                self.window_statistics(start, end, &senders_between(start - size, start))
+            })
+            .collect();
+        StatisticsTrend::from_windows(windows)
+    }
+    
+    /// Alliance level from the latest assessment
+    pub fn alliance_level(&self) -> &SacredAllianceLevel {
+        &self.level
+    }
+    
+    /// Collaboration signals measured from the channel
+    ///
+    /// Attribution balance counts messages from human and AI participants
+    /// sent within `window` before `now`. A ceremony whose votes disagree
+    /// is a conflict, resolved once the ceremony completes; without
+    /// conflicts the resolution signal is 1.0.
+    pub fn alliance_signals(&self, window: chrono::Duration, now: DateTime<Utc>) -> AllianceSignals {
+        let ceremonies = self.ceremonies.len();
+        let completed = self.ceremonies.values()
+            .filter(|c| matches!(c.status, CeremonyStatus::Completed))
+            .count();
+        
+        let conflicts: Vec<&ChannelCeremony> = self.ceremonies.values()
+            .filter(|c| c.votes.values().collect::<HashSet<_>>().len() > 1)
+            .collect();
+        let resolved = conflicts.iter().filter(|c| matches!(c.status, CeremonyStatus::Completed)).count();
+        
+        let (human, ai) = self.history.iter()
+            .filter(|m| m.timestamp <= now && now - m.timestamp <= window)
+            .filter_map(|m| self.participants.iter().find(|p| p.id == m.sender))
+            .fold((0usize, 0usize), |(human, ai), p| match p.participant_type {
+                ParticipantType::Human => (human + 1, ai),
+                ParticipantType::Ai => (human, ai + 1),
+                ParticipantType::Hybrid => (human + 1, ai + 1),
+                ParticipantType::Collective => (human, ai),
+            });
+        
+        let present = self.participants.iter()
+            .filter(|p| matches!(p.presence, PresenceStatus::Active | PresenceStatus::Present))
+            .count();
+        
+        let share = |part: usize, whole: usize| if whole == 0 { 0.0 } else { part as f64 / whole as f64 };
+        AllianceSignals {
+            ceremony_completion: share(completed, ceremonies),
+            attribution_balance: share(human.min(ai), human.max(ai)),
+            conflict_resolution: if conflicts.is_empty() { 1.0 } else { share(resolved, conflicts.len()) },
+            presence_consistency: share(present, self.participants.len()),
         }
     }
+    
+    /// Reassess the channel's alliance level from its measured signals
+    pub fn assess_alliance_level(&mut self, assessor: &mut AllianceAssessor, now: DateTime<Utc>) -> LevelAssessment {
+        let window = chrono::Duration::days(assessor.rules().attribution_window_days);
+        let signals = self.alliance_signals(window, now);
+        assessor.track(self.channel_id.clone(), self.level.clone());
+        let assessment = assessor.assess(&self.channel_id, signals, now);
+        self.level = assessment.level.clone();
+        assessment
+    }
+}
+
+/// Name of a message's content type in statistics
+fn message_type_name(content: &MessageContent) -> &'static str {
+    match content {
+        MessageContent::Text(_) => "text",
+        MessageContent::Ceremony(_) => "ceremony",
+        MessageContent::Code(_) | MessageContent::CodeReference(_) => "code",
+        MessageContent::Presence(_) => "presence",
+        MessageContent::Approval(_) => "approval",
+        MessageContent::ScreenShare(_) => "screen_share",
+    }
 }
 
 #[cfg(test)]
@@ -310,6 +1786,8 @@ mod tests {
             content: MessageContent::Text("Hello".to_string()),
             timestamp: Utc::now(),
             metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
         };
         
         assert!(channel.send_message(message).is_ok());
@@ -336,4 +1814,436 @@ mod tests {
         assert_eq!(stats.active_participants, 1);
         assert_eq!(stats.total_messages, 0);
     }
+    
+    #[test]
+    fn test_statistics_trend() {
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+        let mut channel = BasicSacredAllianceChannel::new("trend".to_string(), ChannelConfig::default());
+        for id in ["human1", "ai1"] {
+            channel.add_participant(Participant {
+                id: id.to_string(),
+                participant_type: ParticipantType::Human,
+                presence: PresenceStatus::Active,
+                capabilities: vec![],
+                joined_at: now - hour * 10,
+            }).unwrap();
+        }
+        
+        let too_early = channel.statistics_trend_at(now, 4, std::time::Duration::from_secs(3600));
+        assert_eq!(too_early.overall_direction(), TrendDirection::InsufficientData);
+        
+        // Messages grow longer every hour, and both participants keep taking part
+        for hours_ago in (0..4).rev() {
+            let words = vec!["word"; 5 * (4 - hours_ago)].join(" ");
+            for sender in ["human1", "ai1"] {
+                channel.send_message(AllianceMessage {
+                    id: Uuid::new_v4(),
+                    sender: sender.to_string(),
+                    content: MessageContent::Text(words.clone()),
+                    timestamp: now - hour * hours_ago as i32 - chrono::Duration::minutes(30),
+                    metadata: HashMap::new(),
+                    reply_to: None,
+                    forwarded_from: None,
+                }).unwrap();
+            }
+        }
+        
+        let trend = channel.statistics_trend_at(now, 4, std::time::Duration::from_secs(3600));
+        assert_eq!(trend.windows.len(), 4);
+        assert!((trend.message_quality_slope - 0.25).abs() < 1e-9);
+        assert_eq!(trend.participant_retention_rate_slope, 0.0);
+        assert!(trend.trend_significance > 0.99);
+        assert_eq!(trend.overall_direction(), TrendDirection::Improving);
+    }
+    
+    #[tokio::test]
+    async fn test_oversized_code_is_sent_by_reference() {
+        let mut storage = crate::storage::MemoryStorage::new();
+        let mut channel = BasicSacredAllianceChannel::new("review".to_string(), ChannelConfig::default());
+        let mut other = BasicSacredAllianceChannel::new("elsewhere".to_string(), ChannelConfig::default());
+        let participant = |id: &str| Participant {
+            id: id.to_string(),
+            participant_type: ParticipantType::Human,
+            presence: PresenceStatus::Active,
+            capabilities: vec![],
+            joined_at: Utc::now(),
+        };
+        channel.add_participant(participant("human1")).unwrap();
+        channel.add_participant(participant("ai1")).unwrap();
+        other.add_participant(participant("outsider")).unwrap();
+        
+        let code: String = (0..2000).map(|i| format!("fn f{}() {{ println!(\"{}\"); }}\n", i, i)).collect();
+        let message = AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: "human1".to_string(),
+            content: MessageContent::Code(CodeContent {
+                language: "rust".to_string(),
+                code: code.clone(),
+                explanation: Some("Whole module".to_string()),
+                intent: CollaborationIntent::Review,
+            }),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        };
+        channel.send_message_with_storage(&mut storage, message).await.unwrap();
+        
+        let sent = &channel.get_history()[0].clone();
+        assert!(serde_json::to_vec(sent).unwrap().len() < 4 * 1024);
+        let MessageContent::CodeReference(reference) = &sent.content else {
+            panic!("expected a code reference");
+        };
+        assert_eq!((reference.size, reference.line_count), (code.len(), 2000));
+        assert_eq!(reference.preview.lines().count(), 21);
+        assert!(reference.preview.ends_with("// ... 1980 more lines"));
+        
+        // A member fetches the full code lazily
+        let delivered = channel.deliver(&storage, "ai1", sent).await.unwrap();
+        assert!(matches!(delivered.content, MessageContent::CodeReference(_)));
+        assert_eq!(channel.fetch_code(&storage, "ai1", reference).await.unwrap().code, code);
+        
+        // Non-members are denied, even through a channel they belong to
+        for (channel, participant) in [(&channel, "outsider"), (&other, "outsider")] {
+            let denied = channel.fetch_code(&storage, participant, reference).await.unwrap_err();
+            assert!(matches!(denied.downcast_ref::<AllianceError>(), Some(AllianceError::NotAMember { .. })));
+        }
+        
+        channel.config.code_delivery = CodeDelivery::Eager;
+        let delivered = channel.deliver(&storage, "ai1", sent).await.unwrap();
+        assert!(matches!(delivered.content, MessageContent::Code(ref full) if full.code == code));
+        
+        assert_eq!(CodeReference::preview("a = 1\nb = 2\nc = 3", "python", 1), "a = 1\n# ... 2 more lines");
+        assert_eq!(CodeReference::preview("short", "rust", 5), "short");
+    }
+    
+    fn synthetic_channel() -> BasicSacredAllianceChannel {
+        let mut channel = BasicSacredAllianceChannel::new("retro".to_string(), ChannelConfig::default())
+            .with_message_policy(MessagePolicy::redacting(vec!["hunter2".to_string()]));
+        
+        channel.add_participant(Participant {
+            id: "human1".to_string(),
+            participant_type: ParticipantType::Human,
+            presence: PresenceStatus::Active,
+            capabilities: vec![],
+            joined_at: Utc::now(),
+        }).unwrap();
+        
+        let base = Utc::now();
+        let contents = vec![
+            MessageContent::Text("The password is hunter2".to_string()),
+            MessageContent::Ceremony(BasicCeremonyAction {
+                action_type: "retrospective".to_string(),
+                description: "Sprint retro".to_string(),
+                parameters: HashMap::new(),
+            }),
+            MessageContent::Code(CodeContent {
+                language: "rust".to_string(),
+                code: "fn main() {}".to_string(),
+                explanation: None,
+                intent: CollaborationIntent::Review,
+            }),
+        ];
+        
+        for (i, content) in contents.into_iter().enumerate() {
+            channel.send_message(AllianceMessage {
+                id: Uuid::new_v4(),
+                sender: "human1".to_string(),
+                content,
+                timestamp: base + chrono::Duration::seconds(i as i64),
+                metadata: HashMap::new(),
+                reply_to: None,
+                forwarded_from: None,
+            }).unwrap();
+        }
+        
+        channel
+    }
+    
+    #[test]
+    fn test_export_json() {
+        let channel = synthetic_channel();
+        let json = channel.export(ExportRange::all(), ExportFormat::Json).unwrap();
+        let export: ChannelExport = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(export.messages.len(), 3);
+        assert!(export.messages.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        assert!(!json.contains("hunter2"));
+        assert_eq!(export.messages[0].metadata.get("redacted").map(String::as_str), Some("true"));
+    }
+    
+    #[test]
+    fn test_export_markdown() {
+        let channel = synthetic_channel();
+        let md = channel.export(ExportRange::all(), ExportFormat::Markdown).unwrap();
+        
+        assert!(md.contains("human1 (Human)"));
+        assert!(md.contains("> **Ceremony `retrospective`**"));
+        assert!(md.contains("```rust\nfn main() {}\n```"));
+        assert!(!md.contains("hunter2"));
+        assert!(md.find("[REDACTED]").unwrap() < md.find("Ceremony").unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_archive_rejects_sends() {
+        let mut channel = synthetic_channel();
+        let mut storage = crate::storage::MemoryStorage::new();
+        
+        let resource_id = channel.archive(&mut storage).await.unwrap();
+        assert!(channel.is_archived());
+        assert!(channel.get_history().is_empty());
+        
+        let stored = storage.get_resource_content(&resource_id).await.unwrap();
+        let export: ChannelExport = serde_json::from_slice(&stored).unwrap();
+        assert_eq!(export.messages.len(), 3);
+        
+        let err = channel.send_message(AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: "human1".to_string(),
+            content: MessageContent::Text("too late".to_string()),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        }).unwrap_err();
+        assert!(matches!(err.downcast_ref::<AllianceError>(), Some(AllianceError::ChannelArchived(_))));
+    }
+    
+    fn ceremony_message(sender: &str, action: BasicCeremonyAction) -> AllianceMessage {
+        AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: sender.to_string(),
+            content: MessageContent::Ceremony(action),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        }
+    }
+    
+    fn vote(ceremony_id: &str, vote: &str) -> BasicCeremonyAction {
+        let mut parameters = HashMap::new();
+        parameters.insert(BasicCeremonyAction::CEREMONY_ID.to_string(), ceremony_id.to_string());
+        parameters.insert("vote".to_string(), vote.to_string());
+        BasicCeremonyAction {
+            action_type: "vote".to_string(),
+            description: "Vote".to_string(),
+            parameters,
+        }
+    }
+    
+    #[test]
+    fn test_defer_until_participant() {
+        let mut channel = synthetic_channel();
+        channel.send_message(ceremony_message("human1", vote("c1", "approve"))).unwrap();
+        channel.send_message(ceremony_message("human1", BasicCeremonyAction::defer(
+            "c1",
+            "Waiting for reviewer".to_string(),
+            None,
+            Some("ai1".to_string()),
+        ))).unwrap();
+        
+        let ceremony = channel.get_ceremony("c1").unwrap();
+        assert!(matches!(ceremony.status, CeremonyStatus::Deferred { .. }));
+        
+        // Cannot resume until the participant joins
+        assert!(channel.resume_ceremony("c1").is_err());
+        
+        channel.add_participant(Participant {
+            id: "ai1".to_string(),
+            participant_type: ParticipantType::Ai,
+            presence: PresenceStatus::Active,
+            capabilities: vec![],
+            joined_at: Utc::now(),
+        }).unwrap();
+        
+        let ceremony = channel.get_ceremony("c1").unwrap();
+        assert!(matches!(ceremony.status, CeremonyStatus::InProgress));
+        assert_eq!(ceremony.votes.get("human1").map(String::as_str), Some("approve"));
+        assert_eq!(ceremony.messages.len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_defer_auto_resume() {
+        let mut channel = synthetic_channel();
+        let resume_at = Utc::now() + chrono::Duration::milliseconds(20);
+        channel.send_message(ceremony_message("human1", BasicCeremonyAction::defer(
+            "c2",
+            "Lunch break".to_string(),
+            Some(resume_at),
+            None,
+        ))).unwrap();
+        
+        let channel = std::sync::Arc::new(tokio::sync::RwLock::new(channel));
+        let handle = BasicSacredAllianceChannel::spawn_auto_resume(channel.clone(), "c2".to_string())
+            .unwrap();
+        handle.await.unwrap();
+        
+        let channel = channel.read().await;
+        assert!(matches!(channel.get_ceremony("c2").unwrap().status, CeremonyStatus::InProgress));
+    }
+    
+    fn text_message(sender: &str, text: &str) -> AllianceMessage {
+        AllianceMessage {
+            id: Uuid::new_v4(),
+            sender: sender.to_string(),
+            content: MessageContent::Text(text.to_string()),
+            timestamp: Utc::now(),
+            metadata: HashMap::new(),
+            reply_to: None,
+            forwarded_from: None,
+        }
+    }
+    
+    #[test]
+    fn test_forwarding_through_intermediary() {
+        let mut channel = synthetic_channel();
+        channel.add_participant(Participant {
+            id: "facilitator".to_string(),
+            participant_type: ParticipantType::Ai,
+            presence: PresenceStatus::Active,
+            capabilities: vec![],
+            joined_at: Utc::now(),
+        }).unwrap();
+        channel.enable_forwarding("facilitator");
+        let before = channel.history.len();
+        
+        // Plain messages are routed through the facilitator, keeping their author
+        channel.send_message(text_message("human1", "Refactor the borrow checker hack")).unwrap();
+        let delivered = channel.history.last().unwrap();
+        assert_eq!(delivered.sender, "human1");
+        assert_eq!(delivered.forwarded_by(), Some("facilitator"));
+        
+        // A transformed forward from the facilitator is delivered as-is
+        let original = text_message("human1", "Fix lifetimes");
+        let forwarded = original.forward(
+            "facilitator",
+            Some(MessageContent::Text("Make the data live long enough".to_string())),
+        );
+        assert!(forwarded.transform_applied);
+        channel.send_message(forwarded.into_message()).unwrap();
+        let delivered = channel.history.last().unwrap();
+        assert_eq!(delivered.sender, "human1");
+        assert_eq!(delivered.forwarding_chain(), vec!["facilitator"]);
+        assert!(matches!(&delivered.content, MessageContent::Text(t) if t.starts_with("Make")));
+        assert_eq!(channel.history.len(), before + 2);
+        
+        // A message that passed through the facilitator and came back is a loop
+        let looped = text_message("human1", "again")
+            .forward("facilitator", None)
+            .into_message()
+            .forward("human1", None)
+            .into_message();
+        channel.send_message(looped).unwrap();
+        assert_eq!(channel.history.len(), before + 2);
+    }
+    
+    #[test]
+    fn test_message_threading() {
+        let mut channel = BasicSacredAllianceChannel::new(
+            "threads".to_string(),
+            ChannelConfig { max_thread_depth: 2, ..ChannelConfig::default() },
+        );
+        for id in ["human1", "ai1"] {
+            channel.add_participant(Participant {
+                id: id.to_string(),
+                participant_type: ParticipantType::Human,
+                presence: PresenceStatus::Active,
+                capabilities: vec![],
+                joined_at: Utc::now(),
+            }).unwrap();
+        }
+        let root = text_message("human1", "Should we split the crate?");
+        let root_id = root.id;
+        channel.send_message(root).unwrap();
+        channel.send_message(text_message("ai1", "Unrelated")).unwrap();
+        
+        let reply = channel.send_reply("threads", root_id, "ai1", MessageContent::Text("Yes".to_string())).unwrap();
+        let nested = channel.send_reply("threads", reply, "human1", MessageContent::Text("Why?".to_string())).unwrap();
+        let thread = channel.get_thread("threads", root_id).unwrap();
+        assert_eq!(thread.iter().map(|m| m.id).collect::<Vec<_>>(), vec![root_id, reply, nested]);
+        assert_eq!(channel.get_thread("threads", reply).unwrap().len(), 2);
+        
+        // Past the depth cap
+        let err = channel.send_reply("threads", nested, "ai1", MessageContent::Text("...".to_string())).unwrap_err();
+        assert!(matches!(err.downcast_ref(), Some(WeaveMeshError::SacredAllianceViolation(_))));
+        
+        // A message replying to its own descendant closes a cycle
+        let mut circular = text_message("human1", "Loop");
+        circular.id = root_id;
+        circular.reply_to = Some(reply);
+        let err = channel.send_message(circular).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(WeaveMeshError::SacredAllianceViolation(reason)) if reason == "circular thread"
+        ));
+        
+        assert!(channel.send_reply("other", root_id, "ai1", MessageContent::Text("?".to_string())).is_err());
+        assert!(channel.send_reply("threads", Uuid::new_v4(), "ai1", MessageContent::Text("?".to_string())).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_channel_federation() {
+        use std::sync::Arc;
+        use tokio::sync::RwLock;
+        
+        let channel = |id: &str, member: &str| {
+            let mut channel = BasicSacredAllianceChannel::new(id.to_string(), ChannelConfig::default());
+            channel.add_participant(Participant {
+                id: member.to_string(),
+                participant_type: ParticipantType::Human,
+                presence: PresenceStatus::Active,
+                capabilities: vec![],
+                joined_at: Utc::now(),
+            }).unwrap();
+            Arc::new(RwLock::new(channel))
+        };
+        let (dev, pm) = (channel("dev", "human1"), channel("pm", "manager"));
+        let federation = AllianceFederation::new();
+        federation.register(Arc::clone(&dev)).await;
+        federation.register(Arc::clone(&pm)).await;
+        
+        let handle = dev.read().await.federate_with("pm", MessageFederationFilter {
+            allow_types: vec!["text".to_string(), "code".to_string()],
+            deny_types: vec!["code".to_string()],
+            transform: Some(Box::new(|mut message: AllianceMessage| {
+                message.metadata.insert("via".to_string(), "dev".to_string());
+                message
+            })),
+        }).unwrap();
+        let reverse = pm.read().await.federate_with("dev", MessageFederationFilter::default());
+        assert!(matches!(reverse, Err(e) if e.downcast_ref::<AllianceError>()
+            .is_some_and(|e| matches!(e, AllianceError::FederationCycle { .. }))));
+        
+        dev.write().await.send_message(text_message("human1", "Shipped the parser")).unwrap();
+        dev.write().await.send_message(AllianceMessage {
+            content: MessageContent::Code(CodeContent {
+                language: "rust".to_string(),
+                code: "fn main() {}".to_string(),
+                explanation: None,
+                intent: CollaborationIntent::Review,
+            }),
+            ..text_message("human1", "")
+        }).unwrap();
+        federation.flush().await;
+        
+        let forwarded = pm.read().await.get_history().to_vec();
+        assert_eq!(forwarded.len(), 1);
+        assert_eq!(forwarded[0].forwarded_from.as_deref(), Some("dev"));
+        assert_eq!(forwarded[0].metadata.get("via").map(String::as_str), Some("dev"));
+        assert_eq!(dev.read().await.get_statistics().federated_messages_forwarded, 1);
+        
+        // Claiming to be forwarded grants nothing to outsiders
+        let mut spoofed = text_message("human1", "Posted straight into pm");
+        spoofed.forwarded_from = Some("dev".to_string());
+        assert!(pm.write().await.send_message(spoofed.clone()).is_err());
+        assert!(pm.write().await.deliver_federated("elsewhere", spoofed).is_err());
+        
+        // Dropping the handle ends the federation and allows the reverse link
+        drop(handle);
+        dev.write().await.send_message(text_message("human1", "Not forwarded")).unwrap();
+        assert_eq!(pm.read().await.get_history().len(), 1);
+        assert!(pm.read().await.federate_with("dev", MessageFederationFilter::default()).is_ok());
+    }
 }
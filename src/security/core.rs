@@ -257,6 +257,13 @@ pub enum SecurityEvent {
         reason: String,
         timestamp: i64,
     },
+    /// Operation that bypassed a required policy
+    PolicyViolation {
+        user_id: Option<String>,
+        policy: String,
+        details: String,
+        timestamp: i64,
+    },
 }
 
 impl SecurityEvent {
@@ -266,7 +273,8 @@ impl SecurityEvent {
             SecurityEvent::AuthenticationAttempt { timestamp, .. } |
             SecurityEvent::AccessAttempt { timestamp, .. } |
             SecurityEvent::TokenCreated { timestamp, .. } |
-            SecurityEvent::TokenRevoked { timestamp, .. } => *timestamp,
+            SecurityEvent::TokenRevoked { timestamp, .. } |
+            SecurityEvent::PolicyViolation { timestamp, .. } => *timestamp,
         }
     }
     
@@ -277,6 +285,7 @@ impl SecurityEvent {
             SecurityEvent::AccessAttempt { user_id, .. } => user_id.as_deref(),
             SecurityEvent::TokenCreated { user_id, .. } => Some(user_id),
             SecurityEvent::TokenRevoked { .. } => None,
+            SecurityEvent::PolicyViolation { user_id, .. } => user_id.as_deref(),
         }
     }
 }
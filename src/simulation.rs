@@ -0,0 +1,909 @@
+//! Mesh Simulation
+//!
+//! Spawns nodes on an in-process message bus and drives each one with a
+//! behavior profile: message rates per `MessageType`, resource publishing,
+//! group churn and injected failures. Every node is a [`BasicNode`] scoring
+//! its peers from delivery outcomes, and the first node's [`MeshManager`]
+//! tracks the others, so the report's [`MeshMetrics`] and quarantines come
+//! from the real mesh code. Runs produce a [`SimulationReport`] with
+//! communication stats, latency percentiles and detected anomalies, so mesh
+//! changes can be load and chaos tested without a network. Enabled with the
+//! `simulation` feature.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::group_communication::{GroupId, GroupRole, GroupRoster, MembershipChange};
+use crate::mesh::discovery::{NodeCapabilities, TrustLevel};
+use crate::mesh::events::{
+    EventPayload, EventPriority, EventStatistics, EventSystem, EventType, MeshEvent,
+    NodeLifecycleType, ResourceEventType,
+};
+use crate::mesh::manager::{ConnectionState, MeshConfig, MeshManager, MeshMetrics, RemoteNode};
+use crate::networking::node_communication::CommunicationStats;
+use crate::networking::zenoh_integration::MessageType;
+use crate::node::{BasicNode, InteractionOutcome, NodeConfig};
+
+/// How often a behavior fires
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RateDistribution {
+    /// Fixed interval
+    Constant { rate_per_sec: f64 },
+    /// Exponentially distributed intervals
+    Poisson { rate_per_sec: f64 },
+    /// `burst_size` events at once, averaging `rate_per_sec`
+    Burst { rate_per_sec: f64, burst_size: usize },
+}
+
+impl RateDistribution {
+    /// Delay until the next firing and how many events fire then, or None if disabled
+    fn next(&self, rng: &mut SimRng) -> Option<(Duration, usize)> {
+        match self {
+            RateDistribution::Constant { rate_per_sec } if *rate_per_sec > 0.0 => {
+                Some((Duration::from_secs_f64(1.0 / rate_per_sec), 1))
+            }
+            RateDistribution::Poisson { rate_per_sec } if *rate_per_sec > 0.0 => {
+                let u = rng.next_f64();
+                Some((Duration::from_secs_f64(-(1.0 - u).ln() / rate_per_sec), 1))
+            }
+            RateDistribution::Burst { rate_per_sec, burst_size } if *rate_per_sec > 0.0 && *burst_size > 0 => {
+                Some((Duration::from_secs_f64(*burst_size as f64 / rate_per_sec), *burst_size))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Traffic of one message type sent by every node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageRate {
+    /// Message type to send
+    pub message_type: MessageType,
+    /// Send rate
+    pub distribution: RateDistribution,
+    /// Payload size in bytes
+    #[serde(default = "default_payload_bytes")]
+    pub payload_bytes: usize,
+}
+
+fn default_payload_bytes() -> usize {
+    256
+}
+
+/// Resource publish and update activity per node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceActivity {
+    /// New resources published per second
+    #[serde(default)]
+    pub publish_per_sec: f64,
+    /// Updates to published resources per second
+    #[serde(default)]
+    pub update_per_sec: f64,
+}
+
+/// Group join/leave churn per node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChurnConfig {
+    /// Membership flips (leave if joined, join if not) per second
+    #[serde(default)]
+    pub flips_per_sec: f64,
+}
+
+/// Failure to inject into a node
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FailureKind {
+    /// Node stops permanently and leaves the mesh
+    Crash,
+    /// Node stops sending and handling for a while
+    Hang { duration_ms: u64 },
+    /// Every message handled afterwards takes this much longer
+    SlowHandler { delay_ms: u64 },
+}
+
+/// A failure injected into one node at a point in the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureInjection {
+    /// Index of the node, from 0
+    pub node: usize,
+    /// Milliseconds after the start of the run
+    pub at_ms: u64,
+    /// What happens
+    #[serde(flatten)]
+    pub kind: FailureKind,
+}
+
+/// Behavior profile for a simulation run, loadable from TOML
+///
+/// ```toml
+/// name = "smoke"
+/// nodes = 5
+/// duration_ms = 2000
+///
+/// [[messages]]
+/// message_type = "Collaboration"
+/// distribution = { kind = "poisson", rate_per_sec = 20.0 }
+///
+/// [[failures]]
+/// node = 1
+/// at_ms = 500
+/// kind = "crash"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationProfile {
+    /// Profile name, copied into the report
+    pub name: String,
+    /// Number of virtual nodes
+    pub nodes: usize,
+    /// Run length in milliseconds
+    pub duration_ms: u64,
+    /// Seed for per-node randomness
+    #[serde(default)]
+    pub seed: u64,
+    /// Message traffic sent by every node
+    #[serde(default)]
+    pub messages: Vec<MessageRate>,
+    /// Resource activity of every node
+    #[serde(default)]
+    pub resources: ResourceActivity,
+    /// Group churn of every node
+    #[serde(default)]
+    pub churn: ChurnConfig,
+    /// Failures to inject
+    #[serde(default)]
+    pub failures: Vec<FailureInjection>,
+    /// p99 delivery latency above which a message type is flagged
+    #[serde(default = "default_latency_threshold_ms")]
+    pub latency_threshold_ms: f64,
+}
+
+fn default_latency_threshold_ms() -> f64 {
+    500.0
+}
+
+impl SimulationProfile {
+    /// Parse a profile from TOML
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let profile: Self = toml::from_str(source)?;
+        if profile.nodes == 0 {
+            anyhow::bail!("Simulation profile '{}' has no nodes", profile.name);
+        }
+        if let Some(failure) = profile.failures.iter().find(|f| f.node >= profile.nodes) {
+            anyhow::bail!("Failure targets node {} but the profile has {} nodes", failure.node, profile.nodes);
+        }
+        Ok(profile)
+    }
+}
+
+/// Delivery latency percentiles for one message type
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyPercentiles {
+    /// Messages measured
+    pub count: usize,
+    /// Median latency in milliseconds
+    pub p50_ms: f64,
+    /// 90th percentile latency in milliseconds
+    pub p90_ms: f64,
+    /// 99th percentile latency in milliseconds
+    pub p99_ms: f64,
+    /// Maximum latency in milliseconds
+    pub max_ms: f64,
+}
+
+impl LatencyPercentiles {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let rank = |p: f64| {
+            let index = ((p * samples.len() as f64).ceil() as usize).clamp(1, samples.len()) - 1;
+            samples[index]
+        };
+        Self {
+            count: samples.len(),
+            p50_ms: rank(0.50),
+            p90_ms: rank(0.90),
+            p99_ms: rank(0.99),
+            max_ms: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Something unusual seen during a run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Anomaly {
+    /// Messages sent to nodes that were gone
+    DeadLetters { count: u64 },
+    /// A node crashed
+    NodeCrashed { node: Uuid },
+    /// A node stopped responding for a while
+    NodeUnresponsive { node: Uuid, duration_ms: u64 },
+    /// Delivery latency of a message type exceeded the profile threshold
+    SlowDelivery { message_type: String, p99_ms: f64 },
+    /// Messages still queued when the run ended
+    UndeliveredAtShutdown { count: u64 },
+    /// A node's peer score fell below `NodeConfig::min_peer_score_for_operations`
+    /// at another node, which quarantined it
+    NodeQuarantined { node: Uuid, reported_by: Uuid },
+}
+
+/// Per-node results
+#[derive(Debug, Clone)]
+pub struct NodeReport {
+    /// Node index in the profile
+    pub index: usize,
+    /// Node identifier
+    pub node_id: Uuid,
+    /// Traffic seen by this node
+    pub stats: CommunicationStats,
+    /// Whether the node crashed
+    pub crashed: bool,
+    /// Whether another node quarantined this one
+    pub quarantined: bool,
+}
+
+/// Results of a simulation run
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    /// Profile name
+    pub profile: String,
+    /// Wall-clock run time in milliseconds
+    pub elapsed_ms: u64,
+    /// Traffic summed over all nodes
+    pub communication: CommunicationStats,
+    /// Per-node results
+    pub nodes: Vec<NodeReport>,
+    /// Metrics of the first node's mesh view at the end of the run
+    pub mesh: MeshMetrics,
+    /// Events published during the run
+    pub events: EventStatistics,
+    /// Delivery latency by message type
+    pub latency: HashMap<String, LatencyPercentiles>,
+    /// Resources published
+    pub resources_published: u64,
+    /// Resource updates
+    pub resources_updated: u64,
+    /// Group joins
+    pub group_joins: u64,
+    /// Group leaves
+    pub group_leaves: u64,
+    /// Messages sent to nodes that were gone
+    pub dead_letters: u64,
+    /// Detected anomalies
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Deterministic xorshift generator so runs are reproducible per seed
+struct SimRng(u64);
+
+impl SimRng {
+    fn new(seed: u64) -> Self {
+        Self((seed ^ 0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Message on the in-process bus
+struct SimMessage {
+    /// Index of the sending node
+    from: usize,
+    message_type: MessageType,
+    bytes: usize,
+    sent_at: Instant,
+}
+
+/// Counters shared by all node tasks
+#[derive(Default)]
+struct Collector {
+    stats: Vec<CommunicationStats>,
+    latencies: HashMap<String, Vec<f64>>,
+    dead_letters: u64,
+    resources_published: u64,
+    resources_updated: u64,
+    group_joins: u64,
+    group_leaves: u64,
+    crashed: Vec<usize>,
+    hangs: Vec<(usize, u64)>,
+    /// Quarantined node and the node that quarantined it
+    quarantined: Vec<(usize, usize)>,
+}
+
+/// State shared by all node tasks
+struct Shared {
+    node_ids: Vec<Uuid>,
+    senders: Vec<mpsc::UnboundedSender<SimMessage>>,
+    online: Vec<AtomicBool>,
+    /// Each node, scoring the peers it interacts with
+    nodes: Vec<Mutex<BasicNode>>,
+    /// Mesh view of node 0, which knows every other node as a remote
+    mesh: MeshManager,
+    collector: Mutex<Collector>,
+    roster: Mutex<GroupRoster>,
+    events: Arc<EventSystem>,
+}
+
+impl Shared {
+    /// Record at `node` how an interaction with `peer` went
+    ///
+    /// The first time `node` stops finding `peer` eligible, `peer` is
+    /// quarantined in the mesh view.
+    async fn record_interaction(&self, node: usize, peer: usize, outcome: InteractionOutcome) {
+        let peer_id = self.node_ids[peer];
+        let eligible = {
+            let mut basic = self.nodes[node].lock().unwrap();
+            basic.record_peer_interaction(&peer_id.to_string(), outcome);
+            basic.is_peer_eligible(&peer_id.to_string())
+        };
+        if eligible {
+            return;
+        }
+        {
+            let mut collector = self.collector.lock().unwrap();
+            if collector.quarantined.iter().any(|(quarantined, _)| *quarantined == peer) {
+                return;
+            }
+            collector.quarantined.push((peer, node));
+        }
+        debug!("Simulated node {} quarantined by {}", peer_id, self.node_ids[node]);
+        if let Err(e) = self.mesh.set_node_quarantined(&peer_id, true).await {
+            // Node 0 is the mesh view's local node, not one of its remotes
+            debug!("Quarantine of {} not recorded in the mesh view: {}", peer_id, e);
+        }
+    }
+
+    /// Set a node's connection state in the mesh view
+    async fn set_connection_state(&self, node: usize, state: ConnectionState) {
+        if let Err(e) = self.mesh.update_node_connection_state(&self.node_ids[node], state).await {
+            debug!("Failed to update simulated node {}: {}", self.node_ids[node], e);
+        }
+    }
+}
+
+/// Scheduled behavior of a node
+enum Action {
+    Send(usize),
+    Publish,
+    Update,
+    Churn,
+    Fail(FailureKind),
+}
+
+/// Runs a [`SimulationProfile`] against in-process virtual nodes
+pub struct Simulation {
+    profile: SimulationProfile,
+    events: Arc<EventSystem>,
+}
+
+impl Simulation {
+    /// Create a simulation for a profile
+    pub fn new(profile: SimulationProfile) -> Self {
+        Self {
+            profile,
+            events: Arc::new(EventSystem::new(Uuid::new_v4(), None)),
+        }
+    }
+
+    /// Events published by the simulated mesh
+    pub fn event_system(&self) -> &Arc<EventSystem> {
+        &self.events
+    }
+
+    /// Run the profile to completion
+    pub async fn run(&self) -> Result<SimulationReport> {
+        let profile = &self.profile;
+        let count = profile.nodes;
+        info!("Starting simulation '{}' with {} nodes for {}ms", profile.name, count, profile.duration_ms);
+
+        let mesh = MeshManager::new(MeshConfig::default()).await?;
+        let mut node_ids = vec![mesh.local_node.id];
+        node_ids.extend((1..count).map(|_| Uuid::new_v4()));
+        for node_id in &node_ids[1..] {
+            let mut node = RemoteNode::new(*node_id, NodeCapabilities::default(), TrustLevel::Basic);
+            node.connection_state = ConnectionState::Connected;
+            mesh.add_node(node).await?;
+        }
+        let nodes = (0..count)
+            .map(|index| Mutex::new(BasicNode::new(NodeConfig {
+                display_name: format!("{}-{}", profile.name, index),
+                ..NodeConfig::default()
+            })))
+            .collect();
+
+        let mut senders = Vec::with_capacity(count);
+        let mut inboxes = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (sender, inbox) = mpsc::unbounded_channel();
+            senders.push(sender);
+            inboxes.push(inbox);
+        }
+
+        let shared = Arc::new(Shared {
+            node_ids: node_ids.clone(),
+            senders,
+            online: (0..count).map(|_| AtomicBool::new(true)).collect(),
+            nodes,
+            mesh,
+            collector: Mutex::new(Collector {
+                stats: vec![CommunicationStats::default(); count],
+                ..Collector::default()
+            }),
+            roster: Mutex::new(GroupRoster::new(GroupId::new("simulation"))),
+            events: self.events.clone(),
+        });
+
+        for node_id in &node_ids {
+            publish_lifecycle(&shared, *node_id, NodeLifecycleType::NodeJoined, None).await;
+        }
+
+        let start = Instant::now();
+        let end = start + Duration::from_millis(profile.duration_ms);
+        let mut tasks = Vec::with_capacity(count);
+        for (index, inbox) in inboxes.into_iter().enumerate() {
+            let node = VirtualNode {
+                index,
+                profile: profile.clone(),
+                shared: shared.clone(),
+                rng: SimRng::new(profile.seed.wrapping_add(index as u64)),
+                handler_delay: Duration::ZERO,
+                in_group: false,
+            };
+            tasks.push(tokio::spawn(node.run(inbox, start, end)));
+        }
+
+        let mut undelivered = 0u64;
+        for task in tasks {
+            undelivered += task.await?;
+        }
+
+        self.build_report(&shared, start.elapsed(), undelivered).await
+    }
+
+    async fn build_report(&self, shared: &Shared, elapsed: Duration, undelivered: u64) -> Result<SimulationReport> {
+        let collector = std::mem::take(&mut *shared.collector.lock().unwrap());
+
+        let mut communication = CommunicationStats::default();
+        let mut delivery_total = 0.0;
+        for stats in &collector.stats {
+            communication.messages_sent += stats.messages_sent;
+            communication.messages_received += stats.messages_received;
+            communication.messages_delivered += stats.messages_delivered;
+            communication.messages_failed += stats.messages_failed;
+            communication.bytes_sent += stats.bytes_sent;
+            communication.bytes_received += stats.bytes_received;
+            delivery_total += stats.avg_delivery_time_ms * stats.messages_received as f64;
+            for (message_type, n) in &stats.messages_by_type {
+                *communication.messages_by_type.entry(message_type.clone()).or_insert(0) += n;
+            }
+        }
+        if communication.messages_received > 0 {
+            communication.avg_delivery_time_ms = delivery_total / communication.messages_received as f64;
+        }
+        communication.messages_timed_out = undelivered;
+
+        let nodes: Vec<NodeReport> = collector.stats.iter().enumerate()
+            .map(|(index, stats)| NodeReport {
+                index,
+                node_id: shared.node_ids[index],
+                stats: stats.clone(),
+                crashed: collector.crashed.contains(&index),
+                quarantined: collector.quarantined.iter().any(|(node, _)| *node == index),
+            })
+            .collect();
+
+        let latency: HashMap<String, LatencyPercentiles> = collector.latencies.into_iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(message_type, samples)| (message_type, LatencyPercentiles::from_samples(samples)))
+            .collect();
+
+        let mut anomalies = Vec::new();
+        if collector.dead_letters > 0 {
+            anomalies.push(Anomaly::DeadLetters { count: collector.dead_letters });
+        }
+        for index in &collector.crashed {
+            anomalies.push(Anomaly::NodeCrashed { node: shared.node_ids[*index] });
+        }
+        for (index, duration_ms) in &collector.hangs {
+            anomalies.push(Anomaly::NodeUnresponsive { node: shared.node_ids[*index], duration_ms: *duration_ms });
+        }
+        let mut slow: Vec<_> = latency.iter()
+            .filter(|(_, p)| p.p99_ms > self.profile.latency_threshold_ms)
+            .map(|(message_type, p)| Anomaly::SlowDelivery { message_type: message_type.clone(), p99_ms: p.p99_ms })
+            .collect();
+        slow.sort_by(|a, b| format!("{:?}", a).cmp(&format!("{:?}", b)));
+        anomalies.extend(slow);
+        for (node, reported_by) in &collector.quarantined {
+            anomalies.push(Anomaly::NodeQuarantined {
+                node: shared.node_ids[*node],
+                reported_by: shared.node_ids[*reported_by],
+            });
+        }
+        if undelivered > 0 {
+            anomalies.push(Anomaly::UndeliveredAtShutdown { count: undelivered });
+        }
+
+        Ok(SimulationReport {
+            profile: self.profile.name.clone(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            communication,
+            nodes,
+            mesh: shared.mesh.get_metrics().await?,
+            events: self.events.get_statistics().await,
+            latency,
+            resources_published: collector.resources_published,
+            resources_updated: collector.resources_updated,
+            group_joins: collector.group_joins,
+            group_leaves: collector.group_leaves,
+            dead_letters: collector.dead_letters,
+            anomalies,
+        })
+    }
+}
+
+/// One simulated node
+struct VirtualNode {
+    index: usize,
+    profile: SimulationProfile,
+    shared: Arc<Shared>,
+    rng: SimRng,
+    handler_delay: Duration,
+    in_group: bool,
+}
+
+impl VirtualNode {
+    /// Drive the node until `end` or a crash, returning messages left unhandled
+    async fn run(mut self, mut inbox: mpsc::UnboundedReceiver<SimMessage>, start: Instant, end: Instant) -> u64 {
+        let mut schedule: Vec<(Instant, Action)> = Vec::new();
+        for (i, rate) in self.profile.messages.clone().iter().enumerate() {
+            if let Some((delay, _)) = rate.distribution.next(&mut self.rng) {
+                schedule.push((start + delay, Action::Send(i)));
+            }
+        }
+        let resources = self.profile.resources.clone();
+        if let Some(delay) = self.poisson(resources.publish_per_sec) {
+            schedule.push((start + delay, Action::Publish));
+        }
+        if let Some(delay) = self.poisson(resources.update_per_sec) {
+            schedule.push((start + delay, Action::Update));
+        }
+        if let Some(delay) = self.poisson(self.profile.churn.flips_per_sec) {
+            schedule.push((start + delay, Action::Churn));
+        }
+        for failure in self.profile.failures.iter().filter(|f| f.node == self.index) {
+            schedule.push((start + Duration::from_millis(failure.at_ms), Action::Fail(failure.kind.clone())));
+        }
+
+        loop {
+            let next = schedule.iter().enumerate().min_by_key(|(_, (at, _))| *at).map(|(i, (at, _))| (i, *at));
+            let wake = next.map(|(_, at)| at.min(end)).unwrap_or(end);
+
+            tokio::select! {
+                message = inbox.recv() => {
+                    match message {
+                        Some(message) => self.handle(message).await,
+                        None => break,
+                    }
+                }
+                _ = tokio::time::sleep_until(wake) => {
+                    let due = match next {
+                        Some((i, at)) if at <= end => i,
+                        _ => break,
+                    };
+                    let (_, action) = schedule.swap_remove(due);
+                    match self.perform(action, end).await {
+                        Some(rescheduled) => schedule.push(rescheduled),
+                        None if self.crashed() => {
+                            inbox.close();
+                            return 0;
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        let mut undelivered = 0;
+        inbox.close();
+        while inbox.try_recv().is_ok() {
+            undelivered += 1;
+        }
+        undelivered
+    }
+
+    fn crashed(&self) -> bool {
+        !self.shared.online[self.index].load(Ordering::Relaxed)
+    }
+
+    fn poisson(&mut self, rate_per_sec: f64) -> Option<Duration> {
+        RateDistribution::Poisson { rate_per_sec }.next(&mut self.rng).map(|(delay, _)| delay)
+    }
+
+    /// Run an action, returning its next occurrence if it repeats
+    async fn perform(&mut self, action: Action, end: Instant) -> Option<(Instant, Action)> {
+        let now = Instant::now();
+        match action {
+            Action::Send(i) => {
+                let rate = self.profile.messages[i].clone();
+                let (delay, burst) = rate.distribution.next(&mut self.rng)?;
+                for _ in 0..burst {
+                    self.send(&rate).await;
+                }
+                Some((now + delay, Action::Send(i)))
+            }
+            Action::Publish => {
+                self.shared.collector.lock().unwrap().resources_published += 1;
+                self.publish_resource(ResourceEventType::ResourceCreated).await;
+                let delay = self.poisson(self.profile.resources.publish_per_sec)?;
+                Some((now + delay, Action::Publish))
+            }
+            Action::Update => {
+                self.shared.collector.lock().unwrap().resources_updated += 1;
+                self.publish_resource(ResourceEventType::ResourceUpdated).await;
+                let delay = self.poisson(self.profile.resources.update_per_sec)?;
+                Some((now + delay, Action::Update))
+            }
+            Action::Churn => {
+                self.flip_membership();
+                let delay = self.poisson(self.profile.churn.flips_per_sec)?;
+                Some((now + delay, Action::Churn))
+            }
+            Action::Fail(kind) => {
+                self.fail(kind, end).await;
+                None
+            }
+        }
+    }
+
+    async fn send(&mut self, rate: &MessageRate) {
+        let count = self.shared.senders.len();
+        if count < 2 {
+            return;
+        }
+        let mut target = self.rng.below(count - 1);
+        if target >= self.index {
+            target += 1;
+        }
+
+        let key = format!("{:?}", rate.message_type);
+        let delivered = self.shared.online[target].load(Ordering::Relaxed)
+            && self.shared.senders[target].send(SimMessage {
+                from: self.index,
+                message_type: rate.message_type.clone(),
+                bytes: rate.payload_bytes,
+                sent_at: Instant::now(),
+            }).is_ok();
+
+        {
+            let mut collector = self.shared.collector.lock().unwrap();
+            let stats = &mut collector.stats[self.index];
+            stats.messages_sent += 1;
+            stats.bytes_sent += rate.payload_bytes as u64;
+            *stats.messages_by_type.entry(key).or_insert(0) += 1;
+            if !delivered {
+                stats.messages_failed += 1;
+                collector.dead_letters += 1;
+            }
+        }
+        if !delivered {
+            let outcome = InteractionOutcome::Failure("dead letter".to_string());
+            self.shared.record_interaction(self.index, target, outcome).await;
+        }
+    }
+
+    async fn handle(&mut self, message: SimMessage) {
+        if !self.handler_delay.is_zero() {
+            tokio::time::sleep(self.handler_delay).await;
+        }
+
+        let latency_ms = message.sent_at.elapsed().as_secs_f64() * 1000.0;
+        let key = format!("{:?}", message.message_type);
+        {
+            let mut collector = self.shared.collector.lock().unwrap();
+            collector.latencies.entry(key).or_default().push(latency_ms);
+
+            let stats = &mut collector.stats[self.index];
+            let received = stats.messages_received as f64;
+            stats.avg_delivery_time_ms = (stats.avg_delivery_time_ms * received + latency_ms) / (received + 1.0);
+            stats.messages_received += 1;
+            stats.messages_delivered += 1;
+            stats.bytes_received += message.bytes as u64;
+        }
+
+        // Handling stands in for the acknowledgement the sender waits for
+        let outcome = InteractionOutcome::Success { latency_ms: latency_ms as u64 };
+        self.shared.record_interaction(message.from, self.index, outcome).await;
+        self.shared.mesh.record_peer_latency(&self.shared.node_ids[self.index], latency_ms).await;
+    }
+
+    fn flip_membership(&mut self) {
+        let member = self.shared.node_ids[self.index].to_string();
+        let change = if self.in_group {
+            MembershipChange::Leave { member: member.clone() }
+        } else {
+            MembershipChange::Join { member: member.clone(), role: GroupRole::Member }
+        };
+        self.shared.roster.lock().unwrap().record_change(&member, change, Utc::now());
+
+        let mut collector = self.shared.collector.lock().unwrap();
+        if self.in_group {
+            collector.group_leaves += 1;
+        } else {
+            collector.group_joins += 1;
+        }
+        self.in_group = !self.in_group;
+    }
+
+    async fn fail(&mut self, kind: FailureKind, end: Instant) {
+        let node_id = self.shared.node_ids[self.index];
+        debug!("Injecting {:?} into simulated node {}", kind, node_id);
+        match kind {
+            FailureKind::Crash => {
+                self.shared.online[self.index].store(false, Ordering::Relaxed);
+                self.shared.collector.lock().unwrap().crashed.push(self.index);
+                let lost = ConnectionState::Lost { last_error: Some("crash injected".to_string()) };
+                self.shared.set_connection_state(self.index, lost).await;
+                publish_lifecycle(&self.shared, node_id, NodeLifecycleType::NodeLeft, Some("crash injected".to_string())).await;
+            }
+            FailureKind::Hang { duration_ms } => {
+                self.shared.collector.lock().unwrap().hangs.push((self.index, duration_ms));
+                let lost = ConnectionState::Lost { last_error: Some("hang injected".to_string()) };
+                self.shared.set_connection_state(self.index, lost).await;
+                let until = (Instant::now() + Duration::from_millis(duration_ms)).min(end);
+                tokio::time::sleep_until(until).await;
+                self.shared.set_connection_state(self.index, ConnectionState::Connected).await;
+            }
+            FailureKind::SlowHandler { delay_ms } => {
+                self.handler_delay = Duration::from_millis(delay_ms);
+            }
+        }
+    }
+
+    async fn publish_resource(&self, resource_type: ResourceEventType) {
+        let node_id = self.shared.node_ids[self.index];
+        let event = MeshEvent {
+            event_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            source_node: node_id,
+            event_type: EventType::Resource { resource_type: resource_type.clone() },
+            payload: EventPayload::Resource {
+                resource_id: format!("sim/{}", node_id),
+                resource_type: "simulated".to_string(),
+                operation: format!("{:?}", resource_type),
+                affected_nodes: vec![node_id],
+                conflict_info: None,
+            },
+            metadata: HashMap::new(),
+            propagation_path: vec![node_id],
+            correlation_id: None,
+            priority: EventPriority::Low,
+        };
+        let _ = self.shared.events.publish_event(event).await;
+    }
+}
+
+async fn publish_lifecycle(shared: &Shared, node_id: Uuid, lifecycle_type: NodeLifecycleType, reason: Option<String>) {
+    let event = shared.events.create_node_event(lifecycle_type, node_id, None, reason);
+    let _ = shared.events.publish_event(event).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMOKE_PROFILE: &str = r#"
+name = "smoke"
+nodes = 5
+duration_ms = 2000
+seed = 42
+
+[[messages]]
+message_type = "Collaboration"
+distribution = { kind = "poisson", rate_per_sec = 40.0 }
+payload_bytes = 128
+
+[[messages]]
+message_type = "Heartbeat"
+distribution = { kind = "constant", rate_per_sec = 5.0 }
+
+[resources]
+publish_per_sec = 2.0
+update_per_sec = 4.0
+
+[churn]
+flips_per_sec = 2.0
+
+[[failures]]
+node = 1
+at_ms = 300
+kind = "crash"
+
+[[failures]]
+node = 3
+at_ms = 200
+kind = "slow_handler"
+delay_ms = 5
+"#;
+
+    #[test]
+    fn test_profile_from_toml() {
+        let profile = SimulationProfile::from_toml(SMOKE_PROFILE).unwrap();
+        assert_eq!(profile.nodes, 5);
+        assert_eq!(profile.messages[0].message_type, MessageType::Collaboration);
+        assert_eq!(profile.messages[1].payload_bytes, 256);
+        assert_eq!(profile.failures[0].kind, FailureKind::Crash);
+        assert_eq!(profile.failures[1].kind, FailureKind::SlowHandler { delay_ms: 5 });
+
+        let bad = SMOKE_PROFILE.replace("node = 1", "node = 9");
+        assert!(SimulationProfile::from_toml(&bad).is_err());
+    }
+
+    #[test]
+    fn test_latency_percentiles() {
+        let p = LatencyPercentiles::from_samples((1..=100).map(|v| v as f64).collect());
+        assert_eq!(p.count, 100);
+        assert_eq!(p.p50_ms, 50.0);
+        assert_eq!(p.p90_ms, 90.0);
+        assert_eq!(p.p99_ms, 99.0);
+        assert_eq!(p.max_ms, 100.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_small_simulation_reports_crash() {
+        let simulation = Simulation::new(SimulationProfile::from_toml(SMOKE_PROFILE).unwrap());
+        let report = simulation.run().await.unwrap();
+
+        assert_eq!(report.profile, "smoke");
+        assert_eq!(report.nodes.len(), 5);
+        assert!(report.elapsed_ms >= 2000);
+        assert!(report.communication.messages_sent > 0);
+        assert!(report.communication.messages_received > 0);
+        assert!(report.latency.contains_key("Collaboration"));
+        assert!(report.resources_published + report.resources_updated > 0);
+        assert!(report.group_joins > 0);
+
+        // Measured by node 0's mesh view: the crashed node is lost, the rest connected
+        assert_eq!(report.mesh.active_nodes, 4);
+        assert_eq!(report.mesh.connected_nodes, 3);
+        assert!(report.mesh.avg_response_time > 0.0);
+
+        let crashed = report.nodes[1].node_id;
+        assert!(report.nodes[1].crashed);
+        assert!(report.anomalies.contains(&Anomaly::NodeCrashed { node: crashed }));
+        assert!(report.anomalies.iter().any(|a| matches!(a, Anomaly::DeadLetters { .. })));
+
+        // Peers sending into the dead letters stop trusting the crashed node
+        assert!(report.nodes[1].quarantined);
+        assert!(report.anomalies.iter().any(|a| matches!(a, Anomaly::NodeQuarantined { node, .. } if *node == crashed)));
+        assert_eq!(report.nodes.iter().filter(|node| node.quarantined).count(), 1);
+
+        let node_left: Vec<MeshEvent> = simulation.event_system().get_event_history(Some("node")).await
+            .into_iter()
+            .filter(|event| event.event_type == EventType::NodeLifecycle { lifecycle_type: NodeLifecycleType::NodeLeft })
+            .collect();
+        assert_eq!(node_left.len(), 1);
+        assert!(matches!(&node_left[0].payload, EventPayload::NodeLifecycle { node_id, .. } if *node_id == crashed));
+    }
+}
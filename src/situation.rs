@@ -9,6 +9,10 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+
+use crate::financial::{Clock, SystemClock};
 
 /// Situation provider trait for implementing situation-specific behavior
 #[async_trait]
@@ -28,6 +32,13 @@ pub trait SituationProvider: Send + Sync {
     /// Detect if this situation applies to the current conditions
     async fn detect_situation(&self, detection_data: &SituationDetectionData) -> Result<SituationMatch>;
     
+    /// Explain which parts of the detection data drive a detection
+    ///
+    /// Providers that cannot explain their detections return None.
+    fn explain(&self, _detection: &SituationDetectionData) -> Option<SituationExplanation> {
+        None
+    }
+    
     /// Adapt behavior for this situation
     async fn adapt_behavior(&self, adaptation_request: &BehaviorAdaptationRequest) -> Result<BehaviorAdaptation>;
     
@@ -153,6 +164,28 @@ pub struct SituationMatch {
     pub priority: u32,
 }
 
+/// Why a situation was or would be detected
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SituationExplanation {
+    /// Probability that the situation matches (0.0 to 1.0)
+    pub match_probability: f64,
+    /// Signals that drove the detection
+    pub contributing_signals: Vec<SignalContribution>,
+    /// Changes to the detection data that would alter the outcome
+    pub counterfactuals: Vec<String>,
+}
+
+/// One field of the detection data and how much it drove the detection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignalContribution {
+    /// Path of the field in `SituationDetectionData`
+    pub signal_name: String,
+    /// Observed value
+    pub signal_value: String,
+    /// Share of the match probability due to this signal
+    pub contribution_to_score: f64,
+}
+
 /// Request for behavior adaptation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BehaviorAdaptationRequest {
@@ -249,6 +282,40 @@ pub struct SituationConfig {
     pub optional_capabilities: Vec<String>,
     /// Configuration parameters
     pub parameters: HashMap<String, serde_json::Value>,
+    /// Log detections and adaptations instead of applying them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Level at which dry-run results are logged
+    #[serde(default = "default_dry_run_log_level", with = "level_serde")]
+    pub dry_run_log_level: tracing::Level,
+}
+
+fn default_dry_run_log_level() -> tracing::Level {
+    tracing::Level::INFO
+}
+
+/// Serializes a `tracing::Level` by name
+mod level_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+    
+    pub fn serialize<S: Serializer>(level: &tracing::Level, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(level.as_str())
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<tracing::Level, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Log a dry-run result at a level chosen at runtime
+fn log_dry_run(level: tracing::Level, situation_id: &str, message: &str) {
+    match level {
+        tracing::Level::ERROR => tracing::error!("[dry run] {}: {}", situation_id, message),
+        tracing::Level::WARN => tracing::warn!("[dry run] {}: {}", situation_id, message),
+        tracing::Level::INFO => tracing::info!("[dry run] {}: {}", situation_id, message),
+        tracing::Level::DEBUG => tracing::debug!("[dry run] {}: {}", situation_id, message),
+        _ => tracing::trace!("[dry run] {}: {}", situation_id, message),
+    }
 }
 
 /// Initialization data for situation providers
@@ -285,8 +352,14 @@ pub struct SituationProviderRegistry {
     providers: HashMap<String, Arc<dyn SituationProvider>>,
     /// Active situations
     active_situations: HashMap<String, SituationState>,
+    /// Situations pinned by operators, overriding provider matches
+    pins: HashMap<String, SituationState>,
+    /// Every pin, unpin and expiry, oldest first
+    pin_audit: Vec<PinAuditEntry>,
+    pin_events: broadcast::Sender<SituationPinEvent>,
     /// Registry configuration
     config: RegistryConfig,
+    clock: Arc<dyn Clock>,
 }
 
 impl std::fmt::Debug for SituationProviderRegistry {
@@ -294,13 +367,14 @@ impl std::fmt::Debug for SituationProviderRegistry {
         f.debug_struct("SituationProviderRegistry")
             .field("providers", &format!("{} providers", self.providers.len()))
             .field("active_situations", &self.active_situations)
+            .field("pins", &self.pins)
             .field("config", &self.config)
             .finish()
     }
 }
 
 /// State of an active situation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SituationState {
     /// Situation ID
     pub situation_id: String,
@@ -312,6 +386,50 @@ pub struct SituationState {
     pub active_adaptations: Vec<String>,
     /// Last update time
     pub last_update: chrono::DateTime<chrono::Utc>,
+    /// Set when an operator pinned this situation
+    pub pinned: Option<PinMarker>,
+}
+
+/// Who pinned a situation, why, and until when
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinMarker {
+    /// Operator who pinned it
+    pub actor: String,
+    /// Why it was pinned
+    pub reason: String,
+    /// When it was pinned
+    pub pinned_at: DateTime<Utc>,
+    /// When the pin lapses
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Change to the pinned situations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SituationPinEvent {
+    /// An operator pinned a situation
+    Pinned {
+        situation_id: String,
+        marker: PinMarker,
+        adaptation_requests: usize,
+    },
+    /// An operator cleared a pin before it expired
+    Unpinned {
+        situation_id: String,
+        actor: String,
+    },
+    /// A pin reached its TTL
+    Expired {
+        situation_id: String,
+    },
+}
+
+/// A pin event and when it happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinAuditEntry {
+    /// When the event happened
+    pub at: DateTime<Utc>,
+    /// What happened
+    pub event: SituationPinEvent,
 }
 
 /// Configuration for the situation provider registry
@@ -362,8 +480,121 @@ impl SituationProviderRegistry {
         Self {
             providers: HashMap::new(),
             active_situations: HashMap::new(),
+            pins: HashMap::new(),
+            pin_audit: Vec::new(),
+            pin_events: broadcast::channel(64).0,
             config,
+            clock: Arc::new(SystemClock),
+        }
+    }
+    
+    /// Use `clock` for pin timestamps and expiry
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Pin a situation, overriding provider matches until `ttl` passes or it is unpinned
+    ///
+    /// Returns the adaptation requests the pinned situation makes, one per
+    /// entry in `state.active_adaptations`.
+    pub fn pin_situation(
+        &mut self,
+        mut state: SituationState,
+        ttl: chrono::Duration,
+        reason: &str,
+        actor: &str,
+    ) -> Result<Vec<BehaviorAdaptationRequest>> {
+        if ttl <= chrono::Duration::zero() {
+            return Err(anyhow::anyhow!("Pin TTL must be positive"));
+        }
+        self.expire_pins();
+        
+        let now = self.clock.now();
+        let marker = PinMarker {
+            actor: actor.to_string(),
+            reason: reason.to_string(),
+            pinned_at: now,
+            expires_at: now + ttl,
+        };
+        state.activated_at = now;
+        state.last_update = now;
+        state.pinned = Some(marker.clone());
+        
+        let requests: Vec<BehaviorAdaptationRequest> = state.active_adaptations.iter()
+            .map(|adaptation| BehaviorAdaptationRequest {
+                adaptation_type: AdaptationType::Custom(adaptation.clone()),
+                current_behavior: HashMap::new(),
+                situation_parameters: HashMap::from([
+                    ("situation_id".to_string(), serde_json::json!(state.situation_id)),
+                    ("confidence".to_string(), serde_json::json!(state.confidence)),
+                    ("pinned_by".to_string(), serde_json::json!(actor)),
+                    ("pin_reason".to_string(), serde_json::json!(reason)),
+                ]),
+                affected_participants: Vec::new(),
+                urgency: UrgencyLevel::High,
+            })
+            .collect();
+        
+        tracing::info!("Situation {} pinned by {} until {}: {}", state.situation_id, actor, marker.expires_at, reason);
+        let situation_id = state.situation_id.clone();
+        self.pins.insert(situation_id.clone(), state);
+        self.record_pin_event(SituationPinEvent::Pinned {
+            situation_id,
+            marker,
+            adaptation_requests: requests.len(),
+        });
+        Ok(requests)
+    }
+    
+    /// Clear a pin before it expires
+    pub fn unpin_situation(&mut self, situation_id: &str, actor: &str) -> Result<()> {
+        self.expire_pins();
+        self.pins.remove(situation_id)
+            .ok_or_else(|| anyhow::anyhow!("Situation not pinned: {}", situation_id))?;
+        tracing::info!("Situation {} unpinned by {}", situation_id, actor);
+        self.record_pin_event(SituationPinEvent::Unpinned {
+            situation_id: situation_id.to_string(),
+            actor: actor.to_string(),
+        });
+        Ok(())
+    }
+    
+    /// Drop pins past their TTL, returning the expired situation IDs
+    pub fn expire_pins(&mut self) -> Vec<String> {
+        let now = self.clock.now();
+        let expired: Vec<String> = self.pins.iter()
+            .filter(|(_, state)| state.pinned.as_ref().is_none_or(|pin| pin.expires_at <= now))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for situation_id in &expired {
+            self.pins.remove(situation_id);
+            self.record_pin_event(SituationPinEvent::Expired { situation_id: situation_id.clone() });
         }
+        expired
+    }
+    
+    /// Pinned situations that have not expired
+    pub fn pinned_situations(&self) -> Vec<&SituationState> {
+        let now = self.clock.now();
+        self.pins.values()
+            .filter(|state| state.pinned.as_ref().is_some_and(|pin| pin.expires_at > now))
+            .collect()
+    }
+    
+    /// Pin changes as they happen
+    pub fn subscribe_pin_events(&self) -> broadcast::Receiver<SituationPinEvent> {
+        self.pin_events.subscribe()
+    }
+    
+    /// Every pin, unpin and expiry so far
+    pub fn pin_audit_log(&self) -> &[PinAuditEntry] {
+        &self.pin_audit
+    }
+    
+    fn record_pin_event(&mut self, event: SituationPinEvent) {
+        self.pin_audit.push(PinAuditEntry { at: self.clock.now(), event: event.clone() });
+        let _ = self.pin_events.send(event);
     }
     
     /// Register a situation provider
@@ -395,7 +626,16 @@ impl SituationProviderRegistry {
     }
     
     /// Detect and activate appropriate situations
+    ///
+    /// While a situation is pinned, provider matches are ignored and nothing
+    /// is activated.
     pub async fn detect_and_activate_situations(&mut self, detection_data: &SituationDetectionData) -> Result<Vec<String>> {
+        self.expire_pins();
+        if !self.pins.is_empty() {
+            tracing::debug!("Situation detection suppressed by {} pinned situations", self.pins.len());
+            return Ok(Vec::new());
+        }
+        
         let mut matches = Vec::new();
         
         // Check all registered providers
@@ -403,6 +643,12 @@ impl SituationProviderRegistry {
             match provider.detect_situation(detection_data).await {
                 Ok(situation_match) => {
                     if situation_match.matches && situation_match.confidence >= self.config.min_activation_confidence {
+                        let config = provider.get_situation_config();
+                        if config.dry_run {
+                            // Dry-run providers are only logged, never activated
+                            Self::log_dry_run_match(situation_id, &config, &situation_match, detection_data);
+                            continue;
+                        }
                         matches.push((situation_id.clone(), situation_match));
                     }
                 }
@@ -434,6 +680,51 @@ impl SituationProviderRegistry {
         Ok(activated)
     }
     
+    /// Evaluate every provider as if it were in dry-run mode
+    ///
+    /// Returns the adaptation requests matching situations would make, after
+    /// logging them. Nothing is activated, whatever the providers' configs say.
+    pub async fn dry_run_once(&self, data: SituationDetectionData) -> Vec<BehaviorAdaptationRequest> {
+        let mut requests = Vec::new();
+        for (situation_id, provider) in &self.providers {
+            match provider.detect_situation(&data).await {
+                Ok(situation_match) => {
+                    if situation_match.matches && situation_match.confidence >= self.config.min_activation_confidence {
+                        let config = provider.get_situation_config();
+                        requests.extend(Self::log_dry_run_match(situation_id, &config, &situation_match, &data));
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error detecting situation {}: {}", situation_id, e);
+                }
+            }
+        }
+        requests
+    }
+    
+    /// Log a match and the adaptations it would request
+    fn log_dry_run_match(
+        situation_id: &str,
+        config: &SituationConfig,
+        situation_match: &SituationMatch,
+        data: &SituationDetectionData,
+    ) -> Vec<BehaviorAdaptationRequest> {
+        log_dry_run(config.dry_run_log_level, situation_id, &format!(
+            "would activate with confidence {:.2} ({})",
+            situation_match.confidence,
+            situation_match.reasons.join("; "),
+        ));
+        let requests = adaptation_requests(situation_id, situation_match, data);
+        for request in &requests {
+            log_dry_run(config.dry_run_log_level, situation_id, &format!(
+                "would request {:?} adaptation for {} participants",
+                request.adaptation_type,
+                request.affected_participants.len(),
+            ));
+        }
+        requests
+    }
+    
     /// Activate a specific situation
     pub async fn activate_situation(&mut self, situation_id: &str, confidence: f64) -> Result<()> {
         if !self.providers.contains_key(situation_id) {
@@ -446,6 +737,7 @@ impl SituationProviderRegistry {
             confidence,
             active_adaptations: Vec::new(),
             last_update: chrono::Utc::now(),
+            pinned: None,
         };
         
         self.active_situations.insert(situation_id.to_string(), state);
@@ -463,7 +755,8 @@ impl SituationProviderRegistry {
     pub async fn request_adaptation(&mut self, request: &BehaviorAdaptationRequest) -> Result<Vec<BehaviorAdaptation>> {
         let mut adaptations = Vec::new();
         
-        for situation_id in self.active_situations.keys() {
+        for situation in self.get_active_situations() {
+            let situation_id = &situation.situation_id;
             if let Some(provider) = self.providers.get(situation_id) {
                 match provider.adapt_behavior(request).await {
                     Ok(adaptation) => {
@@ -482,7 +775,13 @@ impl SituationProviderRegistry {
     }
     
     /// Get list of active situations
+    ///
+    /// Unexpired pins replace the provider-driven situations entirely.
     pub fn get_active_situations(&self) -> Vec<&SituationState> {
+        let pinned = self.pinned_situations();
+        if !pinned.is_empty() {
+            return pinned;
+        }
         self.active_situations.values().collect()
     }
     
@@ -492,6 +791,26 @@ impl SituationProviderRegistry {
     }
 }
 
+/// Adaptation requests for the adaptations a match suggests
+pub fn adaptation_requests(
+    situation_id: &str,
+    situation_match: &SituationMatch,
+    data: &SituationDetectionData,
+) -> Vec<BehaviorAdaptationRequest> {
+    situation_match.suggested_adaptations.iter()
+        .map(|adaptation| BehaviorAdaptationRequest {
+            adaptation_type: AdaptationType::Custom(adaptation.clone()),
+            current_behavior: HashMap::new(),
+            situation_parameters: HashMap::from([
+                ("situation_id".to_string(), serde_json::json!(situation_id)),
+                ("confidence".to_string(), serde_json::json!(situation_match.confidence)),
+            ]),
+            affected_participants: data.participants.iter().map(|p| p.id.clone()).collect(),
+            urgency: UrgencyLevel::Normal,
+        })
+        .collect()
+}
+
 /// Basic situation provider implementation for testing
 pub struct BasicSituationProvider {
     situation_id: String,
@@ -502,6 +821,15 @@ pub struct BasicSituationProvider {
 }
 
 impl BasicSituationProvider {
+    /// Confidence of every detection
+    const DETECTION_CONFIDENCE: f64 = 0.5;
+    /// Connection quality below which the network counts as degraded
+    const MIN_CONNECTION_QUALITY: f64 = 0.5;
+    /// Node count from which the mesh counts as large
+    const LARGE_MESH_NODES: usize = 10;
+    /// Success rate below which a communication pattern counts as failing
+    const MIN_PATTERN_SUCCESS_RATE: f64 = 0.5;
+    
     pub fn new(situation_id: String, situation_name: String) -> Self {
         let config = SituationConfig {
             situation_id: situation_id.clone(),
@@ -511,6 +839,8 @@ impl BasicSituationProvider {
             required_capabilities: vec!["basic-communication".to_string()],
             optional_capabilities: vec![],
             parameters: HashMap::new(),
+            dry_run: false,
+            dry_run_log_level: default_dry_run_log_level(),
         };
         
         Self {
@@ -521,6 +851,35 @@ impl BasicSituationProvider {
             config,
         }
     }
+    
+    /// Only log detections and adaptations, at `log_level`
+    pub fn with_dry_run(mut self, log_level: tracing::Level) -> Self {
+        self.config.dry_run = true;
+        self.config.dry_run_log_level = log_level;
+        self
+    }
+    
+    fn detect(&self, _detection_data: &SituationDetectionData) -> SituationMatch {
+        // Basic implementation always matches with low confidence
+        SituationMatch {
+            matches: true,
+            confidence: Self::DETECTION_CONFIDENCE,
+            reasons: vec!["Basic situation provider always matches".to_string()],
+            suggested_adaptations: vec!["basic-adaptation".to_string()],
+            priority: 1,
+        }
+    }
+    
+    /// Test helper: panic unless this provider detects its situation in `data`
+    pub fn assert_would_detect(&self, data: &SituationDetectionData) {
+        let situation_match = self.detect(data);
+        assert!(
+            situation_match.matches,
+            "situation {} would not be detected: {}",
+            self.situation_id,
+            situation_match.reasons.join("; "),
+        );
+    }
 }
 
 #[async_trait]
@@ -541,14 +900,73 @@ impl SituationProvider for BasicSituationProvider {
         &self.description
     }
     
-    async fn detect_situation(&self, _detection_data: &SituationDetectionData) -> Result<SituationMatch> {
-        // Basic implementation always matches with low confidence
-        Ok(SituationMatch {
-            matches: true,
-            confidence: 0.5,
-            reasons: vec!["Basic situation provider always matches".to_string()],
-            suggested_adaptations: vec!["basic-adaptation".to_string()],
-            priority: 1,
+    async fn detect_situation(&self, detection_data: &SituationDetectionData) -> Result<SituationMatch> {
+        Ok(self.detect(detection_data))
+    }
+    
+    fn explain(&self, detection: &SituationDetectionData) -> Option<SituationExplanation> {
+        // (signal name, observed value, exceeded threshold, counterfactual)
+        let topology = &detection.environment.network_topology;
+        let mut signals = vec![
+            (
+                "environment.network_topology.connection_quality".to_string(),
+                format!("{:.2}", topology.connection_quality),
+                topology.connection_quality < Self::MIN_CONNECTION_QUALITY,
+                format!("connection_quality below {:.2}", Self::MIN_CONNECTION_QUALITY),
+            ),
+            (
+                "environment.network_topology.node_count".to_string(),
+                topology.node_count.to_string(),
+                topology.node_count >= Self::LARGE_MESH_NODES,
+                format!("node_count of at least {}", Self::LARGE_MESH_NODES),
+            ),
+            (
+                "temporal_situation.is_leisure_time".to_string(),
+                detection.temporal_situation.is_leisure_time.to_string(),
+                detection.temporal_situation.is_leisure_time,
+                "is_leisure_time set".to_string(),
+            ),
+        ];
+        for (index, pattern) in detection.communication_patterns.iter().enumerate() {
+            signals.push((
+                format!("communication_patterns[{}].success_rate", index),
+                format!("{:.2}", pattern.success_rate),
+                pattern.success_rate < Self::MIN_PATTERN_SUCCESS_RATE,
+                format!("{} success_rate below {:.2}", pattern.pattern_type, Self::MIN_PATTERN_SUCCESS_RATE),
+            ));
+        }
+        let missing: Vec<&String> = self.config.required_capabilities.iter()
+            .filter(|c| !detection.system_capabilities.contains(c))
+            .collect();
+        signals.push((
+            "system_capabilities".to_string(),
+            detection.system_capabilities.join(","),
+            missing.is_empty(),
+            format!("missing capabilities provided: {:?}", missing),
+        ));
+        
+        let exceeded = signals.iter().filter(|(_, _, exceeded, _)| *exceeded).count();
+        let mut contributing_signals = Vec::new();
+        let mut counterfactuals = Vec::new();
+        for (signal_name, signal_value, exceeded_threshold, counterfactual) in signals {
+            if exceeded_threshold {
+                contributing_signals.push(SignalContribution {
+                    signal_name,
+                    signal_value,
+                    contribution_to_score: Self::DETECTION_CONFIDENCE / exceeded as f64,
+                });
+            } else {
+                counterfactuals.push(format!("Would also contribute with {}", counterfactual));
+            }
+        }
+        if contributing_signals.is_empty() {
+            counterfactuals.push("Matches unconditionally; no signal exceeded its threshold".to_string());
+        }
+        
+        Some(SituationExplanation {
+            match_probability: Self::DETECTION_CONFIDENCE,
+            contributing_signals,
+            counterfactuals,
         })
     }
     
@@ -630,6 +1048,64 @@ mod tests {
         assert!(situation_match.confidence > 0.0);
     }
     
+    #[test]
+    fn test_basic_situation_provider_explain() {
+        let provider = BasicSituationProvider::new(
+            "test-situation".to_string(),
+            "Test Situation".to_string(),
+        );
+        
+        let detection_data = SituationDetectionData {
+            environment: EnvironmentInfo {
+                environment_type: "test".to_string(),
+                security_level: "basic".to_string(),
+                available_resources: vec![],
+                network_topology: NetworkTopology {
+                    topology_type: "mesh".to_string(),
+                    node_count: 12,
+                    connection_quality: 0.9,
+                    bandwidth: "high".to_string(),
+                    latency: "low".to_string(),
+                },
+                device_capabilities: vec![],
+            },
+            participants: vec![],
+            communication_patterns: vec![CommunicationPattern {
+                pattern_type: "sync".to_string(),
+                frequency: 1.0,
+                participants: vec![],
+                success_rate: 0.2,
+                tags: vec![],
+            }],
+            system_capabilities: vec![],
+            user_preferences: HashMap::new(),
+            temporal_situation: TemporalSituation {
+                timestamp: chrono::Utc::now(),
+                timezone: "UTC".to_string(),
+                day_of_week: "Monday".to_string(),
+                time_of_day: "morning".to_string(),
+                is_leisure_time: false,
+            },
+        };
+        
+        let explanation = provider.explain(&detection_data).unwrap();
+        let names: Vec<&str> = explanation.contributing_signals.iter()
+            .map(|s| s.signal_name.as_str())
+            .collect();
+        assert_eq!(names, vec![
+            "environment.network_topology.node_count",
+            "communication_patterns[0].success_rate",
+        ]);
+        assert_eq!(explanation.contributing_signals[1].signal_value, "0.20");
+        let total: f64 = explanation.contributing_signals.iter().map(|s| s.contribution_to_score).sum();
+        assert!((total - explanation.match_probability).abs() < 1e-9);
+        assert!(explanation.counterfactuals.iter().any(|c| c.contains("basic-communication")));
+        
+        let json = serde_json::to_string(&explanation).unwrap();
+        let decoded: SituationExplanation = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, explanation);
+    }
+    
     #[tokio::test]
     async fn test_situation_provider_registry() {
         let mut registry = SituationProviderRegistry::new(RegistryConfig::default());
@@ -651,4 +1127,136 @@ mod tests {
         registry.deactivate_situation("test-situation").unwrap();
         assert_eq!(registry.get_active_situations().len(), 0);
     }
+    
+    #[tokio::test]
+    async fn test_dry_run_logs_instead_of_activating() {
+        let mut registry = SituationProviderRegistry::new(RegistryConfig {
+            min_activation_confidence: 0.5,
+            ..RegistryConfig::default()
+        });
+        let dry = BasicSituationProvider::new("dry".to_string(), "Dry".to_string())
+            .with_dry_run(tracing::Level::DEBUG);
+        registry.register_provider(Arc::new(dry)).unwrap();
+        registry.register_provider(Arc::new(BasicSituationProvider::new("live".to_string(), "Live".to_string()))).unwrap();
+        
+        let data = test_detection_data();
+        BasicSituationProvider::new("dry".to_string(), "Dry".to_string()).assert_would_detect(&data);
+        
+        // The dry-run provider is evaluated but never activated
+        let activated = registry.detect_and_activate_situations(&data).await.unwrap();
+        assert_eq!(activated, vec!["live".to_string()]);
+        
+        // dry_run_once evaluates every provider and activates nothing more
+        registry.deactivate_situation("live").unwrap();
+        let requests = registry.dry_run_once(data).await;
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.adaptation_type == AdaptationType::Custom("basic-adaptation".to_string())));
+        assert_eq!(requests[0].affected_participants, vec!["alice".to_string()]);
+        assert!(registry.get_active_situations().is_empty());
+        
+        // The log level survives a config round trip
+        let config = BasicSituationProvider::new("dry".to_string(), "Dry".to_string())
+            .with_dry_run(tracing::Level::WARN)
+            .get_situation_config();
+        let decoded: SituationConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert!(decoded.dry_run);
+        assert_eq!(decoded.dry_run_log_level, tracing::Level::WARN);
+    }
+    
+    #[tokio::test]
+    async fn test_pinned_situation_overrides_providers_until_expiry() {
+        use crate::financial::ManualClock;
+        
+        let clock = ManualClock::new(chrono::Utc::now());
+        let mut registry = SituationProviderRegistry::new(RegistryConfig {
+            min_activation_confidence: 0.5,
+            ..RegistryConfig::default()
+        }).with_clock(Arc::new(clock.clone()));
+        registry.register_provider(Arc::new(BasicSituationProvider::new("live".to_string(), "Live".to_string()))).unwrap();
+        let mut events = registry.subscribe_pin_events();
+        let data = test_detection_data();
+        
+        let requests = registry.pin_situation(SituationState {
+            situation_id: "degraded".to_string(),
+            activated_at: chrono::Utc::now(),
+            confidence: 1.0,
+            active_adaptations: vec!["shed-load".to_string(), "freeze-deploys".to_string()],
+            last_update: chrono::Utc::now(),
+            pinned: None,
+        }, chrono::Duration::minutes(30), "incident 42", "oncall-ana").unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].situation_parameters["pinned_by"], serde_json::json!("oncall-ana"));
+        
+        // Provider matches are suppressed while pinned
+        assert!(registry.detect_and_activate_situations(&data).await.unwrap().is_empty());
+        let active = registry.get_active_situations();
+        assert_eq!(active.len(), 1);
+        let marker = active[0].pinned.as_ref().unwrap();
+        assert_eq!((marker.actor.as_str(), marker.reason.as_str()), ("oncall-ana", "incident 42"));
+        
+        // After the TTL, detection is provider-driven again
+        clock.advance(chrono::Duration::minutes(31));
+        assert!(registry.pinned_situations().is_empty());
+        assert_eq!(registry.detect_and_activate_situations(&data).await.unwrap(), vec!["live".to_string()]);
+        assert!(registry.get_active_situations().iter().all(|s| s.pinned.is_none()));
+        
+        // Explicit unpinning is audited with the actor
+        registry.pin_situation(SituationState {
+            situation_id: "maintenance".to_string(),
+            activated_at: chrono::Utc::now(),
+            confidence: 1.0,
+            active_adaptations: vec![],
+            last_update: chrono::Utc::now(),
+            pinned: None,
+        }, chrono::Duration::hours(1), "planned window", "oncall-ana").unwrap();
+        registry.unpin_situation("maintenance", "oncall-ben").unwrap();
+        assert!(registry.unpin_situation("maintenance", "oncall-ben").is_err());
+        
+        let audit: Vec<&SituationPinEvent> = registry.pin_audit_log().iter().map(|e| &e.event).collect();
+        assert!(matches!(audit[0], SituationPinEvent::Pinned { situation_id, adaptation_requests: 2, .. } if situation_id == "degraded"));
+        assert_eq!(audit[1], &SituationPinEvent::Expired { situation_id: "degraded".to_string() });
+        assert!(matches!(audit[2], SituationPinEvent::Pinned { situation_id, .. } if situation_id == "maintenance"));
+        assert_eq!(audit[3], &SituationPinEvent::Unpinned {
+            situation_id: "maintenance".to_string(),
+            actor: "oncall-ben".to_string(),
+        });
+        assert_eq!(registry.pin_audit_log()[1].at, clock.now());
+        assert_eq!(events.try_recv().unwrap(), *audit[0]);
+    }
+    
+    fn test_detection_data() -> SituationDetectionData {
+        SituationDetectionData {
+            environment: EnvironmentInfo {
+                environment_type: "test".to_string(),
+                security_level: "basic".to_string(),
+                available_resources: vec![],
+                network_topology: NetworkTopology {
+                    topology_type: "mesh".to_string(),
+                    node_count: 2,
+                    connection_quality: 1.0,
+                    bandwidth: "high".to_string(),
+                    latency: "low".to_string(),
+                },
+                device_capabilities: vec![],
+            },
+            participants: vec![ParticipantInfo {
+                id: "alice".to_string(),
+                participant_type: "human".to_string(),
+                role: "member".to_string(),
+                capabilities: vec![],
+                preferences: HashMap::new(),
+                status: "active".to_string(),
+            }],
+            communication_patterns: vec![],
+            system_capabilities: vec![],
+            user_preferences: HashMap::new(),
+            temporal_situation: TemporalSituation {
+                timestamp: chrono::Utc::now(),
+                timezone: "UTC".to_string(),
+                day_of_week: "Monday".to_string(),
+                time_of_day: "morning".to_string(),
+                is_leisure_time: false,
+            },
+        }
+    }
 }
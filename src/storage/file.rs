@@ -0,0 +1,382 @@
+//! File-backed storage that survives restarts
+//!
+//! [`FileStorage`] keeps each resource as `<resource_id>.json` in one
+//! directory and holds only metadata in memory. Every write goes to a
+//! `.partial` file first and is renamed into place, so a crash never leaves
+//! a half-written resource under its real name. [`FileStorage::open`] removes
+//! leftover partial files and sets aside resources that fail to parse or to
+//! match their checksum.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+
+use super::{
+    checksummed, AccessControl, CompiledSchema, ResourceFilter, ResourceMetadata, Storage, StorageStats, StoredResource,
+    DEFAULT_MAX_CONTENT_SEARCH_BYTES,
+};
+use crate::compute::ComputePool;
+use crate::WeaveMeshError;
+
+/// Extension of resource files
+const RESOURCE_EXTENSION: &str = "json";
+
+/// Extension of writes not yet renamed into place
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// Extension given to resource files that could not be loaded
+const CORRUPT_EXTENSION: &str = "corrupt";
+
+/// Storage persisting each resource as a JSON file in a directory
+///
+/// Reads take `&self`, so tasks sharing the storage behind a
+/// `tokio::sync::RwLock` read concurrently while writes are serialized.
+#[derive(Debug)]
+pub struct FileStorage {
+    dir: PathBuf,
+    resources: HashMap<String, ResourceMetadata>,
+    /// Resources found corrupt, each counted once however often it is read
+    corrupt_resources: std::sync::Mutex<HashSet<String>>,
+    content_searches: AtomicU64,
+    max_content_search_bytes: usize,
+    /// JSON Schemas applied to new resources, by content type
+    schemas: HashMap<String, CompiledSchema>,
+    /// Files set aside by `open` because they could not be loaded
+    quarantined: Vec<PathBuf>,
+    /// Pool checksums are computed on, if any
+    compute_pool: Option<ComputePool>,
+}
+
+impl FileStorage {
+    /// Open the storage in `dir`, creating the directory if needed
+    ///
+    /// Partial writes from an interrupted process are deleted. Resource
+    /// files that fail to parse or whose content no longer matches its
+    /// checksum are renamed to `.corrupt` and listed by [`Self::quarantined`].
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create storage directory {}", dir.display()))?;
+
+        let mut resources = HashMap::new();
+        let mut quarantined = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(PARTIAL_EXTENSION) => {
+                    debug!("Removing partial write {}", path.display());
+                    std::fs::remove_file(&path)?;
+                }
+                Some(RESOURCE_EXTENSION) => match load_resource(&path) {
+                    Ok(metadata) => {
+                        resources.insert(metadata.resource_id.clone(), metadata);
+                    }
+                    Err(e) => {
+                        warn!("Quarantining unreadable resource {}: {}", path.display(), e);
+                        let target = path.with_extension(CORRUPT_EXTENSION);
+                        std::fs::rename(&path, &target)?;
+                        quarantined.push(target);
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            dir,
+            resources,
+            corrupt_resources: std::sync::Mutex::new(HashSet::new()),
+            content_searches: AtomicU64::new(0),
+            max_content_search_bytes: DEFAULT_MAX_CONTENT_SEARCH_BYTES,
+            schemas: HashMap::new(),
+            quarantined,
+            compute_pool: None,
+        })
+    }
+
+    /// Validate resources stored with `content_type` against `schema`
+    ///
+    /// Fails if `schema` is not a valid JSON Schema.
+    pub fn with_schema(mut self, content_type: &str, schema: serde_json::Value) -> Result<Self> {
+        self.schemas.insert(content_type.to_string(), CompiledSchema::compile(schema)?);
+        Ok(self)
+    }
+
+    /// Skip content queries over resources larger than `bytes`
+    pub fn with_max_content_search_bytes(mut self, bytes: usize) -> Self {
+        self.max_content_search_bytes = bytes;
+        self
+    }
+
+    /// Compute and verify checksums on `pool` instead of the calling task
+    pub fn with_compute_pool(mut self, pool: ComputePool) -> Self {
+        self.compute_pool = Some(pool);
+        self
+    }
+
+    /// Directory the resources are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Files found unreadable when the storage was opened
+    pub fn quarantined(&self) -> &[PathBuf] {
+        &self.quarantined
+    }
+
+    /// File holding the resource with `resource_id`
+    ///
+    /// IDs that could escape the directory are rejected.
+    fn resource_path(&self, resource_id: &str) -> Result<PathBuf> {
+        let valid = !resource_id.is_empty()
+            && resource_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(WeaveMeshError::Configuration(format!("invalid resource id: {}", resource_id)).into());
+        }
+        Ok(self.dir.join(format!("{}.{}", resource_id, RESOURCE_EXTENSION)))
+    }
+
+    /// Write `resource` to its file, replacing any previous version atomically
+    async fn write_resource(&self, resource: &StoredResource) -> Result<()> {
+        let path = self.resource_path(&resource.metadata.resource_id)?;
+        // Unique per write, so concurrent writers never share a partial file
+        let partial = path.with_extension(format!("{}.{}", uuid::Uuid::new_v4(), PARTIAL_EXTENSION));
+        tokio::fs::write(&partial, serde_json::to_vec(resource)?).await?;
+        if let Err(e) = tokio::fs::rename(&partial, &path).await {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Read the resource with `resource_id` from disk
+    async fn read_resource(&self, resource_id: &str) -> Result<StoredResource> {
+        if !self.resources.contains_key(resource_id) {
+            return Err(anyhow::anyhow!("Resource not found: {}", resource_id));
+        }
+        let bytes = tokio::fs::read(self.resource_path(resource_id)?).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Whether a resource passes the filter's content query, if it has one
+    fn matches_content(&self, filter: &ResourceFilter, metadata: &ResourceMetadata) -> bool {
+        let Some(query) = &filter.content_query else {
+            return true;
+        };
+        if metadata.size as usize > self.max_content_search_bytes {
+            return false;
+        }
+        let resource = self.resource_path(&metadata.resource_id)
+            .and_then(|path| Ok(std::fs::read(path)?))
+            .and_then(|bytes| Ok(serde_json::from_slice::<StoredResource>(&bytes)?));
+        match resource {
+            Ok(resource) => {
+                self.content_searches.fetch_add(1, Ordering::Relaxed);
+                query.matches(&resource.content)
+            }
+            Err(e) => {
+                warn!("Failed to read {} for a content query: {}", metadata.resource_id, e);
+                false
+            }
+        }
+    }
+}
+
+/// Metadata of the intact resource stored at `path`
+fn load_resource(path: &Path) -> Result<ResourceMetadata> {
+    let resource: StoredResource = serde_json::from_slice(&std::fs::read(path)?)?;
+    if !resource.verify_checksum() {
+        return Err(anyhow::anyhow!("checksum mismatch"));
+    }
+    if path.file_stem().and_then(|stem| stem.to_str()) != Some(resource.metadata.resource_id.as_str()) {
+        return Err(anyhow::anyhow!("file name does not match resource id {}", resource.metadata.resource_id));
+    }
+    Ok(resource.metadata)
+}
+
+impl Storage for FileStorage {
+    async fn store_resource(
+        &mut self,
+        name: String,
+        content: Vec<u8>,
+        content_type: String,
+        access_control: AccessControl,
+        tags: Vec<String>,
+    ) -> Result<String> {
+        let resource_id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now();
+
+        let metadata = ResourceMetadata {
+            resource_id: resource_id.clone(),
+            json_schema: self.schemas.get(&content_type).map(|compiled| compiled.schema().clone()),
+            name,
+            content_type,
+            size: content.len() as u64,
+            created_at: now,
+            modified_at: now,
+            access_control,
+            tags,
+        };
+
+        let (content, checksum) = checksummed(self.compute_pool.as_ref(), content).await?;
+        let resource = StoredResource {
+            metadata,
+            content,
+            checksum: Some(checksum),
+        };
+        resource.check_schema(self.schemas.get(&resource.metadata.content_type))?;
+
+        self.write_resource(&resource).await?;
+        self.resources.insert(resource_id.clone(), resource.metadata);
+        Ok(resource_id)
+    }
+
+    async fn get_resource(&self, resource_id: &str) -> Result<StoredResource> {
+        let resource = self.read_resource(resource_id).await?;
+        let (resource, intact) = resource.verify_checksum_on(self.compute_pool.as_ref()).await?;
+        if !intact {
+            self.corrupt_resources.lock().unwrap().insert(resource_id.to_string());
+            return Err(WeaveMeshError::Generic("checksum mismatch".to_string()).into());
+        }
+        Ok(resource)
+    }
+
+    async fn get_resource_content(&self, resource_id: &str) -> Result<Vec<u8>> {
+        let resource = self.get_resource(resource_id).await?;
+        Ok(resource.content)
+    }
+
+    fn list_resources(&self, filter: Option<ResourceFilter>) -> Vec<ResourceMetadata> {
+        let mut resources: Vec<ResourceMetadata> = self.resources
+            .values()
+            .filter(|metadata| match &filter {
+                Some(filter) => filter.matches(metadata) && self.matches_content(filter, metadata),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        // Sort by modification time (newest first)
+        resources.sort_by_key(|r| std::cmp::Reverse(r.modified_at));
+
+        resources
+    }
+
+    async fn delete_resource(&mut self, resource_id: &str) -> Result<()> {
+        if !self.resources.contains_key(resource_id) {
+            return Err(anyhow::anyhow!("Resource not found: {}", resource_id));
+        }
+        match tokio::fs::remove_file(self.resource_path(resource_id)?).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        self.resources.remove(resource_id);
+        self.corrupt_resources.lock().unwrap().remove(resource_id);
+        Ok(())
+    }
+
+    async fn restore_resource(&mut self, resource: StoredResource) -> Result<()> {
+        if !resource.verify_checksum() {
+            return Err(WeaveMeshError::Generic("checksum mismatch".to_string()).into());
+        }
+        resource.check_schema(self.schemas.get(&resource.metadata.content_type))?;
+        self.write_resource(&resource).await?;
+        self.corrupt_resources.lock().unwrap().remove(&resource.metadata.resource_id);
+        self.resources.insert(resource.metadata.resource_id.clone(), resource.metadata);
+        Ok(())
+    }
+
+    fn get_stats(&self) -> StorageStats {
+        StorageStats {
+            total_resources: self.resources.len(),
+            total_size: self.resources.values().map(|metadata| metadata.size).sum(),
+            index_hit_rate: 0.0,
+            integrity_failures: self.corrupt_resources.lock().unwrap().len() as u64,
+            content_searches_performed: self.content_searches.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Copy every resource of `source` into `destination`, keeping IDs and checksums
+///
+/// Use it to move a [`MemoryStorage`](super::MemoryStorage) onto disk.
+/// Returns the number of resources copied.
+pub async fn migrate_resources<S: Storage, D: Storage>(source: &S, destination: &mut D) -> Result<usize> {
+    let mut copied = 0;
+    for metadata in source.list_resources(None) {
+        let resource = source.get_resource(&metadata.resource_id).await?;
+        destination.restore_resource(resource).await?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_file_storage_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = FileStorage::open(dir.path()).unwrap();
+        let kept = storage.store_resource(
+            "notes.txt".to_string(),
+            b"Persistent weave".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            vec!["notes".to_string()],
+        ).await.unwrap();
+        let deleted = storage.store_resource(
+            "scratch.txt".to_string(),
+            b"temporary".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        storage.delete_resource(&deleted).await.unwrap();
+        drop(storage);
+
+        // Leftovers of an interrupted process
+        std::fs::write(dir.path().join("half.0.partial"), b"{\"metad").unwrap();
+        std::fs::write(dir.path().join("broken.json"), b"not json").unwrap();
+
+        let storage = FileStorage::open(dir.path()).unwrap();
+        assert_eq!(storage.quarantined(), &[dir.path().join("broken.corrupt")]);
+        assert!(!dir.path().join("half.0.partial").exists());
+        assert_eq!(storage.get_resource_content(&kept).await.unwrap(), b"Persistent weave");
+        assert!(storage.get_resource(&deleted).await.is_err());
+
+        let filter = ResourceFilter::default().with_content_query("WEAVE");
+        assert_eq!(storage.list_resources(Some(filter)).len(), 1);
+        let stats = storage.get_stats();
+        assert_eq!(stats.total_resources, 1);
+        assert_eq!(stats.content_searches_performed, 1);
+        assert!(storage.verify_all().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_from_memory_storage() {
+        let mut memory = MemoryStorage::new();
+        let resource_id = memory.store_resource(
+            "config.json".to_string(),
+            b"{}".to_vec(),
+            "application/json".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = FileStorage::open(dir.path()).unwrap();
+        assert_eq!(migrate_resources(&memory, &mut storage).await.unwrap(), 1);
+
+        let reopened = FileStorage::open(dir.path()).unwrap();
+        let migrated = reopened.get_resource(&resource_id).await.unwrap();
+        assert_eq!(migrated.checksum, memory.get_resource(&resource_id).await.unwrap().checksum);
+        assert!(reopened.resource_path("../escape").is_err());
+    }
+}
@@ -0,0 +1,1176 @@
+//! Storage interface for WeaveMesh Core
+//! 
+//! This module provides a basic storage interface that can be implemented
+//! by different storage backends (encrypted, cloud, distributed, etc.)
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
+use anyhow::Result;
+use ring::digest;
+use tokio_util::sync::CancellationToken;
+
+use crate::compute::{ComputeCategory, ComputeError, ComputePool};
+use crate::financial::{UsageKind, UsageMeter, UsageSample};
+use crate::networking::query_cache::{CachedQuery, QueryCache, QueryCacheConfig, QueryCacheStats};
+use crate::WeaveMeshError;
+
+mod file;
+
+pub use file::{migrate_resources, FileStorage};
+
+/// Universal storage interface for WeaveMesh resources
+pub trait Storage: Send + Sync {
+    /// Store a resource and return its unique identifier
+    async fn store_resource(
+        &mut self,
+        name: String,
+        content: Vec<u8>,
+        content_type: String,
+        access_control: AccessControl,
+        tags: Vec<String>,
+    ) -> Result<String>;
+    
+    /// Retrieve a resource by its identifier
+    async fn get_resource(&self, resource_id: &str) -> Result<StoredResource>;
+    
+    /// Get the content of a resource
+    async fn get_resource_content(&self, resource_id: &str) -> Result<Vec<u8>>;
+    
+    /// List resources with optional filtering
+    fn list_resources(&self, filter: Option<ResourceFilter>) -> Vec<ResourceMetadata>;
+    
+    /// Delete a resource
+    async fn delete_resource(&mut self, resource_id: &str) -> Result<()>;
+    
+    /// Put back a resource under its original ID, as when restoring a backup
+    async fn restore_resource(&mut self, resource: StoredResource) -> Result<()>;
+    
+    /// Get storage statistics
+    fn get_stats(&self) -> StorageStats;
+    
+    /// Verify every stored checksum and return the IDs of resources that fail
+    async fn verify_all(&self) -> std::result::Result<Vec<String>, StorageError> {
+        let mut failed = Vec::new();
+        for metadata in self.list_resources(None) {
+            if self.get_resource(&metadata.resource_id).await.is_err() {
+                failed.push(metadata.resource_id);
+            }
+        }
+        failed.sort();
+        Ok(failed)
+    }
+}
+
+/// Metadata about a stored resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceMetadata {
+    /// Unique identifier for this resource
+    pub resource_id: String,
+    
+    /// Human-readable name for the resource
+    pub name: String,
+    
+    /// MIME type of the resource content
+    pub content_type: String,
+    
+    /// Size of the data in bytes
+    pub size: u64,
+    
+    /// When this resource was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    
+    /// When this resource was last modified
+    pub modified_at: chrono::DateTime<chrono::Utc>,
+    
+    /// Access control settings for this resource
+    pub access_control: AccessControl,
+    
+    /// Tags for organizing resources
+    pub tags: Vec<String>,
+    
+    /// JSON Schema the content must satisfy
+    #[serde(default)]
+    pub json_schema: Option<serde_json::Value>,
+}
+
+impl ResourceMetadata {
+    /// Attach the JSON Schema read from `path`
+    ///
+    /// Fails if the file is not JSON or not a valid schema.
+    pub fn with_schema_from_file(mut self, path: &Path) -> Result<Self> {
+        let schema: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?)?;
+        jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON Schema in {}: {}", path.display(), e))?;
+        self.json_schema = Some(schema);
+        Ok(self)
+    }
+}
+
+/// Where a resource's content fails its JSON Schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// JSON Pointer to the offending value; empty for the whole document
+    pub path: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+/// A JSON Schema compiled once for validating every resource stored under it
+#[derive(Clone)]
+pub(crate) struct CompiledSchema {
+    schema: serde_json::Value,
+    validator: Arc<jsonschema::JSONSchema>,
+}
+
+impl CompiledSchema {
+    /// Compile `schema`, failing if it is not a valid JSON Schema
+    pub(crate) fn compile(schema: serde_json::Value) -> Result<Self> {
+        let validator = jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON Schema: {}", e))?;
+        Ok(Self { schema, validator: Arc::new(validator) })
+    }
+    
+    /// The schema as recorded in resource metadata
+    pub(crate) fn schema(&self) -> &serde_json::Value {
+        &self.schema
+    }
+}
+
+impl std::fmt::Debug for CompiledSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompiledSchema").field("schema", &self.schema).finish()
+    }
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Access control settings for a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessControl {
+    /// Whether this resource is private to this node
+    pub is_private: bool,
+    
+    /// Node IDs that can access this resource
+    pub allowed_nodes: Vec<String>,
+    
+    /// Family/group IDs that can access this resource
+    pub allowed_groups: Vec<String>,
+    
+    /// Whether this resource can be shared publicly
+    pub is_public: bool,
+}
+
+impl Default for AccessControl {
+    fn default() -> Self {
+        Self {
+            is_private: true,
+            allowed_nodes: Vec::new(),
+            allowed_groups: Vec::new(),
+            is_public: false,
+        }
+    }
+}
+
+/// Stored resource with content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredResource {
+    /// Metadata about this resource
+    pub metadata: ResourceMetadata,
+    
+    /// Content of the resource
+    pub content: Vec<u8>,
+    
+    /// Hex-encoded SHA-256 of the content, computed at storage time
+    pub checksum: Option<String>,
+}
+
+impl StoredResource {
+    /// Whether the content still matches the stored checksum
+    ///
+    /// Resources stored without a checksum are assumed intact.
+    pub fn verify_checksum(&self) -> bool {
+        match &self.checksum {
+            Some(expected) => *expected == content_checksum(&self.content),
+            None => true,
+        }
+    }
+    
+    /// [`verify_checksum`](Self::verify_checksum) run on `pool`, if there is one
+    pub(crate) async fn verify_checksum_on(self, pool: Option<&ComputePool>) -> Result<(Self, bool)> {
+        match pool {
+            Some(pool) => Ok(pool
+                .run(ComputeCategory::Hashing, &CancellationToken::new(), move || {
+                    let intact = self.verify_checksum();
+                    (self, intact)
+                })
+                .await?),
+            None => {
+                let intact = self.verify_checksum();
+                Ok((self, intact))
+            }
+        }
+    }
+    
+    /// Check the content against the metadata's JSON Schema, if there is one
+    ///
+    /// Content that is not JSON violates any schema.
+    pub fn validate_payload(&self) -> std::result::Result<(), Vec<SchemaViolation>> {
+        let Some(schema) = &self.metadata.json_schema else {
+            return Ok(());
+        };
+        let validator = jsonschema::JSONSchema::compile(schema)
+            .map_err(|e| whole_document(format!("invalid schema: {}", e)))?;
+        self.validate_with(&validator)
+    }
+    
+    /// Check the content against an already compiled schema
+    fn validate_with(&self, validator: &jsonschema::JSONSchema) -> std::result::Result<(), Vec<SchemaViolation>> {
+        let payload: serde_json::Value = serde_json::from_slice(&self.content)
+            .map_err(|e| whole_document(format!("payload is not JSON: {}", e)))?;
+        validator.validate(&payload).map_err(|errors| {
+            errors
+                .map(|error| SchemaViolation {
+                    path: error.instance_path.to_string(),
+                    message: error.to_string(),
+                })
+                .collect()
+        })
+    }
+    
+    /// Reject the resource with a configuration error if its content violates its schema
+    ///
+    /// `cached` is used instead of compiling the resource's schema when it
+    /// is the same schema.
+    pub(crate) fn check_schema(&self, cached: Option<&CompiledSchema>) -> Result<()> {
+        let result = match cached {
+            Some(cached) if self.metadata.json_schema.as_ref() == Some(&cached.schema) => {
+                self.validate_with(&cached.validator)
+            }
+            _ => self.validate_payload(),
+        };
+        result.map_err(|violations| {
+            let details: Vec<String> = violations.iter().map(ToString::to_string).collect();
+            WeaveMeshError::Configuration(format!("schema violation: {}", details.join("; "))).into()
+        })
+    }
+}
+
+/// A violation of the schema by the document as a whole
+fn whole_document(message: String) -> Vec<SchemaViolation> {
+    vec![SchemaViolation { path: String::new(), message }]
+}
+
+/// Hex-encoded SHA-256 of resource content
+pub fn content_checksum(content: &[u8]) -> String {
+    digest::digest(&digest::SHA256, content)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// [`content_checksum`] computed on a compute pool
+pub async fn content_checksum_on(
+    pool: &ComputePool,
+    cancel: &CancellationToken,
+    content: Vec<u8>,
+) -> std::result::Result<String, ComputeError> {
+    pool.run(ComputeCategory::Hashing, cancel, move || content_checksum(&content)).await
+}
+
+/// Content with its checksum, computed on `pool` if there is one
+///
+/// The content is handed back rather than cloned for the pool.
+pub(crate) async fn checksummed(pool: Option<&ComputePool>, content: Vec<u8>) -> Result<(Vec<u8>, String)> {
+    match pool {
+        Some(pool) => Ok(pool
+            .run(ComputeCategory::Hashing, &CancellationToken::new(), move || {
+                let checksum = content_checksum(&content);
+                (content, checksum)
+            })
+            .await?),
+        None => {
+            let checksum = content_checksum(&content);
+            Ok((content, checksum))
+        }
+    }
+}
+
+/// Errors from storage operations
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Invalid content query: {0}")]
+    InvalidQuery(String),
+}
+
+/// Search over the UTF-8 content of a resource
+#[derive(Debug, Clone)]
+pub enum ContentQuery {
+    /// Case-insensitive substring, stored lowercased
+    Substring(String),
+    /// Regular expression
+    Regex(regex::Regex),
+}
+
+impl ContentQuery {
+    /// Whether `content` is UTF-8 text matching the query
+    pub fn matches(&self, content: &[u8]) -> bool {
+        let Ok(text) = std::str::from_utf8(content) else {
+            return false;
+        };
+        match self {
+            ContentQuery::Substring(query) => text.to_lowercase().contains(query.as_str()),
+            ContentQuery::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Filter for listing resources
+#[derive(Debug, Clone, Default)]
+pub struct ResourceFilter {
+    pub content_type: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub is_private: Option<bool>,
+    pub name_contains: Option<String>,
+    /// Search over resource content; only UTF-8 resources can match
+    pub content_query: Option<ContentQuery>,
+}
+
+impl ResourceFilter {
+    /// Also require the content to contain `query`, ignoring case
+    pub fn with_content_query(mut self, query: &str) -> Self {
+        self.content_query = Some(ContentQuery::Substring(query.to_lowercase()));
+        self
+    }
+    
+    /// Also require the content to match the regular expression `pattern`
+    pub fn with_regex_query(mut self, pattern: &str) -> std::result::Result<Self, StorageError> {
+        let regex = regex::Regex::new(pattern)
+            .map_err(|e| StorageError::InvalidQuery(e.to_string()))?;
+        self.content_query = Some(ContentQuery::Regex(regex));
+        Ok(self)
+    }
+    
+    /// Whether `metadata` passes every filter except the content query
+    pub fn matches(&self, metadata: &ResourceMetadata) -> bool {
+        if let Some(ref content_type) = self.content_type {
+            if &metadata.content_type != content_type {
+                return false;
+            }
+        }
+        
+        if let Some(ref tags) = self.tags {
+            if !tags.iter().any(|tag| metadata.tags.contains(tag)) {
+                return false;
+            }
+        }
+        
+        if let Some(is_private) = self.is_private {
+            if metadata.access_control.is_private != is_private {
+                return false;
+            }
+        }
+        
+        if let Some(ref name_contains) = self.name_contains {
+            if !metadata.name.to_lowercase().contains(&name_contains.to_lowercase()) {
+                return false;
+            }
+        }
+        
+        true
+    }
+}
+
+/// Resource queries can be cached; an announced resource invalidates the
+/// queries whose filters it passes, whatever their content query
+impl CachedQuery for ResourceFilter {
+    type Item = ResourceMetadata;
+    
+    fn cache_key(&self) -> String {
+        let mut tags = self.tags.clone();
+        if let Some(tags) = tags.as_mut() {
+            tags.sort();
+            tags.dedup();
+        }
+        let content_query = self.content_query.as_ref().map(|query| match query {
+            ContentQuery::Substring(text) => format!("substring:{}", text),
+            ContentQuery::Regex(regex) => format!("regex:{}", regex.as_str()),
+        });
+        format!(
+            "content_type={:?};tags={:?};private={:?};name={:?};content={:?}",
+            self.content_type,
+            tags,
+            self.is_private,
+            self.name_contains.as_ref().map(|name| name.to_lowercase()),
+            content_query,
+        )
+    }
+    
+    fn matches(&self, metadata: &ResourceMetadata) -> bool {
+        ResourceFilter::matches(self, metadata)
+    }
+}
+
+/// Recent results of resource queries against a shared storage backend
+///
+/// Announcing a stored, changed or removed resource drops the cached
+/// queries it affects, so listings never miss an announced resource.
+pub struct ResourceQueryCache {
+    cache: QueryCache<ResourceFilter, Vec<ResourceMetadata>>,
+}
+
+impl ResourceQueryCache {
+    pub fn new(config: QueryCacheConfig) -> Self {
+        Self { cache: QueryCache::new(config) }
+    }
+    
+    /// Resources of `storage` passing `filter`, from the cache when possible
+    ///
+    /// With `fresh` the storage is always listed.
+    pub async fn list_resources<S: Storage + 'static>(
+        &self,
+        storage: &Arc<tokio::sync::RwLock<S>>,
+        filter: ResourceFilter,
+        fresh: bool,
+    ) -> Vec<ResourceMetadata> {
+        let storage = Arc::clone(storage);
+        let listed = self.cache.get_or_fetch(&filter, fresh, move |filter| {
+            let storage = Arc::clone(&storage);
+            async move { Ok::<_, std::convert::Infallible>(storage.read().await.list_resources(Some(filter))) }
+        }).await;
+        match listed {
+            Ok(resources) => resources,
+            Err(never) => match never {},
+        }
+    }
+    
+    /// Take in the announcement of a stored or changed resource
+    ///
+    /// Returns the number of cached queries dropped: those the resource
+    /// passes and those that already listed it.
+    pub fn resource_announced(&self, metadata: &ResourceMetadata) -> usize {
+        self.cache.invalidate_where(|filter, resources| {
+            filter.matches(metadata) || resources.iter().any(|r| r.resource_id == metadata.resource_id)
+        })
+    }
+    
+    /// Take in the removal of a resource, dropping the queries that listed it
+    pub fn resource_removed(&self, resource_id: &str) -> usize {
+        self.cache.invalidate_where(|_, resources| resources.iter().any(|r| r.resource_id == resource_id))
+    }
+    
+    /// Cache behaviour so far
+    pub fn stats(&self) -> QueryCacheStats {
+        self.cache.stats()
+    }
+}
+
+/// Storage statistics
+#[derive(Debug, Clone)]
+pub struct StorageStats {
+    pub total_resources: usize,
+    pub total_size: u64,
+    /// Fraction of metadata queries answered from the secondary index (0.0 - 1.0)
+    pub index_hit_rate: f64,
+    /// Number of resources found to fail checksum verification
+    pub integrity_failures: u64,
+    /// Number of resource payloads scanned by content queries
+    pub content_searches_performed: u64,
+}
+
+/// Default [`MemoryStorage::with_max_content_search_bytes`] limit
+pub const DEFAULT_MAX_CONTENT_SEARCH_BYTES: usize = 1024 * 1024;
+
+/// Key under which a resource is stored
+pub type StorageKey = String;
+
+/// Secondary index: field name -> field value -> resource keys
+type MetadataIndex = HashMap<String, HashMap<String, Vec<StorageKey>>>;
+
+/// Simple in-memory storage implementation for testing and basic use
+#[derive(Debug)]
+pub struct MemoryStorage {
+    resources: HashMap<String, StoredResource>,
+    metadata_index: MetadataIndex,
+    index_hits: AtomicU64,
+    index_misses: AtomicU64,
+    /// Resources found corrupt, each counted once however often it is read
+    corrupt_resources: std::sync::Mutex<HashSet<String>>,
+    content_searches: AtomicU64,
+    max_content_search_bytes: usize,
+    meter: Option<Arc<dyn UsageMeter>>,
+    meter_context: Option<String>,
+    /// JSON Schemas applied to new resources, by content type
+    schemas: HashMap<String, CompiledSchema>,
+    /// Pool checksums are computed on, if any
+    compute_pool: Option<ComputePool>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self {
+            resources: HashMap::new(),
+            metadata_index: HashMap::new(),
+            index_hits: AtomicU64::new(0),
+            index_misses: AtomicU64::new(0),
+            corrupt_resources: std::sync::Mutex::new(HashSet::new()),
+            content_searches: AtomicU64::new(0),
+            max_content_search_bytes: DEFAULT_MAX_CONTENT_SEARCH_BYTES,
+            meter: None,
+            meter_context: None,
+            schemas: HashMap::new(),
+            compute_pool: None,
+        }
+    }
+    
+    /// Validate resources stored with `content_type` against `schema`
+    ///
+    /// The schema is recorded in each such resource's metadata, so it
+    /// travels with the resource through backups and restores. Fails if
+    /// `schema` is not a valid JSON Schema.
+    pub fn with_schema(mut self, content_type: &str, schema: serde_json::Value) -> Result<Self> {
+        self.schemas.insert(content_type.to_string(), CompiledSchema::compile(schema)?);
+        Ok(self)
+    }
+    
+    /// Report bytes written, read and deleted to a usage meter under `context`
+    pub fn with_meter(mut self, meter: Arc<dyn UsageMeter>, context: Option<String>) -> Self {
+        self.meter = Some(meter);
+        self.meter_context = context;
+        self
+    }
+    
+    /// Skip content queries over resources larger than `bytes`
+    ///
+    /// Oversized resources never match a content query, so a huge payload
+    /// cannot make listing expensive.
+    pub fn with_max_content_search_bytes(mut self, bytes: usize) -> Self {
+        self.max_content_search_bytes = bytes;
+        self
+    }
+    
+    /// Compute and verify checksums on `pool` instead of the calling task
+    pub fn with_compute_pool(mut self, pool: ComputePool) -> Self {
+        self.compute_pool = Some(pool);
+        self
+    }
+    
+    /// Report a storage operation to the usage meter, if any
+    fn meter_usage(&self, kind: UsageKind, bytes: u64) {
+        if let Some(meter) = &self.meter {
+            meter.record_usage(UsageSample {
+                kind,
+                bytes,
+                context: self.meter_context.clone(),
+                attribution: None,
+            });
+        }
+    }
+    
+    /// Build a secondary index over the given metadata fields.
+    ///
+    /// Supported fields are `resource_id`, `name`, `content_type`, `tags`,
+    /// `is_private` and `is_public`. The index is kept up to date on every
+    /// store and delete. Calling this again adds any new fields to the index.
+    pub fn build_metadata_index(&mut self, fields: &[&str]) {
+        for field in fields {
+            let mut values: HashMap<String, Vec<StorageKey>> = HashMap::new();
+            for (key, resource) in &self.resources {
+                for value in metadata_field_values(&resource.metadata, field) {
+                    values.entry(value).or_default().push(key.clone());
+                }
+            }
+            self.metadata_index.insert(field.to_string(), values);
+        }
+    }
+    
+    /// Get all resources whose metadata `field` equals `value`.
+    ///
+    /// Uses the secondary index when `field` was indexed, otherwise falls
+    /// back to a linear scan over all resources.
+    pub fn query_by_metadata_field(&self, field: &str, value: &str) -> Vec<StoredResource> {
+        let mut resources: Vec<StoredResource> = match self.metadata_index.get(field) {
+            Some(values) => {
+                self.index_hits.fetch_add(1, Ordering::Relaxed);
+                values
+                    .get(value)
+                    .map(|keys| {
+                        keys.iter()
+                            .filter_map(|key| self.resources.get(key).cloned())
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            None => {
+                self.index_misses.fetch_add(1, Ordering::Relaxed);
+                self.resources
+                    .values()
+                    .filter(|r| {
+                        metadata_field_values(&r.metadata, field)
+                            .iter()
+                            .any(|v| v == value)
+                    })
+                    .cloned()
+                    .collect()
+            }
+        };
+        
+        // Sort by modification time (newest first)
+        resources.sort_by_key(|r| std::cmp::Reverse(r.metadata.modified_at));
+        
+        resources
+    }
+    
+    /// Add a resource to every indexed field
+    fn index_resource(&mut self, metadata: &ResourceMetadata) {
+        for (field, values) in self.metadata_index.iter_mut() {
+            for value in metadata_field_values(metadata, field) {
+                values.entry(value).or_default().push(metadata.resource_id.clone());
+            }
+        }
+    }
+    
+    /// Remove a resource from every indexed field
+    fn unindex_resource(&mut self, metadata: &ResourceMetadata) {
+        for (field, values) in self.metadata_index.iter_mut() {
+            for value in metadata_field_values(metadata, field) {
+                if let Some(keys) = values.get_mut(&value) {
+                    keys.retain(|key| key != &metadata.resource_id);
+                    if keys.is_empty() {
+                        values.remove(&value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl MemoryStorage {
+    /// Whether a resource passes the filter's content query, if it has one
+    fn matches_content(&self, filter: &ResourceFilter, resource: &StoredResource) -> bool {
+        let Some(query) = &filter.content_query else {
+            return true;
+        };
+        if resource.content.len() > self.max_content_search_bytes {
+            return false;
+        }
+        self.content_searches.fetch_add(1, Ordering::Relaxed);
+        query.matches(&resource.content)
+    }
+}
+
+/// Extract the indexable values of a metadata field
+fn metadata_field_values(metadata: &ResourceMetadata, field: &str) -> Vec<String> {
+    match field {
+        "resource_id" => vec![metadata.resource_id.clone()],
+        "name" => vec![metadata.name.clone()],
+        "content_type" => vec![metadata.content_type.clone()],
+        "tags" => metadata.tags.clone(),
+        "is_private" => vec![metadata.access_control.is_private.to_string()],
+        "is_public" => vec![metadata.access_control.is_public.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Storage for MemoryStorage {
+    async fn store_resource(
+        &mut self,
+        name: String,
+        content: Vec<u8>,
+        content_type: String,
+        access_control: AccessControl,
+        tags: Vec<String>,
+    ) -> Result<String> {
+        let resource_id = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now();
+        
+        let metadata = ResourceMetadata {
+            resource_id: resource_id.clone(),
+            json_schema: self.schemas.get(&content_type).map(|compiled| compiled.schema().clone()),
+            name,
+            content_type,
+            size: content.len() as u64,
+            created_at: now,
+            modified_at: now,
+            access_control,
+            tags,
+        };
+        
+        let (content, checksum) = checksummed(self.compute_pool.as_ref(), content).await?;
+        let resource = StoredResource {
+            metadata,
+            content,
+            checksum: Some(checksum),
+        };
+        resource.check_schema(self.schemas.get(&resource.metadata.content_type))?;
+        
+        self.index_resource(&resource.metadata);
+        self.meter_usage(UsageKind::StorageWrite, resource.metadata.size);
+        self.resources.insert(resource_id.clone(), resource);
+        Ok(resource_id)
+    }
+    
+    async fn get_resource(&self, resource_id: &str) -> Result<StoredResource> {
+        let resource = self.resources
+            .get(resource_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Resource not found: {}", resource_id))?;
+        let (resource, intact) = resource.verify_checksum_on(self.compute_pool.as_ref()).await?;
+        if !intact {
+            self.corrupt_resources.lock().unwrap().insert(resource_id.to_string());
+            return Err(WeaveMeshError::Generic("checksum mismatch".to_string()).into());
+        }
+        self.meter_usage(UsageKind::StorageRead, resource.metadata.size);
+        Ok(resource)
+    }
+    
+    async fn get_resource_content(&self, resource_id: &str) -> Result<Vec<u8>> {
+        let resource = self.get_resource(resource_id).await?;
+        Ok(resource.content)
+    }
+    
+    fn list_resources(&self, filter: Option<ResourceFilter>) -> Vec<ResourceMetadata> {
+        let mut resources: Vec<ResourceMetadata> = self.resources
+            .values()
+            .filter(|r| match &filter {
+                Some(filter) => filter.matches(&r.metadata) && self.matches_content(filter, r),
+                None => true,
+            })
+            .map(|r| r.metadata.clone())
+            .collect();
+        
+        // Sort by modification time (newest first)
+        resources.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+        
+        resources
+    }
+    
+    async fn delete_resource(&mut self, resource_id: &str) -> Result<()> {
+        let resource = self.resources
+            .remove(resource_id)
+            .ok_or_else(|| anyhow::anyhow!("Resource not found: {}", resource_id))?;
+        self.unindex_resource(&resource.metadata);
+        self.corrupt_resources.lock().unwrap().remove(resource_id);
+        self.meter_usage(UsageKind::StorageDelete, resource.metadata.size);
+        Ok(())
+    }
+    
+    async fn restore_resource(&mut self, resource: StoredResource) -> Result<()> {
+        if !resource.verify_checksum() {
+            return Err(WeaveMeshError::Generic("checksum mismatch".to_string()).into());
+        }
+        resource.check_schema(self.schemas.get(&resource.metadata.content_type))?;
+        if let Some(previous) = self.resources.remove(&resource.metadata.resource_id) {
+            self.unindex_resource(&previous.metadata);
+        }
+        self.corrupt_resources.lock().unwrap().remove(&resource.metadata.resource_id);
+        self.index_resource(&resource.metadata);
+        self.meter_usage(UsageKind::StorageWrite, resource.metadata.size);
+        self.resources.insert(resource.metadata.resource_id.clone(), resource);
+        Ok(())
+    }
+    
+    fn get_stats(&self) -> StorageStats {
+        let total_resources = self.resources.len();
+        let total_size: u64 = self.resources
+            .values()
+            .map(|r| r.metadata.size)
+            .sum();
+        
+        let hits = self.index_hits.load(Ordering::Relaxed);
+        let misses = self.index_misses.load(Ordering::Relaxed);
+        let index_hit_rate = if hits + misses > 0 {
+            hits as f64 / (hits + misses) as f64
+        } else {
+            0.0
+        };
+        
+        StorageStats {
+            total_resources,
+            total_size,
+            index_hit_rate,
+            integrity_failures: self.corrupt_resources.lock().unwrap().len() as u64,
+            content_searches_performed: self.content_searches.load(Ordering::Relaxed),
+        }
+    }
+    
+    async fn verify_all(&self) -> std::result::Result<Vec<String>, StorageError> {
+        // Checked directly rather than through `get_resource`, so verification is not metered as reads
+        let mut failed: Vec<String> = self.resources
+            .values()
+            .filter(|r| !r.verify_checksum())
+            .map(|r| r.metadata.resource_id.clone())
+            .collect();
+        failed.sort();
+        self.corrupt_resources.lock().unwrap().extend(failed.iter().cloned());
+        Ok(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_memory_storage() {
+        let mut storage = MemoryStorage::new();
+        
+        // Store a resource
+        let content = b"Hello, WeaveMesh!".to_vec();
+        let resource_id = storage.store_resource(
+            "test.txt".to_string(),
+            content.clone(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            vec!["test".to_string()],
+        ).await.unwrap();
+        
+        // Retrieve the resource
+        let retrieved_content = storage.get_resource_content(&resource_id).await.unwrap();
+        assert_eq!(content, retrieved_content);
+        
+        // Check metadata
+        let resources = storage.list_resources(None);
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].name, "test.txt");
+        
+        // Check stats
+        let stats = storage.get_stats();
+        assert_eq!(stats.total_resources, 1);
+        assert_eq!(stats.total_size, content.len() as u64);
+        
+        // Delete resource
+        storage.delete_resource(&resource_id).await.unwrap();
+        let resources = storage.list_resources(None);
+        assert_eq!(resources.len(), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_resource_filtering() {
+        let mut storage = MemoryStorage::new();
+        
+        // Store multiple resources
+        storage.store_resource(
+            "doc1.txt".to_string(),
+            b"Document 1".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            vec!["document".to_string()],
+        ).await.unwrap();
+        
+        storage.store_resource(
+            "image1.png".to_string(),
+            b"Image data".to_vec(),
+            "image/png".to_string(),
+            AccessControl::default(),
+            vec!["image".to_string()],
+        ).await.unwrap();
+        
+        // Filter by content type
+        let filter = ResourceFilter {
+            content_type: Some("text/plain".to_string()),
+            tags: None,
+            is_private: None,
+            name_contains: None,
+            content_query: None,
+        };
+        
+        let filtered = storage.list_resources(Some(filter));
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].content_type, "text/plain");
+    }
+    
+    #[tokio::test]
+    async fn test_resource_queries_invalidated_by_announcements() {
+        let storage = Arc::new(tokio::sync::RwLock::new(MemoryStorage::new()));
+        let cache = ResourceQueryCache::new(QueryCacheConfig::default());
+        let store = |name: &str, content_type: &str| {
+            let storage = Arc::clone(&storage);
+            let (name, content_type) = (name.to_string(), content_type.to_string());
+            async move {
+                storage.write().await
+                    .store_resource(name, b"data".to_vec(), content_type, AccessControl::default(), Vec::new())
+                    .await
+                    .unwrap()
+            }
+        };
+        let of_type = |content_type: &str| ResourceFilter { content_type: Some(content_type.to_string()), ..Default::default() };
+        
+        store("notes.txt", "text/plain").await;
+        assert_eq!(cache.list_resources(&storage, of_type("text/plain"), false).await.len(), 1);
+        assert_eq!(cache.list_resources(&storage, of_type("image/png"), false).await.len(), 0);
+        
+        // Unannounced changes are not seen within the TTL
+        let more_id = store("more.txt", "text/plain").await;
+        assert_eq!(cache.list_resources(&storage, of_type("text/plain"), false).await.len(), 1);
+        assert_eq!(cache.stats().hits, 1);
+        
+        // Announcing the resource drops only the queries it passes
+        let more = storage.read().await.get_resource(&more_id).await.unwrap().metadata;
+        assert_eq!(cache.resource_announced(&more), 1);
+        assert_eq!(cache.list_resources(&storage, of_type("text/plain"), false).await.len(), 2);
+        assert_eq!(cache.list_resources(&storage, of_type("image/png"), false).await.len(), 0);
+        assert_eq!(cache.stats().hits, 2);
+        
+        // A removal drops the queries that listed the resource
+        storage.write().await.delete_resource(&more_id).await.unwrap();
+        assert_eq!(cache.resource_removed(&more_id), 1);
+        assert_eq!(cache.list_resources(&storage, of_type("text/plain"), false).await.len(), 1);
+        assert_eq!(cache.stats().invalidations, 2);
+        
+        // Equivalent filters share an entry
+        let tagged = |tags: &[&str]| ResourceFilter { tags: Some(tags.iter().map(|t| t.to_string()).collect()), ..Default::default() };
+        assert_eq!(tagged(&["b", "a", "a"]).cache_key(), tagged(&["a", "b"]).cache_key());
+    }
+    
+    #[tokio::test]
+    async fn test_metadata_index() {
+        let mut storage = MemoryStorage::new();
+        
+        storage.store_resource(
+            "doc1.txt".to_string(),
+            b"Document 1".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            vec!["document".to_string()],
+        ).await.unwrap();
+        
+        storage.build_metadata_index(&["content_type"]);
+        
+        let image_id = storage.store_resource(
+            "image1.png".to_string(),
+            b"Image data".to_vec(),
+            "image/png".to_string(),
+            AccessControl::default(),
+            vec!["image".to_string()],
+        ).await.unwrap();
+        
+        // Indexed lookup sees resources stored before and after the index was built
+        assert_eq!(storage.query_by_metadata_field("content_type", "text/plain").len(), 1);
+        assert_eq!(storage.query_by_metadata_field("content_type", "image/png").len(), 1);
+        
+        // Unindexed field falls back to a linear scan
+        let tagged = storage.query_by_metadata_field("tags", "image");
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].metadata.resource_id, image_id);
+        
+        // Deletes are reflected in the index
+        storage.delete_resource(&image_id).await.unwrap();
+        assert!(storage.query_by_metadata_field("content_type", "image/png").is_empty());
+        
+        let stats = storage.get_stats();
+        assert!((stats.index_hit_rate - 0.75).abs() < f64::EPSILON);
+    }
+    
+    #[tokio::test]
+    async fn test_storage_metering() {
+        use crate::financial::{ManualClock, MeteringAggregator, MeteringConfig, OperationType};
+        
+        let clock = ManualClock::new(chrono::Utc::now());
+        let aggregator = Arc::new(MeteringAggregator::with_clock(
+            MeteringConfig { storage_per_gb_day: 24, ..MeteringConfig::default() },
+            Arc::new(clock.clone()),
+        ));
+        let mut storage = MemoryStorage::new()
+            .with_meter(aggregator.clone(), Some("archive".to_string()));
+        
+        let resource_id = storage.store_resource(
+            "blob.bin".to_string(),
+            vec![0u8; 1000],
+            "application/octet-stream".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        storage.get_resource_content(&resource_id).await.unwrap();
+        
+        clock.advance(chrono::Duration::hours(1));
+        let records = aggregator.take_rollups();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].operation_type, OperationType::Storage);
+        assert_eq!(records[0].context.as_deref(), Some("archive"));
+        assert_eq!(records[0].metadata["bytes_written"], "1000");
+        assert_eq!(records[0].metadata["bytes_read"], "1000");
+        // 1000 bytes for an hour is far below one base unit
+        assert_eq!(records[0].cost, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_checksum_verification() {
+        let mut storage = MemoryStorage::new();
+        
+        let intact_id = storage.store_resource(
+            "intact.txt".to_string(),
+            b"Intact".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        let corrupt_id = storage.store_resource(
+            "corrupt.txt".to_string(),
+            b"Corrupt".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        
+        let intact = storage.get_resource(&intact_id).await.unwrap();
+        assert_eq!(intact.checksum.as_deref(), Some(content_checksum(b"Intact").as_str()));
+        
+        // Simulate bit rot in the stored payload
+        storage.resources.get_mut(&corrupt_id).unwrap().content[0] ^= 0x01;
+        
+        let err = storage.get_resource(&corrupt_id).await.unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert_eq!(storage.get_stats().integrity_failures, 1);
+        
+        // Finding the same corruption again does not count it twice
+        assert_eq!(storage.verify_all().await.unwrap(), vec![corrupt_id.clone()]);
+        assert!(storage.get_resource(&corrupt_id).await.is_err());
+        assert_eq!(storage.get_stats().integrity_failures, 1);
+        
+        // Deleting the corrupt resource clears its failure
+        storage.delete_resource(&corrupt_id).await.unwrap();
+        assert_eq!(storage.get_stats().integrity_failures, 0);
+        assert!(storage.verify_all().await.unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_content_search() {
+        let mut storage = MemoryStorage::new().with_max_content_search_bytes(64);
+        
+        let notes_id = storage.store_resource(
+            "notes.md".to_string(),
+            b"Sacred Alliance ceremony notes".to_vec(),
+            "text/markdown".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        let code_id = storage.store_resource(
+            "lib.rs".to_string(),
+            b"fn ceremony_count() -> u32 { 42 }".to_vec(),
+            "text/x-rust".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        storage.store_resource(
+            "blob.bin".to_string(),
+            vec![0xff, 0xfe, b'c', b'e'],
+            "application/octet-stream".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        let mut huge = b"ceremony ".to_vec();
+        huge.resize(1000, b'x');
+        storage.store_resource(
+            "huge.txt".to_string(),
+            huge,
+            "text/plain".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        
+        let ids = |filter: ResourceFilter| {
+            let mut ids: Vec<String> = storage.list_resources(Some(filter))
+                .into_iter()
+                .map(|m| m.resource_id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let mut both = vec![notes_id.clone(), code_id.clone()];
+        both.sort();
+        
+        // Case-insensitive, skipping binary and oversized payloads
+        assert_eq!(ids(ResourceFilter::default().with_content_query("CEREMONY")), both);
+        assert_eq!(
+            ids(ResourceFilter { content_type: Some("text/x-rust".to_string()), ..Default::default() }
+                .with_content_query("ceremony")),
+            vec![code_id.clone()],
+        );
+        assert_eq!(ids(ResourceFilter::default().with_regex_query(r"fn \w+\(").unwrap()), vec![code_id]);
+        assert!(matches!(
+            ResourceFilter::default().with_regex_query("("),
+            Err(StorageError::InvalidQuery(_))
+        ));
+        
+        // Small payloads scanned: three, one after the content type filter, three
+        assert_eq!(storage.get_stats().content_searches_performed, 7);
+    }
+    
+    #[tokio::test]
+    async fn test_schema_validation() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer", "minimum": 0 }
+            }
+        });
+        let mut storage = MemoryStorage::new().with_schema("application/vnd.profile+json", schema.clone()).unwrap();
+        async fn store(storage: &mut MemoryStorage, content: &str) -> Result<String> {
+            storage.store_resource(
+                "profile.json".to_string(),
+                content.as_bytes().to_vec(),
+                "application/vnd.profile+json".to_string(),
+                AccessControl::default(),
+                Vec::new(),
+            ).await
+        }
+        
+        let valid_id = store(&mut storage, r#"{"name": "Ada", "age": 36}"#).await.unwrap();
+        let err = store(&mut storage, r#"{"age": -1}"#).await.unwrap_err();
+        assert!(err.to_string().contains("schema violation"));
+        assert!(store(&mut storage, "not json").await.is_err());
+        assert_eq!(storage.get_stats().total_resources, 1);
+        
+        let mut stored = storage.get_resource(&valid_id).await.unwrap();
+        assert_eq!(stored.metadata.json_schema, Some(schema.clone()));
+        stored.content = br#"{"name": 7, "age": -1}"#.to_vec();
+        let mut violations = stored.validate_payload().unwrap_err();
+        violations.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        assert_eq!(paths, vec!["/age", "/name"]);
+        
+        // Restores are checked against the schema the resource carries
+        stored.checksum = Some(content_checksum(&stored.content));
+        assert!(storage.restore_resource(stored).await.is_err());
+        
+        // Schemas are compiled when configured, so invalid ones fail early
+        assert!(MemoryStorage::new().with_schema("application/json", serde_json::json!({"type": 12})).is_err());
+        
+        // Other content types are not validated
+        storage.store_resource(
+            "notes.txt".to_string(),
+            b"free text".to_vec(),
+            "text/plain".to_string(),
+            AccessControl::default(),
+            Vec::new(),
+        ).await.unwrap();
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profile.schema.json");
+        std::fs::write(&path, schema.to_string()).unwrap();
+        let metadata = storage.list_resources(None).remove(0).with_schema_from_file(&path).unwrap();
+        assert_eq!(metadata.json_schema, Some(schema));
+        std::fs::write(&path, r#"{"type": 12}"#).unwrap();
+        assert!(storage.list_resources(None).remove(0).with_schema_from_file(&path).is_err());
+    }
+}
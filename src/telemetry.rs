@@ -0,0 +1,426 @@
+//! Structured logging and trace export setup
+//!
+//! [`TracingConfig`] installs a `tracing_subscriber` so WeaveMesh modules log
+//! in a known format. With the `otlp` feature, finished spans can also be
+//! sent to an OpenTelemetry collector over OTLP/HTTP with JSON encoding,
+//! through the [`TraceExporter`](crate::networking::TraceExporter) integration point.
+
+use tracing::Level;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Layered, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, Layer, Registry};
+
+/// Registry with the format layer and level filter of a [`TracingConfig`]
+type FormattedRegistry = Layered<LevelFilter, Layered<Box<dyn Layer<Registry> + Send + Sync>, Registry>>;
+
+/// Output format of log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TracingFormat {
+    /// Multi-line, human-oriented output
+    Pretty,
+    /// One JSON object per line
+    Json,
+    /// Single-line, human-oriented output
+    #[default]
+    Compact,
+}
+
+/// How WeaveMesh installs its `tracing` subscriber
+#[derive(Debug, Clone)]
+pub struct TracingConfig {
+    /// Most verbose level logged
+    pub level: Level,
+    /// Line format
+    pub format: TracingFormat,
+    /// Include the module path (event target) on each line
+    pub include_module_path: bool,
+    /// Include the fields of the current span and its parents in JSON output;
+    /// text formats always show the span context
+    pub include_span_fields: bool,
+    /// OTLP/HTTP collector base URL, e.g. `http://localhost:4318`
+    #[cfg(feature = "otlp")]
+    pub otlp_endpoint: Option<String>,
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        Self {
+            level: Level::INFO,
+            format: TracingFormat::default(),
+            include_module_path: true,
+            include_span_fields: true,
+            #[cfg(feature = "otlp")]
+            otlp_endpoint: None,
+        }
+    }
+}
+
+impl TracingConfig {
+    /// Install the subscriber as the global default
+    ///
+    /// Returns false, leaving the existing subscriber in place, when one
+    /// was already installed.
+    pub fn apply(&self) -> bool {
+        let subscriber = self.subscriber(std::io::stdout);
+        #[cfg(feature = "otlp")]
+        let subscriber = subscriber.with(self.otlp_endpoint.as_deref().map(|endpoint| {
+            export::SpanExportLayer::new(export::OtlpHttpExporter::new(endpoint))
+        }));
+        subscriber.try_init().is_ok()
+    }
+
+    /// Subscriber writing lines in the configured format and level to `writer`
+    fn subscriber<W>(&self, writer: W) -> FormattedRegistry
+    where
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        tracing_subscriber::registry()
+            .with(self.fmt_layer(writer))
+            .with(LevelFilter::from_level(self.level))
+    }
+
+    fn fmt_layer<W>(&self, writer: W) -> Box<dyn Layer<Registry> + Send + Sync>
+    where
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        let layer = fmt::layer().with_writer(writer).with_target(self.include_module_path);
+        match self.format {
+            TracingFormat::Pretty => layer.pretty().boxed(),
+            TracingFormat::Compact => layer.compact().boxed(),
+            TracingFormat::Json => layer
+                .json()
+                .with_current_span(self.include_span_fields)
+                .with_span_list(self.include_span_fields)
+                .boxed(),
+        }
+    }
+}
+
+#[cfg(feature = "otlp")]
+pub use export::{OtlpHttpExporter, SpanExportLayer};
+
+#[cfg(feature = "otlp")]
+mod export {
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use chrono::{DateTime, Utc};
+    use tokio::sync::mpsc;
+    use tracing::field::{Field, Visit};
+    use tracing::{span, Subscriber};
+    use tracing_subscriber::layer::Context;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    use crate::networking::{FinishedSpan, TraceContext, TraceExporter};
+
+    /// Most spans sent in one export request
+    const MAX_BATCH: usize = 512;
+
+    /// Sends finished spans to an OTLP/HTTP collector from a background task
+    ///
+    /// Spans are dropped when the exporter was created outside a Tokio runtime.
+    pub struct OtlpHttpExporter {
+        spans: Option<mpsc::UnboundedSender<(FinishedSpan, DateTime<Utc>)>>,
+    }
+
+    impl OtlpHttpExporter {
+        /// Exporter posting to `{endpoint}/v1/traces`
+        pub fn new(endpoint: &str) -> Self {
+            let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+            let spans = tokio::runtime::Handle::try_current().ok().map(|handle| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                handle.spawn(run_exporter(url, rx));
+                tx
+            });
+            Self { spans }
+        }
+    }
+
+    impl TraceExporter for OtlpHttpExporter {
+        fn export(&self, span: FinishedSpan) {
+            if let Some(spans) = &self.spans {
+                let _ = spans.send((span, Utc::now()));
+            }
+        }
+    }
+
+    async fn run_exporter(url: String, mut spans: mpsc::UnboundedReceiver<(FinishedSpan, DateTime<Utc>)>) {
+        let client = reqwest::Client::new();
+        let mut batch = Vec::new();
+        while let Some(span) = spans.recv().await {
+            batch.push(span);
+            while batch.len() < MAX_BATCH {
+                match spans.try_recv() {
+                    Ok(span) => batch.push(span),
+                    Err(_) => break,
+                }
+            }
+            let result = client.post(&url)
+                .header("content-type", "application/json")
+                .body(otlp_request_body(&batch).to_string())
+                .send()
+                .await;
+            if let Err(e) = result {
+                tracing::debug!("Failed to export {} spans: {}", batch.len(), e);
+            }
+            batch.clear();
+        }
+    }
+
+    /// OTLP/JSON `ExportTraceServiceRequest` for spans that ended at the given times
+    pub(super) fn otlp_request_body(spans: &[(FinishedSpan, DateTime<Utc>)]) -> serde_json::Value {
+        let node_id = spans.first().map(|(span, _)| span.node_id.clone()).unwrap_or_default();
+        let spans: Vec<serde_json::Value> = spans.iter()
+            .map(|(span, ended_at)| {
+                let end = ended_at.timestamp_nanos_opt().unwrap_or_default();
+                let start = end - (span.duration_ms * 1_000_000.0) as i64;
+                let mut json = serde_json::json!({
+                    "traceId": span.context.trace_id,
+                    "spanId": span.context.span_id,
+                    "name": span.name,
+                    "kind": 1,
+                    "startTimeUnixNano": start.to_string(),
+                    "endTimeUnixNano": end.to_string(),
+                });
+                if let Some(parent) = &span.context.parent_span_id {
+                    json["parentSpanId"] = serde_json::json!(parent);
+                }
+                json
+            })
+            .collect();
+        serde_json::json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        { "key": "service.name", "value": { "stringValue": "weavemesh" } },
+                        { "key": "service.instance.id", "value": { "stringValue": node_id } },
+                    ]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }]
+            }]
+        })
+    }
+
+    /// Timing and context of an open span
+    struct OpenSpan {
+        context: TraceContext,
+        name: String,
+        started: Instant,
+    }
+
+    /// `tracing` layer handing every closed span to a [`TraceExporter`]
+    ///
+    /// Spans created by [`TraceContext::span`] keep their propagated
+    /// identifiers; other spans join their parent's trace, or the task's
+    /// current trace, or start a new one.
+    pub struct SpanExportLayer {
+        exporter: Arc<dyn TraceExporter>,
+        node_id: String,
+    }
+
+    impl SpanExportLayer {
+        /// Export spans through `exporter`, labelled with this host's name
+        pub fn new(exporter: impl TraceExporter + 'static) -> Self {
+            let node_id = hostname::get()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            Self { exporter: Arc::new(exporter), node_id }
+        }
+    }
+
+    impl<S> Layer<S> for SpanExportLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+            let span = match ctx.span(id) {
+                Some(span) => span,
+                None => return,
+            };
+            let mut fields = TraceFields::default();
+            attrs.record(&mut fields);
+            let context = fields.context().unwrap_or_else(|| {
+                span.parent()
+                    .and_then(|parent| {
+                        let extensions = parent.extensions();
+                        extensions.get::<OpenSpan>().map(|open| open.context.child())
+                    })
+                    .or_else(|| TraceContext::current().map(|current| current.child()))
+                    .unwrap_or_else(|| TraceContext::new_root(true))
+            });
+            let name = fields.otel_name.unwrap_or_else(|| attrs.metadata().name().to_string());
+            span.extensions_mut().insert(OpenSpan { context, name, started: Instant::now() });
+        }
+
+        fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+            let span = match ctx.span(&id) {
+                Some(span) => span,
+                None => return,
+            };
+            let open = match span.extensions_mut().remove::<OpenSpan>() {
+                Some(open) => open,
+                None => return,
+            };
+            self.exporter.export(FinishedSpan {
+                context: open.context,
+                name: open.name,
+                node_id: self.node_id.clone(),
+                duration_ms: open.started.elapsed().as_secs_f64() * 1000.0,
+            });
+        }
+    }
+
+    /// Fields recorded by [`TraceContext::span`]
+    #[derive(Default)]
+    struct TraceFields {
+        otel_name: Option<String>,
+        trace_id: Option<String>,
+        span_id: Option<String>,
+        parent_span_id: Option<String>,
+    }
+
+    impl TraceFields {
+        fn context(&self) -> Option<TraceContext> {
+            Some(TraceContext {
+                trace_id: self.trace_id.clone()?,
+                span_id: self.span_id.clone()?,
+                parent_span_id: self.parent_span_id.clone().filter(|parent| !parent.is_empty()),
+                flags: TraceContext::FLAG_SAMPLED,
+            })
+        }
+    }
+
+    impl Visit for TraceFields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            let slot = match field.name() {
+                "otel.name" => &mut self.otel_name,
+                "trace_id" => &mut self.trace_id,
+                "span_id" => &mut self.span_id,
+                "parent_span_id" => &mut self.parent_span_id,
+                _ => return,
+            };
+            *slot = Some(value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.record_str(field, &format!("{:?}", value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    /// Log output shared between a subscriber and the test reading it
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = CapturedLogs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_json_lines_with_span_fields() {
+        let config = TracingConfig {
+            level: Level::DEBUG,
+            format: TracingFormat::Json,
+            ..TracingConfig::default()
+        };
+        let logs = CapturedLogs::default();
+        tracing::subscriber::with_default(config.subscriber(logs.clone()), || {
+            tracing::info_span!("request", peer = "node-a").in_scope(|| {
+                tracing::debug!(bytes = 42, "logged as JSON");
+                tracing::trace!("below the configured level");
+            });
+        });
+
+        let output = logs.contents();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(line["level"], "DEBUG");
+        assert_eq!(line["target"], module_path!());
+        assert_eq!(line["fields"]["message"], "logged as JSON");
+        assert_eq!(line["fields"]["bytes"], 42);
+        assert_eq!(line["span"]["name"], "request");
+        assert_eq!(line["span"]["peer"], "node-a");
+    }
+
+    #[test]
+    fn test_compact_lines_without_module_path() {
+        let config = TracingConfig {
+            include_module_path: false,
+            ..TracingConfig::default()
+        };
+        let logs = CapturedLogs::default();
+        tracing::subscriber::with_default(config.subscriber(logs.clone()), || {
+            tracing::info!("compact line");
+            tracing::debug!("below the default level");
+        });
+
+        let output = logs.contents();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains("INFO") && output.contains("compact line"));
+        assert!(!output.contains(module_path!()));
+        assert!(serde_json::from_str::<serde_json::Value>(output.trim()).is_err());
+    }
+
+    #[cfg(feature = "otlp")]
+    #[test]
+    fn test_otlp_request_body() {
+        use crate::networking::{FinishedSpan, TraceContext};
+
+        let root = TraceContext::new_root(true);
+        let child = root.child();
+        let ended_at = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let finished = |context: TraceContext, name: &str| FinishedSpan {
+            context,
+            name: name.to_string(),
+            node_id: "node-a".to_string(),
+            duration_ms: 1.5,
+        };
+        let body = export::otlp_request_body(&[
+            (finished(root.clone(), "publish_message"), ended_at),
+            (finished(child.clone(), "check_authorization"), ended_at),
+        ]);
+
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(resource["resource"]["attributes"][1]["value"]["stringValue"], "node-a");
+        let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans[0]["traceId"], root.trace_id.as_str());
+        assert!(spans[0].get("parentSpanId").is_none());
+        assert_eq!(spans[1]["parentSpanId"], root.span_id.as_str());
+        assert_eq!(spans[1]["endTimeUnixNano"], "1700000000000000000");
+        assert_eq!(spans[1]["startTimeUnixNano"], "1699999999998500000");
+    }
+}
@@ -4,7 +4,7 @@
 //! attribution data. It maintains strict separation between objective
 //! measurement (attribution) and subjective value assignment (tokens).
 
-use crate::Attribution;
+use crate::{Attribution, WeaveMeshError};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,9 @@ use uuid::Uuid;
 /// Token amount type - using f64 for precision in calculations
 pub type TokenAmount = f64;
 
+/// Result of token operations
+pub type TokenResult<T> = std::result::Result<T, TokenError>;
+
 /// Unique identifier for token policies
 pub type PolicyId = Uuid;
 
@@ -209,6 +212,45 @@ pub enum TokenError {
     
     #[error("Policy registration failed: {0}")]
     PolicyRegistrationFailed(String),
+    
+    #[error("Pool {pool} has {available} tokens, {requested} requested")]
+    InsufficientPool {
+        pool: String,
+        available: TokenAmount,
+        requested: TokenAmount,
+    },
+}
+
+impl TokenError {
+    /// Prefix of the messages of converted token errors
+    pub const MESSAGE_PREFIX: &'static str = "tokens: ";
+    
+    /// Convert into the crate-level error
+    ///
+    /// Policy violations become Sacred Alliance violations, registration
+    /// failures configuration errors, and the rest generic errors. Messages
+    /// start with [`Self::MESSAGE_PREFIX`], which
+    /// [`WeaveMeshError::is_token_related`] recognises.
+    pub fn into_weavemesh_error(self) -> WeaveMeshError {
+        let message = match &self {
+            TokenError::InsufficientPool { pool, available, requested } => format!(
+                "{}insufficient token balance: pool {} has {}, {} requested",
+                Self::MESSAGE_PREFIX, pool, available, requested,
+            ),
+            other => format!("{}{}", Self::MESSAGE_PREFIX, other),
+        };
+        match self {
+            TokenError::PolicyValidationFailed(_) => WeaveMeshError::SacredAllianceViolation(message),
+            TokenError::PolicyRegistrationFailed(_) => WeaveMeshError::Configuration(message),
+            TokenError::CalculationFailed(_) | TokenError::InsufficientPool { .. } => WeaveMeshError::Generic(message),
+        }
+    }
+}
+
+impl From<TokenError> for WeaveMeshError {
+    fn from(error: TokenError) -> Self {
+        error.into_weavemesh_error()
+    }
 }
 
 #[cfg(test)]
@@ -267,4 +309,25 @@ mod tests {
         // AI should get 4.0 tokens (50% of human allocation)
         assert_eq!(allocation.allocations["ai1"], 4.0);
     }
+    
+    #[test]
+    fn test_token_errors_convert_to_weavemesh_errors() {
+        fn allocate(available: TokenAmount) -> crate::Result<()> {
+            let result: TokenResult<()> = Err(TokenError::InsufficientPool {
+                pool: "rewards".to_string(),
+                available,
+                requested: 10.0,
+            });
+            Ok(result?)
+        }
+        let error = allocate(2.0).unwrap_err();
+        assert!(matches!(&error, WeaveMeshError::Generic(m) if m.contains("insufficient token balance: pool rewards has 2")));
+        assert!(error.is_token_related());
+        
+        let violation = TokenError::PolicyValidationFailed("AI share above cap".to_string()).into_weavemesh_error();
+        assert!(matches!(violation, WeaveMeshError::SacredAllianceViolation(_)));
+        assert!(violation.is_token_related());
+        assert!(WeaveMeshError::from(anyhow::Error::new(TokenError::CalculationFailed("nan".to_string()))).is_token_related());
+        assert!(!WeaveMeshError::Generic("disk full".to_string()).is_token_related());
+    }
 }
@@ -0,0 +1,368 @@
+//! Transport beneath the WeaveMesh protocol, with a local-only mode
+//!
+//! When Zenoh cannot be opened, [`WeaveProtocol`](crate::WeaveProtocol) can
+//! still run on a null transport: publishes succeed and are looped back to
+//! local subscribers, and are optionally journaled. [`ProtocolTransport::go_online`]
+//! later attaches a Zenoh session, moves every existing subscription onto it
+//! and replays the journaled publishes whose TTL has not run out.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::{debug, error, info, warn};
+
+/// Whether the protocol is connected to the mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransportMode {
+    /// Publishing and subscribing through Zenoh
+    Online,
+    /// Null transport: local loopback only
+    LocalOnly,
+}
+
+/// How the protocol behaves without a network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModeConfig {
+    /// Start in local-only mode without trying Zenoh
+    pub start_local: bool,
+    /// Fall back to local-only mode if Zenoh fails to open
+    pub fallback_on_failure: bool,
+    /// Journal local-only publishes for replay when going online
+    pub journal_publishes: bool,
+    /// How long a journaled publish stays worth replaying
+    pub journal_ttl: Duration,
+    /// Most journaled publishes kept; the oldest are dropped first
+    pub max_journal_entries: usize,
+}
+
+impl Default for LocalModeConfig {
+    fn default() -> Self {
+        Self {
+            start_local: false,
+            fallback_on_failure: false,
+            journal_publishes: true,
+            journal_ttl: Duration::from_secs(3600),
+            max_journal_entries: 10_000,
+        }
+    }
+}
+
+/// A transition between transport modes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransportEvent {
+    /// Running on the null transport
+    WentLocal {
+        /// Why, such as the Zenoh error that forced a fallback
+        reason: String,
+        at: DateTime<Utc>,
+    },
+    /// Connected to the mesh
+    WentOnline {
+        /// Journaled publishes sent on connecting
+        replayed: usize,
+        /// Journaled publishes dropped because their TTL ran out
+        expired: usize,
+        at: DateTime<Utc>,
+    },
+}
+
+/// A publish made while local-only
+#[derive(Debug, Clone)]
+pub struct JournaledPublish {
+    pub key: String,
+    pub payload: Vec<u8>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Bounded journal of local-only publishes, oldest first
+#[derive(Debug, Default)]
+pub struct PublishJournal {
+    entries: VecDeque<JournaledPublish>,
+    dropped: u64,
+}
+
+impl PublishJournal {
+    /// Add a publish, dropping the oldest beyond `max_entries`
+    pub fn record(&mut self, entry: JournaledPublish, max_entries: usize) {
+        self.entries.push_back(entry);
+        while self.entries.len() > max_entries {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    /// Publishes waiting for replay
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Publishes dropped by the size limit
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Empty the journal, returning the publishes still within `ttl` of `now`
+    /// and the number that expired
+    pub fn drain_live(&mut self, now: DateTime<Utc>, ttl: Duration) -> (Vec<JournaledPublish>, usize) {
+        let ttl = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+        let (live, expired): (Vec<_>, Vec<_>) = self.entries
+            .drain(..)
+            .partition(|entry| now - entry.published_at <= ttl);
+        (live, expired.len())
+    }
+}
+
+/// Whether the Zenoh-style key expression `expr` matches `key`
+///
+/// `*` matches exactly one chunk and `**` any number of chunks.
+pub fn key_expr_matches(expr: &str, key: &str) -> bool {
+    fn matches(expr: &[&str], key: &[&str]) -> bool {
+        match (expr.first(), key.first()) {
+            (None, None) => true,
+            (Some(&"**"), _) => matches(&expr[1..], key) || (!key.is_empty() && matches(expr, &key[1..])),
+            (Some(chunk), Some(part)) => (*chunk == "*" || chunk == part) && matches(&expr[1..], &key[1..]),
+            _ => false,
+        }
+    }
+    let expr: Vec<&str> = expr.split('/').collect();
+    let key: Vec<&str> = key.split('/').collect();
+    matches(&expr, &key)
+}
+
+/// A publish delivered to a subscription
+#[derive(Debug, Clone)]
+pub struct TransportSample {
+    pub key: String,
+    pub payload: Vec<u8>,
+}
+
+/// A subscription, fed by loopback while local-only and by Zenoh once online
+struct Subscription {
+    key_expr: String,
+    sender: mpsc::UnboundedSender<TransportSample>,
+}
+
+/// Zenoh session or null transport, switchable at runtime
+#[derive(Clone)]
+pub struct ProtocolTransport {
+    session: Arc<RwLock<Option<Arc<zenoh::Session>>>>,
+    subscriptions: Arc<RwLock<Vec<Subscription>>>,
+    journal: Arc<RwLock<PublishJournal>>,
+    config: LocalModeConfig,
+    history: Arc<RwLock<Vec<TransportEvent>>>,
+    events: broadcast::Sender<TransportEvent>,
+}
+
+impl ProtocolTransport {
+    /// Transport over an open Zenoh session
+    pub fn online(session: zenoh::Session, config: LocalModeConfig) -> Self {
+        Self::with_session(Some(Arc::new(session)), config)
+    }
+
+    /// Null transport, recording `reason` as the cause
+    pub fn local_only(config: LocalModeConfig, reason: impl Into<String>) -> Self {
+        let transport = Self::with_session(None, config);
+        let event = TransportEvent::WentLocal { reason: reason.into(), at: Utc::now() };
+        warn!("WeaveMesh transport is local-only: {:?}", event);
+        transport.history.try_write().expect("new transport is unshared").push(event.clone());
+        let _ = transport.events.send(event);
+        transport
+    }
+
+    fn with_session(session: Option<Arc<zenoh::Session>>, config: LocalModeConfig) -> Self {
+        Self {
+            session: Arc::new(RwLock::new(session)),
+            subscriptions: Arc::new(RwLock::new(Vec::new())),
+            journal: Arc::new(RwLock::new(PublishJournal::default())),
+            config,
+            history: Arc::new(RwLock::new(Vec::new())),
+            events: broadcast::channel(64).0,
+        }
+    }
+
+    /// Current mode
+    pub async fn mode(&self) -> TransportMode {
+        match *self.session.read().await {
+            Some(_) => TransportMode::Online,
+            None => TransportMode::LocalOnly,
+        }
+    }
+
+    /// The Zenoh session, when online
+    pub async fn session(&self) -> Option<Arc<zenoh::Session>> {
+        self.session.read().await.clone()
+    }
+
+    /// Mode transitions from now on
+    pub fn subscribe_events(&self) -> broadcast::Receiver<TransportEvent> {
+        self.events.subscribe()
+    }
+
+    /// Every mode transition so far, oldest first
+    pub async fn history(&self) -> Vec<TransportEvent> {
+        self.history.read().await.clone()
+    }
+
+    /// Publishes waiting for replay
+    pub async fn journaled(&self) -> usize {
+        self.journal.read().await.len()
+    }
+
+    /// Publish `payload` on `key`
+    ///
+    /// While local-only this always succeeds: matching local subscriptions
+    /// receive it, and it is journaled if configured.
+    pub async fn put(&self, key: &str, payload: Vec<u8>) -> Result<()> {
+        self.publish(key, payload, self.config.journal_publishes).await
+    }
+
+    /// Publish without journaling, for state that is stale by the time
+    /// the node comes online, such as heartbeats
+    pub async fn put_volatile(&self, key: &str, payload: Vec<u8>) -> Result<()> {
+        self.publish(key, payload, false).await
+    }
+
+    async fn publish(&self, key: &str, payload: Vec<u8>, journal: bool) -> Result<()> {
+        if let Some(session) = self.session().await {
+            return session
+                .put(key, payload)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to publish: {}", e));
+        }
+
+        self.subscriptions.write().await.retain(|subscription| {
+            !key_expr_matches(&subscription.key_expr, key)
+                || subscription.sender.send(TransportSample { key: key.to_string(), payload: payload.clone() }).is_ok()
+        });
+        if journal {
+            self.journal.write().await.record(
+                JournaledPublish { key: key.to_string(), payload, published_at: Utc::now() },
+                self.config.max_journal_entries,
+            );
+        }
+        Ok(())
+    }
+
+    /// Receive publishes matching `key_expr`
+    ///
+    /// The receiver keeps working across [`go_online`](Self::go_online).
+    pub async fn subscribe(&self, key_expr: &str) -> Result<mpsc::UnboundedReceiver<TransportSample>> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        if let Some(session) = self.session().await {
+            Self::forward(&session, key_expr, sender.clone()).await?;
+        }
+        self.subscriptions.write().await.push(Subscription { key_expr: key_expr.to_string(), sender });
+        Ok(receiver)
+    }
+
+    /// Forward Zenoh samples on `key_expr` into a subscription
+    async fn forward(
+        session: &zenoh::Session,
+        key_expr: &str,
+        sender: mpsc::UnboundedSender<TransportSample>,
+    ) -> Result<()> {
+        let subscriber = session
+            .declare_subscriber(key_expr)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to subscribe: {}", e))?;
+        tokio::spawn(async move {
+            while let Ok(sample) = subscriber.recv_async().await {
+                let sample = TransportSample {
+                    key: sample.key_expr().as_str().to_string(),
+                    payload: sample.payload().to_bytes().to_vec(),
+                };
+                if sender.send(sample).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Attach a Zenoh session and leave local-only mode
+    ///
+    /// Existing subscriptions move onto the session, then journaled publishes
+    /// within their TTL are replayed in order. Returns the transition event;
+    /// already being online is not an error and replays nothing.
+    pub async fn go_online(&self, session: zenoh::Session) -> Result<TransportEvent> {
+        let session = Arc::new(session);
+        {
+            let mut current = self.session.write().await;
+            if current.is_some() {
+                debug!("Transport is already online");
+                return Ok(TransportEvent::WentOnline { replayed: 0, expired: 0, at: Utc::now() });
+            }
+
+            let subscriptions = self.subscriptions.read().await;
+            for subscription in subscriptions.iter() {
+                Self::forward(&session, &subscription.key_expr, subscription.sender.clone()).await?;
+            }
+            *current = Some(Arc::clone(&session));
+        }
+
+        let (live, expired) = self.journal.write().await.drain_live(Utc::now(), self.config.journal_ttl);
+        let mut replayed = 0;
+        for entry in live {
+            match session.put(&entry.key, entry.payload).await {
+                Ok(()) => replayed += 1,
+                Err(e) => error!("Failed to replay journaled publish on {}: {}", entry.key, e),
+            }
+        }
+
+        let event = TransportEvent::WentOnline { replayed, expired, at: Utc::now() };
+        info!("WeaveMesh transport is online: {:?}", event);
+        self.history.write().await.push(event.clone());
+        let _ = self.events.send(event.clone());
+        Ok(event)
+    }
+
+    /// Detach and return the Zenoh session, if this is its last user
+    pub async fn take_session(&self) -> Option<zenoh::Session> {
+        let session = self.session.write().await.take()?;
+        Arc::try_unwrap(session).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_expr_matches() {
+        assert!(key_expr_matches("weave/messages/general", "weave/messages/general"));
+        assert!(key_expr_matches("weave/heartbeat/*", "weave/heartbeat/node-1"));
+        assert!(!key_expr_matches("weave/heartbeat/*", "weave/heartbeat/node-1/extra"));
+        assert!(key_expr_matches("weave/**", "weave/messages/general"));
+        assert!(key_expr_matches("weave/**", "weave"));
+        assert!(!key_expr_matches("weave/messages/general", "weave/messages/other"));
+    }
+
+    #[test]
+    fn test_journal_drops_oldest_and_expired() {
+        let now = Utc::now();
+        let entry = |key: &str, age_secs: i64| JournaledPublish {
+            key: key.to_string(),
+            payload: Vec::new(),
+            published_at: now - chrono::Duration::seconds(age_secs),
+        };
+        let mut journal = PublishJournal::default();
+        for (key, age) in [("a", 300), ("b", 120), ("c", 30), ("d", 10)] {
+            journal.record(entry(key, age), 3);
+        }
+        assert_eq!(journal.len(), 3);
+        assert_eq!(journal.dropped(), 1);
+
+        let (live, expired) = journal.drain_live(now, Duration::from_secs(60));
+        assert_eq!(live.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(), vec!["c", "d"]);
+        assert_eq!(expired, 1);
+        assert!(journal.is_empty());
+    }
+}